@@ -0,0 +1,121 @@
+//! Allocations and time for list, set and dict comprehensions over a
+//! 1M-element list: naive codegen that appends to an empty container in
+//! nested `for`/`if` loops, against the fused comprehension() builder.
+//!
+//! Run with `cargo bench --bench comprehensions`. Set COMPREHENSIONS_BENCH_LEN
+//! to change the list length (default 1_000_000).
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::env;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use stdpython::{comprehension, PyDictionary, PyList, PySet};
+
+/// Counts allocations so each comprehension can report how many it made.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn measure<F: FnMut() -> usize>(label: &str, mut f: F) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let out = black_box(f());
+    let elapsed = start.elapsed();
+    let allocs = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{:<44} {:>10.2?}  {:>9} allocations  ({})",
+        label, elapsed, allocs, out
+    );
+}
+
+fn main() {
+    let len: usize = env::var("COMPREHENSIONS_BENCH_LEN")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1_000_000);
+    let xs = PyList::from_vec((0..len as i64).collect());
+    let ys = PyList::from_vec((0..4i64).collect());
+
+    println!("[x * 3 for x in xs] over {} ints", len);
+    measure("  append in a loop", || {
+        let mut out = PyList::new();
+        for x in xs.iter() {
+            out.append(x * 3);
+        }
+        black_box(out).len()
+    });
+    measure("  comprehension(&xs).list()", || {
+        let out = comprehension(&xs).list(|x| x * 3);
+        black_box(out).len()
+    });
+
+    println!("[x + y for x in xs if x % 3 for y in ys if x != y]");
+    measure("  append in nested loops", || {
+        let mut out = PyList::new();
+        for x in xs.iter() {
+            if x % 3 != 0 {
+                for y in ys.iter() {
+                    if x != y {
+                        out.append(x + y);
+                    }
+                }
+            }
+        }
+        black_box(out).len()
+    });
+    measure("  comprehension(&xs).then_for().list()", || {
+        let out = comprehension(&xs)
+            .filter(|x| *x % 3 != 0)
+            .then_for(|x| ys.iter().map(move |y| (x, y)))
+            .filter(|(x, y)| x != y)
+            .list(|(x, y)| x + y);
+        black_box(out).len()
+    });
+
+    println!("{{x % 1000 for x in xs}}");
+    measure("  add in a loop", || {
+        let mut out = PySet::new();
+        for x in xs.iter() {
+            out.add(x % 1000);
+        }
+        black_box(out).len()
+    });
+    measure("  comprehension(&xs).set()", || {
+        let out = comprehension(&xs).set(|x| x % 1000);
+        black_box(out).len()
+    });
+
+    println!("{{x: x * x for x in xs}}");
+    measure("  set_item in a loop", || {
+        let mut out = PyDictionary::new();
+        for x in xs.iter() {
+            out.set_item(*x, x * x);
+        }
+        black_box(out).len()
+    });
+    measure("  comprehension(&xs).dict()", || {
+        let out = comprehension(&xs).dict(|x| (*x, x * x));
+        black_box(out).len()
+    });
+}
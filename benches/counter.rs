@@ -0,0 +1,43 @@
+//! Counter.most_common(n) for small n, which selects with a bounded heap,
+//! against most_common() followed by truncation, which sorts every item.
+//!
+//! Run with `cargo bench --bench counter`. Set COUNTER_BENCH_LEN to change
+//! the number of distinct keys (default 1_000_000).
+
+use std::env;
+use std::hint::black_box;
+use std::time::Instant;
+
+use stdpython::stdlib::collections::Counter;
+
+fn time<F: FnMut() -> usize>(label: &str, mut f: F) {
+    let start = Instant::now();
+    let out = black_box(f());
+    println!("{:<44} {:>10.2?}  ({})", label, start.elapsed(), out);
+}
+
+fn main() {
+    let len: u64 = env::var("COUNTER_BENCH_LEN")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1_000_000);
+    // Keys in a scrambled order with counts 1..=100, so both the heap and
+    // the sort see realistic ties.
+    let mut counter = Counter::new();
+    for i in 0..len {
+        let key = i.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 16;
+        counter.add(key, (i % 100 + 1) as i64);
+    }
+
+    println!("most_common over {} keys", counter.len());
+    for n in [1, 10, 100, 1000] {
+        time(&format!("  most_common(None)[..{}]", n), || {
+            let mut all = counter.most_common(None);
+            all.truncate(n);
+            black_box(all)[0].1 as usize
+        });
+        time(&format!("  most_common(Some({}))", n), || {
+            black_box(counter.most_common(Some(n)))[0].1 as usize
+        });
+    }
+}
@@ -0,0 +1,112 @@
+//! Compares the compiled fnmatch engine with the previous recursive char-by-char matcher.
+//!
+//! Run with `cargo bench --bench glob`. Set GLOB_BENCH_FILES to change the tree size
+//! (default 100_000 files spread over 100 directories).
+
+use std::env;
+use std::fs;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use stdpython::stdlib::{fnmatch, glob};
+
+/// The matcher glob and pathlib used before patterns were compiled: recursive and
+/// re-parsing the pattern on every call.
+fn old_match(name: &[char], pat: &[char]) -> bool {
+    match (pat.first(), name.first()) {
+        (None, None) => true,
+        (None, Some(_)) => false,
+        (Some('*'), _) => {
+            old_match(name, &pat[1..]) || (!name.is_empty() && old_match(&name[1..], pat))
+        }
+        (Some('?'), Some(_)) => old_match(&name[1..], &pat[1..]),
+        (Some('['), Some(&c)) => match pat.iter().position(|&p| p == ']') {
+            Some(end) => {
+                let class = &pat[1..end];
+                let (negated, class) = match class.first() {
+                    Some('!') => (true, &class[1..]),
+                    _ => (false, class),
+                };
+                let mut hit = false;
+                let mut i = 0;
+                while i < class.len() {
+                    if i + 2 < class.len() && class[i + 1] == '-' {
+                        hit |= class[i] <= c && c <= class[i + 2];
+                        i += 3;
+                    } else {
+                        hit |= class[i] == c;
+                        i += 1;
+                    }
+                }
+                hit != negated && old_match(&name[1..], &pat[end + 1..])
+            }
+            None => c == '[' && old_match(&name[1..], &pat[1..]),
+        },
+        (Some(&p), Some(&c)) => p == c && old_match(&name[1..], &pat[1..]),
+        (Some(_), None) => false,
+    }
+}
+
+fn time<F: FnMut() -> usize>(label: &str, mut f: F) -> Duration {
+    let start = Instant::now();
+    let hits = f();
+    let elapsed = start.elapsed();
+    println!("{:<44} {:>10.2?}  ({} matches)", label, elapsed, hits);
+    elapsed
+}
+
+fn build_tree(files: usize) -> PathBuf {
+    let root = env::temp_dir().join(format!("stdpython_glob_bench_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let per_dir = files.div_ceil(100);
+    for d in 0..100 {
+        let dir = root.join(format!("pkg{:02}", d));
+        fs::create_dir_all(&dir).unwrap();
+        for f in 0..per_dir {
+            let ext = if f % 10 == 0 { "rs" } else { "txt" };
+            fs::write(dir.join(format!("test_file_{:05}.{}", f, ext)), b"").unwrap();
+        }
+    }
+    root
+}
+
+fn main() {
+    let files: usize = env::var("GLOB_BENCH_FILES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(100_000);
+    let names: Vec<String> = (0..files)
+        .map(|i| format!("test_file_{:05}.{}", i, if i % 10 == 0 { "rs" } else { "txt" }))
+        .collect();
+
+    for pat in ["*.rs", "test_*_0*1.txt", "*[0-4]?.r[s]", "*a*b*c*d*e*"] {
+        println!("pattern {:?}", pat);
+        let pat_chars: Vec<char> = pat.chars().collect();
+        let old = time("  old recursive matcher", || {
+            names
+                .iter()
+                .filter(|n| old_match(&n.chars().collect::<Vec<_>>(), &pat_chars))
+                .count()
+        });
+        let new = time("  compiled fnmatch::Pattern", || {
+            let compiled = fnmatch::Pattern::new(pat);
+            names.iter().filter(|n| compiled.matches(black_box(n))).count()
+        });
+        println!("  speedup x{:.1}", old.as_secs_f64() / new.as_secs_f64().max(1e-9));
+    }
+
+    let root = build_tree(files);
+    let root_str = root.to_string_lossy().into_owned();
+    println!("tree of {} files", files);
+    time("  glob('*/test_*.rs')", || {
+        glob::iglob("*/test_*.rs", Some(&root_str), false, false).count()
+    });
+    time("  glob('pkg07/*.rs') (literal prefix)", || {
+        glob::iglob("pkg07/*.rs", Some(&root_str), false, false).count()
+    });
+    time("  glob('**/*.rs', recursive=True)", || {
+        glob::iglob("**/*.rs", Some(&root_str), true, false).count()
+    });
+    fs::remove_dir_all(&root).unwrap();
+}
@@ -0,0 +1,105 @@
+//! Attribute lookup on a compiled class by name string, as dynamic code
+//! does with getattr(), against the same lookup by interned Symbol.
+//!
+//! Run with `cargo bench --bench intern`. Set INTERN_BENCH_LEN to change
+//! the number of lookups (default 10_000_000).
+
+use std::env;
+use std::hint::black_box;
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use stdpython::types::intern::SymbolMap;
+use stdpython::types::{intern, PyObjectProtocol, PyTypeId, PyTyped, PyValue, Symbol};
+use stdpython::{PyException, PyResult};
+
+const FIELDS: [&str; 8] = [
+    "identifier",
+    "display_name",
+    "created_at",
+    "updated_at",
+    "owner_account",
+    "balance_cents",
+    "is_active",
+    "last_login",
+];
+
+struct Record {
+    values: [i64; 8],
+}
+
+impl PyTyped for Record {
+    fn py_type(&self) -> PyTypeId {
+        PyTypeId::Object
+    }
+}
+
+fn missing(name: &str) -> PyException {
+    PyException::attribute_error(format!("'Record' object has no attribute '{}'", name))
+}
+
+impl PyObjectProtocol for Record {
+    fn get_attr(&self, name: &str) -> PyResult<PyValue> {
+        let slot = match name {
+            "identifier" => 0,
+            "display_name" => 1,
+            "created_at" => 2,
+            "updated_at" => 3,
+            "owner_account" => 4,
+            "balance_cents" => 5,
+            "is_active" => 6,
+            "last_login" => 7,
+            _ => return Err(missing(name)),
+        };
+        Ok(PyValue::Int(self.values[slot]))
+    }
+
+    fn get_attr_sym(&self, name: Symbol) -> PyResult<PyValue> {
+        static SLOTS: OnceLock<SymbolMap<usize>> = OnceLock::new();
+        let slots = SLOTS.get_or_init(|| FIELDS.iter().copied().zip(0..).collect());
+        match slots.get(name) {
+            Some(&slot) => Ok(PyValue::Int(self.values[slot])),
+            None => Err(missing(name.as_str())),
+        }
+    }
+}
+
+fn time<F: FnMut() -> i64>(label: &str, mut f: F) {
+    let start = Instant::now();
+    let out = black_box(f());
+    println!("{:<36} {:>10.2?}  ({})", label, start.elapsed(), out);
+}
+
+fn main() {
+    let len: usize = env::var("INTERN_BENCH_LEN")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(10_000_000);
+    let record = Record {
+        values: [1, 2, 3, 4, 5, 6, 7, 8],
+    };
+    let names: Vec<String> = FIELDS.iter().map(|s| s.to_string()).collect();
+    let symbols: Vec<Symbol> = FIELDS.iter().map(|s| intern(s)).collect();
+
+    println!("{} attribute lookups over {} fields", len, FIELDS.len());
+    time("  get_attr(&str)", || {
+        let mut sum = 0;
+        for i in 0..len {
+            let name = black_box(&names[i % names.len()]);
+            if let Ok(PyValue::Int(v)) = record.get_attr(name) {
+                sum += v;
+            }
+        }
+        sum
+    });
+    time("  get_attr_sym(Symbol)", || {
+        let mut sum = 0;
+        for i in 0..len {
+            let name = black_box(symbols[i % symbols.len()]);
+            if let Ok(PyValue::Int(v)) = record.get_attr_sym(name) {
+                sum += v;
+            }
+        }
+        sum
+    });
+}
@@ -0,0 +1,80 @@
+//! Allocations and time for compiled `for` loops over a 1M-element list:
+//! the old lowering, which copied the list into a Vec of tuples first,
+//! against the borrowing enumerate()/reversed()/slice adapters.
+//!
+//! Run with `cargo bench --bench loops`. Set LOOPS_BENCH_LEN to change the
+//! list length (default 1_000_000).
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::env;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use stdpython::{enumerate, reversed, PyList, PySlice};
+
+/// Counts allocations so each loop can report how many it made.
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn measure<F: FnMut() -> usize>(label: &str, mut f: F) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let out = black_box(f());
+    let elapsed = start.elapsed();
+    let allocs = ALLOCATIONS.load(Ordering::Relaxed) - before;
+    println!(
+        "{:<44} {:>10.2?}  {:>9} allocations  ({})",
+        label, elapsed, allocs, out
+    );
+}
+
+fn main() {
+    let len: usize = env::var("LOOPS_BENCH_LEN")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1_000_000);
+    let list = PyList::from_vec((0..len).map(|i| format!("item{}", i)).collect());
+
+    println!("for i, s in enumerate(list) over {} strings", len);
+    measure("  materialized Vec<(i64, String)>", || {
+        let pairs: Vec<(i64, String)> = list
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, s)| (i as i64, s))
+            .collect();
+        pairs.iter().map(|(i, s)| *i as usize ^ s.len()).sum()
+    });
+    measure("  enumerate(&list)", || {
+        enumerate(&list).map(|(i, s)| i as usize ^ s.len()).sum()
+    });
+
+    println!("for s in reversed(list[::2])");
+    let every_other = PySlice::new(None, None, Some(2));
+    measure("  list.slice() then reverse", || {
+        let mut picked = list.slice(&every_other).unwrap().into_vec();
+        picked.reverse();
+        picked.iter().map(String::len).sum()
+    });
+    measure("  reversed(list.iter_slice())", || {
+        reversed(list.iter_slice(&every_other).unwrap())
+            .map(String::len)
+            .sum()
+    });
+}
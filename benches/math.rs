@@ -0,0 +1,91 @@
+//! Element-by-element math calls against the bulk slice helpers, over a
+//! list of 1M floats.
+//!
+//! Run with `cargo bench --bench math`. Set MATH_BENCH_LEN to change the
+//! list length (default 1_000_000).
+
+use std::env;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use stdpython::stdlib::math;
+use stdpython::PyResult;
+
+fn time<F: FnMut() -> f64>(label: &str, mut f: F) -> Duration {
+    let start = Instant::now();
+    let out = black_box(f());
+    let elapsed = start.elapsed();
+    println!("{:<44} {:>10.2?}  ({:.3})", label, elapsed, out);
+    elapsed
+}
+
+fn main() {
+    let len: usize = env::var("MATH_BENCH_LEN")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(1_000_000);
+    let xs: Vec<f64> = (0..len).map(|i| (i % 1000) as f64 / 100.0).collect();
+    let ys: Vec<f64> = xs.iter().map(|x| x * 0.5).collect();
+
+    macro_rules! compare {
+        ($what:literal, $scalar:expr, $slice:expr, $in_place:expr) => {
+            println!("{} over {} floats", $what, len);
+            time("  element by element", || {
+                let out: PyResult<Vec<f64>> = xs.iter().map($scalar).collect();
+                out.unwrap().iter().sum()
+            });
+            time("  slice helper", || $slice(&xs).unwrap().iter().sum());
+            time("  element by element, in place", || {
+                let mut buf = xs.clone();
+                for x in buf.iter_mut() {
+                    *x = $scalar(&*x).unwrap();
+                }
+                buf.iter().sum()
+            });
+            time("  in-place helper", || {
+                let mut buf = xs.clone();
+                $in_place(&mut buf).unwrap();
+                buf.iter().sum()
+            });
+        };
+    }
+
+    compare!(
+        "math.sqrt(x)",
+        |x: &f64| math::sqrt(*x),
+        math::sqrt_slice,
+        math::sqrt_in_place
+    );
+    compare!(
+        "math.sin(x)",
+        |x: &f64| math::sin(*x),
+        math::sin_slice,
+        math::sin_in_place
+    );
+    compare!(
+        "math.exp(x)",
+        |x: &f64| math::exp(*x),
+        math::exp_slice,
+        math::exp_in_place
+    );
+    compare!(
+        "math.pow(x, 1.5)",
+        |x: &f64| math::pow(*x, 1.5),
+        |xs: &[f64]| math::pow_slice(xs, 1.5),
+        |xs: &mut [f64]| math::pow_in_place(xs, 1.5)
+    );
+
+    println!("math.fma(x, y, acc) over {} floats", len);
+    time("  element by element", || {
+        let mut acc = vec![1.0; len];
+        for i in 0..len {
+            acc[i] = math::fma(xs[i], ys[i], acc[i]).unwrap();
+        }
+        acc.iter().sum()
+    });
+    time("  in-place helper", || {
+        let mut acc = vec![1.0; len];
+        math::fma_in_place(&mut acc, &xs, &ys).unwrap();
+        acc.iter().sum()
+    });
+}
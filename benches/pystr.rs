@@ -0,0 +1,88 @@
+//! Split/join/replace-heavy workloads on PyStr, with plain `String` code doing
+//! the same work as a baseline.
+//!
+//! Run with `cargo bench --bench pystr`. Set PYSTR_BENCH_LINES to change the
+//! input size (default 200_000 lines).
+
+use std::env;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use stdpython::PyStr;
+
+fn time<F: FnMut() -> usize>(label: &str, mut f: F) -> Duration {
+    let start = Instant::now();
+    let out = black_box(f());
+    let elapsed = start.elapsed();
+    println!("{:<44} {:>10.2?}  ({})", label, elapsed, out);
+    elapsed
+}
+
+fn main() {
+    let lines: usize = env::var("PYSTR_BENCH_LINES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(200_000);
+    let text: Vec<String> = (0..lines)
+        .map(|i| {
+            format!(
+                "  user{} logged in from host{} at port {}  ",
+                i % 97,
+                i % 13,
+                i
+            )
+        })
+        .collect();
+    let strs: Vec<PyStr> = text.iter().map(PyStr::new).collect();
+
+    println!("split + join over {} lines", lines);
+    time("  String: split_whitespace + join", || {
+        text.iter()
+            .map(|l| l.split_whitespace().collect::<Vec<_>>().join("-").len())
+            .sum()
+    });
+    let dash = PyStr::new("-");
+    time("  PyStr: split(None) + join", || {
+        strs.iter()
+            .map(|l| dash.join(l.split(None).unwrap()).as_str().len())
+            .sum()
+    });
+
+    println!("replace");
+    time("  String: replace (hit)", || {
+        text.iter().map(|l| l.replace("host", "node").len()).sum()
+    });
+    time("  PyStr: replace (hit)", || {
+        strs.iter()
+            .map(|l| l.replace("host", "node").as_str().len())
+            .sum()
+    });
+    time("  String: replace (miss)", || {
+        text.iter().map(|l| l.replace("nowhere", "x").len()).sum()
+    });
+    time("  PyStr: replace (miss, shares storage)", || {
+        strs.iter()
+            .map(|l| l.replace("nowhere", "x").as_str().len())
+            .sum()
+    });
+
+    println!("copies and comparisons");
+    time("  String: clone every line", || {
+        text.iter().map(|l| l.clone().len()).sum()
+    });
+    time("  PyStr: clone every line", || {
+        strs.iter().map(|l| l.clone().as_str().len()).sum()
+    });
+    let names = ["__init__", "__call__", "self", "value", "append"];
+    let owned: Vec<String> = (0..lines)
+        .map(|i| names[i % names.len()].to_string())
+        .collect();
+    let interned: Vec<PyStr> = owned.iter().map(|n| PyStr::intern(n)).collect();
+    let (key, key_interned) = ("__init__".to_string(), PyStr::intern("__init__"));
+    time("  String: == against a literal", || {
+        owned.iter().filter(|n| **n == key).count()
+    });
+    time("  PyStr: == against an interned literal", || {
+        interned.iter().filter(|n| **n == key_interned).count()
+    });
+}
@@ -0,0 +1,125 @@
+//! Exercises the alloc-only parts of the crate. With default features this is
+//! an ordinary program; without them it is a `#![no_std]` binary that proves
+//! the crate links against core + alloc only:
+//!
+//! ```text
+//! cargo build --example nostd --no-default-features --profile nostd
+//! ```
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use stdpython::stdlib::string::Template;
+use stdpython::stdlib::{base64, fnmatch};
+use stdpython::{print_to_string, repr, PyDictionary, PyHash, PyList, PyStr, PyValue};
+
+fn run() -> Result<(), &'static str> {
+    let mut d = PyDictionary::new();
+    d.set_item(PyValue::from("answer"), PyValue::Int(42));
+    d.set_item(PyValue::Float(1.0), PyValue::from("one"));
+    if d.get(&PyValue::Int(1)) != Some(&PyValue::from("one")) {
+        return Err("dict lookup");
+    }
+
+    let mut l = PyList::from_vec(Vec::from([3, 1, 2]));
+    l.sort(false);
+    if repr(&l) != "[1, 2, 3]" {
+        return Err("list repr");
+    }
+    if print_to_string(&l) != "[1, 2, 3]\n" {
+        return Err("print");
+    }
+
+    if PyStr::new("abc").py_hash() != Ok(-4594863902769663758) {
+        return Err("str hash");
+    }
+
+    let t = Template::new("$who likes $what");
+    match t.substitute(&[("who", "tim"), ("what", "kung pao")]) {
+        Ok(s) if s == "tim likes kung pao" => {}
+        _ => return Err("template"),
+    }
+
+    if base64::b64encode(b"nostd") != b"bm9zdGQ=" {
+        return Err("base64");
+    }
+    if !fnmatch::fnmatchcase("lib.rs", "*.rs") {
+        return Err("fnmatch");
+    }
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+fn main() {
+    if let Err(what) = run() {
+        eprintln!("nostd example failed: {}", what);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod rt {
+    use core::alloc::{GlobalAlloc, Layout};
+    use core::cell::UnsafeCell;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    const HEAP_SIZE: usize = 1 << 20;
+
+    /// A bump allocator over a static arena; nothing is ever freed.
+    struct Bump {
+        heap: UnsafeCell<[u8; HEAP_SIZE]>,
+        next: AtomicUsize,
+    }
+
+    unsafe impl Sync for Bump {}
+
+    unsafe impl GlobalAlloc for Bump {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let base = self.heap.get() as usize;
+            let mut next = self.next.load(Ordering::Relaxed);
+            loop {
+                let start = (base + next + layout.align() - 1) & !(layout.align() - 1);
+                let end = start - base + layout.size();
+                if end > HEAP_SIZE {
+                    return core::ptr::null_mut();
+                }
+                match self
+                    .next
+                    .compare_exchange(next, end, Ordering::Relaxed, Ordering::Relaxed)
+                {
+                    Ok(_) => return start as *mut u8,
+                    Err(seen) => next = seen,
+                }
+            }
+        }
+
+        unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
+    }
+
+    #[global_allocator]
+    static ALLOC: Bump = Bump {
+        heap: UnsafeCell::new([0; HEAP_SIZE]),
+        next: AtomicUsize::new(0),
+    };
+
+    #[panic_handler]
+    fn panic(_info: &core::panic::PanicInfo) -> ! {
+        loop {}
+    }
+
+    // The C runtime provides the entry point and the `memcmp`/`bcmp` that
+    // `core` lowers slice comparisons to.
+    #[link(name = "c")]
+    extern "C" {}
+
+    #[no_mangle]
+    extern "C" fn main(_argc: i32, _argv: *const *const u8) -> i32 {
+        match super::run() {
+            Ok(()) => 0,
+            Err(_) => 1,
+        }
+    }
+}
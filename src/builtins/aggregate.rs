@@ -0,0 +1,322 @@
+//! min(), max(), sum(), any(), all() and sorted().
+
+use alloc::format;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::ops::{unorderable, PyAdd, PyZero};
+use crate::types::{PyList, PyTruthy, PyTypeId, PyTyped};
+
+/// False for values like NaN that are unordered even against themselves.
+fn is_ordered<K: PartialOrd + ?Sized>(value: &K) -> bool {
+    value.partial_cmp(value).is_some()
+}
+
+/// Keeps the first item whose key compares `want` against every other, the
+/// way CPython's min/max keep the earliest of equal extremes.
+fn extreme<I, K, F>(
+    iterable: I,
+    mut key: F,
+    default: Option<I::Item>,
+    want: Ordering,
+    fname: &str,
+) -> PyResult<I::Item>
+where
+    I: IntoIterator,
+    K: PartialOrd + PyTyped,
+    F: FnMut(&I::Item) -> PyResult<K>,
+{
+    let op = if want == Ordering::Less { "<" } else { ">" };
+    let mut iter = iterable.into_iter();
+    let Some(first) = iter.next() else {
+        return default.ok_or_else(|| {
+            PyException::value_error(format!("{}() iterable argument is empty", fname))
+        });
+    };
+    let mut best_key = key(&first)?;
+    let mut best = first;
+    for item in iter {
+        let k = key(&item)?;
+        match k.partial_cmp(&best_key) {
+            Some(ord) if ord == want => {
+                best = item;
+                best_key = k;
+            }
+            Some(_) => {}
+            None if is_ordered(&k) && is_ordered(&best_key) => {
+                return Err(unorderable(op, &k, &best_key))
+            }
+            // NaN: every comparison is false, so CPython keeps the current best.
+            None => {}
+        }
+    }
+    Ok(best)
+}
+
+/// Python-equivalent min(iterable).
+pub fn min<I>(iterable: I) -> PyResult<I::Item>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd + PyTyped + Clone,
+{
+    extreme(iterable, |x| Ok(x.clone()), None, Ordering::Less, "min")
+}
+
+/// Python-equivalent min(iterable, default=default).
+pub fn min_or<I>(iterable: I, default: I::Item) -> PyResult<I::Item>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd + PyTyped + Clone,
+{
+    extreme(
+        iterable,
+        |x| Ok(x.clone()),
+        Some(default),
+        Ordering::Less,
+        "min",
+    )
+}
+
+/// Python-equivalent min(iterable, key=key[, default=default]).
+pub fn min_by<I, K, F>(iterable: I, key: F, default: Option<I::Item>) -> PyResult<I::Item>
+where
+    I: IntoIterator,
+    K: PartialOrd + PyTyped,
+    F: FnMut(&I::Item) -> PyResult<K>,
+{
+    extreme(iterable, key, default, Ordering::Less, "min")
+}
+
+/// Python-equivalent max(iterable).
+pub fn max<I>(iterable: I) -> PyResult<I::Item>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd + PyTyped + Clone,
+{
+    extreme(iterable, |x| Ok(x.clone()), None, Ordering::Greater, "max")
+}
+
+/// Python-equivalent max(iterable, default=default).
+pub fn max_or<I>(iterable: I, default: I::Item) -> PyResult<I::Item>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd + PyTyped + Clone,
+{
+    extreme(
+        iterable,
+        |x| Ok(x.clone()),
+        Some(default),
+        Ordering::Greater,
+        "max",
+    )
+}
+
+/// Python-equivalent max(iterable, key=key[, default=default]).
+pub fn max_by<I, K, F>(iterable: I, key: F, default: Option<I::Item>) -> PyResult<I::Item>
+where
+    I: IntoIterator,
+    K: PartialOrd + PyTyped,
+    F: FnMut(&I::Item) -> PyResult<K>,
+{
+    extreme(iterable, key, default, Ordering::Greater, "max")
+}
+
+/// Python-equivalent sum(iterable): adds onto the type's zero.
+pub fn sum<I>(iterable: I) -> PyResult<I::Item>
+where
+    I: IntoIterator,
+    I::Item: PyAdd + PyZero + PyTyped,
+{
+    sum_start(iterable, <I::Item as PyZero>::py_zero())
+}
+
+/// Python-equivalent sum(iterable, start). Like CPython, a str or bytes
+/// start is refused in favour of join(). Integers are summed exactly, so
+/// only a total that does not fit raises OverflowError; floats are summed
+/// with Neumaier compensation, as in CPython 3.12.
+pub fn sum_start<I>(iterable: I, start: I::Item) -> PyResult<I::Item>
+where
+    I: IntoIterator,
+    I::Item: PyAdd + PyTyped,
+{
+    match start.py_type() {
+        PyTypeId::Str => Err(PyException::type_error(
+            "sum() can't sum strings [use ''.join(seq) instead]",
+        )),
+        PyTypeId::Bytes => Err(PyException::type_error(
+            "sum() can't sum bytes [use b''.join(seq) instead]",
+        )),
+        _ => PyAdd::py_sum(start, iterable.into_iter()),
+    }
+}
+
+/// Python-equivalent any(iterable): stops at the first true item.
+pub fn any<I>(iterable: I) -> bool
+where
+    I: IntoIterator,
+    I::Item: PyTruthy,
+{
+    iterable.into_iter().any(|item| item.py_bool())
+}
+
+/// Python-equivalent all(iterable): stops at the first false item.
+pub fn all<I>(iterable: I) -> bool
+where
+    I: IntoIterator,
+    I::Item: PyTruthy,
+{
+    iterable.into_iter().all(|item| item.py_bool())
+}
+
+/// Stable sort by `key`, raising TypeError for keys that cannot be ordered.
+/// Reversing keeps equal keys in their original order, as CPython does.
+fn sort_slice<T, K, F>(items: &mut [T], key: F, reverse: bool) -> PyResult<()>
+where
+    K: PartialOrd + PyTyped + ?Sized,
+    F: Fn(&T) -> &K,
+{
+    let mut error = None;
+    items.sort_by(|a, b| {
+        let (a, b) = (key(a), key(b));
+        match a.partial_cmp(b) {
+            Some(ord) if reverse => ord.reverse(),
+            Some(ord) => ord,
+            None => {
+                if error.is_none() && is_ordered(a) && is_ordered(b) {
+                    error = Some(unorderable("<", a, b));
+                }
+                Ordering::Equal
+            }
+        }
+    });
+    error.map_or(Ok(()), Err)
+}
+
+/// Python-equivalent sorted(iterable, reverse=reverse).
+pub fn sorted<I>(iterable: I, reverse: bool) -> PyResult<PyList<I::Item>>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd + PyTyped,
+{
+    let mut items: Vec<I::Item> = iterable.into_iter().collect();
+    sort_slice(&mut items, |item| item, reverse)?;
+    Ok(PyList::from_vec(items))
+}
+
+/// Python-equivalent sorted(iterable, key=key, reverse=reverse); `key` is
+/// called once per item.
+pub fn sorted_by<I, K, F>(iterable: I, mut key: F, reverse: bool) -> PyResult<PyList<I::Item>>
+where
+    I: IntoIterator,
+    K: PartialOrd + PyTyped,
+    F: FnMut(&I::Item) -> PyResult<K>,
+{
+    let mut keyed = iterable
+        .into_iter()
+        .map(|item| Ok((key(&item)?, item)))
+        .collect::<PyResult<Vec<_>>>()?;
+    sort_slice(&mut keyed, |(k, _)| k, reverse)?;
+    Ok(keyed
+        .into_iter()
+        .map(|(_, item)| item)
+        .collect::<Vec<_>>()
+        .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PyFunction, PyList, PyStr, PyValue};
+
+    #[test]
+    fn min_max_basics() {
+        assert_eq!(min(vec![3, 1, 2]).unwrap(), 1);
+        assert_eq!(max([2.5, 7.0, -1.0]).unwrap(), 7.0);
+        let words = PyList::from_vec(vec!["pear", "fig", "apple"]);
+        assert_eq!(*min(words.iter()).unwrap(), "apple");
+        let err = max(Vec::<i64>::new()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: max() iterable argument is empty"
+        );
+        assert_eq!(min_or(Vec::<i64>::new(), 0).unwrap(), 0);
+    }
+
+    #[test]
+    fn key_functions_keep_first_extreme() {
+        let words = ["bb", "a", "cc", "d"];
+        assert_eq!(min_by(words.iter(), |w| Ok(w.len()), None).unwrap(), &"a");
+        assert_eq!(max_by(words.iter(), |w| Ok(w.len()), None).unwrap(), &"bb");
+        let neg = PyFunction::unary("neg", |v| match v {
+            PyValue::Int(i) => Ok(PyValue::Int(-i)),
+            _ => Ok(PyValue::None),
+        });
+        let values = vec![PyValue::Int(1), PyValue::Int(5), PyValue::Int(3)];
+        let smallest = min_by(values, |v| neg.call1(v.clone()), None).unwrap();
+        assert_eq!(smallest, PyValue::Int(5));
+    }
+
+    #[test]
+    fn mixed_types_are_unorderable() {
+        let values = vec![PyValue::Int(1), PyValue::from("a")];
+        let err = max(values).unwrap_err();
+        assert_eq!(
+            err.message,
+            "'>' not supported between instances of 'str' and 'int'"
+        );
+        let mixed = vec![PyValue::Int(2), PyValue::Float(1.5), PyValue::Bool(true)];
+        assert_eq!(min(mixed).unwrap(), PyValue::Bool(true));
+    }
+
+    #[test]
+    fn sum_with_start() {
+        assert_eq!(sum(1..=4).unwrap(), 10);
+        assert_eq!(sum(vec![0.5, 0.25]).unwrap(), 0.75);
+        assert_eq!(sum(vec![0.1; 10]).unwrap(), 1.0);
+        assert_eq!(sum([i64::MAX, 1, -1]).unwrap(), i64::MAX);
+        assert!(sum([i64::MAX, 1]).unwrap_err().is("OverflowError"));
+        let lists = vec![PyList::from_vec(vec![1]), PyList::from_vec(vec![2, 3])];
+        assert_eq!(
+            sum_start(lists, PyList::new()).unwrap().into_vec(),
+            vec![1, 2, 3]
+        );
+        let err = sum_start(vec![PyStr::new("a")], PyStr::new("")).unwrap_err();
+        assert_eq!(
+            err.message,
+            "sum() can't sum strings [use ''.join(seq) instead]"
+        );
+        let values = vec![PyValue::Int(1), PyValue::Float(2.5)];
+        assert_eq!(sum(values).unwrap(), PyValue::Float(3.5));
+        assert!(sum(vec![PyValue::Int(1), PyValue::None]).is_err());
+    }
+
+    #[test]
+    fn any_all_use_truthiness() {
+        assert!(any([0, 0, 3]) && !any(Vec::<i64>::new()));
+        assert!(all(Vec::<PyValue>::new()) && !all(["a", ""]));
+        let values = PyList::from_vec(vec![PyValue::from("x"), PyValue::None]);
+        assert!(any(values.iter()) && !all(values.iter()));
+    }
+
+    #[test]
+    fn sorted_is_stable_and_checks_types() {
+        assert_eq!(sorted(vec![3, 1, 2], false).unwrap().into_vec(), [1, 2, 3]);
+        assert_eq!(
+            sorted("bca".chars(), true).unwrap().into_vec(),
+            ['c', 'b', 'a']
+        );
+        let words = ["bb", "a", "cc", "d"];
+        let by_len = sorted_by(words, |w| Ok(w.len()), true).unwrap();
+        assert_eq!(by_len.into_vec(), ["bb", "cc", "a", "d"]);
+        let mixed = vec![PyValue::Int(1), PyValue::from("a")];
+        assert!(sorted(mixed, false).unwrap_err().is("TypeError"));
+        assert!(sorted_by(
+            [1, 2],
+            |_| Err::<i64, _>(PyException::value_error("k")),
+            false
+        )
+        .unwrap_err()
+        .is("ValueError"));
+    }
+}
@@ -0,0 +1,221 @@
+//! The containers comprehensions build. `[f(x) for x in xs if p(x)]` lowers
+//! to an iterator chain collected by [`py_list_from_iter`], and likewise for
+//! set and dict comprehensions.
+//!
+//! [`comprehension`] spells the same chain clause by clause, so codegen can
+//! emit one call per `for` and `if`:
+//!
+//! ```text
+//! [(x, y) for x in xs if x for y in ys if x != y]
+//!
+//! comprehension(&xs)
+//!     .filter(|x| x.py_bool())
+//!     .then_for(|x| ys.iter().map(move |y| (x, y)))
+//!     .filter(|(x, y)| x != y)
+//!     .list(|pair| pair)
+//! ```
+//!
+//! Every clause is fused into a single pass that writes straight into the
+//! result; no clause buffers its output.
+
+use core::hash::Hash;
+use core::iter::{Filter, FlatMap, FusedIterator, Map};
+
+use crate::types::{PyDictionary, PyList, PySet};
+
+/// Collects a list comprehension.
+pub fn py_list_from_iter<I: IntoIterator>(iter: I) -> PyList<I::Item> {
+    iter.into_iter().collect()
+}
+
+/// Collects a set comprehension.
+pub fn py_set_from_iter<I>(iter: I) -> PySet<I::Item>
+where
+    I: IntoIterator,
+    I::Item: Hash + Eq,
+{
+    iter.into_iter().collect()
+}
+
+/// Collects a dict comprehension. A repeated key keeps its first position
+/// and its last value, as in Python.
+pub fn py_dict_from_iter<I, K, V>(iter: I) -> PyDictionary<K, V>
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: Hash + Eq,
+{
+    iter.into_iter().collect()
+}
+
+/// The clauses of a comprehension, built up from the first `for`.
+#[derive(Clone)]
+pub struct Comprehension<I> {
+    iter: I,
+}
+
+/// Starts a comprehension at its first `for x in iterable` clause.
+pub fn comprehension<I: IntoIterator>(iterable: I) -> Comprehension<I::IntoIter> {
+    Comprehension {
+        iter: iterable.into_iter(),
+    }
+}
+
+impl<I: Iterator> Comprehension<I> {
+    /// An `if cond` clause.
+    pub fn filter<P>(self, cond: P) -> Comprehension<Filter<I, P>>
+    where
+        P: FnMut(&I::Item) -> bool,
+    {
+        Comprehension {
+            iter: self.iter.filter(cond),
+        }
+    }
+
+    /// A nested `for y in iterable` clause. `inner` receives the outer
+    /// variables and yields everything later clauses can see, usually by
+    /// pairing them with `y`.
+    pub fn then_for<J, F>(self, inner: F) -> Comprehension<FlatMap<I, J, F>>
+    where
+        J: IntoIterator,
+        F: FnMut(I::Item) -> J,
+    {
+        Comprehension {
+            iter: self.iter.flat_map(inner),
+        }
+    }
+
+    /// Binds derived variables for later clauses, e.g. `if (n := len(s))`.
+    pub fn map<B, F>(self, f: F) -> Comprehension<Map<I, F>>
+    where
+        F: FnMut(I::Item) -> B,
+    {
+        Comprehension {
+            iter: self.iter.map(f),
+        }
+    }
+
+    /// `[element for ...]`.
+    pub fn list<T, F>(self, element: F) -> PyList<T>
+    where
+        F: FnMut(I::Item) -> T,
+    {
+        let mut out = PyList::with_capacity(self.iter.size_hint().0);
+        out.extend(self.iter.map(element));
+        out
+    }
+
+    /// `{element for ...}`. Unlike [`list`](Self::list) this does not
+    /// presize: set comprehensions often collapse many items into few.
+    pub fn set<T, F>(self, element: F) -> PySet<T>
+    where
+        T: Hash + Eq,
+        F: FnMut(I::Item) -> T,
+    {
+        py_set_from_iter(self.iter.map(element))
+    }
+
+    /// `{key: value for ...}`; `entry` returns the pair. Presized for one
+    /// key per item.
+    pub fn dict<K, V, F>(self, entry: F) -> PyDictionary<K, V>
+    where
+        K: Hash + Eq,
+        F: FnMut(I::Item) -> (K, V),
+    {
+        let mut out = PyDictionary::new();
+        out.reserve(self.iter.size_hint().0);
+        out.update(self.iter.map(entry));
+        out
+    }
+}
+
+/// Iterating a comprehension runs it lazily, as a generator expression.
+impl<I: Iterator> Iterator for Comprehension<I> {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    fn fold<B, F: FnMut(B, I::Item) -> B>(self, init: B, f: F) -> B {
+        self.iter.fold(init, f)
+    }
+}
+
+impl<I: FusedIterator> FusedIterator for Comprehension<I> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::collections::OrderedDict;
+    use crate::types::PyTuple;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn comprehensions_collect() {
+        let xs = PyList::from_vec(alloc::vec![3, 1, 4, 1, 5]);
+        let odds = py_list_from_iter(xs.iter().filter(|x| *x % 2 == 1).map(|x| x * 10));
+        assert_eq!(odds.as_slice(), [30, 10, 10, 50]);
+        let unique = py_set_from_iter(&xs);
+        assert_eq!(unique.len(), 4);
+        let squares = py_dict_from_iter(xs.iter().map(|x| (*x, x * x)));
+        assert_eq!(squares.keys().to_list(), [3, 1, 4, 5]);
+    }
+
+    #[test]
+    fn builder_fuses_nested_clauses() {
+        let xs = PyList::from_vec(alloc::vec![0, 1, 2, 3]);
+        let ys = [1, 2];
+        let pairs = comprehension(&xs)
+            .filter(|x| **x != 0)
+            .then_for(|x| ys.iter().map(move |y| (*x, *y)))
+            .filter(|(x, y)| x != y)
+            .list(|pair| pair);
+        assert_eq!(pairs.as_slice(), [(1, 2), (2, 1), (3, 1), (3, 2)]);
+
+        let sums = comprehension(&xs)
+            .then_for(|x| ys.iter().map(move |y| x + y))
+            .set(|s| s);
+        assert_eq!(sums.len(), 5);
+
+        let words = ["a", "bb", "cc", "ddd"];
+        let by_len = comprehension(words)
+            .map(|w| (w.len(), w))
+            .filter(|(n, _)| *n > 1)
+            .dict(|(n, w)| (n, w));
+        assert_eq!(by_len.keys().to_list(), [2, 3]);
+        assert_eq!(by_len.get(&2), Some(&"cc"));
+
+        let total: i64 = comprehension(&xs).filter(|x| **x % 2 == 1).sum();
+        assert_eq!(total, 4);
+    }
+
+    #[test]
+    fn containers_round_trip_through_iterators() {
+        let mut d: PyDictionary<&str, i64> = [("a", 1)].into_iter().collect();
+        d.extend([("b", 2), ("a", 3)]);
+        let pairs: Vec<(&str, i64)> = d.clone().into_iter().collect();
+        assert_eq!(pairs, [("a", 3), ("b", 2)]);
+        assert_eq!((&d).into_iter().next_back(), Some((&"b", &2)));
+
+        let mut s: PySet<i64> = [1, 2].into_iter().collect();
+        s.extend([2, 3]);
+        assert_eq!(s.into_iter().collect::<Vec<_>>(), [1, 2, 3]);
+
+        let mut od: OrderedDict<i64, i64> = (0..4).map(|i| (i, i)).collect();
+        od.move_to_end(&0, true).unwrap();
+        od.extend([(9, 9)]);
+        let keys: Vec<i64> = od.into_iter().rev().map(|(k, _)| k).collect();
+        assert_eq!(keys, [9, 0, 3, 2, 1]);
+
+        let mut l: PyList<i64> = (1..=3).collect();
+        for x in &mut l {
+            *x *= 2;
+        }
+        l.extend(PyTuple::from_iter([7]));
+        assert_eq!(l.as_slice(), [2, 4, 6, 7]);
+    }
+}
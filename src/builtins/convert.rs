@@ -0,0 +1,397 @@
+//! int(), float(), bool() and str() conversions. Strings follow CPython's
+//! literal grammar: surrounding whitespace, a sign, `_` between digits,
+//! base prefixes, and float's `inf`/`nan` spellings. The `*_value` forms
+//! apply the same rules to a dynamic [`PyValue`], and [`index`] is the
+//! lossless `__index__` conversion that range() and slicing require.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::repr::repr_str;
+use crate::types::{PyBytes, PyRepr, PyTruthy, PyValue};
+
+/// Python-equivalent int(s).
+pub fn int(s: &str) -> PyResult<i64> {
+    int_with_base(s, 10)
+}
+
+/// Python-equivalent int(s, base). Base 0 reads the base from a `0x`, `0o`
+/// or `0b` prefix, defaulting to decimal; bases 2, 8 and 16 also accept
+/// their prefix. Values outside i64 raise OverflowError.
+pub fn int_with_base(s: &str, base: u32) -> PyResult<i64> {
+    if base == 1 || base > 36 {
+        return Err(PyException::value_error(
+            "int() base must be >= 2 and <= 36, or 0",
+        ));
+    }
+    let invalid = || {
+        PyException::value_error(format!(
+            "invalid literal for int() with base {}: {}",
+            base,
+            repr_str(s)
+        ))
+    };
+    let (negative, body) = split_sign(s.trim());
+    let prefixed = |p: char| {
+        let mut chars = body.chars();
+        (chars.next() == Some('0') && chars.next().map(|c| c.to_ascii_lowercase()) == Some(p))
+            .then(|| &body[2..])
+    };
+    let (radix, digits, after_prefix) = match base {
+        0 => match [('x', 16), ('o', 8), ('b', 2)]
+            .into_iter()
+            .find_map(|(p, radix)| prefixed(p).map(|rest| (radix, rest)))
+        {
+            Some((radix, rest)) => (radix, rest, true),
+            // A bare decimal literal in base 0 may not have leading zeros.
+            None if body.starts_with('0') && body.chars().any(|c| !matches!(c, '0' | '_')) => {
+                return Err(invalid())
+            }
+            None => (10, body, false),
+        },
+        16 | 8 | 2 => {
+            let p = match base {
+                16 => 'x',
+                8 => 'o',
+                _ => 'b',
+            };
+            match prefixed(p) {
+                Some(rest) => (base, rest, true),
+                None => (base, body, false),
+            }
+        }
+        _ => (base, body, false),
+    };
+    // An underscore may follow a base prefix, as in 0x_ff.
+    let digits = match digits.strip_prefix('_') {
+        Some(rest) if after_prefix => rest,
+        _ => digits,
+    };
+    let mut value: i128 = 0;
+    let mut seen = false;
+    let mut after_digit = false;
+    for c in digits.chars() {
+        if c == '_' && after_digit {
+            after_digit = false;
+            continue;
+        }
+        let digit = c.to_digit(radix).ok_or_else(invalid)?;
+        value = value * radix as i128 + digit as i128;
+        if value > i64::MAX as i128 + 1 {
+            return Err(int_overflow());
+        }
+        seen = true;
+        after_digit = true;
+    }
+    if !seen || !after_digit {
+        return Err(invalid());
+    }
+    let value = if negative { -value } else { value };
+    i64::try_from(value).map_err(|_| int_overflow())
+}
+
+/// Python-equivalent operator.index(value): ints and bools only, since
+/// anything else would lose information.
+pub fn index(value: &PyValue) -> PyResult<i64> {
+    match value {
+        PyValue::Bool(b) => Ok(*b as i64),
+        PyValue::Int(i) => Ok(*i),
+        other => Err(PyException::type_error(format!(
+            "'{}' object cannot be interpreted as an integer",
+            other.type_name()
+        ))),
+    }
+}
+
+/// Python-equivalent int(value): floats truncate toward zero, and str and
+/// bytes are parsed as decimal literals.
+pub fn int_value(value: &PyValue) -> PyResult<i64> {
+    match value {
+        PyValue::Bool(_) | PyValue::Int(_) => index(value),
+        PyValue::Float(f) => float_to_int(*f),
+        PyValue::Str(s) => int(s.as_str()),
+        PyValue::Bytes(b) => bytes_to_int(b, 10),
+        other => Err(PyException::type_error(format!(
+            "int() argument must be a string, a bytes-like object or a real number, not '{}'",
+            other.type_name()
+        ))),
+    }
+}
+
+/// Python-equivalent int(value, base), which only parses str and bytes.
+pub fn int_value_with_base(value: &PyValue, base: u32) -> PyResult<i64> {
+    match value {
+        PyValue::Str(s) => int_with_base(s.as_str(), base),
+        PyValue::Bytes(b) => bytes_to_int(b, base),
+        _ => Err(PyException::type_error(
+            "int() can't convert non-string with explicit base",
+        )),
+    }
+}
+
+fn bytes_to_int(b: &PyBytes, base: u32) -> PyResult<i64> {
+    let text = String::from_utf8_lossy(b.as_bytes());
+    int_with_base(&text, base).map_err(|err| {
+        if err.is("ValueError") && err.message.starts_with("invalid literal") {
+            PyException::value_error(format!(
+                "invalid literal for int() with base {}: {}",
+                base,
+                b.py_repr()
+            ))
+        } else {
+            err
+        }
+    })
+}
+
+fn float_to_int(f: f64) -> PyResult<i64> {
+    if f.is_nan() {
+        return Err(PyException::value_error(
+            "cannot convert float NaN to integer",
+        ));
+    }
+    if f.is_infinite() {
+        return Err(PyException::new(
+            "OverflowError",
+            "cannot convert float infinity to integer",
+        ));
+    }
+    // i64::MAX is not a float; 2**63 is the first value out of range. No
+    // float lies strictly between -2**63 - 1 and -2**63, so the bounds hold
+    // before truncation, and the cast truncates without std's f64::trunc.
+    if f >= 9_223_372_036_854_775_808.0 || f < i64::MIN as f64 {
+        return Err(int_overflow());
+    }
+    Ok(f as i64)
+}
+
+/// Python-equivalent float(value).
+pub fn float_value(value: &PyValue) -> PyResult<f64> {
+    match value {
+        PyValue::Bool(b) => Ok(*b as i64 as f64),
+        PyValue::Int(i) => Ok(*i as f64),
+        PyValue::Float(f) => Ok(*f),
+        PyValue::Str(s) => float(s.as_str()),
+        PyValue::Bytes(b) => float(&String::from_utf8_lossy(b.as_bytes())).map_err(|_| {
+            PyException::value_error(format!(
+                "could not convert string to float: {}",
+                b.py_repr()
+            ))
+        }),
+        other => Err(PyException::type_error(format!(
+            "float() argument must be a string or a real number, not '{}'",
+            other.type_name()
+        ))),
+    }
+}
+
+/// Python-equivalent bool(value): its truthiness.
+pub fn bool_value(value: &PyValue) -> bool {
+    value.py_bool()
+}
+
+/// Python-equivalent str(value).
+pub fn str_value(value: &PyValue) -> String {
+    value.to_string()
+}
+
+fn int_overflow() -> PyException {
+    PyException::new("OverflowError", "Python int too large to convert to C long")
+}
+
+fn split_sign(s: &str) -> (bool, &str) {
+    match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    }
+}
+
+/// Python-equivalent float(s): decimal and exponent forms with `_` between
+/// digits, plus `inf`, `infinity` and `nan` in any case.
+pub fn float(s: &str) -> PyResult<f64> {
+    let invalid = || {
+        PyException::value_error(format!(
+            "could not convert string to float: {}",
+            repr_str(s)
+        ))
+    };
+    let (negative, body) = split_sign(s.trim());
+    let magnitude = if body.eq_ignore_ascii_case("inf") || body.eq_ignore_ascii_case("infinity") {
+        f64::INFINITY
+    } else if body.eq_ignore_ascii_case("nan") {
+        f64::NAN
+    } else {
+        decimal(body).ok_or_else(invalid)?
+    };
+    Ok(if negative { -magnitude } else { magnitude })
+}
+
+/// Parses `digitpart ["." [digitpart]] [exponent]` or `"." digitpart
+/// [exponent]`, rejecting everything else.
+fn decimal(s: &str) -> Option<f64> {
+    let mut clean = String::with_capacity(s.len());
+    let (mantissa, exponent) = match s.find(['e', 'E']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+    let (int_part, frac_part) = match mantissa.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (mantissa, None),
+    };
+    let int_digits = digit_part(int_part, &mut clean)?;
+    let mut frac_digits = 0;
+    if let Some(frac) = frac_part {
+        clean.push('.');
+        frac_digits = digit_part(frac, &mut clean)?;
+    }
+    if int_digits + frac_digits == 0 {
+        return None;
+    }
+    if let Some(exponent) = exponent {
+        clean.push('e');
+        let (negative, digits) = split_sign(exponent);
+        if negative {
+            clean.push('-');
+        }
+        if digit_part(digits, &mut clean)? == 0 {
+            return None;
+        }
+    }
+    clean.parse().ok()
+}
+
+/// Copies the ASCII digits of `s` into `out`, dropping single underscores
+/// between digits; None if `s` is anything else. Returns the digit count.
+fn digit_part(s: &str, out: &mut String) -> Option<usize> {
+    let mut count = 0;
+    let mut after_digit = false;
+    for c in s.chars() {
+        match c {
+            '0'..='9' => {
+                out.push(c);
+                count += 1;
+                after_digit = true;
+            }
+            '_' if after_digit => after_digit = false,
+            _ => return None,
+        }
+    }
+    (count == 0 || after_digit).then_some(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn int_accepts_cpython_literals() {
+        assert_eq!(int("  -42\n").unwrap(), -42);
+        assert_eq!(int("+1_000_000").unwrap(), 1_000_000);
+        assert_eq!(int("007").unwrap(), 7);
+        assert_eq!(int_with_base("ff", 16).unwrap(), 255);
+        assert_eq!(int_with_base("0xFF", 16).unwrap(), 255);
+        assert_eq!(int_with_base("-0b1010", 0).unwrap(), -10);
+        assert_eq!(int_with_base("0o_17", 0).unwrap(), 15);
+        assert_eq!(int_with_base("0_0", 0).unwrap(), 0);
+        assert_eq!(int_with_base("zz", 36).unwrap(), 1295);
+        assert_eq!(int("-9223372036854775808").unwrap(), i64::MIN);
+    }
+
+    #[test]
+    fn int_rejects_like_cpython() {
+        let err = int("12a").unwrap_err();
+        assert_eq!(err.message, "invalid literal for int() with base 10: '12a'");
+        for bad in ["", " ", "_1", "1_", "1__0", "1.0", "0x10", "- 1"] {
+            assert!(int(bad).unwrap_err().is("ValueError"), "{:?}", bad);
+        }
+        assert!(int_with_base("010", 0).unwrap_err().is("ValueError"));
+        assert!(int_with_base("0x", 16).unwrap_err().is("ValueError"));
+        assert!(int_with_base("12", 2).unwrap_err().is("ValueError"));
+        assert_eq!(
+            int_with_base("1", 37).unwrap_err().message,
+            "int() base must be >= 2 and <= 36, or 0"
+        );
+        assert!(int("9223372036854775808").unwrap_err().is("OverflowError"));
+        assert!(int("99999999999999999999999999999999999999999")
+            .unwrap_err()
+            .is("OverflowError"));
+    }
+
+    #[test]
+    fn conversions_of_dynamic_values() {
+        use crate::types::PyList;
+        assert_eq!(int_value(&PyValue::Bool(true)).unwrap(), 1);
+        assert_eq!(int_value(&PyValue::Float(-3.9)).unwrap(), -3);
+        assert_eq!(
+            int_value(&PyValue::Float(-9_223_372_036_854_775_808.0)).unwrap(),
+            i64::MIN
+        );
+        assert_eq!(int_value(&PyValue::from(" 12 ")).unwrap(), 12);
+        let bytes = PyValue::Bytes(PyBytes::new(&b"12a"[..]));
+        assert_eq!(
+            int_value(&bytes).unwrap_err().message,
+            "invalid literal for int() with base 10: b'12a'"
+        );
+        assert_eq!(
+            int_value(&PyValue::List(PyList::new()))
+                .unwrap_err()
+                .message,
+            "int() argument must be a string, a bytes-like object or a real number, not 'list'"
+        );
+        assert!(int_value(&PyValue::Float(f64::NAN))
+            .unwrap_err()
+            .is("ValueError"));
+        assert!(int_value(&PyValue::Float(f64::INFINITY))
+            .unwrap_err()
+            .is("OverflowError"));
+        assert!(int_value(&PyValue::Float(1e19))
+            .unwrap_err()
+            .is("OverflowError"));
+        assert_eq!(int_value_with_base(&PyValue::from("12"), 16).unwrap(), 18);
+        assert_eq!(
+            int_value_with_base(&PyValue::Int(12), 16)
+                .unwrap_err()
+                .message,
+            "int() can't convert non-string with explicit base"
+        );
+
+        assert_eq!(float_value(&PyValue::Int(2)).unwrap(), 2.0);
+        let bytes = PyValue::Bytes(PyBytes::new(&b"1.5"[..]));
+        assert_eq!(float_value(&bytes).unwrap(), 1.5);
+        assert_eq!(
+            float_value(&PyValue::None).unwrap_err().message,
+            "float() argument must be a string or a real number, not 'NoneType'"
+        );
+        assert!(!bool_value(&PyValue::List(PyList::new())));
+        assert_eq!(str_value(&PyValue::Float(1.0)), "1.0");
+
+        assert_eq!(index(&PyValue::Bool(false)).unwrap(), 0);
+        assert_eq!(
+            index(&PyValue::Float(1.0)).unwrap_err().message,
+            "'float' object cannot be interpreted as an integer"
+        );
+    }
+
+    #[test]
+    fn float_accepts_cpython_literals() {
+        assert_eq!(float(" 1.5 ").unwrap(), 1.5);
+        assert_eq!(float("1_000.000_1").unwrap(), 1000.0001);
+        assert_eq!(float("-.5e-3").unwrap(), -0.0005);
+        assert_eq!(float("1.").unwrap(), 1.0);
+        assert_eq!(float("1E+2_0").unwrap(), 1e20);
+        assert_eq!(float("-Infinity").unwrap(), f64::NEG_INFINITY);
+        assert_eq!(float("inF").unwrap(), f64::INFINITY);
+        assert!(float("+nan").unwrap().is_nan());
+        assert_eq!(float("1e400").unwrap(), f64::INFINITY);
+
+        let err = float("abc").unwrap_err();
+        assert_eq!(err.message, "could not convert string to float: 'abc'");
+        for bad in [
+            "", ".", "e5", "1e", "1_.5", "1._5", "_1", "0x10", "infinit", "1.5f", "--1",
+        ] {
+            assert!(float(bad).unwrap_err().is("ValueError"), "{:?}", bad);
+        }
+    }
+}
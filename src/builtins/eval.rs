@@ -0,0 +1,954 @@
+//! Python-equivalent eval() and exec() over the expression subset that
+//! [`crate::stdlib::ast`] parses, with names looked up in a caller-supplied
+//! namespace. Dotted names that are not bound there resolve to functions
+//! registered with [`python_function!`](crate::python_function), so
+//! `math.sqrt(x)` works when `math` is not a variable.
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::ast::{
+    self, check_hashable, BinOp, BoolOp, CmpOp, Expr, ExprKind, Stmt, UnaryOp,
+};
+use crate::types::compare::{richcompare, CompareOp};
+use crate::types::function::{self, KwargsMap};
+use crate::types::ops::unsupported_operands;
+use crate::types::{
+    PyAdd, PyBytes, PyCallable, PyDictionary, PyList, PySet, PySlice, PyStr, PyTruthy, PyTuple,
+    PyValue,
+};
+
+/// The globals eval() and exec() read and exec() assigns.
+pub type Namespace = PyDictionary<String, PyValue>;
+
+/// Python-equivalent eval(source, namespace) for the supported subset.
+pub fn eval(source: &str, namespace: &Namespace) -> PyResult<PyValue> {
+    let expr = ast::parse_expression_in(source.trim_start_matches([' ', '\t']), "<string>")?;
+    eval_expr(&expr, namespace)
+}
+
+/// Python-equivalent exec(source, namespace): runs simple statements,
+/// binding assigned names in `namespace`.
+pub fn exec(source: &str, namespace: &mut Namespace) -> PyResult<()> {
+    for stmt in ast::parse_in(source, "<string>")? {
+        match stmt {
+            Stmt::Expr(expr) => {
+                eval_expr(&expr, namespace)?;
+            }
+            Stmt::Assign(targets, value) => {
+                let value = eval_expr(&value, namespace)?;
+                for target in &targets {
+                    assign(target, value.clone(), namespace)?;
+                }
+            }
+            Stmt::AugAssign(target, op, value) => {
+                let current = eval_expr(&target, namespace)?;
+                let value = eval_expr(&value, namespace)?;
+                assign(&target, binary(op, current, value)?, namespace)?;
+            }
+            Stmt::Delete(targets) => {
+                for target in &targets {
+                    delete(target, namespace)?;
+                }
+            }
+            Stmt::Pass => {}
+        }
+    }
+    Ok(())
+}
+
+fn name_error(name: &str) -> PyException {
+    PyException::new("NameError", format!("name '{}' is not defined", name))
+}
+
+/// `a.b.c` for a chain of attributes on a name `namespace` does not bind.
+fn dotted_path(expr: &Expr, namespace: &Namespace) -> Option<String> {
+    match &expr.kind {
+        ExprKind::Name(name) if !namespace.contains_key(name) => Some(name.clone()),
+        ExprKind::Attribute(value, attr) => {
+            dotted_path(value, namespace).map(|path| format!("{}.{}", path, attr))
+        }
+        _ => None,
+    }
+}
+
+/// Evaluates a parsed expression against `namespace`.
+pub fn eval_expr(expr: &Expr, namespace: &Namespace) -> PyResult<PyValue> {
+    match &expr.kind {
+        ExprKind::Constant(value) => Ok(value.clone()),
+        ExprKind::Name(name) => namespace.get(name).cloned().ok_or_else(|| name_error(name)),
+        ExprKind::Tuple(items) => {
+            eval_all(items, namespace).map(|v| PyValue::Tuple(PyTuple::from_vec(v)))
+        }
+        ExprKind::List(items) => {
+            eval_all(items, namespace).map(|v| PyValue::List(PyList::from_vec(v)))
+        }
+        ExprKind::Set(items) => {
+            let mut set = PySet::new();
+            for value in eval_all(items, namespace)? {
+                check_hashable(&value)?;
+                set.add(value);
+            }
+            Ok(PyValue::Set(set))
+        }
+        ExprKind::Dict(pairs) => {
+            let mut dict = PyDictionary::new();
+            for (key, value) in pairs {
+                let key = eval_expr(key, namespace)?;
+                check_hashable(&key)?;
+                dict.set_item(key, eval_expr(value, namespace)?);
+            }
+            Ok(PyValue::Dict(dict))
+        }
+        ExprKind::UnaryOp(op, operand) => unary(*op, eval_expr(operand, namespace)?),
+        ExprKind::BinOp(left, op, right) => {
+            let left = eval_expr(left, namespace)?;
+            binary(*op, left, eval_expr(right, namespace)?)
+        }
+        ExprKind::BoolOp(op, values) => {
+            let mut result = PyValue::None;
+            for value in values {
+                result = eval_expr(value, namespace)?;
+                if result.py_bool() == (*op == BoolOp::Or) {
+                    break;
+                }
+            }
+            Ok(result)
+        }
+        ExprKind::Compare(left, comparators) => {
+            let mut left = eval_expr(left, namespace)?;
+            let mut result = PyValue::Bool(true);
+            for (op, right) in comparators {
+                let right = eval_expr(right, namespace)?;
+                result = compare(*op, &left, &right)?;
+                if !result.py_bool() {
+                    break;
+                }
+                left = right;
+            }
+            Ok(result)
+        }
+        ExprKind::IfExp { test, body, orelse } => {
+            if eval_expr(test, namespace)?.py_bool() {
+                eval_expr(body, namespace)
+            } else {
+                eval_expr(orelse, namespace)
+            }
+        }
+        ExprKind::Subscript(value, index) => {
+            let value = eval_expr(value, namespace)?;
+            subscript(&value, index, namespace)
+        }
+        ExprKind::Slice { .. } => Err(PyException::type_error(
+            "slices are only valid as subscripts",
+        )),
+        ExprKind::Attribute(value, attr) => match dotted_path(value, namespace) {
+            Some(module) => match function::lookup(&module, attr) {
+                Ok(entry) => Ok(PyValue::Function(entry.to_function())),
+                Err(err) if err.is("ModuleNotFoundError") => {
+                    let root = module.split('.').next().unwrap_or_default();
+                    Err(name_error(root))
+                }
+                Err(err) => Err(err),
+            },
+            None => {
+                let value = eval_expr(value, namespace)?;
+                Err(PyException::attribute_error(format!(
+                    "'{}' object has no attribute '{}'",
+                    value.type_name(),
+                    attr
+                )))
+            }
+        },
+        ExprKind::Call {
+            func,
+            args,
+            keywords,
+        } => {
+            let func = eval_expr(func, namespace)?;
+            let args = eval_all(args, namespace)?;
+            let mut kwargs = KwargsMap::new();
+            for (name, value) in keywords {
+                kwargs.set_item(name.clone(), eval_expr(value, namespace)?);
+            }
+            match func {
+                PyValue::Function(f) => f.call(&args, &kwargs),
+                other => Err(PyException::type_error(format!(
+                    "'{}' object is not callable",
+                    other.type_name()
+                ))),
+            }
+        }
+    }
+}
+
+fn eval_all(items: &[Expr], namespace: &Namespace) -> PyResult<Vec<PyValue>> {
+    items
+        .iter()
+        .map(|item| eval_expr(item, namespace))
+        .collect()
+}
+
+fn int_of(value: &PyValue) -> Option<i64> {
+    match value {
+        PyValue::Bool(b) => Some(*b as i64),
+        PyValue::Int(i) => Some(*i),
+        _ => None,
+    }
+}
+
+fn float_of(value: &PyValue) -> Option<f64> {
+    match value {
+        PyValue::Float(f) => Some(*f),
+        other => int_of(other).map(|i| i as f64),
+    }
+}
+
+fn overflow(what: &str) -> PyException {
+    PyException::new("OverflowError", format!("integer {} overflowed", what))
+}
+
+fn zero_division(msg: &str) -> PyException {
+    PyException::new("ZeroDivisionError", msg)
+}
+
+fn unary(op: UnaryOp, value: PyValue) -> PyResult<PyValue> {
+    let result = match (op, &value) {
+        (UnaryOp::Not, _) => Some(PyValue::Bool(!value.py_bool())),
+        (UnaryOp::UAdd, PyValue::Float(_)) => Some(value.clone()),
+        (UnaryOp::USub, PyValue::Float(f)) => Some(PyValue::Float(-f)),
+        (UnaryOp::UAdd, _) => int_of(&value).map(PyValue::Int),
+        (UnaryOp::USub, _) => match int_of(&value) {
+            Some(i) => Some(PyValue::Int(
+                i.checked_neg().ok_or_else(|| overflow("negation"))?,
+            )),
+            None => None,
+        },
+        (UnaryOp::Invert, _) => int_of(&value).map(|i| PyValue::Int(!i)),
+    };
+    result.ok_or_else(|| {
+        PyException::type_error(format!(
+            "bad operand type for unary {}: '{}'",
+            op.symbol(),
+            value.type_name()
+        ))
+    })
+}
+
+/// CPython's float_divmod: the floor quotient and the modulus with the
+/// divisor's sign.
+fn float_divmod(x: f64, y: f64) -> (f64, f64) {
+    let mut modulus = x % y;
+    let mut div = (x - modulus) / y;
+    if modulus != 0.0 {
+        if (y < 0.0) != (modulus < 0.0) {
+            modulus += y;
+            div -= 1.0;
+        }
+    } else {
+        modulus = 0.0f64.copysign(y);
+    }
+    let floordiv = if div != 0.0 {
+        let floor = div.floor();
+        if div - floor > 0.5 {
+            floor + 1.0
+        } else {
+            floor
+        }
+    } else {
+        0.0f64.copysign(x / y)
+    };
+    (floordiv, modulus)
+}
+
+fn float_pow(x: f64, y: f64) -> PyResult<f64> {
+    if x == 0.0 && y < 0.0 {
+        return Err(zero_division("0.0 cannot be raised to a negative power"));
+    }
+    if x < 0.0 && y.is_finite() && y.fract() != 0.0 {
+        return Err(PyException::value_error(
+            "negative number cannot be raised to a fractional power",
+        ));
+    }
+    let r = x.powf(y);
+    if r.is_infinite() && x.is_finite() && y.is_finite() {
+        return Err(PyException::new(
+            "OverflowError",
+            "(34, 'Numerical result out of range')",
+        ));
+    }
+    Ok(r)
+}
+
+fn int_binary(op: BinOp, a: i64, b: i64) -> PyResult<PyValue> {
+    let int = |r: Option<i64>, what: &str| r.map(PyValue::Int).ok_or_else(|| overflow(what));
+    match op {
+        BinOp::Add => int(a.checked_add(b), "addition"),
+        BinOp::Sub => int(a.checked_sub(b), "subtraction"),
+        BinOp::Mult => int(a.checked_mul(b), "multiplication"),
+        BinOp::Div => {
+            if b == 0 {
+                return Err(zero_division("division by zero"));
+            }
+            Ok(PyValue::Float(a as f64 / b as f64))
+        }
+        BinOp::FloorDiv | BinOp::Mod => {
+            if b == 0 {
+                let msg = if op == BinOp::Mod {
+                    "integer modulo by zero"
+                } else {
+                    "integer division or modulo by zero"
+                };
+                return Err(zero_division(msg));
+            }
+            let (q, r) = (a.wrapping_div(b), a.wrapping_rem(b));
+            let adjust = r != 0 && ((r < 0) != (b < 0));
+            if op == BinOp::Mod {
+                Ok(PyValue::Int(if adjust { r + b } else { r }))
+            } else {
+                let q = if a == i64::MIN && b == -1 {
+                    return Err(overflow("division"));
+                } else {
+                    q
+                };
+                Ok(PyValue::Int(if adjust { q - 1 } else { q }))
+            }
+        }
+        BinOp::Pow => {
+            if b < 0 {
+                return float_pow(a as f64, b as f64).map(PyValue::Float);
+            }
+            let r = u32::try_from(b).ok().and_then(|b| a.checked_pow(b));
+            match (r, a) {
+                (Some(r), _) => Ok(PyValue::Int(r)),
+                (None, 0 | 1) => Ok(PyValue::Int(a)),
+                (None, -1) => Ok(PyValue::Int(if b % 2 == 0 { 1 } else { -1 })),
+                (None, _) => Err(overflow("exponentiation")),
+            }
+        }
+        BinOp::LShift | BinOp::RShift if b < 0 => {
+            Err(PyException::value_error("negative shift count"))
+        }
+        BinOp::LShift => {
+            if a == 0 {
+                return Ok(PyValue::Int(0));
+            }
+            let shifted = u32::try_from(b)
+                .ok()
+                .filter(|&b| b < 64)
+                .map(|b| (a as i128) << b)
+                .and_then(|r| i64::try_from(r).ok());
+            int(shifted, "shift")
+        }
+        BinOp::RShift => Ok(PyValue::Int(a >> b.min(63))),
+        BinOp::BitAnd => Ok(PyValue::Int(a & b)),
+        BinOp::BitOr => Ok(PyValue::Int(a | b)),
+        BinOp::BitXor => Ok(PyValue::Int(a ^ b)),
+        BinOp::MatMult => Err(unsupported_operands(
+            "@",
+            &PyValue::Int(a),
+            &PyValue::Int(b),
+        )),
+    }
+}
+
+fn float_binary(op: BinOp, a: f64, b: f64) -> Option<PyResult<f64>> {
+    Some(match op {
+        BinOp::Add => Ok(a + b),
+        BinOp::Sub => Ok(a - b),
+        BinOp::Mult => Ok(a * b),
+        BinOp::Div if b == 0.0 => Err(zero_division("float division by zero")),
+        BinOp::Div => Ok(a / b),
+        BinOp::FloorDiv if b == 0.0 => Err(zero_division("float floor division by zero")),
+        BinOp::FloorDiv => Ok(float_divmod(a, b).0),
+        BinOp::Mod if b == 0.0 => Err(zero_division("float modulo")),
+        BinOp::Mod => Ok(float_divmod(a, b).1),
+        BinOp::Pow => float_pow(a, b),
+        _ => return None,
+    })
+}
+
+/// Raises instead of letting `n` copies of a `len`-item sequence overflow
+/// or abort in the allocator: OverflowError when the length does not fit
+/// and `too_long` names the sequence, as for CPython's str and bytes, and
+/// MemoryError otherwise.
+fn check_repeat<T>(len: usize, n: usize, too_long: Option<&str>) -> PyResult<()> {
+    let total = len
+        .checked_mul(n)
+        .filter(|&total| total <= isize::MAX as usize)
+        .ok_or_else(|| match too_long {
+            Some(what) => PyException::new("OverflowError", format!("repeated {} too long", what)),
+            None => PyException::memory_error(""),
+        })?;
+    Vec::<T>::new()
+        .try_reserve_exact(total)
+        .map_err(|_| PyException::memory_error(""))
+}
+
+fn repeat(value: &PyValue, count: i64) -> PyResult<Option<PyValue>> {
+    let n = usize::try_from(count).unwrap_or(0);
+    let items = |items: &[PyValue]| -> PyResult<Vec<PyValue>> {
+        check_repeat::<PyValue>(items.len(), n, None)?;
+        Ok(items
+            .iter()
+            .cycle()
+            .take(items.len() * n)
+            .cloned()
+            .collect())
+    };
+    Ok(Some(match value {
+        PyValue::Str(s) => {
+            check_repeat::<u8>(s.as_str().len(), n, Some("string is"))?;
+            PyValue::Str(PyStr::new(s.as_str().repeat(n)))
+        }
+        PyValue::Bytes(b) => {
+            check_repeat::<u8>(b.as_bytes().len(), n, Some("bytes are"))?;
+            PyValue::Bytes(PyBytes::new(b.as_bytes().repeat(n)))
+        }
+        PyValue::List(l) => PyValue::List(PyList::from_vec(items(l.as_slice())?)),
+        PyValue::Tuple(t) => PyValue::Tuple(PyTuple::from_vec(items(t.as_slice())?)),
+        _ => return Ok(None),
+    }))
+}
+
+fn is_sequence(value: &PyValue) -> bool {
+    matches!(
+        value,
+        PyValue::Str(_) | PyValue::Bytes(_) | PyValue::List(_) | PyValue::Tuple(_)
+    )
+}
+
+/// Python-equivalent `a <op> b` for the binary operators.
+pub fn binary(op: BinOp, a: PyValue, b: PyValue) -> PyResult<PyValue> {
+    if let (Some(x), Some(y)) = (int_of(&a), int_of(&b)) {
+        let both_bool = matches!((&a, &b), (PyValue::Bool(_), PyValue::Bool(_)));
+        return match op {
+            BinOp::BitAnd | BinOp::BitOr | BinOp::BitXor if both_bool => {
+                int_binary(op, x, y).map(|r| PyValue::Bool(r == PyValue::Int(1)))
+            }
+            _ => int_binary(op, x, y),
+        };
+    }
+    if matches!(a, PyValue::Float(_)) || matches!(b, PyValue::Float(_)) {
+        if let (Some(x), Some(y)) = (float_of(&a), float_of(&b)) {
+            if let Some(result) = float_binary(op, x, y) {
+                return result.map(PyValue::Float);
+            }
+        }
+    }
+    let result = match (op, &a, &b) {
+        (BinOp::Add, _, _) if is_sequence(&a) => return a.py_add(b),
+        (BinOp::Mult, seq, n) | (BinOp::Mult, n, seq) if is_sequence(seq) => match int_of(n) {
+            Some(count) => repeat(seq, count)?,
+            None => {
+                return Err(PyException::type_error(format!(
+                    "can't multiply sequence by non-int of type '{}'",
+                    n.type_name()
+                )))
+            }
+        },
+        (BinOp::Sub, PyValue::Set(x), PyValue::Set(y)) => Some(PyValue::Set(x.difference(y))),
+        (BinOp::BitAnd, PyValue::Set(x), PyValue::Set(y)) => Some(PyValue::Set(x.intersection(y))),
+        (BinOp::BitOr, PyValue::Set(x), PyValue::Set(y)) => Some(PyValue::Set(x.union(y))),
+        (BinOp::BitXor, PyValue::Set(x), PyValue::Set(y)) => {
+            Some(PyValue::Set(x.symmetric_difference(y)))
+        }
+        (BinOp::BitOr, PyValue::Dict(x), PyValue::Dict(y)) => {
+            let mut merged = x.clone();
+            merged.update(y.iter().map(|(k, v)| (k.clone(), v.clone())));
+            Some(PyValue::Dict(merged))
+        }
+        _ => None,
+    };
+    result.ok_or_else(|| {
+        let symbol = if op == BinOp::Pow {
+            "** or pow()"
+        } else {
+            op.symbol()
+        };
+        unsupported_operands(symbol, &a, &b)
+    })
+}
+
+fn contains(container: &PyValue, item: &PyValue) -> PyResult<bool> {
+    match container {
+        PyValue::Str(s) => match item {
+            PyValue::Str(sub) => Ok(s.as_str().contains(sub.as_str())),
+            other => Err(PyException::type_error(format!(
+                "'in <string>' requires string as left operand, not {}",
+                other.type_name()
+            ))),
+        },
+        PyValue::Bytes(b) => match item {
+            PyValue::Bytes(sub) => {
+                Ok(sub.is_empty() || b.as_bytes().windows(sub.len()).any(|w| w == sub.as_bytes()))
+            }
+            other => match int_of(other) {
+                Some(byte) if (0..256).contains(&byte) => Ok(b.as_bytes().contains(&(byte as u8))),
+                Some(_) => Err(PyException::value_error("byte must be in range(0, 256)")),
+                None => Err(PyException::type_error(format!(
+                    "a bytes-like object is required, not '{}'",
+                    other.type_name()
+                ))),
+            },
+        },
+        PyValue::List(l) => Ok(l.iter().any(|x| x == item)),
+        PyValue::Tuple(t) => Ok(t.iter().any(|x| x == item)),
+        PyValue::Dict(d) => {
+            check_hashable(item)?;
+            Ok(d.contains_key(item))
+        }
+        PyValue::Set(s) => {
+            check_hashable(item)?;
+            Ok(s.contains(item))
+        }
+        other => Err(PyException::type_error(format!(
+            "argument of type '{}' is not iterable",
+            other.type_name()
+        ))),
+    }
+}
+
+/// Python's `is`: the singletons and small ints CPython caches, and shared
+/// strings and functions.
+fn is_same(a: &PyValue, b: &PyValue) -> bool {
+    match (a, b) {
+        (PyValue::None, PyValue::None) | (PyValue::NotImplemented, PyValue::NotImplemented) => true,
+        (PyValue::Bool(x), PyValue::Bool(y)) => x == y,
+        (PyValue::Int(x), PyValue::Int(y)) => x == y && (-5..=256).contains(x),
+        (PyValue::Str(x), PyValue::Str(y)) => x.ptr_eq(y),
+        (PyValue::Function(x), PyValue::Function(y)) => x == y,
+        _ => false,
+    }
+}
+
+fn compare(op: CmpOp, a: &PyValue, b: &PyValue) -> PyResult<PyValue> {
+    let rich = |op: CompareOp| richcompare(a, b, op);
+    match op {
+        CmpOp::Eq => rich(CompareOp::Eq),
+        CmpOp::NotEq => rich(CompareOp::Ne),
+        CmpOp::Lt => rich(CompareOp::Lt),
+        CmpOp::LtE => rich(CompareOp::Le),
+        CmpOp::Gt => rich(CompareOp::Gt),
+        CmpOp::GtE => rich(CompareOp::Ge),
+        CmpOp::Is => Ok(PyValue::Bool(is_same(a, b))),
+        CmpOp::IsNot => Ok(PyValue::Bool(!is_same(a, b))),
+        CmpOp::In => contains(b, a).map(PyValue::Bool),
+        CmpOp::NotIn => contains(b, a).map(|found| PyValue::Bool(!found)),
+    }
+}
+
+fn slice_bound(bound: &Option<Box<Expr>>, namespace: &Namespace) -> PyResult<Option<i64>> {
+    let Some(bound) = bound else {
+        return Ok(None);
+    };
+    match eval_expr(bound, namespace)? {
+        PyValue::None => Ok(None),
+        value => super::index(&value).map(Some).map_err(|_| {
+            PyException::type_error(
+                "slice indices must be integers or None or have an __index__ method",
+            )
+        }),
+    }
+}
+
+fn index_type_error(value: &PyValue, index: &PyValue) -> PyException {
+    let what = match value {
+        PyValue::Str(_) => {
+            return PyException::type_error(format!(
+                "string indices must be integers, not '{}'",
+                index.type_name()
+            ))
+        }
+        PyValue::Bytes(_) => "byte",
+        other => other.type_name(),
+    };
+    PyException::type_error(format!(
+        "{} indices must be integers or slices, not {}",
+        what,
+        index.type_name()
+    ))
+}
+
+fn subscript(value: &PyValue, index: &Expr, namespace: &Namespace) -> PyResult<PyValue> {
+    if let ExprKind::Slice { lower, upper, step } = &index.kind {
+        let slice = PySlice::new(
+            slice_bound(lower, namespace)?,
+            slice_bound(upper, namespace)?,
+            slice_bound(step, namespace)?,
+        );
+        return match value {
+            PyValue::List(l) => l.slice(&slice).map(PyValue::List),
+            PyValue::Tuple(t) => t.slice(&slice).map(PyValue::Tuple),
+            PyValue::Str(s) => s.slice(&slice).map(PyValue::Str),
+            PyValue::Bytes(b) => b.slice(&slice).map(PyValue::Bytes),
+            PyValue::Dict(_) => Err(PyException::type_error("unhashable type: 'slice'")),
+            other => Err(not_subscriptable(other)),
+        };
+    }
+    let index = eval_expr(index, namespace)?;
+    if let PyValue::Dict(d) = value {
+        check_hashable(&index)?;
+        return d.get_item(&index);
+    }
+    if !is_sequence(value) {
+        return Err(not_subscriptable(value));
+    }
+    let i = int_of(&index).ok_or_else(|| index_type_error(value, &index))?;
+    match value {
+        PyValue::List(l) => l.get(i).cloned(),
+        PyValue::Tuple(t) => t.get(i).cloned(),
+        PyValue::Str(s) => s.char_at(i).map(PyValue::from),
+        PyValue::Bytes(b) => {
+            let len = b.len() as i64;
+            let at = if i < 0 { i + len } else { i };
+            b.as_bytes()
+                .get(usize::try_from(at).unwrap_or(usize::MAX))
+                .map(|&byte| PyValue::Int(byte as i64))
+                .ok_or_else(|| PyException::index_error("index out of range"))
+        }
+        _ => Err(not_subscriptable(value)),
+    }
+}
+
+fn not_subscriptable(value: &PyValue) -> PyException {
+    PyException::type_error(format!(
+        "'{}' object is not subscriptable",
+        value.type_name()
+    ))
+}
+
+fn unpack(value: PyValue, expected: usize) -> PyResult<Vec<PyValue>> {
+    let items: Vec<PyValue> = match value {
+        PyValue::List(l) => l.into_vec(),
+        PyValue::Tuple(t) => t.as_slice().to_vec(),
+        PyValue::Str(s) => s
+            .as_str()
+            .chars()
+            .map(|c| PyValue::from(c.to_string()))
+            .collect(),
+        PyValue::Bytes(b) => b
+            .as_bytes()
+            .iter()
+            .map(|&b| PyValue::Int(b as i64))
+            .collect(),
+        PyValue::Dict(d) => d.keys().to_list(),
+        PyValue::Set(s) => s.iter().cloned().collect(),
+        other => {
+            return Err(PyException::type_error(format!(
+                "cannot unpack non-iterable {} object",
+                other.type_name()
+            )))
+        }
+    };
+    match items.len() {
+        n if n < expected => Err(PyException::value_error(format!(
+            "not enough values to unpack (expected {}, got {})",
+            expected, n
+        ))),
+        n if n > expected => Err(PyException::value_error(format!(
+            "too many values to unpack (expected {})",
+            expected
+        ))),
+        _ => Ok(items),
+    }
+}
+
+/// The value a subscript target's container expression names, for
+/// assigning into it in place.
+fn target_mut<'a>(expr: &Expr, namespace: &'a mut Namespace) -> PyResult<&'a mut PyValue> {
+    match &expr.kind {
+        ExprKind::Name(name) => namespace.get_mut(name).ok_or_else(|| name_error(name)),
+        ExprKind::Subscript(value, index) => {
+            let index = eval_expr(index, namespace)?;
+            match target_mut(value, namespace)? {
+                PyValue::List(l) => {
+                    let i = int_of(&index)
+                        .ok_or_else(|| index_type_error(&PyValue::List(PyList::new()), &index))?;
+                    l.get_mut(i)
+                }
+                PyValue::Dict(d) => {
+                    check_hashable(&index)?;
+                    d.get_item_mut(&index)
+                }
+                other => Err(not_subscriptable(other)),
+            }
+        }
+        _ => Err(PyException::type_error(format!(
+            "cannot assign through {} expressions",
+            expr.node_name()
+        ))),
+    }
+}
+
+fn assign(target: &Expr, value: PyValue, namespace: &mut Namespace) -> PyResult<()> {
+    match &target.kind {
+        ExprKind::Name(name) => {
+            namespace.set_item(name.clone(), value);
+            Ok(())
+        }
+        ExprKind::Tuple(targets) | ExprKind::List(targets) => {
+            for (target, item) in targets.iter().zip(unpack(value, targets.len())?) {
+                assign(target, item, namespace)?;
+            }
+            Ok(())
+        }
+        ExprKind::Subscript(container, index) => {
+            if matches!(index.kind, ExprKind::Slice { .. }) {
+                return Err(PyException::type_error("slice assignment is not supported"));
+            }
+            let index = eval_expr(index, namespace)?;
+            match target_mut(container, namespace)? {
+                PyValue::List(l) => {
+                    let i = int_of(&index)
+                        .ok_or_else(|| index_type_error(&PyValue::List(PyList::new()), &index))?;
+                    *l.get_mut(i).map_err(|_| {
+                        PyException::index_error("list assignment index out of range")
+                    })? = value;
+                    Ok(())
+                }
+                PyValue::Dict(d) => {
+                    check_hashable(&index)?;
+                    d.set_item(index, value);
+                    Ok(())
+                }
+                other => Err(PyException::type_error(format!(
+                    "'{}' object does not support item assignment",
+                    other.type_name()
+                ))),
+            }
+        }
+        _ => Err(PyException::type_error(format!(
+            "cannot assign to {} expressions",
+            target.node_name()
+        ))),
+    }
+}
+
+fn delete(target: &Expr, namespace: &mut Namespace) -> PyResult<()> {
+    match &target.kind {
+        ExprKind::Name(name) => namespace
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| name_error(name)),
+        ExprKind::Tuple(targets) | ExprKind::List(targets) => targets
+            .iter()
+            .try_for_each(|target| delete(target, namespace)),
+        ExprKind::Subscript(container, index) => {
+            let index = eval_expr(index, namespace)?;
+            match target_mut(container, namespace)? {
+                PyValue::List(l) => {
+                    let i = int_of(&index)
+                        .ok_or_else(|| index_type_error(&PyValue::List(PyList::new()), &index))?;
+                    let len = l.len() as i64;
+                    let at = if i < 0 { i + len } else { i };
+                    if !(0..len).contains(&at) {
+                        return Err(PyException::index_error(
+                            "list assignment index out of range",
+                        ));
+                    }
+                    let mut items = core::mem::take(l).into_vec();
+                    items.remove(at as usize);
+                    *l = PyList::from_vec(items);
+                    Ok(())
+                }
+                PyValue::Dict(d) => {
+                    check_hashable(&index)?;
+                    d.pop(&index).map(|_| ())
+                }
+                other => Err(PyException::type_error(format!(
+                    "'{}' object does not support item deletion",
+                    other.type_name()
+                ))),
+            }
+        }
+        _ => Err(PyException::type_error(format!(
+            "cannot delete {} expressions",
+            target.node_name()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::builtins::repr;
+
+    fn run(source: &str) -> String {
+        let namespace: Namespace = [
+            ("x".to_string(), PyValue::Int(3)),
+            ("name".to_string(), PyValue::from("ada")),
+            (
+                "items".to_string(),
+                PyValue::List(PyList::from_vec(vec![
+                    PyValue::Int(1),
+                    PyValue::Int(2),
+                    PyValue::Int(3),
+                ])),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        match eval(source, &namespace) {
+            Ok(value) => repr(&value),
+            Err(err) => format!("{}: {}", err.type_name, err.message),
+        }
+    }
+
+    #[test]
+    fn evaluates_like_cpython() {
+        let cases = [
+            ("x * 2 + 1", "7"),
+            ("-True", "-1"),
+            ("2**-1", "0.5"),
+            ("7//-2", "-4"),
+            ("-7 % 3", "2"),
+            ("7.5 // -2", "-4.0"),
+            ("-7.5 % 2", "0.5"),
+            ("1 < 2 < 3", "True"),
+            ("1 < 3 < 2", "False"),
+            ("1 and 0 or 3", "3"),
+            ("not 1", "False"),
+            ("(1 if 0 else 2)", "2"),
+            ("b'ab'[0]", "97"),
+            ("[1,2,3][::-1]", "[3, 2, 1]"),
+            ("[1,2,3][1::9223372036854775807]", "[2]"),
+            ("'abc'[-1]", "'c'"),
+            ("{1: 2} | {3: 4}", "{1: 2, 3: 4}"),
+            ("{1} - {2}", "{1}"),
+            ("3 & True", "1"),
+            ("True & False", "False"),
+            ("1 == 1.0", "True"),
+            (
+                "name.upper",
+                "AttributeError: 'str' object has no attribute 'upper'",
+            ),
+            ("items[1:] + [x]", "[2, 3, 3]"),
+            ("'a' in name and 2 in items", "True"),
+            ("name * 2", "'adaada'"),
+            ("x is not None", "True"),
+            ("1, 2", "(1, 2)"),
+            ("math.sqrt(x + 13)", "4.0"),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(run(source), expected, "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn errors_like_cpython() {
+        let cases = [
+            (
+                "'ab'['x']",
+                "TypeError: string indices must be integers, not 'str'",
+            ),
+            (
+                "(1,2)['x']",
+                "TypeError: tuple indices must be integers or slices, not str",
+            ),
+            (
+                "b'a'['x']",
+                "TypeError: byte indices must be integers or slices, not str",
+            ),
+            ("(1,)[5]", "IndexError: tuple index out of range"),
+            ("b'a'[5]", "IndexError: index out of range"),
+            ("1[0]", "TypeError: 'int' object is not subscriptable"),
+            ("{}[[1]]", "TypeError: unhashable type: 'list'"),
+            (
+                "1 in 2",
+                "TypeError: argument of type 'int' is not iterable",
+            ),
+            (
+                "1 in 'a'",
+                "TypeError: 'in <string>' requires string as left operand, not int",
+            ),
+            ("-'a'", "TypeError: bad operand type for unary -: 'str'"),
+            ("~1.5", "TypeError: bad operand type for unary ~: 'float'"),
+            (
+                "1 @ 2",
+                "TypeError: unsupported operand type(s) for @: 'int' and 'int'",
+            ),
+            (
+                "2 ** 'a'",
+                "TypeError: unsupported operand type(s) for ** or pow(): 'int' and 'str'",
+            ),
+            (
+                "10.0**400",
+                "OverflowError: (34, 'Numerical result out of range')",
+            ),
+            (
+                "0**-1",
+                "ZeroDivisionError: 0.0 cannot be raised to a negative power",
+            ),
+            (
+                "1//0",
+                "ZeroDivisionError: integer division or modulo by zero",
+            ),
+            ("1%0", "ZeroDivisionError: integer modulo by zero"),
+            ("1.0//0", "ZeroDivisionError: float floor division by zero"),
+            ("1.0%0", "ZeroDivisionError: float modulo"),
+            ("1/0", "ZeroDivisionError: division by zero"),
+            ("1.0/0", "ZeroDivisionError: float division by zero"),
+            ("1<<-1", "ValueError: negative shift count"),
+            (
+                "[1][1:'a']",
+                "TypeError: slice indices must be integers or None or have an __index__ method",
+            ),
+            ("a", "NameError: name 'a' is not defined"),
+            ("{[1]}", "TypeError: unhashable type: 'list'"),
+            ("1()", "TypeError: 'int' object is not callable"),
+            ("(1).x", "AttributeError: 'int' object has no attribute 'x'"),
+            (
+                "'a' * 'b'",
+                "TypeError: can't multiply sequence by non-int of type 'str'",
+            ),
+            (
+                "[1,2] - [1]",
+                "TypeError: unsupported operand type(s) for -: 'list' and 'list'",
+            ),
+            (
+                "1 < 'a'",
+                "TypeError: '<' not supported between instances of 'int' and 'str'",
+            ),
+            ("{'a': 1}['b']", "KeyError: 'b'"),
+            ("'a' * 9223372036854775807", "MemoryError: "),
+            (
+                "'ab' * 9223372036854775807",
+                "OverflowError: repeated string is too long",
+            ),
+            (
+                "b'ab' * 9223372036854775807",
+                "OverflowError: repeated bytes are too long",
+            ),
+            ("[1] * 9223372036854775807", "MemoryError: "),
+            ("x = 1", "SyntaxError: invalid syntax (<string>, line 1)"),
+            (
+                "lambda: 1",
+                "SyntaxError: lambda expressions are not supported (<string>, line 1)",
+            ),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(run(source), expected, "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn exec_binds_names() {
+        let mut namespace = Namespace::new();
+        exec(
+            "a, b = 1, 2\nconfig = {'depth': a + b}; config['depth'] += 1\n\
+             seq = [0, 0]; seq[-1] = 'z'\ndel a",
+            &mut namespace,
+        )
+        .unwrap();
+        assert!(!namespace.contains_key("a"));
+        assert_eq!(namespace.get("b"), Some(&PyValue::Int(2)));
+        assert_eq!(repr(namespace.get("config").unwrap()), "{'depth': 4}");
+        assert_eq!(repr(namespace.get("seq").unwrap()), "[0, 'z']");
+        let err = exec("a, b = [1]", &mut namespace).unwrap_err();
+        assert_eq!(
+            err.message,
+            "not enough values to unpack (expected 2, got 1)"
+        );
+        let err = exec("q += 1", &mut namespace).unwrap_err();
+        assert_eq!(err.message, "name 'q' is not defined");
+    }
+}
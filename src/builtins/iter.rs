@@ -0,0 +1,81 @@
+//! enumerate() and reversed(): lazy adapters that borrow the sequence they
+//! walk instead of copying it.
+
+use core::iter::FusedIterator;
+
+/// Python-equivalent enumerate(iterable). Pass `&list` to borrow the items.
+pub fn enumerate<I: IntoIterator>(iterable: I) -> Enumerate<I::IntoIter> {
+    enumerate_from(iterable, 0)
+}
+
+/// Python-equivalent enumerate(iterable, start).
+pub fn enumerate_from<I: IntoIterator>(iterable: I, start: i64) -> Enumerate<I::IntoIter> {
+    Enumerate {
+        iter: iterable.into_iter(),
+        count: start,
+    }
+}
+
+/// Iterator returned by [`enumerate`]: pairs each item with a Python int
+/// count, which unlike `Iterator::enumerate` may start anywhere.
+#[derive(Debug, Clone)]
+pub struct Enumerate<I> {
+    iter: I,
+    count: i64,
+}
+
+impl<I: Iterator> Iterator for Enumerate<I> {
+    type Item = (i64, I::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.iter.next()?;
+        let count = self.count;
+        self.count += 1;
+        Some((count, item))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator> ExactSizeIterator for Enumerate<I> {}
+
+impl<I: FusedIterator> FusedIterator for Enumerate<I> {}
+
+/// Python-equivalent reversed(seq). Pass `&list` to borrow the items.
+pub fn reversed<I>(seq: I) -> core::iter::Rev<I::IntoIter>
+where
+    I: IntoIterator,
+    I::IntoIter: DoubleEndedIterator,
+{
+    seq.into_iter().rev()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PyList, PySlice, PyTuple};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn enumerate_borrows_and_counts_from_start() {
+        let list = PyList::from_vec(Vec::from(["a", "b", "c"]));
+        let pairs: Vec<(i64, &&str)> = enumerate_from(&list, -1).collect();
+        assert_eq!(pairs, [(-1, &"a"), (0, &"b"), (1, &"c")]);
+        assert_eq!(enumerate(&list).len(), 3);
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn composes_with_slices_and_reversed() {
+        let tuple = PyTuple::from_vec(Vec::from([0, 1, 2, 3, 4, 5]));
+        let odd = tuple
+            .iter_slice(&PySlice::new(Some(1), None, Some(2)))
+            .unwrap();
+        let got: Vec<(i64, &i32)> = enumerate(reversed(odd)).collect();
+        assert_eq!(got, [(0, &5), (1, &3), (2, &1)]);
+        let owned: Vec<i32> = reversed(tuple).collect();
+        assert_eq!(owned, [5, 4, 3, 2, 1, 0]);
+    }
+}
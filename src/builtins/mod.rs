@@ -0,0 +1,386 @@
+//! Python built-in functions.
+
+mod aggregate;
+mod comprehension;
+mod convert;
+#[cfg(feature = "std")]
+mod eval;
+mod iter;
+pub mod zip;
+
+pub use aggregate::{
+    all, any, max, max_by, max_or, min, min_by, min_or, sorted, sorted_by, sum, sum_start,
+};
+pub use comprehension::{
+    comprehension, py_dict_from_iter, py_list_from_iter, py_set_from_iter, Comprehension,
+};
+pub use convert::{
+    bool_value, float, float_value, index, int, int_value, int_value_with_base, int_with_base,
+    str_value,
+};
+#[cfg(feature = "std")]
+pub use eval::{eval, exec, Namespace};
+pub use iter::{enumerate, enumerate_from, reversed, Enumerate};
+pub use zip::{zip, zip_n, zip_n_strict, zip_strict};
+
+use crate::exceptions::PyResult;
+use crate::types::repr::ascii_escape;
+use crate::types::{
+    PyContains, PyDictionary, PyFormat, PyHash, PyKwargs, PyLen, PyObjectProtocol, PyRange,
+    PyRepr, PySlice, PyTruthy, PyTypeId, PyTyped, PyValue,
+};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hash;
+
+/// Python-equivalent isinstance(obj, classinfo).
+pub fn isinstance<T: PyTyped + ?Sized>(obj: &T, classinfo: PyTypeId) -> bool {
+    obj.py_type().is_subclass_of(classinfo)
+}
+
+/// Python-equivalent isinstance(obj, (A, B, ...)).
+pub fn isinstance_any<T: PyTyped + ?Sized>(obj: &T, classinfo: &[PyTypeId]) -> bool {
+    classinfo.iter().any(|&cls| isinstance(obj, cls))
+}
+
+/// Python-equivalent issubclass(cls, classinfo).
+pub fn issubclass(cls: PyTypeId, classinfo: PyTypeId) -> bool {
+    cls.is_subclass_of(classinfo)
+}
+
+/// Python-equivalent type(obj).
+pub fn type_of<T: PyTyped + ?Sized>(obj: &T) -> PyTypeId {
+    obj.py_type()
+}
+
+/// Python-equivalent id(obj).
+pub fn id<T: PyTyped + ?Sized>(obj: &T) -> usize {
+    obj.py_id()
+}
+
+/// Python-equivalent hash(obj); unhashable types raise TypeError.
+pub fn hash<T: PyHash + ?Sized>(obj: &T) -> PyResult<i64> {
+    obj.py_hash()
+}
+
+/// Python-equivalent range(stop).
+pub fn range(stop: i64) -> PyRange {
+    PyRange::from(0..stop)
+}
+
+/// Python-equivalent range(start, stop).
+pub fn range_from(start: i64, stop: i64) -> PyRange {
+    PyRange::from(start..stop)
+}
+
+/// Python-equivalent range(start, stop, step); a zero step raises ValueError.
+pub fn range_step(start: i64, stop: i64, step: i64) -> PyResult<PyRange> {
+    PyRange::new(start, stop, step)
+}
+
+/// Python-equivalent dict(**kwargs).
+pub fn dict_from_kwargs<K, V>(kwargs: PyKwargs) -> PyDictionary<K, V>
+where
+    K: Hash + Eq + From<String>,
+    V: From<PyValue>,
+{
+    PyDictionary::from_kwargs(kwargs)
+}
+
+/// Python-equivalent len(obj).
+pub fn len<T: PyLen + ?Sized>(obj: &T) -> usize {
+    obj.py_len()
+}
+
+/// Python-equivalent `item in container`.
+pub fn contains<C: PyContains<Q> + ?Sized, Q: ?Sized>(container: &C, item: &Q) -> bool {
+    container.py_contains(item)
+}
+
+/// Python-equivalent bool(obj), also known as operator.truth(obj).
+pub fn truth<T: PyTruthy + ?Sized>(obj: &T) -> bool {
+    obj.py_bool()
+}
+
+/// Python-equivalent callable(obj).
+pub fn callable(obj: &PyValue) -> bool {
+    matches!(obj, PyValue::Function(_))
+}
+
+/// Python-equivalent slice(start, stop, step).
+pub fn slice(start: Option<i64>, stop: Option<i64>, step: Option<i64>) -> PySlice {
+    PySlice::new(start, stop, step)
+}
+
+/// Python-equivalent repr(obj).
+pub fn repr<T: PyRepr + ?Sized>(obj: &T) -> String {
+    obj.py_repr()
+}
+
+/// Python-equivalent str(obj). Unlike Rust's `Display`, floats print as
+/// CPython does: `str(&3.0)` is "3.0" and `str(&(0.1 + 0.2))` is
+/// "0.30000000000000004".
+pub fn str<T: PyFormat + ?Sized>(obj: &T) -> String {
+    // An empty spec is object.__format__, i.e. str(), for every built-in type.
+    obj.py_format("").unwrap_or_else(|_| obj.to_string())
+}
+
+/// Python-equivalent ascii(obj): repr() with non-ASCII characters escaped.
+pub fn ascii<T: PyRepr + ?Sized>(obj: &T) -> String {
+    ascii_escape(&obj.py_repr())
+}
+
+/// Python-equivalent format(value, format_spec). An f-string field
+/// `f"{x:>8}"` lowers to `format(&x, ">8")?`, and `f"{x!r:>8}"` to
+/// `format(&repr(&x), ">8")?`, or equivalently
+/// [`format_field`](crate::types::format::format_field)`(&x, Some(Conversion::Repr), ">8")?`.
+pub fn format<T: PyFormat + ?Sized>(value: &T, format_spec: &str) -> PyResult<String> {
+    value.py_format(format_spec)
+}
+
+/// Python-equivalent print(s, file=out): writes `s` and a newline to any
+/// `fmt::Write`. The std print() hands this the current sys.stdout writer.
+pub fn print_to<W: fmt::Write + ?Sized>(out: &mut W, s: &dyn fmt::Display) -> fmt::Result {
+    writeln!(out, "{}", s)
+}
+
+/// The text print(s) would write, for targets without a stdout.
+pub fn print_to_string<S: fmt::Display>(s: S) -> String {
+    let mut out = String::new();
+    // Writing to a String cannot fail.
+    let _ = print_to(&mut out, &s);
+    out
+}
+
+/// Python-equivalent getattr(obj, name[, default]).
+pub fn getattr<T: PyObjectProtocol + ?Sized>(
+    obj: &T,
+    name: &str,
+    default: Option<PyValue>,
+) -> PyResult<PyValue> {
+    match (obj.get_attr(name), default) {
+        (Err(e), Some(default)) if e.is("AttributeError") => Ok(default),
+        (result, _) => result,
+    }
+}
+
+/// Python-equivalent setattr(obj, name, value).
+pub fn setattr<T: PyObjectProtocol + ?Sized>(
+    obj: &mut T,
+    name: &str,
+    value: PyValue,
+) -> PyResult<()> {
+    obj.set_attr(name, value)
+}
+
+/// Python-equivalent delattr(obj, name).
+pub fn delattr<T: PyObjectProtocol + ?Sized>(obj: &mut T, name: &str) -> PyResult<()> {
+    obj.del_attr(name)
+}
+
+/// Python-equivalent hasattr(obj, name). Only AttributeError means "absent";
+/// any other exception propagates, as in Python 3.
+pub fn hasattr<T: PyObjectProtocol + ?Sized>(obj: &T, name: &str) -> PyResult<bool> {
+    match obj.get_attr(name) {
+        Ok(_) => Ok(true),
+        Err(e) if e.is("AttributeError") => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Python-equivalent dir(obj): sorted, without duplicates.
+pub fn dir<T: PyObjectProtocol + ?Sized>(obj: &T) -> Vec<String> {
+    let mut names = obj.dir();
+    names.sort();
+    names.dedup();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{register_class, InstanceDict, PyList};
+
+    struct Point;
+
+    impl PyTyped for Point {
+        fn py_type(&self) -> PyTypeId {
+            register_class("BuiltinsPoint", &[])
+        }
+    }
+
+    #[derive(Default)]
+    struct Account {
+        owner: String,
+        dict: InstanceDict,
+    }
+
+    impl PyTyped for Account {
+        fn py_type(&self) -> PyTypeId {
+            register_class("Account", &[])
+        }
+    }
+
+    impl PyObjectProtocol for Account {
+        fn instance_dict(&self) -> Option<&InstanceDict> {
+            Some(&self.dict)
+        }
+
+        fn instance_dict_mut(&mut self) -> Option<&mut InstanceDict> {
+            Some(&mut self.dict)
+        }
+
+        fn get_attr(&self, name: &str) -> PyResult<PyValue> {
+            match name {
+                "owner" => Ok(PyValue::from(self.owner.as_str())),
+                _ => self
+                    .instance_dict()
+                    .and_then(|d| d.get(&name.to_string()).cloned())
+                    .ok_or_else(|| crate::types::object::no_attribute("Account", name)),
+            }
+        }
+
+        fn set_attr(&mut self, name: &str, value: PyValue) -> PyResult<()> {
+            match (name, value) {
+                ("owner", PyValue::Str(s)) => {
+                    self.owner = s.into_string();
+                    Ok(())
+                }
+                ("owner", _) => Err(crate::PyException::type_error("owner must be str")),
+                (name, value) => {
+                    self.dict.set_item(name.to_string(), value);
+                    Ok(())
+                }
+            }
+        }
+
+        fn dir(&self) -> Vec<String> {
+            let mut names = vec!["owner".to_string()];
+            names.extend(self.dict.keys().cloned());
+            names
+        }
+    }
+
+    impl PyObjectProtocol for Point {}
+
+    #[test]
+    fn attribute_protocol() {
+        let mut acct = Account::default();
+        setattr(&mut acct, "owner", PyValue::from("ann")).unwrap();
+        setattr(&mut acct, "balance", PyValue::Int(10)).unwrap();
+        assert_eq!(getattr(&acct, "owner", None).unwrap(), PyValue::from("ann"));
+        assert_eq!(getattr(&acct, "balance", None).unwrap(), PyValue::Int(10));
+        let balance = crate::types::intern("balance");
+        assert_eq!(acct.get_attr_sym(balance).unwrap(), PyValue::Int(10));
+        assert_eq!(dir(&acct), vec!["balance", "owner"]);
+        delattr(&mut acct, "balance").unwrap();
+        assert!(!hasattr(&acct, "balance").unwrap());
+        let err = getattr(&acct, "balance", None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "AttributeError: 'Account' object has no attribute 'balance'"
+        );
+        assert_eq!(
+            getattr(&acct, "balance", Some(PyValue::Int(0))).unwrap(),
+            PyValue::Int(0)
+        );
+        assert!(setattr(&mut acct, "owner", PyValue::Int(1))
+            .unwrap_err()
+            .is("TypeError"));
+        assert!(setattr(&mut Point, "x", PyValue::Int(1))
+            .unwrap_err()
+            .is("AttributeError"));
+        assert!(dir(&Point).is_empty());
+    }
+
+    #[test]
+    fn isinstance_follows_python_rules() {
+        assert!(isinstance(&true, PyTypeId::Int));
+        assert!(!isinstance(&1i64, PyTypeId::Bool));
+        assert!(isinstance(&PyValue::Float(1.5), PyTypeId::Float));
+        assert!(isinstance_any("x", &[PyTypeId::Int, PyTypeId::Str]));
+        assert!(isinstance(&Point, PyTypeId::Object));
+        assert_eq!(type_of(&Point).name(), "BuiltinsPoint");
+        assert_eq!(type_of(&PyList::<i64>::new()).name(), "list");
+        assert!(issubclass(PyTypeId::Bool, PyTypeId::Object));
+    }
+
+    #[test]
+    fn id_and_hash() {
+        let a = PyValue::from("a");
+        let b = PyValue::from("a");
+        assert_eq!(id(&a), id(&a));
+        assert_ne!(id(&a), id(&b));
+        assert_eq!(hash(&a).unwrap(), hash("a").unwrap());
+        assert_eq!(hash(&1i64).unwrap(), hash(&1.0f64).unwrap());
+        assert!(hash(&PyValue::List(PyList::new())).is_err());
+    }
+
+    #[test]
+    fn callbacks_stored_in_dicts() {
+        use crate::types::{PyCallable, PyDictionary, PyFunction};
+        let mut handlers = PyDictionary::new();
+        handlers.set_item(
+            PyValue::from("neg"),
+            PyValue::from(PyFunction::unary("neg", |x| match x {
+                PyValue::Int(i) => Ok(PyValue::Int(-i)),
+                _ => Ok(PyValue::None),
+            })),
+        );
+        let handler = handlers.get(&PyValue::from("neg")).unwrap();
+        assert!(callable(handler));
+        assert!(!callable(&PyValue::Int(1)));
+        match handler {
+            PyValue::Function(f) => {
+                assert_eq!(
+                    f.call_positional(&[PyValue::Int(3)]).unwrap(),
+                    PyValue::Int(-3)
+                )
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(type_of(handler).name(), "function");
+    }
+
+    #[test]
+    fn container_protocols() {
+        use crate::stdlib::collections::{Deque, OrderedDict};
+        use crate::types::{PyBytes, PyDictionary, PyRange, PyStr};
+        let d: PyDictionary<&str, i64> = [("a", 1)].into_iter().collect();
+        let od: OrderedDict<&str, i64> = [("b", 2)].into_iter().collect();
+        let dq: Deque<i64> = [4, 5].into_iter().collect();
+        let r = PyRange::new(0, 10, 3).unwrap();
+        assert_eq!(
+            (len(&d), len(&od), len(&dq), len(&r), len("é")),
+            (1, 1, 2, 4, 1)
+        );
+        assert!(contains(&d, &"a") && contains(&od, &"b") && contains(&dq, &5));
+        assert!(contains(&r, &9) && !contains(&r, &10));
+        assert!(contains(&PyStr::new("spam"), "pa"));
+        assert!(contains(&PyBytes::new(*b"\x00\x01"), &1u8));
+        assert!(!truth(&PyRange::from(0..0)) && truth(&dq) && !truth(&0.0));
+    }
+
+    #[test]
+    fn repr_and_ascii() {
+        let list = PyList::from_vec(vec!["a", "b"]);
+        assert_eq!(repr(&list), "['a', 'b']");
+        assert_eq!(list.to_string(), "['a', 'b']");
+        assert_eq!(repr("ü"), "'ü'");
+        assert_eq!(ascii("ü"), "'\\xfc'");
+        assert_eq!(repr(&PyValue::Float(1e-5)), "1e-05");
+    }
+
+    #[test]
+    fn str_matches_cpython_for_floats() {
+        assert_eq!(str(&3.0), "3.0");
+        assert_eq!(str(&(0.1 + 0.2)), "0.30000000000000004");
+        assert_eq!(str(&1e16), "1e+16");
+        assert_eq!(str(&-1e-5), "-1e-05");
+        assert_eq!(str(&f64::NAN), "nan");
+        assert_eq!(str(&PyList::from_vec(vec![2.0, 0.5])), "[2.0, 0.5]");
+        assert_eq!(str("spam"), "spam");
+        assert_eq!(str(&true), "True");
+    }
+}
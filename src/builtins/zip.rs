@@ -0,0 +1,274 @@
+//! zip(), lazily over any number of iterables, with Python 3.10's strict=True.
+
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::iter::Map;
+
+use crate::exceptions::{PyException, PyResult};
+
+/// ValueError raised by zip(strict=True) when argument `arg` (1-based) ends
+/// before or after the arguments preceding it.
+fn length_mismatch(arg: usize, longer: bool) -> PyException {
+    let preceding = if arg == 2 {
+        "argument 1".to_string()
+    } else {
+        format!("arguments 1-{}", arg - 1)
+    };
+    let which = if longer { "longer" } else { "shorter" };
+    PyException::value_error(format!(
+        "zip() argument {} is {} than {}",
+        arg, which, preceding
+    ))
+}
+
+/// Python-equivalent zip(a, b).
+pub fn zip<A: IntoIterator, B: IntoIterator>(
+    a: A,
+    b: B,
+) -> core::iter::Zip<A::IntoIter, B::IntoIter> {
+    a.into_iter().zip(b)
+}
+
+type Wrapped<I> = Map<I, fn(<I as Iterator>::Item) -> PyResult<<I as Iterator>::Item>>;
+
+/// Python-equivalent zip(a, b, strict=True).
+pub fn zip_strict<A: IntoIterator, B: IntoIterator>(
+    a: A,
+    b: B,
+) -> ZipStrict<Wrapped<A::IntoIter>, B::IntoIter> {
+    let first: Wrapped<A::IntoIter> = a.into_iter().map(Ok);
+    ZipStrict::new(first, b, 2)
+}
+
+/// Pairs a (possibly already zipped) fallible iterator with the next
+/// argument, checking lengths like zip(strict=True). Nest it to zip more
+/// arguments; [`zip_strict!`](crate::zip_strict) does that for you.
+pub struct ZipStrict<A, B> {
+    a: A,
+    b: B,
+    arg: usize,
+    done: bool,
+}
+
+impl<A, B: Iterator> ZipStrict<A, B> {
+    /// `arg` is the 1-based position of `b` among zip()'s arguments.
+    pub fn new<IB: IntoIterator<IntoIter = B>>(a: A, b: IB, arg: usize) -> Self {
+        ZipStrict {
+            a,
+            b: b.into_iter(),
+            arg,
+            done: false,
+        }
+    }
+}
+
+impl<T, A, B> Iterator for ZipStrict<A, B>
+where
+    A: Iterator<Item = PyResult<T>>,
+    B: Iterator,
+{
+    type Item = PyResult<(T, B::Item)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = match self.a.next() {
+            Some(Ok(x)) => match self.b.next() {
+                Some(y) => return Some(Ok((x, y))),
+                None => Err(length_mismatch(self.arg, false)),
+            },
+            Some(Err(e)) => Err(e),
+            None => match self.b.next() {
+                Some(_) => Err(length_mismatch(self.arg, true)),
+                None => {
+                    self.done = true;
+                    return None;
+                }
+            },
+        };
+        self.done = true;
+        Some(result)
+    }
+}
+
+/// Python-equivalent zip(*iterables) over any number of same-typed
+/// iterables, yielding each row as a Vec.
+pub fn zip_n<I: IntoIterator>(iterables: Vec<I>) -> ZipN<I::IntoIter> {
+    ZipN {
+        iters: iterables.into_iter().map(IntoIterator::into_iter).collect(),
+        done: false,
+    }
+}
+
+/// Python-equivalent zip(*iterables, strict=True).
+pub fn zip_n_strict<I: IntoIterator>(iterables: Vec<I>) -> ZipNStrict<I::IntoIter> {
+    ZipNStrict {
+        inner: zip_n(iterables),
+    }
+}
+
+/// Iterator returned by [`zip_n`].
+pub struct ZipN<I> {
+    iters: Vec<I>,
+    done: bool,
+}
+
+impl<I: Iterator> Iterator for ZipN<I> {
+    type Item = Vec<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.iters.is_empty() {
+            return None;
+        }
+        let mut row = Vec::with_capacity(self.iters.len());
+        for it in &mut self.iters {
+            match it.next() {
+                Some(x) => row.push(x),
+                None => {
+                    self.done = true;
+                    return None;
+                }
+            }
+        }
+        Some(row)
+    }
+}
+
+/// Iterator returned by [`zip_n_strict`].
+pub struct ZipNStrict<I> {
+    inner: ZipN<I>,
+}
+
+impl<I: Iterator> Iterator for ZipNStrict<I> {
+    type Item = PyResult<Vec<I::Item>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let inner = &mut self.inner;
+        if inner.done || inner.iters.is_empty() {
+            return None;
+        }
+        inner.done = true;
+        let mut row = Vec::with_capacity(inner.iters.len());
+        for (i, it) in inner.iters.iter_mut().enumerate() {
+            match it.next() {
+                Some(x) => row.push(x),
+                None if i > 0 => return Some(Err(length_mismatch(i + 1, false))),
+                None => {
+                    // The first argument ended: every other must end too.
+                    for (j, rest) in inner.iters.iter_mut().enumerate().skip(1) {
+                        if rest.next().is_some() {
+                            return Some(Err(length_mismatch(j + 1, true)));
+                        }
+                    }
+                    return None;
+                }
+            }
+        }
+        inner.done = false;
+        Some(Ok(row))
+    }
+}
+
+/// Python-equivalent zip(a, b, ...) over 2 to 6 iterables of any types,
+/// yielding flat tuples.
+#[macro_export]
+macro_rules! zip {
+    ($a:expr, $b:expr $(,)?) => {
+        ::core::iter::IntoIterator::into_iter($a).zip($b)
+    };
+    ($a:expr, $b:expr, $c:expr $(,)?) => {
+        $crate::zip!($a, $b).zip($c).map(|((a, b), c)| (a, b, c))
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr $(,)?) => {
+        $crate::zip!($a, $b, $c)
+            .zip($d)
+            .map(|((a, b, c), d)| (a, b, c, d))
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr $(,)?) => {
+        $crate::zip!($a, $b, $c, $d)
+            .zip($e)
+            .map(|((a, b, c, d), e)| (a, b, c, d, e))
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $f:expr $(,)?) => {
+        $crate::zip!($a, $b, $c, $d, $e)
+            .zip($f)
+            .map(|((a, b, c, d, e), f)| (a, b, c, d, e, f))
+    };
+}
+
+/// Python-equivalent zip(a, b, ..., strict=True) over 2 to 6 iterables,
+/// yielding `PyResult` of flat tuples.
+#[macro_export]
+macro_rules! zip_strict {
+    (@nest $acc:expr, $n:expr ;) => { $acc };
+    (@nest $acc:expr, $n:expr ; $next:expr $(, $rest:expr)*) => {
+        $crate::zip_strict!(@nest $crate::builtins::zip::ZipStrict::new($acc, $next, $n), $n + 1 ; $($rest),*)
+    };
+    ($a:expr, $b:expr $(,)?) => {
+        $crate::builtins::zip::zip_strict($a, $b)
+    };
+    ($a:expr, $b:expr, $c:expr $(,)?) => {
+        $crate::zip_strict!(@nest $crate::zip_strict!($a, $b), 3 ; $c)
+            .map(|r| r.map(|((a, b), c)| (a, b, c)))
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr $(,)?) => {
+        $crate::zip_strict!(@nest $crate::zip_strict!($a, $b), 3 ; $c, $d)
+            .map(|r| r.map(|(((a, b), c), d)| (a, b, c, d)))
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr $(,)?) => {
+        $crate::zip_strict!(@nest $crate::zip_strict!($a, $b), 3 ; $c, $d, $e)
+            .map(|r| r.map(|((((a, b), c), d), e)| (a, b, c, d, e)))
+    };
+    ($a:expr, $b:expr, $c:expr, $d:expr, $e:expr, $f:expr $(,)?) => {
+        $crate::zip_strict!(@nest $crate::zip_strict!($a, $b), 3 ; $c, $d, $e, $f)
+            .map(|r| r.map(|(((((a, b), c), d), e), f)| (a, b, c, d, e, f)))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zip_many_and_infinite() {
+        let rows: Vec<_> = crate::zip!(1.., "abc".chars(), vec![true, false]).collect();
+        assert_eq!(rows, vec![(1, 'a', true), (2, 'b', false)]);
+        let cols = vec![vec![1, 2, 3], vec![4, 5], vec![6, 7, 8]];
+        assert_eq!(
+            zip_n(cols).collect::<Vec<_>>(),
+            vec![vec![1, 4, 6], vec![2, 5, 7]]
+        );
+        assert_eq!(zip(0.., "xy".chars()).last(), Some((1, 'y')));
+    }
+
+    #[test]
+    fn strict_mode_reports_the_offending_argument() {
+        let ok: PyResult<Vec<_>> = zip_strict(vec![1, 2], "ab".chars()).collect();
+        assert_eq!(ok.unwrap(), vec![(1, 'a'), (2, 'b')]);
+
+        let err = zip_strict(vec![1, 2], vec![1])
+            .collect::<PyResult<Vec<_>>>()
+            .unwrap_err();
+        assert_eq!(err.message, "zip() argument 2 is shorter than argument 1");
+
+        let err = crate::zip_strict!(vec![1], vec![2], vec![3, 4])
+            .collect::<PyResult<Vec<_>>>()
+            .unwrap_err();
+        assert_eq!(err.message, "zip() argument 3 is longer than arguments 1-2");
+
+        let rows = crate::zip_strict!(vec![1], vec![2], vec![3])
+            .collect::<PyResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(rows, vec![(1, 2, 3)]);
+
+        let err = zip_n_strict(vec![vec![1, 2], vec![3, 4], vec![5]])
+            .collect::<PyResult<Vec<_>>>()
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            "zip() argument 3 is shorter than arguments 1-2"
+        );
+    }
+}
@@ -0,0 +1,484 @@
+//! Python exception values raised by the runtime.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use crate::types::{PyBytes, PyRepr, PyTuple, PyValue};
+
+/// A raised Python exception: the exception class name, its args, and its
+/// message, which is str(exc).
+///
+/// `Display` shows `Type: message`, the last line of a traceback; repr()
+/// shows `Type(args)`. Exceptions compare equal when all three match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyException {
+    pub type_name: String,
+    pub message: String,
+    /// Python-equivalent exc.args. [`PyException::new`] stores the message
+    /// as the only argument.
+    pub args: Vec<ExceptionArg>,
+}
+
+/// One of exc.args. Exceptions cross threads, so arguments are kept as
+/// plain data; a value that is not (a function, or a container that may
+/// hold one) is kept as its repr.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExceptionArg {
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bytes(PyBytes),
+    Repr(String),
+}
+
+impl From<PyValue> for ExceptionArg {
+    fn from(value: PyValue) -> Self {
+        match value {
+            PyValue::None => ExceptionArg::None,
+            PyValue::Bool(b) => ExceptionArg::Bool(b),
+            PyValue::Int(i) => ExceptionArg::Int(i),
+            PyValue::Float(f) => ExceptionArg::Float(f),
+            PyValue::Str(s) => ExceptionArg::Str(s.as_str().to_string()),
+            PyValue::Bytes(b) => ExceptionArg::Bytes(b),
+            other => ExceptionArg::Repr(other.py_repr()),
+        }
+    }
+}
+
+impl From<&str> for ExceptionArg {
+    fn from(s: &str) -> Self {
+        ExceptionArg::Str(s.to_string())
+    }
+}
+
+impl From<String> for ExceptionArg {
+    fn from(s: String) -> Self {
+        ExceptionArg::Str(s)
+    }
+}
+
+impl From<i64> for ExceptionArg {
+    fn from(i: i64) -> Self {
+        ExceptionArg::Int(i)
+    }
+}
+
+/// A repr-only argument comes back as its repr text.
+impl From<ExceptionArg> for PyValue {
+    fn from(arg: ExceptionArg) -> Self {
+        match arg {
+            ExceptionArg::None => PyValue::None,
+            ExceptionArg::Bool(b) => PyValue::Bool(b),
+            ExceptionArg::Int(i) => PyValue::Int(i),
+            ExceptionArg::Float(f) => PyValue::Float(f),
+            ExceptionArg::Str(s) | ExceptionArg::Repr(s) => PyValue::from(s),
+            ExceptionArg::Bytes(b) => PyValue::Bytes(b),
+        }
+    }
+}
+
+impl ExceptionArg {
+    fn py_str(&self) -> String {
+        match self {
+            ExceptionArg::Str(s) | ExceptionArg::Repr(s) => s.clone(),
+            other => crate::builtins::str(&PyValue::from(other.clone())),
+        }
+    }
+}
+
+impl PyRepr for ExceptionArg {
+    fn py_repr(&self) -> String {
+        match self {
+            ExceptionArg::Repr(s) => s.clone(),
+            other => PyValue::from(other.clone()).py_repr(),
+        }
+    }
+}
+
+/// Result type for fallible runtime operations.
+pub type PyResult<T> = Result<T, PyException>;
+
+impl PyException {
+    pub fn new<T: Into<String>, M: Into<String>>(type_name: T, message: M) -> Self {
+        let message = message.into();
+        let args = match message.is_empty() {
+            true => Vec::new(),
+            false => Vec::from([ExceptionArg::Str(message.clone())]),
+        };
+        PyException {
+            type_name: type_name.into(),
+            message,
+            args,
+        }
+    }
+
+    /// Python-equivalent `Type(*args)`, with str(exc) worked out as CPython
+    /// does: empty for no args, str(arg) for one (repr(key) for KeyError),
+    /// `[Errno n] strerror` for a two-arg OSError, else repr(args).
+    pub fn with_args<T, I>(type_name: T, args: I) -> Self
+    where
+        T: Into<String>,
+        I: IntoIterator,
+        I::Item: Into<ExceptionArg>,
+    {
+        let type_name: String = type_name.into();
+        let args: Vec<ExceptionArg> = args.into_iter().map(Into::into).collect();
+        let message = match args.as_slice() {
+            [] => String::new(),
+            [key] if type_name == "KeyError" => key.py_repr(),
+            [arg] => arg.py_str(),
+            [ExceptionArg::Int(errno), strerror]
+                if ExceptionKind::from_name(&type_name)
+                    .is_some_and(|kind| kind.is_subclass_of(ExceptionKind::OSError)) =>
+            {
+                format!("[Errno {}] {}", errno, strerror.py_str())
+            }
+            _ => PyTuple::from_vec(args.clone()).py_repr(),
+        };
+        PyException {
+            type_name,
+            message,
+            args,
+        }
+    }
+
+    /// Python-equivalent exc.args as a tuple.
+    pub fn args_tuple(&self) -> PyTuple<PyValue> {
+        self.args.iter().cloned().map(PyValue::from).collect()
+    }
+
+    /// The built-in class this exception is, if it is one.
+    pub fn kind(&self) -> Option<ExceptionKind> {
+        ExceptionKind::from_name(&self.type_name)
+    }
+
+    /// Python-equivalent isinstance(exc, kind), following the built-in
+    /// hierarchy: a FileNotFoundError is an OSError and an Exception.
+    pub fn is_instance(&self, kind: ExceptionKind) -> bool {
+        self.kind().is_some_and(|own| own.is_subclass_of(kind))
+    }
+
+    /// Raises this exception where only a panic can: from operators such as
+    /// `x[i]` whose Rust signature has no room for a `PyResult`.
+    #[track_caller]
+    pub fn raise(self) -> ! {
+        panic!("{}", self)
+    }
+
+    /// True if this exception is of the named class.
+    pub fn is(&self, type_name: &str) -> bool {
+        self.type_name == type_name
+    }
+
+    pub fn value_error<M: Into<String>>(message: M) -> Self {
+        Self::new("ValueError", message)
+    }
+
+    pub fn type_error<M: Into<String>>(message: M) -> Self {
+        Self::new("TypeError", message)
+    }
+
+    /// Python-equivalent KeyError(key): the key itself is the argument,
+    /// and str(exc) is its repr.
+    pub fn key_error<K: PyRepr>(key: K) -> Self {
+        Self::with_args("KeyError", [key.exception_arg()])
+    }
+
+    pub fn index_error<M: Into<String>>(message: M) -> Self {
+        Self::new("IndexError", message)
+    }
+
+    pub fn runtime_error<M: Into<String>>(message: M) -> Self {
+        Self::new("RuntimeError", message)
+    }
+
+    pub fn os_error<M: Into<String>>(message: M) -> Self {
+        Self::new("OSError", message)
+    }
+
+    pub fn attribute_error<M: Into<String>>(message: M) -> Self {
+        Self::new("AttributeError", message)
+    }
+
+    pub fn memory_error<M: Into<String>>(message: M) -> Self {
+        Self::new("MemoryError", message)
+    }
+
+    pub fn eof_error<M: Into<String>>(message: M) -> Self {
+        Self::new("EOFError", message)
+    }
+
+    pub fn assertion_error<M: Into<String>>(message: M) -> Self {
+        Self::new("AssertionError", message)
+    }
+}
+
+/// What a failing `assert cond, msg` raises: AssertionError(str(msg)).
+pub fn assertion_failed<M: fmt::Display + ?Sized>(message: &M) -> PyException {
+    PyException::assertion_error(message.to_string())
+}
+
+/// Python's `assert` statement, for functions returning [`PyResult`]:
+/// `py_assert!(cond)` or `py_assert!(cond, msg)` returns AssertionError
+/// from the enclosing function unless `cond` is truthy. `msg` is only
+/// evaluated on failure, as in Python.
+#[macro_export]
+macro_rules! py_assert {
+    ($cond:expr $(,)?) => {
+        if !$crate::types::PyTruthy::py_bool(&$cond) {
+            return ::core::result::Result::Err(
+                $crate::exceptions::PyException::assertion_error("").into(),
+            );
+        }
+    };
+    ($cond:expr, $msg:expr $(,)?) => {
+        if !$crate::types::PyTruthy::py_bool(&$cond) {
+            return ::core::result::Result::Err($crate::exceptions::assertion_failed(&$msg).into());
+        }
+    };
+}
+
+impl fmt::Display for PyException {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.message.is_empty() {
+            write!(f, "{}", self.type_name)
+        } else {
+            write!(f, "{}: {}", self.type_name, self.message)
+        }
+    }
+}
+
+impl Error for PyException {}
+
+/// Python-equivalent repr(exc): `ValueError('msg')`.
+impl PyRepr for PyException {
+    fn py_repr(&self) -> String {
+        match self.args.as_slice() {
+            [arg] => format!("{}({})", self.type_name, arg.py_repr()),
+            _ => format!(
+                "{}{}",
+                self.type_name,
+                PyTuple::from_vec(self.args.clone()).py_repr()
+            ),
+        }
+    }
+}
+
+macro_rules! exception_kinds {
+    ($($kind:ident => $base:ident,)*) => {
+        /// The built-in exception classes, each with its base class.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum ExceptionKind {
+            BaseException,
+            $($kind,)*
+        }
+
+        impl ExceptionKind {
+            /// The class name, e.g. `ValueError`.
+            pub fn name(self) -> &'static str {
+                match self {
+                    ExceptionKind::BaseException => "BaseException",
+                    $(ExceptionKind::$kind => stringify!($kind),)*
+                }
+            }
+
+            /// The built-in class called `name`.
+            pub fn from_name(name: &str) -> Option<ExceptionKind> {
+                match name {
+                    "BaseException" => Some(ExceptionKind::BaseException),
+                    $(stringify!($kind) => Some(ExceptionKind::$kind),)*
+                    _ => None,
+                }
+            }
+
+            /// The direct base class; None for BaseException.
+            pub fn base(self) -> Option<ExceptionKind> {
+                match self {
+                    ExceptionKind::BaseException => None,
+                    $(ExceptionKind::$kind => Some(ExceptionKind::$base),)*
+                }
+            }
+        }
+    };
+}
+
+exception_kinds! {
+    SystemExit => BaseException,
+    KeyboardInterrupt => BaseException,
+    GeneratorExit => BaseException,
+    Exception => BaseException,
+    StopIteration => Exception,
+    StopAsyncIteration => Exception,
+    ArithmeticError => Exception,
+    FloatingPointError => ArithmeticError,
+    OverflowError => ArithmeticError,
+    ZeroDivisionError => ArithmeticError,
+    AssertionError => Exception,
+    AttributeError => Exception,
+    BufferError => Exception,
+    EOFError => Exception,
+    ImportError => Exception,
+    ModuleNotFoundError => ImportError,
+    LookupError => Exception,
+    IndexError => LookupError,
+    KeyError => LookupError,
+    MemoryError => Exception,
+    NameError => Exception,
+    UnboundLocalError => NameError,
+    OSError => Exception,
+    BlockingIOError => OSError,
+    ChildProcessError => OSError,
+    ConnectionError => OSError,
+    BrokenPipeError => ConnectionError,
+    ConnectionAbortedError => ConnectionError,
+    ConnectionRefusedError => ConnectionError,
+    ConnectionResetError => ConnectionError,
+    FileExistsError => OSError,
+    FileNotFoundError => OSError,
+    InterruptedError => OSError,
+    IsADirectoryError => OSError,
+    NotADirectoryError => OSError,
+    PermissionError => OSError,
+    ProcessLookupError => OSError,
+    TimeoutError => OSError,
+    ReferenceError => Exception,
+    RuntimeError => Exception,
+    NotImplementedError => RuntimeError,
+    RecursionError => RuntimeError,
+    SyntaxError => Exception,
+    IndentationError => SyntaxError,
+    TabError => IndentationError,
+    SystemError => Exception,
+    TypeError => Exception,
+    ValueError => Exception,
+    UnicodeError => ValueError,
+    UnicodeDecodeError => UnicodeError,
+    UnicodeEncodeError => UnicodeError,
+    UnicodeTranslateError => UnicodeError,
+    Warning => Exception,
+    BytesWarning => Warning,
+    DeprecationWarning => Warning,
+    EncodingWarning => Warning,
+    FutureWarning => Warning,
+    ImportWarning => Warning,
+    PendingDeprecationWarning => Warning,
+    ResourceWarning => Warning,
+    RuntimeWarning => Warning,
+    SyntaxWarning => Warning,
+    UnicodeWarning => Warning,
+    UserWarning => Warning,
+}
+
+impl ExceptionKind {
+    /// Python-equivalent issubclass(self, other).
+    pub fn is_subclass_of(self, other: ExceptionKind) -> bool {
+        let mut kind = Some(self);
+        while let Some(k) = kind {
+            if k == other {
+                return true;
+            }
+            kind = k.base();
+        }
+        false
+    }
+
+    /// An exception of this class raised with `message`.
+    pub fn with_message<M: Into<String>>(self, message: M) -> PyException {
+        PyException::new(self.name(), message)
+    }
+}
+
+impl fmt::Display for ExceptionKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Python-equivalent `raise Kind`: an instance with no args.
+impl From<ExceptionKind> for PyException {
+    fn from(kind: ExceptionKind) -> Self {
+        PyException::with_args(kind.name(), [] as [ExceptionArg; 0])
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for PyException {
+    fn from(err: std::io::Error) -> Self {
+        use std::io::ErrorKind;
+        let type_name = match err.kind() {
+            ErrorKind::NotFound => "FileNotFoundError",
+            ErrorKind::PermissionDenied => "PermissionError",
+            ErrorKind::AlreadyExists => "FileExistsError",
+            ErrorKind::TimedOut => "TimeoutError",
+            ErrorKind::BrokenPipe => "BrokenPipeError",
+            ErrorKind::ConnectionRefused => "ConnectionRefusedError",
+            ErrorKind::ConnectionReset => "ConnectionResetError",
+            ErrorKind::ConnectionAborted => "ConnectionAbortedError",
+            ErrorKind::Interrupted => "InterruptedError",
+            ErrorKind::WouldBlock => "BlockingIOError",
+            ErrorKind::UnexpectedEof => "EOFError",
+            _ => "OSError",
+        };
+        PyException::new(type_name, err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args_str_and_repr_match_cpython() {
+        let e = PyException::value_error("a");
+        assert_eq!(e.message, "a");
+        assert_eq!(e.py_repr(), "ValueError('a')");
+        assert_eq!(e.args_tuple().len(), 1);
+        let e = PyException::from(ExceptionKind::ValueError);
+        assert_eq!(e.to_string(), "ValueError");
+        assert_eq!(e.py_repr(), "ValueError()");
+        let e = PyException::with_args(
+            "FileNotFoundError",
+            [ExceptionArg::Int(2), ExceptionArg::from("x")],
+        );
+        assert_eq!(e.message, "[Errno 2] x");
+        assert_eq!(e.py_repr(), "FileNotFoundError(2, 'x')");
+        let e = PyException::with_args("KeyError", ["b"]);
+        assert_eq!(e.to_string(), "KeyError: 'b'");
+        assert_eq!(e.py_repr(), "KeyError('b')");
+        let e = crate::PyDictionary::<&str, i64>::new()
+            .pop(&"z")
+            .unwrap_err();
+        assert_eq!(e.to_string(), "KeyError: 'z'");
+        assert_eq!(e.py_repr(), "KeyError('z')");
+        assert_eq!(e.args_tuple().py_repr(), "('z',)");
+        assert_eq!(PyException::key_error(3).args, [ExceptionArg::Int(3)]);
+        let e = PyException::with_args("ValueError", [1, 2]);
+        assert_eq!(e.message, "(1, 2)");
+        assert_eq!(e, PyException::with_args("ValueError", [1, 2]));
+        assert_ne!(e, PyException::with_args("TypeError", [1, 2]));
+    }
+
+    #[test]
+    fn kinds_follow_the_builtin_hierarchy() {
+        let e = PyException::new("FileNotFoundError", "gone");
+        assert_eq!(e.kind(), Some(ExceptionKind::FileNotFoundError));
+        assert!(e.is_instance(ExceptionKind::OSError));
+        assert!(e.is_instance(ExceptionKind::Exception));
+        assert!(!e.is_instance(ExceptionKind::ValueError));
+        assert!(!ExceptionKind::KeyboardInterrupt.is_subclass_of(ExceptionKind::Exception));
+        assert_eq!(
+            ExceptionKind::TabError.base(),
+            Some(ExceptionKind::IndentationError)
+        );
+        assert_eq!(
+            ExceptionKind::KeyError.with_message("'k'").to_string(),
+            "KeyError: 'k'"
+        );
+        assert_eq!(PyException::new("MyError", "x").kind(), None);
+    }
+}
@@ -0,0 +1,395 @@
+//! Conversions between the crate's Python types and objects owned by an
+//! embedded CPython interpreter, via PyO3.
+//!
+//! Every container converts element-wise, so e.g. `PyList<PyStr>` becomes a
+//! Python `list[str]` and extracts back from one. `PyValue` maps onto the
+//! matching built-in type. Rust closures are not `Send`, so a
+//! `PyValue::Function` cannot be exported and converts to its repr.
+
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::types::{
+    PyBool as PyBoolObject, PyBytes as PyBytesObject, PyDict as PyDictObject,
+    PyFloat as PyFloatObject, PyFrozenSet as PyFrozenSetObject, PyList as PyListObject,
+    PyLong as PyLongObject, PySet as PySetObject, PyString as PyStringObject,
+    PyTuple as PyTupleObject,
+};
+use pyo3::{FromPyObject, IntoPy, PyAny, PyErr, PyObject, Python, ToPyObject};
+
+use crate::exceptions::{ExceptionArg, PyException};
+use crate::stdlib::pathlib::{Path, PurePath};
+use crate::types::{PyBytes, PyDictionary, PyList, PySet, PyStr, PyTuple, PyValue};
+
+type ExtractResult<T> = pyo3::PyResult<T>;
+
+/// Raises a runtime exception in the interpreter, keeping its class when it
+/// is a built-in one.
+impl From<PyException> for PyErr {
+    fn from(err: PyException) -> Self {
+        use pyo3::exceptions::*;
+        let msg = err.message;
+        match err.type_name.as_str() {
+            "ValueError" => PyValueError::new_err(msg),
+            "TypeError" => PyTypeError::new_err(msg),
+            "KeyError" => match err.args.as_slice() {
+                [ExceptionArg::Str(key)] => PyKeyError::new_err(key.clone()),
+                [ExceptionArg::Int(key)] => PyKeyError::new_err(*key),
+                _ => PyKeyError::new_err(msg),
+            },
+            "IndexError" => PyIndexError::new_err(msg),
+            "AttributeError" => PyAttributeError::new_err(msg),
+            "RuntimeError" => PyRuntimeError::new_err(msg),
+            "OverflowError" => PyOverflowError::new_err(msg),
+            "FileNotFoundError" => PyFileNotFoundError::new_err(msg),
+            "FileExistsError" => PyFileExistsError::new_err(msg),
+            "PermissionError" => PyPermissionError::new_err(msg),
+            "OSError" => PyOSError::new_err(msg),
+            other => PyRuntimeError::new_err(format!("{}: {}", other, msg)),
+        }
+    }
+}
+
+/// Captures an interpreter exception as a runtime [`PyException`].
+impl From<PyErr> for PyException {
+    fn from(err: PyErr) -> Self {
+        Python::with_gil(|py| {
+            let type_name = err
+                .get_type(py)
+                .name()
+                .map(str::to_string)
+                .unwrap_or_else(|_| "Exception".to_string());
+            let message = err.value(py).to_string();
+            PyException::new(type_name, message)
+        })
+    }
+}
+
+impl ToPyObject for PyStr {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        self.as_str().to_object(py)
+    }
+}
+
+impl IntoPy<PyObject> for PyStr {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.as_str().to_object(py)
+    }
+}
+
+impl<'source> FromPyObject<'source> for PyStr {
+    fn extract(ob: &'source PyAny) -> ExtractResult<Self> {
+        Ok(PyStr::from(ob.extract::<String>()?))
+    }
+}
+
+impl ToPyObject for PyBytes {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        PyBytesObject::new(py, self.as_bytes()).into()
+    }
+}
+
+impl IntoPy<PyObject> for PyBytes {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl<'source> FromPyObject<'source> for PyBytes {
+    fn extract(ob: &'source PyAny) -> ExtractResult<Self> {
+        Ok(PyBytes::new(ob.downcast::<PyBytesObject>()?.as_bytes()))
+    }
+}
+
+impl<T: ToPyObject> ToPyObject for PyList<T> {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        PyListObject::new(py, self.iter().map(|x| x.to_object(py))).into()
+    }
+}
+
+impl<T: IntoPy<PyObject>> IntoPy<PyObject> for PyList<T> {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        PyListObject::new(py, self.into_vec().into_iter().map(|x| x.into_py(py))).into()
+    }
+}
+
+impl<'source, T: FromPyObject<'source>> FromPyObject<'source> for PyList<T> {
+    fn extract(ob: &'source PyAny) -> ExtractResult<Self> {
+        let list = ob.downcast::<PyListObject>()?;
+        list.iter()
+            .map(|item| item.extract())
+            .collect::<ExtractResult<Vec<T>>>()
+            .map(PyList::from_vec)
+    }
+}
+
+impl<T: ToPyObject> ToPyObject for PyTuple<T> {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        PyTupleObject::new(py, self.iter().map(|x| x.to_object(py))).into()
+    }
+}
+
+impl<T: IntoPy<PyObject>> IntoPy<PyObject> for PyTuple<T> {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        let items: Vec<PyObject> = self.into_vec().into_iter().map(|x| x.into_py(py)).collect();
+        PyTupleObject::new(py, items).into()
+    }
+}
+
+impl<'source, T: FromPyObject<'source>> FromPyObject<'source> for PyTuple<T> {
+    fn extract(ob: &'source PyAny) -> ExtractResult<Self> {
+        let tuple = ob.downcast::<PyTupleObject>()?;
+        tuple
+            .iter()
+            .map(|item| item.extract())
+            .collect::<ExtractResult<Vec<T>>>()
+            .map(PyTuple::from_vec)
+    }
+}
+
+impl<K, V> ToPyObject for PyDictionary<K, V>
+where
+    K: ToPyObject + Hash + Eq,
+    V: ToPyObject,
+{
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        let dict = PyDictObject::new(py);
+        for (k, v) in self.iter() {
+            dict.set_item(k.to_object(py), v.to_object(py))
+                .expect("keys of a PyDictionary are hashable");
+        }
+        dict.into()
+    }
+}
+
+impl<K, V> IntoPy<PyObject> for PyDictionary<K, V>
+where
+    K: ToPyObject + Hash + Eq,
+    V: ToPyObject,
+{
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl<'source, K, V> FromPyObject<'source> for PyDictionary<K, V>
+where
+    K: FromPyObject<'source> + Hash + Eq + Clone,
+    V: FromPyObject<'source>,
+{
+    fn extract(ob: &'source PyAny) -> ExtractResult<Self> {
+        let dict = ob.downcast::<PyDictObject>()?;
+        let mut out = PyDictionary::new();
+        for (k, v) in dict.iter() {
+            out.set_item(k.extract()?, v.extract()?);
+        }
+        Ok(out)
+    }
+}
+
+impl<T: ToPyObject + Hash + Eq> ToPyObject for PySet<T> {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        let items: Vec<PyObject> = self.iter().map(|x| x.to_object(py)).collect();
+        PySetObject::new(py, &items)
+            .expect("elements of a PySet are hashable")
+            .into()
+    }
+}
+
+impl<T: ToPyObject + Hash + Eq> IntoPy<PyObject> for PySet<T> {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl<'source, T: FromPyObject<'source> + Hash + Eq> FromPyObject<'source> for PySet<T> {
+    fn extract(ob: &'source PyAny) -> ExtractResult<Self> {
+        let items: HashSet<T> = ob.extract()?;
+        Ok(items.into_iter().collect())
+    }
+}
+
+impl ToPyObject for PyValue {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        match self {
+            PyValue::None => py.None(),
+            PyValue::Bool(b) => b.to_object(py),
+            PyValue::Int(i) => i.to_object(py),
+            PyValue::Float(f) => f.to_object(py),
+            PyValue::Str(s) => s.to_object(py),
+            PyValue::Bytes(b) => b.to_object(py),
+            PyValue::List(l) => l.to_object(py),
+            PyValue::Tuple(t) => t.to_object(py),
+            PyValue::Dict(d) => d.to_object(py),
+            PyValue::Set(s) => s.to_object(py),
+            PyValue::Function(f) => f.to_string().to_object(py),
+            PyValue::NotImplemented => py.NotImplemented(),
+        }
+    }
+}
+
+impl IntoPy<PyObject> for PyValue {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl<'source> FromPyObject<'source> for PyValue {
+    fn extract(ob: &'source PyAny) -> ExtractResult<Self> {
+        if ob.is_none() {
+            Ok(PyValue::None)
+        } else if ob.is_instance_of::<PyBoolObject>() {
+            Ok(PyValue::Bool(ob.extract()?))
+        } else if ob.is_instance_of::<PyLongObject>() {
+            ob.extract::<i64>()
+                .map(PyValue::Int)
+                .map_err(|_| PyValueError::new_err("int too large to convert to a 64-bit PyValue"))
+        } else if ob.is_instance_of::<PyFloatObject>() {
+            Ok(PyValue::Float(ob.extract()?))
+        } else if ob.is_instance_of::<PyStringObject>() {
+            Ok(PyValue::Str(ob.extract()?))
+        } else if ob.is_instance_of::<PyBytesObject>() {
+            Ok(PyValue::Bytes(ob.extract()?))
+        } else if ob.is_instance_of::<PyListObject>() {
+            Ok(PyValue::List(ob.extract()?))
+        } else if ob.is_instance_of::<PyTupleObject>() {
+            Ok(PyValue::Tuple(ob.extract()?))
+        } else if ob.is_instance_of::<PyDictObject>() {
+            Ok(PyValue::Dict(ob.extract()?))
+        } else if ob.is_instance_of::<PySetObject>() || ob.is_instance_of::<PyFrozenSetObject>() {
+            let items = ob
+                .iter()?
+                .map(|item| item.and_then(|i| i.extract::<PyValue>()))
+                .collect::<ExtractResult<PySet<PyValue>>>()?;
+            Ok(PyValue::Set(items))
+        } else {
+            Err(PyTypeError::new_err(format!(
+                "cannot convert '{}' object to PyValue",
+                ob.get_type().name()?
+            )))
+        }
+    }
+}
+
+/// Builds a `pathlib.PurePath`/`pathlib.Path`, falling back to a plain str
+/// if the module cannot be imported.
+fn pathlib_object(py: Python<'_>, class: &str, path: &std::path::Path) -> PyObject {
+    py.import("pathlib")
+        .and_then(|m| m.getattr(class))
+        .and_then(|cls| cls.call1((path.to_object(py),)))
+        .map(Into::into)
+        .unwrap_or_else(|_| path.to_object(py))
+}
+
+impl ToPyObject for PurePath {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        pathlib_object(py, "PurePath", self.as_std_path())
+    }
+}
+
+impl IntoPy<PyObject> for PurePath {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl<'source> FromPyObject<'source> for PurePath {
+    fn extract(ob: &'source PyAny) -> ExtractResult<Self> {
+        Ok(PurePath::new(ob.extract::<std::path::PathBuf>()?))
+    }
+}
+
+impl ToPyObject for Path {
+    fn to_object(&self, py: Python<'_>) -> PyObject {
+        pathlib_object(py, "Path", self.as_std_path())
+    }
+}
+
+impl IntoPy<PyObject> for Path {
+    fn into_py(self, py: Python<'_>) -> PyObject {
+        self.to_object(py)
+    }
+}
+
+impl<'source> FromPyObject<'source> for Path {
+    fn extract(ob: &'source PyAny) -> ExtractResult<Self> {
+        Ok(Path::new(ob.extract::<std::path::PathBuf>()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<T>(value: T) -> T
+    where
+        T: ToPyObject + for<'a> FromPyObject<'a>,
+    {
+        Python::with_gil(|py| value.to_object(py).extract(py).unwrap())
+    }
+
+    #[test]
+    fn containers_round_trip() {
+        let list = PyList::from_vec(vec![PyStr::new("a"), PyStr::new("b")]);
+        assert_eq!(round_trip(list.clone()), list);
+        let tuple = PyTuple::from_vec(vec![1i64, 2, 3]);
+        assert_eq!(round_trip(tuple.clone()), tuple);
+        let dict: PyDictionary<String, f64> = [("x".to_string(), 1.5), ("y".to_string(), -2.0)]
+            .into_iter()
+            .collect();
+        let back = round_trip(dict.clone());
+        assert_eq!(back, dict);
+        assert_eq!(back.keys(), vec!["x", "y"]);
+        let set: PySet<i64> = [1, 2, 3].into_iter().collect();
+        assert_eq!(round_trip(set.clone()), set);
+        assert_eq!(
+            round_trip(PyBytes::new(b"\x00hi".to_vec())).as_bytes(),
+            b"\x00hi"
+        );
+    }
+
+    #[test]
+    fn values_keep_python_types() {
+        let value = PyValue::from(vec![
+            PyValue::None,
+            PyValue::Bool(true),
+            PyValue::Int(7),
+            PyValue::Float(0.5),
+            PyValue::from("s"),
+        ]);
+        Python::with_gil(|py| {
+            let obj = value.to_object(py);
+            assert_eq!(
+                obj.as_ref(py).repr().unwrap().to_str().unwrap(),
+                "[None, True, 7, 0.5, 's']"
+            );
+            let back: PyValue = obj.extract(py).unwrap();
+            assert_eq!(back, value);
+            assert!(
+                matches!(back, PyValue::List(ref l) if matches!(l.get(1), Ok(PyValue::Bool(true))))
+            );
+        });
+    }
+
+    #[test]
+    fn paths_become_pathlib_objects() {
+        Python::with_gil(|py| {
+            let obj = Path::new("/tmp/x.txt").to_object(py);
+            assert_eq!(obj.as_ref(py).get_type().name().unwrap(), "PosixPath");
+            let back: Path = obj.extract(py).unwrap();
+            assert_eq!(back.as_posix(), "/tmp/x.txt");
+        });
+    }
+
+    #[test]
+    fn exceptions_cross_the_boundary() {
+        Python::with_gil(|py| {
+            let err: PyErr = PyException::key_error("k").into();
+            assert!(err.is_instance_of::<pyo3::exceptions::PyKeyError>(py));
+        });
+        let back = PyException::from(PyErr::from(PyException::value_error("bad")));
+        assert!(back.is("ValueError"));
+        assert_eq!(back.message, "bad");
+    }
+}
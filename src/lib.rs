@@ -1,21 +1,133 @@
 //#![feature(c_variadic)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 //use std::collections::HashMap;
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
 use std::fmt::Display;
 
+#[cfg(feature = "std")]
 pub use pyo3::{PyAny, types::PyDict, PyObject};
 pub use python_mod::python_module_nostd;
 
+pub mod builtins;
+pub mod exceptions;
+#[cfg(feature = "interop")]
+pub mod interop;
+pub mod stdlib;
+mod sync;
+pub mod types;
+
+pub use builtins::*;
+pub use exceptions::{ExceptionArg, ExceptionKind, PyException, PyResult};
+pub use types::*;
+
+#[cfg(feature = "std")]
 python_module_nostd!{lib
     use pyo3::{
         PyAny, PyObject,
     };
 }
 
+#[cfg(feature = "std")]
 pub use lib::*;
 
-/// Python-equivalent print() function.
+/// Python-equivalent print() function. Output goes to sys.stdout, which
+/// `stdlib::io::redirect_stdout` and `capture` can swap per thread; a failed
+/// write raises, as println! would panic.
+#[cfg(feature = "std")]
 pub fn print<S: Display>(s: S) {
-    println!("{}", s);
+    // One write per line, so lines from different threads never interleave.
+    if let Err(err) = stdlib::io::write_stdout(&print_to_string(&s)) {
+        err.raise();
+    }
+}
+
+/// Python-equivalent input(prompt): the next line of stdin without its line
+/// ending, raising EOFError at end of input. The prompt is written to stdout
+/// and flushed even when stdin is not a terminal. With the `readline`
+/// feature, lines typed at a terminal are added to the
+/// `stdlib::readline` history.
+#[cfg(feature = "std")]
+pub fn input<S: Display>(prompt: S) -> PyResult<String> {
+    let stdin = std::io::stdin();
+    let line = stdlib::io::input_from(
+        &prompt.to_string(),
+        &mut stdin.lock(),
+        &mut std::io::stdout(),
+    )?;
+    #[cfg(feature = "readline")]
+    if std::io::IsTerminal::is_terminal(&stdin) {
+        stdlib::readline::record_input(&line);
+    }
+    Ok(line)
+}
+
+/// A checkpoint compiled loops run on each back-edge: handles signals that
+/// arrived since the last one, so Ctrl-C raises KeyboardInterrupt here
+/// instead of killing the process. Cheap when nothing is pending.
+#[cfg(feature = "std")]
+#[inline]
+pub fn py_check_signals() -> PyResult<()> {
+    stdlib::signal::install_default_int_handler();
+    stdlib::signal::check_signals()
+}
+
+/// Interpreter shutdown, which compiled programs call as `main` ends: runs
+/// the `stdlib::atexit` handlers, most recent first, then flushes
+/// sys.stdout.
+#[cfg(feature = "std")]
+pub fn py_runtime_shutdown() {
+    shutdown();
+}
+
+/// [`py_runtime_shutdown`], returning whether sys.stdout flushed.
+#[cfg(feature = "std")]
+fn shutdown() -> bool {
+    stdlib::atexit::_run_exitfuncs();
+    if let Err(err) = stdlib::io::flush_stdout() {
+        eprintln!("Exception ignored on flushing sys.stdout:\n{}", err);
+        return false;
+    }
+    true
+}
+
+/// Runs a compiled program's module body the way `python script.py` does:
+/// installs the SIGINT handler, runs `main`, reports an uncaught exception
+/// on stderr (see [`stdlib::sys::exit_status`]), then runs the atexit
+/// handlers and flushes sys.stdout. A failed flush turns a zero status
+/// into 120, as in CPython. [`py_main!`] wraps this as `fn main`.
+#[cfg(feature = "std")]
+pub fn run_python_main<F: FnOnce() -> PyResult<()>>(main: F) -> std::process::ExitCode {
+    stdlib::signal::install_default_int_handler();
+    let status = match main() {
+        Ok(()) => 0,
+        Err(err) => stdlib::sys::exit_status(&err, &mut std::io::stderr()),
+    };
+    let status = match shutdown() {
+        false if status == 0 => 120,
+        _ => status,
+    };
+    // Like a process exit status, only the low byte is kept.
+    std::process::ExitCode::from(status as u8)
+}
+
+/// Defines the program's `fn main` from a `fn() -> PyResult<()>` module
+/// body, through [`run_python_main`]:
+///
+/// ```ignore
+/// fn module_main() -> PyResult<()> { ... }
+/// stdpython::py_main!(module_main);
+/// ```
+#[cfg(feature = "std")]
+#[macro_export]
+macro_rules! py_main {
+    ($main:expr) => {
+        fn main() -> ::std::process::ExitCode {
+            $crate::run_python_main($main)
+        }
+    };
 }
 
 #[cfg(test)]
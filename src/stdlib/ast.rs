@@ -0,0 +1,2135 @@
+//! Python-equivalent ast module for the expression subset compiled programs
+//! evaluate at runtime: literals, names, operators, comparisons, boolean
+//! logic, conditional expressions, subscripts, attributes and calls, plus
+//! simple statements for exec().
+//!
+//! [`literal_eval`] accepts exactly what CPython's does. Syntax outside the
+//! subset (lambdas, comprehensions, compound statements) raises SyntaxError
+//! naming what is unsupported. [`dump`] and [`walk`] inspect the same trees
+//! for tooling scripts.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::builtins::repr;
+use crate::exceptions::{PyException, PyResult};
+use crate::types::repr::repr_str;
+use crate::types::{
+    PyBytes, PyDictionary, PyHash, PyKwargs, PyList, PySet, PyStr, PyTuple, PyValue,
+};
+
+/// Python-equivalent ast.unaryop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnaryOp {
+    UAdd,
+    USub,
+    Invert,
+    Not,
+}
+
+impl UnaryOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            UnaryOp::UAdd => "+",
+            UnaryOp::USub => "-",
+            UnaryOp::Invert => "~",
+            UnaryOp::Not => "not",
+        }
+    }
+}
+
+/// Python-equivalent ast.operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mult,
+    MatMult,
+    Div,
+    FloorDiv,
+    Mod,
+    Pow,
+    LShift,
+    RShift,
+    BitOr,
+    BitXor,
+    BitAnd,
+}
+
+impl BinOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mult => "*",
+            BinOp::MatMult => "@",
+            BinOp::Div => "/",
+            BinOp::FloorDiv => "//",
+            BinOp::Mod => "%",
+            BinOp::Pow => "**",
+            BinOp::LShift => "<<",
+            BinOp::RShift => ">>",
+            BinOp::BitOr => "|",
+            BinOp::BitXor => "^",
+            BinOp::BitAnd => "&",
+        }
+    }
+
+    fn from_symbol(symbol: &str) -> Option<BinOp> {
+        Some(match symbol {
+            "+" => BinOp::Add,
+            "-" => BinOp::Sub,
+            "*" => BinOp::Mult,
+            "@" => BinOp::MatMult,
+            "/" => BinOp::Div,
+            "//" => BinOp::FloorDiv,
+            "%" => BinOp::Mod,
+            "**" => BinOp::Pow,
+            "<<" => BinOp::LShift,
+            ">>" => BinOp::RShift,
+            "|" => BinOp::BitOr,
+            "^" => BinOp::BitXor,
+            "&" => BinOp::BitAnd,
+            _ => return None,
+        })
+    }
+}
+
+/// Python-equivalent ast.boolop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoolOp {
+    And,
+    Or,
+}
+
+/// Python-equivalent ast.cmpop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CmpOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtE,
+    Gt,
+    GtE,
+    Is,
+    IsNot,
+    In,
+    NotIn,
+}
+
+/// An expression node and the line it starts on (`lineno`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    pub kind: ExprKind,
+    pub lineno: usize,
+}
+
+/// Python-equivalent ast.expr subclasses, for the supported subset.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExprKind {
+    Constant(PyValue),
+    Name(String),
+    Tuple(Vec<Expr>),
+    List(Vec<Expr>),
+    Set(Vec<Expr>),
+    Dict(Vec<(Expr, Expr)>),
+    UnaryOp(UnaryOp, Box<Expr>),
+    BinOp(Box<Expr>, BinOp, Box<Expr>),
+    BoolOp(BoolOp, Vec<Expr>),
+    Compare(Box<Expr>, Vec<(CmpOp, Expr)>),
+    IfExp {
+        test: Box<Expr>,
+        body: Box<Expr>,
+        orelse: Box<Expr>,
+    },
+    Subscript(Box<Expr>, Box<Expr>),
+    Slice {
+        lower: Option<Box<Expr>>,
+        upper: Option<Box<Expr>>,
+        step: Option<Box<Expr>>,
+    },
+    Attribute(Box<Expr>, String),
+    Call {
+        func: Box<Expr>,
+        args: Vec<Expr>,
+        keywords: Vec<(String, Expr)>,
+    },
+}
+
+impl Expr {
+    /// The node's ast class name, e.g. `BinOp`.
+    pub fn node_name(&self) -> &'static str {
+        match &self.kind {
+            ExprKind::Constant(_) => "Constant",
+            ExprKind::Name(_) => "Name",
+            ExprKind::Tuple(_) => "Tuple",
+            ExprKind::List(_) => "List",
+            ExprKind::Set(_) => "Set",
+            ExprKind::Dict(_) => "Dict",
+            ExprKind::UnaryOp(..) => "UnaryOp",
+            ExprKind::BinOp(..) => "BinOp",
+            ExprKind::BoolOp(..) => "BoolOp",
+            ExprKind::Compare(..) => "Compare",
+            ExprKind::IfExp { .. } => "IfExp",
+            ExprKind::Subscript(..) => "Subscript",
+            ExprKind::Slice { .. } => "Slice",
+            ExprKind::Attribute(..) => "Attribute",
+            ExprKind::Call { .. } => "Call",
+        }
+    }
+}
+
+/// Python-equivalent ast.stmt subclasses accepted by exec().
+#[derive(Debug, Clone, PartialEq)]
+pub enum Stmt {
+    Expr(Expr),
+    /// `a = b = value`: every target, then the value.
+    Assign(Vec<Expr>, Expr),
+    AugAssign(Expr, BinOp, Expr),
+    Delete(Vec<Expr>),
+    Pass,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Op(&'static str),
+    Newline,
+    End,
+}
+
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield",
+];
+
+/// Longest first, so `**=` wins over `**` and `*`.
+const OPERATORS: &[&str] = &[
+    "**=", "//=", ">>=", "<<=", "...", "**", "//", "<<", ">>", "<=", ">=", "==", "!=", "->", "+=",
+    "-=", "*=", "/=", "%=", "&=", "|=", "^=", "@=", ":=", "+", "-", "*", "/", "%", "@", "&", "|",
+    "^", "~", "<", ">", "(", ")", "[", "]", "{", "}", ",", ":", ".", ";", "=",
+];
+
+fn syntax_error(msg: &str, filename: &str, line: usize) -> PyException {
+    PyException::new(
+        "SyntaxError",
+        format!("{} ({}, line {})", msg, filename, line),
+    )
+}
+
+/// How deeply brackets may nest: CPython's tokenizer MAXLEVEL, lowered in
+/// debug builds, whose unoptimized frames are several times larger, as
+/// CPython lowers its C recursion limit under Py_DEBUG.
+const MAX_BRACKETS: usize = if cfg!(debug_assertions) { 50 } else { 200 };
+
+/// How deeply any expression may nest before parsing raises RecursionError.
+/// This also bounds everything that later recurses over the tree: eval,
+/// literal_eval and dropping it.
+const MAX_DEPTH: usize = if cfg!(debug_assertions) { 100 } else { 1000 };
+
+struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+    line: usize,
+    filename: &'a str,
+    /// Open brackets and the lines they were opened on.
+    brackets: Vec<(char, usize)>,
+    tokens: Vec<(Token, usize)>,
+}
+
+fn is_name_start(c: char) -> bool {
+    c == '_' || c.is_alphabetic()
+}
+
+fn is_name_char(c: char) -> bool {
+    c == '_' || c.is_alphanumeric()
+}
+
+impl<'a> Lexer<'a> {
+    fn error(&self, msg: &str) -> PyException {
+        syntax_error(msg, self.filename, self.line)
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.src[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn peek_at(&self, n: usize) -> Option<char> {
+        self.rest().chars().nth(n)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn push(&mut self, token: Token) {
+        self.tokens.push((token, self.line));
+    }
+
+    fn at_line_start(&self) -> bool {
+        matches!(self.tokens.last(), None | Some((Token::Newline, _)))
+    }
+
+    fn run(mut self, statements: bool) -> PyResult<Vec<(Token, usize)>> {
+        let mut line_start = true;
+        while let Some(c) = self.peek() {
+            if line_start && self.brackets.is_empty() {
+                line_start = false;
+                let indent = self.rest().len() - self.rest().trim_start_matches([' ', '\t']).len();
+                self.pos += indent;
+                let blank = matches!(self.peek(), None | Some('\n' | '\r' | '#'));
+                if indent > 0 && !blank && statements {
+                    return Err(PyException::new(
+                        "IndentationError",
+                        format!("unexpected indent ({}, line {})", self.filename, self.line),
+                    ));
+                }
+                continue;
+            }
+            match c {
+                ' ' | '\t' | '\x0c' | '\r' => {
+                    self.bump();
+                }
+                '#' => {
+                    while !matches!(self.peek(), None | Some('\n')) {
+                        self.bump();
+                    }
+                }
+                '\\' => {
+                    self.bump();
+                    match self.bump() {
+                        Some('\n') => self.line += 1,
+                        Some('\r') if self.peek() == Some('\n') => {
+                            self.bump();
+                            self.line += 1;
+                        }
+                        _ => {
+                            return Err(self
+                                .error("unexpected character after line continuation character"))
+                        }
+                    }
+                }
+                '\n' => {
+                    self.bump();
+                    if self.brackets.is_empty() {
+                        if !self.at_line_start() {
+                            self.push(Token::Newline);
+                        }
+                        line_start = true;
+                    }
+                    self.line += 1;
+                }
+                '0'..='9' => self.number()?,
+                '.' if self.peek_at(1).is_some_and(|d| d.is_ascii_digit()) => self.number()?,
+                '\'' | '"' => self.string("")?,
+                c if is_name_start(c) => {
+                    let start = self.pos;
+                    while self.peek().is_some_and(is_name_char) {
+                        self.bump();
+                    }
+                    let name = &self.src[start..self.pos];
+                    let prefix = name.to_ascii_lowercase();
+                    let is_prefix = matches!(
+                        prefix.as_str(),
+                        "r" | "u" | "b" | "br" | "rb" | "f" | "fr" | "rf"
+                    );
+                    if is_prefix && matches!(self.peek(), Some('\'' | '"')) {
+                        self.string(&prefix)?;
+                    } else {
+                        self.push(Token::Name(name.to_string()));
+                    }
+                }
+                _ => self.operator(c)?,
+            }
+        }
+        if let Some(&(open, line)) = self.brackets.last() {
+            self.line = line;
+            return Err(self.error(&format!("'{}' was never closed", open)));
+        }
+        if !self.at_line_start() {
+            self.push(Token::Newline);
+        }
+        self.push(Token::End);
+        Ok(self.tokens)
+    }
+
+    fn operator(&mut self, c: char) -> PyResult<()> {
+        let Some(op) = OPERATORS.iter().find(|op| self.rest().starts_with(**op)) else {
+            return Err(if c.is_ascii() {
+                self.error("invalid syntax")
+            } else {
+                self.error(&format!("invalid character '{}' (U+{:04X})", c, c as u32))
+            });
+        };
+        self.pos += op.len();
+        match c {
+            '(' | '[' | '{' if self.brackets.len() >= MAX_BRACKETS => {
+                return Err(self.error("too many nested parentheses"))
+            }
+            '(' | '[' | '{' => self.brackets.push((c, self.line)),
+            ')' | ']' | '}' => {
+                let expected = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                match self.brackets.pop() {
+                    None => return Err(self.error(&format!("unmatched '{}'", c))),
+                    Some((open, _)) if open != expected => {
+                        return Err(self.error(&format!(
+                            "closing parenthesis '{}' does not match opening parenthesis '{}'",
+                            c, open
+                        )))
+                    }
+                    Some(_) => {}
+                }
+            }
+            _ => {}
+        }
+        self.push(Token::Op(op));
+        Ok(())
+    }
+
+    fn digits(&mut self, radix: u32, kind: &str) -> PyResult<String> {
+        let mut digits = String::new();
+        loop {
+            match self.peek() {
+                Some(d) if d.is_digit(radix) => {
+                    digits.push(d);
+                    self.bump();
+                }
+                Some('_') if self.peek_at(1).is_some_and(|d| d.is_digit(radix)) => {
+                    self.bump();
+                }
+                Some('_') => return Err(self.error(&format!("invalid {} literal", kind))),
+                _ => return Ok(digits),
+            }
+        }
+    }
+
+    fn number(&mut self) -> PyResult<()> {
+        let radix = match (self.peek(), self.peek_at(1).map(|c| c.to_ascii_lowercase())) {
+            (Some('0'), Some('x')) => Some((16, "hexadecimal")),
+            (Some('0'), Some('o')) => Some((8, "octal")),
+            (Some('0'), Some('b')) => Some((2, "binary")),
+            _ => None,
+        };
+        if let Some((radix, kind)) = radix {
+            self.pos += 2;
+            if self.peek() == Some('_') {
+                self.bump();
+            }
+            let digits = self.digits(radix, kind)?;
+            if digits.is_empty() || self.peek().is_some_and(is_name_char) {
+                return Err(self.error(&format!("invalid {} literal", kind)));
+            }
+            let value = i64::from_str_radix(&digits, radix).map_err(|_| too_large())?;
+            self.push(Token::Int(value));
+            return Ok(());
+        }
+
+        let mut text = self.digits(10, "decimal")?;
+        let mut float = false;
+        if self.peek() == Some('.') {
+            self.bump();
+            float = true;
+            text.push('.');
+            text.push_str(&self.digits(10, "decimal")?);
+        }
+        if matches!(self.peek(), Some('e' | 'E'))
+            && (self.peek_at(1).is_some_and(|c| c.is_ascii_digit())
+                || (matches!(self.peek_at(1), Some('+' | '-'))
+                    && self.peek_at(2).is_some_and(|c| c.is_ascii_digit())))
+        {
+            float = true;
+            text.push('e');
+            self.bump();
+            if let Some(sign @ ('+' | '-')) = self.peek() {
+                text.push(sign);
+                self.bump();
+            }
+            text.push_str(&self.digits(10, "decimal")?);
+        }
+        match self.peek() {
+            Some('j' | 'J') => return Err(self.error("imaginary literals are not supported")),
+            Some(c) if is_name_char(c) => return Err(self.error("invalid decimal literal")),
+            _ => {}
+        }
+        if float {
+            let value = text
+                .parse()
+                .map_err(|_| self.error("invalid decimal literal"))?;
+            self.push(Token::Float(value));
+        } else {
+            if text.len() > 1 && text.starts_with('0') && text.bytes().any(|b| b != b'0') {
+                return Err(self.error(
+                    "leading zeros in decimal integer literals are not permitted; \
+                     use an 0o prefix for octal integers",
+                ));
+            }
+            let value = text.parse().map_err(|_| too_large())?;
+            self.push(Token::Int(value));
+        }
+        Ok(())
+    }
+
+    fn string(&mut self, prefix: &str) -> PyResult<()> {
+        let raw = prefix.contains('r');
+        let bytes = prefix.contains('b');
+        if prefix.contains('f') {
+            return Err(self.error("f-strings are not supported"));
+        }
+        let quote = self.bump().unwrap_or('"');
+        let triple = self.peek() == Some(quote) && self.peek_at(1) == Some(quote);
+        if triple {
+            self.pos += 2;
+        }
+        let start_line = self.line;
+        let mut value = String::new();
+        loop {
+            let Some(c) = self.bump() else {
+                self.line = start_line;
+                let kind = if triple { "triple-quoted " } else { "" };
+                return Err(self.error(&format!(
+                    "unterminated {}string literal (detected at line {})",
+                    kind, start_line
+                )));
+            };
+            match c {
+                c if c == quote
+                    && (!triple
+                        || (self.peek() == Some(quote) && self.peek_at(1) == Some(quote))) =>
+                {
+                    if triple {
+                        self.pos += 2;
+                    }
+                    break;
+                }
+                '\n' if !triple => {
+                    self.line = start_line;
+                    return Err(self.error(&format!(
+                        "unterminated string literal (detected at line {})",
+                        start_line
+                    )));
+                }
+                '\n' => {
+                    self.line += 1;
+                    value.push('\n');
+                }
+                '\\' if raw => {
+                    value.push('\\');
+                    if let Some(next) = self.bump() {
+                        if next == '\n' {
+                            self.line += 1;
+                        }
+                        value.push(next);
+                    }
+                }
+                '\\' => self.escape(&mut value, bytes)?,
+                c if bytes && !c.is_ascii() => {
+                    return Err(self.error("bytes can only contain ASCII literal characters"))
+                }
+                c => value.push(c),
+            }
+        }
+        if bytes {
+            self.push(Token::Bytes(value.chars().map(|c| c as u8).collect()));
+        } else {
+            self.push(Token::Str(value));
+        }
+        Ok(())
+    }
+
+    fn hex_escape(&mut self, len: usize, bytes: bool) -> PyResult<u32> {
+        let digits: String = self.rest().chars().take(len).collect();
+        if digits.len() != len || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            let codec = if bytes {
+                ""
+            } else {
+                "(unicode error) 'unicodeescape' codec can't decode bytes: "
+            };
+            let what = match len {
+                2 => "\\xXX",
+                4 => "\\uXXXX",
+                _ => "\\UXXXXXXXX",
+            };
+            return Err(self.error(&format!("{}truncated {} escape", codec, what)));
+        }
+        self.pos += len;
+        u32::from_str_radix(&digits, 16).map_err(|_| self.error("invalid escape"))
+    }
+
+    fn escape(&mut self, value: &mut String, bytes: bool) -> PyResult<()> {
+        let Some(c) = self.bump() else {
+            return Ok(());
+        };
+        let decoded = match c {
+            '\n' => {
+                self.line += 1;
+                return Ok(());
+            }
+            '\\' | '\'' | '"' => c,
+            'a' => '\x07',
+            'b' => '\x08',
+            'f' => '\x0c',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0b',
+            '0'..='7' => {
+                let mut code = c.to_digit(8).unwrap_or(0);
+                for _ in 0..2 {
+                    match self.peek().and_then(|d| d.to_digit(8)) {
+                        Some(d) => {
+                            code = code * 8 + d;
+                            self.bump();
+                        }
+                        None => break,
+                    }
+                }
+                if bytes {
+                    char::from((code & 0xff) as u8)
+                } else {
+                    char::from_u32(code).unwrap_or('\u{fffd}')
+                }
+            }
+            'x' => char::from_u32(self.hex_escape(2, bytes)?).unwrap_or('\u{fffd}'),
+            'u' | 'U' if !bytes => {
+                let code = self.hex_escape(if c == 'u' { 4 } else { 8 }, bytes)?;
+                char::from_u32(code).ok_or_else(|| {
+                    self.error("(unicode error) 'unicodeescape' codec can't decode bytes: illegal Unicode character")
+                })?
+            }
+            // Decoding these needs the Unicode name database, which is not
+            // carried; refuse rather than keep the escape as literal text.
+            'N' if !bytes => return Err(self.error("\\N{...} escapes are not supported")),
+            other => {
+                if bytes && !other.is_ascii() {
+                    return Err(self.error("bytes can only contain ASCII literal characters"));
+                }
+                value.push('\\');
+                other
+            }
+        };
+        value.push(decoded);
+        Ok(())
+    }
+}
+
+fn too_large() -> PyException {
+    PyException::new("OverflowError", "Python int too large to convert to C long")
+}
+
+fn tokenize(source: &str, filename: &str, statements: bool) -> PyResult<Vec<(Token, usize)>> {
+    Lexer {
+        src: source,
+        pos: 0,
+        line: 1,
+        filename,
+        brackets: Vec::new(),
+        tokens: Vec::new(),
+    }
+    .run(statements)
+}
+
+/// Positional and keyword arguments of a call.
+type CallArgs = (Vec<Expr>, Vec<(String, Expr)>);
+
+struct Parser<'a> {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+    filename: &'a str,
+    /// Nesting of the expression being parsed; see [`MAX_DEPTH`].
+    depth: usize,
+}
+
+fn is_keyword(name: &str) -> bool {
+    KEYWORDS.contains(&name)
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].0
+    }
+
+    fn peek_next(&self) -> &Token {
+        self.tokens
+            .get(self.pos + 1)
+            .map_or(&Token::End, |(token, _)| token)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens[self.pos].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.pos].0.clone();
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    fn error(&self, msg: &str) -> PyException {
+        syntax_error(msg, self.filename, self.line())
+    }
+
+    fn unsupported(&self, what: &str) -> PyException {
+        self.error(&format!("{} are not supported", what))
+    }
+
+    fn is_op(&self, op: &str) -> bool {
+        matches!(self.peek(), Token::Op(o) if *o == op)
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Name(n) if n == keyword)
+    }
+
+    fn eat_op(&mut self, op: &str) -> bool {
+        let found = self.is_op(op);
+        if found {
+            self.advance();
+        }
+        found
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.is_keyword(keyword);
+        if found {
+            self.advance();
+        }
+        found
+    }
+
+    fn expect_op(&mut self, op: &str) -> PyResult<()> {
+        if self.eat_op(op) {
+            Ok(())
+        } else {
+            Err(self.error("invalid syntax"))
+        }
+    }
+
+    /// Enters one more level of expression nesting. Callers step back out
+    /// with `self.depth -= 1`, or not at all when parsing fails.
+    fn descend(&mut self) -> PyResult<()> {
+        if self.depth >= MAX_DEPTH {
+            return Err(PyException::new(
+                "RecursionError",
+                "maximum recursion depth exceeded during compilation",
+            ));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn node(&self, kind: ExprKind, lineno: usize) -> Expr {
+        Expr { kind, lineno }
+    }
+
+    /// Whether the next token can begin an expression.
+    fn starts_expression(&self) -> bool {
+        match self.peek() {
+            Token::Name(n) => {
+                !is_keyword(n)
+                    || matches!(
+                        n.as_str(),
+                        "None" | "True" | "False" | "not" | "lambda" | "await"
+                    )
+            }
+            Token::Op(op) => matches!(*op, "(" | "[" | "{" | "-" | "+" | "~" | "*" | "..."),
+            Token::Newline | Token::End => false,
+            _ => true,
+        }
+    }
+
+    /// `testlist`: one expression, or a tuple of several without brackets.
+    fn testlist(&mut self) -> PyResult<Expr> {
+        let lineno = self.line();
+        let first = self.test()?;
+        if !self.is_op(",") {
+            return Ok(first);
+        }
+        let mut items = Vec::from([first]);
+        while self.eat_op(",") {
+            if !self.starts_expression() {
+                break;
+            }
+            items.push(self.test()?);
+        }
+        Ok(self.node(ExprKind::Tuple(items), lineno))
+    }
+
+    fn test(&mut self) -> PyResult<Expr> {
+        self.descend()?;
+        let expr = self.conditional();
+        self.depth -= 1;
+        expr
+    }
+
+    /// `or_test ['if' or_test 'else' test]`.
+    fn conditional(&mut self) -> PyResult<Expr> {
+        if self.is_keyword("lambda") {
+            return Err(self.unsupported("lambda expressions"));
+        }
+        if self.is_op("*") {
+            return Err(self.unsupported("starred expressions"));
+        }
+        let lineno = self.line();
+        let body = self.or_test()?;
+        if self.is_op(":=") {
+            return Err(self.unsupported("assignment expressions"));
+        }
+        if !self.eat_keyword("if") {
+            return Ok(body);
+        }
+        let test = self.or_test()?;
+        if !self.eat_keyword("else") {
+            return Err(self.error("expected 'else' after 'if' expression"));
+        }
+        let orelse = self.test()?;
+        Ok(self.node(
+            ExprKind::IfExp {
+                test: Box::new(test),
+                body: Box::new(body),
+                orelse: Box::new(orelse),
+            },
+            lineno,
+        ))
+    }
+
+    fn bool_op(&mut self, keyword: &str, op: BoolOp) -> PyResult<Expr> {
+        let lineno = self.line();
+        let first = match op {
+            BoolOp::Or => self.bool_op("and", BoolOp::And)?,
+            BoolOp::And => self.not_test()?,
+        };
+        if !self.is_keyword(keyword) {
+            return Ok(first);
+        }
+        let mut values = Vec::from([first]);
+        while self.eat_keyword(keyword) {
+            values.push(match op {
+                BoolOp::Or => self.bool_op("and", BoolOp::And)?,
+                BoolOp::And => self.not_test()?,
+            });
+        }
+        Ok(self.node(ExprKind::BoolOp(op, values), lineno))
+    }
+
+    fn or_test(&mut self) -> PyResult<Expr> {
+        self.bool_op("or", BoolOp::Or)
+    }
+
+    fn not_test(&mut self) -> PyResult<Expr> {
+        let lineno = self.line();
+        if self.eat_keyword("not") {
+            self.descend()?;
+            let operand = self.not_test()?;
+            self.depth -= 1;
+            return Ok(self.node(ExprKind::UnaryOp(UnaryOp::Not, Box::new(operand)), lineno));
+        }
+        self.comparison()
+    }
+
+    fn comp_op(&mut self) -> Option<CmpOp> {
+        let op = match self.peek() {
+            Token::Op("==") => CmpOp::Eq,
+            Token::Op("!=") => CmpOp::NotEq,
+            Token::Op("<") => CmpOp::Lt,
+            Token::Op("<=") => CmpOp::LtE,
+            Token::Op(">") => CmpOp::Gt,
+            Token::Op(">=") => CmpOp::GtE,
+            Token::Name(n) if n == "in" => CmpOp::In,
+            Token::Name(n) if n == "is" => {
+                self.advance();
+                return Some(if self.eat_keyword("not") {
+                    CmpOp::IsNot
+                } else {
+                    CmpOp::Is
+                });
+            }
+            Token::Name(n)
+                if n == "not" && matches!(self.peek_next(), Token::Name(m) if m == "in") =>
+            {
+                self.advance();
+                CmpOp::NotIn
+            }
+            _ => return None,
+        };
+        self.advance();
+        Some(op)
+    }
+
+    fn comparison(&mut self) -> PyResult<Expr> {
+        let lineno = self.line();
+        let left = self.binary(0)?;
+        let mut comparators = Vec::new();
+        while let Some(op) = self.comp_op() {
+            comparators.push((op, self.binary(0)?));
+        }
+        if comparators.is_empty() {
+            return Ok(left);
+        }
+        Ok(self.node(ExprKind::Compare(Box::new(left), comparators), lineno))
+    }
+
+    /// The left-associative binary operators at precedence `level` and
+    /// tighter, by precedence climbing so an operand costs one frame rather
+    /// than one per level.
+    fn binary(&mut self, level: usize) -> PyResult<Expr> {
+        const LEVELS: &[&[&str]] = &[
+            &["|"],
+            &["^"],
+            &["&"],
+            &["<<", ">>"],
+            &["+", "-"],
+            &["*", "@", "/", "//", "%"],
+        ];
+        let lineno = self.line();
+        let depth = self.depth;
+        let mut left = self.factor()?;
+        loop {
+            let found = match self.peek() {
+                Token::Op(op) => LEVELS
+                    .iter()
+                    .position(|ops| ops.contains(op))
+                    .filter(|&found| found >= level)
+                    .zip(BinOp::from_symbol(op)),
+                _ => None,
+            };
+            let Some((found, op)) = found else {
+                self.depth = depth;
+                return Ok(left);
+            };
+            self.advance();
+            self.descend()?;
+            let right = self.binary(found + 1)?;
+            left = self.node(ExprKind::BinOp(Box::new(left), op, Box::new(right)), lineno);
+        }
+    }
+
+    fn factor(&mut self) -> PyResult<Expr> {
+        let lineno = self.line();
+        let op = match self.peek() {
+            Token::Op("+") => UnaryOp::UAdd,
+            Token::Op("-") => UnaryOp::USub,
+            Token::Op("~") => UnaryOp::Invert,
+            _ => return self.power(),
+        };
+        self.advance();
+        self.descend()?;
+        let operand = self.factor()?;
+        self.depth -= 1;
+        Ok(self.node(ExprKind::UnaryOp(op, Box::new(operand)), lineno))
+    }
+
+    fn power(&mut self) -> PyResult<Expr> {
+        let lineno = self.line();
+        if self.is_keyword("await") {
+            return Err(self.unsupported("await expressions"));
+        }
+        let base = self.primary()?;
+        if !self.eat_op("**") {
+            return Ok(base);
+        }
+        self.descend()?;
+        let exponent = self.factor()?;
+        self.depth -= 1;
+        Ok(self.node(
+            ExprKind::BinOp(Box::new(base), BinOp::Pow, Box::new(exponent)),
+            lineno,
+        ))
+    }
+
+    fn primary(&mut self) -> PyResult<Expr> {
+        let depth = self.depth;
+        let mut expr = self.atom()?;
+        loop {
+            let lineno = expr.lineno;
+            if self.is_op(".") || self.is_op("[") || self.is_op("(") {
+                self.descend()?;
+            }
+            if self.eat_op(".") {
+                match self.advance() {
+                    Token::Name(attr) if !is_keyword(&attr) => {
+                        expr = self.node(ExprKind::Attribute(Box::new(expr), attr), lineno);
+                    }
+                    _ => return Err(self.error("invalid syntax")),
+                }
+            } else if self.eat_op("[") {
+                let index = self.subscript_list()?;
+                self.expect_op("]")?;
+                expr = self.node(ExprKind::Subscript(Box::new(expr), Box::new(index)), lineno);
+            } else if self.eat_op("(") {
+                let (args, keywords) = self.call_args()?;
+                expr = self.node(
+                    ExprKind::Call {
+                        func: Box::new(expr),
+                        args,
+                        keywords,
+                    },
+                    lineno,
+                );
+            } else {
+                self.depth = depth;
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn call_args(&mut self) -> PyResult<CallArgs> {
+        let mut args = Vec::new();
+        let mut keywords: Vec<(String, Expr)> = Vec::new();
+        while !self.eat_op(")") {
+            if self.is_op("*") || self.is_op("**") {
+                return Err(self.unsupported("argument unpacking"));
+            }
+            let keyword = match (self.peek(), self.peek_next()) {
+                (Token::Name(name), Token::Op("=")) if !is_keyword(name) => Some(name.clone()),
+                _ => None,
+            };
+            if let Some(name) = keyword {
+                self.pos += 2;
+                if keywords.iter().any(|(known, _)| *known == name) {
+                    return Err(self.error(&format!("keyword argument repeated: {}", name)));
+                }
+                keywords.push((name, self.test()?));
+            } else {
+                if !keywords.is_empty() {
+                    return Err(self.error("positional argument follows keyword argument"));
+                }
+                args.push(self.test()?);
+                if self.is_keyword("for") {
+                    return Err(self.unsupported("generator expressions"));
+                }
+            }
+            if !self.eat_op(",") {
+                self.expect_op(")")?;
+                break;
+            }
+        }
+        Ok((args, keywords))
+    }
+
+    fn subscript_list(&mut self) -> PyResult<Expr> {
+        let lineno = self.line();
+        let first = self.subscript()?;
+        if !self.is_op(",") {
+            return Ok(first);
+        }
+        let mut items = Vec::from([first]);
+        while self.eat_op(",") {
+            if self.is_op("]") {
+                break;
+            }
+            items.push(self.subscript()?);
+        }
+        Ok(self.node(ExprKind::Tuple(items), lineno))
+    }
+
+    fn subscript(&mut self) -> PyResult<Expr> {
+        let lineno = self.line();
+        let bound = |parser: &mut Self| -> PyResult<Option<Box<Expr>>> {
+            if parser.is_op(":") || parser.is_op("]") || parser.is_op(",") {
+                Ok(None)
+            } else {
+                parser.test().map(|e| Some(Box::new(e)))
+            }
+        };
+        let lower = bound(self)?;
+        if !self.eat_op(":") {
+            return lower
+                .map(|e| *e)
+                .ok_or_else(|| self.error("invalid syntax"));
+        }
+        let upper = bound(self)?;
+        let step = if self.eat_op(":") { bound(self)? } else { None };
+        Ok(self.node(ExprKind::Slice { lower, upper, step }, lineno))
+    }
+
+    /// Items of a bracketed display up to `close`, and whether a comma
+    /// separated them.
+    fn display_items(&mut self, close: &str) -> PyResult<(Vec<Expr>, bool)> {
+        let mut items = Vec::new();
+        let mut comma = false;
+        while !self.eat_op(close) {
+            items.push(self.test()?);
+            if self.is_keyword("for") {
+                return Err(self.unsupported("comprehensions"));
+            }
+            if self.eat_op(",") {
+                comma = true;
+            } else {
+                self.expect_op(close)?;
+                break;
+            }
+        }
+        Ok((items, comma))
+    }
+
+    fn atom(&mut self) -> PyResult<Expr> {
+        let lineno = self.line();
+        let kind = match self.advance() {
+            Token::Int(i) => ExprKind::Constant(PyValue::Int(i)),
+            Token::Float(f) => ExprKind::Constant(PyValue::Float(f)),
+            Token::Str(s) => {
+                let mut s = s;
+                loop {
+                    match self.peek() {
+                        Token::Str(more) => {
+                            s.push_str(more);
+                            self.advance();
+                        }
+                        Token::Bytes(_) => {
+                            return Err(self.error("cannot mix bytes and nonbytes literals"))
+                        }
+                        _ => break,
+                    }
+                }
+                ExprKind::Constant(PyValue::Str(PyStr::new(s)))
+            }
+            Token::Bytes(b) => {
+                let mut b = b;
+                loop {
+                    match self.peek() {
+                        Token::Bytes(more) => {
+                            b.extend_from_slice(more);
+                            self.advance();
+                        }
+                        Token::Str(_) => {
+                            return Err(self.error("cannot mix bytes and nonbytes literals"))
+                        }
+                        _ => break,
+                    }
+                }
+                ExprKind::Constant(PyValue::Bytes(PyBytes::new(b)))
+            }
+            Token::Name(name) => match name.as_str() {
+                "None" => ExprKind::Constant(PyValue::None),
+                "True" => ExprKind::Constant(PyValue::Bool(true)),
+                "False" => ExprKind::Constant(PyValue::Bool(false)),
+                "yield" => return Err(self.unsupported("yield expressions")),
+                name if is_keyword(name) => {
+                    self.pos -= 1;
+                    return Err(self.error("invalid syntax"));
+                }
+                _ => ExprKind::Name(name),
+            },
+            Token::Op("(") => {
+                if self.eat_op(")") {
+                    ExprKind::Tuple(Vec::new())
+                } else {
+                    let first = self.test()?;
+                    if self.is_keyword("for") {
+                        return Err(self.unsupported("generator expressions"));
+                    }
+                    if self.eat_op(")") {
+                        return Ok(first);
+                    }
+                    self.expect_op(",")?;
+                    let (mut rest, _) = self.display_items(")")?;
+                    rest.insert(0, first);
+                    ExprKind::Tuple(rest)
+                }
+            }
+            Token::Op("[") => ExprKind::List(self.display_items("]")?.0),
+            Token::Op("{") => self.brace_display()?,
+            Token::Op("...") => return Err(self.unsupported("Ellipsis literals")),
+            _ => {
+                self.pos = self.pos.saturating_sub(1);
+                return Err(self.error("invalid syntax"));
+            }
+        };
+        Ok(self.node(kind, lineno))
+    }
+
+    fn brace_display(&mut self) -> PyResult<ExprKind> {
+        if self.eat_op("}") {
+            return Ok(ExprKind::Dict(Vec::new()));
+        }
+        if self.is_op("**") {
+            return Err(self.unsupported("dict unpacking"));
+        }
+        let first = self.test()?;
+        if !self.eat_op(":") {
+            if self.is_keyword("for") {
+                return Err(self.unsupported("comprehensions"));
+            }
+            if !self.eat_op(",") {
+                self.expect_op("}")?;
+                return Ok(ExprKind::Set(Vec::from([first])));
+            }
+            let (mut rest, _) = self.display_items("}")?;
+            rest.insert(0, first);
+            return Ok(ExprKind::Set(rest));
+        }
+        let mut pairs = Vec::from([(first, self.test()?)]);
+        loop {
+            if self.is_keyword("for") {
+                return Err(self.unsupported("comprehensions"));
+            }
+            if self.eat_op("}") {
+                return Ok(ExprKind::Dict(pairs));
+            }
+            self.expect_op(",")?;
+            if self.eat_op("}") {
+                return Ok(ExprKind::Dict(pairs));
+            }
+            if self.is_op("**") {
+                return Err(self.unsupported("dict unpacking"));
+            }
+            let key = self.test()?;
+            self.expect_op(":")?;
+            pairs.push((key, self.test()?));
+        }
+    }
+
+    fn expression_input(&mut self) -> PyResult<Expr> {
+        if matches!(self.peek(), Token::End) {
+            return Err(syntax_error("invalid syntax", self.filename, 0));
+        }
+        let expr = self.testlist()?;
+        while matches!(self.peek(), Token::Newline) {
+            self.advance();
+        }
+        if !matches!(self.peek(), Token::End) {
+            return Err(self.error("invalid syntax"));
+        }
+        Ok(expr)
+    }
+
+    fn check_target(&self, target: &Expr, augmented: bool) -> PyResult<()> {
+        let what = match &target.kind {
+            ExprKind::Name(_) | ExprKind::Subscript(..) => return Ok(()),
+            ExprKind::Tuple(items) | ExprKind::List(items) if !augmented => {
+                return items
+                    .iter()
+                    .try_for_each(|item| self.check_target(item, false))
+            }
+            ExprKind::Attribute(..) => return Err(self.unsupported("attribute assignments")),
+            ExprKind::Tuple(_) | ExprKind::List(_) => {
+                let kind = if matches!(target.kind, ExprKind::Tuple(_)) {
+                    "tuple"
+                } else {
+                    "list"
+                };
+                return Err(syntax_error(
+                    &format!(
+                        "'{}' is an illegal expression for augmented assignment",
+                        kind
+                    ),
+                    self.filename,
+                    target.lineno,
+                ));
+            }
+            ExprKind::Constant(_) => "literal",
+            ExprKind::Call { .. } => "function call",
+            ExprKind::Compare(..) => "comparison",
+            ExprKind::BoolOp(..) | ExprKind::BinOp(..) | ExprKind::UnaryOp(..) => "expression",
+            ExprKind::IfExp { .. } => "conditional expression",
+            ExprKind::Set(_) => "set display",
+            ExprKind::Dict(_) => "dict literal",
+            ExprKind::Slice { .. } => "slice",
+        };
+        Err(syntax_error(
+            &format!("cannot assign to {}", what),
+            self.filename,
+            target.lineno,
+        ))
+    }
+
+    fn simple_statement(&mut self) -> PyResult<Stmt> {
+        if let Token::Name(name) = self.peek() {
+            match name.as_str() {
+                "pass" => {
+                    self.advance();
+                    return Ok(Stmt::Pass);
+                }
+                "del" => {
+                    self.advance();
+                    let targets = match self.testlist()? {
+                        Expr {
+                            kind: ExprKind::Tuple(items),
+                            ..
+                        } => items,
+                        target => Vec::from([target]),
+                    };
+                    for target in &targets {
+                        self.check_target(target, false)?;
+                    }
+                    return Ok(Stmt::Delete(targets));
+                }
+                "if" | "for" | "while" | "def" | "class" | "with" | "try" | "async" => {
+                    return Err(self.unsupported(&format!("'{}' statements", name)))
+                }
+                "import" | "from" | "return" | "raise" | "assert" | "global" | "nonlocal"
+                | "break" | "continue" => {
+                    return Err(self.unsupported(&format!("'{}' statements", name)))
+                }
+                _ => {}
+            }
+        }
+        let first = self.testlist()?;
+        if let Token::Op(op) = self.peek() {
+            if let Some(bin) = op.strip_suffix('=').and_then(BinOp::from_symbol) {
+                self.check_target(&first, true)?;
+                self.advance();
+                return Ok(Stmt::AugAssign(first, bin, self.testlist()?));
+            }
+        }
+        if !self.is_op("=") {
+            return Ok(Stmt::Expr(first));
+        }
+        let mut exprs = Vec::from([first]);
+        while self.eat_op("=") {
+            exprs.push(self.testlist()?);
+        }
+        let value = exprs.pop().unwrap_or_else(|| unreachable!());
+        for target in &exprs {
+            self.check_target(target, false)?;
+        }
+        Ok(Stmt::Assign(exprs, value))
+    }
+
+    fn statements(&mut self) -> PyResult<Vec<Stmt>> {
+        let mut body = Vec::new();
+        while !matches!(self.peek(), Token::End) {
+            body.push(self.simple_statement()?);
+            if self.eat_op(";") && !matches!(self.peek(), Token::Newline | Token::End) {
+                continue;
+            }
+            match self.advance() {
+                Token::Newline | Token::End => {}
+                _ => {
+                    self.pos -= 1;
+                    return Err(self.error("invalid syntax"));
+                }
+            }
+        }
+        Ok(body)
+    }
+}
+
+fn parser<'a>(source: &str, filename: &'a str, statements: bool) -> PyResult<Parser<'a>> {
+    Ok(Parser {
+        tokens: tokenize(source, filename, statements)?,
+        pos: 0,
+        filename,
+        depth: 0,
+    })
+}
+
+/// Parses `source` in eval mode, naming `filename` in SyntaxErrors.
+pub fn parse_expression_in(source: &str, filename: &str) -> PyResult<Expr> {
+    parser(source, filename, false)?.expression_input()
+}
+
+/// Parses `source` in exec mode, naming `filename` in SyntaxErrors.
+pub fn parse_in(source: &str, filename: &str) -> PyResult<Vec<Stmt>> {
+    parser(source, filename, true)?.statements()
+}
+
+/// Python-equivalent ast.parse(source, mode='eval').body.
+pub fn parse_expression(source: &str) -> PyResult<Expr> {
+    parse_expression_in(source, "<unknown>")
+}
+
+/// Python-equivalent ast.parse(source).body, for simple statements.
+pub fn parse(source: &str) -> PyResult<Vec<Stmt>> {
+    parse_in(source, "<unknown>")
+}
+
+fn malformed(node: &Expr) -> PyException {
+    PyException::value_error(format!(
+        "malformed node or string on line {}: <ast.{} object at {:#x}>",
+        node.lineno,
+        node.node_name(),
+        node as *const Expr as usize
+    ))
+}
+
+/// TypeError for a dict key or set element Python cannot hash.
+pub(crate) fn check_hashable(value: &PyValue) -> PyResult<()> {
+    value.py_hash().map(|_| ())
+}
+
+fn convert_num(node: &Expr) -> PyResult<PyValue> {
+    match &node.kind {
+        ExprKind::Constant(value @ (PyValue::Int(_) | PyValue::Float(_))) => Ok(value.clone()),
+        _ => Err(malformed(node)),
+    }
+}
+
+fn convert_signed_num(node: &Expr) -> PyResult<PyValue> {
+    match &node.kind {
+        ExprKind::UnaryOp(op @ (UnaryOp::UAdd | UnaryOp::USub), operand) => {
+            match (op, convert_num(operand)?) {
+                (UnaryOp::USub, PyValue::Int(i)) => Ok(PyValue::Int(-i)),
+                (UnaryOp::USub, PyValue::Float(f)) => Ok(PyValue::Float(-f)),
+                (_, value) => Ok(value),
+            }
+        }
+        _ => convert_num(node),
+    }
+}
+
+/// Python-equivalent ast.literal_eval(node): the value of a tree of
+/// literals, raising ValueError for anything else.
+pub fn literal_eval_node(node: &Expr) -> PyResult<PyValue> {
+    match &node.kind {
+        ExprKind::Constant(value) => Ok(value.clone()),
+        ExprKind::Tuple(items) => items
+            .iter()
+            .map(literal_eval_node)
+            .collect::<PyResult<Vec<_>>>()
+            .map(|items| PyValue::Tuple(PyTuple::from_vec(items))),
+        ExprKind::List(items) => items
+            .iter()
+            .map(literal_eval_node)
+            .collect::<PyResult<Vec<_>>>()
+            .map(|items| PyValue::List(PyList::from_vec(items))),
+        ExprKind::Set(items) => {
+            let mut set = PySet::new();
+            for item in items {
+                let value = literal_eval_node(item)?;
+                check_hashable(&value)?;
+                set.add(value);
+            }
+            Ok(PyValue::Set(set))
+        }
+        ExprKind::Call {
+            func,
+            args,
+            keywords,
+        } if matches!(&func.kind, ExprKind::Name(name) if name == "set")
+            && args.is_empty()
+            && keywords.is_empty() =>
+        {
+            Ok(PyValue::Set(PySet::new()))
+        }
+        ExprKind::Dict(pairs) => {
+            let keys = pairs
+                .iter()
+                .map(|(key, _)| literal_eval_node(key))
+                .collect::<PyResult<Vec<_>>>()?;
+            let values = pairs
+                .iter()
+                .map(|(_, value)| literal_eval_node(value))
+                .collect::<PyResult<Vec<_>>>()?;
+            let mut dict = PyDictionary::new();
+            for (key, value) in keys.into_iter().zip(values) {
+                check_hashable(&key)?;
+                dict.set_item(key, value);
+            }
+            Ok(PyValue::Dict(dict))
+        }
+        ExprKind::BinOp(left, BinOp::Add | BinOp::Sub, right) => {
+            // CPython allows only `real +/- imaginary` here, and there are
+            // no complex numbers to build.
+            convert_signed_num(left)?;
+            convert_num(right)?;
+            Err(malformed(node))
+        }
+        _ => convert_signed_num(node),
+    }
+}
+
+/// Python-equivalent ast.literal_eval(node_or_string) for a string.
+pub fn literal_eval(source: &str) -> PyResult<PyValue> {
+    let expr = parse_expression(source.trim_start_matches([' ', '\t']))?;
+    literal_eval_node(&expr)
+}
+
+/// A node to dump or walk: a whole module, one statement, or an
+/// expression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Node<'a> {
+    Module(&'a [Stmt]),
+    Stmt(&'a Stmt),
+    Expr(&'a Expr),
+}
+
+impl<'a> From<&'a [Stmt]> for Node<'a> {
+    fn from(body: &'a [Stmt]) -> Self {
+        Node::Module(body)
+    }
+}
+
+impl<'a> From<&'a Vec<Stmt>> for Node<'a> {
+    fn from(body: &'a Vec<Stmt>) -> Self {
+        Node::Module(body)
+    }
+}
+
+impl<'a> From<&'a Stmt> for Node<'a> {
+    fn from(stmt: &'a Stmt) -> Self {
+        Node::Stmt(stmt)
+    }
+}
+
+impl<'a> From<&'a Expr> for Node<'a> {
+    fn from(expr: &'a Expr) -> Self {
+        Node::Expr(expr)
+    }
+}
+
+impl Node<'_> {
+    /// The node's ast class name.
+    pub fn node_name(&self) -> &'static str {
+        match self {
+            Node::Module(_) => "Module",
+            Node::Stmt(Stmt::Expr(_)) => "Expr",
+            Node::Stmt(Stmt::Assign(..)) => "Assign",
+            Node::Stmt(Stmt::AugAssign(..)) => "AugAssign",
+            Node::Stmt(Stmt::Delete(_)) => "Delete",
+            Node::Stmt(Stmt::Pass) => "Pass",
+            Node::Expr(expr) => expr.node_name(),
+        }
+    }
+}
+
+/// Python-equivalent ast.iter_child_nodes(node), in field order. Operator
+/// and context nodes carry no data here and are left out.
+pub fn iter_child_nodes<'a>(node: impl Into<Node<'a>>) -> Vec<Node<'a>> {
+    let mut children = Vec::new();
+    match node.into() {
+        Node::Module(body) => children.extend(body.iter().map(Node::Stmt)),
+        Node::Stmt(stmt) => match stmt {
+            Stmt::Expr(value) => children.push(Node::Expr(value)),
+            Stmt::Assign(targets, value) => {
+                children.extend(targets.iter().map(Node::Expr));
+                children.push(Node::Expr(value));
+            }
+            Stmt::AugAssign(target, _, value) => {
+                children.extend([Node::Expr(target), Node::Expr(value)]);
+            }
+            Stmt::Delete(targets) => children.extend(targets.iter().map(Node::Expr)),
+            Stmt::Pass => {}
+        },
+        Node::Expr(expr) => match &expr.kind {
+            ExprKind::Constant(_) | ExprKind::Name(_) => {}
+            ExprKind::Tuple(items) | ExprKind::List(items) | ExprKind::Set(items) => {
+                children.extend(items.iter().map(Node::Expr));
+            }
+            ExprKind::Dict(pairs) => {
+                children.extend(pairs.iter().map(|(key, _)| Node::Expr(key)));
+                children.extend(pairs.iter().map(|(_, value)| Node::Expr(value)));
+            }
+            ExprKind::UnaryOp(_, operand) => children.push(Node::Expr(operand)),
+            ExprKind::BinOp(left, _, right) => {
+                children.extend([Node::Expr(left), Node::Expr(right)]);
+            }
+            ExprKind::BoolOp(_, values) => children.extend(values.iter().map(Node::Expr)),
+            ExprKind::Compare(left, comparators) => {
+                children.push(Node::Expr(left));
+                children.extend(comparators.iter().map(|(_, right)| Node::Expr(right)));
+            }
+            ExprKind::IfExp { test, body, orelse } => {
+                children.extend([Node::Expr(test), Node::Expr(body), Node::Expr(orelse)]);
+            }
+            ExprKind::Subscript(value, index) => {
+                children.extend([Node::Expr(value), Node::Expr(index)]);
+            }
+            ExprKind::Slice { lower, upper, step } => {
+                children.extend(
+                    [lower, upper, step]
+                        .into_iter()
+                        .flatten()
+                        .map(|bound| Node::Expr(bound)),
+                );
+            }
+            ExprKind::Attribute(value, _) => children.push(Node::Expr(value)),
+            ExprKind::Call {
+                func,
+                args,
+                keywords,
+            } => {
+                children.push(Node::Expr(func));
+                children.extend(args.iter().map(Node::Expr));
+                children.extend(keywords.iter().map(|(_, value)| Node::Expr(value)));
+            }
+        },
+    }
+    children
+}
+
+/// Python-equivalent ast.walk(node): the node and its descendants,
+/// breadth first.
+pub fn walk<'a>(node: impl Into<Node<'a>>) -> Vec<Node<'a>> {
+    let mut nodes = Vec::from([node.into()]);
+    let mut next = 0;
+    while let Some(&node) = nodes.get(next) {
+        nodes.extend(iter_child_nodes(node));
+        next += 1;
+    }
+    nodes
+}
+
+/// Expression context, as ast.dump() shows it.
+#[derive(Clone, Copy)]
+enum Ctx {
+    Load,
+    Store,
+    Del,
+}
+
+impl Ctx {
+    fn dumped(self) -> Dumped {
+        let name = match self {
+            Ctx::Load => "Load",
+            Ctx::Store => "Store",
+            Ctx::Del => "Del",
+        };
+        Dumped::Node(name, Vec::new())
+    }
+}
+
+/// A node reduced to what ast.dump() prints. A field of `None` is an
+/// optional field left unset, which dump() omits.
+enum Dumped {
+    Node(&'static str, Vec<(&'static str, Option<Dumped>)>),
+    List(Vec<Dumped>),
+    Repr(String),
+}
+
+fn op_node(name: &'static str) -> Dumped {
+    Dumped::Node(name, Vec::new())
+}
+
+fn unary_name(op: UnaryOp) -> &'static str {
+    match op {
+        UnaryOp::UAdd => "UAdd",
+        UnaryOp::USub => "USub",
+        UnaryOp::Invert => "Invert",
+        UnaryOp::Not => "Not",
+    }
+}
+
+fn binop_name(op: BinOp) -> &'static str {
+    match op {
+        BinOp::Add => "Add",
+        BinOp::Sub => "Sub",
+        BinOp::Mult => "Mult",
+        BinOp::MatMult => "MatMult",
+        BinOp::Div => "Div",
+        BinOp::FloorDiv => "FloorDiv",
+        BinOp::Mod => "Mod",
+        BinOp::Pow => "Pow",
+        BinOp::LShift => "LShift",
+        BinOp::RShift => "RShift",
+        BinOp::BitOr => "BitOr",
+        BinOp::BitXor => "BitXor",
+        BinOp::BitAnd => "BitAnd",
+    }
+}
+
+fn cmpop_name(op: CmpOp) -> &'static str {
+    match op {
+        CmpOp::Eq => "Eq",
+        CmpOp::NotEq => "NotEq",
+        CmpOp::Lt => "Lt",
+        CmpOp::LtE => "LtE",
+        CmpOp::Gt => "Gt",
+        CmpOp::GtE => "GtE",
+        CmpOp::Is => "Is",
+        CmpOp::IsNot => "IsNot",
+        CmpOp::In => "In",
+        CmpOp::NotIn => "NotIn",
+    }
+}
+
+fn dump_exprs(items: &[Expr], ctx: Ctx) -> Dumped {
+    Dumped::List(items.iter().map(|item| dump_expr(item, ctx)).collect())
+}
+
+fn dump_expr(expr: &Expr, ctx: Ctx) -> Dumped {
+    let load = |e: &Expr| Some(dump_expr(e, Ctx::Load));
+    let (name, fields) = match &expr.kind {
+        ExprKind::Constant(value) => (
+            "Constant",
+            Vec::from([("value", Some(Dumped::Repr(repr(value)))), ("kind", None)]),
+        ),
+        ExprKind::Name(id) => (
+            "Name",
+            Vec::from([
+                ("id", Some(Dumped::Repr(repr_str(id)))),
+                ("ctx", Some(ctx.dumped())),
+            ]),
+        ),
+        ExprKind::Tuple(items) | ExprKind::List(items) => (
+            expr.node_name(),
+            Vec::from([
+                ("elts", Some(dump_exprs(items, ctx))),
+                ("ctx", Some(ctx.dumped())),
+            ]),
+        ),
+        ExprKind::Set(items) => ("Set", Vec::from([("elts", Some(dump_exprs(items, ctx)))])),
+        ExprKind::Dict(pairs) => (
+            "Dict",
+            Vec::from([
+                (
+                    "keys",
+                    Some(Dumped::List(
+                        pairs.iter().map(|(k, _)| dump_expr(k, Ctx::Load)).collect(),
+                    )),
+                ),
+                (
+                    "values",
+                    Some(Dumped::List(
+                        pairs.iter().map(|(_, v)| dump_expr(v, Ctx::Load)).collect(),
+                    )),
+                ),
+            ]),
+        ),
+        ExprKind::UnaryOp(op, operand) => (
+            "UnaryOp",
+            Vec::from([
+                ("op", Some(op_node(unary_name(*op)))),
+                ("operand", load(operand)),
+            ]),
+        ),
+        ExprKind::BinOp(left, op, right) => (
+            "BinOp",
+            Vec::from([
+                ("left", load(left)),
+                ("op", Some(op_node(binop_name(*op)))),
+                ("right", load(right)),
+            ]),
+        ),
+        ExprKind::BoolOp(op, values) => (
+            "BoolOp",
+            Vec::from([
+                (
+                    "op",
+                    Some(op_node(match op {
+                        BoolOp::And => "And",
+                        BoolOp::Or => "Or",
+                    })),
+                ),
+                ("values", Some(dump_exprs(values, Ctx::Load))),
+            ]),
+        ),
+        ExprKind::Compare(left, comparators) => (
+            "Compare",
+            Vec::from([
+                ("left", load(left)),
+                (
+                    "ops",
+                    Some(Dumped::List(
+                        comparators
+                            .iter()
+                            .map(|(op, _)| op_node(cmpop_name(*op)))
+                            .collect(),
+                    )),
+                ),
+                (
+                    "comparators",
+                    Some(Dumped::List(
+                        comparators
+                            .iter()
+                            .map(|(_, e)| dump_expr(e, Ctx::Load))
+                            .collect(),
+                    )),
+                ),
+            ]),
+        ),
+        ExprKind::IfExp { test, body, orelse } => (
+            "IfExp",
+            Vec::from([
+                ("test", load(test)),
+                ("body", load(body)),
+                ("orelse", load(orelse)),
+            ]),
+        ),
+        ExprKind::Subscript(value, index) => (
+            "Subscript",
+            Vec::from([
+                ("value", load(value)),
+                ("slice", load(index)),
+                ("ctx", Some(ctx.dumped())),
+            ]),
+        ),
+        ExprKind::Slice { lower, upper, step } => (
+            "Slice",
+            Vec::from([
+                ("lower", lower.as_deref().and_then(load)),
+                ("upper", upper.as_deref().and_then(load)),
+                ("step", step.as_deref().and_then(load)),
+            ]),
+        ),
+        ExprKind::Attribute(value, attr) => (
+            "Attribute",
+            Vec::from([
+                ("value", load(value)),
+                ("attr", Some(Dumped::Repr(repr_str(attr)))),
+                ("ctx", Some(ctx.dumped())),
+            ]),
+        ),
+        ExprKind::Call {
+            func,
+            args,
+            keywords,
+        } => (
+            "Call",
+            Vec::from([
+                ("func", load(func)),
+                ("args", Some(dump_exprs(args, Ctx::Load))),
+                (
+                    "keywords",
+                    Some(Dumped::List(
+                        keywords
+                            .iter()
+                            .map(|(arg, value)| {
+                                Dumped::Node(
+                                    "keyword",
+                                    Vec::from([
+                                        ("arg", Some(Dumped::Repr(repr_str(arg)))),
+                                        ("value", load(value)),
+                                    ]),
+                                )
+                            })
+                            .collect(),
+                    )),
+                ),
+            ]),
+        ),
+    };
+    Dumped::Node(name, fields)
+}
+
+fn dump_stmt(stmt: &Stmt) -> Dumped {
+    let (name, fields) = match stmt {
+        Stmt::Expr(value) => (
+            "Expr",
+            Vec::from([("value", Some(dump_expr(value, Ctx::Load)))]),
+        ),
+        Stmt::Assign(targets, value) => (
+            "Assign",
+            Vec::from([
+                ("targets", Some(dump_exprs(targets, Ctx::Store))),
+                ("value", Some(dump_expr(value, Ctx::Load))),
+                ("type_comment", None),
+            ]),
+        ),
+        Stmt::AugAssign(target, op, value) => (
+            "AugAssign",
+            Vec::from([
+                ("target", Some(dump_expr(target, Ctx::Store))),
+                ("op", Some(op_node(binop_name(*op)))),
+                ("value", Some(dump_expr(value, Ctx::Load))),
+            ]),
+        ),
+        Stmt::Delete(targets) => (
+            "Delete",
+            Vec::from([("targets", Some(dump_exprs(targets, Ctx::Del)))]),
+        ),
+        Stmt::Pass => ("Pass", Vec::new()),
+    };
+    Dumped::Node(name, fields)
+}
+
+impl Dumped {
+    /// CPython's ast.dump() `_format`: the text, and whether it is simple
+    /// enough to keep on its parent's line.
+    fn format(&self, indent: Option<&str>, annotate_fields: bool, level: usize) -> (String, bool) {
+        let (prefix, sep, level) = match indent {
+            Some(indent) => {
+                let pad = indent.repeat(level + 1);
+                (format!("\n{}", pad), format!(",\n{}", pad), level + 1)
+            }
+            None => (String::new(), String::from(", "), level),
+        };
+        match self {
+            Dumped::Node(name, fields) => {
+                let mut args = Vec::new();
+                let mut all_simple = true;
+                let mut keywords = annotate_fields;
+                for (field, value) in fields {
+                    let Some(value) = value else {
+                        keywords = true;
+                        continue;
+                    };
+                    let (text, simple) = value.format(indent, annotate_fields, level);
+                    all_simple &= simple;
+                    args.push(if keywords {
+                        format!("{}={}", field, text)
+                    } else {
+                        text
+                    });
+                }
+                if all_simple && args.len() <= 3 {
+                    (format!("{}({})", name, args.join(", ")), args.is_empty())
+                } else {
+                    (format!("{}({}{})", name, prefix, args.join(&sep)), false)
+                }
+            }
+            Dumped::List(items) if items.is_empty() => (String::from("[]"), true),
+            Dumped::List(items) => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|item| item.format(indent, annotate_fields, level).0)
+                    .collect();
+                (format!("[{}{}]", prefix, items.join(&sep)), false)
+            }
+            Dumped::Repr(text) => (text.clone(), true),
+        }
+    }
+}
+
+fn dumped<'a>(node: impl Into<Node<'a>>) -> Dumped {
+    match node.into() {
+        Node::Module(body) => Dumped::Node(
+            "Module",
+            Vec::from([
+                (
+                    "body",
+                    Some(Dumped::List(body.iter().map(dump_stmt).collect())),
+                ),
+                ("type_ignores", Some(Dumped::List(Vec::new()))),
+            ]),
+        ),
+        Node::Stmt(stmt) => dump_stmt(stmt),
+        Node::Expr(expr) => dump_expr(expr, Ctx::Load),
+    }
+}
+
+/// Python-equivalent ast.dump(node).
+pub fn dump<'a>(node: impl Into<Node<'a>>) -> String {
+    dumped(node).format(None, true, 0).0
+}
+
+/// Python-equivalent ast.dump(node, **kwargs): `annotate_fields` and
+/// `indent` (a count of spaces or a string). Nodes carry no column
+/// offsets, so `include_attributes` must be false.
+pub fn dump_with<'a>(node: impl Into<Node<'a>>, kwargs: PyKwargs) -> PyResult<String> {
+    let mut kwargs = kwargs.for_call("dump");
+    let annotate_fields = kwargs.get_bool("annotate_fields").unwrap_or(true);
+    if kwargs.get_bool("include_attributes").unwrap_or(false) {
+        return Err(PyException::value_error(
+            "include_attributes is not supported: nodes have no column offsets",
+        ));
+    }
+    let indent = match kwargs.pop("indent") {
+        None | Some(PyValue::None) => None,
+        Some(PyValue::Str(s)) => Some(s.into_string()),
+        Some(PyValue::Int(n)) => Some(" ".repeat(usize::try_from(n).unwrap_or(0))),
+        Some(other) => return Err(kwargs.type_error("indent", "int or str", &other)),
+    };
+    kwargs.reject_unknown()?;
+    Ok(dumped(node).format(indent.as_deref(), annotate_fields, 0).0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval_repr(source: &str) -> String {
+        match literal_eval(source) {
+            Ok(value) => repr(&value),
+            Err(err) => format!("{}: {}", err.type_name, err.message),
+        }
+    }
+
+    #[test]
+    fn literal_eval_matches_cpython() {
+        let cases = [
+            ("1", "1"),
+            ("-1", "-1"),
+            ("+1.5", "1.5"),
+            ("[1, (2, \"a\"), {3: b\"x\"}]", "[1, (2, 'a'), {3: b'x'}]"),
+            ("set()", "set()"),
+            ("{1, 2}", "{1, 2}"),
+            ("None", "None"),
+            ("  1", "1"),
+            ("\t[1]", "[1]"),
+            ("\"a\" 'b'", "'ab'"),
+            ("{}", "{}"),
+            ("()", "()"),
+            ("(1,)", "(1,)"),
+            ("0x1F", "31"),
+            ("1_000", "1000"),
+            ("1e3", "1000.0"),
+            ("True", "True"),
+            ("'\\x41\\u00e9\\n'", "'Aé\\n'"),
+            ("r'\\n'", "'\\\\n'"),
+            ("1 +", "SyntaxError: invalid syntax (<unknown>, line 1)"),
+            ("{[1]: 2}", "TypeError: unhashable type: 'list'"),
+        ];
+        for (source, expected) in cases {
+            assert_eq!(eval_repr(source), expected, "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn literal_eval_rejects_other_nodes() {
+        let cases = [
+            ("--1", 1, "UnaryOp"),
+            ("1+2", 1, "BinOp"),
+            ("1+x", 1, "Name"),
+            ("x", 1, "Name"),
+            ("set([1])", 1, "Call"),
+            ("-True", 1, "Constant"),
+            ("[1,\n x]", 2, "Name"),
+            ("f(1)", 1, "Call"),
+            ("1 if 1 else 2", 1, "IfExp"),
+            ("10**2", 1, "BinOp"),
+        ];
+        for (source, line, node) in cases {
+            let err = literal_eval(source).unwrap_err();
+            assert!(err.is("ValueError"), "{:?}", source);
+            let prefix = format!(
+                "malformed node or string on line {}: <ast.{} object at 0x",
+                line, node
+            );
+            assert!(
+                err.message.starts_with(&prefix),
+                "{:?}: {}",
+                source,
+                err.message
+            );
+        }
+    }
+
+    #[test]
+    fn syntax_errors_like_cpython() {
+        let cases = [
+            ("", "invalid syntax (<unknown>, line 0)"),
+            ("(1", "'(' was never closed (<unknown>, line 1)"),
+            ("1)", "unmatched ')' (<unknown>, line 1)"),
+            ("'abc", "unterminated string literal (detected at line 1) (<unknown>, line 1)"),
+            (
+                "'''ab",
+                "unterminated triple-quoted string literal (detected at line 1) (<unknown>, line 1)",
+            ),
+            (
+                "0123",
+                "leading zeros in decimal integer literals are not permitted; \
+                 use an 0o prefix for octal integers (<unknown>, line 1)",
+            ),
+            ("1_", "invalid decimal literal (<unknown>, line 1)"),
+            ("1 2", "invalid syntax (<unknown>, line 1)"),
+            ("[1,", "'[' was never closed (<unknown>, line 1)"),
+            ("1 if 2", "expected 'else' after 'if' expression (<unknown>, line 1)"),
+            ("$", "invalid syntax (<unknown>, line 1)"),
+            ("[x for x in y]", "comprehensions are not supported (<unknown>, line 1)"),
+            (
+                "'\\N{BULLET}'",
+                "\\N{...} escapes are not supported (<unknown>, line 1)",
+            ),
+        ];
+        for (source, expected) in cases {
+            let err = parse_expression(source).unwrap_err();
+            assert!(err.is("SyntaxError"), "{:?}", source);
+            assert_eq!(err.message, expected, "{:?}", source);
+        }
+    }
+
+    #[test]
+    fn nesting_is_bounded() {
+        let nested =
+            |open: &str, close: &str, n: usize| format!("{}1{}", open.repeat(n), close.repeat(n));
+        let brackets = nested("[", "]", MAX_BRACKETS);
+        assert_eq!(eval_repr(&brackets).len(), brackets.len());
+        let err = parse_expression(&nested("(", ")", MAX_BRACKETS + 1)).unwrap_err();
+        assert_eq!(
+            err.message,
+            "too many nested parentheses (<unknown>, line 1)"
+        );
+        assert!(parse_expression(&nested("-", "", MAX_DEPTH - 1)).is_ok());
+        for source in [
+            nested("-", "", 100_000),
+            nested("not ", "", 100_000),
+            nested("", "+1", 100_000),
+            nested("", "[0]", 100_000),
+            nested("", "**1", 100_000),
+        ] {
+            let err = parse_expression(&source).unwrap_err();
+            assert!(err.is("RecursionError"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn parses_statements() {
+        let body = parse("a = b = 1; c += 2\npass\n\ndel a, b").unwrap();
+        assert_eq!(body.len(), 4);
+        assert!(matches!(&body[0], Stmt::Assign(targets, _) if targets.len() == 2));
+        assert!(matches!(&body[1], Stmt::AugAssign(_, BinOp::Add, _)));
+        assert_eq!(body[2], Stmt::Pass);
+        assert!(matches!(&body[3], Stmt::Delete(targets) if targets.len() == 2));
+        let err = parse("1 = x").unwrap_err();
+        assert_eq!(err.message, "cannot assign to literal (<unknown>, line 1)");
+        let err = parse("x = 1\n  y = 2").unwrap_err();
+        assert!(err.is("IndentationError"));
+        let err = parse("if x: pass").unwrap_err();
+        assert_eq!(
+            err.message,
+            "'if' statements are not supported (<unknown>, line 1)"
+        );
+    }
+
+    #[test]
+    fn dump_matches_cpython() {
+        let expr = parse_expression("1 + x * 2").unwrap();
+        assert_eq!(
+            dump(&expr),
+            "BinOp(left=Constant(value=1), op=Add(), right=BinOp(left=Name(id='x', ctx=Load()), \
+             op=Mult(), right=Constant(value=2)))"
+        );
+        let module = parse("a, b[0] = f(1, k=[2]); del c\npass").unwrap();
+        assert_eq!(
+            dump(&module),
+            "Module(body=[Assign(targets=[Tuple(elts=[Name(id='a', ctx=Store()), \
+             Subscript(value=Name(id='b', ctx=Load()), slice=Constant(value=0), ctx=Store())], \
+             ctx=Store())], value=Call(func=Name(id='f', ctx=Load()), args=[Constant(value=1)], \
+             keywords=[keyword(arg='k', value=List(elts=[Constant(value=2)], ctx=Load()))])), \
+             Delete(targets=[Name(id='c', ctx=Del())]), Pass()], type_ignores=[])"
+        );
+        let expr = parse_expression("x[1:2] if not y else {1: \"a\"}").unwrap();
+        let indented = dump_with(&expr, PyKwargs::new().with("indent", 2)).unwrap();
+        assert_eq!(
+            indented,
+            "IfExp(\n  test=UnaryOp(\n    op=Not(),\n    operand=Name(id='y', ctx=Load())),\n  \
+             body=Subscript(\n    value=Name(id='x', ctx=Load()),\n    slice=Slice(\n      \
+             lower=Constant(value=1),\n      upper=Constant(value=2)),\n    ctx=Load()),\n  \
+             orelse=Dict(\n    keys=[\n      Constant(value=1)],\n    values=[\n      \
+             Constant(value='a')]))"
+        );
+        let expr = parse_expression("-1 < a.b").unwrap();
+        let bare = dump_with(&expr, PyKwargs::new().with("annotate_fields", false)).unwrap();
+        assert_eq!(
+            bare,
+            "Compare(UnaryOp(USub(), Constant(1)), [Lt()], [Attribute(Name('a', Load()), 'b', Load())])"
+        );
+        let expr = parse_expression("[]").unwrap();
+        let empty = dump_with(&expr, PyKwargs::new().with("indent", 2)).unwrap();
+        assert_eq!(empty, "List(elts=[], ctx=Load())");
+    }
+
+    #[test]
+    fn walk_is_breadth_first() {
+        let expr = parse_expression("f(a + 1, [b])").unwrap();
+        let names: Vec<&str> = walk(&expr).iter().map(Node::node_name).collect();
+        assert_eq!(
+            names,
+            ["Call", "Name", "BinOp", "List", "Name", "Constant", "Name"]
+        );
+        let module = parse("x = 1\npass").unwrap();
+        let names: Vec<&str> = walk(&module).iter().map(Node::node_name).collect();
+        assert_eq!(names, ["Module", "Assign", "Pass", "Name", "Constant"]);
+    }
+}
@@ -0,0 +1,130 @@
+//! Python-equivalent atexit module. Compiled programs end `main` with
+//! [`py_runtime_shutdown`](crate::py_runtime_shutdown), which runs the
+//! handlers registered here, most recent first.
+//!
+//! Functions are not `Send`, so handlers belong to the thread that
+//! registers them, which for a program's cleanup is the main thread.
+
+use std::cell::RefCell;
+use std::fmt::Write;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::exceptions::PyResult;
+use crate::types::{KwargsMap, PyCallable, PyFunction, PyKwargs, PyValue};
+
+struct Handler {
+    func: PyFunction,
+    args: Vec<PyValue>,
+    kwargs: KwargsMap,
+}
+
+thread_local! {
+    static HANDLERS: RefCell<Vec<Handler>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Python-equivalent atexit.register(func): returns `func`, so it also
+/// serves as a decorator.
+pub fn register(func: PyFunction) -> PyFunction {
+    register_with(func, Vec::new(), PyKwargs::new())
+}
+
+/// Python-equivalent atexit.register(func, *args, **kwargs).
+pub fn register_with(func: PyFunction, args: Vec<PyValue>, kwargs: PyKwargs) -> PyFunction {
+    HANDLERS.with(|handlers| {
+        handlers.borrow_mut().push(Handler {
+            func: func.clone(),
+            args,
+            kwargs: kwargs.into(),
+        })
+    });
+    func
+}
+
+/// Python-equivalent atexit.unregister(func): removes every registration
+/// of `func`, doing nothing if there is none.
+pub fn unregister(func: &PyFunction) {
+    HANDLERS.with(|handlers| handlers.borrow_mut().retain(|h| h.func != *func));
+}
+
+/// Python-equivalent atexit._ncallbacks().
+pub fn _ncallbacks() -> usize {
+    HANDLERS.with(|handlers| handlers.borrow().len())
+}
+
+/// Python-equivalent atexit._clear().
+pub fn _clear() {
+    HANDLERS.with(|handlers| handlers.borrow_mut().clear());
+}
+
+/// Python-equivalent atexit._run_exitfuncs(), reporting to stderr.
+pub fn _run_exitfuncs() {
+    let mut report = String::new();
+    run_exitfuncs_to(&mut report);
+    eprint!("{}", report);
+}
+
+/// Runs and removes the handlers, most recently registered first. As in
+/// CPython, an exception from one, SystemExit included, is reported to
+/// `errors` and the rest still run; so does a handler that panics.
+pub fn run_exitfuncs_to<W: Write + ?Sized>(errors: &mut W) {
+    // Popping one at a time lets a handler unregister or add others.
+    while let Some(handler) = HANDLERS.with(|handlers| handlers.borrow_mut().pop()) {
+        let call = || -> PyResult<PyValue> { handler.func.call(&handler.args, &handler.kwargs) };
+        let error = match panic::catch_unwind(AssertUnwindSafe(call)) {
+            Ok(Ok(_)) => continue,
+            Ok(Err(err)) => err.to_string(),
+            Err(payload) => match payload.downcast::<String>() {
+                Ok(message) => *message,
+                Err(payload) => payload
+                    .downcast_ref::<&str>()
+                    .map_or_else(|| String::from("panic"), |s| s.to_string()),
+            },
+        };
+        let _ = writeln!(
+            errors,
+            "Exception ignored in atexit callback: {}\n{}",
+            handler.func, error
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exceptions::PyException;
+    use std::rc::Rc;
+
+    #[test]
+    fn runs_lifo_and_reports_errors() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let push = |name: &'static str| {
+            let log = log.clone();
+            PyFunction::new(name, move |args: &[PyValue], kwargs: &KwargsMap| {
+                log.borrow_mut()
+                    .push(format!("{} {:?} {}", name, args, kwargs.len()));
+                Ok(PyValue::None)
+            })
+        };
+        register(push("first"));
+        let g = push("g");
+        register(g.clone());
+        register(g.clone());
+        unregister(&g);
+        register_with(
+            push("last"),
+            vec![PyValue::Int(1)],
+            PyKwargs::new().with("k", 2),
+        );
+        register(PyFunction::nullary("fails", || {
+            Err(PyException::new("SystemExit", "3"))
+        }));
+        assert_eq!(_ncallbacks(), 3);
+
+        let mut errors = String::new();
+        run_exitfuncs_to(&mut errors);
+        assert_eq!(*log.borrow(), ["last [Int(1)] 1", "first [] 0"]);
+        assert!(errors.starts_with("Exception ignored in atexit callback: <function fails at 0x"));
+        assert!(errors.ends_with(">\nSystemExit: 3\n"));
+        assert_eq!(_ncallbacks(), 0);
+    }
+}
@@ -0,0 +1,170 @@
+//! Python-equivalent base64 module (standard and URL-safe alphabets).
+
+use crate::exceptions::{PyException, PyResult};
+use alloc::format;
+use alloc::vec::Vec;
+
+const STANDARD: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URLSAFE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Maximum encoded line length used by encodebytes(), as in MIME.
+pub const MAXLINESIZE: usize = 76;
+
+fn encode_with(data: &[u8], alphabet: &[u8; 64]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        out.push(alphabet[(n >> 18) as usize & 63]);
+        out.push(alphabet[(n >> 12) as usize & 63]);
+        out.push(if chunk.len() > 1 {
+            alphabet[(n >> 6) as usize & 63]
+        } else {
+            b'='
+        });
+        out.push(if chunk.len() > 2 {
+            alphabet[n as usize & 63]
+        } else {
+            b'='
+        });
+    }
+    out
+}
+
+fn decode_with(data: &[u8], alphabet: &[u8; 64], validate: bool) -> PyResult<Vec<u8>> {
+    let mut lookup = [255u8; 256];
+    for (i, &c) in alphabet.iter().enumerate() {
+        lookup[c as usize] = i as u8;
+    }
+
+    let mut out = Vec::with_capacity(data.len() / 4 * 3);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    let mut padding = 0;
+    let mut quads = 0usize;
+    for &c in data {
+        if c == b'=' {
+            padding += 1;
+            quads += 1;
+            continue;
+        }
+        let v = lookup[c as usize];
+        if v == 255 {
+            if validate {
+                return Err(PyException::new("binascii.Error", "Non-base64 digit found"));
+            }
+            continue;
+        }
+        if padding > 0 {
+            if validate {
+                return Err(PyException::new(
+                    "binascii.Error",
+                    "Discontinuous padding not allowed",
+                ));
+            }
+            // Padding in the middle ends the current quantum; Python discards what follows.
+            break;
+        }
+        quads += 1;
+        acc = (acc << 6) | u32::from(v);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    if !quads.is_multiple_of(4) {
+        let digits = quads - padding;
+        if digits % 4 == 1 {
+            return Err(PyException::new(
+                "binascii.Error",
+                format!(
+                    "Invalid base64-encoded string: number of data characters ({}) cannot be 1 more than a multiple of 4",
+                    digits
+                ),
+            ));
+        }
+        return Err(PyException::new("binascii.Error", "Incorrect padding"));
+    }
+    Ok(out)
+}
+
+/// Python-equivalent base64.b64encode().
+pub fn b64encode(data: &[u8]) -> Vec<u8> {
+    encode_with(data, STANDARD)
+}
+
+/// Python-equivalent base64.b64decode(); non-alphabet bytes are skipped unless `validate`.
+pub fn b64decode(data: &[u8], validate: bool) -> PyResult<Vec<u8>> {
+    decode_with(data, STANDARD, validate)
+}
+
+/// Python-equivalent base64.standard_b64encode().
+pub fn standard_b64encode(data: &[u8]) -> Vec<u8> {
+    b64encode(data)
+}
+
+/// Python-equivalent base64.standard_b64decode().
+pub fn standard_b64decode(data: &[u8]) -> PyResult<Vec<u8>> {
+    b64decode(data, false)
+}
+
+/// Python-equivalent base64.urlsafe_b64encode().
+pub fn urlsafe_b64encode(data: &[u8]) -> Vec<u8> {
+    encode_with(data, URLSAFE)
+}
+
+/// Python-equivalent base64.urlsafe_b64decode().
+pub fn urlsafe_b64decode(data: &[u8]) -> PyResult<Vec<u8>> {
+    decode_with(data, URLSAFE, false)
+}
+
+/// Python-equivalent base64.encodebytes(): wrapped at 76 characters, newline-terminated.
+pub fn encodebytes(data: &[u8]) -> Vec<u8> {
+    let encoded = b64encode(data);
+    let mut out = Vec::with_capacity(encoded.len() + encoded.len() / MAXLINESIZE + 1);
+    for line in encoded.chunks(MAXLINESIZE) {
+        out.extend_from_slice(line);
+        out.push(b'\n');
+    }
+    out
+}
+
+/// Python-equivalent base64.decodebytes().
+pub fn decodebytes(data: &[u8]) -> PyResult<Vec<u8>> {
+    b64decode(data, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for input in [&b""[..], b"f", b"fo", b"foo", b"foob", b"fooba", b"foobar"] {
+            let encoded = b64encode(input);
+            assert_eq!(b64decode(&encoded, true).unwrap(), input);
+        }
+        assert_eq!(b64encode(b"foobar"), b"Zm9vYmFy");
+        assert_eq!(b64encode(b"fo"), b"Zm8=");
+    }
+
+    #[test]
+    fn padding_errors() {
+        assert!(b64decode(b"Zm8", false).unwrap_err().is("binascii.Error"));
+        assert!(b64decode(b"Zm9v!", true).is_err());
+        assert_eq!(b64decode(b"Zm9v\n", false).unwrap(), b"foo");
+    }
+
+    #[test]
+    fn urlsafe_and_lines() {
+        assert_eq!(urlsafe_b64encode(&[0xfb, 0xff]), b"-_8=");
+        let wrapped = encodebytes(&[0u8; 60]);
+        assert_eq!(wrapped.iter().filter(|&&c| c == b'\n').count(), 2);
+    }
+}
@@ -0,0 +1,542 @@
+//! Python-equivalent cmd module: line-oriented command interpreters.
+//!
+//! A compiled `class Shell(cmd.Cmd)` implements [`Cmd`], keeping the
+//! instance attributes cmd.Cmd reads (`prompt`, `intro`, `lastcmd`, ...)
+//! in a [`CmdState`] and listing its `do_*` methods in
+//! [`commands`](Cmd::commands), which stands in for the `getattr(self,
+//! 'do_' + cmd)` lookup. Every other method is provided and can be
+//! overridden as a subclass would; [`do_help`] plays `super().do_help`.
+//!
+//! Output goes to sys.stdout and input comes from sys.stdin. There is no
+//! readline completion: lines are read as input() reads them.
+
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use crate::exceptions::PyResult;
+use crate::stdlib::io::{flush_stdout, readline, write_stdout};
+use crate::types::{PyCallable, PyFunction, PyTruthy, PyValue};
+
+/// Python-equivalent cmd.IDENTCHARS: what a command name may contain.
+pub const IDENTCHARS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_";
+
+/// Python-equivalent cmd.PROMPT.
+pub const PROMPT: &str = "(Cmd) ";
+
+/// A `do_*` method: takes the rest of the line and returns a value whose
+/// truth stops cmdloop(), as a Python method returning True does.
+pub type CommandFn<C> = fn(&mut C, &str) -> PyResult<PyValue>;
+
+enum Handler<C> {
+    Method(CommandFn<C>),
+    Function(PyFunction),
+    /// A `help_*` method with no `do_*` to go with it.
+    Topic,
+}
+
+/// One `do_*` method, or a help topic, of a [`Cmd`].
+pub struct Command<C> {
+    name: String,
+    doc: Option<String>,
+    handler: Handler<C>,
+}
+
+impl<C> Command<C> {
+    /// `do_<name>`, implemented by `method`.
+    pub fn new(name: &str, method: CommandFn<C>) -> Self {
+        Command {
+            name: name.to_string(),
+            doc: None,
+            handler: Handler::Method(method),
+        }
+    }
+
+    /// A callable taking the argument string, such as a registered
+    /// `do_<name>` function; a leading `do_` is dropped from its name.
+    pub fn function(function: PyFunction) -> Self {
+        let name = function.name();
+        Command {
+            name: name.strip_prefix("do_").unwrap_or(name).to_string(),
+            doc: None,
+            handler: Handler::Function(function),
+        }
+    }
+
+    /// A `help_<name>` topic that is not a command, listed under
+    /// [`misc_header`](CmdState::misc_header).
+    pub fn topic(name: &str, help: &str) -> Self {
+        Command {
+            name: name.to_string(),
+            doc: Some(help.to_string()),
+            handler: Handler::Topic,
+        }
+    }
+
+    /// The docstring `help <name>` prints.
+    pub fn with_doc(mut self, doc: &str) -> Self {
+        self.doc = Some(doc.to_string());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn doc(&self) -> Option<&str> {
+        self.doc.as_deref()
+    }
+
+    pub fn is_topic(&self) -> bool {
+        matches!(self.handler, Handler::Topic)
+    }
+}
+
+/// The instance attributes cmd.Cmd keeps, with its class defaults.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CmdState {
+    pub prompt: String,
+    pub intro: Option<String>,
+    /// The last nonempty command, which emptyline() repeats.
+    pub lastcmd: String,
+    /// Lines cmdloop() runs before reading any input.
+    pub cmdqueue: VecDeque<String>,
+    pub identchars: String,
+    pub doc_leader: String,
+    pub doc_header: String,
+    pub misc_header: String,
+    pub undoc_header: String,
+    /// `%s` is replaced by the topic.
+    pub nohelp: String,
+    /// Underlines help headers; empty for none.
+    pub ruler: String,
+}
+
+impl Default for CmdState {
+    fn default() -> Self {
+        CmdState {
+            prompt: PROMPT.to_string(),
+            intro: None,
+            lastcmd: String::new(),
+            cmdqueue: VecDeque::new(),
+            identchars: IDENTCHARS.to_string(),
+            doc_leader: String::new(),
+            doc_header: "Documented commands (type help <topic>):".to_string(),
+            misc_header: "Miscellaneous help topics:".to_string(),
+            undoc_header: "Undocumented commands:".to_string(),
+            nohelp: "*** No help on %s".to_string(),
+            ruler: "=".to_string(),
+        }
+    }
+}
+
+/// Python-equivalent cmd.Cmd.
+pub trait Cmd: Sized {
+    fn cmd_state(&self) -> &CmdState;
+
+    fn cmd_state_mut(&mut self) -> &mut CmdState;
+
+    /// The class's `do_*` methods and `help_*` topics. `help` itself is
+    /// always available through [`do_help`](Cmd::do_help).
+    fn commands(&self) -> Vec<Command<Self>>;
+
+    /// Python-equivalent Cmd.cmdloop(intro): reads lines from sys.stdin
+    /// until a command returns a true value. End of input runs the `EOF`
+    /// command.
+    fn cmdloop(&mut self, intro: Option<&str>) -> PyResult<()> {
+        let stdin = std::io::stdin();
+        cmdloop_with(self, intro, || readline(&mut stdin.lock()))
+    }
+
+    /// Python-equivalent Cmd.onecmd(line): runs one line, returning whether
+    /// to stop.
+    fn onecmd(&mut self, line: &str) -> PyResult<bool> {
+        let (cmd, arg, line) = self.parseline(line);
+        if line.is_empty() {
+            return self.emptyline();
+        }
+        let Some(cmd) = cmd else {
+            return self.default(&line);
+        };
+        self.cmd_state_mut().lastcmd = if line == "EOF" {
+            String::new()
+        } else {
+            line.clone()
+        };
+        if cmd.is_empty() {
+            return self.default(&line);
+        }
+        let arg = arg.unwrap_or_default();
+        let found = self
+            .commands()
+            .into_iter()
+            .find(|command| command.name == cmd && !command.is_topic());
+        let stop = match found.map(|command| command.handler) {
+            Some(Handler::Method(method)) => method(self, &arg)?,
+            Some(Handler::Function(function)) => {
+                function.call_positional(&[PyValue::from(arg.as_str())])?
+            }
+            Some(Handler::Topic) | None if cmd == "help" => return self.do_help(&arg),
+            Some(Handler::Topic) | None => return self.default(&line),
+        };
+        Ok(stop.py_bool())
+    }
+
+    /// Python-equivalent Cmd.parseline(line): the command name, the rest
+    /// of the line, and the stripped line. `?` is `help` and `!` is
+    /// `shell` when there is a shell command.
+    fn parseline(&self, line: &str) -> (Option<String>, Option<String>, String) {
+        let mut line = line.trim().to_string();
+        if line.is_empty() {
+            return (None, None, line);
+        } else if let Some(rest) = line.strip_prefix('?') {
+            line = format!("help {}", rest);
+        } else if let Some(rest) = line.strip_prefix('!') {
+            if !self.commands().iter().any(|c| c.name == "shell") {
+                return (None, None, line);
+            }
+            line = format!("shell {}", rest);
+        }
+        let identchars = &self.cmd_state().identchars;
+        let end = line
+            .find(|c: char| !identchars.contains(c))
+            .unwrap_or(line.len());
+        let (cmd, arg) = line.split_at(end);
+        (Some(cmd.to_string()), Some(arg.trim().to_string()), line)
+    }
+
+    /// Python-equivalent Cmd.emptyline(): repeats the last command.
+    fn emptyline(&mut self) -> PyResult<bool> {
+        let lastcmd = self.cmd_state().lastcmd.clone();
+        if lastcmd.is_empty() {
+            return Ok(false);
+        }
+        self.onecmd(&lastcmd)
+    }
+
+    /// Python-equivalent Cmd.default(line), for an unknown command.
+    fn default(&mut self, line: &str) -> PyResult<bool> {
+        write_stdout(&format!("*** Unknown syntax: {}\n", line))?;
+        Ok(false)
+    }
+
+    /// Python-equivalent Cmd.precmd(line), run on each line before it is
+    /// interpreted.
+    fn precmd(&mut self, line: String) -> PyResult<String> {
+        Ok(line)
+    }
+
+    /// Python-equivalent Cmd.postcmd(stop, line).
+    fn postcmd(&mut self, stop: bool, line: &str) -> PyResult<bool> {
+        let _ = line;
+        Ok(stop)
+    }
+
+    /// Python-equivalent Cmd.preloop().
+    fn preloop(&mut self) -> PyResult<()> {
+        Ok(())
+    }
+
+    /// Python-equivalent Cmd.postloop().
+    fn postloop(&mut self) -> PyResult<()> {
+        Ok(())
+    }
+
+    /// Python-equivalent Cmd.do_help(arg): a command's docstring, or with
+    /// no argument, the commands and topics in columns.
+    fn do_help(&mut self, arg: &str) -> PyResult<bool> {
+        do_help(self, arg)
+    }
+}
+
+/// Python-equivalent Cmd.cmdloop(intro) reading lines from `reader`
+/// instead of sys.stdin.
+pub fn cmdloop_from<C: Cmd, R: BufRead>(
+    cmd: &mut C,
+    intro: Option<&str>,
+    mut reader: R,
+) -> PyResult<()> {
+    cmdloop_with(cmd, intro, || readline(&mut reader))
+}
+
+fn cmdloop_with<C: Cmd>(
+    cmd: &mut C,
+    intro: Option<&str>,
+    mut next_line: impl FnMut() -> PyResult<String>,
+) -> PyResult<()> {
+    cmd.preloop()?;
+    if let Some(intro) = intro {
+        cmd.cmd_state_mut().intro = Some(intro.to_string());
+    }
+    if let Some(intro) = cmd.cmd_state().intro.as_deref().filter(|i| !i.is_empty()) {
+        write_stdout(&format!("{}\n", intro))?;
+    }
+    loop {
+        let line = match cmd.cmd_state_mut().cmdqueue.pop_front() {
+            Some(line) => line,
+            None => {
+                write_stdout(&cmd.cmd_state().prompt)?;
+                flush_stdout()?;
+                let line = next_line()?;
+                match line.strip_suffix('\n') {
+                    _ if line.is_empty() => "EOF".to_string(),
+                    Some(line) => line.strip_suffix('\r').unwrap_or(line).to_string(),
+                    None => line,
+                }
+            }
+        };
+        let line = cmd.precmd(line)?;
+        let stop = cmd.onecmd(&line)?;
+        if cmd.postcmd(stop, &line)? {
+            break;
+        }
+    }
+    cmd.postloop()
+}
+
+/// The default Cmd.do_help, for overrides to fall back on.
+pub fn do_help<C: Cmd>(cmd: &mut C, arg: &str) -> PyResult<bool> {
+    let commands = cmd.commands();
+    let state = cmd.cmd_state();
+    if !arg.is_empty() {
+        let doc = commands
+            .iter()
+            .filter(|c| c.name == arg)
+            .find_map(|c| c.doc.as_deref().filter(|d| !d.is_empty()));
+        let text = match doc {
+            Some(doc) => doc.to_string(),
+            None if arg == "help" => HELP_DOC.to_string(),
+            None => state.nohelp.replace("%s", arg),
+        };
+        write_stdout(&format!("{}\n", text))?;
+        return Ok(false);
+    }
+    let mut names: Vec<&str> = commands
+        .iter()
+        .filter(|c| !c.is_topic())
+        .map(|c| c.name.as_str())
+        .chain(["help"])
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    let mut topics: Vec<&str> = commands
+        .iter()
+        .filter(|c| c.is_topic())
+        .map(|c| c.name.as_str())
+        .collect();
+    let (mut documented, mut undocumented) = (Vec::new(), Vec::new());
+    for name in names {
+        let has_doc = name == "help"
+            || commands
+                .iter()
+                .any(|c| c.name == name && c.doc.as_deref().is_some_and(|d| !d.is_empty()));
+        if let Some(i) = topics.iter().position(|t| *t == name) {
+            topics.remove(i);
+            documented.push(name);
+        } else if has_doc {
+            documented.push(name);
+        } else {
+            undocumented.push(name);
+        }
+    }
+    topics.sort_unstable();
+    topics.dedup();
+    write_stdout(&format!("{}\n", state.doc_leader))?;
+    print_topics(state, &state.doc_header, &documented)?;
+    print_topics(state, &state.misc_header, &topics)?;
+    print_topics(state, &state.undoc_header, &undocumented)?;
+    Ok(false)
+}
+
+const HELP_DOC: &str = "List available commands with \"help\" or detailed help with \"help cmd\".";
+
+/// Python-equivalent Cmd.print_topics(header, cmds, 15, 80).
+fn print_topics(state: &CmdState, header: &str, cmds: &[&str]) -> PyResult<()> {
+    if cmds.is_empty() {
+        return Ok(());
+    }
+    write_stdout(&format!("{}\n", header))?;
+    if !state.ruler.is_empty() {
+        write_stdout(&format!("{}\n", state.ruler.repeat(header.chars().count())))?;
+    }
+    write_stdout(&columnize(cmds, 79))?;
+    write_stdout("\n")
+}
+
+/// Python-equivalent Cmd.columnize(list, displaywidth), returning the text
+/// instead of writing it: as few rows as fit in `displaywidth`, filled
+/// column by column.
+pub fn columnize<S: AsRef<str>>(list: &[S], displaywidth: usize) -> String {
+    let width = |i: usize| list[i].as_ref().chars().count();
+    let size = list.len();
+    match size {
+        0 => return "<empty>\n".to_string(),
+        1 => return format!("{}\n", list[0].as_ref()),
+        _ => {}
+    }
+    let mut layout = (size, 1, vec![0]);
+    for nrows in 1..size {
+        let ncols = size.div_ceil(nrows);
+        let colwidths: Vec<usize> = (0..ncols)
+            .map(|col| {
+                (col * nrows..((col + 1) * nrows).min(size))
+                    .map(width)
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let total: usize = colwidths.iter().sum::<usize>() + 2 * (ncols - 1);
+        if total <= displaywidth {
+            layout = (nrows, ncols, colwidths);
+            break;
+        }
+    }
+    let (nrows, ncols, colwidths) = layout;
+    let mut out = String::new();
+    for row in 0..nrows {
+        let mut texts: Vec<&str> = (0..ncols)
+            .map(|col| list.get(row + nrows * col).map_or("", AsRef::as_ref))
+            .collect();
+        while texts.last().is_some_and(|t| t.is_empty()) {
+            texts.pop();
+        }
+        let padded: Vec<String> = texts
+            .iter()
+            .zip(&colwidths)
+            .map(|(text, &w)| format!("{:<w$}", text, w = w))
+            .collect();
+        out.push_str(&padded.join("  "));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::io::capture;
+
+    struct Shell {
+        state: CmdState,
+        total: i64,
+        log: Vec<String>,
+    }
+
+    impl Shell {
+        fn new() -> Self {
+            Shell {
+                state: CmdState::default(),
+                total: 0,
+                log: Vec::new(),
+            }
+        }
+
+        fn do_add(&mut self, arg: &str) -> PyResult<PyValue> {
+            self.total += crate::builtins::int(arg)?;
+            write_stdout(&format!("{}\n", self.total))?;
+            Ok(PyValue::None)
+        }
+
+        fn do_quit(&mut self, _arg: &str) -> PyResult<PyValue> {
+            Ok(PyValue::Bool(true))
+        }
+    }
+
+    impl Cmd for Shell {
+        fn cmd_state(&self) -> &CmdState {
+            &self.state
+        }
+
+        fn cmd_state_mut(&mut self) -> &mut CmdState {
+            &mut self.state
+        }
+
+        fn commands(&self) -> Vec<Command<Self>> {
+            vec![
+                Command::new("add", Shell::do_add).with_doc("Add a number to the total."),
+                Command::new("quit", Shell::do_quit),
+                Command::new("EOF", Shell::do_quit),
+                Command::function(PyFunction::unary("do_echo", |arg| {
+                    write_stdout(&format!("{}\n", arg))?;
+                    Ok(PyValue::None)
+                })),
+                Command::topic("usage", "Type numbers to add them."),
+            ]
+        }
+
+        fn precmd(&mut self, line: String) -> PyResult<String> {
+            self.log.push(line.clone());
+            Ok(line)
+        }
+    }
+
+    #[test]
+    fn cmdloop_dispatches_until_a_command_stops() {
+        let mut shell = Shell::new();
+        shell.state.prompt = "> ".to_string();
+        let input = "add 2\n\n  add 3  \necho hi there\nbogus 1\n?usage\nquit\nadd 100\n";
+        let (result, out) =
+            capture(|| cmdloop_from(&mut shell, Some("Welcome."), input.as_bytes()));
+        result.unwrap();
+        assert_eq!(
+            out,
+            "Welcome.\n> 2\n> 4\n> 7\n> hi there\n> *** Unknown syntax: bogus 1\n\
+             > Type numbers to add them.\n> "
+        );
+        assert_eq!(shell.total, 7);
+        assert_eq!(shell.log[1], "");
+
+        // End of input runs EOF; a failing command propagates.
+        let mut shell = Shell::new();
+        let (result, out) = capture(|| cmdloop_from(&mut shell, None, "".as_bytes()));
+        result.unwrap();
+        assert_eq!(out, PROMPT);
+        let err = capture(|| shell.onecmd("add x")).0.unwrap_err();
+        assert!(err.is("ValueError"));
+    }
+
+    #[test]
+    fn help_lists_commands_and_topics() {
+        let mut shell = Shell::new();
+        let (stop, out) = capture(|| shell.onecmd("help"));
+        assert!(!stop.unwrap());
+        assert_eq!(
+            out,
+            "\nDocumented commands (type help <topic>):\n\
+             ========================================\n\
+             add  help\n\n\
+             Miscellaneous help topics:\n\
+             ==========================\n\
+             usage\n\n\
+             Undocumented commands:\n\
+             ======================\n\
+             EOF  echo  quit\n\n"
+        );
+        let (_, out) = capture(|| shell.onecmd("help add"));
+        assert_eq!(out, "Add a number to the total.\n");
+        let (_, out) = capture(|| shell.onecmd("help quit"));
+        assert_eq!(out, "*** No help on quit\n");
+        let (_, out) = capture(|| shell.onecmd("!ls"));
+        assert_eq!(out, "*** Unknown syntax: !ls\n");
+    }
+
+    #[test]
+    fn columnize_matches_cpython() {
+        let words: Vec<String> = (0..30).map(|i| format!("word{}", i * 7)).collect();
+        assert_eq!(
+            columnize(&words, 40),
+            "word0   word56   word112  word168\n\
+             word7   word63   word119  word175\n\
+             word14  word70   word126  word182\n\
+             word21  word77   word133  word189\n\
+             word28  word84   word140  word196\n\
+             word35  word91   word147  word203\n\
+             word42  word98   word154\n\
+             word49  word105  word161\n"
+        );
+        assert_eq!(columnize::<&str>(&[], 80), "<empty>\n");
+        assert_eq!(
+            columnize(&["averyverylongword", "x"], 5),
+            "averyverylongword\nx\n"
+        );
+    }
+}
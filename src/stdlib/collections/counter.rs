@@ -0,0 +1,321 @@
+//! collections.Counter: a dict of counts whose most_common(n) selects the
+//! top n with a bounded heap instead of sorting every item.
+
+use alloc::collections::BinaryHeap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::cmp::{Ordering, Reverse};
+use core::fmt;
+use core::hash::Hash;
+
+use crate::types::dict::Iter;
+use crate::types::{
+    ItemsView, KeysView, PyContains, PyDictionary, PyLen, PyRepr, PyTruthy, ValuesView,
+};
+
+/// Python-equivalent collections.Counter. Keys keep insertion order, which
+/// breaks ties in most_common() as in CPython.
+#[derive(Clone)]
+pub struct Counter<K> {
+    counts: PyDictionary<K, i64>,
+}
+
+impl<K> Default for Counter<K> {
+    fn default() -> Self {
+        Counter {
+            counts: PyDictionary::default(),
+        }
+    }
+}
+
+impl<K> Counter<K> {
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Iterates `(key, count)` pairs in insertion order.
+    pub fn iter(&self) -> Iter<'_, K, i64> {
+        self.counts.iter()
+    }
+
+    /// Python-equivalent Counter.keys(): a borrowing view in insertion
+    /// order.
+    pub fn keys(&self) -> KeysView<Iter<'_, K, i64>> {
+        self.counts.keys()
+    }
+
+    /// Python-equivalent Counter.values().
+    pub fn values(&self) -> ValuesView<Iter<'_, K, i64>> {
+        self.counts.values()
+    }
+
+    /// Python-equivalent Counter.items().
+    pub fn items(&self) -> ItemsView<Iter<'_, K, i64>> {
+        self.counts.items()
+    }
+
+    /// Python-equivalent Counter.total().
+    pub fn total(&self) -> i64 {
+        self.counts.iter().map(|(_, count)| count).sum()
+    }
+
+    /// Python-equivalent Counter.elements(): each key repeated by its count,
+    /// skipping keys whose count is not positive.
+    pub fn elements(&self) -> impl Iterator<Item = &K> {
+        self.counts
+            .iter()
+            .flat_map(|(key, &count)| core::iter::repeat_n(key, count.max(0) as usize))
+    }
+}
+
+/// One item as most_common() ranks it: by count, then earlier first.
+struct Ranked<'a, K> {
+    count: i64,
+    index: usize,
+    key: &'a K,
+}
+
+impl<K> Ranked<'_, K> {
+    fn rank(&self) -> (i64, Reverse<usize>) {
+        (self.count, Reverse(self.index))
+    }
+}
+
+impl<K> PartialEq for Ranked<'_, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.rank() == other.rank()
+    }
+}
+
+impl<K> Eq for Ranked<'_, K> {}
+
+impl<K> PartialOrd for Ranked<'_, K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K> Ord for Ranked<'_, K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.rank().cmp(&other.rank())
+    }
+}
+
+impl<K: Hash + Eq> Counter<K> {
+    pub fn new() -> Self {
+        Counter {
+            counts: PyDictionary::new(),
+        }
+    }
+
+    /// Python-equivalent counter[key]: 0 for a missing key.
+    pub fn get<Q>(&self, key: &Q) -> i64
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+
+    /// Python-equivalent counter[key] = count.
+    pub fn set_item(&mut self, key: K, count: i64) {
+        self.counts.set_item(key, count);
+    }
+
+    /// Python-equivalent counter[key] += delta.
+    pub fn add(&mut self, key: K, delta: i64) {
+        match self.counts.get_mut(&key) {
+            Some(count) => *count += delta,
+            None => self.counts.set_item(key, delta),
+        }
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.counts.contains_key(key)
+    }
+
+    /// Python-equivalent `del counter[key]`, returning the count.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<i64>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.counts.remove(key)
+    }
+
+    /// Python-equivalent Counter.update(iterable): counts each element.
+    pub fn update<I: IntoIterator<Item = K>>(&mut self, iterable: I) {
+        for key in iterable {
+            self.add(key, 1);
+        }
+    }
+
+    /// Python-equivalent Counter.subtract(iterable). Counts may go to zero
+    /// or below.
+    pub fn subtract<I: IntoIterator<Item = K>>(&mut self, iterable: I) {
+        for key in iterable {
+            self.add(key, -1);
+        }
+    }
+
+    /// Python-equivalent Counter.most_common(n): the `n` highest counts
+    /// (all of them for None), largest first, ties in insertion order.
+    /// A bounded min-heap keeps this O(len · log n) for small `n`.
+    pub fn most_common(&self, n: Option<usize>) -> Vec<(K, i64)>
+    where
+        K: Clone,
+    {
+        let ranked = self
+            .counts
+            .iter()
+            .enumerate()
+            .map(|(index, (key, &count))| Ranked { count, index, key });
+        let mut top: Vec<Ranked<'_, K>> = match n {
+            Some(n) if n < self.len() => {
+                if n == 0 {
+                    return Vec::new();
+                }
+                let mut heap = BinaryHeap::with_capacity(n);
+                for item in ranked {
+                    if heap.len() < n {
+                        heap.push(Reverse(item));
+                    } else if let Some(mut smallest) = heap.peek_mut() {
+                        if item > smallest.0 {
+                            *smallest = Reverse(item);
+                        }
+                    }
+                }
+                heap.into_iter().map(|Reverse(item)| item).collect()
+            }
+            _ => ranked.collect(),
+        };
+        top.sort_unstable_by(|a, b| b.cmp(a));
+        top.into_iter()
+            .map(|item| (item.key.clone(), item.count))
+            .collect()
+    }
+}
+
+impl<K: Hash + Eq> FromIterator<K> for Counter<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.update(iter);
+        counter
+    }
+}
+
+impl<K: Hash + Eq> Extend<K> for Counter<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<K: Hash + Eq> PartialEq for Counter<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.counts == other.counts
+    }
+}
+
+impl<K> PyLen for Counter<K> {
+    fn py_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K> PyTruthy for Counter<K> {
+    fn py_bool(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl<K: Hash + Eq> PyContains<K> for Counter<K> {
+    fn py_contains(&self, item: &K) -> bool {
+        self.contains_key(item)
+    }
+}
+
+impl<'a, K> IntoIterator for &'a Counter<K> {
+    type Item = (&'a K, &'a i64);
+    type IntoIter = Iter<'a, K, i64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K: fmt::Debug> fmt::Debug for Counter<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// `Counter({'a': 2, 'b': 1})` in most_common() order, or `Counter()`.
+impl<K: Hash + Eq + Clone + PyRepr> PyRepr for Counter<K> {
+    fn py_repr(&self) -> String {
+        if self.is_empty() {
+            return "Counter()".into();
+        }
+        let parts: Vec<String> = self
+            .most_common(None)
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k.py_repr(), v))
+            .collect();
+        format!("Counter({{{}}})", parts.join(", "))
+    }
+}
+
+impl<K: Hash + Eq + Clone + PyRepr> fmt::Display for Counter<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.py_repr())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_and_arithmetic() {
+        let mut c: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(c.get(&'a'), 5);
+        assert_eq!(c.get(&'z'), 0);
+        assert_eq!(c.total(), 11);
+        c.subtract("aaz".chars());
+        assert_eq!((c.get(&'a'), c.get(&'z')), (3, -1));
+        assert_eq!(c.elements().filter(|k| **k == 'z').count(), 0);
+        assert_eq!(c.elements().count(), 9);
+        assert_eq!(c.remove(&'z'), Some(-1));
+        assert_eq!(
+            c.to_string(),
+            "Counter({'a': 3, 'b': 2, 'r': 2, 'c': 1, 'd': 1})"
+        );
+        assert_eq!(Counter::<i64>::new().to_string(), "Counter()");
+    }
+
+    #[test]
+    fn most_common_ties_keep_insertion_order() {
+        let c: Counter<&str> = ["x", "y", "z", "y", "w", "z", "v"].into_iter().collect();
+        assert_eq!(c.most_common(Some(1)), vec![("y", 2)]);
+        assert_eq!(c.most_common(Some(3)), vec![("y", 2), ("z", 2), ("x", 1)]);
+        assert_eq!(c.most_common(Some(0)), vec![]);
+        let all = c.most_common(None);
+        assert_eq!(all, c.most_common(Some(100)));
+        assert_eq!(all, vec![("y", 2), ("z", 2), ("x", 1), ("w", 1), ("v", 1)]);
+        // The heap agrees with a full sort for every n.
+        let big: Counter<u32> = (0..500u32).map(|i| (i * 7919) % 61).collect();
+        let sorted = big.most_common(None);
+        for n in 0..=big.len() {
+            assert_eq!(big.most_common(Some(n)), sorted[..n]);
+        }
+    }
+}
@@ -0,0 +1,387 @@
+//! collections.deque over a VecDeque ring buffer.
+
+use alloc::collections::vec_deque::{self, VecDeque};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::list::normalize_index;
+use crate::types::{PyContains, PyIterable, PyLen, PyRepr, PySequence, PyTruthy};
+
+/// Python-equivalent collections.deque. With a `maxlen`, adding at one end
+/// discards from the other once the deque is full, as in CPython.
+/// Comparisons look only at the items, lexicographically, and not at maxlen.
+#[derive(Debug, Clone, Default)]
+pub struct Deque<T> {
+    items: VecDeque<T>,
+    maxlen: Option<usize>,
+}
+
+impl<T> Deque<T> {
+    pub fn new() -> Self {
+        Deque {
+            items: VecDeque::new(),
+            maxlen: None,
+        }
+    }
+
+    /// Python-equivalent deque(maxlen=n).
+    pub fn with_maxlen(maxlen: usize) -> Self {
+        Deque {
+            items: VecDeque::with_capacity(maxlen.min(1024)),
+            maxlen: Some(maxlen),
+        }
+    }
+
+    /// Python-equivalent deque.maxlen.
+    pub fn maxlen(&self) -> Option<usize> {
+        self.maxlen
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> vec_deque::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> vec_deque::IterMut<'_, T> {
+        self.items.iter_mut()
+    }
+
+    fn is_full(&self) -> bool {
+        self.maxlen.is_some_and(|max| self.items.len() >= max)
+    }
+
+    /// Python-equivalent deque.append(x): drops the leftmost item when full.
+    pub fn append(&mut self, value: T) {
+        if self.maxlen == Some(0) {
+            return;
+        }
+        if self.is_full() {
+            self.items.pop_front();
+        }
+        self.items.push_back(value);
+    }
+
+    /// Python-equivalent deque.appendleft(x): drops the rightmost item when
+    /// full.
+    pub fn appendleft(&mut self, value: T) {
+        if self.maxlen == Some(0) {
+            return;
+        }
+        if self.is_full() {
+            self.items.pop_back();
+        }
+        self.items.push_front(value);
+    }
+
+    /// Python-equivalent deque.extend(iterable).
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        match self.maxlen {
+            None => self.items.extend(iter),
+            Some(max) => {
+                for value in iter {
+                    if max == 0 {
+                        continue;
+                    }
+                    if self.items.len() == max {
+                        self.items.pop_front();
+                    }
+                    self.items.push_back(value);
+                }
+            }
+        }
+    }
+
+    /// Python-equivalent deque.extendleft(iterable): each item goes to the
+    /// front in turn, so the input ends up reversed.
+    pub fn extendleft<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let max = self.maxlen.unwrap_or(usize::MAX);
+        for value in iter {
+            if max == 0 {
+                continue;
+            }
+            if self.items.len() == max {
+                self.items.pop_back();
+            }
+            self.items.push_front(value);
+        }
+    }
+
+    /// Python-equivalent deque.pop().
+    pub fn pop(&mut self) -> PyResult<T> {
+        self.items
+            .pop_back()
+            .ok_or_else(|| PyException::index_error("pop from an empty deque"))
+    }
+
+    /// Python-equivalent deque.popleft().
+    pub fn popleft(&mut self) -> PyResult<T> {
+        self.items
+            .pop_front()
+            .ok_or_else(|| PyException::index_error("pop from an empty deque"))
+    }
+
+    /// Python-equivalent deque[index], with negative indices counting from
+    /// the right.
+    pub fn get(&self, index: i64) -> PyResult<&T> {
+        normalize_index(index, self.len())
+            .map(|i| &self.items[i])
+            .ok_or_else(|| PyException::index_error("deque index out of range"))
+    }
+
+    /// Python-equivalent deque[index] = value.
+    pub fn set(&mut self, index: i64, value: T) -> PyResult<()> {
+        let i = normalize_index(index, self.len())
+            .ok_or_else(|| PyException::index_error("deque index out of range"))?;
+        self.items[i] = value;
+        Ok(())
+    }
+
+    /// Python-equivalent deque.insert(i, x): out-of-range indices clamp to
+    /// the ends; a full bounded deque raises IndexError.
+    pub fn insert(&mut self, index: i64, value: T) -> PyResult<()> {
+        if self.is_full() {
+            return Err(PyException::index_error(
+                "deque already at its maximum size",
+            ));
+        }
+        let len = self.len() as i64;
+        let i = if index < 0 {
+            (index + len).max(0)
+        } else {
+            index.min(len)
+        };
+        self.items.insert(i as usize, value);
+        Ok(())
+    }
+
+    /// Python-equivalent deque.rotate(n): positive `n` moves items from the
+    /// right end to the left.
+    pub fn rotate(&mut self, n: i64) {
+        let len = self.items.len();
+        if len == 0 {
+            return;
+        }
+        let n = n.rem_euclid(len as i64) as usize;
+        self.items.rotate_right(n);
+    }
+
+    /// Python-equivalent deque.reverse(), in place.
+    pub fn reverse(&mut self) {
+        self.items.make_contiguous().reverse();
+    }
+
+    /// Python-equivalent deque.clear().
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+}
+
+impl<T: PartialEq> Deque<T> {
+    /// Python-equivalent deque.count(x).
+    pub fn count(&self, value: &T) -> usize {
+        self.items.iter().filter(|item| *item == value).count()
+    }
+
+    /// Python-equivalent deque.remove(x): the first occurrence only.
+    pub fn remove(&mut self, value: &T) -> PyResult<()>
+    where
+        T: PyRepr,
+    {
+        let i = self
+            .items
+            .iter()
+            .position(|item| item == value)
+            .ok_or_else(|| {
+                PyException::value_error(format!("{} is not in deque", value.py_repr()))
+            })?;
+        self.items.remove(i);
+        Ok(())
+    }
+}
+
+impl<T> FromIterator<T> for Deque<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Deque {
+            items: iter.into_iter().collect(),
+            maxlen: None,
+        }
+    }
+}
+
+impl<T> Extend<T> for Deque<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        Deque::extend(self, iter);
+    }
+}
+
+impl<T> IntoIterator for Deque<T> {
+    type Item = T;
+    type IntoIter = vec_deque::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Deque<T> {
+    type Item = &'a T;
+    type IntoIter = vec_deque::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut Deque<T> {
+    type Item = &'a mut T;
+    type IntoIter = vec_deque::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter_mut()
+    }
+}
+
+impl<T> PyLen for Deque<T> {
+    fn py_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> PyTruthy for Deque<T> {
+    fn py_bool(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl<T: PartialEq> PyContains<T> for Deque<T> {
+    fn py_contains(&self, item: &T) -> bool {
+        self.items.contains(item)
+    }
+}
+
+impl<T> PyIterable for Deque<T> {
+    type Item<'a>
+        = &'a T
+    where
+        T: 'a;
+    type Iter<'a>
+        = vec_deque::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn py_iter(&self) -> Self::Iter<'_> {
+        self.items.iter()
+    }
+}
+
+impl<T> PySequence for Deque<T> {
+    fn py_getitem(&self, index: i64) -> PyResult<&T> {
+        self.get(index)
+    }
+}
+
+/// `deque([1, 2])`, or `deque([1, 2], maxlen=3)` when bounded.
+impl<T: PyRepr> PyRepr for Deque<T> {
+    fn py_repr(&self) -> String {
+        let parts: Vec<String> = self.items.iter().map(PyRepr::py_repr).collect();
+        match self.maxlen {
+            Some(max) => format!("deque([{}], maxlen={})", parts.join(", "), max),
+            None => format!("deque([{}])", parts.join(", ")),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for Deque<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl<T: Eq> Eq for Deque<T> {}
+
+impl<T: PartialOrd> PartialOrd for Deque<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.items.partial_cmp(&other.items)
+    }
+}
+
+impl<T: Ord> Ord for Deque<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.items.cmp(&other.items)
+    }
+}
+
+impl<T: Hash> Hash for Deque<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.items.hash(state);
+    }
+}
+
+impl<T: PyRepr> fmt::Display for Deque<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.py_repr())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maxlen_trims_the_opposite_end() {
+        let mut d = Deque::with_maxlen(3);
+        d.extend([1, 2, 3]);
+        d.extendleft([4, 5]);
+        assert_eq!(d.to_string(), "deque([5, 4, 1], maxlen=3)");
+        d.extend([6]);
+        assert_eq!(d.iter().copied().collect::<Vec<_>>(), vec![4, 1, 6]);
+        d.rotate(1);
+        assert_eq!(d.to_string(), "deque([6, 4, 1], maxlen=3)");
+        d.rotate(-2);
+        assert_eq!(d.to_string(), "deque([1, 6, 4], maxlen=3)");
+        assert!(d.insert(0, 1).unwrap_err().is("IndexError"));
+        let mut none = Deque::with_maxlen(0);
+        none.extendleft([1, 2]);
+        assert_eq!(none.to_string(), "deque([], maxlen=0)");
+    }
+
+    #[test]
+    fn comparisons_ignore_maxlen() {
+        let bounded: Deque<i64> = {
+            let mut d = Deque::with_maxlen(5);
+            d.extend([1, 2]);
+            d
+        };
+        let unbounded: Deque<i64> = [1, 2].into_iter().collect();
+        assert_eq!(bounded, unbounded);
+        assert!(unbounded < [1, 3].into_iter().collect());
+        assert!(unbounded > [1].into_iter().collect());
+    }
+
+    #[test]
+    fn indexing_and_errors() {
+        let mut d: Deque<i32> = [1, 2, 3].into_iter().collect();
+        d.set(-1, 9).unwrap();
+        assert_eq!(*d.get(-3).unwrap(), 1);
+        assert!(d.get(3).unwrap_err().is("IndexError"));
+        d.reverse();
+        assert_eq!(d.to_string(), "deque([9, 2, 1])");
+        assert!(d.remove(&5).unwrap_err().is("ValueError"));
+        d.remove(&2).unwrap();
+        assert_eq!(d.popleft().unwrap(), 9);
+        assert_eq!(d.pop().unwrap(), 1);
+        assert!(d.pop().unwrap_err().is("IndexError"));
+    }
+}
@@ -0,0 +1,9 @@
+//! Python-equivalent collections module.
+
+pub mod counter;
+pub mod deque;
+pub mod ordered_dict;
+
+pub use counter::Counter;
+pub use deque::Deque;
+pub use ordered_dict::OrderedDict;
@@ -0,0 +1,567 @@
+//! collections.OrderedDict over a doubly linked list of hash-indexed slots,
+//! so removal and move_to_end() relink in O(1) instead of shifting keys.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::Hash;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::hash::hash_key;
+use crate::types::table::IndexTable;
+use crate::types::{
+    ItemsView, KeysView, PyContains, PyDictionary, PyIterable, PyLen, PyMapping, PyRepr, PyTruthy,
+    ValuesView,
+};
+
+/// End-of-list marker for `prev`/`next`.
+const NIL: usize = usize::MAX;
+
+#[derive(Clone)]
+struct Node<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+    prev: usize,
+    next: usize,
+}
+
+/// Python-equivalent collections.OrderedDict. Unlike PyDictionary, equality
+/// between two OrderedDicts is order-sensitive; against a plain dict it is
+/// not.
+#[derive(Clone)]
+pub struct OrderedDict<K, V> {
+    slots: Vec<Option<Node<K, V>>>,
+    /// Vacated slots, reused before the vector grows.
+    free: Vec<usize>,
+    table: IndexTable,
+    head: usize,
+    tail: usize,
+    len: usize,
+}
+
+impl<K, V> Default for OrderedDict<K, V> {
+    fn default() -> Self {
+        OrderedDict {
+            slots: Vec::new(),
+            free: Vec::new(),
+            table: IndexTable::new(),
+            head: NIL,
+            tail: NIL,
+            len: 0,
+        }
+    }
+}
+
+impl<K, V> OrderedDict<K, V> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates `(key, value)` pairs from first to last.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            slots: &self.slots,
+            front: self.head,
+            back: self.tail,
+            remaining: self.len,
+        }
+    }
+
+    /// Python-equivalent reversed(od): from last to first.
+    pub fn reversed(&self) -> core::iter::Rev<Iter<'_, K, V>> {
+        self.iter().rev()
+    }
+
+    /// Python-equivalent od.keys(): a borrowing view in link order.
+    pub fn keys(&self) -> KeysView<Iter<'_, K, V>> {
+        KeysView(self.iter())
+    }
+
+    /// Python-equivalent od.values().
+    pub fn values(&self) -> ValuesView<Iter<'_, K, V>> {
+        ValuesView(self.iter())
+    }
+
+    /// Python-equivalent od.items().
+    pub fn items(&self) -> ItemsView<Iter<'_, K, V>> {
+        ItemsView(self.iter())
+    }
+
+    fn node(&self, i: usize) -> &Node<K, V> {
+        self.slots[i].as_ref().expect("linked slot is occupied")
+    }
+
+    fn node_mut(&mut self, i: usize) -> &mut Node<K, V> {
+        self.slots[i].as_mut().expect("linked slot is occupied")
+    }
+
+    fn unlink(&mut self, i: usize) {
+        let (prev, next) = {
+            let node = self.node(i);
+            (node.prev, node.next)
+        };
+        match prev {
+            NIL => self.head = next,
+            p => self.node_mut(p).next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            n => self.node_mut(n).prev = prev,
+        }
+    }
+
+    fn link_back(&mut self, i: usize) {
+        let tail = self.tail;
+        let node = self.node_mut(i);
+        node.prev = tail;
+        node.next = NIL;
+        match tail {
+            NIL => self.head = i,
+            t => self.node_mut(t).next = i,
+        }
+        self.tail = i;
+    }
+
+    fn link_front(&mut self, i: usize) {
+        let head = self.head;
+        let node = self.node_mut(i);
+        node.prev = NIL;
+        node.next = head;
+        match head {
+            NIL => self.tail = i,
+            h => self.node_mut(h).prev = i,
+        }
+        self.head = i;
+    }
+
+    /// Unlinks slot `i` and frees it; the caller has dropped it from the table.
+    fn take(&mut self, i: usize) -> Node<K, V> {
+        self.unlink(i);
+        self.len -= 1;
+        self.free.push(i);
+        self.slots[i].take().expect("linked slot is occupied")
+    }
+}
+
+impl<K: Hash + Eq, V> OrderedDict<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn position<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slots = &self.slots;
+        self.table.find(hash_key(key), |i| {
+            slots[i].as_ref().is_some_and(|n| n.key.borrow() == key)
+        })
+    }
+
+    /// Python-equivalent key in od.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.position(key).is_some()
+    }
+
+    /// Python-equivalent od.get(key).
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.position(key).map(|i| &self.node(i).value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.position(key)?;
+        Some(&mut self.node_mut(i).value)
+    }
+
+    /// Python-equivalent od[key] = value: new keys go last, existing keys
+    /// keep their place.
+    pub fn set_item(&mut self, key: K, value: V) {
+        if let Some(i) = self.position(&key) {
+            self.node_mut(i).value = value;
+            return;
+        }
+        let hash = hash_key(&key);
+        let node = Node {
+            hash,
+            key,
+            value,
+            prev: NIL,
+            next: NIL,
+        };
+        let i = match self.free.pop() {
+            Some(i) => {
+                self.slots[i] = Some(node);
+                i
+            }
+            None => {
+                self.slots.push(Some(node));
+                self.slots.len() - 1
+            }
+        };
+        self.table.insert(hash, i);
+        self.link_back(i);
+        self.len += 1;
+    }
+
+    /// Python-equivalent od.update(other).
+    pub fn update<I: IntoIterator<Item = (K, V)>>(&mut self, pairs: I) {
+        for (key, value) in pairs {
+            self.set_item(key, value);
+        }
+    }
+
+    /// Python-equivalent od.clear().
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Removes `key`, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let slots = &self.slots;
+        let i = self.table.remove(hash_key(key), |i| {
+            slots[i].as_ref().is_some_and(|n| n.key.borrow() == key)
+        })?;
+        Some(self.take(i).value)
+    }
+
+    /// Python-equivalent od.pop(key): raises KeyError when missing.
+    pub fn pop(&mut self, key: &K) -> PyResult<V>
+    where
+        K: PyRepr,
+    {
+        self.remove(key).ok_or_else(|| PyException::key_error(key))
+    }
+
+    /// Python-equivalent od.popitem(last=True): LIFO order when `last`,
+    /// FIFO otherwise.
+    pub fn popitem(&mut self, last: bool) -> PyResult<(K, V)> {
+        let i = if last { self.tail } else { self.head };
+        if i == NIL {
+            return Err(PyException::key_error("dictionary is empty"));
+        }
+        self.table.remove(self.node(i).hash, |j| j == i);
+        let node = self.take(i);
+        Ok((node.key, node.value))
+    }
+
+    /// Looks up `key` and moves it last, as an LRU cache does on a hit.
+    pub(crate) fn get_and_move_to_end<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.position(key)?;
+        self.unlink(i);
+        self.link_back(i);
+        Some(&self.node(i).value)
+    }
+
+    /// Python-equivalent od.move_to_end(key, last=True).
+    pub fn move_to_end(&mut self, key: &K, last: bool) -> PyResult<()>
+    where
+        K: PyRepr,
+    {
+        let i = self
+            .position(key)
+            .ok_or_else(|| PyException::key_error(key))?;
+        self.unlink(i);
+        if last {
+            self.link_back(i);
+        } else {
+            self.link_front(i);
+        }
+        Ok(())
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> OrderedDict<K, V> {
+    /// Python-equivalent od[key]: raises KeyError when missing.
+    pub fn get_item(&self, key: &K) -> PyResult<V>
+    where
+        K: PyRepr,
+    {
+        self.get(key)
+            .cloned()
+            .ok_or_else(|| PyException::key_error(key))
+    }
+}
+
+/// Borrowing iterator over an OrderedDict, in link order.
+pub struct Iter<'a, K, V> {
+    slots: &'a [Option<Node<K, V>>],
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter { ..*self }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.slots[self.front].as_ref()?;
+        self.front = node.next;
+        self.remaining -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.slots[self.back].as_ref()?;
+        self.back = node.prev;
+        self.remaining -= 1;
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+/// Owning iterator over an OrderedDict, in link order.
+pub struct IntoIter<K, V> {
+    slots: Vec<Option<Node<K, V>>>,
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.slots[self.front].take()?;
+        self.front = node.next;
+        self.remaining -= 1;
+        Some((node.key, node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<(K, V)> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let node = self.slots[self.back].take()?;
+        self.back = node.prev;
+        self.remaining -= 1;
+        Some((node.key, node.value))
+    }
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+/// Iterator over an OrderedDict's keys: what `for key in od` visits.
+pub type Keys<'a, K, V> = core::iter::Map<Iter<'a, K, V>, fn((&'a K, &'a V)) -> &'a K>;
+
+impl<K, V> PyLen for OrderedDict<K, V> {
+    fn py_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> PyTruthy for OrderedDict<K, V> {
+    fn py_bool(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl<K: Hash + Eq, V> PyContains<K> for OrderedDict<K, V> {
+    fn py_contains(&self, item: &K) -> bool {
+        self.contains_key(item)
+    }
+}
+
+impl<K, V> PyIterable for OrderedDict<K, V> {
+    type Item<'a>
+        = &'a K
+    where
+        Self: 'a;
+    type Iter<'a>
+        = Keys<'a, K, V>
+    where
+        Self: 'a;
+
+    fn py_iter(&self) -> Self::Iter<'_> {
+        let key: for<'a> fn((&'a K, &'a V)) -> &'a K = |(k, _)| k;
+        self.iter().map(key)
+    }
+}
+
+impl<K: Hash + Eq + PyRepr, V> PyMapping for OrderedDict<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn py_getitem(&self, key: &K) -> PyResult<&V> {
+        self.get(key).ok_or_else(|| PyException::key_error(key))
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a OrderedDict<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// OrderedDicts compare equal only when their pairs match in order.
+impl<K: PartialEq, V: PartialEq> PartialEq for OrderedDict<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().eq(other.iter())
+    }
+}
+
+impl<K: Eq, V: Eq> Eq for OrderedDict<K, V> {}
+
+/// Against a plain dict, order is ignored.
+impl<K: Hash + Eq, V: PartialEq> PartialEq<PyDictionary<K, V>> for OrderedDict<K, V> {
+    fn eq(&self, other: &PyDictionary<K, V>) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: Hash + Eq, V: PartialEq> PartialEq<OrderedDict<K, V>> for PyDictionary<K, V> {
+    fn eq(&self, other: &OrderedDict<K, V>) -> bool {
+        other == self
+    }
+}
+
+impl<K, V> IntoIterator for OrderedDict<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            slots: self.slots,
+            front: self.head,
+            back: self.tail,
+            remaining: self.len,
+        }
+    }
+}
+
+impl<K: Hash + Eq, V> Extend<(K, V)> for OrderedDict<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for OrderedDict<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut dict = OrderedDict::new();
+        dict.update(iter);
+        dict
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for OrderedDict<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// `OrderedDict({'a': 1})`, or `OrderedDict()`, as CPython 3.12 prints it.
+impl<K: PyRepr, V: PyRepr> PyRepr for OrderedDict<K, V> {
+    fn py_repr(&self) -> String {
+        if self.is_empty() {
+            return "OrderedDict()".into();
+        }
+        let parts: Vec<String> = self
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k.py_repr(), v.py_repr()))
+            .collect();
+        format!("OrderedDict({{{}}})", parts.join(", "))
+    }
+}
+
+impl<K: PyRepr, V: PyRepr> fmt::Display for OrderedDict<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.py_repr())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reordering_and_slot_reuse() {
+        let mut d: OrderedDict<_, _> = (0..5).map(|i| (i, i * 10)).collect();
+        d.move_to_end(&1, true).unwrap();
+        d.move_to_end(&4, false).unwrap();
+        assert_eq!(d.keys().to_list(), vec![4, 0, 2, 3, 1]);
+        assert_eq!(d.remove(&2), Some(20));
+        d.set_item(7, 70);
+        d.set_item(0, 1);
+        assert_eq!(d.keys().to_list(), vec![4, 0, 3, 1, 7]);
+        assert_eq!(
+            d.reversed().map(|(k, _)| *k).collect::<Vec<_>>(),
+            vec![7, 1, 3, 0, 4]
+        );
+        assert_eq!(d.popitem(true).unwrap(), (7, 70));
+        assert_eq!(d.popitem(false).unwrap(), (4, 40));
+        assert_eq!(d.get(&0), Some(&1));
+        assert!(d.move_to_end(&9, true).unwrap_err().is("KeyError"));
+        d.clear();
+        assert!(d.popitem(true).unwrap_err().is("KeyError"));
+    }
+
+    #[test]
+    fn equality_semantics_match_cpython() {
+        let a: OrderedDict<_, _> = [("a", 1), ("b", 2)].into_iter().collect();
+        let b: OrderedDict<_, _> = [("b", 2), ("a", 1)].into_iter().collect();
+        let plain: PyDictionary<_, _> = [("b", 2), ("a", 1)].into_iter().collect();
+        assert_ne!(a, b);
+        assert_eq!(a, plain);
+        assert_eq!(plain, b);
+        assert_eq!(a.py_repr(), "OrderedDict({'a': 1, 'b': 2})");
+        assert_eq!(OrderedDict::<i32, i32>::new().to_string(), "OrderedDict()");
+    }
+}
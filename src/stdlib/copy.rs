@@ -0,0 +1,269 @@
+//! Python-equivalent copy module.
+//!
+//! Runtime containers own their elements, so a shallow copy is a `clone()`:
+//! shared objects, which compiled code holds as `Rc<RefCell<T>>`, stay
+//! shared. [`deepcopy`] also copies what those handles point to. Like
+//! CPython's, it keeps a memo keyed by object address, so an object reached
+//! twice is copied once and reference cycles are reproduced instead of
+//! recursing forever.
+
+use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::cell::RefCell;
+use core::hash::Hash;
+
+use crate::stdlib::collections::{Deque, OrderedDict};
+use crate::types::{PyBytes, PyDictionary, PyFunction, PyList, PySet, PyStr, PyTuple, PyValue};
+
+/// The `memo` of a deepcopy() call: the copy already made of each shared
+/// object, by the address of the original.
+#[derive(Default)]
+pub struct Memo {
+    copies: BTreeMap<usize, Box<dyn Any>>,
+}
+
+impl Memo {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The copy made of the object at `addr`, if any.
+    pub fn get<T: Clone + 'static>(&self, addr: usize) -> Option<T> {
+        self.copies.get(&addr)?.downcast_ref::<T>().cloned()
+    }
+
+    pub fn insert<T: 'static>(&mut self, addr: usize, copy: T) {
+        self.copies.insert(addr, Box::new(copy));
+    }
+}
+
+/// Python-equivalent `__deepcopy__`. Immutable atoms (numbers, str, bytes,
+/// functions) return themselves.
+pub trait PyDeepCopy: Clone {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self;
+}
+
+/// Python-equivalent copy.copy(x).
+pub fn copy<T: Clone>(x: &T) -> T {
+    x.clone()
+}
+
+/// Python-equivalent copy.deepcopy(x).
+pub fn deepcopy<T: PyDeepCopy>(x: &T) -> T {
+    x.py_deepcopy(&mut Memo::new())
+}
+
+macro_rules! impl_atomic {
+    ($($t:ty),*) => {
+        $(impl PyDeepCopy for $t {
+            fn py_deepcopy(&self, _memo: &mut Memo) -> Self {
+                self.clone()
+            }
+        })*
+    };
+}
+
+impl_atomic!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_atomic!(f32, f64, bool, char, ());
+impl_atomic!(&'static str, String, PyStr, PyBytes, PyFunction);
+
+impl<T: PyDeepCopy> PyDeepCopy for Option<T> {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        self.as_ref().map(|value| value.py_deepcopy(memo))
+    }
+}
+
+impl<T: PyDeepCopy> PyDeepCopy for Box<T> {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        Box::new((**self).py_deepcopy(memo))
+    }
+}
+
+impl<T: PyDeepCopy> PyDeepCopy for Vec<T> {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        self.iter().map(|item| item.py_deepcopy(memo)).collect()
+    }
+}
+
+impl<T: PyDeepCopy> PyDeepCopy for PyList<T> {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        PyList::from_vec(self.iter().map(|item| item.py_deepcopy(memo)).collect())
+    }
+}
+
+impl<T: PyDeepCopy> PyDeepCopy for PyTuple<T> {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        PyTuple::from_vec(self.iter().map(|item| item.py_deepcopy(memo)).collect())
+    }
+}
+
+impl<K: PyDeepCopy + Hash + Eq, V: PyDeepCopy> PyDeepCopy for PyDictionary<K, V> {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        self.iter()
+            .map(|(key, value)| (key.py_deepcopy(memo), value.py_deepcopy(memo)))
+            .collect()
+    }
+}
+
+impl<T: PyDeepCopy + Hash + Eq> PyDeepCopy for PySet<T> {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        self.iter().map(|item| item.py_deepcopy(memo)).collect()
+    }
+}
+
+impl<T: PyDeepCopy> PyDeepCopy for Deque<T> {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        let mut out = match self.maxlen() {
+            Some(maxlen) => Deque::with_maxlen(maxlen),
+            None => Deque::new(),
+        };
+        out.extend(self.iter().map(|item| item.py_deepcopy(memo)));
+        out
+    }
+}
+
+impl<K: PyDeepCopy + Hash + Eq, V: PyDeepCopy> PyDeepCopy for OrderedDict<K, V> {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        self.iter()
+            .map(|(key, value)| (key.py_deepcopy(memo), value.py_deepcopy(memo)))
+            .collect()
+    }
+}
+
+impl PyDeepCopy for PyValue {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        match self {
+            PyValue::List(list) => PyValue::List(list.py_deepcopy(memo)),
+            PyValue::Tuple(tuple) => PyValue::Tuple(tuple.py_deepcopy(memo)),
+            PyValue::Dict(dict) => PyValue::Dict(dict.py_deepcopy(memo)),
+            PyValue::Set(set) => PyValue::Set(set.py_deepcopy(memo)),
+            atom => atom.clone(),
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident)+) => {
+        impl<$($name: PyDeepCopy),+> PyDeepCopy for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+                let ($($name,)+) = self;
+                ($($name.py_deepcopy(memo),)+)
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A B);
+impl_tuple!(A B C);
+impl_tuple!(A B C D);
+
+/// An immutable shared object: copied once however often it is reached.
+impl<T: PyDeepCopy + 'static> PyDeepCopy for Rc<T> {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        let addr = Rc::as_ptr(self) as usize;
+        if let Some(copy) = memo.get::<Rc<T>>(addr) {
+            return copy;
+        }
+        let copy = Rc::new((**self).py_deepcopy(memo));
+        memo.insert(addr, copy.clone());
+        copy
+    }
+}
+
+/// A mutable shared object. The new handle enters the memo before its
+/// contents are copied, so a cycle back to it finds the copy, as with
+/// CPython's `_reconstruct`.
+impl<T: PyDeepCopy + 'static> PyDeepCopy for Rc<RefCell<T>> {
+    fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+        let addr = Rc::as_ptr(self) as usize;
+        if let Some(copy) = memo.get::<Rc<RefCell<T>>>(addr) {
+            return copy;
+        }
+        let copy = Rc::new(RefCell::new(self.borrow().clone()));
+        memo.insert(addr, copy.clone());
+        let contents = self.borrow().py_deepcopy(memo);
+        *copy.borrow_mut() = contents;
+        copy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Node {
+        label: i64,
+        next: Option<Rc<RefCell<Node>>>,
+    }
+
+    impl PyDeepCopy for Node {
+        fn py_deepcopy(&self, memo: &mut Memo) -> Self {
+            Node {
+                label: self.label,
+                next: self.next.py_deepcopy(memo),
+            }
+        }
+    }
+
+    #[test]
+    fn nested_containers_are_independent() {
+        let mut inner = PyDictionary::new();
+        inner.set_item(PyValue::from("xs"), PyValue::from(alloc::vec![1, 2]));
+        let original = PyList::from_vec(alloc::vec![PyValue::Dict(inner)]);
+        let mut copied = deepcopy(&original);
+        assert_eq!(copied, original);
+        if let PyValue::Dict(dict) = &mut copied.as_mut_slice()[0] {
+            dict.set_item(PyValue::from("xs"), PyValue::None);
+        }
+        assert_ne!(copied, original);
+
+        let mut d = Deque::with_maxlen(2);
+        d.extend([PyList::from_vec(alloc::vec![1])]);
+        assert_eq!(deepcopy(&d).maxlen(), Some(2));
+    }
+
+    #[test]
+    fn shallow_copy_shares_objects() {
+        let shared = Rc::new(RefCell::new(PyList::from_vec(alloc::vec![1])));
+        let outer = PyList::from_vec(alloc::vec![shared.clone(), shared.clone()]);
+
+        let shallow = copy(&outer);
+        assert!(Rc::ptr_eq(&shallow.as_slice()[0], &shared));
+
+        let deep = deepcopy(&outer);
+        let (a, b) = (&deep.as_slice()[0], &deep.as_slice()[1]);
+        assert!(!Rc::ptr_eq(a, &shared));
+        assert!(Rc::ptr_eq(a, b));
+        a.borrow_mut().append(2);
+        assert_eq!(shared.borrow().len(), 1);
+    }
+
+    #[test]
+    fn cycles_are_reproduced() {
+        let a = Rc::new(RefCell::new(Node {
+            label: 1,
+            next: None,
+        }));
+        let b = Rc::new(RefCell::new(Node {
+            label: 2,
+            next: Some(a.clone()),
+        }));
+        a.borrow_mut().next = Some(b.clone());
+
+        let a2 = deepcopy(&a);
+        let b2 = a2.borrow().next.clone().unwrap();
+        assert!(!Rc::ptr_eq(&a2, &a) && !Rc::ptr_eq(&b2, &b));
+        assert_eq!(b2.borrow().label, 2);
+        assert!(Rc::ptr_eq(b2.borrow().next.as_ref().unwrap(), &a2));
+
+        a.borrow_mut().next = None;
+        a2.borrow_mut().next = None;
+    }
+}
@@ -0,0 +1,147 @@
+//! Python-equivalent cProfile module: a deterministic profiler built on the
+//! sys.setprofile() hook, timing every call of functions that keep an
+//! inspect frame. Report with [`pstats::Stats`](crate::stdlib::pstats::Stats).
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::stdlib::inspect::{Code, Frame};
+use crate::stdlib::sys::{self, HookFunction, TraceEvent};
+
+/// Totals for one function, as pstats reports them.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct FunctionStats {
+    /// Calls, including recursive ones.
+    pub ncalls: u64,
+    /// Calls that were not recursive.
+    pub pcalls: u64,
+    /// Seconds spent in the function itself, excluding callees.
+    pub tottime: f64,
+    /// Seconds spent in the function and its callees.
+    pub cumtime: f64,
+}
+
+#[derive(Default)]
+struct Recorder {
+    /// Calls in progress: the function, when it started, and the time its
+    /// callees have taken so far.
+    stack: Vec<(Code, Instant, Duration)>,
+    index: HashMap<Code, usize>,
+    stats: Vec<(Code, FunctionStats)>,
+}
+
+impl Recorder {
+    fn record(&mut self, frame: &Frame, event: TraceEvent) {
+        let now = Instant::now();
+        match event {
+            TraceEvent::Call => self.stack.push((frame.f_code, now, Duration::ZERO)),
+            TraceEvent::Return => {
+                // A return from a call already running when profiling began.
+                let Some((code, start, callees)) = self.stack.pop() else {
+                    return;
+                };
+                let elapsed = now - start;
+                let recursive = self.stack.iter().any(|(outer, ..)| *outer == code);
+                if let Some(caller) = self.stack.last_mut() {
+                    caller.2 += elapsed;
+                }
+                let slot = *self.index.entry(code).or_insert_with(|| {
+                    self.stats.push((code, FunctionStats::default()));
+                    self.stats.len() - 1
+                });
+                let stats = &mut self.stats[slot].1;
+                stats.ncalls += 1;
+                stats.tottime += elapsed.saturating_sub(callees).as_secs_f64();
+                if !recursive {
+                    stats.pcalls += 1;
+                    stats.cumtime += elapsed.as_secs_f64();
+                }
+            }
+            TraceEvent::Line => {}
+        }
+    }
+}
+
+/// Python-equivalent cProfile.Profile. Profiling covers the thread that
+/// calls [`enable`](Self::enable), and replaces any sys.setprofile() hook
+/// there until [`disable`](Self::disable).
+#[derive(Default)]
+pub struct Profile {
+    recorder: Rc<RefCell<Recorder>>,
+}
+
+impl Profile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Python-equivalent Profile.enable().
+    pub fn enable(&self) {
+        let recorder = self.recorder.clone();
+        let hook: HookFunction =
+            Rc::new(move |frame: &Frame, event| recorder.borrow_mut().record(frame, event));
+        sys::setprofile(Some(hook));
+    }
+
+    /// Python-equivalent Profile.disable().
+    pub fn disable(&self) {
+        sys::setprofile(None);
+        self.recorder.borrow_mut().stack.clear();
+    }
+
+    /// Python-equivalent Profile.runcall(func).
+    pub fn runcall<R, F: FnOnce() -> R>(&self, func: F) -> R {
+        self.enable();
+        let result = func();
+        self.disable();
+        result
+    }
+
+    /// Python-equivalent Profile.clear().
+    pub fn clear(&self) {
+        *self.recorder.borrow_mut() = Recorder::default();
+    }
+
+    /// The totals so far, per function, in order of first return.
+    pub fn stats(&self) -> Vec<(Code, FunctionStats)> {
+        self.recorder.borrow().stats.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::inspect::enter_frame;
+
+    fn fib(n: u32) -> u32 {
+        let _frame = enter_frame("fib", "bench.py", 2);
+        if n < 2 {
+            n
+        } else {
+            fib(n - 1) + fib(n - 2)
+        }
+    }
+
+    fn main() -> u32 {
+        let _frame = enter_frame("main", "bench.py", 5);
+        fib(5)
+    }
+
+    #[test]
+    fn counts_calls_and_recursion() {
+        let _outside = enter_frame("setup", "bench.py", 1);
+        let profile = Profile::new();
+        assert_eq!(profile.runcall(main), 5);
+        assert!(sys::getprofile().is_none());
+        let stats = profile.stats();
+        let names: Vec<&str> = stats.iter().map(|(code, _)| code.co_name).collect();
+        assert_eq!(names, ["fib", "main"]);
+        let (fib_stats, main_stats) = (stats[0].1, stats[1].1);
+        assert_eq!((fib_stats.ncalls, fib_stats.pcalls), (15, 1));
+        assert_eq!((main_stats.ncalls, main_stats.pcalls), (1, 1));
+        assert!(main_stats.cumtime >= fib_stats.cumtime);
+        assert!(main_stats.tottime <= main_stats.cumtime);
+    }
+}
@@ -0,0 +1,375 @@
+//! Python-equivalent dataclasses module: the runtime half of compiled
+//! `@dataclass` classes.
+//!
+//! [`py_dataclass!`](crate::py_dataclass) declares the struct and derives
+//! `__init__`, `__repr__`, `__eq__`, the optional ordering and frozen
+//! behaviour, and the fields() metadata from its field list. Generated code
+//! reads and assigns fields directly; the attribute protocol covers dynamic
+//! getattr() and setattr().
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::object::no_attribute;
+use crate::types::{PyDictionary, PyRepr, PyTuple, PyTyped, PyValue};
+
+/// How a field gets its value when `__init__` is not given one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldDefault {
+    /// dataclasses.MISSING: the argument is required.
+    Missing,
+    /// `field: T = value`.
+    Value,
+    /// `field(default_factory=f)`: called for every new instance.
+    Factory,
+}
+
+/// Python-equivalent dataclasses.Field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field {
+    pub name: &'static str,
+    /// The annotation, as written in the Rust declaration.
+    pub type_name: &'static str,
+    pub default: FieldDefault,
+}
+
+/// Implemented by [`py_dataclass!`](crate::py_dataclass) for each compiled
+/// dataclass.
+pub trait PyDataclass: PyTyped {
+    const NAME: &'static str;
+    const FIELDS: &'static [Field];
+    const FROZEN: bool;
+
+    /// Calls `visit` with each field's name and value, in declaration order.
+    fn visit_fields(&self, visit: &mut dyn FnMut(&'static str, PyValue));
+}
+
+/// Python-equivalent dataclasses.fields(cls).
+pub fn fields<T: PyDataclass>() -> &'static [Field] {
+    T::FIELDS
+}
+
+/// Python-equivalent dataclasses.asdict(obj). Nested dataclasses convert
+/// to dicts too, since their `PyValue` form is their asdict().
+pub fn asdict<T: PyDataclass + ?Sized>(obj: &T) -> PyDictionary<String, PyValue> {
+    let mut out = PyDictionary::new();
+    obj.visit_fields(&mut |name, value| out.set_item(name.to_string(), value));
+    out
+}
+
+/// asdict(obj) as a `PyValue`: how a dataclass converts when stored in a
+/// field of another one.
+pub fn asdict_value<T: PyDataclass + ?Sized>(obj: &T) -> PyValue {
+    let mut out = PyDictionary::new();
+    obj.visit_fields(&mut |name, value| out.set_item(PyValue::from(name), value));
+    PyValue::Dict(out)
+}
+
+/// Python-equivalent dataclasses.astuple(obj).
+pub fn astuple<T: PyDataclass + ?Sized>(obj: &T) -> PyTuple<PyValue> {
+    let mut out = Vec::with_capacity(T::FIELDS.len());
+    obj.visit_fields(&mut |_, value| out.push(value));
+    PyTuple::from_vec(out)
+}
+
+/// The generated `__repr__`: `Point(x=1, y=2)`.
+pub fn repr_fields(class: &str, fields: &[(&str, &dyn PyRepr)]) -> String {
+    let parts: Vec<String> = fields
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value.py_repr()))
+        .collect();
+    format!("{}({})", class, parts.join(", "))
+}
+
+/// dataclasses.FrozenInstanceError for assigning or deleting `name`.
+pub fn frozen_error(action: &str, name: &str) -> PyException {
+    PyException::new(
+        "FrozenInstanceError",
+        format!("cannot {} field '{}'", action, name),
+    )
+}
+
+/// The generated `__setattr__`/`__delattr__` for dynamic access: frozen
+/// instances refuse every name; others have no `__dict__` to fall back on,
+/// like a class with `__slots__`.
+pub fn reject_setattr<T: PyDataclass + ?Sized>(action: &str, name: &str) -> PyResult<()> {
+    if T::FROZEN {
+        Err(frozen_error(action, name))
+    } else {
+        Err(no_attribute(T::NAME, name))
+    }
+}
+
+/// The generated `__dir__`: the field names.
+pub fn field_names<T: PyDataclass + ?Sized>() -> Vec<String> {
+    T::FIELDS.iter().map(|f| f.name.to_string()).collect()
+}
+
+/// Declares a compiled `@dataclass` and derives its protocol impls.
+///
+/// Fields are written `name: Type`, `name: Type = default` or
+/// `name: Type => factory` (a `default_factory`). `new()` takes every field
+/// in order, with `Option<Type>` for the defaulted ones. An optional leading
+/// `#[dataclass(frozen, order)]` mirrors the decorator arguments: `frozen`
+/// refuses setattr() and adds `__hash__`, `order` adds PartialOrd comparing
+/// fields as a tuple. Field types need `Clone + PartialEq + PyRepr` and
+/// `Into<PyValue>`.
+#[macro_export]
+macro_rules! py_dataclass {
+    (
+        #[dataclass($($flag:ident),* $(,)?)]
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$fmeta:meta])*
+                $fvis:vis $field:ident : $ty:ty $(= $default:expr)? $(=> $factory:expr)?
+            ),* $(,)?
+        }
+    ) => {
+        $($crate::py_dataclass!(@check $flag);)*
+
+        $(#[$meta])*
+        $vis struct $name {
+            $($(#[$fmeta])* $fvis $field: $ty,)*
+        }
+
+        impl $name {
+            /// The generated `__init__`.
+            #[allow(clippy::too_many_arguments)]
+            pub fn new(
+                $($field: $crate::py_dataclass!(@param $ty $(= $default)? $(=> $factory)?)),*
+            ) -> Self {
+                $name {
+                    $($field: $crate::py_dataclass!(@value $field $(= $default)? $(=> $factory)?),)*
+                }
+            }
+        }
+
+        impl $crate::stdlib::dataclasses::PyDataclass for $name {
+            const NAME: &'static str = stringify!($name);
+            const FIELDS: &'static [$crate::stdlib::dataclasses::Field] = &[$(
+                $crate::stdlib::dataclasses::Field {
+                    name: stringify!($field),
+                    type_name: stringify!($ty),
+                    default: $crate::py_dataclass!(@default $(= $default)? $(=> $factory)?),
+                }
+            ),*];
+            const FROZEN: bool = $crate::py_dataclass!(@frozen $($flag)*);
+
+            fn visit_fields(&self, visit: &mut dyn FnMut(&'static str, $crate::types::PyValue)) {
+                $(visit(stringify!($field), $crate::types::PyValue::from(self.$field.clone()));)*
+            }
+        }
+
+        impl $crate::types::PyTyped for $name {
+            fn py_type(&self) -> $crate::types::PyTypeId {
+                $crate::types::register_class(stringify!($name), &[])
+            }
+        }
+
+        impl $crate::types::PyRepr for $name {
+            fn py_repr(&self) -> $crate::stdlib::dataclasses::ReprString {
+                $crate::stdlib::dataclasses::repr_fields(
+                    stringify!($name),
+                    &[$((stringify!($field), &self.$field as &dyn $crate::types::PyRepr)),*],
+                )
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(&$crate::types::PyRepr::py_repr(self))
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                true $(&& self.$field == other.$field)*
+            }
+        }
+
+        impl From<$name> for $crate::types::PyValue {
+            fn from(obj: $name) -> Self {
+                $crate::stdlib::dataclasses::asdict_value(&obj)
+            }
+        }
+
+        impl $crate::types::PyObjectProtocol for $name {
+            fn get_attr(&self, name: &str) -> $crate::exceptions::PyResult<$crate::types::PyValue> {
+                match name {
+                    $(stringify!($field) => Ok($crate::types::PyValue::from(self.$field.clone())),)*
+                    _ => Err($crate::types::object::no_attribute(stringify!($name), name)),
+                }
+            }
+
+            fn set_attr(&mut self, name: &str, _value: $crate::types::PyValue) -> $crate::exceptions::PyResult<()> {
+                $crate::stdlib::dataclasses::reject_setattr::<Self>("assign to", name)
+            }
+
+            fn del_attr(&mut self, name: &str) -> $crate::exceptions::PyResult<()> {
+                $crate::stdlib::dataclasses::reject_setattr::<Self>("delete", name)
+            }
+
+            fn dir(&self) -> $crate::stdlib::dataclasses::Names {
+                $crate::stdlib::dataclasses::field_names::<Self>()
+            }
+        }
+
+        $crate::py_dataclass!(@impls $name [$($field)*] $($flag)*);
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident { $($body:tt)* }
+    ) => {
+        $crate::py_dataclass! {
+            #[dataclass()]
+            $(#[$meta])*
+            $vis struct $name { $($body)* }
+        }
+    };
+
+    (@check frozen) => {};
+    (@check order) => {};
+    (@check $other:ident) => {
+        compile_error!(concat!("unsupported dataclass option: ", stringify!($other)));
+    };
+
+    (@param $ty:ty) => { $ty };
+    (@param $ty:ty = $default:expr) => { Option<$ty> };
+    (@param $ty:ty => $factory:expr) => { Option<$ty> };
+
+    (@value $field:ident) => { $field };
+    (@value $field:ident = $default:expr) => { $field.unwrap_or_else(|| $default) };
+    (@value $field:ident => $factory:expr) => { $field.unwrap_or_else($factory) };
+
+    (@default) => { $crate::stdlib::dataclasses::FieldDefault::Missing };
+    (@default = $default:expr) => { $crate::stdlib::dataclasses::FieldDefault::Value };
+    (@default => $factory:expr) => { $crate::stdlib::dataclasses::FieldDefault::Factory };
+
+    (@frozen) => { false };
+    (@frozen frozen $($rest:ident)*) => { true };
+    (@frozen $other:ident $($rest:ident)*) => { $crate::py_dataclass!(@frozen $($rest)*) };
+
+    (@impls $name:ident [$($field:ident)*]) => {};
+    // frozen=True with the default eq=True also generates __hash__.
+    (@impls $name:ident [$($field:ident)*] frozen $($rest:ident)*) => {
+        impl $crate::types::PyHash for $name {
+            fn py_hash(&self) -> $crate::exceptions::PyResult<i64> {
+                $crate::types::PyHash::py_hash(&$crate::stdlib::dataclasses::astuple(self))
+            }
+        }
+        $crate::py_dataclass!(@impls $name [$($field)*] $($rest)*);
+    };
+    (@impls $name:ident [$($field:ident)*] order $($rest:ident)*) => {
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+                $(
+                    match self.$field.partial_cmp(&other.$field)? {
+                        ::core::cmp::Ordering::Equal => {}
+                        ord => return Some(ord),
+                    }
+                )*
+                Some(::core::cmp::Ordering::Equal)
+            }
+        }
+        $crate::py_dataclass!(@impls $name [$($field)*] $($rest)*);
+    };
+}
+
+/// What the generated `__repr__` returns, named here so the expansion
+/// compiles in no_std crates without `alloc` in scope.
+pub type ReprString = String;
+/// What the generated `__dir__` returns.
+pub type Names = Vec<String>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PyHash, PyObjectProtocol};
+    use alloc::vec;
+
+    crate::py_dataclass! {
+        #[dataclass(order)]
+        #[derive(Debug, Clone)]
+        pub struct Item {
+            pub name: String,
+            pub price: f64 = 0.0,
+            pub tags: Vec<i64> => Vec::new,
+        }
+    }
+
+    crate::py_dataclass! {
+        #[dataclass(frozen)]
+        #[derive(Debug, Clone)]
+        struct Order {
+            item: Item,
+            qty: i64,
+        }
+    }
+
+    crate::py_dataclass! {
+        #[dataclass(frozen)]
+        #[derive(Debug, Clone)]
+        struct Sku {
+            code: String,
+        }
+    }
+
+    #[test]
+    fn init_repr_and_eq() {
+        let a = Item::new("pen".into(), None, None);
+        assert_eq!(a.py_repr(), "Item(name='pen', price=0.0, tags=[])");
+        let b = Item::new("pen".into(), Some(1.5), Some(vec![1]));
+        assert_eq!(b.to_string(), "Item(name='pen', price=1.5, tags=[1])");
+        assert!(a != b && a < b);
+        assert_eq!(a, Item::new("pen".into(), Some(0.0), None));
+        assert!(Item::new("a".into(), Some(9.0), None) < a);
+    }
+
+    #[test]
+    fn fields_asdict_and_astuple() {
+        let names: Vec<_> = fields::<Item>().iter().map(|f| f.name).collect();
+        assert_eq!(names, ["name", "price", "tags"]);
+        assert_eq!(fields::<Item>()[1].default, FieldDefault::Value);
+        assert_eq!(fields::<Item>()[2].default, FieldDefault::Factory);
+        assert_eq!(fields::<Item>()[2].type_name, "Vec<i64>");
+
+        let order = Order::new(Item::new("pen".into(), None, None), 2);
+        assert_eq!(asdict(&order).keys().to_list(), ["item", "qty"]);
+        assert_eq!(
+            asdict_value(&order).py_repr(),
+            "{'item': {'name': 'pen', 'price': 0.0, 'tags': []}, 'qty': 2}"
+        );
+        assert_eq!(astuple(&order).len(), 2);
+    }
+
+    #[test]
+    fn frozen_instances_refuse_setattr() {
+        let mut order = Order::new(Item::new("pen".into(), None, None), 2);
+        assert_eq!(order.get_attr("qty").unwrap(), PyValue::Int(2));
+        let err = order.set_attr("qty", PyValue::Int(3)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "FrozenInstanceError: cannot assign to field 'qty'"
+        );
+        assert!(order.del_attr("qty").unwrap_err().is("FrozenInstanceError"));
+        assert!(order.get_attr("nope").unwrap_err().is("AttributeError"));
+        // Like CPython, hashing fails when a field is unhashable.
+        assert!(order.py_hash().unwrap_err().is("TypeError"));
+        let sku = Sku::new("x1".into());
+        assert_eq!(sku.py_hash(), Sku::new("x1".into()).py_hash());
+        assert_eq!(
+            sku.py_hash().unwrap(),
+            PyTuple::from_vec(vec!["x1"]).py_hash().unwrap()
+        );
+
+        let mut item = Item::new("pen".into(), None, None);
+        assert!(item
+            .set_attr("price", PyValue::Float(1.0))
+            .unwrap_err()
+            .is("AttributeError"));
+        assert_eq!(crate::builtins::dir(&item), ["name", "price", "tags"]);
+    }
+}
@@ -0,0 +1,886 @@
+//! Python-equivalent datetime module: date, time and datetime with
+//! proleptic-Gregorian ordinals and ISO 8601 parsing.
+//!
+//! Nothing here reads the clock, so the module builds without std. Zones
+//! with rules, such as `stdlib::zoneinfo::ZoneInfo`, plug in through
+//! [`TzInfo`].
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::time::{format_time, StructTime};
+use crate::types::{PyFormat, PyRepr};
+
+/// Python-equivalent datetime.MINYEAR.
+pub const MINYEAR: i32 = 1;
+/// Python-equivalent datetime.MAXYEAR.
+pub const MAXYEAR: i32 = 9999;
+
+/// Days from 0001-01-01 (ordinal 1) to 1970-01-01.
+const UNIX_EPOCH_ORDINAL: i64 = 719163;
+
+pub fn is_leap(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic-Gregorian date, in constant time
+/// (Howard Hinnant's `days_from_civil`).
+pub fn days_from_civil(year: i32, month: u32, day: u32) -> i64 {
+    let y = year as i64 - i64::from(month <= 2);
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// The inverse of [`days_from_civil`].
+pub fn civil_from_days(days: i64) -> (i32, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+    (year, month, day)
+}
+
+fn invalid_isoformat(s: &str) -> PyException {
+    PyException::value_error(format!("Invalid isoformat string: {}", s.py_repr()))
+}
+
+/// Parses exactly `n` ASCII digits at the start of `s`.
+fn digits(s: &str, n: usize) -> Option<u32> {
+    let head = s.get(..n)?;
+    if !head.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    head.parse().ok()
+}
+
+/// Python-equivalent datetime.date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> PyResult<Date> {
+        if !(MINYEAR..=MAXYEAR).contains(&year) {
+            return Err(PyException::value_error(format!(
+                "year {} is out of range",
+                year
+            )));
+        }
+        if !(1..=12).contains(&month) {
+            return Err(PyException::value_error("month must be in 1..12"));
+        }
+        if day < 1 || day > days_in_month(year, month) {
+            return Err(PyException::value_error("day is out of range for month"));
+        }
+        Ok(Date { year, month, day })
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u32 {
+        self.month
+    }
+
+    pub fn day(&self) -> u32 {
+        self.day
+    }
+
+    /// Python-equivalent date.toordinal(): 0001-01-01 is day 1.
+    pub fn toordinal(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) + UNIX_EPOCH_ORDINAL
+    }
+
+    /// Python-equivalent date.fromordinal(n).
+    pub fn fromordinal(ordinal: i64) -> PyResult<Date> {
+        if !(1..=3652059).contains(&ordinal) {
+            return Err(PyException::value_error(format!(
+                "ordinal must be in 1..3652059, not {}",
+                ordinal
+            )));
+        }
+        let (year, month, day) = civil_from_days(ordinal - UNIX_EPOCH_ORDINAL);
+        Ok(Date { year, month, day })
+    }
+
+    /// Python-equivalent date.weekday(): Monday is 0.
+    pub fn weekday(&self) -> u32 {
+        ((self.toordinal() + 6) % 7) as u32
+    }
+
+    /// Python-equivalent date.isoweekday(): Monday is 1.
+    pub fn isoweekday(&self) -> u32 {
+        self.weekday() + 1
+    }
+
+    /// Python-equivalent date.timetuple().tm_yday: 1 for January 1st.
+    pub fn yday(&self) -> u32 {
+        (days_from_civil(self.year, self.month, self.day) - days_from_civil(self.year, 1, 1) + 1)
+            as u32
+    }
+
+    /// Python-equivalent date.isocalendar(): (ISO year, week, weekday).
+    pub fn isocalendar(&self) -> (i32, u32, u32) {
+        let ordinal = self.toordinal();
+        // The ISO year is the one containing this week's Thursday.
+        let thursday = ordinal - self.weekday() as i64 + 3;
+        let (iso_year, _, _) = civil_from_days(thursday - UNIX_EPOCH_ORDINAL);
+        let jan1 = days_from_civil(iso_year, 1, 1) + UNIX_EPOCH_ORDINAL;
+        let week = ((thursday - jan1) / 7 + 1) as u32;
+        (iso_year, week, self.isoweekday())
+    }
+
+    /// Python-equivalent date.fromisocalendar(year, week, day).
+    pub fn fromisocalendar(year: i32, week: u32, day: u32) -> PyResult<Date> {
+        if !(MINYEAR..=MAXYEAR).contains(&year) {
+            return Err(PyException::value_error(format!(
+                "Year is out of range: {}",
+                year
+            )));
+        }
+        let jan1_weekday = Date {
+            year,
+            month: 1,
+            day: 1,
+        }
+        .weekday();
+        let long_year = jan1_weekday == 3 || (jan1_weekday == 2 && is_leap(year));
+        if week == 0 || week > 52 + u32::from(long_year) {
+            return Err(PyException::value_error(format!("Invalid week: {}", week)));
+        }
+        if !(1..=7).contains(&day) {
+            return Err(PyException::value_error(format!(
+                "Invalid weekday: {} (range is [1, 7])",
+                day
+            )));
+        }
+        let jan4 = Date {
+            year,
+            month: 1,
+            day: 4,
+        };
+        let week1_monday = jan4.toordinal() - jan4.weekday() as i64;
+        Date::fromordinal(week1_monday + (week as i64 - 1) * 7 + day as i64 - 1)
+            .map_err(|_| PyException::value_error(format!("Year is out of range: {}", year)))
+    }
+
+    /// Python-equivalent date.fromisoformat(s): `YYYY-MM-DD`, `YYYYMMDD`,
+    /// and the ISO week forms `YYYY-Www[-D]` / `YYYYWww[D]`.
+    pub fn fromisoformat(s: &str) -> PyResult<Date> {
+        Self::parse_iso(s).ok_or_else(|| invalid_isoformat(s))?
+    }
+
+    /// None for a syntax error; Some(Err) for well-formed but invalid fields.
+    fn parse_iso(s: &str) -> Option<PyResult<Date>> {
+        let year = digits(s, 4)? as i32;
+        let rest = &s[4..];
+        let (extended, rest) = match rest.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        if let Some(week_part) = rest.strip_prefix('W') {
+            let week = digits(week_part, 2)?;
+            let day_part = &week_part[2..];
+            let day = match (extended, day_part) {
+                (_, "") => 1,
+                (true, d) => digits(d.strip_prefix('-')?, 1).filter(|_| d.len() == 2)?,
+                (false, d) => digits(d, 1).filter(|_| d.len() == 1)?,
+            };
+            return Some(Date::fromisocalendar(year, week, day));
+        }
+        let month = digits(rest, 2)?;
+        let rest = &rest[2..];
+        let rest = if extended {
+            rest.strip_prefix('-')?
+        } else {
+            rest
+        };
+        let day = digits(rest, 2)?;
+        if rest.len() != 2 {
+            return None;
+        }
+        Some(Date::new(year, month, day))
+    }
+
+    /// Python-equivalent date.isoformat().
+    pub fn isoformat(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    /// Python-equivalent date.timetuple(): midnight, tm_isdst = -1.
+    pub fn timetuple(&self) -> StructTime {
+        self.struct_time(0, 0, 0, None)
+    }
+
+    fn struct_time(&self, hour: u32, min: u32, sec: u32, offset: Option<i32>) -> StructTime {
+        StructTime {
+            tm_year: self.year,
+            tm_mon: self.month,
+            tm_mday: self.day,
+            tm_hour: hour,
+            tm_min: min,
+            tm_sec: sec,
+            tm_wday: self.weekday(),
+            tm_yday: self.yday(),
+            tm_isdst: -1,
+            tm_zone: offset.map(tzname),
+            tm_gmtoff: offset,
+        }
+    }
+
+    /// Python-equivalent date.strftime(format); time fields are zero.
+    pub fn strftime(&self, format: &str) -> String {
+        format_time(format, &self.timetuple(), Some(0))
+    }
+
+    /// Python-equivalent date.ctime().
+    pub fn ctime(&self) -> String {
+        self.strftime("%c")
+    }
+}
+
+/// `format(d, spec)` is `d.strftime(spec)`, or str(d) for an empty spec.
+impl PyFormat for Date {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        Ok(if spec.is_empty() {
+            self.to_string()
+        } else {
+            self.strftime(spec)
+        })
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.isoformat())
+    }
+}
+
+impl PyRepr for Date {
+    fn py_repr(&self) -> String {
+        format!("datetime.date({}, {}, {})", self.year, self.month, self.day)
+    }
+}
+
+/// Python-equivalent datetime.time. The timezone, when present, is a fixed
+/// UTC offset in seconds, as datetime.timezone(timedelta(seconds=...)).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Time {
+    hour: u32,
+    minute: u32,
+    second: u32,
+    microsecond: u32,
+    utcoffset: Option<i32>,
+}
+
+impl Time {
+    pub fn new(hour: u32, minute: u32, second: u32, microsecond: u32) -> PyResult<Time> {
+        let check = |value: u32, max: u32, name: &str| {
+            if value > max {
+                Err(PyException::value_error(format!(
+                    "{} must be in 0..{}",
+                    name, max
+                )))
+            } else {
+                Ok(())
+            }
+        };
+        check(hour, 23, "hour")?;
+        check(minute, 59, "minute")?;
+        check(second, 59, "second")?;
+        check(microsecond, 999999, "microsecond")?;
+        Ok(Time {
+            hour,
+            minute,
+            second,
+            microsecond,
+            utcoffset: None,
+        })
+    }
+
+    /// Attaches a fixed UTC offset, which must be under a day either way.
+    pub fn with_utcoffset(mut self, seconds: Option<i32>) -> PyResult<Time> {
+        if seconds.is_some_and(|s| s.unsigned_abs() >= 86400) {
+            return Err(PyException::value_error(
+                "offset must be a timedelta strictly between -timedelta(hours=24) and timedelta(hours=24).",
+            ));
+        }
+        self.utcoffset = seconds;
+        Ok(self)
+    }
+
+    pub fn hour(&self) -> u32 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u32 {
+        self.minute
+    }
+
+    pub fn second(&self) -> u32 {
+        self.second
+    }
+
+    pub fn microsecond(&self) -> u32 {
+        self.microsecond
+    }
+
+    /// Python-equivalent time.utcoffset(), in seconds.
+    pub fn utcoffset(&self) -> Option<i32> {
+        self.utcoffset
+    }
+
+    /// Python-equivalent time.fromisoformat(s): `HH[:MM[:SS[.fff[fff]]]]` or
+    /// the basic `HH[MM[SS[.ffffff]]]`, optionally followed by `Z` or a
+    /// `±HH[:MM[:SS[.ffffff]]]` offset. Extra fraction digits are truncated.
+    pub fn fromisoformat(s: &str) -> PyResult<Time> {
+        Self::parse_iso(s).ok_or_else(|| invalid_isoformat(s))?
+    }
+
+    fn parse_iso(s: &str) -> Option<PyResult<Time>> {
+        let tz_at = s.find(['+', '-', 'Z']).unwrap_or(s.len());
+        let (clock, tz) = s.split_at(tz_at);
+        let (h, m, sec, us) = parse_clock(clock)?;
+        let offset = match tz {
+            "" => None,
+            "Z" => Some(0),
+            tz => {
+                let sign = if tz.starts_with('-') { -1 } else { 1 };
+                let (oh, om, os, ous) = parse_clock(&tz[1..])?;
+                if ous != 0 {
+                    // Sub-second offsets exist in CPython but not here.
+                    return None;
+                }
+                Some(sign * (oh * 3600 + om * 60 + os) as i32)
+            }
+        };
+        Some(Time::new(h, m, sec, us).and_then(|t| t.with_utcoffset(offset)))
+    }
+
+    /// Python-equivalent time.isoformat(): microseconds only when nonzero.
+    pub fn isoformat(&self) -> String {
+        let mut out = format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second);
+        if self.microsecond != 0 {
+            out.push_str(&format!(".{:06}", self.microsecond));
+        }
+        if let Some(offset) = self.utcoffset {
+            out.push_str(&format_offset(offset, ":"));
+        }
+        out
+    }
+
+    /// Python-equivalent time.tzname() of a datetime.timezone offset.
+    pub fn tzname(&self) -> Option<String> {
+        self.utcoffset.map(tzname)
+    }
+
+    /// Python-equivalent time.strftime(format), dated 1900-01-01.
+    pub fn strftime(&self, format: &str) -> String {
+        DateTime::new(Date::new(1900, 1, 1).unwrap(), *self).strftime(format)
+    }
+}
+
+/// Name of a fixed-offset timezone: `UTC`, or `UTC+HH:MM[:SS]`.
+fn tzname(offset: i32) -> String {
+    if offset == 0 {
+        "UTC".into()
+    } else {
+        format!("UTC{}", format_offset(offset, ":"))
+    }
+}
+
+/// `HH[:MM[:SS[.f+]]]` or `HH[MM[SS[.f+]]]`, returning (h, m, s, µs).
+fn parse_clock(s: &str) -> Option<(u32, u32, u32, u32)> {
+    let (whole, fraction) = match s.find(['.', ',']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+    let extended = whole.contains(':');
+    let mut fields = [0u32; 3];
+    let mut rest = whole;
+    let mut count = 0;
+    while !rest.is_empty() {
+        if count == 3 {
+            return None;
+        }
+        if count > 0 && extended {
+            rest = rest.strip_prefix(':')?;
+        }
+        fields[count] = digits(rest, 2)?;
+        rest = &rest[2..];
+        count += 1;
+    }
+    if count == 0 || (fraction.is_some() && count != 3) {
+        return None;
+    }
+    let micro = match fraction {
+        None => 0,
+        Some(f) if f.is_empty() || !f.bytes().all(|b| b.is_ascii_digit()) => return None,
+        Some(f) => {
+            let kept = &f[..f.len().min(6)];
+            kept.parse::<u32>().ok()? * 10u32.pow(6 - kept.len() as u32)
+        }
+    };
+    Some((fields[0], fields[1], fields[2], micro))
+}
+
+/// `+HH:MM`, with `:SS` only when the offset has seconds.
+pub(crate) fn format_offset(offset: i32, sep: &str) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let offset = offset.unsigned_abs();
+    let mut out = format!("{}{:02}{}{:02}", sign, offset / 3600, sep, offset / 60 % 60);
+    if !offset.is_multiple_of(60) {
+        out.push_str(&format!("{}{:02}", sep, offset % 60));
+    }
+    out
+}
+
+/// `format(d, spec)` is `d.strftime(spec)`, or str(d) for an empty spec.
+impl PyFormat for Time {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        Ok(if spec.is_empty() {
+            self.to_string()
+        } else {
+            self.strftime(spec)
+        })
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.isoformat())
+    }
+}
+
+/// Python-equivalent datetime.tzinfo, for zones whose offset depends on
+/// the date. Each method is asked about a datetime's wall-clock fields
+/// (and its fold), not about any offset attached to it.
+pub trait TzInfo: fmt::Debug + Sync {
+    /// Python-equivalent tzinfo.utcoffset(dt), in seconds east of UTC.
+    fn utcoffset(&self, dt: &DateTime) -> Option<i32>;
+
+    /// Python-equivalent tzinfo.dst(dt): the daylight-saving part of the
+    /// offset, in seconds.
+    fn dst(&self, dt: &DateTime) -> Option<i32>;
+
+    /// Python-equivalent tzinfo.tzname(dt), e.g. "EST".
+    fn tzname(&self, dt: &DateTime) -> Option<String>;
+
+    /// Python-equivalent tzinfo.fromutc(dt): the naive wall-clock time in
+    /// this zone at the naive UTC time `utc`, with fold set on the second
+    /// pass through a repeated hour. The default suits fixed offsets.
+    fn fromutc(&self, utc: &DateTime) -> DateTime {
+        utc.shift(self.utcoffset(utc).unwrap_or(0) as i64)
+    }
+}
+
+/// A datetime's tzinfo. Zones are shared and never freed, as CPython
+/// caches ZoneInfo objects, so identity is the pointer.
+#[derive(Clone, Copy)]
+struct TzRef(&'static dyn TzInfo);
+
+impl PartialEq for TzRef {
+    fn eq(&self, other: &Self) -> bool {
+        core::ptr::addr_eq(self.0, other.0)
+    }
+}
+
+impl Eq for TzRef {}
+
+impl Hash for TzRef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (self.0 as *const dyn TzInfo).cast::<()>().hash(state);
+    }
+}
+
+impl fmt::Debug for TzRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Python-equivalent datetime.datetime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateTime {
+    date: Date,
+    time: Time,
+    tzinfo: Option<TzRef>,
+    fold: bool,
+}
+
+impl DateTime {
+    pub fn new(date: Date, time: Time) -> DateTime {
+        DateTime {
+            date,
+            time,
+            tzinfo: None,
+            fold: false,
+        }
+    }
+
+    /// Python-equivalent dt.replace(tzinfo=tz). A zone replaces any fixed
+    /// offset the time carried; None makes the datetime naive.
+    pub fn replace_tzinfo(mut self, tz: Option<&'static dyn TzInfo>) -> DateTime {
+        self.tzinfo = tz.map(TzRef);
+        self.time.utcoffset = None;
+        self
+    }
+
+    /// Python-equivalent dt.replace(fold=fold): which of a repeated wall
+    /// time's two instants is meant.
+    pub fn replace_fold(mut self, fold: bool) -> DateTime {
+        self.fold = fold;
+        self
+    }
+
+    /// Python-equivalent datetime.tzinfo, for datetimes with a zone.
+    pub fn tzinfo(&self) -> Option<&'static dyn TzInfo> {
+        self.tzinfo.map(|tz| tz.0)
+    }
+
+    /// Python-equivalent datetime.fold.
+    pub fn fold(&self) -> bool {
+        self.fold
+    }
+
+    /// Wall-clock seconds since 1970-01-01T00:00, ignoring any zone.
+    pub(crate) fn wall_seconds(&self) -> i64 {
+        let t = &self.time;
+        days_from_civil(self.date.year, self.date.month, self.date.day) * 86400
+            + (t.hour * 3600 + t.minute * 60 + t.second) as i64
+    }
+
+    /// The naive datetime `wall_seconds` after the epoch, with
+    /// `microsecond`.
+    pub(crate) fn from_wall_seconds(wall_seconds: i64, microsecond: u32) -> DateTime {
+        let (year, month, day) = civil_from_days(wall_seconds.div_euclid(86400));
+        let secs = wall_seconds.rem_euclid(86400) as u32;
+        DateTime::new(
+            Date { year, month, day },
+            Time {
+                hour: secs / 3600,
+                minute: secs / 60 % 60,
+                second: secs % 60,
+                microsecond,
+                utcoffset: None,
+            },
+        )
+    }
+
+    /// This wall-clock time moved by `seconds`, as a naive datetime.
+    pub(crate) fn shift(&self, seconds: i64) -> DateTime {
+        DateTime::from_wall_seconds(self.wall_seconds() + seconds, self.time.microsecond)
+    }
+
+    /// The time with this datetime's current offset, for formatting.
+    fn fixed_time(&self) -> Time {
+        Time {
+            utcoffset: self.utcoffset(),
+            ..self.time
+        }
+    }
+
+    /// Python-equivalent datetime.astimezone(tz): the same instant as wall
+    /// time in `tz`. Naive datetimes are taken as UTC, as in timestamp().
+    pub fn astimezone(&self, tz: &'static dyn TzInfo) -> DateTime {
+        let utc = self
+            .shift(-(self.utcoffset().unwrap_or(0) as i64))
+            .replace_tzinfo(None);
+        tz.fromutc(&utc).replace_tzinfo(Some(tz))
+    }
+
+    /// Python-equivalent datetime.date().
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    /// Python-equivalent datetime.timetz(), with a zone's offset at this
+    /// datetime fixed in place of the zone.
+    pub fn time(&self) -> Time {
+        self.fixed_time()
+    }
+
+    pub fn utcoffset(&self) -> Option<i32> {
+        match self.tzinfo {
+            Some(tz) => tz.0.utcoffset(self),
+            None => self.time.utcoffset,
+        }
+    }
+
+    /// Python-equivalent datetime.dst(): None unless a zone says otherwise.
+    pub fn dst(&self) -> Option<i32> {
+        self.tzinfo.and_then(|tz| tz.0.dst(self))
+    }
+
+    pub fn weekday(&self) -> u32 {
+        self.date.weekday()
+    }
+
+    pub fn isocalendar(&self) -> (i32, u32, u32) {
+        self.date.isocalendar()
+    }
+
+    /// Python-equivalent datetime.timestamp() for aware datetimes, or for
+    /// naive ones taken as UTC (this runtime has no local timezone).
+    pub fn timestamp(&self) -> f64 {
+        let seconds = self.wall_seconds() - self.utcoffset().unwrap_or(0) as i64;
+        seconds as f64 + self.time.microsecond as f64 / 1e6
+    }
+
+    /// Python-equivalent datetime.fromisoformat(s): any ISO date form, then
+    /// optionally one separator character and an ISO time.
+    pub fn fromisoformat(s: &str) -> PyResult<DateTime> {
+        Self::parse_iso(s).ok_or_else(|| invalid_isoformat(s))?
+    }
+
+    fn parse_iso(s: &str) -> Option<PyResult<DateTime>> {
+        // The date part's length decides where the separator sits.
+        let date_len = match s.as_bytes().get(4..).unwrap_or_default() {
+            [b'-', b'W', _, _, b'-', _, ..] => 10,
+            [b'-', b'W', ..] => 8,
+            [b'W', _, _, d, ..] if d.is_ascii_digit() => 8,
+            [b'W', ..] => 7,
+            [b'-', ..] => 10,
+            _ => 8,
+        };
+        let date_part = s.get(..date_len.min(s.len()))?;
+        let date = match Date::parse_iso(date_part)? {
+            Ok(date) => date,
+            Err(e) => return Some(Err(e)),
+        };
+        let rest = &s[date_part.len()..];
+        let time = match rest.chars().next() {
+            None => Time::new(0, 0, 0, 0).ok()?,
+            Some(sep) => match Time::parse_iso(&rest[sep.len_utf8()..])? {
+                Ok(time) => time,
+                Err(e) => return Some(Err(e)),
+            },
+        };
+        Some(Ok(DateTime::new(date, time)))
+    }
+
+    /// Python-equivalent datetime.isoformat(sep='T').
+    pub fn isoformat(&self, sep: char) -> String {
+        format!(
+            "{}{}{}",
+            self.date.isoformat(),
+            sep,
+            self.fixed_time().isoformat()
+        )
+    }
+
+    pub fn tzname(&self) -> Option<String> {
+        match self.tzinfo {
+            Some(tz) => tz.0.tzname(self),
+            None => self.time.tzname(),
+        }
+    }
+
+    /// Python-equivalent datetime.timetuple(). Zone fields are only filled
+    /// for aware datetimes, where they drive %z and %Z.
+    pub fn timetuple(&self) -> StructTime {
+        let t = &self.time;
+        let mut tt = self
+            .date
+            .struct_time(t.hour, t.minute, t.second, self.utcoffset());
+        if self.tzinfo.is_some() {
+            tt.tm_zone = self.tzname();
+            tt.tm_isdst = self.dst().map_or(-1, |dst| i32::from(dst != 0));
+        }
+        tt
+    }
+
+    /// Python-equivalent datetime.strftime(format).
+    pub fn strftime(&self, format: &str) -> String {
+        format_time(format, &self.timetuple(), Some(self.time.microsecond))
+    }
+
+    /// Python-equivalent datetime.ctime().
+    pub fn ctime(&self) -> String {
+        self.strftime("%c")
+    }
+}
+
+/// Python-equivalent str(datetime), which separates with a space.
+/// `format(d, spec)` is `d.strftime(spec)`, or str(d) for an empty spec.
+impl PyFormat for DateTime {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        Ok(if spec.is_empty() {
+            self.to_string()
+        } else {
+            self.strftime(spec)
+        })
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.isoformat(' '))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_days_round_trip() {
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2000, 3, 1), 11017);
+        assert_eq!(days_from_civil(1, 1, 1), -UNIX_EPOCH_ORDINAL + 1);
+        for days in (-UNIX_EPOCH_ORDINAL..2_932_897).step_by(997) {
+            let (y, m, d) = civil_from_days(days);
+            assert_eq!(days_from_civil(y, m, d), days);
+        }
+    }
+
+    #[test]
+    fn ordinals_and_weekdays_match_cpython() {
+        let d = Date::new(2024, 2, 29).unwrap();
+        assert_eq!(d.toordinal(), 738945);
+        assert_eq!(d.weekday(), 3);
+        assert_eq!(Date::fromordinal(1).unwrap(), Date::new(1, 1, 1).unwrap());
+        assert_eq!(Date::new(1, 1, 1).unwrap().weekday(), 0);
+        assert_eq!(Date::new(9999, 12, 31).unwrap().toordinal(), 3652059);
+        assert_eq!(d.yday(), 60);
+        assert!(Date::new(2023, 2, 29).unwrap_err().is("ValueError"));
+        assert_eq!(d.py_repr(), "datetime.date(2024, 2, 29)");
+    }
+
+    #[test]
+    fn isocalendar_matches_cpython() {
+        let cases = [
+            ((2021, 1, 3), (2020, 53, 7)),
+            ((2024, 12, 30), (2025, 1, 1)),
+            ((2026, 1, 1), (2026, 1, 4)),
+            ((2015, 12, 31), (2015, 53, 4)),
+        ];
+        for ((y, m, d), iso) in cases {
+            let date = Date::new(y, m, d).unwrap();
+            assert_eq!(date.isocalendar(), iso);
+            assert_eq!(Date::fromisocalendar(iso.0, iso.1, iso.2).unwrap(), date);
+        }
+        assert!(Date::fromisocalendar(2021, 53, 1).is_err());
+    }
+
+    #[test]
+    fn date_fromisoformat_forms() {
+        let d = Date::new(2021, 1, 4).unwrap();
+        for s in [
+            "2021-01-04",
+            "20210104",
+            "2021-W01-1",
+            "2021W011",
+            "2021-W01",
+            "2021W01",
+        ] {
+            assert_eq!(Date::fromisoformat(s).unwrap(), d, "{}", s);
+        }
+        let err = Date::fromisoformat("2021-1-04").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: Invalid isoformat string: '2021-1-04'"
+        );
+        assert_eq!(
+            Date::fromisoformat("2021-02-30").unwrap_err().message,
+            "day is out of range for month"
+        );
+    }
+
+    #[test]
+    fn time_and_datetime_fromisoformat() {
+        let t = Time::fromisoformat("04:23:01.000384+05:30").unwrap();
+        assert_eq!((t.hour(), t.minute(), t.second()), (4, 23, 1));
+        assert_eq!(t.microsecond(), 384);
+        assert_eq!(t.utcoffset(), Some(19800));
+        assert_eq!(t.isoformat(), "04:23:01.000384+05:30");
+        assert_eq!(Time::fromisoformat("0423").unwrap().isoformat(), "04:23:00");
+        assert_eq!(
+            Time::fromisoformat("12:00:00.1234567Z")
+                .unwrap()
+                .isoformat(),
+            "12:00:00.123456+00:00"
+        );
+        assert!(Time::fromisoformat("24:00").is_err());
+        assert!(Time::fromisoformat("12:3").is_err());
+
+        let dt = DateTime::fromisoformat("2011-11-04T00:05:23Z").unwrap();
+        assert_eq!(dt.to_string(), "2011-11-04 00:05:23+00:00");
+        assert_eq!(dt.timestamp(), 1320365123.0);
+        let dt = DateTime::fromisoformat("20111104 000523-0130").unwrap();
+        assert_eq!(dt.isoformat('T'), "2011-11-04T00:05:23-01:30");
+        let dt = DateTime::fromisoformat("2011-W01-2T00:05:23.283").unwrap();
+        assert_eq!(dt.isoformat('T'), "2011-01-04T00:05:23.283000");
+        assert_eq!(
+            DateTime::fromisoformat("2011-11-04").unwrap().to_string(),
+            "2011-11-04 00:00:00"
+        );
+    }
+
+    #[test]
+    fn strftime_matches_cpython() {
+        let all = "%a %A %b %B %c %d %f %H %I %j %m %M %p %S %U %w %W %y %Y %z %Z %% %x %X %G %V %u %e %q 100%";
+        let time = Time::new(15, 5, 3, 123)
+            .unwrap()
+            .with_utcoffset(Some(19800));
+        let dt = DateTime::new(Date::new(2011, 1, 4).unwrap(), time.unwrap());
+        assert_eq!(
+            dt.strftime(all),
+            "Tue Tuesday Jan January Tue Jan  4 15:05:03 2011 04 000123 15 03 004 01 05 PM 03 01 2 01 11 2011 +0530 UTC+05:30 % 01/04/11 15:05:03 2011 01 2  4 %q 100%"
+        );
+        let dt = DateTime::new(Date::new(5, 1, 1).unwrap(), Time::new(0, 0, 0, 0).unwrap());
+        assert_eq!(
+            dt.strftime(all),
+            "Sat Saturday Jan January Sat Jan  1 00:00:00 5 01 000000 00 12 001 01 00 AM 00 00 6 00 05 5   % 01/01/05 00:00:00 4 53 6  1 %q 100%"
+        );
+        let t = Time::new(1, 2, 3, 0).unwrap();
+        assert_eq!(
+            t.strftime("%Y-%m-%d %H %z|%Z|%c|%j|%a"),
+            "1900-01-01 01 ||Mon Jan  1 01:02:03 1900|001|Mon"
+        );
+        let t = t.with_utcoffset(Some(-3615)).unwrap();
+        assert_eq!(t.strftime("%z %Z"), "-010015 UTC-01:00:15");
+        assert_eq!(
+            Date::new(2024, 3, 1).unwrap().strftime("%H:%M %z%Z"),
+            "00:00 "
+        );
+        let dt = DateTime::fromisoformat("2011-01-04T05:06:07").unwrap();
+        assert_eq!(dt.ctime(), "Tue Jan  4 05:06:07 2011");
+        let tt = Date::new(2011, 1, 4).unwrap().timetuple();
+        assert_eq!((tt.tm_wday, tt.tm_yday, tt.tm_isdst), (1, 4, -1));
+    }
+
+    #[test]
+    fn format_delegates_to_strftime() {
+        let d = Date::new(2024, 3, 1).unwrap();
+        assert_eq!(crate::format(&d, "%Y-%m-%d").unwrap(), "2024-03-01");
+        assert_eq!(crate::format(&d, "").unwrap(), d.to_string());
+        let dt = DateTime::fromisoformat("2011-01-04T05:06:07").unwrap();
+        assert_eq!(dt.py_format("%H:%M").unwrap(), "05:06");
+    }
+}
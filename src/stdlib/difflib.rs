@@ -0,0 +1,815 @@
+//! Python-equivalent difflib module: SequenceMatcher over any sequence of
+//! ordered elements, unified_diff() and ndiff() over lines, and
+//! get_close_matches(). Pure computation, so available without `std`.
+//!
+//! Generators become vectors: unified_diff() and ndiff() return every
+//! output line, and get_grouped_opcodes() every group.
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::builtins::repr;
+use crate::exceptions::{PyException, PyResult};
+use crate::types::PyKwargs;
+
+/// Python-equivalent difflib.Match: `a[a..a + size] == b[b..b + size]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Match {
+    pub a: usize,
+    pub b: usize,
+    pub size: usize,
+}
+
+/// The operation of an opcode, printed as CPython's tag string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tag {
+    Replace,
+    Delete,
+    Insert,
+    Equal,
+}
+
+impl Tag {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Tag::Replace => "replace",
+            Tag::Delete => "delete",
+            Tag::Insert => "insert",
+            Tag::Equal => "equal",
+        }
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// `(tag, i1, i2, j1, j2)`: `a[i1..i2]` becomes `b[j1..j2]` by `tag`.
+pub type Opcode = (Tag, usize, usize, usize, usize);
+
+fn calculate_ratio(matches: usize, length: usize) -> f64 {
+    if length == 0 {
+        1.0
+    } else {
+        2.0 * matches as f64 / length as f64
+    }
+}
+
+/// Python-equivalent difflib.SequenceMatcher(isjunk, a, b, autojunk).
+///
+/// Elements of `b` for which `isjunk` holds never start a match, and with
+/// `autojunk` a `b` of 200 or more elements also treats elements making up
+/// more than 1% of it as junk.
+#[derive(Debug, Clone)]
+pub struct SequenceMatcher<T: Ord + Clone> {
+    isjunk: Option<fn(&T) -> bool>,
+    autojunk: bool,
+    a: Vec<T>,
+    b: Vec<T>,
+    /// Indices in `b` of each element that is neither junk nor popular.
+    b2j: BTreeMap<T, Vec<usize>>,
+    bjunk: BTreeSet<T>,
+    bpopular: BTreeSet<T>,
+}
+
+impl<T: Ord + Clone> SequenceMatcher<T> {
+    pub fn new<A, B>(isjunk: Option<fn(&T) -> bool>, a: A, b: B) -> Self
+    where
+        A: IntoIterator<Item = T>,
+        B: IntoIterator<Item = T>,
+    {
+        Self::with_autojunk(isjunk, a, b, true)
+    }
+
+    pub fn with_autojunk<A, B>(isjunk: Option<fn(&T) -> bool>, a: A, b: B, autojunk: bool) -> Self
+    where
+        A: IntoIterator<Item = T>,
+        B: IntoIterator<Item = T>,
+    {
+        let mut matcher = SequenceMatcher {
+            isjunk,
+            autojunk,
+            a: a.into_iter().collect(),
+            b: Vec::new(),
+            b2j: BTreeMap::new(),
+            bjunk: BTreeSet::new(),
+            bpopular: BTreeSet::new(),
+        };
+        matcher.set_seq2(b);
+        matcher
+    }
+
+    pub fn set_seqs<A, B>(&mut self, a: A, b: B)
+    where
+        A: IntoIterator<Item = T>,
+        B: IntoIterator<Item = T>,
+    {
+        self.set_seq1(a);
+        self.set_seq2(b);
+    }
+
+    pub fn set_seq1<A: IntoIterator<Item = T>>(&mut self, a: A) {
+        self.a = a.into_iter().collect();
+    }
+
+    /// Sets the second sequence, which is indexed for matching; when
+    /// comparing one sequence against many, make it this one.
+    pub fn set_seq2<B: IntoIterator<Item = T>>(&mut self, b: B) {
+        self.b = b.into_iter().collect();
+        self.chain_b();
+    }
+
+    fn chain_b(&mut self) {
+        let mut b2j: BTreeMap<T, Vec<usize>> = BTreeMap::new();
+        for (i, elt) in self.b.iter().enumerate() {
+            b2j.entry(elt.clone()).or_default().push(i);
+        }
+        self.bjunk.clear();
+        if let Some(isjunk) = self.isjunk {
+            b2j.retain(|elt, _| {
+                let junk = isjunk(elt);
+                if junk {
+                    self.bjunk.insert(elt.clone());
+                }
+                !junk
+            });
+        }
+        self.bpopular.clear();
+        let n = self.b.len();
+        if self.autojunk && n >= 200 {
+            let ntest = n / 100 + 1;
+            b2j.retain(|elt, indices| {
+                let popular = indices.len() > ntest;
+                if popular {
+                    self.bpopular.insert(elt.clone());
+                }
+                !popular
+            });
+        }
+        self.b2j = b2j;
+    }
+
+    /// Elements of `b` that `isjunk` marked as junk.
+    pub fn bjunk(&self) -> &BTreeSet<T> {
+        &self.bjunk
+    }
+
+    /// Elements of `b` that autojunk marked as popular.
+    pub fn bpopular(&self) -> &BTreeSet<T> {
+        &self.bpopular
+    }
+
+    /// Python-equivalent find_longest_match(alo, ahi, blo, bhi): the
+    /// longest junk-free block matching in `a[alo..ahi]` and `b[blo..bhi]`,
+    /// earliest in `a` and then in `b` on ties, extended by the junk on
+    /// either side of it.
+    pub fn find_longest_match(&self, alo: usize, ahi: usize, blo: usize, bhi: usize) -> Match {
+        let (a, b) = (&self.a, &self.b);
+        let isbjunk = |elt: &T| self.bjunk.contains(elt);
+        let (mut besti, mut bestj, mut bestsize) = (alo, blo, 0);
+        let mut j2len: BTreeMap<usize, usize> = BTreeMap::new();
+        for (i, elt) in a.iter().enumerate().take(ahi).skip(alo) {
+            let mut newj2len = BTreeMap::new();
+            for &j in self.b2j.get(elt).into_iter().flatten() {
+                if j < blo {
+                    continue;
+                }
+                if j >= bhi {
+                    break;
+                }
+                let k = j.checked_sub(1).and_then(|p| j2len.get(&p)).unwrap_or(&0) + 1;
+                newj2len.insert(j, k);
+                if k > bestsize {
+                    (besti, bestj, bestsize) = (i + 1 - k, j + 1 - k, k);
+                }
+            }
+            j2len = newj2len;
+        }
+        for junk in [false, true] {
+            while besti > alo
+                && bestj > blo
+                && isbjunk(&b[bestj - 1]) == junk
+                && a[besti - 1] == b[bestj - 1]
+            {
+                (besti, bestj, bestsize) = (besti - 1, bestj - 1, bestsize + 1);
+            }
+            while besti + bestsize < ahi
+                && bestj + bestsize < bhi
+                && isbjunk(&b[bestj + bestsize]) == junk
+                && a[besti + bestsize] == b[bestj + bestsize]
+            {
+                bestsize += 1;
+            }
+        }
+        Match {
+            a: besti,
+            b: bestj,
+            size: bestsize,
+        }
+    }
+
+    /// Python-equivalent get_matching_blocks(): non-adjacent matching
+    /// blocks in increasing order, ending with `Match(len(a), len(b), 0)`.
+    pub fn get_matching_blocks(&self) -> Vec<Match> {
+        let (la, lb) = (self.a.len(), self.b.len());
+        let mut queue = vec![(0, la, 0, lb)];
+        let mut blocks = Vec::new();
+        while let Some((alo, ahi, blo, bhi)) = queue.pop() {
+            let m = self.find_longest_match(alo, ahi, blo, bhi);
+            if m.size > 0 {
+                blocks.push(m);
+                if alo < m.a && blo < m.b {
+                    queue.push((alo, m.a, blo, m.b));
+                }
+                if m.a + m.size < ahi && m.b + m.size < bhi {
+                    queue.push((m.a + m.size, ahi, m.b + m.size, bhi));
+                }
+            }
+        }
+        blocks.sort();
+
+        let mut merged = Vec::new();
+        let mut current = Match {
+            a: 0,
+            b: 0,
+            size: 0,
+        };
+        for m in blocks {
+            if current.a + current.size == m.a && current.b + current.size == m.b {
+                current.size += m.size;
+            } else {
+                if current.size > 0 {
+                    merged.push(current);
+                }
+                current = m;
+            }
+        }
+        if current.size > 0 {
+            merged.push(current);
+        }
+        merged.push(Match {
+            a: la,
+            b: lb,
+            size: 0,
+        });
+        merged
+    }
+
+    /// Python-equivalent get_opcodes(): the edits turning `a` into `b`.
+    pub fn get_opcodes(&self) -> Vec<Opcode> {
+        let (mut i, mut j) = (0, 0);
+        let mut answer = Vec::new();
+        for m in self.get_matching_blocks() {
+            let tag = if i < m.a && j < m.b {
+                Some(Tag::Replace)
+            } else if i < m.a {
+                Some(Tag::Delete)
+            } else if j < m.b {
+                Some(Tag::Insert)
+            } else {
+                None
+            };
+            if let Some(tag) = tag {
+                answer.push((tag, i, m.a, j, m.b));
+            }
+            (i, j) = (m.a + m.size, m.b + m.size);
+            if m.size > 0 {
+                answer.push((Tag::Equal, m.a, i, m.b, j));
+            }
+        }
+        answer
+    }
+
+    /// Python-equivalent get_grouped_opcodes(n): the opcodes split into
+    /// hunks with up to `n` lines of context each.
+    pub fn get_grouped_opcodes(&self, n: usize) -> Vec<Vec<Opcode>> {
+        let mut codes = self.get_opcodes();
+        if codes.is_empty() {
+            codes.push((Tag::Equal, 0, 1, 0, 1));
+        }
+        if let Some((Tag::Equal, i1, i2, j1, j2)) = codes.first().copied() {
+            codes[0] = (
+                Tag::Equal,
+                i1.max(i2.saturating_sub(n)),
+                i2,
+                j1.max(j2.saturating_sub(n)),
+                j2,
+            );
+        }
+        if let Some((Tag::Equal, i1, i2, j1, j2)) = codes.last().copied() {
+            *codes.last_mut().unwrap() = (Tag::Equal, i1, i2.min(i1 + n), j1, j2.min(j1 + n));
+        }
+        let mut groups = Vec::new();
+        let mut group = Vec::new();
+        for (tag, mut i1, i2, mut j1, j2) in codes {
+            if tag == Tag::Equal && i2 - i1 > 2 * n {
+                group.push((tag, i1, i2.min(i1 + n), j1, j2.min(j1 + n)));
+                groups.push(core::mem::take(&mut group));
+                i1 = i1.max(i2.saturating_sub(n));
+                j1 = j1.max(j2.saturating_sub(n));
+            }
+            group.push((tag, i1, i2, j1, j2));
+        }
+        if !group.is_empty() && (group.len() != 1 || group[0].0 != Tag::Equal) {
+            groups.push(group);
+        }
+        groups
+    }
+
+    /// Python-equivalent ratio(): `2 * M / T` for `M` matched elements out
+    /// of `T` in both sequences; 1.0 when both are empty.
+    pub fn ratio(&self) -> f64 {
+        let matches = self.get_matching_blocks().iter().map(|m| m.size).sum();
+        calculate_ratio(matches, self.a.len() + self.b.len())
+    }
+
+    /// Python-equivalent quick_ratio(): an upper bound on ratio() from the
+    /// element counts alone.
+    pub fn quick_ratio(&self) -> f64 {
+        let mut avail: BTreeMap<&T, isize> = BTreeMap::new();
+        for elt in &self.b {
+            *avail.entry(elt).or_default() += 1;
+        }
+        let mut matches = 0;
+        for elt in &self.a {
+            if let Some(count) = avail.get_mut(elt) {
+                if *count > 0 {
+                    matches += 1;
+                }
+                *count -= 1;
+            }
+        }
+        calculate_ratio(matches, self.a.len() + self.b.len())
+    }
+
+    /// Python-equivalent real_quick_ratio(): an upper bound on ratio() from
+    /// the lengths alone.
+    pub fn real_quick_ratio(&self) -> f64 {
+        let (la, lb) = (self.a.len(), self.b.len());
+        calculate_ratio(la.min(lb), la + lb)
+    }
+}
+
+/// Python-equivalent difflib.get_close_matches(word, possibilities, n,
+/// cutoff): up to `n` possibilities scoring at least `cutoff`, best first.
+pub fn get_close_matches<S: AsRef<str>>(
+    word: &str,
+    possibilities: &[S],
+    n: usize,
+    cutoff: f64,
+) -> PyResult<Vec<String>> {
+    if n == 0 {
+        return Err(PyException::value_error(format!("n must be > 0: {}", n)));
+    }
+    if !(0.0..=1.0).contains(&cutoff) {
+        return Err(PyException::value_error(format!(
+            "cutoff must be in [0.0, 1.0]: {}",
+            repr(&cutoff)
+        )));
+    }
+    let mut s = SequenceMatcher::new(None, [], word.chars());
+    let mut result = Vec::new();
+    for x in possibilities {
+        let x = x.as_ref();
+        s.set_seq1(x.chars());
+        if s.real_quick_ratio() >= cutoff && s.quick_ratio() >= cutoff {
+            let ratio = s.ratio();
+            if ratio >= cutoff {
+                result.push((ratio, x));
+            }
+        }
+    }
+    // heapq.nlargest() on (score, word) tuples: ties go to the larger word.
+    result.sort_by(|(r1, x1), (r2, x2)| r2.total_cmp(r1).then_with(|| x2.cmp(x1)));
+    Ok(result
+        .into_iter()
+        .take(n)
+        .map(|(_, x)| x.to_string())
+        .collect())
+}
+
+/// Python-equivalent difflib.IS_LINE_JUNK(line): blank, or a lone `#`.
+pub fn is_line_junk(line: &&str) -> bool {
+    let rest = line.trim_start();
+    let rest = rest.strip_prefix('#').unwrap_or(rest);
+    rest.trim().is_empty()
+}
+
+/// Python-equivalent difflib.IS_CHARACTER_JUNK(ch): a space or tab.
+pub fn is_character_junk(ch: &char) -> bool {
+    matches!(ch, ' ' | '\t')
+}
+
+fn format_range_unified(start: usize, stop: usize) -> String {
+    let mut beginning = start + 1;
+    let length = stop - start;
+    if length == 1 {
+        return format!("{}", beginning);
+    }
+    if length == 0 {
+        beginning -= 1;
+    }
+    format!("{},{}", beginning, length)
+}
+
+/// The keywords of unified_diff() beyond the two sequences.
+struct UnifiedOptions {
+    fromfile: String,
+    tofile: String,
+    fromfiledate: String,
+    tofiledate: String,
+    n: usize,
+    lineterm: String,
+}
+
+/// Python-equivalent difflib.unified_diff(a, b, fromfile, tofile): the
+/// diff lines with three lines of context. Header lines end with "\n";
+/// content lines keep whatever ending the input lines had.
+pub fn unified_diff<S: AsRef<str>>(a: &[S], b: &[S], fromfile: &str, tofile: &str) -> Vec<String> {
+    unified(
+        a,
+        b,
+        &UnifiedOptions {
+            fromfile: fromfile.to_string(),
+            tofile: tofile.to_string(),
+            fromfiledate: String::new(),
+            tofiledate: String::new(),
+            n: 3,
+            lineterm: "\n".to_string(),
+        },
+    )
+}
+
+/// Python-equivalent difflib.unified_diff(a, b, **kwargs): fromfile,
+/// tofile, fromfiledate, tofiledate, n and lineterm.
+pub fn unified_diff_with<S: AsRef<str>>(
+    a: &[S],
+    b: &[S],
+    kwargs: PyKwargs,
+) -> PyResult<Vec<String>> {
+    let mut kwargs = kwargs.for_call("unified_diff");
+    let mut text = |key: &str| kwargs.get_str(key).map(Option::unwrap_or_default);
+    let options = UnifiedOptions {
+        fromfile: text("fromfile")?,
+        tofile: text("tofile")?,
+        fromfiledate: text("fromfiledate")?,
+        tofiledate: text("tofiledate")?,
+        lineterm: kwargs
+            .get_str("lineterm")?
+            .unwrap_or_else(|| "\n".to_string()),
+        n: kwargs.get_int("n")?.unwrap_or(3).max(0) as usize,
+    };
+    kwargs.reject_unknown()?;
+    Ok(unified(a, b, &options))
+}
+
+fn unified<S: AsRef<str>>(a: &[S], b: &[S], options: &UnifiedOptions) -> Vec<String> {
+    let a: Vec<&str> = a.iter().map(AsRef::as_ref).collect();
+    let b: Vec<&str> = b.iter().map(AsRef::as_ref).collect();
+    let date = |d: &str| {
+        if d.is_empty() {
+            String::new()
+        } else {
+            format!("\t{}", d)
+        }
+    };
+    let term = &options.lineterm;
+    let mut out = Vec::new();
+    let groups = SequenceMatcher::new(None, a.iter().copied(), b.iter().copied())
+        .get_grouped_opcodes(options.n);
+    for (index, group) in groups.iter().enumerate() {
+        if index == 0 {
+            out.push(format!(
+                "--- {}{}{}",
+                options.fromfile,
+                date(&options.fromfiledate),
+                term
+            ));
+            out.push(format!(
+                "+++ {}{}{}",
+                options.tofile,
+                date(&options.tofiledate),
+                term
+            ));
+        }
+        let (first, last) = (group[0], group[group.len() - 1]);
+        out.push(format!(
+            "@@ -{} +{} @@{}",
+            format_range_unified(first.1, last.2),
+            format_range_unified(first.3, last.4),
+            term
+        ));
+        for &(tag, i1, i2, j1, j2) in group {
+            if tag == Tag::Equal {
+                out.extend(a[i1..i2].iter().map(|line| format!(" {}", line)));
+                continue;
+            }
+            if matches!(tag, Tag::Replace | Tag::Delete) {
+                out.extend(a[i1..i2].iter().map(|line| format!("-{}", line)));
+            }
+            if matches!(tag, Tag::Replace | Tag::Insert) {
+                out.extend(b[j1..j2].iter().map(|line| format!("+{}", line)));
+            }
+        }
+    }
+    out
+}
+
+/// Python-equivalent difflib.Differ: line-by-line comparison with `?`
+/// guide lines marking the changes within lines that are close matches.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Differ {
+    pub linejunk: Option<fn(&&str) -> bool>,
+    pub charjunk: Option<fn(&char) -> bool>,
+}
+
+impl Differ {
+    pub fn new(linejunk: Option<fn(&&str) -> bool>, charjunk: Option<fn(&char) -> bool>) -> Self {
+        Differ { linejunk, charjunk }
+    }
+
+    /// Python-equivalent Differ.compare(a, b): each line prefixed with
+    /// "- ", "+ ", "  " or "? ".
+    pub fn compare<S: AsRef<str>>(&self, a: &[S], b: &[S]) -> Vec<String> {
+        let a: Vec<&str> = a.iter().map(AsRef::as_ref).collect();
+        let b: Vec<&str> = b.iter().map(AsRef::as_ref).collect();
+        let mut out = Vec::new();
+        let cruncher = SequenceMatcher::new(self.linejunk, a.iter().copied(), b.iter().copied());
+        for (tag, alo, ahi, blo, bhi) in cruncher.get_opcodes() {
+            match tag {
+                Tag::Replace => self.fancy_replace(&mut out, &a, alo, ahi, &b, blo, bhi),
+                Tag::Delete => dump(&mut out, '-', &a[alo..ahi]),
+                Tag::Insert => dump(&mut out, '+', &b[blo..bhi]),
+                Tag::Equal => dump(&mut out, ' ', &a[alo..ahi]),
+            }
+        }
+        out
+    }
+
+    /// Replaces `a[alo..ahi]` by `b[blo..bhi]`, pairing up the most
+    /// similar lines (ratio over 0.75) and marking their differences.
+    #[allow(clippy::too_many_arguments)]
+    fn fancy_replace(
+        &self,
+        out: &mut Vec<String>,
+        a: &[&str],
+        alo: usize,
+        ahi: usize,
+        b: &[&str],
+        blo: usize,
+        bhi: usize,
+    ) {
+        let (mut best_ratio, cutoff) = (0.74, 0.75);
+        let (mut best_i, mut best_j) = (0, 0);
+        let mut eq = None;
+        let mut cruncher = SequenceMatcher::new(self.charjunk, [], []);
+        for (j, bj) in b.iter().enumerate().take(bhi).skip(blo) {
+            cruncher.set_seq2(bj.chars());
+            for (i, ai) in a.iter().enumerate().take(ahi).skip(alo) {
+                if ai == bj {
+                    eq.get_or_insert((i, j));
+                    continue;
+                }
+                cruncher.set_seq1(ai.chars());
+                if cruncher.real_quick_ratio() > best_ratio
+                    && cruncher.quick_ratio() > best_ratio
+                    && cruncher.ratio() > best_ratio
+                {
+                    (best_ratio, best_i, best_j) = (cruncher.ratio(), i, j);
+                }
+            }
+        }
+        if best_ratio < cutoff {
+            let Some((i, j)) = eq else {
+                plain_replace(out, &a[alo..ahi], &b[blo..bhi]);
+                return;
+            };
+            (best_i, best_j) = (i, j);
+        } else {
+            eq = None;
+        }
+        self.fancy_helper(out, a, alo, best_i, b, blo, best_j);
+        let (aelt, belt) = (a[best_i], b[best_j]);
+        if eq.is_none() {
+            let (mut atags, mut btags) = (String::new(), String::new());
+            cruncher.set_seqs(aelt.chars(), belt.chars());
+            for (tag, ai1, ai2, bj1, bj2) in cruncher.get_opcodes() {
+                let (la, lb) = (ai2 - ai1, bj2 - bj1);
+                let (amark, bmark) = match tag {
+                    Tag::Replace => ('^', '^'),
+                    Tag::Delete => ('-', ' '),
+                    Tag::Insert => (' ', '+'),
+                    Tag::Equal => (' ', ' '),
+                };
+                atags.extend(core::iter::repeat_n(amark, la));
+                btags.extend(core::iter::repeat_n(bmark, lb));
+            }
+            qformat(out, aelt, belt, &atags, &btags);
+        } else {
+            out.push(format!("  {}", aelt));
+        }
+        self.fancy_helper(out, a, best_i + 1, ahi, b, best_j + 1, bhi);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn fancy_helper(
+        &self,
+        out: &mut Vec<String>,
+        a: &[&str],
+        alo: usize,
+        ahi: usize,
+        b: &[&str],
+        blo: usize,
+        bhi: usize,
+    ) {
+        if alo < ahi {
+            if blo < bhi {
+                self.fancy_replace(out, a, alo, ahi, b, blo, bhi);
+            } else {
+                dump(out, '-', &a[alo..ahi]);
+            }
+        } else if blo < bhi {
+            dump(out, '+', &b[blo..bhi]);
+        }
+    }
+}
+
+fn dump(out: &mut Vec<String>, tag: char, lines: &[&str]) {
+    out.extend(lines.iter().map(|line| format!("{} {}", tag, line)));
+}
+
+fn plain_replace(out: &mut Vec<String>, a: &[&str], b: &[&str]) {
+    if b.len() < a.len() {
+        dump(out, '+', b);
+        dump(out, '-', a);
+    } else {
+        dump(out, '-', a);
+        dump(out, '+', b);
+    }
+}
+
+/// Keeps the tabs and spaces of `line` under blank marks, so the guide
+/// lines up with it.
+fn keep_original_ws(line: &str, tags: &str) -> String {
+    let guide: String = line
+        .chars()
+        .zip(tags.chars())
+        .map(|(c, tag)| {
+            if tag == ' ' && c.is_whitespace() {
+                c
+            } else {
+                tag
+            }
+        })
+        .collect();
+    guide.trim_end().to_string()
+}
+
+fn qformat(out: &mut Vec<String>, aline: &str, bline: &str, atags: &str, btags: &str) {
+    let atags = keep_original_ws(aline, atags);
+    let btags = keep_original_ws(bline, btags);
+    out.push(format!("- {}", aline));
+    if !atags.is_empty() {
+        out.push(format!("? {}\n", atags));
+    }
+    out.push(format!("+ {}", bline));
+    if !btags.is_empty() {
+        out.push(format!("? {}\n", btags));
+    }
+}
+
+/// Python-equivalent difflib.ndiff(a, b): Differ output with no line junk
+/// and spaces and tabs as character junk.
+pub fn ndiff<S: AsRef<str>>(a: &[S], b: &[S]) -> Vec<String> {
+    Differ::new(None, Some(is_character_junk)).compare(a, b)
+}
+
+/// Python-equivalent difflib.restore(delta, which): recovers sequence 1
+/// or 2 from ndiff() output.
+pub fn restore<S: AsRef<str>>(delta: &[S], which: u8) -> PyResult<Vec<String>> {
+    let tag = match which {
+        1 => "- ",
+        2 => "+ ",
+        _ => {
+            return Err(PyException::value_error(format!(
+                "unknown delta choice (must be 1 or 2): {}",
+                which
+            )))
+        }
+    };
+    Ok(delta
+        .iter()
+        .map(AsRef::as_ref)
+        .filter(|line| line.starts_with("  ") || line.starts_with(tag))
+        .map(|line| line[2..].to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(text: &str) -> Vec<&str> {
+        text.split_inclusive('\n').collect()
+    }
+
+    #[test]
+    fn matching_blocks_and_opcodes() {
+        let s = SequenceMatcher::new(None, "abxcd".chars(), "abcd".chars());
+        assert_eq!(
+            s.get_matching_blocks(),
+            [
+                Match {
+                    a: 0,
+                    b: 0,
+                    size: 2
+                },
+                Match {
+                    a: 3,
+                    b: 2,
+                    size: 2
+                },
+                Match {
+                    a: 5,
+                    b: 4,
+                    size: 0
+                },
+            ]
+        );
+        let s = SequenceMatcher::new(None, "qabxcd".chars(), "abycdf".chars());
+        let ops: Vec<String> = s
+            .get_opcodes()
+            .iter()
+            .map(|(tag, i1, i2, j1, j2)| format!("{} {} {} {} {}", tag, i1, i2, j1, j2))
+            .collect();
+        assert_eq!(
+            ops,
+            [
+                "delete 0 1 0 0",
+                "equal 1 3 0 2",
+                "replace 3 4 2 3",
+                "equal 4 6 3 5",
+                "insert 6 6 5 6",
+            ]
+        );
+    }
+
+    #[test]
+    fn ratios_and_junk() {
+        let s = SequenceMatcher::new(
+            Some(|c: &char| *c == ' '),
+            "private Thread currentThread;".chars(),
+            "private volatile Thread currentThread;".chars(),
+        );
+        assert_eq!(format!("{:.3}", s.ratio()), "0.866");
+        assert!(s.quick_ratio() >= s.ratio());
+        assert!(s.real_quick_ratio() >= s.quick_ratio());
+        assert!(s.bjunk().contains(&' '));
+        assert_eq!(SequenceMatcher::<char>::new(None, [], []).ratio(), 1.0);
+    }
+
+    #[test]
+    fn close_matches() {
+        let words = ["ape", "apple", "peach", "puppy"];
+        assert_eq!(
+            get_close_matches("appel", &words, 3, 0.6).unwrap(),
+            ["apple", "ape"]
+        );
+        assert!(get_close_matches("x", &words, 0, 0.6).is_err());
+        assert!(get_close_matches("x", &words, 3, 1.5).is_err());
+    }
+
+    #[test]
+    fn unified_matches_cpython() {
+        let a = lines("one\ntwo\nthree\nfour\n");
+        let b = lines("zero\none\ntree\nfour\n");
+        let diff = unified_diff(&a, &b, "Original", "Current");
+        assert_eq!(
+            diff.concat(),
+            "--- Original\n+++ Current\n@@ -1,4 +1,4 @@\n+zero\n one\n-two\n-three\n+tree\n four\n"
+        );
+        let kwargs = PyKwargs::new().with("n", 0).with("lineterm", "");
+        let diff = unified_diff_with(&["a", "b"], &["a", "c"], kwargs).unwrap();
+        assert_eq!(diff, ["--- ", "+++ ", "@@ -2 +2 @@", "-b", "+c"]);
+        assert!(unified_diff(&a, &a, "x", "y").is_empty());
+    }
+
+    #[test]
+    fn ndiff_marks_intraline_changes() {
+        let a = lines("one\ntwo\nthree\n");
+        let b = lines("ore\ntree\nemu\n");
+        let diff = ndiff(&a, &b);
+        assert_eq!(
+            diff.concat(),
+            "- one\n?  ^\n+ ore\n?  ^\n- two\n- three\n?  -\n+ tree\n+ emu\n"
+        );
+        assert_eq!(restore(&diff, 1).unwrap().concat(), a.concat());
+        assert_eq!(restore(&diff, 2).unwrap().concat(), b.concat());
+    }
+}
@@ -0,0 +1,318 @@
+//! Python-equivalent doctest module, turned around: the `>>>` examples and
+//! their expected output come from CPython, and each example's source is
+//! paired with the Rust calls that should behave the same. Running them
+//! under [`capture`] shows where this crate drifts from CPython.
+//!
+//! Output is compared as doctest does without option flags: exactly,
+//! except that `<BLANKLINE>` stands for an empty line and an expected
+//! traceback matches on its final `Type: message` line only.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::exceptions::PyResult;
+use crate::stdlib::io::capture;
+use crate::types::PyRepr;
+
+const TRACEBACK_HEADER: &str = "Traceback (most recent call last):";
+
+/// Python-equivalent doctest.Example.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    /// The statement, with `... ` continuation prompts removed and one
+    /// newline at the end.
+    pub source: String,
+    /// Expected output, each line ending in a newline; empty if none.
+    pub want: String,
+    /// The `Type: message` line(s) when `want` is a traceback.
+    pub exc_msg: Option<String>,
+    /// 0-based line of the `>>>` prompt.
+    pub lineno: usize,
+}
+
+/// Python-equivalent DocTestParser().get_examples(text): every `>>>`
+/// example, with its expected output running to the next blank line or
+/// prompt.
+pub fn get_examples(text: &str) -> Vec<Example> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut examples = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let indent = line.len() - line.trim_start().len();
+        let Some(first) = line[indent..].strip_prefix(">>>") else {
+            i += 1;
+            continue;
+        };
+        let lineno = i;
+        let mut source = format!("{}\n", first.strip_prefix(' ').unwrap_or(first));
+        i += 1;
+        while let Some(rest) = lines.get(i).and_then(|l| l.get(indent..)) {
+            let Some(more) = rest.strip_prefix("...") else {
+                break;
+            };
+            source.push_str(more.strip_prefix(' ').unwrap_or(more));
+            source.push('\n');
+            i += 1;
+        }
+        let mut want = String::new();
+        while let Some(line) = lines.get(i) {
+            let rest = line.get(indent..).unwrap_or("");
+            let dedented = line.len() - line.trim_start().len() < indent;
+            if line.trim().is_empty() || dedented || rest.starts_with(">>>") {
+                break;
+            }
+            if rest.trim_end() == "<BLANKLINE>" {
+                want.push('\n');
+            } else {
+                want.push_str(rest);
+                want.push('\n');
+            }
+            i += 1;
+        }
+        let exc_msg = want.strip_prefix(TRACEBACK_HEADER).map(|rest| {
+            // Skip the indented stack lines; what follows is the exception.
+            rest.lines()
+                .skip(1)
+                .skip_while(|l| l.starts_with(' ') || l.starts_with('\t') || l.is_empty())
+                .map(|l| format!("{}\n", l))
+                .collect()
+        });
+        examples.push(Example {
+            source,
+            want,
+            exc_msg,
+            lineno,
+        });
+    }
+    examples
+}
+
+/// Python-equivalent sys.displayhook: what the interactive prompt prints
+/// for an expression statement, i.e. its repr unless it is None.
+pub fn displayhook<T: PyRepr + ?Sized>(value: &T) {
+    let repr = value.py_repr();
+    if repr != "None" {
+        crate::print(repr);
+    }
+}
+
+/// An example whose output differs from CPython's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub example: Example,
+    pub got: String,
+}
+
+/// What running one document's examples found.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TestResults {
+    /// The document name, e.g. the corpus file.
+    pub name: String,
+    pub attempted: usize,
+    pub failures: Vec<Failure>,
+    /// Examples nobody supplied an implementation for.
+    pub missing: Vec<Example>,
+}
+
+impl TestResults {
+    pub fn passed(&self) -> usize {
+        self.attempted - self.failures.len()
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// `name: 3 passed, 1 failed, 0 missing`, then each divergence in the
+/// layout doctest reports failures with.
+impl fmt::Display for TestResults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} passed, {} failed, {} missing",
+            self.name,
+            self.passed(),
+            self.failures.len(),
+            self.missing.len()
+        )?;
+        for failure in &self.failures {
+            let example = &failure.example;
+            writeln!(f, "{}", "*".repeat(70))?;
+            writeln!(f, "Line {}, in {}", example.lineno + 1, self.name)?;
+            writeln!(f, "Failed example:")?;
+            for line in example.source.lines() {
+                writeln!(f, "    {}", line)?;
+            }
+            for (label, text) in [("Expected:", &example.want), ("Got:", &failure.got)] {
+                writeln!(f, "{}", label)?;
+                if text.is_empty() {
+                    writeln!(f, "    nothing")?;
+                }
+                for line in text.lines() {
+                    writeln!(f, "    {}", line)?;
+                }
+            }
+        }
+        for example in &self.missing {
+            writeln!(
+                f,
+                "Line {}, in {}: no implementation for {:?}",
+                example.lineno + 1,
+                self.name,
+                example.source.trim_end()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+type Implementation = Box<dyn Fn() -> PyResult<()>>;
+
+/// Python-equivalent doctest.DocTestRunner, running the Rust
+/// implementation registered for each example's source.
+#[derive(Default)]
+pub struct DocTestRunner {
+    implementations: BTreeMap<String, Implementation>,
+}
+
+impl DocTestRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pairs an example's source (without prompts) with the calls that
+    /// should print what CPython prints for it. Expression results are
+    /// shown with [`displayhook`].
+    pub fn example<F>(&mut self, source: &str, implementation: F) -> &mut Self
+    where
+        F: Fn() -> PyResult<()> + 'static,
+    {
+        let key = format!("{}\n", source.trim_end());
+        self.implementations.insert(key, Box::new(implementation));
+        self
+    }
+
+    /// Runs every example in `examples`, comparing captured print() output
+    /// (and any exception raised) with what CPython printed.
+    pub fn run(&self, name: &str, examples: &[Example]) -> TestResults {
+        let mut results = TestResults {
+            name: name.to_string(),
+            ..TestResults::default()
+        };
+        for example in examples {
+            let Some(implementation) = self.implementations.get(&example.source) else {
+                results.missing.push(example.clone());
+                continue;
+            };
+            results.attempted += 1;
+            let (outcome, mut got) = capture(implementation);
+            let matched = match (outcome, &example.exc_msg) {
+                (Ok(()), _) => got == example.want,
+                (Err(err), exc_msg) => {
+                    let raised = format!("{}\n", err);
+                    let matched = exc_msg.as_deref() == Some(raised.as_str());
+                    got.push_str(TRACEBACK_HEADER);
+                    got.push_str("\n  ...\n");
+                    got.push_str(&raised);
+                    matched
+                }
+            };
+            if !matched {
+                results.failures.push(Failure {
+                    example: example.clone(),
+                    got,
+                });
+            }
+        }
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exceptions::PyException;
+    use crate::types::PyValue;
+
+    const DOC: &str = "\
+Some prose.
+
+>>> 'a' + 'b'
+'ab'
+>>> for i in range(2):
+...     print(i)
+0
+1
+>>> print('x\\n')
+x
+<BLANKLINE>
+
+    >>> int('z')
+    Traceback (most recent call last):
+      File \"<stdin>\", line 1, in <module>
+    ValueError: invalid literal for int() with base 10: 'z'
+>>> None
+>>> 1 / 0
+0
+";
+
+    #[test]
+    fn parses_examples_like_doctest() {
+        let examples = get_examples(DOC);
+        assert_eq!(examples.len(), 6);
+        assert_eq!(examples[0].source, "'a' + 'b'\n");
+        assert_eq!(examples[0].want, "'ab'\n");
+        assert_eq!(examples[0].lineno, 2);
+        assert_eq!(examples[1].source, "for i in range(2):\n    print(i)\n");
+        assert_eq!(examples[1].want, "0\n1\n");
+        assert_eq!(examples[2].want, "x\n\n");
+        assert_eq!(
+            examples[3].exc_msg.as_deref(),
+            Some("ValueError: invalid literal for int() with base 10: 'z'\n")
+        );
+        assert_eq!(examples[4].want, "");
+    }
+
+    #[test]
+    fn runner_reports_divergences() {
+        let mut runner = DocTestRunner::new();
+        runner
+            .example("'a' + 'b'", || {
+                displayhook("ab");
+                Ok(())
+            })
+            .example("for i in range(2):\n    print(i)", || {
+                for i in crate::range(2) {
+                    crate::print(i);
+                }
+                Ok(())
+            })
+            .example("print('x\\n')", || {
+                crate::print("x\n");
+                Ok(())
+            })
+            .example("int('z')", || crate::int("z").map(|_| ()))
+            .example("None", || {
+                displayhook(&PyValue::None);
+                Ok(())
+            })
+            .example("1 / 0", || {
+                Err(PyException::new("ZeroDivisionError", "division by zero"))
+            });
+        let results = runner.run("DOC", &get_examples(DOC));
+        assert_eq!(results.attempted, 6);
+        assert_eq!(results.passed(), 5);
+        assert!(results.missing.is_empty());
+        let report = results.to_string();
+        assert!(report.starts_with("DOC: 5 passed, 1 failed, 0 missing\n"));
+        assert!(report
+            .contains("Line 18, in DOC\nFailed example:\n    1 / 0\nExpected:\n    0\nGot:\n"));
+        assert!(report.contains("    ZeroDivisionError: division by zero\n"));
+
+        let results = DocTestRunner::new().run("DOC", &get_examples(DOC));
+        assert_eq!((results.attempted, results.missing.len()), (0, 6));
+        assert!(!results.is_ok());
+    }
+}
@@ -0,0 +1,317 @@
+//! A python-dotenv style loader: reads `KEY=value` lines from a `.env` file
+//! into the process environment, as ops scripts do at startup.
+//!
+//! Lines may start with `export`; `#` starts a comment (inside an unquoted
+//! value, only after whitespace). Single-quoted values are literal.
+//! Double-quoted values take backslash escapes and, like unquoted ones,
+//! expand `${NAME}` and `${NAME:-default}`. Quoted values may span lines.
+
+use std::env;
+use std::fs;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::os::PathLike;
+use crate::types::PyDictionary;
+
+/// Python-equivalent dotenv.load_dotenv(path, override=...): sets each
+/// variable in the file, leaving ones already in the environment alone
+/// unless `override_`. A missing file sets nothing. Returns whether the
+/// file had any variables.
+pub fn load_dotenv<P: PathLike>(path: P, override_: bool) -> PyResult<bool> {
+    let path = path.fspath();
+    let text = match fs::read_to_string(&path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err.into()),
+    };
+    let values = parse(&text, override_)?;
+    for (key, value) in values.iter() {
+        if override_ || env::var_os(key).is_none() {
+            env::set_var(key, value);
+        }
+    }
+    Ok(!values.is_empty())
+}
+
+/// Python-equivalent dotenv.dotenv_values(path): the file's variables,
+/// without touching the environment.
+pub fn dotenv_values<P: PathLike>(path: P) -> PyResult<PyDictionary<String, String>> {
+    parse(&fs::read_to_string(path.fspath())?, false)
+}
+
+/// Parses `.env` text. `${NAME}` sees the environment first unless
+/// `override_`, in which case earlier values in the file win, matching what
+/// loading the file would leave in the environment.
+pub fn parse(text: &str, override_: bool) -> PyResult<PyDictionary<String, String>> {
+    let mut parser = Parser {
+        chars: text.chars().collect(),
+        pos: 0,
+        line: 1,
+    };
+    let mut values = PyDictionary::new();
+    while let Some((key, value)) = parser.binding()? {
+        let value = match value {
+            Value::Literal(value) => value,
+            Value::Expand(value) => expand(&value, &values, override_),
+        };
+        values.set_item(key, value);
+    }
+    Ok(values)
+}
+
+enum Value {
+    Literal(String),
+    Expand(String),
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, msg: &str) -> PyException {
+        PyException::value_error(format!("{} on line {} of .env file", msg, self.line))
+    }
+
+    fn skip_blanks(&mut self) {
+        while self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+            self.bump();
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.bump() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(c) = self
+            .peek()
+            .filter(|c| c.is_alphanumeric() || "_.-".contains(*c))
+        {
+            word.push(c);
+            self.bump();
+        }
+        word
+    }
+
+    /// The next `KEY=value`, skipping blank lines, comments and bare keys.
+    fn binding(&mut self) -> PyResult<Option<(String, Value)>> {
+        loop {
+            self.skip_blanks();
+            match self.peek() {
+                None => return Ok(None),
+                Some('\n' | '\r') => {
+                    self.bump();
+                    continue;
+                }
+                Some('#') => {
+                    self.skip_line();
+                    continue;
+                }
+                _ => {}
+            }
+            let mut key = self.word();
+            if key == "export" && self.peek().is_some_and(|c| c == ' ' || c == '\t') {
+                self.skip_blanks();
+                key = self.word();
+            }
+            if key.is_empty() {
+                return Err(self.error("expected a variable name"));
+            }
+            self.skip_blanks();
+            if self.peek() != Some('=') {
+                // A bare `KEY` has no value to set.
+                self.skip_line();
+                continue;
+            }
+            self.bump();
+            self.skip_blanks();
+            let value = match self.peek() {
+                Some(quote @ ('\'' | '"')) => {
+                    self.bump();
+                    let value = self.quoted(quote)?;
+                    self.skip_blanks();
+                    match self.peek() {
+                        None | Some('\n' | '\r' | '#') => {}
+                        Some(_) => return Err(self.error("unexpected text after quoted value")),
+                    }
+                    value
+                }
+                _ => Value::Expand(self.unquoted()),
+            };
+            self.skip_line();
+            return Ok(Some((key, value)));
+        }
+    }
+
+    fn quoted(&mut self, quote: char) -> PyResult<Value> {
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                None => return Err(self.error("unterminated quoted value")),
+                Some(c) if c == quote => break,
+                Some('\\') if quote == '"' => match self.bump() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some(c @ ('\\' | '"' | '\'' | '$')) => {
+                        // `\$` stays escaped until expansion sees it.
+                        if c == '$' {
+                            value.push('\\');
+                        }
+                        value.push(c);
+                    }
+                    Some(c) => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                    None => return Err(self.error("unterminated quoted value")),
+                },
+                Some(c) => value.push(c),
+            }
+        }
+        Ok(match quote {
+            '"' => Value::Expand(value),
+            _ => Value::Literal(value),
+        })
+    }
+
+    fn unquoted(&mut self) -> String {
+        let mut value = String::new();
+        while let Some(c) = self.peek().filter(|&c| c != '\n' && c != '\r') {
+            if c == '#' && value.ends_with([' ', '\t']) {
+                break;
+            }
+            value.push(c);
+            self.bump();
+        }
+        value.trim_end().to_string()
+    }
+}
+
+/// Replaces `${NAME}` and `${NAME:-default}`; an unset name without a
+/// default expands to nothing, and `\$` is a literal `$`.
+fn expand(value: &str, earlier: &PyDictionary<String, String>, override_: bool) -> String {
+    let lookup = |name: &str| {
+        let from_env = || env::var(name).ok();
+        let from_file = || earlier.get(name).cloned();
+        if override_ {
+            from_file().or_else(from_env)
+        } else {
+            from_env().or_else(from_file)
+        }
+    };
+    let mut out = String::new();
+    let mut rest = value;
+    while let Some(i) = rest.find(['$', '\\']) {
+        out.push_str(&rest[..i]);
+        rest = &rest[i..];
+        if let Some(after) = rest.strip_prefix("\\$") {
+            out.push('$');
+            rest = after;
+            continue;
+        }
+        let body = rest
+            .strip_prefix("${")
+            .and_then(|r| r.find('}').map(|end| (r, end)));
+        match body {
+            Some((r, end)) => {
+                let (name, default) = match r[..end].split_once(":-") {
+                    Some((name, default)) => (name, Some(default)),
+                    None => (&r[..end], None),
+                };
+                let found = lookup(name).filter(|v| !v.is_empty());
+                out.push_str(&found.or(default.map(String::from)).unwrap_or_default());
+                rest = &r[end + 1..];
+            }
+            None => {
+                out.push_str(&rest[..1]);
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quoting_exports_and_comments() {
+        env::set_var("STDPYTHON_DOTENV_HOME", "/home/app");
+        let text = concat!(
+            "# settings\n",
+            "export PORT=8080\n",
+            "HOST = example.com # trailing comment\n",
+            "URL=http://${HOST}:${PORT}/x#frag\n",
+            "LITERAL='${HOST} \\n stays'\n",
+            "ESCAPED=\"tab\\there\\n\\\"q\\\" \\$HOME\"\n",
+            "MULTI=\"line one\nline two\"\n",
+            "DATA=${STDPYTHON_DOTENV_HOME}/data\n",
+            "FALLBACK=${STDPYTHON_DOTENV_UNSET:-none}\n",
+            "BARE\n",
+            "EMPTY=\n",
+        );
+        let values = parse(text, false).unwrap();
+        let get = |k: &str| values.get(k).unwrap().as_str();
+        assert_eq!(get("PORT"), "8080");
+        assert_eq!(get("HOST"), "example.com");
+        assert_eq!(get("URL"), "http://example.com:8080/x#frag");
+        assert_eq!(get("LITERAL"), "${HOST} \\n stays");
+        assert_eq!(get("ESCAPED"), "tab\there\n\"q\" $HOME");
+        assert_eq!(get("MULTI"), "line one\nline two");
+        assert_eq!(get("DATA"), "/home/app/data");
+        assert_eq!(get("FALLBACK"), "none");
+        assert_eq!(get("EMPTY"), "");
+        assert!(!values.contains_key("BARE"));
+
+        let err = parse("A=\"open\n", false).unwrap_err();
+        assert_eq!(
+            err.message,
+            "unterminated quoted value on line 2 of .env file"
+        );
+        assert!(parse("A='x' y\n", false).unwrap_err().is("ValueError"));
+    }
+
+    #[test]
+    fn loads_into_the_environment() {
+        let path = env::temp_dir().join(format!("stdpython-dotenv-{}", std::process::id()));
+        fs::write(
+            &path,
+            "STDPYTHON_DOTENV_NEW=1\nSTDPYTHON_DOTENV_KEPT=file\n",
+        )
+        .unwrap();
+        env::set_var("STDPYTHON_DOTENV_KEPT", "env");
+        assert!(load_dotenv(&path, false).unwrap());
+        assert_eq!(env::var("STDPYTHON_DOTENV_NEW").unwrap(), "1");
+        assert_eq!(env::var("STDPYTHON_DOTENV_KEPT").unwrap(), "env");
+        assert!(load_dotenv(&path, true).unwrap());
+        assert_eq!(env::var("STDPYTHON_DOTENV_KEPT").unwrap(), "file");
+        assert_eq!(dotenv_values(&path).unwrap().len(), 2);
+        fs::remove_file(&path).unwrap();
+        assert!(!load_dotenv(&path, false).unwrap());
+        assert!(dotenv_values(&path).unwrap_err().is("FileNotFoundError"));
+    }
+}
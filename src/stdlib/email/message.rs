@@ -0,0 +1,553 @@
+//! Python-equivalent email.message module.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::base64;
+
+/// Headers that may appear at most once, as enforced by the default email policy.
+const UNIQUE_HEADERS: &[&str] = &[
+    "bcc",
+    "cc",
+    "content-disposition",
+    "content-transfer-encoding",
+    "content-type",
+    "date",
+    "from",
+    "message-id",
+    "mime-version",
+    "reply-to",
+    "sender",
+    "subject",
+    "to",
+];
+
+/// Maximum header line length before folding.
+const MAX_LINE_LEN: usize = 78;
+
+/// The body of a message: nothing yet, a decoded text/binary payload, or sub-parts.
+#[derive(Debug, Clone, PartialEq)]
+enum Payload {
+    Empty,
+    Text(String),
+    Bytes(Vec<u8>),
+    Multipart(Vec<EmailMessage>),
+}
+
+/// Python-equivalent email.message.EmailMessage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailMessage {
+    headers: Vec<(String, String)>,
+    payload: Payload,
+    boundary: Option<String>,
+}
+
+impl Default for EmailMessage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EmailMessage {
+    pub fn new() -> Self {
+        EmailMessage {
+            headers: Vec::new(),
+            payload: Payload::Empty,
+            boundary: None,
+        }
+    }
+
+    /// Python-equivalent msg[name] = value (appends; unique headers may only appear once).
+    pub fn set_item(&mut self, name: &str, value: &str) -> PyResult<()> {
+        let lower = name.to_ascii_lowercase();
+        if UNIQUE_HEADERS.contains(&lower.as_str()) && self.contains(name) {
+            return Err(PyException::value_error(format!(
+                "There may be at most 1 {} headers in a message",
+                name
+            )));
+        }
+        self.headers.push((name.to_string(), value.to_string()));
+        Ok(())
+    }
+
+    /// Python-equivalent del msg[name]: removes every occurrence, silently if absent.
+    pub fn del_item(&mut self, name: &str) {
+        self.headers.retain(|(k, _)| !k.eq_ignore_ascii_case(name));
+    }
+
+    /// Python-equivalent name in msg.
+    pub fn contains(&self, name: &str) -> bool {
+        self.headers
+            .iter()
+            .any(|(k, _)| k.eq_ignore_ascii_case(name))
+    }
+
+    /// Python-equivalent msg.get(name): the first matching header value.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Python-equivalent msg.get_all(name).
+    pub fn get_all(&self, name: &str) -> Vec<&str> {
+        self.headers
+            .iter()
+            .filter(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+            .collect()
+    }
+
+    /// Python-equivalent msg.replace_header(name, value); raises KeyError when absent.
+    pub fn replace_header(&mut self, name: &str, value: &str) -> PyResult<()> {
+        match self
+            .headers
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        {
+            Some(entry) => {
+                entry.1 = value.to_string();
+                Ok(())
+            }
+            None => Err(PyException::key_error(name)),
+        }
+    }
+
+    pub fn keys(&self) -> Vec<&str> {
+        self.headers.iter().map(|(k, _)| k.as_str()).collect()
+    }
+
+    pub fn values(&self) -> Vec<&str> {
+        self.headers.iter().map(|(_, v)| v.as_str()).collect()
+    }
+
+    pub fn items(&self) -> Vec<(&str, &str)> {
+        self.headers
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.headers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+
+    /// Python-equivalent msg.get_content_type(), defaulting to text/plain.
+    pub fn get_content_type(&self) -> String {
+        self.get("Content-Type")
+            .and_then(|v| v.split(';').next())
+            .map(|v| v.trim().to_ascii_lowercase())
+            .filter(|v| v.contains('/'))
+            .unwrap_or_else(|| "text/plain".to_string())
+    }
+
+    pub fn get_content_maintype(&self) -> String {
+        let ctype = self.get_content_type();
+        ctype.split('/').next().unwrap_or("text").to_string()
+    }
+
+    pub fn get_content_subtype(&self) -> String {
+        let ctype = self.get_content_type();
+        ctype.split('/').nth(1).unwrap_or("plain").to_string()
+    }
+
+    /// Python-equivalent msg.get_filename() from Content-Disposition.
+    pub fn get_filename(&self) -> Option<String> {
+        self.get("Content-Disposition")
+            .and_then(|v| header_param(v, "filename"))
+    }
+
+    pub fn get_boundary(&self) -> Option<&str> {
+        self.boundary.as_deref()
+    }
+
+    pub fn is_multipart(&self) -> bool {
+        matches!(self.payload, Payload::Multipart(_))
+    }
+
+    pub fn is_attachment(&self) -> bool {
+        self.get("Content-Disposition")
+            .map(|v| {
+                v.trim_start()
+                    .to_ascii_lowercase()
+                    .starts_with("attachment")
+            })
+            .unwrap_or(false)
+    }
+
+    /// Python-equivalent msg.iter_parts(): the direct sub-parts of a multipart message.
+    pub fn iter_parts(&self) -> impl Iterator<Item = &EmailMessage> {
+        let parts: &[EmailMessage] = match &self.payload {
+            Payload::Multipart(parts) => parts,
+            _ => &[],
+        };
+        parts.iter()
+    }
+
+    /// Python-equivalent msg.iter_attachments().
+    pub fn iter_attachments(&self) -> impl Iterator<Item = &EmailMessage> {
+        self.iter_parts().filter(|p| p.is_attachment())
+    }
+
+    /// Python-equivalent msg.get_content() for text parts.
+    pub fn get_content(&self) -> Option<&str> {
+        match &self.payload {
+            Payload::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// The decoded bytes of a non-multipart part (text parts are UTF-8 encoded).
+    pub fn get_payload_bytes(&self) -> Option<Vec<u8>> {
+        match &self.payload {
+            Payload::Text(text) => Some(text.as_bytes().to_vec()),
+            Payload::Bytes(data) => Some(data.clone()),
+            _ => None,
+        }
+    }
+
+    /// Python-equivalent msg.set_content(text) producing a text/plain body.
+    pub fn set_content(&mut self, text: &str) {
+        self.set_text_content(text, "plain");
+    }
+
+    /// Python-equivalent msg.set_content(text, subtype=...) (e.g. "html").
+    pub fn set_text_content(&mut self, text: &str, subtype: &str) {
+        self.clear_content();
+        let mut text = text.to_string();
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        self.headers.push((
+            "Content-Type".to_string(),
+            format!("text/{}; charset=\"utf-8\"", subtype),
+        ));
+        self.headers.push((
+            "Content-Transfer-Encoding".to_string(),
+            text_transfer_encoding(&text).to_string(),
+        ));
+        self.ensure_mime_version();
+        self.payload = Payload::Text(text);
+    }
+
+    /// Python-equivalent msg.set_content(data, maintype, subtype) for binary content.
+    pub fn set_bytes_content(&mut self, data: &[u8], maintype: &str, subtype: &str) {
+        self.clear_content();
+        self.headers.push((
+            "Content-Type".to_string(),
+            format!("{}/{}", maintype, subtype),
+        ));
+        self.headers.push((
+            "Content-Transfer-Encoding".to_string(),
+            "base64".to_string(),
+        ));
+        self.ensure_mime_version();
+        self.payload = Payload::Bytes(data.to_vec());
+    }
+
+    /// Python-equivalent msg.add_attachment(text, subtype=..., filename=...).
+    pub fn add_text_attachment(&mut self, text: &str, subtype: &str, filename: Option<&str>) {
+        let mut part = EmailMessage::new();
+        part.set_text_content(text, subtype);
+        self.attach_part(part, filename);
+    }
+
+    /// Python-equivalent msg.add_attachment(data, maintype=..., subtype=..., filename=...).
+    pub fn add_attachment(
+        &mut self,
+        data: &[u8],
+        maintype: &str,
+        subtype: &str,
+        filename: Option<&str>,
+    ) {
+        let mut part = EmailMessage::new();
+        part.set_bytes_content(data, maintype, subtype);
+        self.attach_part(part, filename);
+    }
+
+    fn attach_part(&mut self, mut part: EmailMessage, filename: Option<&str>) {
+        let disposition = match filename {
+            Some(name) => format!("attachment; filename=\"{}\"", quote_param(name)),
+            None => "attachment".to_string(),
+        };
+        part.headers.insert(
+            1.min(part.headers.len()),
+            ("Content-Disposition".to_string(), disposition),
+        );
+        self.make_mixed();
+        if let Payload::Multipart(parts) = &mut self.payload {
+            parts.push(part);
+        }
+    }
+
+    /// Python-equivalent msg.make_mixed(): moves any existing body into the first sub-part.
+    pub fn make_mixed(&mut self) {
+        if self.is_multipart() {
+            return;
+        }
+        let mut parts = Vec::new();
+        if !matches!(self.payload, Payload::Empty) {
+            let mut body = EmailMessage::new();
+            let (content, rest): (Vec<_>, Vec<_>) = self
+                .headers
+                .drain(..)
+                .partition(|(k, _)| k.to_ascii_lowercase().starts_with("content-"));
+            self.headers = rest;
+            body.headers = content;
+            body.payload = std::mem::replace(&mut self.payload, Payload::Empty);
+            parts.push(body);
+        } else {
+            self.clear_content();
+        }
+        let boundary = make_boundary();
+        self.headers.push((
+            "Content-Type".to_string(),
+            format!("multipart/mixed; boundary=\"{}\"", boundary),
+        ));
+        self.ensure_mime_version();
+        self.boundary = Some(boundary);
+        self.payload = Payload::Multipart(parts);
+    }
+
+    /// Python-equivalent msg.clear_content(): drops the body and Content-* headers.
+    pub fn clear_content(&mut self) {
+        self.headers
+            .retain(|(k, _)| !k.to_ascii_lowercase().starts_with("content-"));
+        self.payload = Payload::Empty;
+        self.boundary = None;
+    }
+
+    fn ensure_mime_version(&mut self) {
+        if !self.contains("MIME-Version") {
+            self.headers
+                .push(("MIME-Version".to_string(), "1.0".to_string()));
+        }
+    }
+
+    /// Python-equivalent msg.as_string() using "\n" line endings.
+    pub fn as_string(&self) -> String {
+        let mut out = String::new();
+        self.write_to(&mut out);
+        out
+    }
+
+    /// Python-equivalent msg.as_bytes() using "\r\n" line endings, as sent over SMTP.
+    pub fn as_bytes(&self) -> Vec<u8> {
+        let text = self.as_string();
+        let mut out = Vec::with_capacity(text.len() + text.len() / 40);
+        for line in text.split_inclusive('\n') {
+            match line.strip_suffix('\n') {
+                Some(body) => {
+                    out.extend_from_slice(body.strip_suffix('\r').unwrap_or(body).as_bytes());
+                    out.extend_from_slice(b"\r\n");
+                }
+                None => out.extend_from_slice(line.as_bytes()),
+            }
+        }
+        out
+    }
+
+    fn write_to(&self, out: &mut String) {
+        for (name, value) in &self.headers {
+            out.push_str(&fold_header(name, &encode_header_value(value)));
+            out.push('\n');
+        }
+        out.push('\n');
+        match &self.payload {
+            Payload::Empty => {}
+            Payload::Text(text) => {
+                if self.transfer_encoding() == "base64" {
+                    out.push_str(&base64_lines(text.as_bytes()));
+                } else {
+                    out.push_str(text);
+                }
+            }
+            Payload::Bytes(data) => out.push_str(&base64_lines(data)),
+            Payload::Multipart(parts) => {
+                let boundary = self.boundary.as_deref().unwrap_or_default();
+                for part in parts {
+                    out.push_str("--");
+                    out.push_str(boundary);
+                    out.push('\n');
+                    part.write_to(out);
+                    if !out.ends_with('\n') {
+                        out.push('\n');
+                    }
+                    out.push('\n');
+                }
+                out.push_str("--");
+                out.push_str(boundary);
+                out.push_str("--\n");
+            }
+        }
+    }
+
+    fn transfer_encoding(&self) -> String {
+        self.get("Content-Transfer-Encoding")
+            .map(|v| v.trim().to_ascii_lowercase())
+            .unwrap_or_else(|| "7bit".to_string())
+    }
+}
+
+impl fmt::Display for EmailMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_string())
+    }
+}
+
+/// 7bit for short ASCII lines, base64 otherwise (always safe for SMTP servers without 8BITMIME).
+fn text_transfer_encoding(text: &str) -> &'static str {
+    if text.is_ascii() && text.lines().all(|line| line.len() <= MAX_LINE_LEN) {
+        "7bit"
+    } else {
+        "base64"
+    }
+}
+
+fn base64_lines(data: &[u8]) -> String {
+    String::from_utf8(base64::encodebytes(data)).expect("base64 output is ASCII")
+}
+
+/// RFC 2047 encoded-word for non-ASCII header values.
+fn encode_header_value(value: &str) -> String {
+    if value.is_ascii() {
+        return value.to_string();
+    }
+    let encoded = base64::b64encode(value.as_bytes());
+    format!("=?utf-8?b?{}?=", String::from_utf8_lossy(&encoded))
+}
+
+/// Folds a header at whitespace so lines stay within 78 characters where possible.
+fn fold_header(name: &str, value: &str) -> String {
+    let mut out = format!("{}:", name);
+    let mut line_len = out.len();
+    for word in value.split(' ').filter(|w| !w.is_empty()) {
+        if line_len + 1 + word.len() > MAX_LINE_LEN && line_len > name.len() + 1 {
+            out.push_str("\n ");
+            line_len = 1;
+        } else {
+            out.push(' ');
+            line_len += 1;
+        }
+        out.push_str(word);
+        line_len += word.len();
+    }
+    out
+}
+
+fn quote_param(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Extracts a `name=value` parameter from a structured header such as Content-Disposition.
+fn header_param(header: &str, name: &str) -> Option<String> {
+    header.split(';').skip(1).find_map(|param| {
+        let (key, value) = param.split_once('=')?;
+        if !key.trim().eq_ignore_ascii_case(name) {
+            return None;
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .map(|v| v.replace("\\\"", "\"").replace("\\\\", "\\"))
+            .unwrap_or_else(|| value.to_string());
+        Some(value)
+    })
+}
+
+/// Boundary in the same shape CPython generates: 15 '=' then 19 digits then "==".
+fn make_boundary() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let mut token = nanos
+        ^ COUNTER
+            .fetch_add(1, Ordering::Relaxed)
+            .wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    token ^= token >> 33;
+    token = token.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    token ^= token >> 33;
+    format!("==============={:019}==", token % (i64::MAX as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn headers_are_case_insensitive_and_unique() {
+        let mut msg = EmailMessage::new();
+        msg.set_item("Subject", "Hello").unwrap();
+        assert_eq!(msg.get("subject"), Some("Hello"));
+        assert!(msg
+            .set_item("SUBJECT", "Again")
+            .unwrap_err()
+            .is("ValueError"));
+        msg.set_item("Received", "a").unwrap();
+        msg.set_item("Received", "b").unwrap();
+        assert_eq!(msg.get_all("received"), vec!["a", "b"]);
+        msg.del_item("received");
+        assert!(!msg.contains("Received"));
+        assert!(msg.replace_header("X-Missing", "v").is_err());
+    }
+
+    #[test]
+    fn simple_text_message() {
+        let mut msg = EmailMessage::new();
+        msg.set_item("Subject", "Build finished").unwrap();
+        msg.set_content("All green.");
+        assert_eq!(
+            msg.as_string(),
+            "Subject: Build finished\n\
+             Content-Type: text/plain; charset=\"utf-8\"\n\
+             Content-Transfer-Encoding: 7bit\n\
+             MIME-Version: 1.0\n\
+             \n\
+             All green.\n"
+        );
+        assert_eq!(msg.get_content(), Some("All green.\n"));
+    }
+
+    #[test]
+    fn attachments_make_multipart() {
+        let mut msg = EmailMessage::new();
+        msg.set_content("See attached.");
+        msg.add_attachment(&[0, 1, 2], "application", "octet-stream", Some("data.bin"));
+        msg.add_text_attachment("a,b\n", "csv", Some("report.csv"));
+        assert!(msg.is_multipart());
+        assert_eq!(msg.get_content_type(), "multipart/mixed");
+        assert_eq!(msg.iter_parts().count(), 3);
+        let names: Vec<_> = msg
+            .iter_attachments()
+            .filter_map(|p| p.get_filename())
+            .collect();
+        assert_eq!(names, vec!["data.bin", "report.csv"]);
+
+        let text = msg.as_string();
+        let boundary = msg.get_boundary().unwrap();
+        assert!(text.contains(&format!("--{}\nContent-Type: text/plain", boundary)));
+        assert!(text.contains("Content-Transfer-Encoding: base64\nMIME-Version: 1.0\n\nAAEC\n"));
+        assert!(text.ends_with(&format!("--{}--\n", boundary)));
+    }
+
+    #[test]
+    fn non_ascii_is_encoded() {
+        let mut msg = EmailMessage::new();
+        msg.set_item("Subject", "café").unwrap();
+        msg.set_content("naïve");
+        let text = msg.as_string();
+        assert!(text.contains("Subject: =?utf-8?b?Y2Fmw6k=?="));
+        assert!(text.contains("Content-Transfer-Encoding: base64"));
+        assert!(msg.as_bytes().windows(2).any(|w| w == b"\r\n"));
+    }
+}
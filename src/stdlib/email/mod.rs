@@ -0,0 +1,5 @@
+//! Python-equivalent email package (message construction and serialization).
+
+pub mod message;
+
+pub use message::EmailMessage;
@@ -0,0 +1,621 @@
+//! Python-equivalent enum module: the runtime half of compiled Enum,
+//! IntEnum and Flag classes.
+//!
+//! [`py_enum!`](crate::py_enum) turns a class body into a Rust type. Enum
+//! and IntEnum members become the variants of a fieldless enum; Flag
+//! members become associated constants of a bit-set struct, since any
+//! combination of them is a valid value too. A member written without a
+//! value takes auto()'s value, as in CPython 3.11.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::PyRepr;
+
+/// Implemented by [`py_enum!`](crate::py_enum) for Enum and IntEnum classes.
+pub trait PyEnum: Copy + Eq + 'static {
+    type Value: PartialEq + PyRepr + 'static;
+
+    const CLASS: &'static str;
+    /// Every member, in definition order: what `for m in Cls` visits.
+    const MEMBERS: &'static [Self];
+
+    /// Python-equivalent member.name.
+    fn name(&self) -> &'static str;
+
+    /// Python-equivalent member.value.
+    fn value(&self) -> &'static Self::Value;
+
+    /// Python-equivalent Cls(value): the first member with that value.
+    fn from_value(value: &Self::Value) -> PyResult<Self> {
+        Self::MEMBERS
+            .iter()
+            .copied()
+            .find(|m| m.value() == value)
+            .ok_or_else(|| {
+                PyException::value_error(format!(
+                    "{} is not a valid {}",
+                    value.py_repr(),
+                    Self::CLASS
+                ))
+            })
+    }
+
+    /// Python-equivalent Cls[name].
+    fn from_name(name: &str) -> PyResult<Self> {
+        Self::MEMBERS
+            .iter()
+            .copied()
+            .find(|m| m.name() == name)
+            .ok_or_else(|| PyException::key_error(name))
+    }
+}
+
+/// The generated `__repr__` of an Enum member: `<Color.RED: 1>`.
+pub fn enum_repr<E: PyEnum>(member: &E) -> String {
+    format!(
+        "<{}.{}: {}>",
+        E::CLASS,
+        member.name(),
+        member.value().py_repr()
+    )
+}
+
+/// Implemented by [`py_enum!`](crate::py_enum) for Flag classes.
+pub trait PyFlag: Copy + Eq + 'static {
+    const CLASS: &'static str;
+    const NAMES: &'static [&'static str];
+    const VALUES: &'static [i64];
+
+    fn bits(&self) -> i64;
+
+    /// Wraps `bits` without checking them; see [`from_value`](PyFlag::from_value).
+    fn from_bits(bits: i64) -> Self;
+
+    /// Every bit some member uses.
+    fn mask() -> i64 {
+        Self::VALUES.iter().fold(0, |acc, v| acc | v)
+    }
+
+    /// Python-equivalent Cls(value): any combination of member bits.
+    fn from_value(value: i64) -> PyResult<Self> {
+        if value & !Self::mask() != 0 {
+            return Err(PyException::value_error(format!(
+                "<flag '{}'> invalid value {}",
+                Self::CLASS,
+                value
+            )));
+        }
+        Ok(Self::from_bits(value))
+    }
+
+    /// Python-equivalent Cls[name].
+    fn from_name(name: &str) -> PyResult<Self> {
+        Self::NAMES
+            .iter()
+            .position(|n| *n == name)
+            .map(|i| Self::from_bits(Self::VALUES[i]))
+            .ok_or_else(|| PyException::key_error(name))
+    }
+
+    /// Python-equivalent flag.name: a member's own name, the names of the
+    /// single-bit members it combines joined by `|`, or None for zero.
+    fn name(&self) -> Option<String> {
+        let bits = self.bits();
+        if let Some(i) = Self::VALUES.iter().position(|v| *v == bits) {
+            return Some(Self::NAMES[i].into());
+        }
+        if bits == 0 {
+            return None;
+        }
+        let names: Vec<&str> = flag_members::<Self>(bits).map(|(n, _)| n).collect();
+        Some(names.join("|"))
+    }
+
+    /// Python-equivalent `other in flag`.
+    fn contains_flag(&self, other: Self) -> bool {
+        self.bits() & other.bits() == other.bits()
+    }
+}
+
+/// The canonical (single-bit) members set in `bits`, in definition order.
+fn flag_members<F: PyFlag>(bits: i64) -> impl Iterator<Item = (&'static str, i64)> {
+    F::NAMES
+        .iter()
+        .zip(F::VALUES)
+        .filter(move |(_, v)| v.count_ones() == 1 && bits & **v != 0)
+        .map(|(n, v)| (*n, *v))
+}
+
+/// Iterator over the members a flag value contains: `for m in flag`.
+pub struct FlagIter<F> {
+    bits: i64,
+    next: usize,
+    marker: core::marker::PhantomData<F>,
+}
+
+impl<F: PyFlag> FlagIter<F> {
+    pub fn new(flag: F) -> Self {
+        FlagIter {
+            bits: flag.bits(),
+            next: 0,
+            marker: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<F: PyFlag> Iterator for FlagIter<F> {
+    type Item = F;
+
+    fn next(&mut self) -> Option<F> {
+        let (_, value) = flag_members::<F>(self.bits).nth(self.next)?;
+        self.next += 1;
+        Some(F::from_bits(value))
+    }
+}
+
+/// len(flag): how many single-bit members it contains.
+pub fn flag_len<F: PyFlag>(flag: &F) -> usize {
+    flag_members::<F>(flag.bits()).count()
+}
+
+/// The generated `__repr__` of a flag: `<Perm.R|W: 3>`, or `<Perm: 0>`.
+pub fn flag_repr<F: PyFlag>(flag: &F) -> String {
+    match flag.name() {
+        Some(name) => format!("<{}.{}: {}>", F::CLASS, name, flag.bits()),
+        None => format!("<{}: {}>", F::CLASS, flag.bits()),
+    }
+}
+
+/// The generated `__str__` of a flag: `Perm.R|W`, or `Perm(0)`.
+pub fn flag_str<F: PyFlag>(flag: &F) -> String {
+    match flag.name() {
+        Some(name) => format!("{}.{}", F::CLASS, name),
+        None => format!("{}({})", F::CLASS, flag.bits()),
+    }
+}
+
+/// Member values with auto() resolved the way CPython 3.11 does: one more
+/// than the previous value for Enum, the next power of two above the
+/// highest value so far for Flag, and 1 for the first member.
+pub const fn auto_values<const N: usize>(spec: [Option<i64>; N], flag: bool) -> [i64; N] {
+    let mut out = [0; N];
+    let mut i = 0;
+    while i < N {
+        out[i] = match spec[i] {
+            Some(value) => value,
+            None if i == 0 => 1,
+            None if flag => {
+                let mut highest = out[0];
+                let mut j = 1;
+                while j < i {
+                    if out[j] > highest {
+                        highest = out[j];
+                    }
+                    j += 1;
+                }
+                if highest <= 0 {
+                    1
+                } else {
+                    1 << (64 - (highest as u64).leading_zeros())
+                }
+            }
+            None => out[i - 1] + 1,
+        };
+        i += 1;
+    }
+    out
+}
+
+/// The value `values` holds for `name`, for declaring flag constants.
+pub const fn member_value(names: &[&str], values: &[i64], name: &str) -> i64 {
+    let mut i = 0;
+    while i < names.len() {
+        if str_eq(names[i], name) {
+            return values[i];
+        }
+        i += 1;
+    }
+    panic!("unknown flag member")
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Declares a compiled `class X(Enum)`, `X(IntEnum)` or `X(Flag)`.
+///
+/// `enum Color: Enum { RED = 1, GREEN }` gives int values, with members
+/// lacking a value taking auto(). `enum Mode: Enum<&'static str> { .. }`
+/// takes values of another type, all explicit. IntEnum members also
+/// compare and do arithmetic with `i64`, giving `i64` results. Flag
+/// declares a struct whose members are associated constants combined with
+/// `|`, `&`, `^` and `!`.
+#[macro_export]
+macro_rules! py_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident : Enum < $vty:ty > {
+            $($(#[$vmeta:meta])* $variant:ident = $value:expr),* $(,)?
+        }
+    ) => {
+        $crate::py_enum!(@enum [$(#[$meta])*] $vis $name $vty
+            [$($(#[$vmeta])* $variant)*] (&[$($value),*]) []);
+        $crate::py_enum!(@display $name);
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident : Enum {
+            $($(#[$vmeta:meta])* $variant:ident $(= $value:expr)?),* $(,)?
+        }
+    ) => {
+        $crate::py_enum!(@enum [$(#[$meta])*] $vis $name i64
+            [$($(#[$vmeta])* $variant)*]
+            (&$crate::stdlib::enum_::auto_values([$($crate::py_enum!(@spec $($value)?)),*], false))
+            []);
+        $crate::py_enum!(@display $name);
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident : IntEnum {
+            $($(#[$vmeta:meta])* $variant:ident $(= $value:expr)?),* $(,)?
+        }
+    ) => {
+        $crate::py_enum!(@enum [$(#[$meta])*] $vis $name i64
+            [$($(#[$vmeta])* $variant)*]
+            (&$crate::stdlib::enum_::auto_values([$($crate::py_enum!(@spec $($value)?)),*], false))
+            [$crate::types::PyTypeId::Int]);
+
+        // IntEnum's str() is int's since 3.11.
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(f, "{}", i64::from(*self))
+            }
+        }
+
+        impl From<$name> for i64 {
+            fn from(member: $name) -> i64 {
+                *<$name as $crate::stdlib::enum_::PyEnum>::value(&member)
+            }
+        }
+
+        impl From<$name> for $crate::types::PyValue {
+            fn from(member: $name) -> Self {
+                $crate::types::PyValue::Int(i64::from(member))
+            }
+        }
+
+        impl PartialEq<i64> for $name {
+            fn eq(&self, other: &i64) -> bool {
+                i64::from(*self) == *other
+            }
+        }
+
+        impl PartialEq<$name> for i64 {
+            fn eq(&self, other: &$name) -> bool {
+                *self == i64::from(*other)
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+                i64::from(*self).partial_cmp(&i64::from(*other))
+            }
+        }
+
+        impl PartialOrd<i64> for $name {
+            fn partial_cmp(&self, other: &i64) -> Option<::core::cmp::Ordering> {
+                i64::from(*self).partial_cmp(other)
+            }
+        }
+
+        $crate::py_enum!(@int_op $name Add add +);
+        $crate::py_enum!(@int_op $name Sub sub -);
+        $crate::py_enum!(@int_op $name Mul mul *);
+        $crate::py_enum!(@int_op $name BitAnd bitand &);
+        $crate::py_enum!(@int_op $name BitOr bitor |);
+    };
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident : Flag {
+            $($(#[$vmeta:meta])* $variant:ident $(= $value:expr)?),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        $vis struct $name(i64);
+
+        #[allow(non_upper_case_globals)]
+        impl $name {
+            $(
+                $(#[$vmeta])*
+                pub const $variant: $name = $name($crate::stdlib::enum_::member_value(
+                    <$name as $crate::stdlib::enum_::PyFlag>::NAMES,
+                    <$name as $crate::stdlib::enum_::PyFlag>::VALUES,
+                    stringify!($variant),
+                ));
+            )*
+        }
+
+        impl $crate::stdlib::enum_::PyFlag for $name {
+            const CLASS: &'static str = stringify!($name);
+            const NAMES: &'static [&'static str] = &[$(stringify!($variant)),*];
+            const VALUES: &'static [i64] =
+                &$crate::stdlib::enum_::auto_values([$($crate::py_enum!(@spec $($value)?)),*], true);
+
+            fn bits(&self) -> i64 {
+                self.0
+            }
+
+            fn from_bits(bits: i64) -> Self {
+                $name(bits)
+            }
+        }
+
+        impl ::core::ops::BitOr for $name {
+            type Output = $name;
+
+            fn bitor(self, rhs: $name) -> $name {
+                $name(self.0 | rhs.0)
+            }
+        }
+
+        impl ::core::ops::BitAnd for $name {
+            type Output = $name;
+
+            fn bitand(self, rhs: $name) -> $name {
+                $name(self.0 & rhs.0)
+            }
+        }
+
+        impl ::core::ops::BitXor for $name {
+            type Output = $name;
+
+            fn bitxor(self, rhs: $name) -> $name {
+                $name(self.0 ^ rhs.0)
+            }
+        }
+
+        /// `~flag`: the member bits not set, never negative.
+        impl ::core::ops::Not for $name {
+            type Output = $name;
+
+            fn not(self) -> $name {
+                $name(!self.0 & <$name as $crate::stdlib::enum_::PyFlag>::mask())
+            }
+        }
+
+        impl $crate::types::PyContains<$name> for $name {
+            fn py_contains(&self, item: &$name) -> bool {
+                $crate::stdlib::enum_::PyFlag::contains_flag(self, *item)
+            }
+        }
+
+        impl $crate::types::PyLen for $name {
+            fn py_len(&self) -> usize {
+                $crate::stdlib::enum_::flag_len(self)
+            }
+        }
+
+        impl $crate::types::PyTruthy for $name {
+            fn py_bool(&self) -> bool {
+                self.0 != 0
+            }
+        }
+
+        impl $crate::types::PyIterable for $name {
+            type Item<'a> = $name;
+            type Iter<'a> = $crate::stdlib::enum_::FlagIter<$name>;
+
+            fn py_iter(&self) -> Self::Iter<'_> {
+                $crate::stdlib::enum_::FlagIter::new(*self)
+            }
+        }
+
+        impl $crate::types::PyTyped for $name {
+            fn py_type(&self) -> $crate::types::PyTypeId {
+                $crate::types::register_class(stringify!($name), &[])
+            }
+        }
+
+        impl $crate::types::PyRepr for $name {
+            fn py_repr(&self) -> $crate::stdlib::enum_::ReprString {
+                $crate::stdlib::enum_::flag_repr(self)
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(&$crate::stdlib::enum_::flag_str(self))
+            }
+        }
+    };
+
+    (@spec) => { None };
+    (@spec $value:expr) => { Some($value) };
+
+    (@enum [$($meta:tt)*] $vis:vis $name:ident $vty:ty
+        [$($(#[$vmeta:meta])* $variant:ident)*] ($values:expr) [$($base:path),*]) => {
+        $($meta)*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
+        $vis enum $name {
+            $($(#[$vmeta])* $variant,)*
+        }
+
+        impl $name {
+            const VALUES: &'static [$vty] = $values;
+        }
+
+        impl $crate::stdlib::enum_::PyEnum for $name {
+            type Value = $vty;
+
+            const CLASS: &'static str = stringify!($name);
+            const MEMBERS: &'static [Self] = &[$($name::$variant),*];
+
+            fn name(&self) -> &'static str {
+                match self {
+                    $($name::$variant => stringify!($variant),)*
+                }
+            }
+
+            fn value(&self) -> &'static $vty {
+                &$name::VALUES[*self as usize]
+            }
+        }
+
+        impl $crate::types::PyTyped for $name {
+            fn py_type(&self) -> $crate::types::PyTypeId {
+                $crate::types::register_class(stringify!($name), &[$($base),*])
+            }
+        }
+
+        impl $crate::types::PyRepr for $name {
+            fn py_repr(&self) -> $crate::stdlib::enum_::ReprString {
+                $crate::stdlib::enum_::enum_repr(self)
+            }
+        }
+    };
+
+    (@display $name:ident) => {
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let name = $crate::stdlib::enum_::PyEnum::name(self);
+                write!(f, "{}.{}", stringify!($name), name)
+            }
+        }
+    };
+
+    (@int_op $name:ident $trait:ident $method:ident $op:tt) => {
+        impl ::core::ops::$trait<i64> for $name {
+            type Output = i64;
+
+            fn $method(self, rhs: i64) -> i64 {
+                i64::from(self) $op rhs
+            }
+        }
+
+        impl ::core::ops::$trait<$name> for $name {
+            type Output = i64;
+
+            fn $method(self, rhs: $name) -> i64 {
+                i64::from(self) $op i64::from(rhs)
+            }
+        }
+
+        impl ::core::ops::$trait<$name> for i64 {
+            type Output = i64;
+
+            fn $method(self, rhs: $name) -> i64 {
+                self $op i64::from(rhs)
+            }
+        }
+    };
+}
+
+/// What the generated `__repr__` returns, named here so the expansion
+/// compiles in no_std crates without `alloc` in scope.
+pub type ReprString = String;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PyContains, PyIterable, PyLen, PyTruthy};
+    use alloc::string::ToString;
+
+    crate::py_enum! {
+        enum Color: Enum {
+            RED = 1,
+            GREEN,
+            BLUE = 10,
+            PINK,
+        }
+    }
+
+    crate::py_enum! {
+        enum Mode: Enum<&'static str> {
+            READ = "r",
+            WRITE = "w",
+        }
+    }
+
+    crate::py_enum! {
+        enum Level: IntEnum {
+            LOW = 1,
+            HIGH,
+        }
+    }
+
+    crate::py_enum! {
+        enum Perm: Flag {
+            R,
+            W,
+            X,
+            RW = 3,
+        }
+    }
+
+    #[test]
+    fn enum_members_and_lookup() {
+        let values: Vec<i64> = Color::MEMBERS.iter().map(|c| *c.value()).collect();
+        assert_eq!(values, [1, 2, 10, 11]);
+        assert_eq!(Color::GREEN.py_repr(), "<Color.GREEN: 2>");
+        assert_eq!(Color::GREEN.to_string(), "Color.GREEN");
+        assert_eq!(Color::from_value(&10).unwrap(), Color::BLUE);
+        assert_eq!(
+            Color::from_value(&5).unwrap_err().to_string(),
+            "ValueError: 5 is not a valid Color"
+        );
+        assert_eq!(Color::from_name("PINK").unwrap(), Color::PINK);
+        assert_eq!(
+            Color::from_name("X").unwrap_err().to_string(),
+            "KeyError: 'X'"
+        );
+        assert_eq!(Mode::WRITE.py_repr(), "<Mode.WRITE: 'w'>");
+        assert_eq!(Mode::from_value(&"r").unwrap(), Mode::READ);
+    }
+
+    #[test]
+    fn int_enum_acts_as_int() {
+        assert_eq!(Level::HIGH.to_string(), "2");
+        assert_eq!(Level::LOW + 1, 2);
+        assert_eq!(10 - Level::HIGH, 8);
+        assert!(Level::LOW < Level::HIGH && Level::HIGH == 2 && Level::HIGH > 1);
+        assert_eq!(Level::HIGH.py_repr(), "<Level.HIGH: 2>");
+        assert!(crate::builtins::isinstance(
+            &Level::LOW,
+            crate::types::PyTypeId::Int
+        ));
+    }
+
+    #[test]
+    fn flag_bitwise_operations() {
+        assert_eq!((Perm::R.bits(), Perm::W.bits(), Perm::X.bits()), (1, 2, 4));
+        let rx = Perm::R | Perm::X;
+        assert_eq!(rx.py_repr(), "<Perm.R|X: 5>");
+        assert_eq!((Perm::R | Perm::W).to_string(), "Perm.RW");
+        assert_eq!((!Perm::R).to_string(), "Perm.W|X");
+        assert_eq!((rx & Perm::W).py_repr(), "<Perm: 0>");
+        assert_eq!((rx & Perm::W).to_string(), "Perm(0)");
+        assert!(rx.py_contains(&Perm::X) && !rx.py_contains(&Perm::RW));
+        assert_eq!(rx.py_len(), 2);
+        assert!(!(rx ^ rx).py_bool());
+        assert_eq!(rx.py_iter().collect::<Vec<_>>(), [Perm::R, Perm::X]);
+        assert_eq!(Perm::from_value(6).unwrap(), Perm::W | Perm::X);
+        assert!(Perm::from_value(8).unwrap_err().is("ValueError"));
+        assert_eq!(Perm::from_name("RW").unwrap(), Perm::RW);
+    }
+}
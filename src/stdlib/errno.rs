@@ -0,0 +1,138 @@
+//! Python-equivalent errno module: the standard error codes of the target
+//! platform and the errorcode mapping back to their names.
+//!
+//! Apple platforms number codes past ERANGE differently; every other target
+//! uses the Linux numbering.
+
+macro_rules! errno_table {
+    ($($name:ident = $linux:expr, $apple:expr;)*) => {
+        $(
+            #[cfg(not(target_vendor = "apple"))]
+            pub const $name: i32 = $linux;
+            #[cfg(target_vendor = "apple")]
+            pub const $name: i32 = $apple;
+        )*
+
+        /// `(code, name)` for every constant above, in numeric order on Linux.
+        pub const ERRORCODE: &[(i32, &str)] = &[$(($name, stringify!($name))),*];
+    };
+}
+
+errno_table! {
+    EPERM = 1, 1;
+    ENOENT = 2, 2;
+    ESRCH = 3, 3;
+    EINTR = 4, 4;
+    EIO = 5, 5;
+    ENXIO = 6, 6;
+    E2BIG = 7, 7;
+    ENOEXEC = 8, 8;
+    EBADF = 9, 9;
+    ECHILD = 10, 10;
+    EAGAIN = 11, 35;
+    ENOMEM = 12, 12;
+    EACCES = 13, 13;
+    EFAULT = 14, 14;
+    ENOTBLK = 15, 15;
+    EBUSY = 16, 16;
+    EEXIST = 17, 17;
+    EXDEV = 18, 18;
+    ENODEV = 19, 19;
+    ENOTDIR = 20, 20;
+    EISDIR = 21, 21;
+    EINVAL = 22, 22;
+    ENFILE = 23, 23;
+    EMFILE = 24, 24;
+    ENOTTY = 25, 25;
+    ETXTBSY = 26, 26;
+    EFBIG = 27, 27;
+    ENOSPC = 28, 28;
+    ESPIPE = 29, 29;
+    EROFS = 30, 30;
+    EMLINK = 31, 31;
+    EPIPE = 32, 32;
+    EDOM = 33, 33;
+    ERANGE = 34, 34;
+    EDEADLK = 35, 11;
+    ENAMETOOLONG = 36, 63;
+    ENOLCK = 37, 77;
+    ENOSYS = 38, 78;
+    ENOTEMPTY = 39, 66;
+    ELOOP = 40, 62;
+    EOVERFLOW = 75, 84;
+    EILSEQ = 84, 92;
+    ENOTSOCK = 88, 38;
+    EDESTADDRREQ = 89, 39;
+    EMSGSIZE = 90, 40;
+    EPROTOTYPE = 91, 41;
+    ENOPROTOOPT = 92, 42;
+    EPROTONOSUPPORT = 93, 43;
+    ENOTSUP = 95, 45;
+    EAFNOSUPPORT = 97, 47;
+    EADDRINUSE = 98, 48;
+    EADDRNOTAVAIL = 99, 49;
+    ENETDOWN = 100, 50;
+    ENETUNREACH = 101, 51;
+    ENETRESET = 102, 52;
+    ECONNABORTED = 103, 53;
+    ECONNRESET = 104, 54;
+    ENOBUFS = 105, 55;
+    EISCONN = 106, 56;
+    ENOTCONN = 107, 57;
+    ESHUTDOWN = 108, 58;
+    ETIMEDOUT = 110, 60;
+    ECONNREFUSED = 111, 61;
+    EHOSTDOWN = 112, 64;
+    EHOSTUNREACH = 113, 65;
+    EALREADY = 114, 37;
+    EINPROGRESS = 115, 36;
+    ESTALE = 116, 70;
+    EDQUOT = 122, 69;
+    ECANCELED = 125, 89;
+}
+
+/// Python-equivalent errno.EWOULDBLOCK, an alias everywhere.
+pub const EWOULDBLOCK: i32 = EAGAIN;
+
+/// Python-equivalent errno.EOPNOTSUPP: an alias of ENOTSUP on Linux only.
+#[cfg(not(target_vendor = "apple"))]
+pub const EOPNOTSUPP: i32 = ENOTSUP;
+#[cfg(target_vendor = "apple")]
+pub const EOPNOTSUPP: i32 = 102;
+
+/// Python-equivalent errno.errorcode[code].
+pub fn errorcode(code: i32) -> Option<&'static str> {
+    ERRORCODE
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, name)| *name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip() {
+        assert_eq!(errorcode(ENOENT), Some("ENOENT"));
+        assert_eq!(errorcode(EWOULDBLOCK), Some("EAGAIN"));
+        assert_eq!(errorcode(0), None);
+        for (i, (code, _)) in ERRORCODE.iter().enumerate() {
+            assert!(ERRORCODE[i + 1..].iter().all(|(c, _)| c != code));
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn linux_values_match_cpython() {
+        assert_eq!(
+            (EAGAIN, EDEADLK, ENOTEMPTY, ECONNREFUSED),
+            (11, 35, 39, 111)
+        );
+        #[cfg(feature = "std")]
+        assert_eq!(
+            std::io::Error::from_raw_os_error(EEXIST).kind(),
+            std::io::ErrorKind::AlreadyExists
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! Python-equivalent fcntl module: descriptor flags and advisory flock()
+//! locks, on Linux, Android and Apple platforms.
+//!
+//! Descriptors are anything [`FileDescriptor`] accepts, as select() takes
+//! them. Calls interrupted by a signal are retried (PEP 475).
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+use crate::exceptions::PyException;
+use crate::exceptions::PyResult;
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+use crate::stdlib::os::fd::{retry, sys};
+use crate::stdlib::select::FileDescriptor;
+
+pub const F_GETFD: i32 = 1;
+pub const F_SETFD: i32 = 2;
+pub const F_GETFL: i32 = 3;
+pub const F_SETFL: i32 = 4;
+pub const FD_CLOEXEC: i32 = 1;
+
+pub const LOCK_SH: i32 = 1;
+pub const LOCK_EX: i32 = 2;
+pub const LOCK_NB: i32 = 4;
+pub const LOCK_UN: i32 = 8;
+
+/// Python-equivalent fcntl.fcntl(fd, cmd, arg) for the integer-argument
+/// commands, e.g. `fcntl(fd, F_SETFL, fcntl(fd, F_GETFL, 0)? | O_NONBLOCK)`.
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+pub fn fcntl<F: FileDescriptor>(fd: F, cmd: i32, arg: i32) -> PyResult<i32> {
+    let fd = fd.fileno()?;
+    retry(|| unsafe { sys::fcntl(fd, cmd, arg) })
+}
+
+/// Python-equivalent fcntl.flock(fd, operation): LOCK_SH, LOCK_EX or
+/// LOCK_UN, with LOCK_NB to raise BlockingIOError instead of waiting.
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+pub fn flock<F: FileDescriptor>(fd: F, operation: i32) -> PyResult<()> {
+    let fd = fd.fileno()?;
+    retry(|| unsafe { sys::flock(fd, operation) })?;
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+pub fn fcntl<F: FileDescriptor>(_fd: F, _cmd: i32, _arg: i32) -> PyResult<i32> {
+    Err(PyException::os_error(
+        "fcntl is not supported on this platform",
+    ))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+pub fn flock<F: FileDescriptor>(_fd: F, _operation: i32) -> PyResult<()> {
+    Err(PyException::os_error(
+        "flock is not supported on this platform",
+    ))
+}
+
+#[cfg(all(
+    test,
+    any(target_os = "linux", target_os = "android", target_vendor = "apple")
+))]
+mod tests {
+    use super::*;
+    use crate::stdlib::os;
+
+    #[test]
+    fn flags_and_locks() {
+        let (r, w) = os::pipe().unwrap();
+        let flags = fcntl(r, F_GETFL, 0).unwrap();
+        assert_eq!(flags & os::O_NONBLOCK, 0);
+        fcntl(r, F_SETFL, flags | os::O_NONBLOCK).unwrap();
+        assert_ne!(fcntl(r, F_GETFL, 0).unwrap() & os::O_NONBLOCK, 0);
+        assert!(os::read(r, 1).unwrap_err().is("BlockingIOError"));
+        assert_eq!(fcntl(r, F_GETFD, 0).unwrap() & FD_CLOEXEC, FD_CLOEXEC);
+        os::close(r).unwrap();
+        os::close(w).unwrap();
+
+        let path = std::env::temp_dir().join(format!("stdpython-flock-{}", std::process::id()));
+        let a = std::fs::File::create(&path).unwrap();
+        let b = std::fs::File::open(&path).unwrap();
+        flock(&a, LOCK_EX).unwrap();
+        // flock locks belong to the open file description, so a second
+        // open of the same file conflicts even within one process.
+        assert!(flock(&b, LOCK_SH | LOCK_NB)
+            .unwrap_err()
+            .is("BlockingIOError"));
+        flock(&a, LOCK_UN).unwrap();
+        flock(&b, LOCK_SH | LOCK_NB).unwrap();
+        assert!(fcntl(-1, F_GETFL, 0).unwrap_err().is("ValueError"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
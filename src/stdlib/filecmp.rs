@@ -0,0 +1,409 @@
+//! Python-equivalent filecmp module: compare files by stat() signature and
+//! contents, and directories one level at a time (dircmp). Built on
+//! os.stat() and pathlib, so paths may be `&str`, `std::path::Path` or
+//! pathlib's `Path`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path as StdPath, PathBuf};
+
+use crate::builtins::repr;
+use crate::exceptions::PyResult;
+use crate::print;
+use crate::stdlib::os;
+use crate::stdlib::pathlib::Path;
+use crate::stdlib::stat::{s_ifmt, S_IFDIR, S_IFREG};
+use crate::sync::Lock;
+
+/// Python-equivalent filecmp.BUFSIZE.
+pub const BUFSIZE: usize = 8 * 1024;
+
+/// Python-equivalent filecmp.DEFAULT_IGNORES.
+pub const DEFAULT_IGNORES: &[&str] = &[
+    "RCS",
+    "CVS",
+    "tags",
+    ".git",
+    ".hg",
+    ".bzr",
+    "_darcs",
+    "__pycache__",
+];
+
+/// `(file type, size, mtime in ns)`: files with equal signatures are
+/// taken to be equal by a shallow comparison.
+type Signature = (u32, u64, i64);
+
+type CacheKey = (PathBuf, PathBuf, Signature, Signature);
+
+/// Content comparisons, valid while both files keep their signatures.
+static CACHE: Lock<Option<HashMap<CacheKey, bool>>> = Lock::new(None);
+
+fn signature(path: &StdPath) -> PyResult<Signature> {
+    let st = os::stat(path, true)?;
+    Ok((s_ifmt(st.st_mode), st.st_size, st.st_mtime_ns))
+}
+
+/// Python-equivalent filecmp.clear_cache().
+pub fn clear_cache() {
+    *CACHE.lock() = None;
+}
+
+/// Python-equivalent filecmp.cmp(f1, f2, shallow): true if the files seem
+/// equal. Non-regular files never are; with `shallow`, equal stat()
+/// signatures are enough, otherwise the contents are compared.
+pub fn cmp<P: AsRef<StdPath>, Q: AsRef<StdPath>>(f1: P, f2: Q, shallow: bool) -> PyResult<bool> {
+    let (f1, f2) = (f1.as_ref(), f2.as_ref());
+    let (s1, s2) = (signature(f1)?, signature(f2)?);
+    if s1.0 != S_IFREG || s2.0 != S_IFREG {
+        return Ok(false);
+    }
+    if shallow && s1 == s2 {
+        return Ok(true);
+    }
+    if s1.1 != s2.1 {
+        return Ok(false);
+    }
+    let key = (f1.to_path_buf(), f2.to_path_buf(), s1, s2);
+    if let Some(&outcome) = CACHE.lock().as_ref().and_then(|c| c.get(&key)) {
+        return Ok(outcome);
+    }
+    let outcome = do_cmp(f1, f2)?;
+    let mut cache = CACHE.lock();
+    let cache = cache.get_or_insert_with(HashMap::new);
+    if cache.len() > 100 {
+        cache.clear();
+    }
+    cache.insert(key, outcome);
+    Ok(outcome)
+}
+
+fn read_chunk(file: &mut File, buf: &mut [u8]) -> PyResult<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+fn do_cmp(f1: &StdPath, f2: &StdPath) -> PyResult<bool> {
+    let (mut a, mut b) = (File::open(f1)?, File::open(f2)?);
+    let (mut buf1, mut buf2) = ([0u8; BUFSIZE], [0u8; BUFSIZE]);
+    loop {
+        let n1 = read_chunk(&mut a, &mut buf1)?;
+        let n2 = read_chunk(&mut b, &mut buf2)?;
+        if buf1[..n1] != buf2[..n2] {
+            return Ok(false);
+        }
+        if n1 == 0 {
+            return Ok(true);
+        }
+    }
+}
+
+/// Python-equivalent filecmp.cmpfiles(a, b, common, shallow): the names in
+/// `common` split into `(match, mismatch, errors)`, errors being names
+/// that could not be compared in both directories.
+pub fn cmpfiles<P, Q, S>(
+    a: P,
+    b: Q,
+    common: &[S],
+    shallow: bool,
+) -> (Vec<String>, Vec<String>, Vec<String>)
+where
+    P: AsRef<StdPath>,
+    Q: AsRef<StdPath>,
+    S: AsRef<str>,
+{
+    let (mut same, mut diff, mut funny) = (Vec::new(), Vec::new(), Vec::new());
+    for name in common {
+        let name = name.as_ref();
+        let target = match cmp(a.as_ref().join(name), b.as_ref().join(name), shallow) {
+            Ok(true) => &mut same,
+            Ok(false) => &mut diff,
+            Err(_) => &mut funny,
+        };
+        target.push(name.to_string());
+    }
+    (same, diff, funny)
+}
+
+/// Python-equivalent filecmp.dircmp: a comparison of two directories,
+/// computed when created, with one nested dircmp per common subdirectory.
+/// Names are compared exactly; `ignore` and `hide` list names left out of
+/// both sides.
+#[derive(Debug, Clone)]
+pub struct DirCmp {
+    pub left: PathBuf,
+    pub right: PathBuf,
+    pub ignore: Vec<String>,
+    pub hide: Vec<String>,
+    /// Sorted entries of each side, less the hidden and ignored names.
+    pub left_list: Vec<String>,
+    pub right_list: Vec<String>,
+    pub common: Vec<String>,
+    pub left_only: Vec<String>,
+    pub right_only: Vec<String>,
+    pub common_dirs: Vec<String>,
+    pub common_files: Vec<String>,
+    /// Common names of differing types, or that stat() fails on.
+    pub common_funny: Vec<String>,
+    pub same_files: Vec<String>,
+    pub diff_files: Vec<String>,
+    /// Common files that could not be compared.
+    pub funny_files: Vec<String>,
+    pub subdirs: BTreeMap<String, DirCmp>,
+}
+
+fn list_dir(dir: &StdPath, skip: &[String]) -> PyResult<Vec<String>> {
+    let mut names: Vec<String> = Path::new(dir)
+        .iterdir()?
+        .iter()
+        .map(|path| path.name())
+        .filter(|name| !skip.contains(name))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+impl DirCmp {
+    /// Python-equivalent filecmp.dircmp(a, b, ignore=None, hide=None):
+    /// `ignore` defaults to [`DEFAULT_IGNORES`] and `hide` to "." and "..".
+    pub fn new<P: AsRef<StdPath>, Q: AsRef<StdPath>>(
+        a: P,
+        b: Q,
+        ignore: Option<&[&str]>,
+        hide: Option<&[&str]>,
+    ) -> PyResult<DirCmp> {
+        let owned = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        Self::compare(
+            a.as_ref().to_path_buf(),
+            b.as_ref().to_path_buf(),
+            owned(ignore.unwrap_or(DEFAULT_IGNORES)),
+            owned(hide.unwrap_or(&[".", ".."])),
+        )
+    }
+
+    fn compare(
+        left: PathBuf,
+        right: PathBuf,
+        ignore: Vec<String>,
+        hide: Vec<String>,
+    ) -> PyResult<DirCmp> {
+        let skip: Vec<String> = hide.iter().chain(&ignore).cloned().collect();
+        let left_list = list_dir(&left, &skip)?;
+        let right_list = list_dir(&right, &skip)?;
+        let (common, left_only): (Vec<String>, Vec<String>) = left_list
+            .iter()
+            .cloned()
+            .partition(|name| right_list.contains(name));
+        let right_only = right_list
+            .iter()
+            .filter(|name| !left_list.contains(name))
+            .cloned()
+            .collect();
+
+        let (mut common_dirs, mut common_files, mut common_funny) =
+            (Vec::new(), Vec::new(), Vec::new());
+        for name in &common {
+            let kinds = os::stat(left.join(name), true).and_then(|a| {
+                Ok((
+                    s_ifmt(a.st_mode),
+                    s_ifmt(os::stat(right.join(name), true)?.st_mode),
+                ))
+            });
+            match kinds {
+                Ok((S_IFDIR, S_IFDIR)) => common_dirs.push(name.clone()),
+                Ok((S_IFREG, S_IFREG)) => common_files.push(name.clone()),
+                _ => common_funny.push(name.clone()),
+            }
+        }
+        let (same_files, diff_files, funny_files) = cmpfiles(&left, &right, &common_files, true);
+
+        let mut subdirs = BTreeMap::new();
+        for name in &common_dirs {
+            let sub = Self::compare(
+                left.join(name),
+                right.join(name),
+                ignore.clone(),
+                hide.clone(),
+            )?;
+            subdirs.insert(name.clone(), sub);
+        }
+        Ok(DirCmp {
+            left,
+            right,
+            ignore,
+            hide,
+            left_list,
+            right_list,
+            common,
+            left_only,
+            right_only,
+            common_dirs,
+            common_files,
+            common_funny,
+            same_files,
+            diff_files,
+            funny_files,
+            subdirs,
+        })
+    }
+
+    /// This comparison and those of all subdirectories below it, top-down
+    /// in the order os.walk() would visit them.
+    pub fn walk(&self) -> Vec<&DirCmp> {
+        let mut out = vec![self];
+        for sub in self.subdirs.values() {
+            out.extend(sub.walk());
+        }
+        out
+    }
+
+    /// Python-equivalent dircmp.report(): prints the differences between
+    /// the two directories.
+    pub fn report(&self) {
+        print(format!(
+            "diff {} {}",
+            self.left.display(),
+            self.right.display()
+        ));
+        if !self.left_only.is_empty() {
+            print(format!(
+                "Only in {} : {}",
+                self.left.display(),
+                repr(&self.left_only)
+            ));
+        }
+        if !self.right_only.is_empty() {
+            print(format!(
+                "Only in {} : {}",
+                self.right.display(),
+                repr(&self.right_only)
+            ));
+        }
+        let sections = [
+            ("Identical files", &self.same_files),
+            ("Differing files", &self.diff_files),
+            ("Trouble with common files", &self.funny_files),
+            ("Common subdirectories", &self.common_dirs),
+            ("Common funny cases", &self.common_funny),
+        ];
+        for (title, names) in sections {
+            if !names.is_empty() {
+                print(format!("{} : {}", title, repr(names)));
+            }
+        }
+    }
+
+    /// Python-equivalent dircmp.report_partial_closure(): this report and
+    /// those of the immediate subdirectories.
+    pub fn report_partial_closure(&self) {
+        self.report();
+        for sub in self.subdirs.values() {
+            print("");
+            sub.report();
+        }
+    }
+
+    /// Python-equivalent dircmp.report_full_closure(): reports for the
+    /// whole tree, recursively.
+    pub fn report_full_closure(&self) {
+        self.report();
+        for sub in self.subdirs.values() {
+            print("");
+            sub.report_full_closure();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::io::capture;
+    use std::{env, fs};
+
+    fn fixture(name: &str) -> PathBuf {
+        let root =
+            env::temp_dir().join(format!("stdpython_filecmp_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        for (side, files) in [
+            (
+                "a",
+                &[("same", "x"), ("diff", "one"), ("left", ""), ("sub/f", "1")][..],
+            ),
+            (
+                "b",
+                &[
+                    ("same", "x"),
+                    ("diff", "three"),
+                    ("right", ""),
+                    ("sub/f", "22"),
+                ][..],
+            ),
+        ] {
+            fs::create_dir_all(root.join(side).join("sub")).unwrap();
+            fs::create_dir_all(root.join(side).join(".git")).unwrap();
+            for (file, text) in files {
+                fs::write(root.join(side).join(file), text).unwrap();
+            }
+        }
+        fs::write(root.join("a/kind"), "").unwrap();
+        fs::create_dir(root.join("b/kind")).unwrap();
+        root
+    }
+
+    #[test]
+    fn cmp_by_signature_and_contents() {
+        let root = fixture("cmp");
+        assert!(cmp(root.join("a/same"), root.join("b/same"), false).unwrap());
+        assert!(!cmp(root.join("a/diff"), root.join("b/diff"), false).unwrap());
+        assert!(!cmp(root.join("a/sub"), root.join("b/sub"), true).unwrap());
+        assert!(cmp(root.join("a/missing"), root.join("b/same"), true).is_err());
+        let (same, diff, errors) = cmpfiles(
+            root.join("a"),
+            root.join("b"),
+            &["same", "diff", "left"],
+            false,
+        );
+        assert_eq!(
+            (same, diff, errors),
+            (
+                vec!["same".to_string()],
+                vec!["diff".to_string()],
+                vec!["left".to_string()]
+            )
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn dircmp_classifies_and_reports() {
+        let root = fixture("dircmp");
+        let (a, b) = (root.join("a"), root.join("b"));
+        let dc = DirCmp::new(Path::new(&a), &b, None, None).unwrap();
+        assert_eq!(dc.left_list, ["diff", "kind", "left", "same", "sub"]);
+        assert_eq!(dc.left_only, ["left"]);
+        assert_eq!(dc.right_only, ["right"]);
+        assert_eq!(dc.common_dirs, ["sub"]);
+        assert_eq!(dc.common_funny, ["kind"]);
+        assert_eq!(dc.diff_files, ["diff"]);
+        assert_eq!(dc.subdirs["sub"].diff_files, ["f"]);
+        assert_eq!(dc.walk().len(), 2);
+
+        let ((), out) = capture(|| dc.report_full_closure());
+        let expected = format!(
+            "diff {a} {b}\nOnly in {a} : ['left']\nOnly in {b} : ['right']\n\
+             Identical files : ['same']\nDiffering files : ['diff']\nCommon subdirectories : ['sub']\n\
+             Common funny cases : ['kind']\n\ndiff {a}/sub {b}/sub\nDiffering files : ['f']\n",
+            a = a.display(),
+            b = b.display()
+        );
+        assert_eq!(out, expected);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,356 @@
+//! A py-filelock style advisory lock on a file, for scripts coordinating
+//! access to shared files: flock(2) on Linux, Android and Apple platforms,
+//! LockFileEx on Windows.
+//!
+//! `with lock.acquire(timeout=5):` becomes holding the [`FileLockGuard`]
+//! that [`FileLock::acquire`] returns; the lock is released when it drops.
+//! Acquiring again while held only bumps a counter, as py-filelock does.
+
+use std::fs::{File, OpenOptions};
+use std::path::{Path as StdPath, PathBuf};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::os::PathLike;
+use crate::sync::Lock;
+
+/// Whether other holders may lock the file at the same time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockMode {
+    /// Any number of shared holders, as a read lock.
+    Shared,
+    /// A single holder, as a write lock.
+    #[default]
+    Exclusive,
+}
+
+/// Python-equivalent filelock.FileLock(lock_file).
+pub struct FileLock {
+    path: PathBuf,
+    mode: LockMode,
+    poll_interval: Duration,
+    held: Lock<Held>,
+}
+
+struct Held {
+    file: Option<File>,
+    count: usize,
+}
+
+/// Holds one acquisition of a [`FileLock`]; dropping it releases that
+/// acquisition.
+pub struct FileLockGuard<'a> {
+    lock: &'a FileLock,
+}
+
+impl Drop for FileLockGuard<'_> {
+    fn drop(&mut self) {
+        let _ = self.lock.release(false);
+    }
+}
+
+impl FileLock {
+    /// An exclusive lock on `path`, which is created if missing and left in
+    /// place on release.
+    pub fn new<P: PathLike>(path: P) -> Self {
+        FileLock {
+            path: PathBuf::from(path.fspath()),
+            mode: LockMode::Exclusive,
+            poll_interval: Duration::from_millis(50),
+            held: Lock::new(Held {
+                file: None,
+                count: 0,
+            }),
+        }
+    }
+
+    pub fn with_mode(mut self, mode: LockMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// How long acquire() sleeps between attempts while waiting.
+    pub fn with_poll_interval(mut self, seconds: f64) -> Self {
+        self.poll_interval = Duration::try_from_secs_f64(seconds.max(0.0)).unwrap_or(Duration::MAX);
+        self
+    }
+
+    /// Python-equivalent FileLock.lock_file.
+    pub fn lock_file(&self) -> &StdPath {
+        &self.path
+    }
+
+    pub fn mode(&self) -> LockMode {
+        self.mode
+    }
+
+    /// Python-equivalent FileLock.is_locked.
+    pub fn is_locked(&self) -> bool {
+        self.held.lock().file.is_some()
+    }
+
+    /// Python-equivalent FileLock.lock_counter.
+    pub fn lock_counter(&self) -> usize {
+        self.held.lock().count
+    }
+
+    /// Python-equivalent FileLock.acquire(timeout): waits up to `timeout`
+    /// seconds, forever for None or a negative timeout, and tries once for
+    /// 0. Raises TimeoutError if the lock is still held elsewhere.
+    pub fn acquire(&self, timeout: Option<f64>) -> PyResult<FileLockGuard<'_>> {
+        let deadline = match timeout {
+            Some(t) if t.is_nan() => {
+                return Err(PyException::value_error("timeout must not be NaN"))
+            }
+            // A deadline too far off for Instant never comes.
+            Some(t) if t >= 0.0 => Duration::try_from_secs_f64(t)
+                .ok()
+                .and_then(|wait| Instant::now().checked_add(wait)),
+            _ => None,
+        };
+        loop {
+            {
+                let mut held = self.held.lock();
+                if held.file.is_some() {
+                    held.count += 1;
+                    return Ok(FileLockGuard { lock: self });
+                }
+                let file = OpenOptions::new()
+                    .read(true)
+                    .write(true)
+                    .create(true)
+                    .truncate(false)
+                    .open(&self.path)?;
+                if try_lock(&file, self.mode)? {
+                    held.file = Some(file);
+                    held.count = 1;
+                    return Ok(FileLockGuard { lock: self });
+                }
+            }
+            let now = Instant::now();
+            if deadline.is_some_and(|d| now >= d) {
+                return Err(PyException::new(
+                    "TimeoutError",
+                    format!(
+                        "The file lock '{}' could not be acquired.",
+                        self.path.display()
+                    ),
+                ));
+            }
+            let wait = deadline.map_or(self.poll_interval, |d| self.poll_interval.min(d - now));
+            thread::sleep(wait);
+            crate::stdlib::signal::check_signals()?;
+        }
+    }
+
+    /// Python-equivalent FileLock.release(force): undoes one acquire(), or
+    /// all of them with `force`, unlocking the file when none remain.
+    pub fn release(&self, force: bool) -> PyResult<()> {
+        let mut held = self.held.lock();
+        if held.file.is_none() {
+            return Ok(());
+        }
+        held.count = if force { 0 } else { held.count - 1 };
+        if held.count == 0 {
+            if let Some(file) = held.file.take() {
+                unlock(&file)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = self.release(true);
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+fn try_lock(file: &File, mode: LockMode) -> PyResult<bool> {
+    use crate::stdlib::fcntl::{flock, LOCK_EX, LOCK_NB, LOCK_SH};
+    let operation = match mode {
+        LockMode::Shared => LOCK_SH,
+        LockMode::Exclusive => LOCK_EX,
+    };
+    match flock(file, operation | LOCK_NB) {
+        Ok(()) => Ok(true),
+        Err(err) if err.is("BlockingIOError") => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+fn unlock(file: &File) -> PyResult<()> {
+    crate::stdlib::fcntl::flock(file, crate::stdlib::fcntl::LOCK_UN)
+}
+
+#[cfg(windows)]
+mod win {
+    use std::ffi::c_void;
+
+    pub const LOCKFILE_FAIL_IMMEDIATELY: u32 = 0x1;
+    pub const LOCKFILE_EXCLUSIVE_LOCK: u32 = 0x2;
+    pub const ERROR_LOCK_VIOLATION: i32 = 33;
+
+    #[repr(C)]
+    pub struct Overlapped {
+        pub internal: usize,
+        pub internal_high: usize,
+        pub offset: u32,
+        pub offset_high: u32,
+        pub event: *mut c_void,
+    }
+
+    impl Overlapped {
+        /// Locks are taken on the whole file from offset 0.
+        pub fn at_start() -> Self {
+            Overlapped {
+                internal: 0,
+                internal_high: 0,
+                offset: 0,
+                offset_high: 0,
+                event: std::ptr::null_mut(),
+            }
+        }
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        pub fn LockFileEx(
+            file: *mut c_void,
+            flags: u32,
+            reserved: u32,
+            bytes_low: u32,
+            bytes_high: u32,
+            overlapped: *mut Overlapped,
+        ) -> i32;
+        pub fn UnlockFileEx(
+            file: *mut c_void,
+            reserved: u32,
+            bytes_low: u32,
+            bytes_high: u32,
+            overlapped: *mut Overlapped,
+        ) -> i32;
+    }
+}
+
+#[cfg(windows)]
+fn try_lock(file: &File, mode: LockMode) -> PyResult<bool> {
+    use std::os::windows::io::AsRawHandle;
+    let mut flags = win::LOCKFILE_FAIL_IMMEDIATELY;
+    if mode == LockMode::Exclusive {
+        flags |= win::LOCKFILE_EXCLUSIVE_LOCK;
+    }
+    let mut overlapped = win::Overlapped::at_start();
+    let ok = unsafe {
+        win::LockFileEx(
+            file.as_raw_handle().cast(),
+            flags,
+            0,
+            u32::MAX,
+            u32::MAX,
+            &mut overlapped,
+        )
+    };
+    if ok != 0 {
+        return Ok(true);
+    }
+    let err = std::io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(win::ERROR_LOCK_VIOLATION) => Ok(false),
+        _ => Err(err.into()),
+    }
+}
+
+#[cfg(windows)]
+fn unlock(file: &File) -> PyResult<()> {
+    use std::os::windows::io::AsRawHandle;
+    let mut overlapped = win::Overlapped::at_start();
+    let ok = unsafe {
+        win::UnlockFileEx(
+            file.as_raw_handle().cast(),
+            0,
+            u32::MAX,
+            u32::MAX,
+            &mut overlapped,
+        )
+    };
+    if ok == 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_vendor = "apple",
+    windows
+)))]
+fn try_lock(_file: &File, _mode: LockMode) -> PyResult<bool> {
+    Err(PyException::os_error(
+        "file locking is not supported on this platform",
+    ))
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_vendor = "apple",
+    windows
+)))]
+fn unlock(_file: &File) -> PyResult<()> {
+    Ok(())
+}
+
+#[cfg(all(
+    test,
+    any(target_os = "linux", target_os = "android", target_vendor = "apple")
+))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exclusive_and_shared_locks() {
+        let path = std::env::temp_dir().join(format!("stdpython-filelock-{}", std::process::id()));
+        let first = FileLock::new(&path);
+        let second = FileLock::new(&path).with_poll_interval(0.01);
+        {
+            let _guard = first.acquire(None).unwrap();
+            assert!(first.is_locked());
+            let _again = first.acquire(Some(0.0)).unwrap();
+            assert_eq!(first.lock_counter(), 2);
+            let started = Instant::now();
+            let err = second.acquire(Some(0.05)).err().unwrap();
+            assert!(started.elapsed() >= Duration::from_millis(50));
+            assert!(err.is("TimeoutError"));
+            assert_eq!(
+                err.message,
+                format!("The file lock '{}' could not be acquired.", path.display())
+            );
+        }
+        assert!(!first.is_locked());
+        drop(second.acquire(Some(0.0)).unwrap());
+        let patient = FileLock::new(&path).with_poll_interval(f64::INFINITY);
+        drop(patient.acquire(Some(f64::INFINITY)).unwrap());
+        drop(patient.acquire(Some(1e300)).unwrap());
+
+        let readers = [
+            FileLock::new(&path).with_mode(LockMode::Shared),
+            FileLock::new(&path).with_mode(LockMode::Shared),
+        ];
+        let guards: Vec<_> = readers
+            .iter()
+            .map(|r| r.acquire(Some(0.0)).unwrap())
+            .collect();
+        assert!(first.acquire(Some(0.0)).is_err());
+        drop(guards);
+        let guard = first.acquire(Some(0.0)).unwrap();
+        first.release(true).unwrap();
+        assert!(!first.is_locked());
+        drop(guard);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
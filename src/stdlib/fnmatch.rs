@@ -0,0 +1,485 @@
+//! Python-equivalent fnmatch module.
+//!
+//! This is the single shell-pattern engine used by `glob` and `pathlib` as well.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::stdlib::functools::LruCache;
+use crate::sync::Lock;
+
+/// One element of a parsed shell pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    /// A literal character.
+    Char(char),
+    /// `?`: any single character.
+    Any,
+    /// `*`: any run of characters (consecutive stars are collapsed).
+    Star,
+    /// `[...]` / `[!...]`: a character class of inclusive ranges.
+    Class {
+        negated: bool,
+        ranges: Vec<(char, char)>,
+    },
+}
+
+/// Parses a shell pattern into tokens using CPython's bracket rules: an unterminated
+/// `[` is literal, `]` directly after `[` or `[!` is a member, and `lo-hi` ranges with
+/// `lo > hi` match nothing.
+pub fn tokenize(pat: &str) -> Vec<Token> {
+    let chars: Vec<char> = pat.chars().collect();
+    let n = chars.len();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < n {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '*' => {
+                if tokens.last() != Some(&Token::Star) {
+                    tokens.push(Token::Star);
+                }
+            }
+            '?' => tokens.push(Token::Any),
+            '[' => match class_end(&chars, i) {
+                Some(j) => {
+                    tokens.push(parse_class(&chars[i..j]));
+                    i = j + 1;
+                }
+                None => tokens.push(Token::Char('[')),
+            },
+            _ => tokens.push(Token::Char(c)),
+        }
+    }
+    tokens
+}
+
+/// Index of the `]` closing a class whose contents start at `start`, if any.
+fn class_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut j = start;
+    if chars.get(j) == Some(&'!') {
+        j += 1;
+    }
+    if chars.get(j) == Some(&']') {
+        j += 1;
+    }
+    while j < chars.len() && chars[j] != ']' {
+        j += 1;
+    }
+    (j < chars.len()).then_some(j)
+}
+
+fn parse_class(body: &[char]) -> Token {
+    let (negated, body) = match body.first() {
+        Some('!') => (true, &body[1..]),
+        _ => (false, body),
+    };
+    let mut ranges = Vec::new();
+    let mut k = 0;
+    while k < body.len() {
+        if k + 2 < body.len() && body[k + 1] == '-' {
+            ranges.push((body[k], body[k + 2]));
+            k += 3;
+        } else {
+            ranges.push((body[k], body[k]));
+            k += 1;
+        }
+    }
+    Token::Class { negated, ranges }
+}
+
+fn token_matches(token: &Token, c: char) -> bool {
+    match token {
+        Token::Char(expected) => *expected == c,
+        Token::Any => true,
+        Token::Star => false,
+        Token::Class { negated, ranges } => {
+            ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != *negated
+        }
+    }
+}
+
+/// Matches `name` against already-parsed tokens.
+///
+/// Uses the classic single-backtrack-point algorithm: on mismatch only the most recent
+/// `*` is extended, so matching is O(len(name) * len(tokens)) with no recursion.
+pub fn match_tokens(tokens: &[Token], name: &str) -> bool {
+    // Byte offsets into `name`; each step decodes the next char in place.
+    let (mut t, mut s) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while let Some(c) = name[s..].chars().next() {
+        match tokens.get(t) {
+            Some(Token::Star) => {
+                backtrack = Some((t, s));
+                t += 1;
+            }
+            Some(token) if token_matches(token, c) => {
+                t += 1;
+                s += c.len_utf8();
+            }
+            _ => match backtrack {
+                Some((star_t, star_s)) => {
+                    let skipped = name[star_s..].chars().next().map_or(1, char::len_utf8);
+                    t = star_t + 1;
+                    s = star_s + skipped;
+                    backtrack = Some((star_t, s));
+                }
+                None => return false,
+            },
+        }
+    }
+    tokens[t..].iter().all(|token| *token == Token::Star)
+}
+
+/// How a compiled pattern is matched; the common shapes avoid the general matcher.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Strategy {
+    /// No wildcards at all: plain string equality.
+    Literal(String),
+    /// `prefix*suffix` (either side may be empty): two string comparisons.
+    Affix { prefix: String, suffix: String },
+    /// Anything else: reject on the literal prefix, then run the token matcher on the rest.
+    General { prefix: String, rest: Vec<Token> },
+}
+
+/// A shell pattern compiled once and matched many times.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    source: String,
+    strategy: Strategy,
+}
+
+impl Pattern {
+    /// Compiles `pat` as-is (case-sensitive, like fnmatchcase()).
+    pub fn new(pat: &str) -> Self {
+        let tokens = tokenize(pat);
+        let literal_run = |tokens: &[Token]| -> Option<String> {
+            tokens
+                .iter()
+                .map(|t| match t {
+                    Token::Char(c) => Some(*c),
+                    _ => None,
+                })
+                .collect()
+        };
+        let prefix_len = tokens
+            .iter()
+            .take_while(|t| matches!(t, Token::Char(_)))
+            .count();
+        let prefix = literal_run(&tokens[..prefix_len]).unwrap_or_default();
+        let strategy = if prefix_len == tokens.len() {
+            Strategy::Literal(prefix)
+        } else {
+            let after_star = &tokens[prefix_len + 1..];
+            match (&tokens[prefix_len], literal_run(after_star)) {
+                (Token::Star, Some(suffix)) => Strategy::Affix { prefix, suffix },
+                _ => Strategy::General {
+                    prefix,
+                    rest: tokens[prefix_len..].to_vec(),
+                },
+            }
+        };
+        Pattern {
+            source: pat.to_string(),
+            strategy,
+        }
+    }
+
+    /// The pattern text this was compiled from.
+    pub fn as_str(&self) -> &str {
+        &self.source
+    }
+
+    /// True if the pattern has no wildcards and so matches exactly one name.
+    pub fn is_literal(&self) -> bool {
+        matches!(self.strategy, Strategy::Literal(_))
+    }
+
+    /// The literal text every match must start with.
+    pub fn literal_prefix(&self) -> &str {
+        match &self.strategy {
+            Strategy::Literal(lit) => lit,
+            Strategy::Affix { prefix, .. } | Strategy::General { prefix, .. } => prefix,
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        match &self.strategy {
+            Strategy::Literal(lit) => name == lit,
+            Strategy::Affix { prefix, suffix } => {
+                name.len() >= prefix.len() + suffix.len()
+                    && name.starts_with(prefix.as_str())
+                    && name.ends_with(suffix.as_str())
+            }
+            Strategy::General { prefix, rest } => match name.strip_prefix(prefix.as_str()) {
+                Some(remainder) => match_tokens(rest, remainder),
+                None => false,
+            },
+        }
+    }
+}
+
+/// Python-equivalent os.path.normcase(): case-folds and uses `\` on Windows, identity elsewhere.
+pub fn normcase(s: &str) -> String {
+    if cfg!(windows) {
+        s.to_lowercase().replace('/', "\\")
+    } else {
+        s.to_string()
+    }
+}
+
+/// Compiled patterns, shared across calls as CPython's `_compile_pattern`
+/// lru_cache shares them.
+static PATTERNS: Lock<Option<LruCache<String, Arc<Pattern>>>> = Lock::new(None);
+
+/// `pat` compiled, from the pattern cache when it was seen recently.
+pub fn compile(pat: &str) -> Arc<Pattern> {
+    let mut cache = PATTERNS.lock();
+    let cache = cache.get_or_insert_with(|| LruCache::new(Some(32768)));
+    if let Some(pattern) = cache.get(pat) {
+        return pattern.clone();
+    }
+    let pattern = Arc::new(Pattern::new(pat));
+    cache.insert(pat.to_string(), pattern.clone());
+    pattern
+}
+
+/// Python-equivalent fnmatch.fnmatchcase(name, pat).
+pub fn fnmatchcase(name: &str, pat: &str) -> bool {
+    compile(pat).matches(name)
+}
+
+/// Python-equivalent fnmatch.fnmatch(name, pat), normalizing case per platform.
+pub fn fnmatch(name: &str, pat: &str) -> bool {
+    fnmatchcase(&normcase(name), &normcase(pat))
+}
+
+/// Python-equivalent fnmatch.filter(names, pat).
+pub fn filter<S: AsRef<str>>(names: &[S], pat: &str) -> Vec<String> {
+    let pattern = compile(&normcase(pat));
+    names
+        .iter()
+        .map(|n| n.as_ref())
+        .filter(|n| pattern.matches(&normcase(n)))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Equivalent of Python's re.escape() for a single character.
+fn escape_char(c: char, out: &mut String) {
+    if "()[]{}?*+-|^$\\.&~# \t\n\r\x0b\x0c".contains(c) {
+        out.push('\\');
+    }
+    out.push(c);
+}
+
+/// Python-equivalent fnmatch.translate(pat): the regular expression CPython 3.12 produces.
+pub fn translate(pat: &str) -> String {
+    let chars: Vec<char> = pat.chars().collect();
+    let n = chars.len();
+    // `None` marks a star; fixed pieces hold regex text.
+    let mut parts: Vec<Option<String>> = Vec::new();
+    let mut i = 0;
+    while i < n {
+        let c = chars[i];
+        i += 1;
+        match c {
+            '*' => {
+                if parts.last() != Some(&None) {
+                    parts.push(None);
+                }
+            }
+            '?' => parts.push(Some(".".to_string())),
+            '[' => match class_end(&chars, i) {
+                Some(j) => {
+                    parts.push(Some(translate_class(&chars, i, j)));
+                    i = j + 1;
+                }
+                None => parts.push(Some("\\[".to_string())),
+            },
+            _ => {
+                let mut s = String::new();
+                escape_char(c, &mut s);
+                parts.push(Some(s));
+            }
+        }
+    }
+
+    let mut res = String::new();
+    let mut k = 0;
+    while k < parts.len() {
+        if let Some(fixed) = &parts[k] {
+            res.push_str(fixed);
+            k += 1;
+            continue;
+        }
+        break;
+    }
+    while k < parts.len() {
+        // parts[k] is a star.
+        k += 1;
+        if k == parts.len() {
+            res.push_str(".*");
+            break;
+        }
+        let mut fixed = String::new();
+        while let Some(Some(piece)) = parts.get(k) {
+            fixed.push_str(piece);
+            k += 1;
+        }
+        if k == parts.len() {
+            res.push_str(".*");
+            res.push_str(&fixed);
+        } else {
+            res.push_str(&format!("(?>.*?{})", fixed));
+        }
+    }
+    format!("(?s:{})\\Z", res)
+}
+
+/// Translates the class body `chars[i..j]` exactly as CPython's fnmatch does.
+fn translate_class(chars: &[char], start: usize, j: usize) -> String {
+    let body: String = chars[start..j].iter().collect();
+    let mut stuff = if !body.contains('-') {
+        body.replace('\\', "\\\\")
+    } else {
+        let mut chunks: Vec<String> = Vec::new();
+        let mut i = start;
+        let mut k = if chars[start] == '!' {
+            start + 2
+        } else {
+            start + 1
+        };
+        loop {
+            let found = (k..j).find(|&p| chars[p] == '-');
+            match found {
+                Some(p) => {
+                    chunks.push(chars[i..p].iter().collect());
+                    i = p + 1;
+                    k = p + 3;
+                }
+                None => break,
+            }
+        }
+        let chunk: String = chars[i..j].iter().collect();
+        if !chunk.is_empty() {
+            chunks.push(chunk);
+        } else if let Some(last) = chunks.last_mut() {
+            last.push('-');
+        }
+        // Remove empty ranges -- invalid in a regex.
+        for k in (1..chunks.len()).rev() {
+            let prev_last = chunks[k - 1].chars().last();
+            let next_first = chunks[k].chars().next();
+            if let (Some(a), Some(b)) = (prev_last, next_first) {
+                if a > b {
+                    let mut merged: String = chunks[k - 1].clone();
+                    merged.pop();
+                    merged.extend(chunks[k].chars().skip(1));
+                    chunks[k - 1] = merged;
+                    chunks.remove(k);
+                }
+            }
+        }
+        chunks
+            .iter()
+            .map(|s| s.replace('\\', "\\\\").replace('-', "\\-"))
+            .collect::<Vec<_>>()
+            .join("-")
+    };
+    // Escape set operations (&&, ~~ and ||).
+    let mut escaped = String::with_capacity(stuff.len());
+    for c in stuff.chars() {
+        if matches!(c, '&' | '~' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    stuff = escaped;
+    if stuff.is_empty() {
+        "(?!)".to_string()
+    } else if stuff == "!" {
+        ".".to_string()
+    } else if let Some(rest) = stuff.strip_prefix('!') {
+        format!("[^{}]", rest)
+    } else if stuff.starts_with('^') || stuff.starts_with('[') {
+        format!("[\\{}]", stuff)
+    } else {
+        format!("[{}]", stuff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic_matching() {
+        assert!(fnmatchcase("foo.txt", "*.txt"));
+        assert!(!fnmatchcase("foo.txt", "*.py"));
+        assert!(fnmatchcase("a", "?"));
+        assert!(!fnmatchcase("", "?"));
+        assert!(fnmatchcase("", "*"));
+        assert!(fnmatchcase("abcabd", "*ab?"));
+        assert!(fnmatchcase("a*b", "a[*]b"));
+    }
+
+    #[test]
+    fn classes() {
+        assert!(fnmatchcase("b", "[a-c]"));
+        assert!(!fnmatchcase("b", "[!a-c]"));
+        assert!(fnmatchcase("]", "[]]"));
+        assert!(fnmatchcase("x", "[!]]"));
+        assert!(fnmatchcase("-", "[a-]"));
+        assert!(!fnmatchcase("m", "[z-a]"));
+        assert!(fnmatchcase("[", "["));
+        assert!(fnmatchcase("[ab", "[ab"));
+    }
+
+    #[test]
+    fn many_stars_are_not_exponential() {
+        let name = "a".repeat(200);
+        let pat = format!("{}b", "*a".repeat(50));
+        assert!(!fnmatchcase(&name, &pat));
+    }
+
+    #[test]
+    fn filter_names() {
+        let names = ["a.py", "b.rs", "c.py"];
+        assert_eq!(filter(&names, "*.py"), vec!["a.py", "c.py"]);
+    }
+
+    #[test]
+    fn translate_matches_cpython() {
+        assert_eq!(translate("*.txt"), r"(?s:.*\.txt)\Z");
+        assert_eq!(translate("a*b*c"), r"(?s:a(?>.*?b).*c)\Z");
+        assert_eq!(translate("[!a-c]?"), r"(?s:[^a-c].)\Z");
+        assert_eq!(translate("[^x]"), r"(?s:[\^x])\Z");
+        assert_eq!(translate("["), r"(?s:\[)\Z");
+        assert_eq!(translate("[z-a]"), r"(?s:(?!))\Z");
+        assert_eq!(translate("**"), r"(?s:.*)\Z");
+    }
+
+    #[test]
+    fn compiled_strategies() {
+        let lit = Pattern::new("Cargo.toml");
+        assert!(lit.is_literal());
+        assert!(lit.matches("Cargo.toml") && !lit.matches("Cargo.lock"));
+
+        let affix = Pattern::new("test_*.rs");
+        assert_eq!(affix.literal_prefix(), "test_");
+        assert!(affix.matches("test_a.rs"));
+        assert!(affix.matches("test_.rs"));
+        assert!(!affix.matches("test.rs"));
+
+        let general = Pattern::new("mod[0-9]*é?");
+        assert_eq!(general.literal_prefix(), "mod");
+        assert!(general.matches("mod1xxéz"));
+        assert!(!general.matches("modx"));
+        assert!(Pattern::new("*é*").matches("café"));
+    }
+}
@@ -0,0 +1,229 @@
+//! Python-equivalent functools caching: an [`LruCache`] map and the
+//! [`lru_cache`] function wrapper built on it.
+//!
+//! The crate's own hot paths share the same cache, e.g. fnmatch keeps its
+//! compiled patterns in one, as CPython does with `functools.lru_cache`.
+
+use alloc::format;
+use alloc::string::String;
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::Hash;
+
+use crate::stdlib::collections::OrderedDict;
+use crate::sync::Lock;
+
+/// Python-equivalent functools._CacheInfo.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CacheInfo {
+    pub hits: usize,
+    pub misses: usize,
+    /// None for an unbounded cache.
+    pub maxsize: Option<usize>,
+    pub currsize: usize,
+}
+
+/// `CacheInfo(hits=1, misses=2, maxsize=128, currsize=2)`.
+impl fmt::Display for CacheInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let maxsize = match self.maxsize {
+            Some(n) => format!("{}", n),
+            None => String::from("None"),
+        };
+        write!(
+            f,
+            "CacheInfo(hits={}, misses={}, maxsize={}, currsize={})",
+            self.hits, self.misses, maxsize, self.currsize
+        )
+    }
+}
+
+/// A map that holds at most `maxsize` entries, evicting the least recently
+/// used, and counts hits and misses. A `maxsize` of 0 caches nothing; None
+/// never evicts.
+#[derive(Clone)]
+pub struct LruCache<K, V> {
+    entries: OrderedDict<K, V>,
+    maxsize: Option<usize>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<K: Hash + Eq, V> LruCache<K, V> {
+    pub fn new(maxsize: Option<usize>) -> Self {
+        LruCache {
+            entries: OrderedDict::new(),
+            maxsize,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Looks up `key`, counting a hit or a miss and marking the entry as
+    /// most recently used.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let found = self.entries.get_and_move_to_end(key);
+        if found.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+        found
+    }
+
+    /// Stores `value`, evicting the least recently used entry when full.
+    pub fn insert(&mut self, key: K, value: V) {
+        match self.maxsize {
+            Some(0) => return,
+            Some(max) if self.entries.len() >= max && !self.entries.contains_key(&key) => {
+                let _ = self.entries.popitem(false);
+            }
+            _ => {}
+        }
+        self.entries.remove(&key);
+        self.entries.set_item(key, value);
+    }
+
+    /// The cached value for `key`, computing and storing it on a miss.
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: K, f: F) -> V
+    where
+        V: Clone,
+    {
+        if let Some(value) = self.get(&key) {
+            return value.clone();
+        }
+        let value = f();
+        self.insert(key, value.clone());
+        value
+    }
+
+    /// Python-equivalent cache_info().
+    pub fn cache_info(&self) -> CacheInfo {
+        CacheInfo {
+            hits: self.hits,
+            misses: self.misses,
+            maxsize: self.maxsize,
+            currsize: self.entries.len(),
+        }
+    }
+
+    /// Python-equivalent cache_clear(): drops the entries and the counts.
+    pub fn cache_clear(&mut self) {
+        self.entries.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+/// Python-equivalent functools.lru_cache(maxsize)(func) for a function of
+/// one hashable argument; pass a tuple for several. Safe to share between
+/// threads: the lock is not held while `func` runs, so two threads missing
+/// on the same key may both compute it, as in CPython.
+pub fn lru_cache<K, V, F>(maxsize: Option<usize>, func: F) -> LruCached<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(K) -> V,
+{
+    LruCached {
+        func,
+        cache: Lock::new(LruCache::new(maxsize)),
+    }
+}
+
+/// A function wrapped by [`lru_cache`].
+pub struct LruCached<K, V, F> {
+    func: F,
+    cache: Lock<LruCache<K, V>>,
+}
+
+impl<K, V, F> LruCached<K, V, F>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    F: Fn(K) -> V,
+{
+    pub fn call(&self, arg: K) -> V {
+        if let Some(value) = self.cache.lock().get(&arg) {
+            return value.clone();
+        }
+        let value = (self.func)(arg.clone());
+        self.cache.lock().insert(arg, value.clone());
+        value
+    }
+
+    pub fn cache_info(&self) -> CacheInfo {
+        self.cache.lock().cache_info()
+    }
+
+    pub fn cache_clear(&self) {
+        self.cache.lock().cache_clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::cell::Cell;
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = LruCache::new(Some(2));
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        assert_eq!(cache.get("a"), Some(&1));
+        cache.insert("c", 3);
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get_or_insert_with("a", || 9), 1);
+        assert_eq!(cache.get_or_insert_with("d", || 4), 4);
+        assert_eq!(cache.get("c"), None);
+        assert_eq!(
+            cache.cache_info().to_string(),
+            "CacheInfo(hits=2, misses=3, maxsize=2, currsize=2)"
+        );
+        cache.cache_clear();
+        assert_eq!(
+            cache.cache_info(),
+            CacheInfo {
+                maxsize: Some(2),
+                ..Default::default()
+            }
+        );
+
+        let mut none = LruCache::new(Some(0));
+        assert_eq!(none.get_or_insert_with(1, || "x"), "x");
+        assert!(none.is_empty());
+    }
+
+    #[test]
+    fn wraps_a_function() {
+        let calls = Cell::new(0);
+        let fib = lru_cache(None, |n: u64| {
+            calls.set(calls.get() + 1);
+            n * 2
+        });
+        assert_eq!(fib.call(3), 6);
+        assert_eq!(fib.call(3), 6);
+        assert_eq!(fib.call(4), 8);
+        assert_eq!(calls.get(), 2);
+        let info = fib.cache_info();
+        assert_eq!(
+            (info.hits, info.misses, info.maxsize, info.currsize),
+            (1, 2, None, 2)
+        );
+        fib.cache_clear();
+        assert_eq!(fib.cache_info().currsize, 0);
+    }
+}
@@ -0,0 +1,760 @@
+//! Python-equivalent gettext module: message catalogs looked up by domain
+//! and language, with the GNU API (textdomain/bindtextdomain/gettext) on
+//! top.
+//!
+//! Catalogs are GNU `.mo` files, as msgfmt writes them, or JSON objects
+//! mapping each msgid to its translation (a list of forms for plural
+//! messages). In both, the "" entry is the header carrying `Plural-Forms`,
+//! and a context-qualified msgid is `context + "\x04" + msgid`. Without a
+//! catalog every lookup falls back to the untranslated message.
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::json::{loads, JSONValue};
+use crate::stdlib::sys;
+use crate::sync::Lock;
+
+const LE_MAGIC: u32 = 0x950412de;
+const BE_MAGIC: u32 = 0xde120495;
+const CONTEXT: char = '\x04';
+
+/// A compiled `plural=` expression from a catalog's Plural-Forms header:
+/// the C subset gettext allows, over the single variable `n`.
+#[derive(Debug, Clone, PartialEq)]
+enum Plural {
+    N,
+    Num(i64),
+    Not(Box<Plural>),
+    Binary(char, Box<Plural>, Box<Plural>),
+    /// `&&`, `||` and the comparisons, spelled as in C.
+    Logic(&'static str, Box<Plural>, Box<Plural>),
+    Cond(Box<Plural>, Box<Plural>, Box<Plural>),
+}
+
+/// Binary operators by precedence level, loosest first.
+const LEVELS: [&[&str]; 6] = [
+    &["||"],
+    &["&&"],
+    &["==", "!="],
+    &["<=", ">=", "<", ">"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+struct PluralParser<'a> {
+    rest: &'a str,
+}
+
+impl PluralParser<'_> {
+    fn error() -> PyException {
+        PyException::value_error("plural forms expression could be dangerous")
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(token) {
+            // `!` must not swallow the first half of `!=`, nor `<` of `<=`.
+            Some(after) if !(matches!(token, "!" | "<" | ">") && after.starts_with('=')) => {
+                self.rest = after;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn conditional(&mut self) -> PyResult<Plural> {
+        let cond = self.binary(0)?;
+        if !self.eat("?") {
+            return Ok(cond);
+        }
+        let then = self.conditional()?;
+        if !self.eat(":") {
+            return Err(Self::error());
+        }
+        let otherwise = self.conditional()?;
+        Ok(Plural::Cond(
+            Box::new(cond),
+            Box::new(then),
+            Box::new(otherwise),
+        ))
+    }
+
+    fn binary(&mut self, level: usize) -> PyResult<Plural> {
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        'operators: loop {
+            for &op in LEVELS[level] {
+                if self.eat(op) {
+                    let right = Box::new(self.binary(level + 1)?);
+                    let left_box = Box::new(left);
+                    left = match op {
+                        "+" | "-" | "*" | "/" | "%" => {
+                            Plural::Binary(op.chars().next().unwrap(), left_box, right)
+                        }
+                        _ => Plural::Logic(op, left_box, right),
+                    };
+                    continue 'operators;
+                }
+            }
+            return Ok(left);
+        }
+    }
+
+    fn unary(&mut self) -> PyResult<Plural> {
+        if self.eat("!") {
+            return Ok(Plural::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let inner = self.conditional()?;
+            return if self.eat(")") {
+                Ok(inner)
+            } else {
+                Err(Self::error())
+            };
+        }
+        if self.eat("n") {
+            return Ok(Plural::N);
+        }
+        let end = self
+            .rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        let value = self.rest[..end].parse().map_err(|_| Self::error())?;
+        self.rest = &self.rest[end..];
+        Ok(Plural::Num(value))
+    }
+}
+
+impl Plural {
+    /// Compiles `expr`, e.g. `n%10==1 && n%100!=11 ? 0 : 1`.
+    fn parse(expr: &str) -> PyResult<Plural> {
+        let mut parser = PluralParser { rest: expr };
+        let plural = parser.conditional()?;
+        if parser.rest.trim().is_empty() {
+            Ok(plural)
+        } else {
+            Err(PluralParser::error())
+        }
+    }
+
+    /// The form index for `n`. Division by zero gives 0 rather than
+    /// failing a lookup.
+    fn eval(&self, n: i64) -> i64 {
+        match self {
+            Plural::N => n,
+            Plural::Num(v) => *v,
+            Plural::Not(e) => (e.eval(n) == 0) as i64,
+            Plural::Binary(op, a, b) => {
+                let (a, b) = (a.eval(n), b.eval(n));
+                match op {
+                    '+' => a.wrapping_add(b),
+                    '-' => a.wrapping_sub(b),
+                    '*' => a.wrapping_mul(b),
+                    '/' => a.checked_div(b).unwrap_or(0),
+                    _ => a.checked_rem(b).unwrap_or(0),
+                }
+            }
+            Plural::Logic(op, a, b) => {
+                let a = a.eval(n);
+                let result = match *op {
+                    "&&" => a != 0 && b.eval(n) != 0,
+                    "||" => a != 0 || b.eval(n) != 0,
+                    "==" => a == b.eval(n),
+                    "!=" => a != b.eval(n),
+                    "<" => a < b.eval(n),
+                    ">" => a > b.eval(n),
+                    "<=" => a <= b.eval(n),
+                    _ => a >= b.eval(n),
+                };
+                result as i64
+            }
+            Plural::Cond(c, t, f) => {
+                if c.eval(n) != 0 {
+                    t.eval(n)
+                } else {
+                    f.eval(n)
+                }
+            }
+        }
+    }
+}
+
+/// Python-equivalent gettext.c2py(plural) as a function: the plural form
+/// index a Plural-Forms `plural=` expression picks for `n`.
+pub fn plural_index(expr: &str, n: i64) -> PyResult<i64> {
+    Ok(Plural::parse(expr)?.eval(n))
+}
+
+/// A loaded message catalog; Python's NullTranslations when empty and
+/// GNUTranslations otherwise.
+#[derive(Debug, Clone, Default)]
+pub struct Translations {
+    /// Translated forms by msgid; one form unless the message has plurals.
+    catalog: HashMap<String, Vec<String>>,
+    /// Header fields, keys lowercased.
+    info: HashMap<String, String>,
+    plural: Option<Plural>,
+    fallback: Option<Box<Translations>>,
+}
+
+impl Translations {
+    /// Python-equivalent gettext.NullTranslations(): translates nothing.
+    pub fn null() -> Self {
+        Self::default()
+    }
+
+    fn insert(&mut self, msgid: String, forms: Vec<String>) -> PyResult<()> {
+        if msgid.is_empty() {
+            let header = forms.into_iter().next().unwrap_or_default();
+            for line in header.lines() {
+                if let Some((key, value)) = line.split_once(':') {
+                    self.info
+                        .insert(key.trim().to_lowercase(), value.trim().to_string());
+                }
+            }
+            if let Some(forms) = self.info.get("plural-forms") {
+                let expr = forms
+                    .split(';')
+                    .find_map(|field| field.trim().strip_prefix("plural="))
+                    .unwrap_or("n != 1");
+                self.plural = Some(Plural::parse(expr)?);
+            }
+        } else {
+            self.catalog.insert(msgid, forms);
+        }
+        Ok(())
+    }
+
+    /// Reads a GNU `.mo` file's contents. Strings are decoded as UTF-8
+    /// unless the header names ISO-8859-1.
+    pub fn from_mo(data: &[u8]) -> PyResult<Self> {
+        let bad = || PyException::os_error("Bad magic number");
+        let word = |offset: usize, big_endian: bool| -> PyResult<u32> {
+            let bytes: [u8; 4] = data
+                .get(offset..offset + 4)
+                .and_then(|b| b.try_into().ok())
+                .ok_or_else(|| PyException::os_error("File is corrupt"))?;
+            Ok(if big_endian {
+                u32::from_be_bytes(bytes)
+            } else {
+                u32::from_le_bytes(bytes)
+            })
+        };
+        let big_endian = match word(0, false).map_err(|_| bad())? {
+            LE_MAGIC => false,
+            BE_MAGIC => true,
+            _ => return Err(bad()),
+        };
+        let version = word(4, big_endian)?;
+        if version >> 16 > 1 {
+            return Err(PyException::os_error(format!(
+                "Bad version number {}",
+                version >> 16
+            )));
+        }
+        let count = word(8, big_endian)? as usize;
+        let originals = word(12, big_endian)? as usize;
+        let translations = word(16, big_endian)? as usize;
+        let string = |table: usize, i: usize| -> PyResult<&[u8]> {
+            let len = word(table + 8 * i, big_endian)? as usize;
+            let offset = word(table + 8 * i + 4, big_endian)? as usize;
+            data.get(offset..offset + len)
+                .ok_or_else(|| PyException::os_error("File is corrupt"))
+        };
+        let mut raw = Vec::new();
+        for i in 0..count {
+            raw.push((string(originals, i)?, string(translations, i)?));
+        }
+        let mut translations = Translations::null();
+        // The header, which names the charset, sorts first.
+        let latin1 = raw.first().is_some_and(|(msgid, header)| {
+            msgid.is_empty()
+                && String::from_utf8_lossy(header)
+                    .to_lowercase()
+                    .contains("charset=iso-8859-1")
+        });
+        let decode = |bytes: &[u8]| -> String {
+            if latin1 {
+                bytes.iter().map(|&b| b as char).collect()
+            } else {
+                String::from_utf8_lossy(bytes).into_owned()
+            }
+        };
+        for (msgid, msgstr) in raw {
+            let msgid = decode(msgid);
+            // A plural entry is "singular\0plural" -> "form0\0form1...".
+            let msgid = msgid.split('\0').next().unwrap_or("").to_string();
+            let forms = decode(msgstr).split('\0').map(str::to_string).collect();
+            translations.insert(msgid, forms)?;
+        }
+        Ok(translations)
+    }
+
+    /// Reads a JSON catalog: `{"": "Plural-Forms: ...", "Hello": "Hallo",
+    /// "%d file": ["%d Datei", "%d Dateien"]}`.
+    pub fn from_json(text: &str) -> PyResult<Self> {
+        let JSONValue::Object(entries) = loads(text)? else {
+            return Err(PyException::value_error("a JSON catalog must be an object"));
+        };
+        let mut translations = Translations::null();
+        for (msgid, value) in entries.iter() {
+            let forms = match value {
+                JSONValue::Str(s) => vec![s.clone()],
+                JSONValue::Array(items) => items
+                    .iter()
+                    .map(|item| match item {
+                        JSONValue::Str(s) => Ok(s.clone()),
+                        _ => Err(PyException::value_error(format!(
+                            "plural forms of {:?} must be strings",
+                            msgid
+                        ))),
+                    })
+                    .collect::<PyResult<_>>()?,
+                _ => {
+                    return Err(PyException::value_error(format!(
+                        "translation of {:?} must be a string or a list",
+                        msgid
+                    )))
+                }
+            };
+            translations.insert(msgid.clone(), forms)?;
+        }
+        Ok(translations)
+    }
+
+    /// Python-equivalent translations.add_fallback(fallback): consulted for
+    /// messages this catalog lacks.
+    pub fn add_fallback(&mut self, fallback: Translations) {
+        match &mut self.fallback {
+            Some(existing) => existing.add_fallback(fallback),
+            None => self.fallback = Some(Box::new(fallback)),
+        }
+    }
+
+    /// Python-equivalent translations.info(): the catalog header's fields,
+    /// keys lowercased.
+    pub fn info(&self) -> &HashMap<String, String> {
+        &self.info
+    }
+
+    /// Python-equivalent translations.charset().
+    pub fn charset(&self) -> Option<&str> {
+        self.info
+            .get("content-type")
+            .and_then(|t| t.split("charset=").nth(1))
+            .map(str::trim)
+    }
+
+    fn lookup(&self, key: &str, n: Option<i64>) -> Option<&str> {
+        let Some(forms) = self.catalog.get(key) else {
+            return self.fallback.as_ref()?.lookup(key, n);
+        };
+        let index = match (n, &self.plural) {
+            (None, _) => 0,
+            (Some(n), Some(plural)) => plural.eval(n),
+            (Some(n), None) => (n != 1) as i64,
+        };
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| forms.get(i))
+            .map(String::as_str)
+    }
+
+    /// Python-equivalent translations.gettext(message).
+    pub fn gettext<'a>(&'a self, message: &'a str) -> &'a str {
+        self.lookup(message, None).unwrap_or(message)
+    }
+
+    /// Python-equivalent translations.ngettext(singular, plural, n).
+    pub fn ngettext<'a>(&'a self, singular: &'a str, plural: &'a str, n: i64) -> &'a str {
+        self.lookup(singular, Some(n))
+            .unwrap_or(if n == 1 { singular } else { plural })
+    }
+
+    /// Python-equivalent translations.pgettext(context, message).
+    pub fn pgettext<'a>(&'a self, context: &str, message: &'a str) -> &'a str {
+        self.lookup(&format!("{}{}{}", context, CONTEXT, message), None)
+            .unwrap_or(message)
+    }
+
+    /// Python-equivalent translations.npgettext(context, singular, plural, n).
+    pub fn npgettext<'a>(
+        &'a self,
+        context: &str,
+        singular: &'a str,
+        plural: &'a str,
+        n: i64,
+    ) -> &'a str {
+        self.lookup(&format!("{}{}{}", context, CONTEXT, singular), Some(n))
+            .unwrap_or(if n == 1 { singular } else { plural })
+    }
+}
+
+/// Every name a locale such as "de_DE.UTF-8@euro" may have a catalog
+/// under, most specific first, as gettext's _expand_lang() lists them.
+fn expand_lang(locale: &str) -> Vec<String> {
+    let (rest, modifier) = match locale.split_once('@') {
+        Some((rest, modifier)) => (rest, format!("@{}", modifier)),
+        None => (locale, String::new()),
+    };
+    let (rest, codeset) = match rest.split_once('.') {
+        Some((rest, codeset)) => (rest, format!(".{}", codeset)),
+        None => (rest, String::new()),
+    };
+    let (language, territory) = match rest.split_once('_') {
+        Some((language, territory)) => (language, format!("_{}", territory)),
+        None => (rest, String::new()),
+    };
+    let mut names = Vec::new();
+    for mask in 0..8 {
+        let parts = [(2, &territory), (1, &codeset), (4, &modifier)];
+        if parts
+            .iter()
+            .any(|(bit, part)| mask & bit != 0 && part.is_empty())
+        {
+            continue;
+        }
+        let mut name = language.to_string();
+        for (bit, part) in parts {
+            if mask & bit != 0 {
+                name.push_str(part);
+            }
+        }
+        names.push(name);
+    }
+    names.reverse();
+    names
+}
+
+/// The languages to search: `languages` if given, else the first of
+/// LANGUAGE, LC_ALL, LC_MESSAGES and LANG that is set, each expanded and
+/// cut off at "C".
+fn candidate_languages(languages: Option<&[&str]>) -> Vec<String> {
+    let mut chosen: Vec<String> = match languages {
+        Some(languages) => languages.iter().map(|l| l.to_string()).collect(),
+        None => ["LANGUAGE", "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|value| !value.is_empty())
+            .map(|value| value.split(':').map(str::to_string).collect())
+            .unwrap_or_default(),
+    };
+    if !chosen.iter().any(|l| l == "C") {
+        chosen.push("C".to_string());
+    }
+    let mut expanded = Vec::new();
+    for language in chosen {
+        for name in expand_lang(&language) {
+            if name == "C" {
+                return expanded;
+            }
+            if !expanded.contains(&name) {
+                expanded.push(name);
+            }
+        }
+    }
+    expanded
+}
+
+fn default_localedir() -> PathBuf {
+    Path::new(&sys::base_prefix()).join("share").join("locale")
+}
+
+/// Python-equivalent gettext.find(domain, localedir=None, languages=None,
+/// all=True): every catalog for `domain`, best match first, looking for
+/// `<localedir>/<lang>/LC_MESSAGES/<domain>.mo` and then `.json`.
+pub fn find(domain: &str, localedir: Option<&Path>, languages: Option<&[&str]>) -> Vec<PathBuf> {
+    let localedir = localedir.map_or_else(default_localedir, Path::to_path_buf);
+    let mut found = Vec::new();
+    for language in candidate_languages(languages) {
+        let dir = localedir.join(&language).join("LC_MESSAGES");
+        for extension in ["mo", "json"] {
+            let path = dir.join(format!("{}.{}", domain, extension));
+            if path.is_file() {
+                found.push(path);
+            }
+        }
+    }
+    found
+}
+
+fn load(path: &Path) -> PyResult<Translations> {
+    let data = fs::read(path)?;
+    if path.extension().is_some_and(|e| e == "json") {
+        Translations::from_json(&String::from_utf8_lossy(&data))
+    } else {
+        Translations::from_mo(&data)
+    }
+}
+
+/// Python-equivalent gettext.translation(domain, localedir=None,
+/// languages=None, fallback=False): the best catalog for `domain`, with
+/// the less specific ones as its fallbacks. Without a catalog this raises
+/// FileNotFoundError, or with `fallback` returns null translations.
+pub fn translation(
+    domain: &str,
+    localedir: Option<&Path>,
+    languages: Option<&[&str]>,
+    fallback: bool,
+) -> PyResult<Translations> {
+    let paths = find(domain, localedir, languages);
+    if paths.is_empty() {
+        if fallback {
+            return Ok(Translations::null());
+        }
+        return Err(PyException::new(
+            "FileNotFoundError",
+            format!(
+                "[Errno 2] No translation file found for domain: '{}'",
+                domain
+            ),
+        ));
+    }
+    let mut result: Option<Translations> = None;
+    for path in paths {
+        let loaded = load(&path)?;
+        match &mut result {
+            Some(first) => first.add_fallback(loaded),
+            None => result = Some(loaded),
+        }
+    }
+    Ok(result.unwrap_or_default())
+}
+
+struct Domains {
+    current: Option<String>,
+    bound: HashMap<String, PathBuf>,
+    loaded: HashMap<(String, Vec<String>), Arc<Translations>>,
+}
+
+static DOMAINS: Lock<Option<Domains>> = Lock::new(None);
+
+fn with_domains<R>(f: impl FnOnce(&mut Domains) -> R) -> R {
+    let mut domains = DOMAINS.lock();
+    f(domains.get_or_insert_with(|| Domains {
+        current: None,
+        bound: HashMap::new(),
+        loaded: HashMap::new(),
+    }))
+}
+
+/// Python-equivalent gettext.textdomain(domain=None): sets the domain the
+/// module-level functions use, returning it ("messages" by default).
+pub fn textdomain(domain: Option<&str>) -> String {
+    with_domains(|d| {
+        if let Some(domain) = domain {
+            d.current = Some(domain.to_string());
+        }
+        d.current.clone().unwrap_or_else(|| "messages".to_string())
+    })
+}
+
+/// Python-equivalent gettext.bindtextdomain(domain, localedir=None):
+/// where `domain`'s catalogs live, returning the directory now bound.
+pub fn bindtextdomain(domain: &str, localedir: Option<&Path>) -> PathBuf {
+    with_domains(|d| {
+        if let Some(localedir) = localedir {
+            d.bound.insert(domain.to_string(), localedir.to_path_buf());
+            d.loaded.retain(|(loaded, _), _| loaded != domain);
+        }
+        d.bound
+            .get(domain)
+            .cloned()
+            .unwrap_or_else(default_localedir)
+    })
+}
+
+/// The catalog the module-level functions use for `domain`, loaded once
+/// per domain and language list. Catalogs that fail to load act as absent.
+fn domain_translations(domain: &str) -> Arc<Translations> {
+    let localedir = bindtextdomain(domain, None);
+    let key = (domain.to_string(), candidate_languages(None));
+    if let Some(cached) = with_domains(|d| d.loaded.get(&key).cloned()) {
+        return cached;
+    }
+    let loaded = Arc::new(translation(domain, Some(&localedir), None, true).unwrap_or_default());
+    with_domains(|d| d.loaded.insert(key, Arc::clone(&loaded)));
+    loaded
+}
+
+/// Python-equivalent gettext.dgettext(domain, message).
+pub fn dgettext(domain: &str, message: &str) -> String {
+    domain_translations(domain).gettext(message).to_string()
+}
+
+/// Python-equivalent gettext.dngettext(domain, singular, plural, n).
+pub fn dngettext(domain: &str, singular: &str, plural: &str, n: i64) -> String {
+    domain_translations(domain)
+        .ngettext(singular, plural, n)
+        .to_string()
+}
+
+/// Python-equivalent gettext.gettext(message), in the current textdomain.
+pub fn gettext(message: &str) -> String {
+    dgettext(&textdomain(None), message)
+}
+
+/// Python-equivalent gettext.ngettext(singular, plural, n).
+pub fn ngettext(singular: &str, plural: &str, n: i64) -> String {
+    dngettext(&textdomain(None), singular, plural, n)
+}
+
+/// Python-equivalent gettext.pgettext(context, message).
+pub fn pgettext(context: &str, message: &str) -> String {
+    domain_translations(&textdomain(None))
+        .pgettext(context, message)
+        .to_string()
+}
+
+/// Python-equivalent gettext.npgettext(context, singular, plural, n).
+pub fn npgettext(context: &str, singular: &str, plural: &str, n: i64) -> String {
+    domain_translations(&textdomain(None))
+        .npgettext(context, singular, plural, n)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A little-endian .mo file, as msgfmt lays it out, for `entries`
+    /// sorted by msgid.
+    fn mo(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut entries = entries.to_vec();
+        entries.sort();
+        let n = entries.len();
+        let mut data = Vec::new();
+        for word in [LE_MAGIC, 0, n as u32, 28, 28 + 8 * n as u32, 0, 0] {
+            data.extend(word.to_le_bytes());
+        }
+        let mut strings = Vec::new();
+        let mut tables = [Vec::new(), Vec::new()];
+        let base = 28 + 16 * n;
+        for (msgid, msgstr) in &entries {
+            for (table, s) in tables.iter_mut().zip([msgid, msgstr]) {
+                table.extend((s.len() as u32).to_le_bytes());
+                table.extend(((base + strings.len()) as u32).to_le_bytes());
+                strings.extend(s.as_bytes());
+                strings.push(0);
+            }
+        }
+        data.extend(tables.concat());
+        data.extend(strings);
+        data
+    }
+
+    const POLISH: &str = "n==1 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2";
+
+    #[test]
+    fn plural_expressions() {
+        let forms: Vec<i64> = [1, 2, 5, 12, 22, 25, 101]
+            .iter()
+            .map(|&n| plural_index(POLISH, n).unwrap())
+            .collect();
+        assert_eq!(forms, [0, 1, 2, 2, 1, 2, 2]);
+        assert_eq!(plural_index("n != 1", 0).unwrap(), 1);
+        assert_eq!(plural_index("(n > 1)", 1).unwrap(), 0);
+        assert_eq!(plural_index("!(n%2)", 4).unwrap(), 1);
+        assert_eq!(plural_index("0", 7).unwrap(), 0);
+        assert_eq!(plural_index("n / 0", 7).unwrap(), 0);
+        for bad in ["n ? 1", "(n", "n +", "os.system('x')", "n n"] {
+            assert!(
+                plural_index(bad, 1).unwrap_err().is("ValueError"),
+                "{}",
+                bad
+            );
+        }
+    }
+
+    #[test]
+    fn mo_and_json_catalogs() {
+        let header = "Content-Type: text/plain; charset=UTF-8\n\
+                      Plural-Forms: nplurals=3; plural=";
+        let header = format!("{}{};\n", header, POLISH);
+        let data = mo(&[
+            ("", &header),
+            ("Hello", "Cześć"),
+            ("%d file\0%d files", "%d plik\0%d pliki\0%d plików"),
+            ("menu\x04Open", "Otwórz"),
+        ]);
+        let t = Translations::from_mo(&data).unwrap();
+        assert_eq!(t.gettext("Hello"), "Cześć");
+        assert_eq!(t.gettext("Bye"), "Bye");
+        assert_eq!(t.ngettext("%d file", "%d files", 3), "%d pliki");
+        assert_eq!(t.ngettext("%d file", "%d files", 5), "%d plików");
+        assert_eq!(t.ngettext("%d dir", "%d dirs", 1), "%d dir");
+        assert_eq!(t.pgettext("menu", "Open"), "Otwórz");
+        assert_eq!(t.pgettext("file", "Open"), "Open");
+        assert_eq!(t.charset(), Some("UTF-8"));
+
+        let mut big_endian = data.clone();
+        big_endian[..4].copy_from_slice(&LE_MAGIC.to_be_bytes());
+        assert!(Translations::from_mo(&big_endian).is_err());
+        assert_eq!(
+            Translations::from_mo(b"nonsense").unwrap_err().message,
+            "Bad magic number"
+        );
+
+        let mut t = Translations::from_json(
+            r#"{"": "Plural-Forms: nplurals=2; plural=(n > 1);",
+                "Hello": "Bonjour", "%d file": ["%d fichier", "%d fichiers"]}"#,
+        )
+        .unwrap();
+        assert_eq!(t.ngettext("%d file", "%d files", 0), "%d fichier");
+        assert_eq!(t.gettext("Hello"), "Bonjour");
+        t.add_fallback(Translations::from_json(r#"{"Bye": "Salut"}"#).unwrap());
+        assert_eq!(t.gettext("Bye"), "Salut");
+        assert!(Translations::from_json("[1]").is_err());
+        assert!(Translations::from_json(r#"{"a": 1}"#).is_err());
+    }
+
+    #[test]
+    fn languages_and_lookup_on_disk() {
+        assert_eq!(
+            expand_lang("de_DE.UTF-8@euro"),
+            [
+                "de_DE.UTF-8@euro",
+                "de_DE@euro",
+                "de.UTF-8@euro",
+                "de@euro",
+                "de_DE.UTF-8",
+                "de_DE",
+                "de.UTF-8",
+                "de"
+            ]
+        );
+        assert_eq!(
+            candidate_languages(Some(&["pt_BR", "C", "fr"])),
+            ["pt_BR", "pt"]
+        );
+
+        let root = env::temp_dir().join(format!("stdpython_gettext_{}", std::process::id()));
+        let de = root.join("de").join("LC_MESSAGES");
+        fs::create_dir_all(&de).unwrap();
+        fs::write(de.join("app.mo"), mo(&[("Hello", "Hallo")])).unwrap();
+        let de_at = root.join("de_AT").join("LC_MESSAGES");
+        fs::create_dir_all(&de_at).unwrap();
+        fs::write(de_at.join("app.json"), r#"{"Hello": "Servus"}"#).unwrap();
+
+        let t = translation("app", Some(&root), Some(&["de_AT.UTF-8"]), false).unwrap();
+        assert_eq!(t.gettext("Hello"), "Servus");
+        let t = translation("app", Some(&root), Some(&["de_DE"]), false).unwrap();
+        assert_eq!(t.gettext("Hello"), "Hallo");
+        let err = translation("app", Some(&root), Some(&["ja"]), false).unwrap_err();
+        assert!(err.is("FileNotFoundError"));
+        let t = translation("app", Some(&root), Some(&["ja"]), true).unwrap();
+        assert_eq!(t.ngettext("a", "as", 2), "as");
+
+        assert_eq!(bindtextdomain("stdpython-test", Some(&root)), root);
+        assert_eq!(dgettext("stdpython-test", "Hello"), "Hello");
+        assert_eq!(textdomain(None), "messages");
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,430 @@
+//! Python-equivalent glob module.
+
+use std::fs;
+use std::path::{Path as StdPath, PathBuf};
+use std::rc::Rc;
+
+use crate::stdlib::fnmatch;
+use crate::stdlib::os::PathLike;
+
+const MAGIC_CHARS: &[char] = &['*', '?', '['];
+
+/// Python-equivalent glob.has_magic(s).
+pub fn has_magic(s: &str) -> bool {
+    s.contains(MAGIC_CHARS)
+}
+
+/// Python-equivalent glob.escape(pathname): brackets every magic character outside the drive.
+pub fn escape(pathname: &str) -> String {
+    let (drive, rest) = split_drive(pathname);
+    let mut out = String::from(drive);
+    for c in rest.chars() {
+        if MAGIC_CHARS.contains(&c) {
+            out.push('[');
+            out.push(c);
+            out.push(']');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Python-equivalent glob.glob(pathname, root_dir=..., recursive=..., include_hidden=...).
+///
+/// With `root_dir`, relative patterns are resolved against it and results are relative to it.
+pub fn glob<P: PathLike>(
+    pathname: P,
+    root_dir: Option<&str>,
+    recursive: bool,
+    include_hidden: bool,
+) -> Vec<String> {
+    iglob(pathname, root_dir, recursive, include_hidden).collect()
+}
+
+/// Python-equivalent glob.iglob(): a lazy iterator that reads one directory at a time.
+pub fn iglob<P: PathLike>(
+    pathname: P,
+    root_dir: Option<&str>,
+    recursive: bool,
+    include_hidden: bool,
+) -> IGlob {
+    let ctx = Rc::new(Context {
+        root_dir: root_dir.map(str::to_string),
+        recursive,
+        include_hidden,
+    });
+    let pathname = pathname.fspath();
+    let pathname = if recursive {
+        collapse_recursive(&pathname)
+    } else {
+        pathname
+    };
+    let skip_empty = recursive && pathname.starts_with("**");
+    let mut inner = iglob_impl(ctx, pathname, false);
+    if skip_empty {
+        // A leading "**" also yields the (empty) starting directory; CPython drops it.
+        inner = Box::new(inner.filter(|p| !p.is_empty()));
+    }
+    IGlob { inner }
+}
+
+/// Iterator returned by [`iglob`].
+pub struct IGlob {
+    inner: Box<dyn Iterator<Item = String>>,
+}
+
+impl Iterator for IGlob {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.inner.next()
+    }
+}
+
+struct Context {
+    root_dir: Option<String>,
+    recursive: bool,
+    include_hidden: bool,
+}
+
+impl Context {
+    fn full(&self, path: &str) -> PathBuf {
+        match (&self.root_dir, path.is_empty()) {
+            (Some(root), true) => PathBuf::from(root),
+            (Some(root), false) => StdPath::new(root).join(path),
+            (None, true) => PathBuf::from("."),
+            (None, false) => PathBuf::from(path),
+        }
+    }
+
+    fn is_dir(&self, path: &str) -> bool {
+        self.full(path).is_dir()
+    }
+
+    fn lexists(&self, path: &str) -> bool {
+        fs::symlink_metadata(self.full(path)).is_ok()
+    }
+
+    /// Sorted (name, is_dir) entries of a directory; unreadable directories are empty.
+    fn list_dir(&self, dir: &str, dironly: bool) -> Vec<(String, bool)> {
+        let mut names: Vec<(String, bool)> = match fs::read_dir(self.full(dir)) {
+            Ok(entries) => entries
+                .filter_map(Result::ok)
+                .map(|e| {
+                    let is_dir = e.path().is_dir();
+                    (e.file_name().to_string_lossy().into_owned(), is_dir)
+                })
+                .filter(|(_, is_dir)| !dironly || *is_dir)
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        names.sort();
+        names
+    }
+}
+
+/// Adjacent `**` segments match the same paths as one, but would multiply the walk.
+fn collapse_recursive(pathname: &str) -> String {
+    let mut segments: Vec<&str> = Vec::new();
+    for seg in pathname.split('/') {
+        if seg == "**" && segments.last() == Some(&"**") {
+            continue;
+        }
+        segments.push(seg);
+    }
+    segments.join("/")
+}
+
+fn is_sep(c: char) -> bool {
+    c == '/' || (cfg!(windows) && c == '\\')
+}
+
+fn split_drive(path: &str) -> (&str, &str) {
+    if cfg!(windows) && path.len() >= 2 && path.as_bytes()[1] == b':' {
+        path.split_at(2)
+    } else {
+        ("", path)
+    }
+}
+
+/// os.path.split() equivalent: (head without trailing separators, tail).
+fn split(path: &str) -> (&str, &str) {
+    match path.rfind(is_sep) {
+        Some(i) => {
+            let head = &path[..=i];
+            let trimmed = head.trim_end_matches(is_sep);
+            let head = if trimmed.is_empty() || trimmed.ends_with(':') {
+                head
+            } else {
+                trimmed
+            };
+            (head, &path[i + 1..])
+        }
+        None => ("", path),
+    }
+}
+
+fn join(dir: &str, name: &str) -> String {
+    if dir.is_empty() {
+        name.to_string()
+    } else if dir.ends_with(is_sep) {
+        format!("{}{}", dir, name)
+    } else {
+        format!("{}/{}", dir, name)
+    }
+}
+
+fn is_recursive(pattern: &str) -> bool {
+    pattern == "**"
+}
+
+fn is_hidden(name: &str) -> bool {
+    name.starts_with('.')
+}
+
+type Paths = Box<dyn Iterator<Item = String>>;
+
+fn iglob_impl(ctx: Rc<Context>, pathname: String, dironly: bool) -> Paths {
+    let (dirname, basename) = split(&pathname);
+    let (dirname, basename) = (dirname.to_string(), basename.to_string());
+    if !has_magic(&pathname) {
+        let found = if basename.is_empty() {
+            ctx.is_dir(&pathname)
+        } else {
+            ctx.lexists(&pathname)
+        };
+        return Box::new(found.then_some(pathname).into_iter());
+    }
+    // Compile the last segment once; it is matched against every directory in `dirs`.
+    let segment = Rc::new(Segment::compile(&ctx, &basename));
+    if dirname.is_empty() {
+        return select(ctx, String::new(), &segment, dironly);
+    }
+
+    let dirs: Paths = if dirname != pathname && has_magic(&dirname) {
+        iglob_impl(ctx.clone(), dirname, true)
+    } else {
+        Box::new(std::iter::once(dirname))
+    };
+    Box::new(dirs.flat_map(move |dir| {
+        let names = select(ctx.clone(), dir.clone(), &segment, dironly);
+        names.map(move |name| join(&dir, &name))
+    }))
+}
+
+/// One path segment of a glob pattern, compiled once per pattern.
+enum Segment {
+    /// `**` with recursive=True.
+    Recursive,
+    /// A wildcard segment; `hidden_ok` when it may match dotfiles.
+    Wildcard {
+        pattern: fnmatch::Pattern,
+        hidden_ok: bool,
+    },
+    /// A name without wildcards: a single existence check, no directory listing.
+    Literal(String),
+    /// A trailing separator: the directory itself, if it is one.
+    Empty,
+}
+
+impl Segment {
+    fn compile(ctx: &Context, pattern: &str) -> Segment {
+        if ctx.recursive && is_recursive(pattern) {
+            Segment::Recursive
+        } else if has_magic(pattern) {
+            Segment::Wildcard {
+                pattern: fnmatch::Pattern::new(&fnmatch::normcase(pattern)),
+                hidden_ok: ctx.include_hidden || is_hidden(pattern),
+            }
+        } else if pattern.is_empty() {
+            Segment::Empty
+        } else {
+            Segment::Literal(pattern.to_string())
+        }
+    }
+}
+
+/// Names in `dir` matching one pattern segment, relative to `dir`.
+fn select(ctx: Rc<Context>, dir: String, segment: &Rc<Segment>, dironly: bool) -> Paths {
+    match &**segment {
+        Segment::Recursive => {
+            Box::new(std::iter::once(String::new()).chain(RecursiveWalk::new(ctx, dir, dironly)))
+        }
+        Segment::Wildcard { pattern, hidden_ok } => {
+            let names: Vec<String> = ctx
+                .list_dir(&dir, dironly)
+                .into_iter()
+                .map(|(name, _)| name)
+                .filter(|name| *hidden_ok || !is_hidden(name))
+                .filter(|name| {
+                    if cfg!(windows) {
+                        pattern.matches(&fnmatch::normcase(name))
+                    } else {
+                        pattern.matches(name)
+                    }
+                })
+                .collect();
+            Box::new(names.into_iter())
+        }
+        Segment::Empty => Box::new(ctx.is_dir(&dir).then(String::new).into_iter()),
+        Segment::Literal(name) => {
+            let found = ctx.lexists(&join(&dir, name));
+            Box::new(found.then(|| name.clone()).into_iter())
+        }
+    }
+}
+
+/// Lazy pre-order walk below a directory, used for `**`; symlinked directories are not followed.
+struct RecursiveWalk {
+    ctx: Rc<Context>,
+    dir: String,
+    dironly: bool,
+    stack: Vec<std::vec::IntoIter<(String, bool)>>,
+}
+
+impl RecursiveWalk {
+    fn new(ctx: Rc<Context>, dir: String, dironly: bool) -> Self {
+        let first = ctx.list_dir(&dir, false).into_iter();
+        RecursiveWalk {
+            ctx,
+            dir,
+            dironly,
+            stack: vec![first],
+        }
+    }
+}
+
+impl Iterator for RecursiveWalk {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            let top = self.stack.last_mut()?;
+            let Some((rel, is_dir)) = top.next() else {
+                self.stack.pop();
+                continue;
+            };
+            let name = rel.rsplit('/').next().unwrap_or(&rel);
+            if !self.ctx.include_hidden && is_hidden(name) {
+                continue;
+            }
+            if is_dir {
+                let full = join(&self.dir, &rel);
+                let is_link = fs::symlink_metadata(self.ctx.full(&full))
+                    .map(|m| m.file_type().is_symlink())
+                    .unwrap_or(false);
+                if !is_link {
+                    let children: Vec<(String, bool)> = self
+                        .ctx
+                        .list_dir(&full, false)
+                        .into_iter()
+                        .map(|(child, child_is_dir)| (join(&rel, &child), child_is_dir))
+                        .collect();
+                    self.stack.push(children.into_iter());
+                }
+            }
+            if !self.dironly || is_dir {
+                return Some(rel);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn fixture(name: &str) -> String {
+        let root = env::temp_dir().join(format!("stdpython_glob_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("sub/deep")).unwrap();
+        for file in [
+            "a.txt",
+            "b.py",
+            ".hidden.txt",
+            "sub/c.txt",
+            "sub/deep/d.txt",
+        ] {
+            fs::write(root.join(file), b"").unwrap();
+        }
+        root.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn magic_and_escape() {
+        assert!(has_magic("*.txt"));
+        assert!(!has_magic("plain.txt"));
+        assert_eq!(escape("a[b]*?.txt"), "a[[]b][*][?].txt");
+    }
+
+    #[test]
+    fn matches_files() {
+        let root = fixture("files");
+        let found = glob(format!("{}/*.txt", root), None, false, false);
+        assert_eq!(found, vec![format!("{}/a.txt", root)]);
+        let found = glob(format!("{}/.*", root), None, false, false);
+        assert_eq!(found, vec![format!("{}/.hidden.txt", root)]);
+        let found = glob(format!("{}/*/*.txt", root), None, false, false);
+        assert_eq!(found, vec![format!("{}/sub/c.txt", root)]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn recursive_double_star() {
+        let root = fixture("recursive");
+        let found = glob(format!("{}/**/*.txt", root), None, true, false);
+        assert_eq!(
+            found,
+            vec![
+                format!("{}/a.txt", root),
+                format!("{}/sub/c.txt", root),
+                format!("{}/sub/deep/d.txt", root),
+            ]
+        );
+        let not_recursive = glob(format!("{}/**/*.txt", root), None, false, false);
+        assert_eq!(not_recursive, vec![format!("{}/sub/c.txt", root)]);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn root_dir_and_hidden() {
+        let root = fixture("root_dir");
+        assert_eq!(glob("*.txt", Some(&root), false, false), vec!["a.txt"]);
+        assert_eq!(
+            glob("*.txt", Some(&root), false, true),
+            vec![".hidden.txt", "a.txt"]
+        );
+        assert_eq!(
+            glob("**", Some(&root), true, false),
+            vec![
+                "a.txt",
+                "b.py",
+                "sub",
+                "sub/c.txt",
+                "sub/deep",
+                "sub/deep/d.txt"
+            ]
+        );
+        assert_eq!(
+            glob("**/", Some(&root), true, false),
+            vec!["sub/", "sub/deep/"]
+        );
+        assert_eq!(
+            glob("**/**/**/d.txt", Some(&root), true, false),
+            vec!["sub/deep/d.txt"]
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn iglob_is_lazy() {
+        let root = fixture("lazy");
+        let mut it = iglob("**", Some(&root), true, false);
+        assert_eq!(it.next().as_deref(), Some("a.txt"));
+        fs::remove_dir_all(&root).unwrap();
+        // The remaining directories are only read on demand, so they are now gone.
+        assert!(it.count() <= 2);
+    }
+}
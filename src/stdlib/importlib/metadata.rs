@@ -0,0 +1,163 @@
+//! Python-equivalent importlib.metadata: installed distribution versions.
+//!
+//! Distributions declared by the compiled program are registered up front; anything else
+//! is looked up in `*.dist-info` / `*.egg-info` directories on PYTHONPATH and in
+//! site-packages, as CPython does.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::sysconfig;
+
+fn registry() -> &'static RwLock<BTreeMap<String, BTreeMap<String, String>>> {
+    static REGISTRY: OnceLock<RwLock<BTreeMap<String, BTreeMap<String, String>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(BTreeMap::new()))
+}
+
+/// PEP 503 name normalization: case-folded, with runs of `-`, `_` and `.` as a single `-`.
+pub fn normalize_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut last_sep = false;
+    for c in name.chars() {
+        if matches!(c, '-' | '_' | '.') {
+            if !last_sep {
+                out.push('-');
+            }
+            last_sep = true;
+        } else {
+            out.push(c.to_ascii_lowercase());
+            last_sep = false;
+        }
+    }
+    out
+}
+
+/// Declares a distribution bundled with the compiled program.
+pub fn register_distribution(name: &str, version: &str) {
+    let mut metadata = BTreeMap::new();
+    metadata.insert("Name".to_string(), name.to_string());
+    metadata.insert("Version".to_string(), version.to_string());
+    registry()
+        .write()
+        .expect("metadata registry poisoned")
+        .insert(normalize_name(name), metadata);
+}
+
+fn not_found(name: &str) -> PyException {
+    PyException::new(
+        "PackageNotFoundError",
+        format!("No package metadata was found for {}", name),
+    )
+}
+
+fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = env::var_os("PYTHONPATH")
+        .map(|p| env::split_paths(&p).collect())
+        .unwrap_or_default();
+    for key in ["purelib", "platlib"] {
+        if let Ok(dir) = sysconfig::get_path(key, None, None, true) {
+            let dir = PathBuf::from(dir);
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+    dirs
+}
+
+/// Parses the RFC 822-style header block of a METADATA / PKG-INFO file.
+fn parse_metadata(text: &str) -> BTreeMap<String, String> {
+    let mut out = BTreeMap::new();
+    for line in text.lines() {
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            out.entry(key.trim().to_string())
+                .or_insert_with(|| value.trim().to_string());
+        }
+    }
+    out
+}
+
+fn find_on_disk(name: &str) -> Option<BTreeMap<String, String>> {
+    let wanted = normalize_name(name);
+    for dir in search_dirs() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let (stem, meta_file) = if let Some(stem) = file_name.strip_suffix(".dist-info") {
+                (stem, "METADATA")
+            } else if let Some(stem) = file_name.strip_suffix(".egg-info") {
+                (stem, "PKG-INFO")
+            } else {
+                continue;
+            };
+            let dist = stem.split('-').next().unwrap_or(stem);
+            if normalize_name(dist) != wanted {
+                continue;
+            }
+            if let Ok(text) = fs::read_to_string(entry.path().join(meta_file)) {
+                return Some(parse_metadata(&text));
+            }
+        }
+    }
+    None
+}
+
+/// Python-equivalent importlib.metadata.metadata(name): the distribution's header fields.
+pub fn metadata(name: &str) -> PyResult<BTreeMap<String, String>> {
+    if let Some(found) = registry()
+        .read()
+        .expect("metadata registry poisoned")
+        .get(&normalize_name(name))
+    {
+        return Ok(found.clone());
+    }
+    find_on_disk(name).ok_or_else(|| not_found(name))
+}
+
+/// Python-equivalent importlib.metadata.version(name).
+pub fn version(name: &str) -> PyResult<String> {
+    metadata(name)?
+        .get("Version")
+        .cloned()
+        .ok_or_else(|| not_found(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_versions() {
+        register_distribution("My_Package", "1.2.3");
+        assert_eq!(version("my-package").unwrap(), "1.2.3");
+        assert_eq!(version("MY.PACKAGE").unwrap(), "1.2.3");
+        assert!(version("not-installed-anywhere-xyz")
+            .unwrap_err()
+            .is("PackageNotFoundError"));
+    }
+
+    #[test]
+    fn dist_info_lookup() {
+        let dir = env::temp_dir().join(format!("stdpython_metadata_{}", std::process::id()));
+        let info = dir.join("Sample_Dist-0.4.0.dist-info");
+        fs::create_dir_all(&info).unwrap();
+        fs::write(
+            info.join("METADATA"),
+            "Metadata-Version: 2.1\nName: Sample-Dist\nVersion: 0.4.0\n\nBody\n",
+        )
+        .unwrap();
+        let text = fs::read_to_string(info.join("METADATA")).unwrap();
+        assert_eq!(parse_metadata(&text)["Version"], "0.4.0");
+        assert_eq!(normalize_name("Sample__Dist"), "sample-dist");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,221 @@
+//! Python-equivalent importlib package: module location and package data for compiled programs.
+//!
+//! Compiled code has no import system, so the compiler registers where each module came
+//! from (and any embedded data files) at startup, and the APIs here resolve against that.
+
+pub mod metadata;
+pub mod resources;
+
+use std::collections::HashMap;
+use std::env;
+use std::path::{Path as StdPath, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::{PyDictionary, PyValue};
+
+fn module_paths() -> &'static RwLock<HashMap<String, PathBuf>> {
+    static PATHS: OnceLock<RwLock<HashMap<String, PathBuf>>> = OnceLock::new();
+    PATHS.get_or_init(|| RwLock::new(HashMap::new()))
+}
+
+/// Records the source file of a compiled module (its `__file__`).
+pub fn register_module_path<P: Into<PathBuf>>(module: &str, file: P) {
+    module_paths()
+        .write()
+        .expect("module path registry poisoned")
+        .insert(module.to_string(), file.into());
+}
+
+pub(crate) fn module_not_found(name: &str) -> PyException {
+    PyException::new("ModuleNotFoundError", format!("No module named '{}'", name))
+}
+
+/// Directories searched for modules that were not registered: PYTHONPATH, then the cwd.
+fn search_path() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = env::var_os("PYTHONPATH")
+        .map(|p| env::split_paths(&p).collect())
+        .unwrap_or_default();
+    if let Ok(cwd) = env::current_dir() {
+        dirs.push(cwd);
+    }
+    dirs
+}
+
+/// Resolves a dotted module name to its file: the registry first, then `pkg/mod.py` or
+/// `pkg/mod/__init__.py` on PYTHONPATH.
+pub fn module_path(name: &str) -> PyResult<PathBuf> {
+    if let Some(path) = module_paths()
+        .read()
+        .expect("module path registry poisoned")
+        .get(name)
+    {
+        return Ok(path.clone());
+    }
+    let rel: PathBuf = name.split('.').collect();
+    for dir in search_path() {
+        let package = dir.join(&rel).join("__init__.py");
+        if package.is_file() {
+            return Ok(package);
+        }
+        let module = dir.join(&rel).with_extension("py");
+        if module.is_file() {
+            return Ok(module);
+        }
+    }
+    Err(module_not_found(name))
+}
+
+/// The directory holding a package's files (for a plain module, its containing directory).
+pub fn package_dir(name: &str) -> PyResult<PathBuf> {
+    let file = module_path(name)?;
+    Ok(file.parent().map(StdPath::to_path_buf).unwrap_or(file))
+}
+
+/// Per-module state the compiler creates for each compiled module: its `__name__`,
+/// `__file__`, `__package__` and module globals.
+#[derive(Debug, Clone)]
+pub struct ModuleContext {
+    name: String,
+    file: Option<PathBuf>,
+    package: Option<String>,
+    globals: PyDictionary<String, PyValue>,
+}
+
+impl ModuleContext {
+    /// Context for an imported module. A file named `__init__.py` makes the module its own
+    /// package; otherwise the package is the name up to the last dot. The file is also
+    /// registered so `module_path` resolves it.
+    pub fn new<P: Into<PathBuf>>(name: &str, file: P) -> Self {
+        let file = file.into();
+        register_module_path(name, file.clone());
+        let package = if file.file_name().is_some_and(|f| f == "__init__.py") {
+            name.to_string()
+        } else {
+            name.rsplit_once('.').map_or("", |(pkg, _)| pkg).to_string()
+        };
+        Self::with_parts(name, Some(file), Some(package))
+    }
+
+    /// Context for the program's entry point: `__name__` is `"__main__"` and, as for a
+    /// script run directly, `__package__` is None.
+    pub fn main<P: Into<PathBuf>>(file: P) -> Self {
+        Self::with_parts("__main__", Some(file.into()), None)
+    }
+
+    /// Context for a module with no source file (e.g. one built into the binary).
+    pub fn builtin(name: &str) -> Self {
+        Self::with_parts(name, None, None)
+    }
+
+    fn with_parts(name: &str, file: Option<PathBuf>, package: Option<String>) -> Self {
+        let mut globals = PyDictionary::new();
+        globals.set_item("__name__".to_string(), PyValue::from(name));
+        if let Some(file) = &file {
+            globals.set_item(
+                "__file__".to_string(),
+                PyValue::from(file.to_string_lossy().into_owned()),
+            );
+        }
+        globals.set_item("__package__".to_string(), PyValue::from(package.clone()));
+        ModuleContext {
+            name: name.to_string(),
+            file,
+            package,
+            globals,
+        }
+    }
+
+    /// The module's `__name__`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The module's `__file__`, if it came from a source file.
+    pub fn file(&self) -> Option<&StdPath> {
+        self.file.as_deref()
+    }
+
+    /// The module's `__package__`.
+    pub fn package(&self) -> Option<&str> {
+        self.package.as_deref()
+    }
+
+    /// Python-equivalent `__name__ == "__main__"`.
+    pub fn is_main(&self) -> bool {
+        self.name == "__main__"
+    }
+
+    /// Python-equivalent globals().
+    pub fn globals(&self) -> &PyDictionary<String, PyValue> {
+        &self.globals
+    }
+
+    pub fn globals_mut(&mut self) -> &mut PyDictionary<String, PyValue> {
+        &mut self.globals
+    }
+
+    /// Looks up a module-level name, raising NameError if it is unbound.
+    pub fn get_global(&self, name: &str) -> PyResult<PyValue> {
+        self.globals
+            .get(name)
+            .cloned()
+            .ok_or_else(|| PyException::new("NameError", format!("name '{}' is not defined", name)))
+    }
+
+    pub fn set_global<V: Into<PyValue>>(&mut self, name: &str, value: V) {
+        self.globals.set_item(name.to_string(), value.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_modules_resolve() {
+        register_module_path("app.config", "/srv/app/config.py");
+        assert_eq!(
+            module_path("app.config").unwrap(),
+            PathBuf::from("/srv/app/config.py")
+        );
+        assert_eq!(
+            package_dir("app.config").unwrap(),
+            PathBuf::from("/srv/app")
+        );
+        assert!(module_path("definitely.not.here")
+            .unwrap_err()
+            .is("ModuleNotFoundError"));
+    }
+
+    #[test]
+    fn module_context_dunders() {
+        let main = ModuleContext::main("/srv/app/run.py");
+        assert!(main.is_main());
+        assert_eq!(main.package(), None);
+        assert!(main.get_global("__package__").unwrap().is_none());
+
+        let sub = ModuleContext::new("app.models.user", "/srv/app/models/user.py");
+        assert!(!sub.is_main());
+        assert_eq!(sub.package(), Some("app.models"));
+        assert_eq!(sub.file(), Some(StdPath::new("/srv/app/models/user.py")));
+        assert_eq!(
+            module_path("app.models.user").unwrap(),
+            PathBuf::from("/srv/app/models/user.py")
+        );
+
+        let mut pkg = ModuleContext::new("app.models", "/srv/app/models/__init__.py");
+        assert_eq!(pkg.package(), Some("app.models"));
+        pkg.set_global("VERSION", 3);
+        assert_eq!(
+            pkg.get_global("__name__").unwrap().to_string(),
+            "app.models"
+        );
+        assert_eq!(pkg.get_global("VERSION").unwrap().to_string(), "3");
+        assert!(pkg.get_global("missing").unwrap_err().is("NameError"));
+
+        let top = ModuleContext::new("app", "/srv/app.py");
+        assert_eq!(top.package(), Some(""));
+        assert!(ModuleContext::builtin("sys").file().is_none());
+    }
+}
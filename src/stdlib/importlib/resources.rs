@@ -0,0 +1,337 @@
+//! Python-equivalent importlib.resources: data files shipped alongside a package.
+//!
+//! Files are either embedded in the binary (`register_embedded`, typically fed by
+//! `include_bytes!`) or read from a package directory on disk.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path as StdPath, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::importlib::{module_not_found, package_dir};
+use crate::stdlib::tempfile::NamedTemporaryFile;
+
+/// Embedded files per package, keyed by "/"-separated relative path.
+type EmbeddedFiles = BTreeMap<String, BTreeMap<String, &'static [u8]>>;
+
+fn embedded() -> &'static RwLock<EmbeddedFiles> {
+    static EMBEDDED: OnceLock<RwLock<EmbeddedFiles>> = OnceLock::new();
+    EMBEDDED.get_or_init(|| RwLock::new(BTreeMap::new()))
+}
+
+fn directories() -> &'static RwLock<BTreeMap<String, PathBuf>> {
+    static DIRS: OnceLock<RwLock<BTreeMap<String, PathBuf>>> = OnceLock::new();
+    DIRS.get_or_init(|| RwLock::new(BTreeMap::new()))
+}
+
+/// Embeds `data` as `package`'s resource at `path` ("/"-separated, relative to the package).
+pub fn register_embedded(package: &str, path: &str, data: &'static [u8]) {
+    embedded()
+        .write()
+        .expect("resource registry poisoned")
+        .entry(package.to_string())
+        .or_default()
+        .insert(path.trim_matches('/').to_string(), data);
+}
+
+/// Serves `package`'s resources from `dir` on disk.
+pub fn register_directory<P: Into<PathBuf>>(package: &str, dir: P) {
+    directories()
+        .write()
+        .expect("resource registry poisoned")
+        .insert(package.to_string(), dir.into());
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Root {
+    Embedded,
+    Disk(PathBuf),
+}
+
+/// Python-equivalent importlib.resources.abc.Traversable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Traversable {
+    package: String,
+    root: Root,
+    /// "/"-separated path below the package root; empty for the root itself.
+    rel: String,
+}
+
+/// Python-equivalent importlib.resources.files(package).
+pub fn files(package: &str) -> PyResult<Traversable> {
+    let root = if embedded()
+        .read()
+        .expect("resource registry poisoned")
+        .contains_key(package)
+    {
+        Root::Embedded
+    } else if let Some(dir) = directories()
+        .read()
+        .expect("resource registry poisoned")
+        .get(package)
+    {
+        Root::Disk(dir.clone())
+    } else {
+        Root::Disk(package_dir(package).map_err(|_| module_not_found(package))?)
+    };
+    Ok(Traversable {
+        package: package.to_string(),
+        root,
+        rel: String::new(),
+    })
+}
+
+impl Traversable {
+    /// Python-equivalent Traversable.name.
+    pub fn name(&self) -> String {
+        match self.rel.rsplit('/').next() {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => self.package.rsplit('.').next().unwrap_or("").to_string(),
+        }
+    }
+
+    /// Python-equivalent Traversable.joinpath(*descendants) / `traversable / name`.
+    pub fn joinpath(&self, name: &str) -> Traversable {
+        let mut rel = self.rel.clone();
+        for part in name
+            .split(['/', '\\'])
+            .filter(|p| !p.is_empty() && *p != ".")
+        {
+            if part == ".." {
+                if let Some(i) = rel.rfind('/') {
+                    rel.truncate(i);
+                } else {
+                    rel.clear();
+                }
+                continue;
+            }
+            if !rel.is_empty() {
+                rel.push('/');
+            }
+            rel.push_str(part);
+        }
+        Traversable {
+            package: self.package.clone(),
+            root: self.root.clone(),
+            rel,
+        }
+    }
+
+    fn disk_path(&self) -> Option<PathBuf> {
+        match &self.root {
+            Root::Disk(dir) => Some(
+                self.rel
+                    .split('/')
+                    .filter(|p| !p.is_empty())
+                    .fold(dir.clone(), |p, c| p.join(c)),
+            ),
+            Root::Embedded => None,
+        }
+    }
+
+    fn embedded_bytes(&self) -> Option<&'static [u8]> {
+        embedded()
+            .read()
+            .expect("resource registry poisoned")
+            .get(&self.package)
+            .and_then(|files| files.get(&self.rel).copied())
+    }
+
+    pub fn is_file(&self) -> bool {
+        match self.disk_path() {
+            Some(path) => path.is_file(),
+            None => self.embedded_bytes().is_some(),
+        }
+    }
+
+    pub fn is_dir(&self) -> bool {
+        match self.disk_path() {
+            Some(path) => path.is_dir(),
+            None => {
+                let prefix = if self.rel.is_empty() {
+                    String::new()
+                } else {
+                    format!("{}/", self.rel)
+                };
+                embedded()
+                    .read()
+                    .expect("resource registry poisoned")
+                    .get(&self.package)
+                    .map(|files| files.keys().any(|k| k.starts_with(&prefix)))
+                    .unwrap_or(false)
+            }
+        }
+    }
+
+    /// Python-equivalent Traversable.iterdir().
+    pub fn iterdir(&self) -> PyResult<Vec<Traversable>> {
+        if let Some(path) = self.disk_path() {
+            let mut names: Vec<String> = fs::read_dir(path)?
+                .filter_map(Result::ok)
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect();
+            names.sort();
+            return Ok(names.iter().map(|n| self.joinpath(n)).collect());
+        }
+        let prefix = if self.rel.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", self.rel)
+        };
+        let registry = embedded().read().expect("resource registry poisoned");
+        let mut children: Vec<String> = registry
+            .get(&self.package)
+            .map(|files| {
+                files
+                    .keys()
+                    .filter_map(|k| k.strip_prefix(&prefix))
+                    .map(|rest| rest.split('/').next().unwrap_or(rest).to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        children.dedup();
+        Ok(children.iter().map(|n| self.joinpath(n)).collect())
+    }
+
+    /// Python-equivalent Traversable.read_bytes().
+    pub fn read_bytes(&self) -> PyResult<Vec<u8>> {
+        match self.disk_path() {
+            Some(path) => Ok(fs::read(path)?),
+            None => self.embedded_bytes().map(<[u8]>::to_vec).ok_or_else(|| {
+                PyException::new(
+                    "FileNotFoundError",
+                    format!(
+                        "No such resource: '{}' in package '{}'",
+                        self.rel, self.package
+                    ),
+                )
+            }),
+        }
+    }
+
+    /// Python-equivalent Traversable.read_text() (UTF-8).
+    pub fn read_text(&self) -> PyResult<String> {
+        String::from_utf8(self.read_bytes()?).map_err(|e| {
+            PyException::new(
+                "UnicodeDecodeError",
+                format!("'utf-8' codec can't decode: {}", e),
+            )
+        })
+    }
+}
+
+impl std::fmt::Display for Traversable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.disk_path() {
+            Some(path) => write!(f, "{}", path.display()),
+            None => write!(f, "{}:{}", self.package, self.rel),
+        }
+    }
+}
+
+/// Result of [`as_file`]: a real filesystem path, valid while this value lives.
+#[derive(Debug)]
+pub struct ResourcePath {
+    path: PathBuf,
+    _temp: Option<NamedTemporaryFile>,
+}
+
+impl ResourcePath {
+    pub fn path(&self) -> &StdPath {
+        &self.path
+    }
+}
+
+/// Python-equivalent importlib.resources.as_file(traversable): embedded resources are
+/// written to a temporary file that is removed when the returned value is dropped.
+pub fn as_file(resource: &Traversable) -> PyResult<ResourcePath> {
+    if let Some(path) = resource.disk_path() {
+        return Ok(ResourcePath { path, _temp: None });
+    }
+    let data = resource.read_bytes()?;
+    let suffix = resource
+        .name()
+        .rfind('.')
+        .map(|i| resource.name()[i..].to_string())
+        .unwrap_or_default();
+    let mut temp = NamedTemporaryFile::builder()
+        .suffix(&suffix)
+        .delete_on_close(false)
+        .named()?;
+    std::io::Write::write_all(&mut temp, &data)?;
+    temp.close()?;
+    Ok(ResourcePath {
+        path: PathBuf::from(temp.name()),
+        _temp: Some(temp),
+    })
+}
+
+/// Python-equivalent importlib.resources.read_text(package, resource).
+pub fn read_text(package: &str, resource: &str) -> PyResult<String> {
+    files(package)?.joinpath(resource).read_text()
+}
+
+/// Python-equivalent importlib.resources.read_binary(package, resource).
+pub fn read_binary(package: &str, resource: &str) -> PyResult<Vec<u8>> {
+    files(package)?.joinpath(resource).read_bytes()
+}
+
+/// Python-equivalent importlib.resources.is_resource(package, name).
+pub fn is_resource(package: &str, name: &str) -> PyResult<bool> {
+    Ok(files(package)?.joinpath(name).is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_resources() {
+        register_embedded("demo.data", "greeting.txt", b"hello");
+        register_embedded("demo.data", "nested/table.csv", b"a,b\n");
+        let root = files("demo.data").unwrap();
+        assert_eq!(root.joinpath("greeting.txt").read_text().unwrap(), "hello");
+        assert!(root.joinpath("nested").is_dir());
+        let names: Vec<String> = root
+            .iterdir()
+            .unwrap()
+            .iter()
+            .map(Traversable::name)
+            .collect();
+        assert_eq!(names, vec!["greeting.txt", "nested"]);
+        assert!(root
+            .joinpath("missing")
+            .read_bytes()
+            .unwrap_err()
+            .is("FileNotFoundError"));
+
+        let table = root.joinpath("nested").joinpath("table.csv");
+        let path = {
+            let on_disk = as_file(&table).unwrap();
+            assert_eq!(fs::read(on_disk.path()).unwrap(), b"a,b\n");
+            on_disk.path().to_path_buf()
+        };
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn directory_resources() {
+        let dir = std::env::temp_dir().join(format!("stdpython_resources_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("schema.json"), b"{}").unwrap();
+        register_directory("demo.disk", &dir);
+        assert_eq!(read_text("demo.disk", "schema.json").unwrap(), "{}");
+        assert!(is_resource("demo.disk", "schema.json").unwrap());
+        assert_eq!(
+            as_file(&files("demo.disk").unwrap().joinpath("schema.json"))
+                .unwrap()
+                .path(),
+            dir.join("schema.json")
+        );
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(files("no.such.package")
+            .unwrap_err()
+            .is("ModuleNotFoundError"));
+    }
+}
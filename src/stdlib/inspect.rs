@@ -0,0 +1,606 @@
+//! Python-equivalent inspect module, reduced to what logging and
+//! decorator-heavy code reaches for: signature(), stack(), currentframe()
+//! and getsourcefile().
+//!
+//! Compiled code has no interpreter frames or code objects to inspect, so
+//! both come from bookkeeping the generated code does itself: functions
+//! [`register`] their source location and signature once, and calls
+//! [`enter_frame`] on the way in, which keeps a per-thread frame stack that
+//! unwinds as the returned guards drop. The same calls report to the
+//! sys.settrace() and sys.setprofile() hooks.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::sys::{self, TraceEvent};
+use crate::sync::Lock;
+use crate::types::{function, PyFunction};
+
+/// Python-equivalent inspect.Parameter.kind, in CPython's required order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ParameterKind {
+    PositionalOnly,
+    PositionalOrKeyword,
+    VarPositional,
+    KeywordOnly,
+    VarKeyword,
+}
+
+impl ParameterKind {
+    /// The name of the kind, e.g. `POSITIONAL_OR_KEYWORD`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ParameterKind::PositionalOnly => "POSITIONAL_ONLY",
+            ParameterKind::PositionalOrKeyword => "POSITIONAL_OR_KEYWORD",
+            ParameterKind::VarPositional => "VAR_POSITIONAL",
+            ParameterKind::KeywordOnly => "KEYWORD_ONLY",
+            ParameterKind::VarKeyword => "VAR_KEYWORD",
+        }
+    }
+
+    /// Python-equivalent Parameter.kind.description, e.g. "keyword-only".
+    pub fn description(self) -> &'static str {
+        match self {
+            ParameterKind::PositionalOnly => "positional-only",
+            ParameterKind::PositionalOrKeyword => "positional or keyword",
+            ParameterKind::VarPositional => "variadic positional",
+            ParameterKind::KeywordOnly => "keyword-only",
+            ParameterKind::VarKeyword => "variadic keyword",
+        }
+    }
+}
+
+impl fmt::Display for ParameterKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Python-equivalent inspect.Parameter. The default and annotation are
+/// kept as source text, which is what str(signature) shows of them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Parameter {
+    pub name: String,
+    pub kind: ParameterKind,
+    pub default: Option<String>,
+    pub annotation: Option<String>,
+}
+
+impl Parameter {
+    pub fn new(name: &str, kind: ParameterKind) -> Self {
+        Parameter {
+            name: name.to_string(),
+            kind,
+            default: None,
+            annotation: None,
+        }
+    }
+
+    pub fn with_default(mut self, default: &str) -> Self {
+        self.default = Some(default.to_string());
+        self
+    }
+
+    pub fn with_annotation(mut self, annotation: &str) -> Self {
+        self.annotation = Some(annotation.to_string());
+        self
+    }
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ParameterKind::VarPositional => f.write_str("*")?,
+            ParameterKind::VarKeyword => f.write_str("**")?,
+            _ => {}
+        }
+        f.write_str(&self.name)?;
+        if let Some(annotation) = &self.annotation {
+            write!(f, ": {}", annotation)?;
+        }
+        match (&self.default, &self.annotation) {
+            (Some(default), Some(_)) => write!(f, " = {}", default),
+            (Some(default), None) => write!(f, "={}", default),
+            (None, _) => Ok(()),
+        }
+    }
+}
+
+/// Python-equivalent inspect.Signature.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Signature {
+    pub parameters: Vec<Parameter>,
+    pub return_annotation: Option<String>,
+}
+
+/// Splits `text` at top-level occurrences of `sep`, outside brackets and
+/// string literals.
+fn split_top_level(text: &str, sep: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in text.char_indices() {
+        if let Some(q) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            c if c == sep && depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+/// `name: annotation = default` split into its three parts.
+fn split_parameter(text: &str) -> (&str, Option<&str>, Option<&str>) {
+    let mut default = None;
+    let mut head = text;
+    let bytes = text.as_bytes();
+    let eq = split_top_level(text, '=')
+        .iter()
+        .scan(0, |offset, part| {
+            *offset += part.len() + 1;
+            Some(*offset - 1)
+        })
+        .find(|&i| {
+            i < text.len()
+                && bytes.get(i + 1) != Some(&b'=')
+                && !matches!(
+                    i.checked_sub(1).map(|p| bytes[p]),
+                    Some(b'=' | b'<' | b'>' | b'!')
+                )
+        });
+    if let Some(eq) = eq {
+        head = &text[..eq];
+        default = Some(text[eq + 1..].trim());
+    }
+    match split_top_level(head, ':').as_slice() {
+        [name, annotation, ..] => (name.trim(), Some(annotation.trim()), default),
+        _ => (head.trim(), None, default),
+    }
+}
+
+impl Signature {
+    /// Python-equivalent Signature(parameters, return_annotation), which
+    /// checks the kinds are in order, that no parameter without a default
+    /// follows one with a default, and that names are unique.
+    pub fn new(parameters: Vec<Parameter>, return_annotation: Option<String>) -> PyResult<Self> {
+        let mut seen_default = false;
+        for (i, param) in parameters.iter().enumerate() {
+            if let Some(prev) = i.checked_sub(1).map(|p| &parameters[p]) {
+                if param.kind < prev.kind {
+                    return Err(PyException::value_error(format!(
+                        "wrong parameter order: {} parameter before {} parameter",
+                        prev.kind.description(),
+                        param.kind.description()
+                    )));
+                }
+            }
+            if matches!(
+                param.kind,
+                ParameterKind::PositionalOnly | ParameterKind::PositionalOrKeyword
+            ) {
+                if param.default.is_some() {
+                    seen_default = true;
+                } else if seen_default {
+                    return Err(PyException::value_error(
+                        "non-default argument follows default argument",
+                    ));
+                }
+            }
+            if parameters[..i].iter().any(|p| p.name == param.name) {
+                return Err(PyException::value_error(format!(
+                    "duplicate parameter name: '{}'",
+                    param.name
+                )));
+            }
+        }
+        Ok(Signature {
+            parameters,
+            return_annotation,
+        })
+    }
+
+    /// Parses the text str(signature) produces, e.g.
+    /// `"(a, /, b: int = 1, *args, c, **kwargs) -> str"`; the form the
+    /// compiler registers signatures in.
+    pub fn parse(text: &str) -> PyResult<Self> {
+        let invalid = || PyException::value_error(format!("invalid signature: {:?}", text));
+        let text = text.trim();
+        let parts = split_top_level(text, '-');
+        let close = parts
+            .iter()
+            .scan(0, |offset, part| {
+                *offset += part.len() + 1;
+                Some(*offset - 1)
+            })
+            .find(|&i| text[i..].starts_with("->"))
+            .unwrap_or(text.len());
+        let return_annotation = text
+            .get(close..)
+            .and_then(|rest| rest.strip_prefix("->"))
+            .map(|anno| anno.trim().to_string());
+        let params = text[..close]
+            .trim()
+            .strip_prefix('(')
+            .and_then(|p| p.strip_suffix(')'))
+            .ok_or_else(invalid)?;
+
+        let mut parameters: Vec<Parameter> = Vec::new();
+        let mut keyword_only = false;
+        for item in split_top_level(params, ',') {
+            let item = item.trim();
+            match item {
+                "" if params.trim().is_empty() => continue,
+                "" => return Err(invalid()),
+                "/" => {
+                    for param in &mut parameters {
+                        param.kind = ParameterKind::PositionalOnly;
+                    }
+                    continue;
+                }
+                "*" => {
+                    keyword_only = true;
+                    continue;
+                }
+                _ => {}
+            }
+            let (kind, rest) = if let Some(rest) = item.strip_prefix("**") {
+                (ParameterKind::VarKeyword, rest)
+            } else if let Some(rest) = item.strip_prefix('*') {
+                keyword_only = true;
+                (ParameterKind::VarPositional, rest)
+            } else if keyword_only {
+                (ParameterKind::KeywordOnly, item)
+            } else {
+                (ParameterKind::PositionalOrKeyword, item)
+            };
+            let (name, annotation, default) = split_parameter(rest);
+            if name.is_empty() || !name.chars().all(|c| c == '_' || c.is_alphanumeric()) {
+                return Err(invalid());
+            }
+            parameters.push(Parameter {
+                name: name.to_string(),
+                kind,
+                default: default.map(str::to_string),
+                annotation: annotation.map(str::to_string),
+            });
+        }
+        Signature::new(parameters, return_annotation)
+    }
+
+    /// The parameter called `name`, as `signature.parameters[name]`.
+    pub fn parameter(&self, name: &str) -> Option<&Parameter> {
+        self.parameters.iter().find(|p| p.name == name)
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        let mut pos_only_separator = false;
+        let mut kw_only_separator = true;
+        for param in &self.parameters {
+            if param.kind == ParameterKind::PositionalOnly {
+                pos_only_separator = true;
+            } else if pos_only_separator {
+                parts.push("/".to_string());
+                pos_only_separator = false;
+            }
+            if param.kind == ParameterKind::VarPositional {
+                kw_only_separator = false;
+            } else if param.kind == ParameterKind::KeywordOnly && kw_only_separator {
+                parts.push("*".to_string());
+                kw_only_separator = false;
+            }
+            parts.push(param.to_string());
+        }
+        if pos_only_separator {
+            parts.push("/".to_string());
+        }
+        write!(f, "({})", parts.join(", "))?;
+        if let Some(annotation) = &self.return_annotation {
+            write!(f, " -> {}", annotation)?;
+        }
+        Ok(())
+    }
+}
+
+/// What the compiler records about a function, keyed by qualified name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionInfo {
+    pub qualname: String,
+    pub module: String,
+    pub filename: String,
+    pub firstlineno: u32,
+    pub signature: Signature,
+}
+
+static FUNCTIONS: Lock<BTreeMap<String, FunctionInfo>> = Lock::new(BTreeMap::new());
+
+/// Records a compiled function so signature() and getsourcefile() can
+/// find it; registering a qualified name again replaces the entry.
+pub fn register(info: FunctionInfo) {
+    FUNCTIONS.lock().insert(info.qualname.clone(), info);
+}
+
+/// The registration of `qualname`, if any; functions declared with
+/// [`python_function!`](crate::python_function) are found by name or
+/// `module::name` path without registering them here.
+pub fn function_info(qualname: &str) -> Option<FunctionInfo> {
+    if let Some(info) = FUNCTIONS.lock().get(qualname) {
+        return Some(info.clone());
+    }
+    let entry = function::find_function(qualname)?;
+    Some(FunctionInfo {
+        qualname: entry.name.to_string(),
+        module: entry.module.replace("::", "."),
+        filename: entry.file.to_string(),
+        firstlineno: entry.line,
+        signature: Signature::parse(&entry.signature()).ok()?,
+    })
+}
+
+/// Python-equivalent inspect.signature(func), by qualified name. Raises
+/// ValueError for a function that was not registered, as CPython does for
+/// callables without signature metadata.
+pub fn signature(qualname: &str) -> PyResult<Signature> {
+    function_info(qualname)
+        .map(|info| info.signature)
+        .ok_or_else(|| PyException::value_error(format!("no signature found for {}", qualname)))
+}
+
+/// Python-equivalent inspect.signature(func) for a function value, looked
+/// up under its `__name__`.
+pub fn signature_of(func: &PyFunction) -> PyResult<Signature> {
+    signature(func.name())
+}
+
+/// Python-equivalent inspect.getsourcefile(object), by qualified name.
+pub fn getsourcefile(qualname: &str) -> Option<String> {
+    function_info(qualname).map(|info| info.filename)
+}
+
+/// Python-equivalent inspect.getfile(object): TypeError, as for a
+/// built-in, when the function was not registered.
+pub fn getfile(qualname: &str) -> PyResult<String> {
+    getsourcefile(qualname)
+        .ok_or_else(|| PyException::type_error(format!("{} is a built-in function", qualname)))
+}
+
+/// Python-equivalent code object, as far as frames expose it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Code {
+    pub co_name: &'static str,
+    pub co_filename: &'static str,
+    pub co_firstlineno: u32,
+}
+
+/// Python-equivalent frame object: a snapshot of one call on the stack.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Frame {
+    pub f_code: Code,
+    pub f_lineno: u32,
+    /// The calling frame.
+    pub f_back: Option<Box<Frame>>,
+}
+
+/// Python-equivalent inspect.FrameInfo.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FrameInfo {
+    pub frame: Frame,
+    pub filename: &'static str,
+    pub lineno: u32,
+    pub function: &'static str,
+}
+
+thread_local! {
+    /// The calls in progress on this thread, outermost first.
+    static FRAMES: RefCell<Vec<(Code, u32)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pops the frame [`enter_frame`] pushed when dropped, including while
+/// unwinding from a raised exception.
+#[must_use = "the frame is popped when the guard drops"]
+pub struct FrameGuard {
+    depth: usize,
+}
+
+impl Drop for FrameGuard {
+    fn drop(&mut self) {
+        sys::call_hooks(TraceEvent::Return);
+        FRAMES.with(|frames| frames.borrow_mut().truncate(self.depth));
+    }
+}
+
+/// Pushes a frame for a call to `name`, defined at `filename:firstlineno`,
+/// until the returned guard drops.
+pub fn enter_frame(name: &'static str, filename: &'static str, firstlineno: u32) -> FrameGuard {
+    let code = Code {
+        co_name: name,
+        co_filename: filename,
+        co_firstlineno: firstlineno,
+    };
+    let guard = FRAMES.with(|frames| {
+        let mut frames = frames.borrow_mut();
+        frames.push((code, firstlineno));
+        FrameGuard {
+            depth: frames.len() - 1,
+        }
+    });
+    sys::call_hooks(TraceEvent::Call);
+    guard
+}
+
+/// Sets the line the innermost frame is executing, its f_lineno.
+pub fn set_lineno(lineno: u32) {
+    FRAMES.with(|frames| {
+        if let Some(top) = frames.borrow_mut().last_mut() {
+            top.1 = lineno;
+        }
+    });
+    sys::call_hooks(TraceEvent::Line);
+}
+
+/// The frame `depth` calls up from the innermost one, with its callers
+/// linked through f_back.
+pub(crate) fn frame_at(depth: usize) -> Option<Frame> {
+    FRAMES.with(|frames| {
+        let frames = frames.borrow();
+        let end = frames.len().checked_sub(depth)?;
+        frames[..end].iter().fold(None, |back, &(code, lineno)| {
+            Some(Frame {
+                f_code: code,
+                f_lineno: lineno,
+                f_back: back.map(Box::new),
+            })
+        })
+    })
+}
+
+/// Python-equivalent inspect.currentframe(): None outside any registered
+/// call, which CPython also allows for implementations without frames.
+pub fn currentframe() -> Option<Frame> {
+    frame_at(0)
+}
+
+/// Python-equivalent inspect.stack(): the caller's frame and all frames
+/// above it, innermost first.
+pub fn stack() -> Vec<FrameInfo> {
+    let mut out = Vec::new();
+    let mut frame = currentframe();
+    while let Some(current) = frame {
+        frame = current.f_back.as_deref().cloned();
+        out.push(FrameInfo {
+            filename: current.f_code.co_filename,
+            lineno: current.f_lineno,
+            function: current.f_code.co_name,
+            frame: current,
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::python_function! {
+        fn clamp(value: i64, low: i64 = 0, *, high: i64 = 255) -> i64 {
+            value.max(low).min(high)
+        }
+    }
+
+    #[test]
+    fn signatures_parse_and_print_like_cpython() {
+        for text in [
+            "()",
+            "(a, b=1, *args, c, d: int = 2, **kwargs) -> str",
+            "(x, /, y, *, z='a, b')",
+            "(p: dict[str, int] = {'k': (1, 2)}, q=a == b)",
+        ] {
+            assert_eq!(Signature::parse(text).unwrap().to_string(), text);
+        }
+        let sig = Signature::parse("(x, /, y=0, *, z)").unwrap();
+        assert_eq!(
+            sig.parameter("x").unwrap().kind,
+            ParameterKind::PositionalOnly
+        );
+        assert_eq!(sig.parameter("y").unwrap().default.as_deref(), Some("0"));
+        assert_eq!(sig.parameter("z").unwrap().kind.to_string(), "KEYWORD_ONLY");
+
+        let err = Signature::parse("(a=1, b)").unwrap_err();
+        assert_eq!(err.message, "non-default argument follows default argument");
+        assert!(Signature::parse("(a, a)").is_err());
+        assert!(Signature::parse("a, b").is_err());
+        let params = vec![
+            Parameter::new("k", ParameterKind::KeywordOnly),
+            Parameter::new("p", ParameterKind::PositionalOrKeyword),
+        ];
+        assert_eq!(
+            Signature::new(params, None).unwrap_err().message,
+            "wrong parameter order: keyword-only parameter before positional or keyword parameter"
+        );
+    }
+
+    #[test]
+    fn registry_backs_signature_and_source_file() {
+        register(FunctionInfo {
+            qualname: "inspect_test.greet".to_string(),
+            module: "inspect_test".to_string(),
+            filename: "app/greet.py".to_string(),
+            firstlineno: 3,
+            signature: Signature::parse("(name: str, punct='!') -> str").unwrap(),
+        });
+        let sig = signature("inspect_test.greet").unwrap();
+        assert_eq!(sig.to_string(), "(name: str, punct='!') -> str");
+        assert_eq!(
+            getsourcefile("inspect_test.greet").as_deref(),
+            Some("app/greet.py")
+        );
+        assert!(signature("inspect_test.missing").is_err());
+        assert!(getfile("len").unwrap_err().is("TypeError"));
+    }
+
+    fn helper() -> (Vec<FrameInfo>, String) {
+        let _frame = enter_frame("helper", "app/util.py", 10);
+        set_lineno(12);
+        (
+            stack(),
+            sys::_getframe(1).unwrap().f_code.co_name.to_string(),
+        )
+    }
+
+    #[test]
+    fn frames_follow_calls() {
+        assert!(currentframe().is_none());
+        assert!(sys::_getframe(0).is_err());
+        {
+            let _frame = enter_frame("main", "app/main.py", 1);
+            set_lineno(5);
+            let (stack, caller) = helper();
+            assert_eq!(caller, "main");
+            let calls: Vec<_> = stack
+                .iter()
+                .map(|f| (f.function, f.filename, f.lineno))
+                .collect();
+            assert_eq!(
+                calls,
+                [("helper", "app/util.py", 12), ("main", "app/main.py", 5)]
+            );
+            assert_eq!(sys::_getframe(0).unwrap().f_code.co_name, "main");
+        }
+        assert!(stack().is_empty());
+    }
+
+    #[test]
+    fn python_functions_are_found_without_registering() {
+        assert_eq!(clamp::call1(300), 255);
+        assert_eq!(
+            signature("clamp").unwrap().to_string(),
+            "(value, low=0, *, high=255)"
+        );
+        let path = format!("{}::clamp", module_path!());
+        let info = function_info(&path).unwrap();
+        assert_eq!(info.module, "stdpython.stdlib.inspect.tests");
+        assert_eq!(getsourcefile("clamp").as_deref(), Some(file!()));
+    }
+}
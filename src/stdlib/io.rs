@@ -0,0 +1,1019 @@
+//! Python-equivalent io module: open(), in-memory streams and the file-like
+//! protocol.
+
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, Cursor, IsTerminal, Read, Seek, SeekFrom, Write};
+use std::iter::FusedIterator;
+use std::rc::Rc;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::os::PathLike;
+use crate::types::{PyKwargs, PyValue};
+
+pub const SEEK_SET: i32 = 0;
+pub const SEEK_CUR: i32 = 1;
+pub const SEEK_END: i32 = 2;
+
+/// Default buffer size, as in io.DEFAULT_BUFFER_SIZE.
+pub const DEFAULT_BUFFER_SIZE: usize = 8192;
+
+/// Converts Python's (offset, whence) pair into a SeekFrom.
+pub fn seek_from(offset: i64, whence: i32) -> PyResult<SeekFrom> {
+    match whence {
+        SEEK_SET if offset < 0 => Err(PyException::value_error(format!(
+            "negative seek position {}",
+            offset
+        ))),
+        SEEK_SET => Ok(SeekFrom::Start(offset as u64)),
+        SEEK_CUR => Ok(SeekFrom::Current(offset)),
+        SEEK_END => Ok(SeekFrom::End(offset)),
+        _ => Err(PyException::value_error(format!(
+            "invalid whence ({}, should be 0, 1 or 2)",
+            whence
+        ))),
+    }
+}
+
+/// The file object protocol shared by real files, temporary files and in-memory streams.
+///
+/// Anything that reads or writes "a file" (json.dump, csv writers, ...) accepts `impl FileLike`.
+pub trait FileLike: Read + Write + Seek {
+    /// Python-equivalent f.tell().
+    fn tell(&mut self) -> PyResult<u64> {
+        Ok(self.stream_position()?)
+    }
+
+    /// Python-equivalent f.seek(offset, whence); returns the new absolute position.
+    fn seek_whence(&mut self, offset: i64, whence: i32) -> PyResult<u64> {
+        Ok(self.seek(seek_from(offset, whence)?)?)
+    }
+
+    /// Python-equivalent f.truncate(size): resizes to `size` (default: the current position).
+    fn truncate(&mut self, size: Option<u64>) -> PyResult<u64>;
+
+    /// Python-equivalent f.closed.
+    fn closed(&self) -> bool {
+        false
+    }
+
+    /// Python-equivalent f.name, when the stream has one.
+    fn name(&self) -> Option<String> {
+        None
+    }
+
+    /// Writes a str as UTF-8; returns the number of characters written.
+    fn write_str(&mut self, s: &str) -> PyResult<usize> {
+        self.write_all(s.as_bytes())?;
+        Ok(s.chars().count())
+    }
+
+    /// Python-equivalent f.readinto(b): fills `buf` from the stream, e.g. a
+    /// [`PyMemoryView::as_mut_slice`](crate::types::PyMemoryView::as_mut_slice),
+    /// and returns the count read, which is short only at end of stream.
+    fn readinto(&mut self, buf: &mut [u8]) -> PyResult<usize> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(filled)
+    }
+
+    /// Reads the rest of the stream as a UTF-8 str.
+    fn read_str(&mut self) -> PyResult<String> {
+        let mut data = Vec::new();
+        self.read_to_end(&mut data)?;
+        String::from_utf8(data).map_err(|e| {
+            PyException::new(
+                "UnicodeDecodeError",
+                format!("'utf-8' codec can't decode: {}", e),
+            )
+        })
+    }
+}
+
+impl FileLike for File {
+    fn truncate(&mut self, size: Option<u64>) -> PyResult<u64> {
+        let size = match size {
+            Some(size) => size,
+            None => self.stream_position()?,
+        };
+        self.set_len(size)?;
+        Ok(size)
+    }
+}
+
+/// Python-equivalent io.BytesIO.
+#[derive(Debug, Clone, Default)]
+pub struct BytesIO {
+    inner: Cursor<Vec<u8>>,
+    closed: bool,
+}
+
+impl BytesIO {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// BytesIO(initial_bytes), positioned at the start.
+    pub fn with_bytes(data: &[u8]) -> Self {
+        BytesIO {
+            inner: Cursor::new(data.to_vec()),
+            closed: false,
+        }
+    }
+
+    /// Python-equivalent BytesIO.getvalue().
+    pub fn getvalue(&self) -> &[u8] {
+        self.inner.get_ref()
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.inner.into_inner()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.get_ref().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.get_ref().is_empty()
+    }
+
+    pub fn close(&mut self) {
+        self.closed = true;
+    }
+
+    fn check_open(&self) -> io::Result<()> {
+        if self.closed {
+            Err(io::Error::other("I/O operation on closed file."))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Read for BytesIO {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.check_open()?;
+        self.inner.read(buf)
+    }
+}
+
+impl Write for BytesIO {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.check_open()?;
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.check_open()
+    }
+}
+
+impl Seek for BytesIO {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.check_open()?;
+        self.inner.seek(pos)
+    }
+}
+
+impl FileLike for BytesIO {
+    fn truncate(&mut self, size: Option<u64>) -> PyResult<u64> {
+        if self.closed {
+            return Err(PyException::value_error("I/O operation on closed file."));
+        }
+        let size = size.unwrap_or(self.inner.position());
+        let len = (size as usize).min(self.len());
+        self.inner.get_mut().truncate(len);
+        Ok(size)
+    }
+
+    fn closed(&self) -> bool {
+        self.closed
+    }
+}
+
+/// Python-equivalent open(file, mode): a file object over one OS handle,
+/// buffered unless opened with `buffering=0`.
+pub fn open<P: PathLike>(file: P, mode: &str) -> PyResult<PyFile> {
+    PyFile::open(file, mode, PyKwargs::new())
+}
+
+/// Python-equivalent open(file, mode, buffering=..., encoding=...,
+/// newline=...). Only UTF-8 text is supported.
+pub fn open_with<P: PathLike>(file: P, mode: &str, kwargs: PyKwargs) -> PyResult<PyFile> {
+    PyFile::open(file, mode, kwargs.for_call("open"))
+}
+
+/// A file opened by [`open`]. Reads and writes share a single handle, so
+/// "r+", "w+" and "a+" streams can interleave them: pending writes are
+/// flushed before reading and read-ahead is given back before writing.
+#[derive(Debug)]
+pub struct PyFile {
+    file: File,
+    name: String,
+    mode: String,
+    readable: bool,
+    writable: bool,
+    binary: bool,
+    newline: Newline,
+    /// 0 for unbuffered binary I/O.
+    buffer_size: usize,
+    line_buffering: bool,
+    rbuf: Vec<u8>,
+    rpos: usize,
+    wbuf: Vec<u8>,
+    closed: bool,
+}
+
+impl PyFile {
+    fn open<P: PathLike>(file: P, mode: &str, mut kwargs: PyKwargs) -> PyResult<PyFile> {
+        let buffering = kwargs.get_int("buffering")?.unwrap_or(-1);
+        let encoding = kwargs.get_str("encoding")?;
+        let newline = match kwargs.pop("newline") {
+            None | Some(PyValue::None) => None,
+            Some(PyValue::Str(s)) => Some(s.into_string()),
+            Some(other) => return Err(kwargs.type_error("newline", "str or None", &other)),
+        };
+        kwargs.reject_unknown()?;
+
+        let invalid = || PyException::value_error(format!("invalid mode: '{}'", mode));
+        let mut seen = String::new();
+        for c in mode.chars() {
+            if !"rwxabt+".contains(c) || seen.contains(c) {
+                return Err(invalid());
+            }
+            seen.push(c);
+        }
+        let has = |c| seen.contains(c);
+        let binary = has('b');
+        if binary && has('t') {
+            return Err(PyException::value_error(
+                "can't have text and binary mode at once",
+            ));
+        }
+        if seen.chars().filter(|c| "rwxa".contains(*c)).count() != 1 {
+            return Err(PyException::value_error(
+                "must have exactly one of create/read/write/append mode",
+            ));
+        }
+        if binary && encoding.is_some() {
+            return Err(PyException::value_error(
+                "binary mode doesn't take an encoding argument",
+            ));
+        }
+        if binary && newline.is_some() {
+            return Err(PyException::value_error(
+                "binary mode doesn't take a newline argument",
+            ));
+        }
+        if !binary && buffering == 0 {
+            return Err(PyException::value_error("can't have unbuffered text I/O"));
+        }
+        if let Some(encoding) = encoding {
+            let normalized = encoding.to_ascii_lowercase().replace(['-', '_'], "");
+            if normalized != "utf8" {
+                return Err(PyException::new(
+                    "LookupError",
+                    format!("unknown encoding: {}", encoding),
+                ));
+            }
+        }
+        let newline = Newline::from_arg(newline.as_deref())?;
+
+        let plus = has('+');
+        let mut options = OpenOptions::new();
+        options.read(has('r') || plus);
+        if has('w') {
+            options.write(true).create(true).truncate(true);
+        } else if has('x') {
+            options.write(true).create_new(true);
+        } else if has('a') {
+            options.append(true).create(true);
+        } else if plus {
+            options.write(true);
+        }
+        let name = file.fspath();
+        let handle = options.open(&name)?;
+
+        let tty = handle.is_terminal();
+        let line_buffering = !binary && (buffering == 1 || (buffering < 0 && tty));
+        let buffer_size = match buffering {
+            0 => 0,
+            n if n > 1 => n as usize,
+            _ => DEFAULT_BUFFER_SIZE,
+        };
+        Ok(PyFile {
+            file: handle,
+            name,
+            mode: mode.to_string(),
+            readable: has('r') || plus,
+            writable: !has('r') || plus,
+            binary,
+            newline,
+            buffer_size,
+            line_buffering,
+            rbuf: Vec::new(),
+            rpos: 0,
+            wbuf: Vec::new(),
+            closed: false,
+        })
+    }
+
+    /// Python-equivalent f.mode, as passed to open().
+    pub fn mode(&self) -> &str {
+        &self.mode
+    }
+
+    pub fn readable(&self) -> bool {
+        self.readable
+    }
+
+    pub fn writable(&self) -> bool {
+        self.writable
+    }
+
+    /// Python-equivalent f.line_buffering: text streams flush on every
+    /// newline when opened with `buffering=1` or attached to a terminal.
+    pub fn line_buffering(&self) -> bool {
+        self.line_buffering
+    }
+
+    /// Python-equivalent f.isatty().
+    pub fn isatty(&self) -> PyResult<bool> {
+        self.check_closed()?;
+        Ok(self.file.is_terminal())
+    }
+
+    /// Python-equivalent f.fileno().
+    #[cfg(unix)]
+    pub fn fileno(&self) -> PyResult<i32> {
+        use std::os::unix::io::AsRawFd;
+        self.check_closed()?;
+        Ok(self.file.as_raw_fd())
+    }
+
+    /// Python-equivalent f.fileno().
+    #[cfg(not(unix))]
+    pub fn fileno(&self) -> PyResult<i32> {
+        self.check_closed()?;
+        Err(PyException::os_error(
+            "fileno() is not supported on this platform",
+        ))
+    }
+
+    /// Python-equivalent f.readline(size) on a text stream.
+    pub fn readline(&mut self, size: Option<usize>) -> PyResult<String> {
+        self.check_closed()?;
+        let newline = self.newline;
+        readline_with(self, newline, size)
+    }
+
+    /// `for line in f:`, split as the stream's `newline=` says.
+    pub fn iter_lines(&mut self) -> Lines<&mut PyFile> {
+        let newline = self.newline;
+        Lines::with_newline(self, newline)
+    }
+
+    /// Python-equivalent f.close(): flushes, then refuses further I/O.
+    /// Closing twice is allowed.
+    pub fn close(&mut self) -> PyResult<()> {
+        if self.closed {
+            return Ok(());
+        }
+        let flushed = self.flush_writes();
+        self.closed = true;
+        Ok(flushed?)
+    }
+
+    fn check_closed(&self) -> PyResult<()> {
+        if self.closed {
+            Err(PyException::value_error("I/O operation on closed file."))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn check_io(&self, allowed: bool, what: &str) -> io::Result<()> {
+        if self.closed {
+            Err(io::Error::other("I/O operation on closed file."))
+        } else if !allowed {
+            Err(io::Error::other(format!("not {}", what)))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn flush_writes(&mut self) -> io::Result<()> {
+        if !self.wbuf.is_empty() {
+            self.file.write_all(&self.wbuf)?;
+            self.wbuf.clear();
+        }
+        Ok(())
+    }
+
+    /// Gives read-ahead back to the OS position so a write or seek lands
+    /// where the caller has read up to.
+    fn drop_read_ahead(&mut self) -> io::Result<()> {
+        let unread = (self.rbuf.len() - self.rpos) as i64;
+        self.rbuf.clear();
+        self.rpos = 0;
+        if unread > 0 {
+            self.file.seek(SeekFrom::Current(-unread))?;
+        }
+        Ok(())
+    }
+}
+
+impl Read for PyFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.check_io(self.readable, "readable")?;
+        if self.rpos == self.rbuf.len() && buf.len() >= self.buffer_size {
+            self.flush_writes()?;
+            return self.file.read(buf);
+        }
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl BufRead for PyFile {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.check_io(self.readable, "readable")?;
+        if self.rpos == self.rbuf.len() {
+            self.flush_writes()?;
+            self.rbuf.resize(self.buffer_size.max(1), 0);
+            self.rpos = 0;
+            match self.file.read(&mut self.rbuf) {
+                Ok(n) => self.rbuf.truncate(n),
+                Err(e) => {
+                    self.rbuf.clear();
+                    return Err(e);
+                }
+            }
+        }
+        Ok(&self.rbuf[self.rpos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.rpos = (self.rpos + amt).min(self.rbuf.len());
+    }
+}
+
+impl Write for PyFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.check_io(self.writable, "writable")?;
+        self.drop_read_ahead()?;
+        if self.buffer_size == 0 {
+            return self.file.write(buf);
+        }
+        self.wbuf.extend_from_slice(buf);
+        if self.wbuf.len() >= self.buffer_size {
+            self.flush_writes()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.check_io(true, "")?;
+        self.flush_writes()?;
+        self.file.flush()
+    }
+}
+
+impl Seek for PyFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.check_io(true, "")?;
+        self.flush_writes()?;
+        let unread = (self.rbuf.len() - self.rpos) as i64;
+        self.rbuf.clear();
+        self.rpos = 0;
+        let pos = match pos {
+            SeekFrom::Current(offset) => SeekFrom::Current(offset - unread),
+            pos => pos,
+        };
+        self.file.seek(pos)
+    }
+}
+
+impl FileLike for PyFile {
+    fn truncate(&mut self, size: Option<u64>) -> PyResult<u64> {
+        self.check_closed()?;
+        let size = match size {
+            Some(size) => {
+                self.flush_writes()?;
+                size
+            }
+            None => self.stream_position()?,
+        };
+        self.file.set_len(size)?;
+        Ok(size)
+    }
+
+    fn closed(&self) -> bool {
+        self.closed
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.name.clone())
+    }
+
+    /// Text streams translate "\n" per `newline=` and flush at a line end
+    /// when line buffered.
+    fn write_str(&mut self, s: &str) -> PyResult<usize> {
+        let encoded = if self.binary {
+            Cow::Borrowed(s)
+        } else {
+            self.newline.encode(s)
+        };
+        self.write_all(encoded.as_bytes())?;
+        if self.line_buffering && s.contains(['\n', '\r']) {
+            self.flush()?;
+        }
+        Ok(s.chars().count())
+    }
+
+    /// With `newline=None`, "\r\n" and "\r" read as "\n".
+    fn read_str(&mut self) -> PyResult<String> {
+        let mut data = Vec::new();
+        self.read_to_end(&mut data)?;
+        let text = String::from_utf8(data).map_err(|e| {
+            PyException::new(
+                "UnicodeDecodeError",
+                format!("'utf-8' codec can't decode: {}", e),
+            )
+        })?;
+        if self.newline == Newline::Universal && text.contains('\r') {
+            Ok(text.replace("\r\n", "\n").replace('\r', "\n"))
+        } else {
+            Ok(text)
+        }
+    }
+}
+
+impl Drop for PyFile {
+    fn drop(&mut self) {
+        let _ = self.flush_writes();
+    }
+}
+
+/// The `newline=` argument of a text stream, which decides both what ends a
+/// line on read and how "\n" is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Newline {
+    /// `newline=None`: "\n", "\r" and "\r\n" all end a line and read as
+    /// "\n"; "\n" is written as the platform line separator.
+    #[default]
+    Universal,
+    /// `newline=''`: any of the three ends a line but is returned as is.
+    Untranslated,
+    /// `newline='\n'`.
+    Lf,
+    /// `newline='\r'`: only "\r" ends a line, and "\n" is written as "\r".
+    Cr,
+    /// `newline='\r\n'`.
+    CrLf,
+}
+
+impl Newline {
+    /// Parses Python's `newline=` argument, raising ValueError like open().
+    pub fn from_arg(newline: Option<&str>) -> PyResult<Newline> {
+        match newline {
+            None => Ok(Newline::Universal),
+            Some("") => Ok(Newline::Untranslated),
+            Some("\n") => Ok(Newline::Lf),
+            Some("\r") => Ok(Newline::Cr),
+            Some("\r\n") => Ok(Newline::CrLf),
+            Some(other) => Err(PyException::value_error(format!(
+                "illegal newline value: {}",
+                other
+            ))),
+        }
+    }
+
+    /// Translates the "\n"s of text about to be written.
+    pub fn encode<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        let sep = match self {
+            Newline::Universal if cfg!(windows) => "\r\n",
+            Newline::Cr => "\r",
+            Newline::CrLf => "\r\n",
+            _ => return Cow::Borrowed(s),
+        };
+        if s.contains('\n') {
+            Cow::Owned(s.replace('\n', sep))
+        } else {
+            Cow::Borrowed(s)
+        }
+    }
+}
+
+/// Python-equivalent f.readline() on a text stream: the next line with its
+/// "\n", or "" at end of input.
+pub fn readline<R: BufRead + ?Sized>(reader: &mut R) -> PyResult<String> {
+    readline_with(reader, Newline::Lf, None)
+}
+
+fn peek<R: BufRead + ?Sized>(reader: &mut R) -> io::Result<Option<u8>> {
+    loop {
+        match reader.fill_buf() {
+            Ok(buf) => return Ok(buf.first().copied()),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Python-equivalent f.readline(size) on a text stream opened with
+/// `newline`: at most `size` characters, stopping after the line ending.
+pub fn readline_with<R: BufRead + ?Sized>(
+    reader: &mut R,
+    newline: Newline,
+    size: Option<usize>,
+) -> PyResult<String> {
+    let mut line = Vec::new();
+    let mut chars = 0;
+    let room = |chars: usize| size.is_none_or(|n| chars < n);
+    while let Some(b) = peek(reader)? {
+        // UTF-8 continuation bytes belong to the character already counted.
+        if b & 0xC0 != 0x80 {
+            if !room(chars) {
+                break;
+            }
+            chars += 1;
+        }
+        reader.consume(1);
+        line.push(b);
+        match (newline, b) {
+            (Newline::Universal | Newline::Untranslated | Newline::Lf, b'\n') => break,
+            (Newline::Cr, b'\r') => break,
+            (Newline::CrLf, b'\n') if line.ends_with(b"\r\n") => break,
+            (Newline::Universal, b'\r') => {
+                if peek(reader)? == Some(b'\n') {
+                    reader.consume(1);
+                }
+                *line.last_mut().unwrap() = b'\n';
+                break;
+            }
+            (Newline::Untranslated, b'\r') => {
+                if room(chars) && peek(reader)? == Some(b'\n') {
+                    reader.consume(1);
+                    line.push(b'\n');
+                }
+                break;
+            }
+            _ => {}
+        }
+    }
+    String::from_utf8(line).map_err(|e| {
+        PyException::new(
+            "UnicodeDecodeError",
+            format!("'utf-8' codec can't decode: {}", e),
+        )
+    })
+}
+
+/// `for line in f:` over a text stream. Lines keep their line ending; the
+/// last one may lack it. Iteration stops after the first error.
+pub struct Lines<R> {
+    reader: R,
+    newline: Newline,
+    done: bool,
+}
+
+impl<R: BufRead> Lines<R> {
+    /// Lines ending in "\n", as with `newline='\n'`.
+    pub fn new(reader: R) -> Self {
+        Self::with_newline(reader, Newline::Lf)
+    }
+
+    /// Lines split and translated as a stream opened with `newline`.
+    pub fn with_newline(reader: R, newline: Newline) -> Self {
+        Lines {
+            reader,
+            newline,
+            done: false,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = PyResult<String>;
+
+    fn next(&mut self) -> Option<PyResult<String>> {
+        if self.done {
+            return None;
+        }
+        match readline_with(&mut self.reader, self.newline, None) {
+            Ok(line) if line.is_empty() => {
+                self.done = true;
+                None
+            }
+            Ok(line) => Some(Ok(line)),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+impl<R: BufRead> FusedIterator for Lines<R> {}
+
+/// input(prompt) over any streams: writes and flushes the prompt, then reads
+/// one line without its line ending. End of input raises EOFError.
+pub fn input_from<R, W>(prompt: &str, reader: &mut R, out: &mut W) -> PyResult<String>
+where
+    R: BufRead + ?Sized,
+    W: Write + ?Sized,
+{
+    out.write_all(prompt.as_bytes())?;
+    out.flush()?;
+    let mut line = readline(reader)?;
+    if line.is_empty() {
+        return Err(PyException::eof_error("EOF when reading a line"));
+    }
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+    Ok(line)
+}
+
+thread_local! {
+    /// Writers standing in for the process stdout on this thread, innermost
+    /// last. Empty means sys.stdout is the real stdout.
+    static STDOUT: RefCell<Vec<Box<dyn fmt::Write>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Makes `writer` this thread's sys.stdout until the matching
+/// pop_stdout(); print() and sys.stdout.write() go to it. Writers must not
+/// print themselves.
+pub fn push_stdout(writer: Box<dyn fmt::Write>) {
+    STDOUT.with(|s| s.borrow_mut().push(writer));
+}
+
+/// Undoes the last push_stdout() on this thread, returning its writer, or
+/// None if stdout was not redirected.
+pub fn pop_stdout() -> Option<Box<dyn fmt::Write>> {
+    STDOUT.with(|s| s.borrow_mut().pop())
+}
+
+/// Python-equivalent `with contextlib.redirect_stdout(writer): f()`. The
+/// writer is popped again even if `f` panics. Other threads keep their
+/// own stdout.
+pub fn redirect_stdout<R, F: FnOnce() -> R>(writer: Box<dyn fmt::Write>, f: F) -> R {
+    struct Guard;
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            pop_stdout();
+        }
+    }
+
+    push_stdout(writer);
+    let _guard = Guard;
+    f()
+}
+
+/// A writer that appends to a buffer its creator keeps a handle on.
+struct SharedBuffer(Rc<RefCell<String>>);
+
+impl fmt::Write for SharedBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.borrow_mut().push_str(s);
+        Ok(())
+    }
+}
+
+/// Runs `f` with this thread's stdout redirected to a buffer, like
+/// `redirect_stdout(io.StringIO())` or pytest's capsys, and returns f's
+/// result with everything it printed. Captures nest.
+pub fn capture<R, F: FnOnce() -> R>(f: F) -> (R, String) {
+    let buffer = Rc::new(RefCell::new(String::new()));
+    let result = redirect_stdout(Box::new(SharedBuffer(Rc::clone(&buffer))), f);
+    let text = buffer.take();
+    (result, text)
+}
+
+/// Writes `s` to this thread's sys.stdout: the innermost redirection, or
+/// the process stdout.
+pub(crate) fn write_stdout(s: &str) -> PyResult<()> {
+    let redirected = STDOUT.with(|stack| {
+        stack
+            .borrow_mut()
+            .last_mut()
+            .map(|writer| writer.write_str(s))
+    });
+    match redirected {
+        Some(Ok(())) => Ok(()),
+        Some(Err(fmt::Error)) => Err(PyException::os_error("write to sys.stdout failed")),
+        None => Ok(io::stdout().lock().write_all(s.as_bytes())?),
+    }
+}
+
+/// Python-equivalent sys.stdout.flush(). Redirected writers have nothing to
+/// flush.
+pub(crate) fn flush_stdout() -> PyResult<()> {
+    if STDOUT.with(|s| s.borrow().is_empty()) {
+        io::stdout().flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytesio_protocol() {
+        let mut buf = BytesIO::new();
+        assert_eq!(buf.write_str("hello world").unwrap(), 11);
+        assert_eq!(buf.tell().unwrap(), 11);
+        buf.seek_whence(-5, SEEK_END).unwrap();
+        assert_eq!(buf.read_str().unwrap(), "world");
+        buf.truncate(Some(5)).unwrap();
+        assert_eq!(buf.getvalue(), b"hello");
+        assert!(buf.seek_whence(0, 3).unwrap_err().is("ValueError"));
+        buf.close();
+        assert!(buf.closed());
+        assert!(buf.write_str("x").is_err());
+    }
+
+    #[test]
+    fn readinto_fills_a_memoryview() {
+        let mut stream = BytesIO::with_bytes(b"abcdef");
+        let mut view = crate::types::PyMemoryView::from(vec![0u8; 4]);
+        assert_eq!(stream.readinto(view.as_mut_slice().unwrap()).unwrap(), 4);
+        assert_eq!(view, *b"abcd".as_slice());
+        assert_eq!(stream.readinto(view.as_mut_slice().unwrap()).unwrap(), 2);
+        assert_eq!(view.tobytes().as_bytes(), b"efcd");
+    }
+
+    #[test]
+    fn lines_and_input() {
+        let mut lines = Lines::new(Cursor::new("one\ntwo\nlast"));
+        assert_eq!(lines.next().unwrap().unwrap(), "one\n");
+        assert_eq!(lines.next().unwrap().unwrap(), "two\n");
+        assert_eq!(lines.next().unwrap().unwrap(), "last");
+        assert!(lines.next().is_none());
+        let bad: Vec<_> = Lines::new(Cursor::new(b"\xff\n".to_vec())).collect();
+        assert!(bad[0].as_ref().unwrap_err().is("UnicodeDecodeError"));
+
+        let mut stdin = Cursor::new("alice\r\nbob");
+        let mut stdout = Vec::new();
+        assert_eq!(
+            input_from("name? ", &mut stdin, &mut stdout).unwrap(),
+            "alice"
+        );
+        assert_eq!(input_from("", &mut stdin, &mut stdout).unwrap(), "bob");
+        assert_eq!(stdout, b"name? ");
+        let eof = input_from("", &mut stdin, &mut stdout).unwrap_err();
+        assert!(eof.is("EOFError"));
+        assert_eq!(eof.message, "EOF when reading a line");
+    }
+
+    #[test]
+    fn newline_modes() {
+        let text = "a\r\nb\rc\nd";
+        let lines = |newline| -> Vec<String> {
+            Lines::with_newline(Cursor::new(text), newline)
+                .map(Result::unwrap)
+                .collect()
+        };
+        assert_eq!(lines(Newline::Universal), ["a\n", "b\n", "c\n", "d"]);
+        assert_eq!(lines(Newline::Untranslated), ["a\r\n", "b\r", "c\n", "d"]);
+        assert_eq!(lines(Newline::Lf), ["a\r\n", "b\rc\n", "d"]);
+        assert_eq!(lines(Newline::Cr), ["a\r", "\nb\r", "c\nd"]);
+        assert_eq!(lines(Newline::CrLf), ["a\r\n", "b\rc\nd"]);
+        assert!(Newline::from_arg(Some("x")).unwrap_err().is("ValueError"));
+        assert_eq!(Newline::from_arg(Some("")).unwrap(), Newline::Untranslated);
+        assert_eq!(Newline::Cr.encode("a\nb"), "a\rb");
+        assert_eq!(Newline::Lf.encode("a\nb"), "a\nb");
+
+        let mut f = Cursor::new("h\u{e9}llo\nworld\n");
+        assert_eq!(
+            readline_with(&mut f, Newline::Universal, Some(3)).unwrap(),
+            "h\u{e9}l"
+        );
+        assert_eq!(
+            readline_with(&mut f, Newline::Universal, Some(0)).unwrap(),
+            ""
+        );
+        assert_eq!(
+            readline_with(&mut f, Newline::Universal, None).unwrap(),
+            "lo\n"
+        );
+        assert_eq!(
+            readline_with(&mut f, Newline::Universal, Some(99)).unwrap(),
+            "world\n"
+        );
+        assert_eq!(readline(&mut f).unwrap(), "");
+    }
+
+    #[test]
+    fn open_modes_share_one_handle() {
+        let path = std::env::temp_dir().join(format!("stdpython-open-{}", std::process::id()));
+        let mut f = open(&path, "w+").unwrap();
+        assert_eq!(f.mode(), "w+");
+        assert!(f.readable() && f.writable());
+        f.write_str("one\ntwo\n").unwrap();
+        f.seek_whence(0, SEEK_SET).unwrap();
+        assert_eq!(f.readline(None).unwrap(), "one\n");
+        assert_eq!(f.tell().unwrap(), 4);
+        f.write_str("TWO").unwrap();
+        f.seek_whence(0, SEEK_SET).unwrap();
+        assert_eq!(f.read_str().unwrap(), "one\nTWO\n");
+        assert_eq!(f.truncate(Some(3)).unwrap(), 3);
+        assert!(f.fileno().unwrap_or(0) >= 0);
+        assert!(!f.isatty().unwrap());
+        f.close().unwrap();
+        assert!(f.closed());
+        assert!(f.readline(None).unwrap_err().is("ValueError"));
+
+        let mut f = open(&path, "a+").unwrap();
+        f.write_str("\r\nend\r").unwrap();
+        f.seek_whence(0, SEEK_SET).unwrap();
+        let lines: Vec<String> = f.iter_lines().map(Result::unwrap).collect();
+        assert_eq!(lines, ["one\n", "end\n"]);
+        drop(f);
+
+        let kwargs = PyKwargs::new().with("buffering", 0);
+        let mut f = open_with(&path, "rb", kwargs).unwrap();
+        assert!(!f.writable());
+        assert!(f.write_str("x").unwrap_err().is("OSError"));
+        let mut raw = [0u8; 16];
+        assert_eq!(f.readinto(&mut raw).unwrap(), 9);
+        assert_eq!(&raw[..9], b"one\r\nend\r");
+
+        let bad = |mode: &str, kwargs: PyKwargs| open_with(&path, mode, kwargs).unwrap_err();
+        assert_eq!(
+            bad("rw", PyKwargs::new()).message,
+            "must have exactly one of create/read/write/append mode"
+        );
+        assert_eq!(bad("rr", PyKwargs::new()).message, "invalid mode: 'rr'");
+        assert_eq!(
+            bad("r", PyKwargs::new().with("buffering", 0)).message,
+            "can't have unbuffered text I/O"
+        );
+        assert!(bad("rb", PyKwargs::new().with("newline", "")).is("ValueError"));
+        assert!(bad("r", PyKwargs::new().with("encoding", "latin-1")).is("LookupError"));
+        assert!(bad("x", PyKwargs::new()).is("FileExistsError"));
+        open_with(&path, "r", PyKwargs::new().with("encoding", "UTF-8")).unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn capture_nests_per_thread() {
+        let ((inner, other_thread), outer) = capture(|| {
+            crate::print("outer 1");
+            let inner = capture(|| crate::print(1.5));
+            crate::print("outer 2");
+            let other_thread = std::thread::spawn(|| pop_stdout().is_none()).join();
+            (inner.1, other_thread.unwrap())
+        });
+        assert_eq!(inner, "1.5\n");
+        assert_eq!(outer, "outer 1\nouter 2\n");
+        assert!(other_thread);
+        assert!(pop_stdout().is_none());
+        let panicked = std::panic::catch_unwind(|| capture(|| panic!("boom")));
+        assert!(panicked.is_err());
+        assert!(pop_stdout().is_none());
+    }
+
+    #[test]
+    fn redirect_stdout_to_any_writer() {
+        struct Upper(Rc<RefCell<String>>);
+
+        impl fmt::Write for Upper {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0.borrow_mut().push_str(&s.to_uppercase());
+                Ok(())
+            }
+        }
+
+        let seen = Rc::new(RefCell::new(String::new()));
+        redirect_stdout(Box::new(Upper(Rc::clone(&seen))), || {
+            crate::print("spam");
+            crate::stdlib::sys::stdout().write("eggs").unwrap();
+        });
+        assert_eq!(*seen.borrow(), "SPAM\nEGGS");
+
+        push_stdout(Box::new(String::new()));
+        write_stdout("kept").unwrap();
+        assert!(pop_stdout().is_some());
+        assert!(pop_stdout().is_none());
+    }
+}
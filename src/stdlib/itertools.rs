@@ -0,0 +1,266 @@
+//! Python-equivalent itertools module: lazy iterators over compiled sequences.
+//!
+//! Arithmetic goes through [`PyAdd`], so `count` and `accumulate` work for any
+//! type in the numeric tower (ints, floats, [`PyValue`](crate::types::PyValue)
+//! mixing the two) and raise what Python would on overflow or a bad operand.
+
+use core::iter::FusedIterator;
+
+use crate::exceptions::PyResult;
+use crate::types::PyAdd;
+
+/// Python-equivalent itertools.count(start, step).
+///
+/// Yields `Err` once if an addition fails (e.g. OverflowError), then stops.
+#[derive(Debug, Clone)]
+pub struct Count<T> {
+    /// The next value, or the error computing it raised.
+    next: Option<PyResult<T>>,
+    step: T,
+}
+
+/// Python-equivalent itertools.count(start, step).
+pub fn count<T: PyAdd + Clone>(start: T, step: T) -> Count<T> {
+    Count {
+        next: Some(Ok(start)),
+        step,
+    }
+}
+
+impl<T: PyAdd + Clone> Iterator for Count<T> {
+    type Item = PyResult<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next.take()?;
+        if let Ok(value) = &current {
+            self.next = Some(value.clone().py_add(self.step.clone()));
+        }
+        Some(current)
+    }
+}
+
+/// Python-equivalent itertools.accumulate(iterable, func, initial=initial).
+pub struct Accumulate<I: Iterator, F> {
+    iter: I,
+    func: F,
+    total: Option<I::Item>,
+    /// `initial` is yielded before anything is taken from `iter`.
+    pending: bool,
+    done: bool,
+}
+
+/// Python-equivalent itertools.accumulate(iterable): running sums.
+#[allow(clippy::type_complexity)]
+pub fn accumulate<I>(
+    iterable: I,
+) -> Accumulate<I::IntoIter, fn(I::Item, I::Item) -> PyResult<I::Item>>
+where
+    I: IntoIterator,
+    I::Item: PyAdd + Clone,
+{
+    accumulate_with(iterable, PyAdd::py_add, None)
+}
+
+/// Python-equivalent itertools.accumulate(iterable, func, initial=initial).
+///
+/// With `initial`, it is yielded first and the output is one longer than the
+/// input, as in Python 3.8+.
+pub fn accumulate_with<I, F>(
+    iterable: I,
+    func: F,
+    initial: Option<I::Item>,
+) -> Accumulate<I::IntoIter, F>
+where
+    I: IntoIterator,
+    I::Item: Clone,
+    F: FnMut(I::Item, I::Item) -> PyResult<I::Item>,
+{
+    Accumulate {
+        iter: iterable.into_iter(),
+        func,
+        pending: initial.is_some(),
+        total: initial,
+        done: false,
+    }
+}
+
+impl<I, F> Iterator for Accumulate<I, F>
+where
+    I: Iterator,
+    I::Item: Clone,
+    F: FnMut(I::Item, I::Item) -> PyResult<I::Item>,
+{
+    type Item = PyResult<I::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.pending {
+            self.pending = false;
+            return self.total.clone().map(Ok);
+        }
+        let item = self.iter.next()?;
+        let total = match self.total.take() {
+            Some(total) => match (self.func)(total, item) {
+                Ok(total) => total,
+                Err(err) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+            },
+            None => item,
+        };
+        self.total = Some(total.clone());
+        Some(Ok(total))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        let (low, high) = self.iter.size_hint();
+        let extra = usize::from(self.pending);
+        (
+            low.saturating_add(extra),
+            high.and_then(|h| h.checked_add(extra)),
+        )
+    }
+}
+
+/// Python-equivalent itertools.chain: each iterable in turn, pulled from
+/// `iterables` only when the previous one is exhausted.
+pub struct Chain<I: Iterator>
+where
+    I::Item: IntoIterator,
+{
+    iterables: I,
+    current: Option<<I::Item as IntoIterator>::IntoIter>,
+}
+
+/// Python-equivalent itertools.chain(*iterables).
+pub fn chain<I>(iterables: I) -> Chain<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+{
+    chain_from_iterable(iterables)
+}
+
+/// Python-equivalent itertools.chain.from_iterable(iterables). Lazy: an
+/// infinite `iterables` is fine, and each inner iterable is only started
+/// once reached.
+pub fn chain_from_iterable<I>(iterables: I) -> Chain<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: IntoIterator,
+{
+    Chain {
+        iterables: iterables.into_iter(),
+        current: None,
+    }
+}
+
+impl<I: Iterator> Iterator for Chain<I>
+where
+    I::Item: IntoIterator,
+{
+    type Item = <I::Item as IntoIterator>::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.current.as_mut().and_then(Iterator::next) {
+                return Some(item);
+            }
+            // Never poll an exhausted iterator again, which may not be fused.
+            self.current = None;
+            self.current = Some(self.iterables.next()?.into_iter());
+        }
+    }
+}
+
+impl<I: FusedIterator> FusedIterator for Chain<I> where I::Item: IntoIterator {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PyValue;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn count_steps_through_the_numeric_tower() {
+        let ints: Vec<i64> = count(10, -3).take(4).map(Result::unwrap).collect();
+        assert_eq!(ints, vec![10, 7, 4, 1]);
+        let floats: Vec<f64> = count(0.5, 0.25).take(3).map(Result::unwrap).collect();
+        assert_eq!(floats, vec![0.5, 0.75, 1.0]);
+        let mixed: Vec<PyValue> = count(PyValue::Int(1), PyValue::Float(0.5))
+            .take(3)
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(
+            mixed,
+            vec![PyValue::Int(1), PyValue::Float(1.5), PyValue::Float(2.0)]
+        );
+        let mut near_max = count(i64::MAX - 1, 1);
+        assert_eq!(near_max.next().unwrap().unwrap(), i64::MAX - 1);
+        assert_eq!(near_max.next().unwrap().unwrap(), i64::MAX);
+        assert!(near_max.next().unwrap().unwrap_err().is("OverflowError"));
+        assert!(near_max.next().is_none());
+        assert!(count(PyValue::Int(0), PyValue::from("a"))
+            .nth(1)
+            .unwrap()
+            .unwrap_err()
+            .is("TypeError"));
+    }
+
+    #[test]
+    fn accumulate_with_initial_and_func() {
+        let sums: PyResult<Vec<i64>> = accumulate([1, 2, 3, 4]).collect();
+        assert_eq!(sums.unwrap(), vec![1, 3, 6, 10]);
+        let with_initial: PyResult<Vec<i64>> =
+            accumulate_with([1, 2, 3], i64::py_add, Some(100)).collect();
+        assert_eq!(with_initial.unwrap(), vec![100, 101, 103, 106]);
+        let only_initial: PyResult<Vec<i64>> =
+            accumulate_with(Vec::new(), i64::py_add, Some(5)).collect();
+        assert_eq!(only_initial.unwrap(), vec![5]);
+        assert!(accumulate(Vec::<i64>::new()).next().is_none());
+        let maxes: PyResult<Vec<i32>> =
+            accumulate_with([3, 1, 4, 1, 5], |a, b| Ok(a.max(b)), None).collect();
+        assert_eq!(maxes.unwrap(), vec![3, 3, 4, 4, 5]);
+        assert_eq!(
+            accumulate_with([1, 2], i64::py_add, Some(0)).size_hint(),
+            (3, Some(3))
+        );
+
+        let mut bad = accumulate([PyValue::Int(1), PyValue::from("x"), PyValue::Int(2)]);
+        assert_eq!(bad.next().unwrap().unwrap(), PyValue::Int(1));
+        assert!(bad.next().unwrap().unwrap_err().is("TypeError"));
+        assert!(bad.next().is_none());
+    }
+
+    #[test]
+    fn chain_is_lazy() {
+        let flat: Vec<i32> = chain([vec![1, 2], vec![], vec![3]]).collect();
+        assert_eq!(flat, vec![1, 2, 3]);
+        // An infinite outer iterable only gets consumed as far as needed.
+        let mut started = 0;
+        let runs = (0..).map(|n| {
+            started += 1;
+            vec![n; n]
+        });
+        let first: Vec<usize> = chain_from_iterable(runs).take(4).collect();
+        assert_eq!(first, vec![1, 2, 2, 3]);
+        assert_eq!(started, 4);
+        // Stays exhausted even over an iterator that resumes after None.
+        let mut calls = 0;
+        let flaky = core::iter::from_fn(move || {
+            calls += 1;
+            (calls % 2 == 1).then_some(calls)
+        });
+        let mut chained = chain([flaky]);
+        assert_eq!(chained.next(), Some(1));
+        assert_eq!(chained.next(), None);
+        assert_eq!(chained.next(), None);
+    }
+}
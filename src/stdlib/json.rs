@@ -0,0 +1,1256 @@
+//! Python-equivalent json module: loads(), dumps() and JSONEncoder.
+//!
+//! Integers keep their exact value and objects keep their members in
+//! document order, so `dumps(loads(s))` round-trips like CPython's.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::repr::repr_float;
+use crate::types::{PyDictionary, PyKwargs, PyList, PySet, PyStr, PyTuple, PyValue};
+
+/// A decoded JSON document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JSONValue {
+    Null,
+    Bool(bool),
+    Int(i64),
+    /// An integer outside the i64 range, kept as its decimal digits so no
+    /// precision is lost.
+    BigInt(String),
+    Float(f64),
+    Str(String),
+    Array(Vec<JSONValue>),
+    /// Members in insertion order; a repeated key keeps its first position
+    /// and takes the last value, as a Python dict does.
+    Object(PyDictionary<String, JSONValue>),
+}
+
+impl JSONValue {
+    pub fn is_null(&self) -> bool {
+        matches!(self, JSONValue::Null)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JSONValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            JSONValue::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    /// The value of any number, rounding integers that do not fit an f64.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            JSONValue::Int(i) => Some(*i as f64),
+            JSONValue::BigInt(digits) => digits.parse().ok(),
+            JSONValue::Float(f) => Some(*f),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JSONValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JSONValue]> {
+        match self {
+            JSONValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&PyDictionary<String, JSONValue>> {
+        match self {
+            JSONValue::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// Looks up an object member.
+    pub fn get(&self, key: &str) -> Option<&JSONValue> {
+        self.as_object()?.get(key)
+    }
+
+    /// Converts a runtime value the way json.dumps() accepts it: dict keys
+    /// that are bool, int, float or None become strings, any other key or
+    /// value raises TypeError.
+    pub fn from_py(value: &PyValue) -> PyResult<JSONValue> {
+        Ok(match value {
+            PyValue::None => JSONValue::Null,
+            PyValue::Bool(b) => JSONValue::Bool(*b),
+            PyValue::Int(i) => JSONValue::Int(*i),
+            PyValue::Float(f) => JSONValue::Float(*f),
+            PyValue::Str(s) => JSONValue::Str(s.to_string()),
+            PyValue::List(items) => {
+                JSONValue::Array(items.iter().map(Self::from_py).collect::<PyResult<_>>()?)
+            }
+            PyValue::Tuple(items) => {
+                JSONValue::Array(items.iter().map(Self::from_py).collect::<PyResult<_>>()?)
+            }
+            PyValue::Dict(d) => {
+                let mut members = PyDictionary::new();
+                for (k, v) in d.iter() {
+                    let key = match k {
+                        PyValue::Str(s) => s.to_string(),
+                        PyValue::None | PyValue::Bool(_) => JSONValue::from_py(k)?.to_string(),
+                        PyValue::Int(i) => i.to_string(),
+                        PyValue::Float(f) => float_literal(*f),
+                        other => {
+                            return Err(PyException::type_error(format!(
+                                "keys must be str, int, float, bool or None, not {}",
+                                other.type_name()
+                            )))
+                        }
+                    };
+                    members.set_item(key, Self::from_py(v)?);
+                }
+                JSONValue::Object(members)
+            }
+            other => return Err(not_serializable(other.type_name())),
+        })
+    }
+
+    /// Converts to a runtime value the way json.loads() builds one. BigInt
+    /// has no PyValue counterpart and becomes the nearest float.
+    pub fn into_py(self) -> PyValue {
+        match self {
+            JSONValue::Null => PyValue::None,
+            JSONValue::Bool(b) => PyValue::Bool(b),
+            JSONValue::Int(i) => PyValue::Int(i),
+            JSONValue::BigInt(digits) => PyValue::Float(digits.parse().unwrap_or(f64::NAN)),
+            JSONValue::Float(f) => PyValue::Float(f),
+            JSONValue::Str(s) => PyValue::from(s),
+            JSONValue::Array(items) => PyValue::from(
+                items
+                    .into_iter()
+                    .map(JSONValue::into_py)
+                    .collect::<Vec<_>>(),
+            ),
+            JSONValue::Object(members) => {
+                let mut d = PyDictionary::new();
+                for (k, v) in members {
+                    d.set_item(PyValue::from(k), v.into_py());
+                }
+                PyValue::Dict(d)
+            }
+        }
+    }
+}
+
+/// Types json.dumps() can serialize, so compiled code can pass its own
+/// values instead of building a [`JSONValue`] first.
+pub trait ToJson {
+    /// The JSON document for this value; TypeError for anything json.dumps()
+    /// rejects.
+    fn to_json(&self) -> PyResult<JSONValue>;
+
+    /// This value as a JSON object key: str, int, float, bool and None keys
+    /// become strings, as json.dumps() makes them; anything else raises
+    /// TypeError.
+    fn to_json_key(&self) -> PyResult<String> {
+        json_key(self.to_json()?)
+    }
+
+    /// Encodes with `encoder`. [`JSONValue`] overrides this to skip the copy.
+    fn encode_json(&self, encoder: &JSONEncoder) -> PyResult<String> {
+        encoder.encode_value(&self.to_json()?)
+    }
+}
+
+/// Types json.loads() output can be converted into; TypeError when the
+/// document has a different shape.
+pub trait FromJson: Sized {
+    fn from_json(value: JSONValue) -> PyResult<Self>;
+}
+
+impl ToJson for JSONValue {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        Ok(self.clone())
+    }
+
+    fn encode_json(&self, encoder: &JSONEncoder) -> PyResult<String> {
+        encoder.encode_value(self)
+    }
+}
+
+impl FromJson for JSONValue {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        Ok(value)
+    }
+}
+
+impl ToJson for PyValue {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        JSONValue::from_py(self)
+    }
+
+    fn to_json_key(&self) -> PyResult<String> {
+        match self {
+            PyValue::List(_) | PyValue::Tuple(_) | PyValue::Dict(_) => {
+                Err(bad_key(self.type_name()))
+            }
+            _ => json_key(self.to_json()?),
+        }
+    }
+}
+
+impl FromJson for PyValue {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        Ok(value.into_py())
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        (**self).to_json()
+    }
+}
+
+/// The Python type json.loads() would build for `value`, for error messages.
+fn json_type_name(value: &JSONValue) -> &'static str {
+    match value {
+        JSONValue::Null => "NoneType",
+        JSONValue::Bool(_) => "bool",
+        JSONValue::Int(_) | JSONValue::BigInt(_) => "int",
+        JSONValue::Float(_) => "float",
+        JSONValue::Str(_) => "str",
+        JSONValue::Array(_) => "list",
+        JSONValue::Object(_) => "dict",
+    }
+}
+
+fn json_key(key: JSONValue) -> PyResult<String> {
+    match key {
+        JSONValue::Str(s) => Ok(s),
+        JSONValue::Int(i) => Ok(i.to_string()),
+        JSONValue::BigInt(digits) => Ok(digits),
+        JSONValue::Float(f) => Ok(float_literal(f)),
+        JSONValue::Null | JSONValue::Bool(_) => Ok(key.to_string()),
+        other => Err(bad_key(json_type_name(&other))),
+    }
+}
+
+fn bad_key(type_name: &str) -> PyException {
+    PyException::type_error(format!(
+        "keys must be str, int, float, bool or None, not {}",
+        type_name
+    ))
+}
+
+fn unexpected(expected: &str, value: &JSONValue) -> PyException {
+    PyException::type_error(format!(
+        "expected {} from JSON, got {}",
+        expected,
+        json_type_name(value)
+    ))
+}
+
+impl ToJson for () {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        Ok(JSONValue::Null)
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        Ok(JSONValue::Bool(*self))
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        value.as_bool().ok_or_else(|| unexpected("bool", &value))
+    }
+}
+
+macro_rules! json_int {
+    ($($t:ty),*) => {$(
+        impl ToJson for $t {
+            fn to_json(&self) -> PyResult<JSONValue> {
+                Ok(match i64::try_from(*self) {
+                    Ok(i) => JSONValue::Int(i),
+                    Err(_) => JSONValue::BigInt(self.to_string()),
+                })
+            }
+        }
+
+        impl FromJson for $t {
+            fn from_json(value: JSONValue) -> PyResult<Self> {
+                let int = match &value {
+                    JSONValue::Int(i) => <$t>::try_from(*i).ok(),
+                    JSONValue::BigInt(digits) => digits.parse().ok(),
+                    _ => return Err(unexpected("int", &value)),
+                };
+                int.ok_or_else(|| {
+                    PyException::new(
+                        "OverflowError",
+                        format!("JSON integer out of range for {}", stringify!($t)),
+                    )
+                })
+            }
+        }
+    )*};
+}
+
+json_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl ToJson for f64 {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        Ok(JSONValue::Float(*self))
+    }
+}
+
+impl FromJson for f64 {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        value.as_f64().ok_or_else(|| unexpected("float", &value))
+    }
+}
+
+impl ToJson for f32 {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        Ok(JSONValue::Float(f64::from(*self)))
+    }
+}
+
+impl FromJson for f32 {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        f64::from_json(value).map(|f| f as f32)
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        Ok(JSONValue::Str(self.to_string()))
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        self.as_str().to_json()
+    }
+}
+
+impl FromJson for String {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        match value {
+            JSONValue::Str(s) => Ok(s),
+            other => Err(unexpected("str", &other)),
+        }
+    }
+}
+
+impl ToJson for PyStr {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        self.as_str().to_json()
+    }
+}
+
+impl FromJson for PyStr {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        String::from_json(value).map(PyStr::from)
+    }
+}
+
+/// None serializes as null, and null converts back to None.
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        match self {
+            Some(value) => value.to_json(),
+            None => Ok(JSONValue::Null),
+        }
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        match value {
+            JSONValue::Null => Ok(None),
+            other => T::from_json(other).map(Some),
+        }
+    }
+}
+
+fn json_array<'a, T: ToJson + 'a>(items: impl Iterator<Item = &'a T>) -> PyResult<JSONValue> {
+    items
+        .map(ToJson::to_json)
+        .collect::<PyResult<_>>()
+        .map(JSONValue::Array)
+}
+
+fn from_json_array<T: FromJson>(value: JSONValue) -> PyResult<Vec<T>> {
+    match value {
+        JSONValue::Array(items) => items.into_iter().map(T::from_json).collect(),
+        other => Err(unexpected("list", &other)),
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        json_array(self.iter())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        json_array(self.iter())
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        from_json_array(value)
+    }
+}
+
+impl<T: ToJson> ToJson for PyList<T> {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        json_array(self.iter())
+    }
+}
+
+impl<T: FromJson> FromJson for PyList<T> {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        from_json_array(value).map(PyList::from_vec)
+    }
+}
+
+/// Tuples serialize as arrays, as in CPython.
+impl<T: ToJson> ToJson for PyTuple<T> {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        json_array(self.iter())
+    }
+
+    fn to_json_key(&self) -> PyResult<String> {
+        Err(bad_key("tuple"))
+    }
+}
+
+impl<T: FromJson> FromJson for PyTuple<T> {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        from_json_array(value).map(PyTuple::from_vec)
+    }
+}
+
+/// Sets are not JSON serializable in CPython either.
+impl<T> ToJson for PySet<T> {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        Err(not_serializable("set"))
+    }
+}
+
+/// Dict keys that are bool, int, float or None become strings, as
+/// json.dumps() does; any other key raises TypeError.
+impl<K: ToJson, V: ToJson> ToJson for PyDictionary<K, V> {
+    fn to_json(&self) -> PyResult<JSONValue> {
+        let mut members = PyDictionary::new();
+        for (k, v) in self.iter() {
+            let key = k.to_json_key()?;
+            members.set_item(key, v.to_json()?);
+        }
+        Ok(JSONValue::Object(members))
+    }
+}
+
+impl<V: FromJson> FromJson for PyDictionary<String, V> {
+    fn from_json(value: JSONValue) -> PyResult<Self> {
+        let JSONValue::Object(members) = value else {
+            return Err(unexpected("dict", &value));
+        };
+        let mut out = PyDictionary::new();
+        out.reserve(members.len());
+        for (k, v) in members {
+            out.set_item(k, V::from_json(v)?);
+        }
+        Ok(out)
+    }
+}
+
+/// Compact json.dumps() output.
+impl fmt::Display for JSONValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = JSONEncoder::new().encode(self).map_err(|_| fmt::Error)?;
+        f.write_str(&text)
+    }
+}
+
+fn not_serializable(type_name: &str) -> PyException {
+    PyException::type_error(format!(
+        "Object of type {} is not JSON serializable",
+        type_name
+    ))
+}
+
+/// float.__repr__, except for the non-finite spellings JSON uses.
+fn float_literal(f: f64) -> String {
+    if f.is_nan() {
+        "NaN".to_string()
+    } else if f == f64::INFINITY {
+        "Infinity".to_string()
+    } else if f == f64::NEG_INFINITY {
+        "-Infinity".to_string()
+    } else {
+        repr_float(f)
+    }
+}
+
+/// Python-equivalent json.loads(s).
+pub fn loads(s: &str) -> PyResult<JSONValue> {
+    let mut parser = Parser {
+        src: s,
+        pos: 0,
+        depth: 0,
+    };
+    parser.skip_whitespace();
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.pos != s.len() {
+        return Err(parser.error("Extra data", parser.pos));
+    }
+    Ok(value)
+}
+
+/// Python-equivalent json.loads(s), converted to `T`.
+pub fn loads_as<T: FromJson>(s: &str) -> PyResult<T> {
+    T::from_json(loads(s)?)
+}
+
+/// Python-equivalent json.dumps(obj) with default arguments.
+pub fn dumps<T: ToJson + ?Sized>(value: &T) -> PyResult<String> {
+    JSONEncoder::new().encode(value)
+}
+
+/// Python-equivalent json.dumps(obj, **kwargs); see
+/// [`JSONEncoder::from_kwargs`] for the accepted keywords.
+pub fn dumps_with<T: ToJson + ?Sized>(value: &T, kwargs: PyKwargs) -> PyResult<String> {
+    JSONEncoder::from_kwargs(kwargs)?.encode(value)
+}
+
+/// How deeply arrays and objects may nest before loads() raises
+/// RecursionError, CPython's default recursion limit.
+const MAX_DEPTH: usize = 1000;
+
+/// The RecursionError loads() raises past MAX_DEPTH.
+fn too_deep(kind: &str) -> PyException {
+    PyException::new(
+        "RecursionError",
+        format!(
+            "maximum recursion depth exceeded while decoding a JSON {} from a unicode string",
+            kind
+        ),
+    )
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser<'_> {
+    /// json.JSONDecodeError, with CPython's line/column/char position.
+    fn error(&self, msg: &str, at: usize) -> PyException {
+        let before = &self.src[..at];
+        let lineno = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let colno = before[line_start..].chars().count() + 1;
+        PyException::new(
+            "JSONDecodeError",
+            format!(
+                "{}: line {} column {} (char {})",
+                msg,
+                lineno,
+                colno,
+                before.chars().count()
+            ),
+        )
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, literal: &str) -> bool {
+        if self.src[self.pos..].starts_with(literal) {
+            self.pos += literal.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn value(&mut self) -> PyResult<JSONValue> {
+        match self.peek() {
+            Some(b'{') => self.nested("object", Self::object),
+            Some(b'[') => self.nested("array", Self::array),
+            _ => self.scalar(),
+        }
+    }
+
+    /// Anything but a container, kept out of value() so the frames that
+    /// recurse through nested containers stay small.
+    fn scalar(&mut self) -> PyResult<JSONValue> {
+        let start = self.pos;
+        match self.peek() {
+            Some(b'"') => return Ok(JSONValue::Str(self.string()?)),
+            Some(b'-' | b'0'..=b'9') => {
+                if let Some(number) = self.number() {
+                    return Ok(number);
+                }
+            }
+            _ => {}
+        }
+        let constants = [
+            ("null", JSONValue::Null),
+            ("true", JSONValue::Bool(true)),
+            ("false", JSONValue::Bool(false)),
+            ("NaN", JSONValue::Float(f64::NAN)),
+            ("Infinity", JSONValue::Float(f64::INFINITY)),
+            ("-Infinity", JSONValue::Float(f64::NEG_INFINITY)),
+        ];
+        for (literal, value) in constants {
+            if self.eat(literal) {
+                return Ok(value);
+            }
+        }
+        Err(self.error("Expecting value", start))
+    }
+
+    /// Parses a container one level deeper, refusing to recurse past
+    /// MAX_DEPTH so hostile input cannot overflow the stack.
+    fn nested(
+        &mut self,
+        kind: &str,
+        parse: fn(&mut Self) -> PyResult<JSONValue>,
+    ) -> PyResult<JSONValue> {
+        if self.depth >= MAX_DEPTH {
+            return Err(too_deep(kind));
+        }
+        self.depth += 1;
+        let value = parse(self);
+        self.depth -= 1;
+        value
+    }
+
+    /// `-?(0|[1-9]\d*)(\.\d+)?([eE][-+]?\d+)?`, or None if nothing matches.
+    fn number(&mut self) -> Option<JSONValue> {
+        let bytes = self.src.as_bytes();
+        let start = self.pos;
+        let digits = |mut i: usize| {
+            while i < bytes.len() && bytes[i].is_ascii_digit() {
+                i += 1;
+            }
+            i
+        };
+        let mut end = start + usize::from(bytes[start] == b'-');
+        match bytes.get(end) {
+            Some(b'0') => end += 1,
+            Some(b'1'..=b'9') => end = digits(end),
+            _ => return None,
+        }
+        let int_end = end;
+        if bytes.get(end) == Some(&b'.') && bytes.get(end + 1).is_some_and(u8::is_ascii_digit) {
+            end = digits(end + 1);
+        }
+        if matches!(bytes.get(end), Some(b'e' | b'E')) {
+            let sign = usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+            if bytes.get(end + 1 + sign).is_some_and(u8::is_ascii_digit) {
+                end = digits(end + 1 + sign);
+            }
+        }
+        self.pos = end;
+        let text = &self.src[start..end];
+        if end == int_end {
+            return Some(match text.parse() {
+                Ok(i) => JSONValue::Int(i),
+                Err(_) => JSONValue::BigInt(text.to_string()),
+            });
+        }
+        text.parse().ok().map(JSONValue::Float)
+    }
+
+    fn string(&mut self) -> PyResult<String> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut out = String::new();
+        loop {
+            let rest = &self.src[self.pos..];
+            let Some(c) = rest.chars().next() else {
+                return Err(self.error("Unterminated string starting at", start));
+            };
+            match c {
+                '"' => {
+                    self.pos += 1;
+                    return Ok(out);
+                }
+                '\\' => {
+                    let esc_at = self.pos;
+                    self.pos += 1;
+                    let esc = self.peek();
+                    self.pos += 1;
+                    match esc {
+                        Some(b'"') => out.push('"'),
+                        Some(b'\\') => out.push('\\'),
+                        Some(b'/') => out.push('/'),
+                        Some(b'b') => out.push('\u{8}'),
+                        Some(b'f') => out.push('\u{c}'),
+                        Some(b'n') => out.push('\n'),
+                        Some(b'r') => out.push('\r'),
+                        Some(b't') => out.push('\t'),
+                        Some(b'u') => out.push(self.unicode_escape(esc_at)?),
+                        None => return Err(self.error("Unterminated string starting at", start)),
+                        Some(_) => return Err(self.error("Invalid \\escape", esc_at)),
+                    }
+                }
+                c if (c as u32) < 0x20 => {
+                    return Err(self.error("Invalid control character at", self.pos))
+                }
+                c => {
+                    out.push(c);
+                    self.pos += c.len_utf8();
+                }
+            }
+        }
+    }
+
+    fn hex4(&mut self, esc_at: usize) -> PyResult<u32> {
+        let digits = self.src.get(self.pos..self.pos + 4);
+        match digits.and_then(|d| {
+            d.bytes()
+                .all(|b| b.is_ascii_hexdigit())
+                .then(|| u32::from_str_radix(d, 16).ok())
+                .flatten()
+        }) {
+            Some(n) => {
+                self.pos += 4;
+                Ok(n)
+            }
+            None => Err(self.error("Invalid \\uXXXX escape", esc_at + 1)),
+        }
+    }
+
+    /// A `\uXXXX` escape, combining surrogate pairs. A lone surrogate cannot
+    /// live in a Rust string and decodes to U+FFFD.
+    fn unicode_escape(&mut self, esc_at: usize) -> PyResult<char> {
+        let hi = self.hex4(esc_at)?;
+        if (0xd800..0xdc00).contains(&hi) && self.src[self.pos..].starts_with("\\u") {
+            let save = self.pos;
+            self.pos += 2;
+            let lo = self.hex4(save)?;
+            if (0xdc00..0xe000).contains(&lo) {
+                let c = 0x10000 + ((hi - 0xd800) << 10) + (lo - 0xdc00);
+                return Ok(char::from_u32(c).unwrap_or('\u{fffd}'));
+            }
+            self.pos = save;
+        }
+        Ok(char::from_u32(hi).unwrap_or('\u{fffd}'))
+    }
+
+    fn array(&mut self) -> PyResult<JSONValue> {
+        self.pos += 1;
+        let mut items = Vec::new();
+        self.skip_whitespace();
+        if self.eat("]") {
+            return Ok(JSONValue::Array(items));
+        }
+        loop {
+            self.skip_whitespace();
+            items.push(self.value()?);
+            self.skip_whitespace();
+            if self.eat("]") {
+                return Ok(JSONValue::Array(items));
+            }
+            if !self.eat(",") {
+                return Err(self.error("Expecting ',' delimiter", self.pos));
+            }
+        }
+    }
+
+    fn object(&mut self) -> PyResult<JSONValue> {
+        self.pos += 1;
+        let mut members = PyDictionary::new();
+        self.skip_whitespace();
+        if self.eat("}") {
+            return Ok(JSONValue::Object(members));
+        }
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error(
+                    "Expecting property name enclosed in double quotes",
+                    self.pos,
+                ));
+            }
+            let key = self.string()?;
+            self.skip_whitespace();
+            if !self.eat(":") {
+                return Err(self.error("Expecting ':' delimiter", self.pos));
+            }
+            self.skip_whitespace();
+            members.set_item(key, self.value()?);
+            self.skip_whitespace();
+            if self.eat("}") {
+                return Ok(JSONValue::Object(members));
+            }
+            if !self.eat(",") {
+                return Err(self.error("Expecting ',' delimiter", self.pos));
+            }
+        }
+    }
+}
+
+/// Python-equivalent json.JSONEncoder; each builder method sets the
+/// json.dumps() keyword argument of the same name.
+#[derive(Debug, Clone)]
+pub struct JSONEncoder {
+    indent: Option<String>,
+    separators: Option<(String, String)>,
+    sort_keys: bool,
+    ensure_ascii: bool,
+    allow_nan: bool,
+}
+
+impl Default for JSONEncoder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JSONEncoder {
+    pub fn new() -> Self {
+        JSONEncoder {
+            indent: None,
+            separators: None,
+            sort_keys: false,
+            ensure_ascii: true,
+            allow_nan: true,
+        }
+    }
+
+    /// JSONEncoder(**kwargs): indent, separators, sort_keys, ensure_ascii
+    /// and allow_nan. skipkeys and check_circular are accepted but change
+    /// nothing, since keys are always strings and values cannot form cycles.
+    pub fn from_kwargs(kwargs: PyKwargs) -> PyResult<Self> {
+        let mut kwargs = kwargs.for_call("JSONEncoder.__init__");
+        let mut encoder = JSONEncoder::new();
+        match kwargs.pop("indent") {
+            None | Some(PyValue::None) => {}
+            Some(PyValue::Int(n)) => encoder = encoder.indent(n.max(0) as usize),
+            Some(PyValue::Str(s)) => encoder = encoder.indent_str(s.into_string()),
+            Some(other) => return Err(kwargs.type_error("indent", "int, str or None", &other)),
+        }
+        match kwargs.pop("separators") {
+            None | Some(PyValue::None) => {}
+            Some(PyValue::Tuple(pair)) => match pair.as_slice() {
+                [PyValue::Str(item), PyValue::Str(key)] => {
+                    encoder = encoder.separators(item.as_str(), key.as_str())
+                }
+                _ => {
+                    return Err(PyException::value_error(
+                        "separators must be a pair of strings",
+                    ))
+                }
+            },
+            Some(other) => return Err(kwargs.type_error("separators", "tuple", &other)),
+        }
+        if let Some(sort_keys) = kwargs.get_bool("sort_keys") {
+            encoder = encoder.sort_keys(sort_keys);
+        }
+        if let Some(ensure_ascii) = kwargs.get_bool("ensure_ascii") {
+            encoder = encoder.ensure_ascii(ensure_ascii);
+        }
+        if let Some(allow_nan) = kwargs.get_bool("allow_nan") {
+            encoder = encoder.allow_nan(allow_nan);
+        }
+        kwargs.pop("skipkeys");
+        kwargs.pop("check_circular");
+        kwargs.reject_unknown()?;
+        Ok(encoder)
+    }
+
+    /// `indent=n`: pretty-print with `n` spaces per level.
+    pub fn indent(mut self, spaces: usize) -> Self {
+        self.indent = Some(" ".repeat(spaces));
+        self
+    }
+
+    /// `indent="..."`: pretty-print with the given string per level.
+    pub fn indent_str<S: Into<String>>(mut self, indent: S) -> Self {
+        self.indent = Some(indent.into());
+        self
+    }
+
+    /// `separators=(item_separator, key_separator)`.
+    pub fn separators<A: Into<String>, B: Into<String>>(mut self, item: A, key: B) -> Self {
+        self.separators = Some((item.into(), key.into()));
+        self
+    }
+
+    /// `sort_keys=True`: emit object members sorted by key instead of in
+    /// insertion order.
+    pub fn sort_keys(mut self, sort_keys: bool) -> Self {
+        self.sort_keys = sort_keys;
+        self
+    }
+
+    pub fn ensure_ascii(mut self, ensure_ascii: bool) -> Self {
+        self.ensure_ascii = ensure_ascii;
+        self
+    }
+
+    /// `allow_nan=False` raises ValueError for NaN and infinities.
+    pub fn allow_nan(mut self, allow_nan: bool) -> Self {
+        self.allow_nan = allow_nan;
+        self
+    }
+
+    /// Python-equivalent JSONEncoder.encode(o).
+    pub fn encode<T: ToJson + ?Sized>(&self, value: &T) -> PyResult<String> {
+        value.encode_json(self)
+    }
+
+    fn encode_value(&self, value: &JSONValue) -> PyResult<String> {
+        let mut out = String::new();
+        self.write(value, 0, &mut out)?;
+        Ok(out)
+    }
+
+    fn item_separator(&self) -> &str {
+        match (&self.separators, &self.indent) {
+            (Some((item, _)), _) => item,
+            (None, Some(_)) => ",",
+            (None, None) => ", ",
+        }
+    }
+
+    fn key_separator(&self) -> &str {
+        self.separators.as_ref().map_or(": ", |(_, key)| key)
+    }
+
+    /// Writes the separator before item `i` of a container at depth `level`.
+    fn newline(&self, i: usize, level: usize, out: &mut String) {
+        if i > 0 {
+            out.push_str(self.item_separator());
+        }
+        if let Some(indent) = &self.indent {
+            out.push('\n');
+            for _ in 0..level {
+                out.push_str(indent);
+            }
+        }
+    }
+
+    fn close(&self, level: usize, out: &mut String) {
+        if let Some(indent) = &self.indent {
+            out.push('\n');
+            for _ in 0..level {
+                out.push_str(indent);
+            }
+        }
+    }
+
+    fn write(&self, value: &JSONValue, level: usize, out: &mut String) -> PyResult<()> {
+        match value {
+            JSONValue::Null => out.push_str("null"),
+            JSONValue::Bool(true) => out.push_str("true"),
+            JSONValue::Bool(false) => out.push_str("false"),
+            JSONValue::Int(i) => out.push_str(&i.to_string()),
+            JSONValue::BigInt(digits) => out.push_str(digits),
+            JSONValue::Float(f) => {
+                if !f.is_finite() && !self.allow_nan {
+                    return Err(PyException::value_error(format!(
+                        "Out of range float values are not JSON compliant: {}",
+                        repr_float(*f)
+                    )));
+                }
+                out.push_str(&float_literal(*f));
+            }
+            JSONValue::Str(s) => self.write_str(s, out),
+            JSONValue::Array(items) => {
+                if items.is_empty() {
+                    out.push_str("[]");
+                    return Ok(());
+                }
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    self.newline(i, level + 1, out);
+                    self.write(item, level + 1, out)?;
+                }
+                self.close(level, out);
+                out.push(']');
+            }
+            JSONValue::Object(members) => {
+                if members.is_empty() {
+                    out.push_str("{}");
+                    return Ok(());
+                }
+                let mut entries: Vec<(&String, &JSONValue)> = members.iter().collect();
+                if self.sort_keys {
+                    entries.sort_by(|a, b| a.0.cmp(b.0));
+                }
+                out.push('{');
+                for (i, (key, item)) in entries.into_iter().enumerate() {
+                    self.newline(i, level + 1, out);
+                    self.write_str(key, out);
+                    out.push_str(self.key_separator());
+                    self.write(item, level + 1, out)?;
+                }
+                self.close(level, out);
+                out.push('}');
+            }
+        }
+        Ok(())
+    }
+
+    fn write_str(&self, s: &str, out: &mut String) {
+        out.push('"');
+        for c in s.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                '\u{8}' => out.push_str("\\b"),
+                '\u{c}' => out.push_str("\\f"),
+                c if (c as u32) < 0x20 || (self.ensure_ascii && !c.is_ascii()) => {
+                    let mut units = [0u16; 2];
+                    for unit in c.encode_utf16(&mut units) {
+                        out.push_str(&format!("\\u{:04x}", unit));
+                    }
+                }
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn integers_keep_precision() {
+        let v = loads("[9007199254740993, 123456789012345678901234567890, 1.5, -0]").unwrap();
+        let items = v.as_array().unwrap();
+        assert_eq!(items[0], JSONValue::Int(9007199254740993));
+        assert_eq!(
+            items[1],
+            JSONValue::BigInt("123456789012345678901234567890".to_string())
+        );
+        assert_eq!(items[2], JSONValue::Float(1.5));
+        assert_eq!(items[3], JSONValue::Int(0));
+        assert_eq!(
+            dumps(&v).unwrap(),
+            "[9007199254740993, 123456789012345678901234567890, 1.5, 0]"
+        );
+    }
+
+    #[test]
+    fn objects_keep_insertion_order() {
+        let v = loads(r#"{"b": 1, "a": [true, null], "c": {"z": 1e3}, "b": 2}"#).unwrap();
+        assert_eq!(
+            dumps(&v).unwrap(),
+            r#"{"b": 2, "a": [true, null], "c": {"z": 1000.0}}"#
+        );
+        let sorted = JSONEncoder::new()
+            .sort_keys(true)
+            .indent(2)
+            .encode(&v)
+            .unwrap();
+        assert_eq!(
+            sorted,
+            "{\n  \"a\": [\n    true,\n    null\n  ],\n  \"b\": 2,\n  \"c\": {\n    \"z\": 1000.0\n  }\n}"
+        );
+        assert_eq!(v.get("b"), Some(&JSONValue::Int(2)));
+    }
+
+    #[test]
+    fn strings_escape_like_cpython() {
+        let v = loads(r#""caf\u00e9 \ud83d\ude00 \"q\"\n""#).unwrap();
+        assert_eq!(v.as_str(), Some("café 😀 \"q\"\n"));
+        assert_eq!(dumps(&v).unwrap(), r#""caf\u00e9 \ud83d\ude00 \"q\"\n""#);
+        let plain = JSONEncoder::new().ensure_ascii(false).encode(&v).unwrap();
+        assert_eq!(plain, "\"café 😀 \\\"q\\\"\\n\"");
+    }
+
+    #[test]
+    fn errors_match_cpython() {
+        let cases = [
+            ("", "Expecting value: line 1 column 1 (char 0)"),
+            ("[1,]", "Expecting value: line 1 column 4 (char 3)"),
+            (
+                "{\n  'a': 1}",
+                "Expecting property name enclosed in double quotes: line 2 column 3 (char 4)",
+            ),
+            ("[1 2]", "Expecting ',' delimiter: line 1 column 4 (char 3)"),
+            (
+                "{\"a\" 1}",
+                "Expecting ':' delimiter: line 1 column 6 (char 5)",
+            ),
+            (
+                "\"abc",
+                "Unterminated string starting at: line 1 column 1 (char 0)",
+            ),
+            ("1 2", "Extra data: line 1 column 3 (char 2)"),
+            ("\"a\\x\"", "Invalid \\escape: line 1 column 3 (char 2)"),
+            (
+                "\"\\u12\"",
+                "Invalid \\uXXXX escape: line 1 column 3 (char 2)",
+            ),
+            (
+                "\"a\u{1}\"",
+                "Invalid control character at: line 1 column 3 (char 2)",
+            ),
+        ];
+        for (src, msg) in cases {
+            let err = loads(src).unwrap_err();
+            assert!(err.is("JSONDecodeError"));
+            assert_eq!(err.message, msg, "{:?}", src);
+        }
+    }
+
+    #[test]
+    fn nesting_is_bounded() {
+        let deep = format!("{}{}", "[".repeat(500), "]".repeat(500));
+        assert!(loads(&deep).is_ok());
+        let err = loads(&"[".repeat(100_000)).unwrap_err();
+        assert!(err.is("RecursionError"));
+        assert!(loads(&"{\"a\": ".repeat(100_000))
+            .unwrap_err()
+            .is("RecursionError"));
+    }
+
+    #[test]
+    fn non_finite_floats() {
+        let v = loads("[NaN, -Infinity]").unwrap();
+        assert_eq!(dumps(&v).unwrap(), "[NaN, -Infinity]");
+        let err = JSONEncoder::new().allow_nan(false).encode(&v).unwrap_err();
+        assert!(err.is("ValueError"));
+    }
+
+    #[test]
+    fn converts_runtime_values() {
+        let mut d = PyDictionary::new();
+        d.set_item(PyValue::Int(1), PyValue::from("one"));
+        d.set_item(PyValue::Bool(true), PyValue::Float(2.0));
+        d.set_item(PyValue::from("k"), PyValue::None);
+        let v = JSONValue::from_py(&PyValue::Dict(d)).unwrap();
+        assert_eq!(v.to_string(), r#"{"1": 2.0, "k": null}"#);
+        let back = loads("{\"x\": [1, 2.5]}").unwrap().into_py();
+        assert_eq!(back.to_string(), "{'x': [1, 2.5]}");
+        let t = PyValue::from(crate::types::PyTuple::from_vec(Vec::new()));
+        let mut bad = PyDictionary::new();
+        bad.set_item(t, PyValue::None);
+        assert!(JSONValue::from_py(&PyValue::Dict(bad))
+            .unwrap_err()
+            .is("TypeError"));
+    }
+
+    #[test]
+    fn dumps_accepts_keyword_arguments() {
+        let value = loads(r#"{"b": 1, "a": [1]}"#).unwrap();
+        let kwargs = PyKwargs::new()
+            .with("indent", -1)
+            .with("sort_keys", 1)
+            .with(
+                "separators",
+                PyValue::Tuple(vec![",".into(), "=".into()].into()),
+            );
+        assert_eq!(
+            dumps_with(&value, kwargs).unwrap(),
+            "{\n\"a\"=[\n1\n],\n\"b\"=1\n}"
+        );
+        let err = dumps_with(&value, PyKwargs::new().with("foo", 1)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TypeError: JSONEncoder.__init__() got an unexpected keyword argument 'foo'"
+        );
+        let kwargs = PyKwargs::new().with("indent", 1.5);
+        assert!(dumps_with(&value, kwargs).unwrap_err().is("TypeError"));
+    }
+
+    #[test]
+    fn dumps_native_values() {
+        let mut d: PyDictionary<i64, PyList<i64>> = PyDictionary::new();
+        d.set_item(1, PyList::from_vec(vec![1, 2]));
+        assert_eq!(dumps(&d).unwrap(), r#"{"1": [1, 2]}"#);
+
+        let mut mixed: PyDictionary<PyValue, PyValue> = PyDictionary::new();
+        mixed.set_item(PyValue::Float(2.5), PyValue::None);
+        mixed.set_item(
+            PyValue::None,
+            PyValue::from(PyTuple::from_vec(vec![PyValue::Int(1)])),
+        );
+        assert_eq!(dumps(&mixed).unwrap(), r#"{"2.5": null, "null": [1]}"#);
+
+        let mut big: PyDictionary<PyStr, Option<u64>> = PyDictionary::new();
+        big.set_item(PyStr::from("u"), Some(u64::MAX));
+        big.set_item(PyStr::from("n"), None);
+        assert_eq!(
+            dumps(&big).unwrap(),
+            r#"{"u": 18446744073709551615, "n": null}"#
+        );
+        assert_eq!(dumps("é").unwrap(), r#""\u00e9""#);
+        assert_eq!(
+            dumps_with(
+                &vec![true, false],
+                PyKwargs::new().with(
+                    "separators",
+                    PyValue::Tuple(vec![",".into(), ":".into()].into())
+                )
+            )
+            .unwrap(),
+            "[true,false]"
+        );
+
+        let mut sets: PyDictionary<&str, PySet<i64>> = PyDictionary::new();
+        sets.set_item("a", PySet::new());
+        let err = dumps(&sets).unwrap_err();
+        assert_eq!(err.message, "Object of type set is not JSON serializable");
+        let mut tuple_key: PyDictionary<PyTuple<i64>, i64> = PyDictionary::new();
+        tuple_key.set_item(PyTuple::from_vec(vec![1]), 1);
+        let err = dumps(&tuple_key).unwrap_err();
+        assert_eq!(
+            err.message,
+            "keys must be str, int, float, bool or None, not tuple"
+        );
+    }
+
+    #[test]
+    fn loads_into_native_values() {
+        let d: PyDictionary<String, Vec<i64>> = loads_as(r#"{"a": [1, 2]}"#).unwrap();
+        assert_eq!(d.get("a").unwrap(), &vec![1, 2]);
+        let xs: PyList<Option<f64>> = loads_as("[1, 2.5, null]").unwrap();
+        assert_eq!(xs.into_vec(), vec![Some(1.0), Some(2.5), None]);
+        let n: u64 = loads_as("18446744073709551615").unwrap();
+        assert_eq!(n, u64::MAX);
+        assert!(loads_as::<u8>("256").unwrap_err().is("OverflowError"));
+        let err = loads_as::<Vec<String>>(r#"["a", 1]"#).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "TypeError: expected str from JSON, got int"
+        );
+        let v: PyValue = loads_as("[true]").unwrap();
+        assert_eq!(v.to_string(), "[True]");
+    }
+}
@@ -0,0 +1,459 @@
+//! Python-equivalent locale module, without the C library's locale
+//! database: setlocale() records the requested name per category, and the
+//! numeric conventions come from a small built-in table keyed by language.
+//! Names outside that table raise locale.Error as an uninstalled locale
+//! would, so scripts that fall back to "C" on error keep working.
+
+use std::env;
+
+use crate::builtins::{float, int};
+use crate::exceptions::{PyException, PyResult};
+use crate::sync::Lock;
+use crate::types::{PyFormat, PyValue};
+
+pub const LC_CTYPE: i32 = 0;
+pub const LC_NUMERIC: i32 = 1;
+pub const LC_TIME: i32 = 2;
+pub const LC_COLLATE: i32 = 3;
+pub const LC_MONETARY: i32 = 4;
+pub const LC_MESSAGES: i32 = 5;
+pub const LC_ALL: i32 = 6;
+
+/// Python-equivalent locale.CHAR_MAX: in a grouping, no further grouping.
+pub const CHAR_MAX: u8 = 127;
+
+const CATEGORY_NAMES: [&str; 6] = [
+    "LC_CTYPE",
+    "LC_NUMERIC",
+    "LC_TIME",
+    "LC_COLLATE",
+    "LC_MONETARY",
+    "LC_MESSAGES",
+];
+
+/// The locale set for each category; "" until setlocale() is called, which
+/// reads as "C" as in a freshly started CPython.
+static CURRENT: Lock<[String; 6]> = Lock::new([const { String::new() }; 6]);
+
+fn error(msg: &str) -> PyException {
+    PyException::new("locale.Error", msg)
+}
+
+/// The numeric fields of localeconv().
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conventions {
+    pub decimal_point: &'static str,
+    pub thousands_sep: &'static str,
+    /// Group sizes from the decimal point leftwards; a final 0 repeats the
+    /// last size and CHAR_MAX stops grouping.
+    pub grouping: Vec<u8>,
+}
+
+/// Conventions for a locale name such as "de_DE.UTF-8", or None for a
+/// language the table does not know.
+fn conventions_for(name: &str) -> Option<Conventions> {
+    if matches!(name, "" | "C" | "POSIX") || name.starts_with("C.") {
+        return Some(Conventions {
+            decimal_point: ".",
+            thousands_sep: "",
+            grouping: Vec::new(),
+        });
+    }
+    let language = name.split(['_', '.', '@']).next().unwrap_or("");
+    let (decimal_point, thousands_sep) = match language {
+        "en" | "ja" | "ko" | "zh" | "th" | "he" => (".", ","),
+        "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" => (",", "."),
+        "fr" | "ru" | "pl" | "cs" | "sk" | "fi" | "sv" | "nb" | "uk" => (",", "\u{202f}"),
+        _ => return None,
+    };
+    Some(Conventions {
+        decimal_point,
+        thousands_sep,
+        grouping: vec![3, 3, 0],
+    })
+}
+
+fn category_index(category: i32) -> PyResult<usize> {
+    usize::try_from(category)
+        .ok()
+        .filter(|&i| i < CATEGORY_NAMES.len())
+        .ok_or_else(|| error("invalid locale category"))
+}
+
+/// The locale "" selects for `category`: LC_ALL, then the category's own
+/// variable, then LANG, as the C library resolves it.
+fn from_environment(category: &str) -> String {
+    ["LC_ALL", category, "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_else(|| "C".to_string())
+}
+
+fn name_of(current: &str) -> &str {
+    if current.is_empty() {
+        "C"
+    } else {
+        current
+    }
+}
+
+/// Python-equivalent locale.setlocale(category, locale=None). With None it
+/// only reports the current setting; "" takes the setting from the
+/// environment. Returns the locale now in effect.
+pub fn setlocale(category: i32, locale: Option<&str>) -> PyResult<String> {
+    let mut current = CURRENT.lock();
+    let indices: Vec<usize> = if category == LC_ALL {
+        (0..CATEGORY_NAMES.len()).collect()
+    } else {
+        vec![category_index(category)?]
+    };
+    if let Some(locale) = locale {
+        let names: Vec<String> = indices
+            .iter()
+            .map(|&i| match locale {
+                "" => from_environment(CATEGORY_NAMES[i]),
+                name => name.to_string(),
+            })
+            .collect();
+        if names.iter().any(|name| conventions_for(name).is_none()) {
+            return Err(error("unsupported locale setting"));
+        }
+        for (&i, name) in indices.iter().zip(names) {
+            current[i] = name;
+        }
+    }
+    let first = name_of(&current[indices[0]]);
+    if indices.iter().all(|&i| name_of(&current[i]) == first) {
+        return Ok(first.to_string());
+    }
+    // Mixed categories read back as glibc spells them.
+    let parts: Vec<String> = indices
+        .iter()
+        .map(|&i| format!("{}={}", CATEGORY_NAMES[i], name_of(&current[i])))
+        .collect();
+    Ok(parts.join(";"))
+}
+
+/// Python-equivalent locale.getlocale(category=LC_CTYPE): the language code
+/// and encoding, or (None, None) for the C locale.
+pub fn getlocale(category: i32) -> PyResult<(Option<String>, Option<String>)> {
+    if category == LC_ALL {
+        return Err(PyException::type_error("category LC_ALL is not supported"));
+    }
+    let name = CURRENT.lock()[category_index(category)?].clone();
+    let name = name_of(&name);
+    if matches!(name, "C" | "POSIX") {
+        return Ok((None, None));
+    }
+    let name = name.split('@').next().unwrap_or(name);
+    Ok(match name.split_once('.') {
+        Some((code, encoding)) => (Some(code.to_string()), Some(encoding.to_string())),
+        None => (Some(name.to_string()), None),
+    })
+}
+
+/// Python-equivalent locale.getencoding(). Strings are always UTF-8 here.
+pub fn getencoding() -> &'static str {
+    "UTF-8"
+}
+
+/// Python-equivalent locale.localeconv(), numeric fields only.
+pub fn localeconv() -> Conventions {
+    let name = CURRENT.lock()[LC_NUMERIC as usize].clone();
+    conventions_for(name_of(&name)).unwrap_or_else(|| conventions_for("C").unwrap())
+}
+
+/// Inserts `sep` between the groups of the ASCII digits `digits`.
+fn group(digits: &str, grouping: &[u8], sep: &str) -> String {
+    let mut groups = Vec::new();
+    let mut rest = digits;
+    let mut sizes = grouping.iter();
+    let mut size = 0;
+    loop {
+        match sizes.next() {
+            Some(&CHAR_MAX) => break,
+            // 0, or running out, repeats the last size.
+            Some(&0) | None => {}
+            Some(&next) => size = next as usize,
+        }
+        if size == 0 || rest.len() <= size {
+            break;
+        }
+        let (head, tail) = rest.split_at(rest.len() - size);
+        groups.push(tail);
+        rest = head;
+    }
+    groups.push(rest);
+    groups.reverse();
+    groups.join(sep)
+}
+
+/// Rewrites a number formatted in the C locale with the current decimal
+/// point, grouping the integer digits if asked.
+fn localize(number: &str, grouping: bool, conv: &Conventions) -> String {
+    let start = number.find(|c: char| c.is_ascii_digit()).unwrap_or(0);
+    let (sign, unsigned) = number.split_at(start);
+    let end = unsigned
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(unsigned.len());
+    let (digits, rest) = unsigned.split_at(end);
+    let digits = if grouping {
+        group(digits, &conv.grouping, conv.thousands_sep)
+    } else {
+        digits.to_string()
+    };
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) => format!("{}{}", conv.decimal_point, fraction),
+        None => rest.to_string(),
+    };
+    format!("{}{}{}", sign, digits, rest)
+}
+
+/// One `%` directive of a format_string() template.
+struct Directive {
+    left: bool,
+    zero: bool,
+    flags: String,
+    width: usize,
+    precision: Option<usize>,
+    conversion: char,
+}
+
+impl Directive {
+    fn parse(chars: &mut std::iter::Peekable<std::str::Chars<'_>>) -> PyResult<Directive> {
+        let mut directive = Directive {
+            left: false,
+            zero: false,
+            flags: String::new(),
+            width: 0,
+            precision: None,
+            conversion: '%',
+        };
+        while let Some(&c) = chars.peek() {
+            match c {
+                '-' => directive.left = true,
+                '0' => directive.zero = true,
+                '+' | ' ' | '#' => directive.flags.push(c),
+                _ => break,
+            }
+            chars.next();
+        }
+        while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+            directive.width = directive.width * 10 + d as usize;
+            chars.next();
+        }
+        if chars.next_if_eq(&'.').is_some() {
+            let mut precision = 0;
+            while let Some(d) = chars.peek().and_then(|c| c.to_digit(10)) {
+                precision = precision * 10 + d as usize;
+                chars.next();
+            }
+            directive.precision = Some(precision);
+        }
+        directive.conversion = chars
+            .next()
+            .ok_or_else(|| PyException::value_error("incomplete format"))?;
+        Ok(directive)
+    }
+
+    /// Formats one argument, localizing numbers.
+    fn apply(&self, value: &PyValue, grouping: bool, conv: &Conventions) -> PyResult<String> {
+        let precision = self
+            .precision
+            .map(|p| format!(".{}", p))
+            .unwrap_or_default();
+        let sign = self.flags.replace('#', "");
+        let alternate = if self.flags.contains('#') { "#" } else { "" };
+        let body = match self.conversion {
+            'd' | 'i' | 'u' => {
+                let value = match value {
+                    PyValue::Float(f) => PyValue::Int(f.trunc() as i64),
+                    other => other.clone(),
+                };
+                localize(&value.py_format(&format!("{}d", sign))?, grouping, conv)
+            }
+            c @ ('f' | 'F' | 'e' | 'E' | 'g' | 'G') => {
+                let precision = if precision.is_empty() {
+                    ".6"
+                } else {
+                    &precision
+                };
+                let spec = format!("{}{}{}{}", sign, alternate, precision, c);
+                localize(&value.py_format(&spec)?, grouping, conv)
+            }
+            's' => value.py_format(&precision)?,
+            'r' => crate::builtins::repr(value),
+            c => {
+                return Err(PyException::value_error(format!(
+                    "unsupported format character '{}'",
+                    c
+                )))
+            }
+        };
+        let padding = self.width.saturating_sub(body.chars().count());
+        Ok(if self.left {
+            format!("{}{}", body, " ".repeat(padding))
+        } else if self.zero && self.conversion != 's' {
+            let digits = body.find(|c: char| c.is_ascii_digit()).unwrap_or(0);
+            format!(
+                "{}{}{}",
+                &body[..digits],
+                "0".repeat(padding),
+                &body[digits..]
+            )
+        } else {
+            format!("{}{}", " ".repeat(padding), body)
+        })
+    }
+}
+
+/// Python-equivalent locale.format_string(f, val, grouping=False): `%`
+/// formatting of `args` in order, with numbers using the LC_NUMERIC
+/// decimal point and, if `grouping`, its thousands separator.
+pub fn format_string(f: &str, args: &[PyValue], grouping: bool) -> PyResult<String> {
+    let conv = localeconv();
+    let mut out = String::with_capacity(f.len());
+    let mut args = args.iter();
+    let mut chars = f.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        let directive = Directive::parse(&mut chars)?;
+        if directive.conversion == '%' {
+            out.push('%');
+            continue;
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| PyException::type_error("not enough arguments for format string"))?;
+        out.push_str(&directive.apply(value, grouping, &conv)?);
+    }
+    if args.next().is_some() {
+        return Err(PyException::type_error(
+            "not all arguments converted during string formatting",
+        ));
+    }
+    Ok(out)
+}
+
+/// Python-equivalent locale.str(val): the float formatted like str() but
+/// with the locale's decimal point.
+pub fn str(val: f64) -> PyResult<String> {
+    format_string("%.12g", &[PyValue::Float(val)], false)
+}
+
+/// Python-equivalent locale.delocalize(string): a locale-formatted number
+/// rewritten with "." as decimal point and no thousands separators.
+pub fn delocalize(string: &str) -> String {
+    let conv = localeconv();
+    let mut s = string.to_string();
+    if !conv.thousands_sep.is_empty() {
+        s = s.replace(conv.thousands_sep, "");
+    }
+    if conv.decimal_point != "." {
+        s = s.replace(conv.decimal_point, ".");
+    }
+    s
+}
+
+/// Python-equivalent locale.atof(string).
+pub fn atof(string: &str) -> PyResult<f64> {
+    float(&delocalize(string))
+}
+
+/// Python-equivalent locale.atoi(string).
+pub fn atoi(string: &str) -> PyResult<i64> {
+    int(&delocalize(string))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs `f` under `locale` for LC_NUMERIC, restoring the C locale after;
+    /// CURRENT is process-wide, so tests touching it take this lock.
+    fn with_numeric<R>(locale: &str, f: impl FnOnce() -> R) -> R {
+        static SERIAL: Lock<()> = Lock::new(());
+        let _serial = SERIAL.lock();
+        setlocale(LC_NUMERIC, Some(locale)).unwrap();
+        let result = f();
+        setlocale(LC_NUMERIC, Some("C")).unwrap();
+        result
+    }
+
+    #[test]
+    fn setlocale_and_getlocale() {
+        with_numeric("C", || {
+            assert_eq!(getlocale(LC_CTYPE).unwrap(), (None, None));
+            let err = setlocale(LC_TIME, Some("xx_YY")).unwrap_err();
+            assert!(err.is("locale.Error"));
+            assert_eq!(err.message, "unsupported locale setting");
+            assert!(setlocale(42, None).is_err());
+            assert!(getlocale(LC_ALL).unwrap_err().is("TypeError"));
+
+            setlocale(LC_NUMERIC, Some("de_DE.UTF-8")).unwrap();
+            assert_eq!(setlocale(LC_NUMERIC, None).unwrap(), "de_DE.UTF-8");
+            assert_eq!(
+                getlocale(LC_NUMERIC).unwrap(),
+                (Some("de_DE".to_string()), Some("UTF-8".to_string()))
+            );
+            assert_eq!(
+                setlocale(LC_ALL, None).unwrap(),
+                "LC_CTYPE=C;LC_NUMERIC=de_DE.UTF-8;LC_TIME=C;LC_COLLATE=C;\
+                 LC_MONETARY=C;LC_MESSAGES=C"
+            );
+            assert_eq!(localeconv().decimal_point, ",");
+            setlocale(LC_NUMERIC, Some("C")).unwrap();
+            assert_eq!(setlocale(LC_ALL, None).unwrap(), "C");
+        });
+    }
+
+    #[test]
+    fn format_string_groups_and_localizes() {
+        let n = [PyValue::Float(1234567.891)];
+        assert_eq!(format_string("%.2f", &n, true).unwrap(), "1234567.89");
+        with_numeric("en_US.UTF-8", || {
+            assert_eq!(format_string("%.2f", &n, true).unwrap(), "1,234,567.89");
+            assert_eq!(
+                format_string(
+                    "%d items, %s",
+                    &[PyValue::Int(-12345), PyValue::from("ok")],
+                    true
+                )
+                .unwrap(),
+                "-12,345 items, ok"
+            );
+            assert_eq!(
+                format_string("[%8d|%-6d]", &[PyValue::Int(1234), PyValue::Int(5)], true).unwrap(),
+                "[   1,234|5     ]"
+            );
+            assert_eq!(
+                format_string("%06.1f%%", &[PyValue::Float(-2.5)], false).unwrap(),
+                "-002.5%"
+            );
+        });
+        with_numeric("de_DE", || {
+            assert_eq!(format_string("%.2f", &n, true).unwrap(), "1.234.567,89");
+            assert_eq!(str(0.5).unwrap(), "0,5");
+            assert_eq!(atof("1.234,5").unwrap(), 1234.5);
+            assert_eq!(atoi("-1.000").unwrap(), -1000);
+        });
+        assert!(format_string("%d %d", &[PyValue::Int(1)], false)
+            .unwrap_err()
+            .is("TypeError"));
+        assert!(format_string("%d", &[PyValue::Int(1), PyValue::Int(2)], false).is_err());
+    }
+
+    #[test]
+    fn grouping_rules() {
+        assert_eq!(group("1234567", &[3, 3, 0], ","), "1,234,567");
+        assert_eq!(group("123", &[3, 3, 0], ","), "123");
+        // Indian-style: three, then twos.
+        assert_eq!(group("12345678", &[3, 2, 0], ","), "1,23,45,678");
+        assert_eq!(group("1234567", &[3, CHAR_MAX], ","), "1234,567");
+        assert_eq!(group("1234567", &[], ","), "1234567");
+    }
+}
@@ -0,0 +1,420 @@
+//! Python-equivalent math module, with bulk variants for compiled loops.
+//!
+//! `[math.sqrt(x) for x in xs]` can lower to [`sqrt_slice`], and
+//! `xs[i] = math.sqrt(xs[i])` over every index to [`sqrt_in_place`]. The bulk
+//! helpers compute a whole block in a loop the optimizer can vectorize, then
+//! check the block for the inputs Python rejects in a second pass. They raise
+//! what the element-by-element loop would, for the first bad element.
+
+use crate::exceptions::{PyException, PyResult};
+
+/// Elements per block of the in-place helpers.
+const BLOCK: usize = 256;
+
+fn domain_error() -> PyException {
+    PyException::value_error("math domain error")
+}
+
+fn range_error() -> PyException {
+    PyException::new("OverflowError", "math range error")
+}
+
+/// Whether `y = f(x)` is an error in Python: a NaN from a non-NaN input or
+/// an infinity from a finite one.
+fn is_invalid(x: f64, y: f64) -> bool {
+    (y.is_nan() && !x.is_nan()) | (y.is_infinite() && x.is_finite())
+}
+
+fn unary_error(_x: f64, y: f64) -> PyException {
+    if y.is_nan() {
+        domain_error()
+    } else {
+        range_error()
+    }
+}
+
+fn checked(x: f64, y: f64) -> PyResult<f64> {
+    if is_invalid(x, y) {
+        Err(unary_error(x, y))
+    } else {
+        Ok(y)
+    }
+}
+
+/// Position of the first pair for which `invalid` holds. The common case,
+/// no error, is a branch-free scan.
+fn first_invalid<F>(xs: &[f64], ys: &[f64], invalid: F) -> Option<usize>
+where
+    F: Fn(f64, f64) -> bool,
+{
+    let any = xs
+        .iter()
+        .zip(ys)
+        .fold(false, |acc, (x, y)| acc | invalid(*x, *y));
+    if !any {
+        return None;
+    }
+    xs.iter().zip(ys).position(|(x, y)| invalid(*x, *y))
+}
+
+fn map_slice<F, I, E>(xs: &[f64], f: F, invalid: I, error: E) -> PyResult<Vec<f64>>
+where
+    F: Fn(f64) -> f64,
+    I: Fn(f64, f64) -> bool,
+    E: Fn(f64, f64) -> PyException,
+{
+    let out: Vec<f64> = xs.iter().map(|x| f(*x)).collect();
+    match first_invalid(xs, &out, invalid) {
+        Some(i) => Err(error(xs[i], out[i])),
+        None => Ok(out),
+    }
+}
+
+/// Replaces each element with `f` of it. On error the elements before the
+/// bad one have been replaced and the rest are untouched, as in the loop.
+fn map_in_place<F, I, E>(xs: &mut [f64], f: F, invalid: I, error: E) -> PyResult<()>
+where
+    F: Fn(f64) -> f64,
+    I: Fn(f64, f64) -> bool,
+    E: Fn(f64, f64) -> PyException,
+{
+    let mut buf = [0.0; BLOCK];
+    for block in xs.chunks_mut(BLOCK) {
+        let out = &mut buf[..block.len()];
+        for (y, x) in out.iter_mut().zip(block.iter()) {
+            *y = f(*x);
+        }
+        if let Some(i) = first_invalid(block, out, &invalid) {
+            let err = error(block[i], out[i]);
+            block[..i].copy_from_slice(&out[..i]);
+            return Err(err);
+        }
+        block.copy_from_slice(out);
+    }
+    Ok(())
+}
+
+macro_rules! unary {
+    ($(#[$doc:meta] $name:ident, $slice:ident, $in_place:ident;)*) => {
+        $(
+            crate::python_function! {
+                #[$doc]
+                pub fn $name(x: f64) -> PyResult<f64> {
+                    checked(x, x.$name())
+                }
+            }
+
+            #[doc = concat!("`[math.", stringify!($name), "(x) for x in xs]`.")]
+            pub fn $slice(xs: &[f64]) -> PyResult<Vec<f64>> {
+                map_slice(xs, f64::$name, is_invalid, unary_error)
+            }
+
+            #[doc = concat!("`xs[i] = math.", stringify!($name), "(xs[i])` for each index.")]
+            pub fn $in_place(xs: &mut [f64]) -> PyResult<()> {
+                map_in_place(xs, f64::$name, is_invalid, unary_error)
+            }
+        )*
+    };
+}
+
+unary! {
+    /// Python-equivalent math.sqrt(x).
+    sqrt, sqrt_slice, sqrt_in_place;
+    /// Python-equivalent math.sin(x).
+    sin, sin_slice, sin_in_place;
+    /// Python-equivalent math.exp(x).
+    exp, exp_slice, exp_in_place;
+}
+
+/// Python raises only when both operands are finite: a NaN result is a
+/// domain error, and so is an infinite one from `0 ** negative`.
+fn pow_invalid(x: f64, y: f64, r: f64) -> bool {
+    x.is_finite() & y.is_finite() & !r.is_finite()
+}
+
+fn pow_error(x: f64, r: f64) -> PyException {
+    if r.is_nan() || x == 0.0 {
+        domain_error()
+    } else {
+        range_error()
+    }
+}
+
+crate::python_function! {
+    /// Python-equivalent math.pow(x, y).
+    pub fn pow(x: f64, y: f64) -> PyResult<f64> {
+        let r = x.powf(y);
+        if pow_invalid(x, y, r) {
+            Err(pow_error(x, r))
+        } else {
+            Ok(r)
+        }
+    }
+}
+
+/// `[math.pow(x, y) for x in xs]`.
+pub fn pow_slice(xs: &[f64], y: f64) -> PyResult<Vec<f64>> {
+    map_slice(xs, |x| x.powf(y), |x, r| pow_invalid(x, y, r), pow_error)
+}
+
+/// `xs[i] = math.pow(xs[i], y)` for each index.
+pub fn pow_in_place(xs: &mut [f64], y: f64) -> PyResult<()> {
+    map_in_place(xs, |x| x.powf(y), |x, r| pow_invalid(x, y, r), pow_error)
+}
+
+fn fma_invalid(x: f64, y: f64, z: f64, r: f64) -> bool {
+    (r.is_nan() & !(x.is_nan() | y.is_nan() | z.is_nan()))
+        | (r.is_infinite() & x.is_finite() & y.is_finite() & z.is_finite())
+}
+
+fn fma_error(r: f64) -> PyException {
+    if r.is_nan() {
+        PyException::value_error("invalid operation in fma")
+    } else {
+        PyException::new("OverflowError", "overflow in fma")
+    }
+}
+
+crate::python_function! {
+    /// Python-equivalent math.fma(x, y, z): `x * y + z` with a single rounding.
+    pub fn fma(x: f64, y: f64, z: f64) -> PyResult<f64> {
+        let r = x.mul_add(y, z);
+        if fma_invalid(x, y, z, r) {
+            Err(fma_error(r))
+        } else {
+            Ok(r)
+        }
+    }
+}
+
+fn check_lengths(name: &str, lens: &[usize]) -> PyResult<()> {
+    if lens.windows(2).all(|w| w[0] == w[1]) {
+        Ok(())
+    } else {
+        Err(PyException::value_error(format!(
+            "{}() arguments have different lengths",
+            name
+        )))
+    }
+}
+
+/// `[math.fma(x, y, z) for x, y, z in zip(xs, ys, zs, strict=True)]`.
+pub fn fma_slice(xs: &[f64], ys: &[f64], zs: &[f64]) -> PyResult<Vec<f64>> {
+    check_lengths("fma_slice", &[xs.len(), ys.len(), zs.len()])?;
+    let out: Vec<f64> = xs
+        .iter()
+        .zip(ys)
+        .zip(zs)
+        .map(|((x, y), z)| x.mul_add(*y, *z))
+        .collect();
+    let bad = (0..out.len()).fold(false, |acc, i| {
+        acc | fma_invalid(xs[i], ys[i], zs[i], out[i])
+    });
+    if bad {
+        for (i, r) in out.iter().enumerate() {
+            if fma_invalid(xs[i], ys[i], zs[i], *r) {
+                return Err(fma_error(*r));
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// `acc[i] = math.fma(xs[i], ys[i], acc[i])` for each index: the
+/// multiply-accumulate step of dot products and polynomial evaluation.
+pub fn fma_in_place(acc: &mut [f64], xs: &[f64], ys: &[f64]) -> PyResult<()> {
+    check_lengths("fma_in_place", &[acc.len(), xs.len(), ys.len()])?;
+    let mut buf = [0.0; BLOCK];
+    for ((block, xs), ys) in acc
+        .chunks_mut(BLOCK)
+        .zip(xs.chunks(BLOCK))
+        .zip(ys.chunks(BLOCK))
+    {
+        let out = &mut buf[..block.len()];
+        for (i, r) in out.iter_mut().enumerate() {
+            *r = xs[i].mul_add(ys[i], block[i]);
+        }
+        let bad = (0..out.len()).fold(false, |acc, i| {
+            acc | fma_invalid(xs[i], ys[i], block[i], out[i])
+        });
+        if bad {
+            for i in 0..out.len() {
+                if fma_invalid(xs[i], ys[i], block[i], out[i]) {
+                    block[..i].copy_from_slice(&out[..i]);
+                    return Err(fma_error(out[i]));
+                }
+            }
+        }
+        block.copy_from_slice(out);
+    }
+    Ok(())
+}
+
+/// Python-equivalent math.fsum(iterable): the correctly rounded sum,
+/// tracked exactly as a list of non-overlapping partial sums (Shewchuk's
+/// algorithm, as in CPython). The builtin sum() only compensates.
+pub fn fsum<I: IntoIterator<Item = f64>>(iterable: I) -> PyResult<f64> {
+    let mut partials: Vec<f64> = Vec::new();
+    let mut special_sum = 0.0;
+    let mut inf_sum = 0.0;
+    for original in iterable {
+        let mut x = original;
+        let mut kept = 0;
+        for j in 0..partials.len() {
+            let mut y = partials[j];
+            if x.abs() < y.abs() {
+                core::mem::swap(&mut x, &mut y);
+            }
+            let hi = x + y;
+            let lo = y - (hi - x);
+            if lo != 0.0 {
+                partials[kept] = lo;
+                kept += 1;
+            }
+            x = hi;
+        }
+        partials.truncate(kept);
+        if x == 0.0 {
+            continue;
+        }
+        if x.is_finite() {
+            partials.push(x);
+            continue;
+        }
+        // Non-finite: an overflow of finite inputs, or an inf or nan summand.
+        if original.is_finite() {
+            return Err(PyException::new(
+                "OverflowError",
+                "intermediate overflow in fsum",
+            ));
+        }
+        if original.is_infinite() {
+            inf_sum += original;
+        }
+        special_sum += original;
+        partials.clear();
+    }
+    if special_sum != 0.0 {
+        if inf_sum.is_nan() {
+            return Err(PyException::value_error("-inf + inf in fsum"));
+        }
+        return Ok(special_sum);
+    }
+    let Some(mut hi) = partials.pop() else {
+        return Ok(0.0);
+    };
+    let mut lo = 0.0;
+    while let Some(y) = partials.pop() {
+        let x = hi;
+        hi = x + y;
+        lo = y - (hi - x);
+        if lo != 0.0 {
+            break;
+        }
+    }
+    // Round half-even: if the rest of the partials push the same way as
+    // `lo`, the true sum lies past the halfway point.
+    if let Some(&next) = partials.last() {
+        if (lo < 0.0 && next < 0.0) || (lo > 0.0 && next > 0.0) {
+            let y = lo * 2.0;
+            let x = hi + y;
+            if y == x - hi {
+                hi = x;
+            }
+        }
+    }
+    Ok(hi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::function::{call_function, list_functions, list_modules};
+    use crate::types::{PyKwargs, PyValue};
+
+    #[test]
+    fn scalars_raise_like_cpython() {
+        assert_eq!(sqrt(2.25).unwrap(), 1.5);
+        assert!(sqrt(f64::NAN).unwrap().is_nan());
+        assert_eq!(sqrt(-1.0).unwrap_err().message, "math domain error");
+        assert!(sin(f64::INFINITY).unwrap_err().is("ValueError"));
+        assert!(exp(1000.0).unwrap_err().is("OverflowError"));
+        assert_eq!(exp(f64::INFINITY).unwrap(), f64::INFINITY);
+        assert!(pow(0.0, -1.0).unwrap_err().is("ValueError"));
+        assert!(pow(-8.0, 1.0 / 3.0).unwrap_err().is("ValueError"));
+        assert!(pow(10.0, 400.0).unwrap_err().is("OverflowError"));
+        assert_eq!(pow(0.0, f64::NEG_INFINITY).unwrap(), f64::INFINITY);
+        assert_eq!(fma(2.0, 3.0, 1.0).unwrap(), 7.0);
+        assert!(fma(f64::INFINITY, 0.0, 1.0).unwrap_err().is("ValueError"));
+        assert!(fma(f64::MAX, 2.0, 0.0).unwrap_err().is("OverflowError"));
+    }
+
+    #[test]
+    fn bulk_helpers_match_the_loop() {
+        let xs: Vec<f64> = (0..600).map(|i| i as f64 / 7.0).collect();
+        let by_element: Vec<f64> = xs.iter().map(|x| sqrt(*x).unwrap()).collect();
+        assert_eq!(sqrt_slice(&xs).unwrap(), by_element);
+        let mut ys = xs.clone();
+        sqrt_in_place(&mut ys).unwrap();
+        assert_eq!(ys, by_element);
+        assert_eq!(pow_slice(&[2.0, 3.0], 2.0).unwrap(), [4.0, 9.0]);
+        assert_eq!(
+            fma_slice(&[1.0, 2.0], &[3.0, 4.0], &[5.0, 6.0]).unwrap(),
+            [8.0, 14.0]
+        );
+        let mut acc = [1.0, 1.0];
+        fma_in_place(&mut acc, &[2.0, 3.0], &[4.0, 5.0]).unwrap();
+        assert_eq!(acc, [9.0, 16.0]);
+        assert!(fma_slice(&[1.0], &[], &[]).unwrap_err().is("ValueError"));
+    }
+
+    #[test]
+    fn fsum_is_exact() {
+        assert_eq!(fsum([0.1; 10]).unwrap(), 1.0);
+        assert_eq!(
+            fsum([1e100, 1.0, -1e100, 1e-100, 1e50, -1.0, -1e50]).unwrap(),
+            1e-100
+        );
+        assert_eq!(fsum([1.0, 1e100, 1.0, -1e100]).unwrap(), 2.0);
+        assert_eq!(
+            fsum([2.0f64.powi(53), 1.0, 2.0f64.powi(-1)]).unwrap(),
+            2.0f64.powi(53) + 2.0
+        );
+        assert_eq!(fsum([]).unwrap(), 0.0);
+        assert_eq!(fsum([f64::INFINITY, 1.0]).unwrap(), f64::INFINITY);
+        assert!(fsum([f64::INFINITY, f64::NEG_INFINITY])
+            .unwrap_err()
+            .is("ValueError"));
+        assert!(fsum([f64::MAX, f64::MAX]).unwrap_err().is("OverflowError"));
+        assert!(fsum([f64::NAN, 1.0]).unwrap().is_nan());
+    }
+
+    #[test]
+    fn in_place_stops_at_the_first_bad_element() {
+        let mut xs: Vec<f64> = (0..300).map(|i| i as f64).collect();
+        xs[270] = -1.0;
+        assert!(sqrt_in_place(&mut xs).unwrap_err().is("ValueError"));
+        assert_eq!(xs[4], 2.0);
+        assert_eq!(xs[269], (269f64).sqrt());
+        assert_eq!(xs[270], -1.0);
+        assert_eq!(xs[271], 271.0);
+        assert!(exp_slice(&[1.0, 1000.0]).unwrap_err().is("OverflowError"));
+    }
+
+    #[test]
+    fn dispatches_by_name() {
+        let none = PyKwargs::new();
+        let four = call_function("math.sqrt", &[PyValue::Int(16)], &none).unwrap();
+        assert_eq!(four, PyValue::Float(4.0));
+        let err = call_function("math.sqrt", &[PyValue::Float(-1.0)], &none).unwrap_err();
+        assert_eq!(err.message, "math domain error");
+        let err = call_function("math.tau", &[], &none).unwrap_err();
+        assert_eq!(err.message, "module 'math' has no attribute 'tau'");
+        let err = call_function("cmath.sqrt", &[], &none).unwrap_err();
+        assert!(err.is("ModuleNotFoundError"));
+        let err = call_function("math.pow", &[PyValue::from("2")], &none).unwrap_err();
+        assert!(err.is("TypeError"));
+        assert_eq!(list_functions("math"), ["exp", "fma", "pow", "sin", "sqrt"]);
+        assert!(list_modules().iter().any(|module| module == "math"));
+    }
+}
@@ -0,0 +1,523 @@
+//! Python-equivalent mimetypes module: guess a file's type and encoding
+//! from its name, or an extension from a type. The built-in tables are
+//! CPython's; init() layers the system mime.types files on top of them
+//! when they exist, as the first module-level call does implicitly.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::exceptions::PyResult;
+use crate::sync::Lock;
+
+/// Python-equivalent mimetypes.knownfiles: read by init() when present.
+pub const KNOWNFILES: &[&str] = &[
+    "/etc/mime.types",
+    "/etc/httpd/mime.types",
+    "/etc/httpd/conf/mime.types",
+    "/etc/apache/mime.types",
+    "/etc/apache2/mime.types",
+    "/usr/local/etc/httpd/conf/mime.types",
+    "/usr/local/lib/netscape/mime.types",
+    "/usr/local/etc/mime.types",
+];
+
+/// Registered types, in the order that makes the first extension of each
+/// type the one guess_extension() prefers.
+const TYPES_MAP: &[(&str, &str)] = &[
+    (".js", "application/javascript"),
+    (".mjs", "application/javascript"),
+    (".json", "application/json"),
+    (".webmanifest", "application/manifest+json"),
+    (".doc", "application/msword"),
+    (".dot", "application/msword"),
+    (".wiz", "application/msword"),
+    (".nq", "application/n-quads"),
+    (".nt", "application/n-triples"),
+    (".bin", "application/octet-stream"),
+    (".a", "application/octet-stream"),
+    (".dll", "application/octet-stream"),
+    (".exe", "application/octet-stream"),
+    (".o", "application/octet-stream"),
+    (".obj", "application/octet-stream"),
+    (".so", "application/octet-stream"),
+    (".oda", "application/oda"),
+    (".pdf", "application/pdf"),
+    (".p7c", "application/pkcs7-mime"),
+    (".ps", "application/postscript"),
+    (".ai", "application/postscript"),
+    (".eps", "application/postscript"),
+    (".trig", "application/trig"),
+    (".m3u", "application/vnd.apple.mpegurl"),
+    (".m3u8", "application/vnd.apple.mpegurl"),
+    (".xls", "application/vnd.ms-excel"),
+    (".xlb", "application/vnd.ms-excel"),
+    (".ppt", "application/vnd.ms-powerpoint"),
+    (".pot", "application/vnd.ms-powerpoint"),
+    (".ppa", "application/vnd.ms-powerpoint"),
+    (".pps", "application/vnd.ms-powerpoint"),
+    (".pwz", "application/vnd.ms-powerpoint"),
+    (".wasm", "application/wasm"),
+    (".bcpio", "application/x-bcpio"),
+    (".cpio", "application/x-cpio"),
+    (".csh", "application/x-csh"),
+    (".dvi", "application/x-dvi"),
+    (".gtar", "application/x-gtar"),
+    (".hdf", "application/x-hdf"),
+    (".h5", "application/x-hdf5"),
+    (".latex", "application/x-latex"),
+    (".mif", "application/x-mif"),
+    (".cdf", "application/x-netcdf"),
+    (".nc", "application/x-netcdf"),
+    (".p12", "application/x-pkcs12"),
+    (".pfx", "application/x-pkcs12"),
+    (".ram", "application/x-pn-realaudio"),
+    (".pyc", "application/x-python-code"),
+    (".pyo", "application/x-python-code"),
+    (".sh", "application/x-sh"),
+    (".shar", "application/x-shar"),
+    (".swf", "application/x-shockwave-flash"),
+    (".sv4cpio", "application/x-sv4cpio"),
+    (".sv4crc", "application/x-sv4crc"),
+    (".tar", "application/x-tar"),
+    (".tcl", "application/x-tcl"),
+    (".tex", "application/x-tex"),
+    (".texi", "application/x-texinfo"),
+    (".texinfo", "application/x-texinfo"),
+    (".roff", "application/x-troff"),
+    (".t", "application/x-troff"),
+    (".tr", "application/x-troff"),
+    (".man", "application/x-troff-man"),
+    (".me", "application/x-troff-me"),
+    (".ms", "application/x-troff-ms"),
+    (".ustar", "application/x-ustar"),
+    (".src", "application/x-wais-source"),
+    (".xsl", "application/xml"),
+    (".rdf", "application/xml"),
+    (".wsdl", "application/xml"),
+    (".xpdl", "application/xml"),
+    (".zip", "application/zip"),
+    (".3gp", "audio/3gpp"),
+    (".3gpp", "audio/3gpp"),
+    (".3g2", "audio/3gpp2"),
+    (".3gpp2", "audio/3gpp2"),
+    (".aac", "audio/aac"),
+    (".adts", "audio/aac"),
+    (".loas", "audio/aac"),
+    (".ass", "audio/aac"),
+    (".au", "audio/basic"),
+    (".snd", "audio/basic"),
+    (".mp3", "audio/mpeg"),
+    (".mp2", "audio/mpeg"),
+    (".opus", "audio/opus"),
+    (".aif", "audio/x-aiff"),
+    (".aifc", "audio/x-aiff"),
+    (".aiff", "audio/x-aiff"),
+    (".ra", "audio/x-pn-realaudio"),
+    (".wav", "audio/x-wav"),
+    (".avif", "image/avif"),
+    (".bmp", "image/bmp"),
+    (".gif", "image/gif"),
+    (".ief", "image/ief"),
+    (".jpg", "image/jpeg"),
+    (".jpe", "image/jpeg"),
+    (".jpeg", "image/jpeg"),
+    (".heic", "image/heic"),
+    (".heif", "image/heif"),
+    (".png", "image/png"),
+    (".svg", "image/svg+xml"),
+    (".tiff", "image/tiff"),
+    (".tif", "image/tiff"),
+    (".ico", "image/vnd.microsoft.icon"),
+    (".ras", "image/x-cmu-raster"),
+    (".pnm", "image/x-portable-anymap"),
+    (".pbm", "image/x-portable-bitmap"),
+    (".pgm", "image/x-portable-graymap"),
+    (".ppm", "image/x-portable-pixmap"),
+    (".rgb", "image/x-rgb"),
+    (".xbm", "image/x-xbitmap"),
+    (".xpm", "image/x-xpixmap"),
+    (".xwd", "image/x-xwindowdump"),
+    (".eml", "message/rfc822"),
+    (".mht", "message/rfc822"),
+    (".mhtml", "message/rfc822"),
+    (".nws", "message/rfc822"),
+    (".css", "text/css"),
+    (".csv", "text/csv"),
+    (".html", "text/html"),
+    (".htm", "text/html"),
+    (".n3", "text/n3"),
+    (".txt", "text/plain"),
+    (".bat", "text/plain"),
+    (".c", "text/plain"),
+    (".h", "text/plain"),
+    (".ksh", "text/plain"),
+    (".pl", "text/plain"),
+    (".srt", "text/plain"),
+    (".rtx", "text/richtext"),
+    (".tsv", "text/tab-separated-values"),
+    (".vtt", "text/vtt"),
+    (".py", "text/x-python"),
+    (".etx", "text/x-setext"),
+    (".sgm", "text/x-sgml"),
+    (".sgml", "text/x-sgml"),
+    (".vcf", "text/x-vcard"),
+    (".xml", "text/xml"),
+    (".mp4", "video/mp4"),
+    (".mpeg", "video/mpeg"),
+    (".m1v", "video/mpeg"),
+    (".mpa", "video/mpeg"),
+    (".mpe", "video/mpeg"),
+    (".mpg", "video/mpeg"),
+    (".mov", "video/quicktime"),
+    (".qt", "video/quicktime"),
+    (".webm", "video/webm"),
+    (".avi", "video/x-msvideo"),
+    (".movie", "video/x-sgi-movie"),
+];
+
+/// Common but non-standard types, consulted only when `strict` is false.
+const COMMON_TYPES: &[(&str, &str)] = &[
+    (".rtf", "application/rtf"),
+    (".midi", "audio/midi"),
+    (".mid", "audio/midi"),
+    (".jpg", "image/jpg"),
+    (".pict", "image/pict"),
+    (".pct", "image/pict"),
+    (".pic", "image/pict"),
+    (".webp", "image/webp"),
+    (".xul", "text/xul"),
+];
+
+const ENCODINGS_MAP: &[(&str, &str)] = &[
+    (".gz", "gzip"),
+    (".Z", "compress"),
+    (".bz2", "bzip2"),
+    (".xz", "xz"),
+    (".br", "br"),
+];
+
+const SUFFIX_MAP: &[(&str, &str)] = &[
+    (".svgz", ".svg.gz"),
+    (".tgz", ".tar.gz"),
+    (".taz", ".tar.gz"),
+    (".tz", ".tar.gz"),
+    (".tbz2", ".tar.bz2"),
+    (".txz", ".tar.xz"),
+];
+
+fn owned(table: &[(&str, &str)]) -> HashMap<String, String> {
+    table
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// `(root, ext)` as posixpath.splitext(): leading dots of the last
+/// component do not start an extension.
+fn splitext(path: &str) -> (&str, &str) {
+    let name_start = path.rfind('/').map_or(0, |i| i + 1);
+    let name = &path[name_start..];
+    match name.rfind('.') {
+        Some(dot) if name[..dot].bytes().any(|b| b != b'.') => path.split_at(name_start + dot),
+        _ => (path, ""),
+    }
+}
+
+/// urllib.parse's splittype: `("scheme", rest)` when `url` starts with
+/// one, the scheme lowercased.
+fn splittype(url: &str) -> (Option<String>, &str) {
+    match url.find(':') {
+        Some(colon) if colon > 0 && !url[..colon].contains('/') => {
+            (Some(url[..colon].to_lowercase()), &url[colon + 1..])
+        }
+        _ => (None, url),
+    }
+}
+
+/// Python-equivalent mimetypes.MimeTypes: a type database seeded with the
+/// built-in tables.
+#[derive(Debug, Clone)]
+pub struct MimeTypes {
+    /// Extension to type, indexed by `strict` as in CPython.
+    types_map: [HashMap<String, String>; 2],
+    /// Type to extensions in the order they were added.
+    types_map_inv: [HashMap<String, Vec<String>>; 2],
+    pub encodings_map: HashMap<String, String>,
+    pub suffix_map: HashMap<String, String>,
+}
+
+impl Default for MimeTypes {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MimeTypes {
+    pub fn new() -> Self {
+        let mut db = MimeTypes {
+            types_map: Default::default(),
+            types_map_inv: Default::default(),
+            encodings_map: owned(ENCODINGS_MAP),
+            suffix_map: owned(SUFFIX_MAP),
+        };
+        for (ext, ty) in TYPES_MAP {
+            db.add_type(ty, ext, true);
+        }
+        for (ext, ty) in COMMON_TYPES {
+            db.add_type(ty, ext, false);
+        }
+        db
+    }
+
+    /// Maps `ext` (with its leading dot) to `ty`; a later mapping for the
+    /// same extension replaces the earlier one.
+    pub fn add_type(&mut self, ty: &str, ext: &str, strict: bool) {
+        let i = usize::from(strict);
+        self.types_map[i].insert(ext.to_string(), ty.to_string());
+        let exts = self.types_map_inv[i].entry(ty.to_string()).or_default();
+        if !exts.iter().any(|e| e == ext) {
+            exts.push(ext.to_string());
+        }
+    }
+
+    /// Python-equivalent MimeTypes.guess_type(url, strict): `(type,
+    /// encoding)`, either of which may be unknown. Handles data: URLs and
+    /// compressed suffixes such as ".tar.gz" and ".tgz".
+    pub fn guess_type(&self, url: &str, strict: bool) -> (Option<String>, Option<String>) {
+        let (scheme, url) = splittype(url);
+        if scheme.as_deref() == Some("data") {
+            let Some(comma) = url.find(',') else {
+                return (None, None);
+            };
+            let ty = &url[..url[..comma].find(';').unwrap_or(comma)];
+            if ty.contains('=') || !ty.contains('/') {
+                return (Some("text/plain".to_string()), None);
+            }
+            return (Some(ty.to_string()), None);
+        }
+        let mut base = url.to_string();
+        let (b, e) = splitext(&base);
+        let (mut root, mut ext) = (b.to_string(), e.to_string());
+        while let Some(replacement) = self.suffix_map.get(&ext.to_lowercase()) {
+            base = root + replacement;
+            let (b, e) = splitext(&base);
+            (root, ext) = (b.to_string(), e.to_string());
+        }
+        // encodings_map is case sensitive, as in CPython.
+        let encoding = self.encodings_map.get(&ext).cloned();
+        if encoding.is_some() {
+            let (_, e) = splitext(&root);
+            ext = e.to_string();
+        }
+        let lookup = |map: &HashMap<String, String>| {
+            map.get(&ext)
+                .or_else(|| map.get(&ext.to_lowercase()))
+                .cloned()
+        };
+        let ty = lookup(&self.types_map[1]).or_else(|| {
+            if strict {
+                None
+            } else {
+                lookup(&self.types_map[0])
+            }
+        });
+        (ty, encoding)
+    }
+
+    /// Python-equivalent MimeTypes.guess_all_extensions(type, strict).
+    pub fn guess_all_extensions(&self, ty: &str, strict: bool) -> Vec<String> {
+        let ty = ty.to_lowercase();
+        let mut exts = self.types_map_inv[1].get(&ty).cloned().unwrap_or_default();
+        if !strict {
+            for ext in self.types_map_inv[0].get(&ty).into_iter().flatten() {
+                if !exts.contains(ext) {
+                    exts.push(ext.clone());
+                }
+            }
+        }
+        exts
+    }
+
+    /// Python-equivalent MimeTypes.guess_extension(type, strict): the
+    /// preferred extension for `ty`, or None.
+    pub fn guess_extension(&self, ty: &str, strict: bool) -> Option<String> {
+        self.guess_all_extensions(ty, strict).into_iter().next()
+    }
+
+    /// Python-equivalent MimeTypes.read(filename, strict).
+    pub fn read<P: AsRef<Path>>(&mut self, filename: P, strict: bool) -> PyResult<()> {
+        let text = fs::read(filename)?;
+        self.readfp(&String::from_utf8_lossy(&text), strict);
+        Ok(())
+    }
+
+    /// Python-equivalent MimeTypes.readfp(): adds the mappings of a
+    /// mime.types text, one `type ext...` line each, `#` starting a comment.
+    pub fn readfp(&mut self, text: &str, strict: bool) {
+        for line in text.lines() {
+            let mut words = line.split_whitespace().take_while(|w| !w.starts_with('#'));
+            let Some(ty) = words.next() else { continue };
+            for suffix in words {
+                self.add_type(ty, &format!(".{}", suffix), strict);
+            }
+        }
+    }
+
+    /// The extension-to-type map for `strict`, as MimeTypes.types_map.
+    pub fn types_map(&self, strict: bool) -> &HashMap<String, String> {
+        &self.types_map[usize::from(strict)]
+    }
+}
+
+/// The module-level database, created by init() on first use.
+static DB: Lock<Option<MimeTypes>> = Lock::new(None);
+
+fn read_existing(db: &mut MimeTypes, files: &[&str]) {
+    for file in files {
+        if Path::new(file).is_file() {
+            let _ = db.read(file, true);
+        }
+    }
+}
+
+fn with_db<R>(f: impl FnOnce(&mut MimeTypes) -> R) -> R {
+    let mut db = DB.lock();
+    f(db.get_or_insert_with(|| {
+        let mut db = MimeTypes::new();
+        read_existing(&mut db, KNOWNFILES);
+        db
+    }))
+}
+
+/// Python-equivalent mimetypes.init(files): rebuilds the database from the
+/// built-in tables and [`KNOWNFILES`], or with `files` only adds those
+/// to an existing one. Files that do not exist are skipped.
+pub fn init(files: Option<&[&str]>) {
+    let mut db = DB.lock();
+    match (files, db.as_mut()) {
+        (Some(files), Some(db)) => read_existing(db, files),
+        (files, _) => {
+            let mut fresh = MimeTypes::new();
+            read_existing(&mut fresh, KNOWNFILES);
+            read_existing(&mut fresh, files.unwrap_or_default());
+            *db = Some(fresh);
+        }
+    }
+}
+
+/// Python-equivalent mimetypes.inited.
+pub fn inited() -> bool {
+    DB.lock().is_some()
+}
+
+/// Python-equivalent mimetypes.read_mime_types(file): the strict
+/// extension map after reading `file`, or None if it cannot be read.
+pub fn read_mime_types<P: AsRef<Path>>(file: P) -> Option<HashMap<String, String>> {
+    let mut db = MimeTypes::new();
+    db.read(file, true).ok()?;
+    Some(db.types_map[1].clone())
+}
+
+/// Python-equivalent mimetypes.guess_type(url, strict=True).
+pub fn guess_type(url: &str, strict: bool) -> (Option<String>, Option<String>) {
+    with_db(|db| db.guess_type(url, strict))
+}
+
+/// Python-equivalent mimetypes.guess_all_extensions(type, strict=True).
+pub fn guess_all_extensions(ty: &str, strict: bool) -> Vec<String> {
+    with_db(|db| db.guess_all_extensions(ty, strict))
+}
+
+/// Python-equivalent mimetypes.guess_extension(type, strict=True).
+pub fn guess_extension(ty: &str, strict: bool) -> Option<String> {
+    with_db(|db| db.guess_extension(ty, strict))
+}
+
+/// Python-equivalent mimetypes.add_type(type, ext, strict=True).
+pub fn add_type(ty: &str, ext: &str, strict: bool) {
+    with_db(|db| db.add_type(ty, ext, strict))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn guess(db: &MimeTypes, url: &str, strict: bool) -> (String, String) {
+        let (ty, enc) = db.guess_type(url, strict);
+        (ty.unwrap_or_default(), enc.unwrap_or_default())
+    }
+
+    fn pair(ty: &str, enc: &str) -> (String, String) {
+        (ty.to_string(), enc.to_string())
+    }
+
+    #[test]
+    fn guesses_match_cpython() {
+        let db = MimeTypes::new();
+        assert_eq!(
+            guess(&db, "a.tar.gz", true),
+            pair("application/x-tar", "gzip")
+        );
+        assert_eq!(guess(&db, "x.tgz", true), pair("application/x-tar", "gzip"));
+        assert_eq!(guess(&db, "http://h/a.HTML", true), pair("text/html", ""));
+        assert_eq!(guess(&db, "a.TXT.Z", true), pair("text/plain", "compress"));
+        assert_eq!(guess(&db, "a.svgz", true), pair("image/svg+xml", "gzip"));
+        assert_eq!(guess(&db, "data:,hi", true), pair("text/plain", ""));
+        assert_eq!(
+            guess(&db, "data:image/png;base64,xx", true),
+            pair("image/png", "")
+        );
+        assert_eq!(guess(&db, "Makefile", true), pair("", ""));
+        assert_eq!(guess(&db, ".bashrc", true), pair("", ""));
+        assert_eq!(guess(&db, "a.xul", true), pair("", ""));
+        assert_eq!(guess(&db, "a.xul", false), pair("text/xul", ""));
+    }
+
+    #[test]
+    fn extensions_in_preferred_order() {
+        let db = MimeTypes::new();
+        assert_eq!(
+            db.guess_extension("text/plain", true).as_deref(),
+            Some(".txt")
+        );
+        assert_eq!(
+            db.guess_all_extensions("IMAGE/JPEG", true),
+            [".jpg", ".jpe", ".jpeg"]
+        );
+        assert_eq!(db.guess_extension("image/pict", true), None);
+        assert_eq!(
+            db.guess_extension("image/pict", false).as_deref(),
+            Some(".pict")
+        );
+    }
+
+    #[test]
+    fn reads_mime_types_text() {
+        let mut db = MimeTypes::new();
+        db.readfp(
+            "# comment\ntext/x-demo demo dm # not.an.ext\n\nbogus\n",
+            true,
+        );
+        assert_eq!(guess(&db, "f.dm", true), pair("text/x-demo", ""));
+        assert_eq!(
+            db.guess_all_extensions("text/x-demo", true),
+            [".demo", ".dm"]
+        );
+        assert_eq!(guess(&db, "f.ext", true), pair("", ""));
+    }
+
+    #[test]
+    fn module_database_is_initialised_lazily() {
+        assert_eq!(
+            guess_type("index.html", true).0.as_deref(),
+            Some("text/html")
+        );
+        assert!(inited());
+        add_type("application/x-stdpython-test", ".stdpy", true);
+        assert_eq!(
+            guess_extension("application/x-stdpython-test", true).as_deref(),
+            Some(".stdpy")
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Rust implementations of Python standard library modules.
+
+pub mod ast;
+#[cfg(feature = "std")]
+pub mod atexit;
+pub mod base64;
+#[cfg(feature = "std")]
+pub mod cmd;
+pub mod collections;
+pub mod copy;
+#[cfg(feature = "std")]
+pub mod cprofile;
+pub mod dataclasses;
+pub mod datetime;
+pub mod difflib;
+#[cfg(feature = "std")]
+pub mod doctest;
+#[cfg(feature = "std")]
+pub mod dotenv;
+pub mod enum_;
+#[cfg(feature = "std")]
+pub mod email;
+pub mod errno;
+#[cfg(feature = "std")]
+pub mod fcntl;
+#[cfg(feature = "std")]
+pub mod filecmp;
+#[cfg(feature = "std")]
+pub mod filelock;
+pub mod fnmatch;
+pub mod functools;
+#[cfg(feature = "std")]
+pub mod gettext;
+#[cfg(feature = "std")]
+pub mod glob;
+#[cfg(feature = "std")]
+pub mod importlib;
+#[cfg(feature = "std")]
+pub mod inspect;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod itertools;
+pub mod json;
+#[cfg(feature = "std")]
+pub mod locale;
+#[cfg(feature = "std")]
+pub mod math;
+#[cfg(feature = "std")]
+pub mod mimetypes;
+#[cfg(feature = "std")]
+pub mod os;
+#[cfg(feature = "std")]
+pub mod pathlib;
+pub mod pprint;
+#[cfg(feature = "std")]
+pub mod pstats;
+#[cfg(feature = "std")]
+pub mod random;
+#[cfg(feature = "readline")]
+pub mod readline;
+#[cfg(feature = "std")]
+pub mod resource;
+#[cfg(feature = "std")]
+pub mod select;
+pub mod shlex;
+#[cfg(feature = "std")]
+pub mod signal;
+#[cfg(feature = "std")]
+pub mod smtplib;
+pub mod sortedcontainers;
+pub mod stat;
+#[cfg(feature = "std")]
+pub mod sys;
+pub mod string;
+#[cfg(feature = "std")]
+pub mod subprocess;
+#[cfg(feature = "std")]
+pub mod sysconfig;
+#[cfg(feature = "std")]
+pub mod tempfile;
+pub mod time;
+pub mod typing;
+#[cfg(feature = "std")]
+pub mod unittest;
+#[cfg(feature = "std")]
+pub mod venv;
+#[cfg(feature = "std")]
+pub mod wave;
+#[cfg(feature = "std")]
+pub mod webbrowser;
+#[cfg(feature = "std")]
+pub mod zoneinfo;
@@ -0,0 +1,777 @@
+//! Python-equivalent os module: file-descriptor level I/O, stat(), walk()
+//! and the os.PathLike protocol.
+//!
+//! The fd functions call the C library directly and are available on
+//! Linux, Android and Apple platforms, whose O_* values are listed here.
+//! Like CPython, descriptors created here are non-inheritable and calls
+//! interrupted by a signal are retried (PEP 475).
+
+use std::fs;
+use std::path::{Path as StdPath, PathBuf};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::pathlib::{FileStats, Path, PurePath};
+use crate::types::{PyBytes, PyRepr};
+
+pub mod path;
+
+/// Python-equivalent os.SEEK_SET.
+pub const SEEK_SET: i32 = 0;
+/// Python-equivalent os.SEEK_CUR.
+pub const SEEK_CUR: i32 = 1;
+/// Python-equivalent os.SEEK_END.
+pub const SEEK_END: i32 = 2;
+
+pub const O_RDONLY: i32 = 0;
+pub const O_WRONLY: i32 = 1;
+pub const O_RDWR: i32 = 2;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+mod flags {
+    pub const O_CREAT: i32 = 0o100;
+    pub const O_EXCL: i32 = 0o200;
+    pub const O_NOCTTY: i32 = 0o400;
+    pub const O_TRUNC: i32 = 0o1000;
+    pub const O_APPEND: i32 = 0o2000;
+    pub const O_NONBLOCK: i32 = 0o4000;
+    pub const O_CLOEXEC: i32 = 0o2000000;
+}
+
+#[cfg(target_vendor = "apple")]
+mod flags {
+    pub const O_NONBLOCK: i32 = 0x4;
+    pub const O_APPEND: i32 = 0x8;
+    pub const O_CREAT: i32 = 0x200;
+    pub const O_TRUNC: i32 = 0x400;
+    pub const O_EXCL: i32 = 0x800;
+    pub const O_NOCTTY: i32 = 0x20000;
+    pub const O_CLOEXEC: i32 = 0x1000000;
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+pub use flags::*;
+
+/// Python-equivalent os.PathLike: anything with a filesystem path
+/// representation, accepted wherever Python takes `str | os.PathLike`.
+pub trait PathLike {
+    /// Python-equivalent `__fspath__()`.
+    fn fspath(&self) -> String;
+}
+
+impl PathLike for str {
+    fn fspath(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl PathLike for String {
+    fn fspath(&self) -> String {
+        self.clone()
+    }
+}
+
+impl PathLike for PurePath {
+    fn fspath(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl PathLike for Path {
+    fn fspath(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Non-UTF-8 paths are decoded lossily.
+impl PathLike for StdPath {
+    fn fspath(&self) -> String {
+        self.to_string_lossy().into_owned()
+    }
+}
+
+impl PathLike for PathBuf {
+    fn fspath(&self) -> String {
+        self.as_path().fspath()
+    }
+}
+
+impl<T: PathLike + ?Sized> PathLike for &T {
+    fn fspath(&self) -> String {
+        (**self).fspath()
+    }
+}
+
+/// Python-equivalent os.fspath(path).
+pub fn fspath<P: PathLike>(path: P) -> String {
+    path.fspath()
+}
+
+/// Python-equivalent os.fsencode(filename): the path as UTF-8 bytes.
+pub fn fsencode<P: PathLike>(filename: P) -> PyBytes {
+    PyBytes::new(filename.fspath().into_bytes())
+}
+
+/// Python-equivalent os.fsdecode(filename). Invalid UTF-8 is replaced
+/// rather than smuggled through as surrogates.
+pub fn fsdecode(filename: &[u8]) -> String {
+    String::from_utf8_lossy(filename).into_owned()
+}
+
+/// Python-equivalent os.getcwd().
+pub fn getcwd() -> PyResult<String> {
+    Ok(std::env::current_dir()?.fspath())
+}
+
+/// Python-equivalent os.chdir(path).
+pub fn chdir<P: PathLike>(path: P) -> PyResult<()> {
+    Ok(std::env::set_current_dir(path.fspath())?)
+}
+
+/// Python-equivalent os.getenv(key): None when unset. A value that is not
+/// valid Unicode is decoded lossily.
+pub fn getenv(key: &str) -> Option<String> {
+    std::env::var_os(key).map(|value| value.to_string_lossy().into_owned())
+}
+
+/// `int(os.getenv(key, default))`: `default` when unset, otherwise the
+/// value parsed as int() parses a string, raising its ValueError.
+pub fn getenv_int(key: &str, default: i64) -> PyResult<i64> {
+    getenv(key).map_or(Ok(default), |value| crate::builtins::int(&value))
+}
+
+/// `float(os.getenv(key, default))`, raising float()'s ValueError.
+pub fn getenv_float(key: &str, default: f64) -> PyResult<f64> {
+    getenv(key).map_or(Ok(default), |value| crate::builtins::float(&value))
+}
+
+/// A boolean flag from the environment: `default` when unset, otherwise
+/// 1/true/yes/on or 0/false/no/off in any case and ignoring surrounding
+/// whitespace; anything else raises ValueError.
+pub fn getenv_bool(key: &str, default: bool) -> PyResult<bool> {
+    let Some(value) = getenv(key) else {
+        return Ok(default);
+    };
+    match value.trim().to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(PyException::value_error(format!(
+            "invalid truth value {} for environment variable {}",
+            value.py_repr(),
+            key
+        ))),
+    }
+}
+
+/// Python-equivalent os.stat(path, follow_symlinks=...).
+pub fn stat<P: AsRef<StdPath>>(path: P, follow_symlinks: bool) -> PyResult<FileStats> {
+    let path = path.as_ref();
+    let lmeta = fs::symlink_metadata(path)?;
+    let is_symlink = lmeta.file_type().is_symlink();
+    if follow_symlinks && is_symlink {
+        return Ok(FileStats::from_metadata(&fs::metadata(path)?, true));
+    }
+    Ok(FileStats::from_metadata(&lmeta, is_symlink))
+}
+
+/// Python-equivalent os.lstat(path).
+pub fn lstat<P: AsRef<StdPath>>(path: P) -> PyResult<FileStats> {
+    stat(path, false)
+}
+
+/// Python-equivalent os.replace(src, dst): renames `src` to `dst`,
+/// atomically replacing any file already there.
+///
+/// Where rename(2) refuses because the two are on different filesystems
+/// (EXDEV), as when a temporary file from gettempdir() is moved into
+/// place, a file or symlink is instead copied to a temporary file beside
+/// `dst` with its permissions and times, flushed to disk, renamed over
+/// `dst`, and only then removed from `src`. `dst` is never seen half
+/// written, but unlike a rename the move as a whole is not atomic: a crash
+/// can leave both copies. Directories still fail with EXDEV.
+pub fn replace<P: PathLike, Q: PathLike>(src: P, dst: Q) -> PyResult<()> {
+    replace_path(&PathBuf::from(src.fspath()), &PathBuf::from(dst.fspath()))
+}
+
+/// [`replace`] for paths that may not be UTF-8.
+pub(crate) fn replace_path(src: &StdPath, dst: &StdPath) -> PyResult<()> {
+    match fs::rename(src, dst) {
+        Err(err) if err.kind() == std::io::ErrorKind::CrossesDevices => {
+            replace_by_copy(src, dst, err)
+        }
+        result => Ok(result?),
+    }
+}
+
+fn replace_by_copy(src: &StdPath, dst: &StdPath, exdev: std::io::Error) -> PyResult<()> {
+    let meta = fs::symlink_metadata(src)?;
+    if meta.is_dir() {
+        return Err(exdev.into());
+    }
+    let tmp = temp_sibling(dst);
+    let copied = (|| -> std::io::Result<()> {
+        if meta.file_type().is_symlink() {
+            copy_symlink(src, &tmp)?;
+        } else {
+            let mut file = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&tmp)?;
+            std::io::copy(&mut fs::File::open(src)?, &mut file)?;
+            file.set_permissions(meta.permissions())?;
+            file.set_times(
+                fs::FileTimes::new()
+                    .set_accessed(meta.accessed()?)
+                    .set_modified(meta.modified()?),
+            )?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp, dst)?;
+        sync_parent(dst)
+    })();
+    if let Err(err) = copied {
+        let _ = fs::remove_file(&tmp);
+        return Err(err.into());
+    }
+    fs::remove_file(src)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_symlink(src: &StdPath, dst: &StdPath) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(src)?, dst)
+}
+
+#[cfg(windows)]
+fn copy_symlink(src: &StdPath, dst: &StdPath) -> std::io::Result<()> {
+    let target = fs::read_link(src)?;
+    if fs::metadata(src).is_ok_and(|m| m.is_dir()) {
+        std::os::windows::fs::symlink_dir(target, dst)
+    } else {
+        std::os::windows::fs::symlink_file(target, dst)
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn copy_symlink(_src: &StdPath, _dst: &StdPath) -> std::io::Result<()> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+/// A name for a temporary file in the same directory as `path`, so that
+/// renaming it onto `path` stays on one filesystem.
+pub(crate) fn temp_sibling(path: &StdPath) -> PathBuf {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(
+        ".{}.{}.{}.tmp",
+        name,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+/// Flushes the directory entry of `path` to disk, so a rename into it
+/// survives a crash. Windows has no directory handles to sync.
+pub(crate) fn sync_parent(path: &StdPath) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => StdPath::new("."),
+        };
+        fs::File::open(parent)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Python-equivalent os.walk(top, topdown, followlinks): a lazy iterator
+/// of `(dirpath, dirnames, filenames)`. Unreadable directories are skipped,
+/// as with the default `onerror=None`.
+pub fn walk<P: AsRef<StdPath>>(top: P, topdown: bool, followlinks: bool) -> Walk {
+    Walk {
+        stack: vec![Frame::Visit(top.as_ref().to_path_buf())],
+        pending: None,
+        top_down: topdown,
+        follow_symlinks: followlinks,
+        symlinks_as_files: false,
+    }
+}
+
+/// Subdirectory names, each with whether the walk descends into it.
+type Subdirs = Vec<(String, bool)>;
+
+enum Frame {
+    Visit(PathBuf),
+    Yield(PathBuf, Vec<String>, Vec<String>),
+}
+
+/// Iterator returned by [`walk`], also behind pathlib's Path.walk().
+pub struct Walk {
+    stack: Vec<Frame>,
+    /// Top-down only: the directory just yielded and its subdirectories,
+    /// descended into on the next call so [`Walk::prune`] can drop some.
+    pending: Option<(PathBuf, Subdirs)>,
+    top_down: bool,
+    follow_symlinks: bool,
+    /// Path.walk() lists unfollowed directory symlinks under filenames;
+    /// os.walk() lists them under dirnames but does not descend.
+    symlinks_as_files: bool,
+}
+
+impl Walk {
+    /// Switches to Path.walk()'s classification of directory symlinks.
+    pub(crate) fn path_semantics(mut self) -> Self {
+        self.symlinks_as_files = true;
+        self
+    }
+
+    /// Keeps only the subdirectories of the last yielded directory for which
+    /// `keep` holds, like assigning to `dirnames[:]` in a top-down walk.
+    /// Has no effect bottom-up.
+    pub fn prune<F: FnMut(&str) -> bool>(&mut self, mut keep: F) {
+        if let Some((_, dirs)) = self.pending.as_mut() {
+            dirs.retain(|(name, _)| keep(name));
+        }
+    }
+
+    /// `(dirnames with whether to descend, filenames)` of `dir`.
+    fn list_dir(&self, dir: &StdPath) -> Option<(Subdirs, Vec<String>)> {
+        let mut dirs = Vec::new();
+        let mut files = Vec::new();
+        for entry in fs::read_dir(dir).ok()? {
+            let Ok(entry) = entry else { continue };
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let is_link = entry.file_type().is_ok_and(|t| t.is_symlink());
+            let is_dir = if self.follow_symlinks || !self.symlinks_as_files {
+                entry.path().is_dir()
+            } else {
+                !is_link && entry.file_type().is_ok_and(|t| t.is_dir())
+            };
+            if is_dir {
+                dirs.push((name, self.follow_symlinks || !is_link));
+            } else {
+                files.push(name);
+            }
+        }
+        Some((dirs, files))
+    }
+
+    fn push_children(&mut self, dir: &StdPath, dirs: &[(String, bool)]) {
+        for (name, descend) in dirs.iter().rev() {
+            if *descend {
+                self.stack.push(Frame::Visit(dir.join(name)));
+            }
+        }
+    }
+}
+
+impl Iterator for Walk {
+    type Item = (PathBuf, Vec<String>, Vec<String>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((dir, dirs)) = self.pending.take() {
+            self.push_children(&dir, &dirs);
+        }
+        loop {
+            match self.stack.pop()? {
+                Frame::Yield(dir, dirs, files) => return Some((dir, dirs, files)),
+                Frame::Visit(dir) => {
+                    let Some((dirs, files)) = self.list_dir(&dir) else {
+                        continue;
+                    };
+                    let names: Vec<String> = dirs.iter().map(|(n, _)| n.clone()).collect();
+                    if self.top_down {
+                        self.pending = Some((dir.clone(), dirs));
+                        return Some((dir, names, files));
+                    }
+                    self.stack.push(Frame::Yield(dir.clone(), names, files));
+                    self.push_children(&dir, &dirs);
+                }
+            }
+        }
+    }
+}
+
+/// Python-equivalent os.times_result: CPU times of this process and its
+/// waited-for children, and wall-clock time since an arbitrary point, all in
+/// seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TimesResult {
+    pub user: f64,
+    pub system: f64,
+    pub children_user: f64,
+    pub children_system: f64,
+    pub elapsed: f64,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+mod clock {
+    use std::ffi::{c_int, c_long};
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub const SC_CLK_TCK: c_int = 2;
+    #[cfg(target_vendor = "apple")]
+    pub const SC_CLK_TCK: c_int = 3;
+
+    extern "C" {
+        /// `struct tms` is four `clock_t`s: utime, stime, cutime, cstime.
+        pub fn times(buf: *mut [c_long; 4]) -> c_long;
+        pub fn sysconf(name: c_int) -> c_long;
+    }
+}
+
+/// Python-equivalent os.times().
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+pub fn times() -> PyResult<TimesResult> {
+    let mut tms = [0; 4];
+    let elapsed = unsafe { clock::times(&mut tms) };
+    let ticks = unsafe { clock::sysconf(clock::SC_CLK_TCK) };
+    if elapsed == -1 || ticks <= 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    let seconds = |t: std::ffi::c_long| t as f64 / ticks as f64;
+    Ok(TimesResult {
+        user: seconds(tms[0]),
+        system: seconds(tms[1]),
+        children_user: seconds(tms[2]),
+        children_system: seconds(tms[3]),
+        elapsed: seconds(elapsed),
+    })
+}
+
+/// Python-equivalent os.times().
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+pub fn times() -> PyResult<TimesResult> {
+    Err(crate::exceptions::PyException::os_error(
+        "times() is not supported on this platform",
+    ))
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+pub use fd::*;
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+pub(crate) mod fd {
+    use super::O_CLOEXEC;
+    use crate::exceptions::{PyException, PyResult};
+    use crate::types::{PyBytes, PyKwargs};
+
+    pub(crate) mod sys {
+        use std::ffi::{c_char, c_int, c_uint, c_void};
+
+        pub const F_SETFD: c_int = 2;
+        pub const FD_CLOEXEC: c_int = 1;
+
+        extern "C" {
+            pub fn open(path: *const c_char, flags: c_int, ...) -> c_int;
+            pub fn read(fd: c_int, buf: *mut c_void, count: usize) -> isize;
+            pub fn write(fd: c_int, buf: *const c_void, count: usize) -> isize;
+            pub fn close(fd: c_int) -> c_int;
+            pub fn pipe(fds: *mut c_int) -> c_int;
+            pub fn dup(fd: c_int) -> c_int;
+            pub fn dup2(fd: c_int, fd2: c_int) -> c_int;
+            pub fn fcntl(fd: c_int, cmd: c_int, ...) -> c_int;
+            pub fn flock(fd: c_int, operation: c_int) -> c_int;
+            pub fn lseek(fd: c_int, offset: i64, whence: c_int) -> i64;
+        }
+
+        /// `mode_t` is promoted to an unsigned int through open()'s varargs.
+        pub type Mode = c_uint;
+    }
+
+    /// Runs `call` until it fails with something other than EINTR, handling
+    /// signals that arrived in between (PEP 475).
+    pub(crate) fn retry<T: PartialOrd + Default>(mut call: impl FnMut() -> T) -> PyResult<T> {
+        loop {
+            let result = call();
+            if result >= T::default() {
+                return Ok(result);
+            }
+            let err = std::io::Error::last_os_error();
+            if err.kind() != std::io::ErrorKind::Interrupted {
+                return Err(err.into());
+            }
+            crate::stdlib::signal::check_signals()?;
+        }
+    }
+
+    fn set_cloexec(fd: i32) -> PyResult<()> {
+        retry(|| unsafe { sys::fcntl(fd, sys::F_SETFD, sys::FD_CLOEXEC) })?;
+        Ok(())
+    }
+
+    /// Python-equivalent os.open(path, flags, mode=0o777).
+    pub fn open<P: AsRef<std::path::Path>>(path: P, flags: i32, mode: u32) -> PyResult<i32> {
+        use std::os::unix::ffi::OsStrExt;
+        let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes())
+            .map_err(|_| PyException::value_error("embedded null byte"))?;
+        let flags = flags | O_CLOEXEC;
+        retry(|| unsafe { sys::open(path.as_ptr(), flags, mode as sys::Mode) })
+    }
+
+    /// Python-equivalent os.open(path, flags, **kwargs), where the only
+    /// keyword is `mode` (default 0o777).
+    pub fn open_with<P: AsRef<std::path::Path>>(
+        path: P,
+        flags: i32,
+        kwargs: PyKwargs,
+    ) -> PyResult<i32> {
+        let mut kwargs = kwargs.for_call("open");
+        let mode = match kwargs.get_int("mode")? {
+            None => 0o777,
+            Some(mode) => u32::try_from(mode)
+                .map_err(|_| PyException::new("OverflowError", "mode is out of range"))?,
+        };
+        kwargs.reject_unknown()?;
+        open(path, flags, mode)
+    }
+
+    /// Python-equivalent os.read(fd, n): at most `n` bytes, empty at EOF.
+    pub fn read(fd: i32, n: usize) -> PyResult<PyBytes> {
+        let mut buf = vec![0u8; n];
+        let got = retry(|| unsafe { sys::read(fd, buf.as_mut_ptr().cast(), n) })?;
+        buf.truncate(got as usize);
+        Ok(PyBytes::new(buf))
+    }
+
+    /// Python-equivalent os.write(fd, data): the number of bytes written.
+    pub fn write(fd: i32, data: &[u8]) -> PyResult<usize> {
+        let wrote = retry(|| unsafe { sys::write(fd, data.as_ptr().cast(), data.len()) })?;
+        Ok(wrote as usize)
+    }
+
+    /// Python-equivalent os.close(fd). Not retried on EINTR, as in CPython,
+    /// since the descriptor may already be gone.
+    pub fn close(fd: i32) -> PyResult<()> {
+        if unsafe { sys::close(fd) } < 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+
+    /// Python-equivalent os.pipe(): `(read_fd, write_fd)`.
+    pub fn pipe() -> PyResult<(i32, i32)> {
+        let mut fds = [0i32; 2];
+        retry(|| unsafe { sys::pipe(fds.as_mut_ptr()) })?;
+        for fd in fds {
+            set_cloexec(fd)?;
+        }
+        Ok((fds[0], fds[1]))
+    }
+
+    /// Python-equivalent os.dup(fd).
+    pub fn dup(fd: i32) -> PyResult<i32> {
+        let new = retry(|| unsafe { sys::dup(fd) })?;
+        set_cloexec(new)?;
+        Ok(new)
+    }
+
+    /// Python-equivalent os.dup2(fd, fd2, inheritable=True): makes `fd2` refer
+    /// to `fd`'s file, closing it first if needed.
+    pub fn dup2(fd: i32, fd2: i32, inheritable: bool) -> PyResult<i32> {
+        let new = retry(|| unsafe { sys::dup2(fd, fd2) })?;
+        if !inheritable {
+            set_cloexec(new)?;
+        }
+        Ok(new)
+    }
+
+    /// Python-equivalent os.lseek(fd, pos, how): the new absolute position.
+    pub fn lseek(fd: i32, pos: i64, how: i32) -> PyResult<i64> {
+        retry(|| unsafe { sys::lseek(fd, pos, how) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_like_protocol() {
+        let pure = PurePath::new("/tmp/a b.txt");
+        assert_eq!(fspath(&pure), "/tmp/a b.txt");
+        assert_eq!(fspath(Path::new("rel/x")), "rel/x");
+        assert_eq!(fspath(PathBuf::from("p")), "p");
+        assert_eq!(fsencode("caf\u{e9}").as_bytes(), "caf\u{e9}".as_bytes());
+        assert_eq!(fsdecode(fsencode(&pure).as_bytes()), "/tmp/a b.txt");
+        assert_eq!(fsdecode(b"a\xffb"), "a\u{fffd}b");
+
+        let cwd = getcwd().unwrap();
+        chdir(Path::cwd().unwrap()).unwrap();
+        assert_eq!(getcwd().unwrap(), cwd);
+        assert!(chdir("/no/such/stdpython-dir")
+            .unwrap_err()
+            .is("FileNotFoundError"));
+    }
+
+    #[test]
+    fn replace_overwrites_and_copies_across_devices() {
+        let dir = std::env::temp_dir().join(format!("stdpython-replace-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (src, dst) = (dir.join("src.txt"), dir.join("dst.txt"));
+        fs::write(&src, "new").unwrap();
+        fs::write(&dst, "old").unwrap();
+        replace(&src, &dst).unwrap();
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "new");
+        assert!(!src.exists());
+        assert!(replace(&src, &dst).unwrap_err().is("FileNotFoundError"));
+
+        // The EXDEV fallback, exercised directly since the test machine may
+        // have only one filesystem.
+        let exdev = || std::io::Error::from(std::io::ErrorKind::CrossesDevices);
+        fs::write(&src, "copied").unwrap();
+        let mut perms = fs::metadata(&src).unwrap().permissions();
+        perms.set_readonly(true);
+        fs::set_permissions(&src, perms).unwrap();
+        let mtime = std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000);
+        fs::File::open(&src).unwrap().set_modified(mtime).unwrap();
+        replace_by_copy(&src, &dst, exdev()).unwrap();
+        assert!(!src.exists());
+        let meta = fs::metadata(&dst).unwrap();
+        assert_eq!(fs::read_to_string(&dst).unwrap(), "copied");
+        assert!(meta.permissions().readonly());
+        assert_eq!(meta.modified().unwrap(), mtime);
+
+        let sub = dir.join("sub");
+        fs::create_dir(&sub).unwrap();
+        let err = replace_by_copy(&sub, &dir.join("moved"), exdev()).unwrap_err();
+        assert!(err.is("OSError"));
+        assert!(sub.is_dir());
+        let leftovers = fs::read_dir(&dir)
+            .unwrap()
+            .filter(|e| {
+                e.as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .ends_with(".tmp")
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+
+        let mut perms = meta.permissions();
+        #[allow(clippy::permissions_set_readonly_false)]
+        perms.set_readonly(false);
+        fs::set_permissions(&dst, perms).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn typed_environment_lookups() {
+        std::env::set_var("STDPYTHON_TEST_PORT", " 8_080 ");
+        std::env::set_var("STDPYTHON_TEST_RATIO", "0.25");
+        std::env::set_var("STDPYTHON_TEST_DEBUG", "Yes");
+        std::env::set_var("STDPYTHON_TEST_BAD", "maybe");
+        assert_eq!(getenv_int("STDPYTHON_TEST_PORT", 80).unwrap(), 8080);
+        assert_eq!(getenv_int("STDPYTHON_TEST_UNSET", 80).unwrap(), 80);
+        assert_eq!(getenv_float("STDPYTHON_TEST_RATIO", 1.0).unwrap(), 0.25);
+        assert!(getenv_bool("STDPYTHON_TEST_DEBUG", false).unwrap());
+        assert!(!getenv_bool("STDPYTHON_TEST_UNSET", false).unwrap());
+        assert_eq!(
+            getenv_int("STDPYTHON_TEST_BAD", 0).unwrap_err().to_string(),
+            "ValueError: invalid literal for int() with base 10: 'maybe'"
+        );
+        assert!(getenv_float("STDPYTHON_TEST_BAD", 0.0)
+            .unwrap_err()
+            .is("ValueError"));
+        assert_eq!(
+            getenv_bool("STDPYTHON_TEST_BAD", false)
+                .unwrap_err()
+                .message,
+            "invalid truth value 'maybe' for environment variable STDPYTHON_TEST_BAD"
+        );
+        assert_eq!(getenv("STDPYTHON_TEST_UNSET"), None);
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+    #[test]
+    fn process_times() {
+        let t = times().unwrap();
+        assert!(t.elapsed > 0.0);
+        assert!(t.user >= 0.0 && t.system >= 0.0 && t.children_user >= 0.0);
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+    #[test]
+    fn pipe_and_dup2_redirection() {
+        let (r, w) = pipe().unwrap();
+        assert_eq!(write(w, b"hello").unwrap(), 5);
+        let w2 = dup(w).unwrap();
+        close(w).unwrap();
+        write(w2, b" world").unwrap();
+        let (r3, w3) = pipe().unwrap();
+        // Point w3 at the first pipe, as redirecting stdout would.
+        assert_eq!(dup2(w2, w3, true).unwrap(), w3);
+        write(w3, b"!").unwrap();
+        close(w2).unwrap();
+        close(w3).unwrap();
+        close(r3).unwrap();
+        assert_eq!(read(r, 64).unwrap().as_bytes(), b"hello world!");
+        assert!(read(r, 64).unwrap().is_empty());
+        close(r).unwrap();
+        assert!(close(-1).unwrap_err().is("OSError"));
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+    #[test]
+    fn open_flags_and_seek() {
+        let path = std::env::temp_dir().join(format!("stdpython-os-{}", std::process::id()));
+        let fd = open(&path, O_RDWR | O_CREAT | O_TRUNC, 0o600).unwrap();
+        write(fd, b"abcdef").unwrap();
+        assert_eq!(lseek(fd, -2, SEEK_END).unwrap(), 4);
+        assert_eq!(read(fd, 10).unwrap().as_bytes(), b"ef");
+        lseek(fd, 0, SEEK_SET).unwrap();
+        assert_eq!(read(fd, 3).unwrap().as_bytes(), b"abc");
+        close(fd).unwrap();
+        let err = open(&path, O_WRONLY | O_CREAT | O_EXCL, 0o600).unwrap_err();
+        assert!(err.is("FileExistsError"));
+        let kwargs = crate::types::PyKwargs::new().with("mode", 0o600);
+        close(open_with(&path, O_RDONLY, kwargs).unwrap()).unwrap();
+        let kwargs = crate::types::PyKwargs::new().with("dir_fd", 3);
+        assert!(open_with(&path, O_RDONLY, kwargs)
+            .unwrap_err()
+            .is("TypeError"));
+        std::fs::remove_file(&path).unwrap();
+        assert!(open(&path, O_RDONLY, 0)
+            .unwrap_err()
+            .is("FileNotFoundError"));
+    }
+
+    #[test]
+    fn walk_orders_and_prunes() {
+        let top = std::env::temp_dir().join(format!("stdpython-walk-{}", std::process::id()));
+        fs::create_dir_all(top.join("a/deep")).unwrap();
+        fs::create_dir_all(top.join("skip")).unwrap();
+        fs::write(top.join("a/f.txt"), "").unwrap();
+        fs::write(top.join("skip/g.txt"), "").unwrap();
+
+        let rel = |p: &PathBuf| p.strip_prefix(&top).unwrap().to_string_lossy().into_owned();
+        let mut seen = Vec::new();
+        let mut it = walk(&top, true, false);
+        while let Some((dir, mut dirs, files)) = it.next() {
+            it.prune(|name| name != "skip");
+            dirs.sort();
+            seen.push((rel(&dir), dirs, files));
+        }
+        seen.sort();
+        assert_eq!(seen.len(), 3);
+        assert_eq!(
+            seen[0],
+            ("".into(), vec!["a".into(), "skip".into()], vec![])
+        );
+        assert_eq!(
+            seen[1],
+            ("a".into(), vec!["deep".into()], vec!["f.txt".into()])
+        );
+
+        let order: Vec<String> = walk(&top, false, false).map(|(d, _, _)| rel(&d)).collect();
+        assert_eq!(order.last().map(String::as_str), Some(""));
+        let deep = order.iter().position(|d| d.ends_with("deep")).unwrap();
+        let a = order.iter().position(|d| d == "a").unwrap();
+        assert!(deep < a);
+        assert_eq!(walk(top.join("missing"), true, false).count(), 0);
+        fs::remove_dir_all(&top).unwrap();
+    }
+}
@@ -0,0 +1,171 @@
+//! Python-equivalent os.path: lexical path arithmetic.
+//!
+//! These follow posixpath; on Windows `\` is accepted as a separator too,
+//! but drive letters get no special treatment.
+
+use super::PathLike;
+use crate::exceptions::{PyException, PyResult};
+
+/// Python-equivalent os.sep.
+pub const SEP: char = std::path::MAIN_SEPARATOR;
+
+fn is_sep(c: char) -> bool {
+    c == '/' || (cfg!(windows) && c == '\\')
+}
+
+/// Python-equivalent os.path.isabs(path).
+pub fn isabs<P: PathLike>(path: P) -> bool {
+    path.fspath().starts_with(is_sep)
+}
+
+/// Python-equivalent os.path.normpath(path): collapses separators, `.` and
+/// `name/..` without touching the filesystem.
+pub fn normpath<P: PathLike>(path: P) -> String {
+    let path = path.fspath();
+    let path = path.as_str();
+    if path.is_empty() {
+        return ".".into();
+    }
+    // POSIX leaves exactly two leading slashes alone.
+    let leading = path.chars().take_while(|&c| is_sep(c)).count();
+    let root = match leading {
+        0 => "",
+        2 => "//",
+        _ => "/",
+    };
+    let parts = resolve(path.split(is_sep), !root.is_empty());
+    let joined = root.to_string() + &parts.join("/");
+    if joined.is_empty() {
+        ".".into()
+    } else {
+        joined
+    }
+}
+
+/// Drops empty and `.` parts and cancels `..` against the part before it.
+/// Leading `..` parts survive only in relative paths.
+fn resolve<'a, I: IntoIterator<Item = &'a str>>(parts: I, absolute: bool) -> Vec<&'a str> {
+    let mut out: Vec<&str> = Vec::new();
+    for part in parts {
+        match part {
+            "" | "." => {}
+            ".." if out.last().is_some_and(|last| *last != "..") => {
+                out.pop();
+            }
+            ".." if absolute => {}
+            part => out.push(part),
+        }
+    }
+    out
+}
+
+/// `..` for each part of `start` past the common prefix, then the rest of
+/// `path`: how to reach `path` from `start` when both share an anchor.
+pub(crate) fn walk_up<'a>(path: &[&'a str], start: &[&str]) -> Vec<&'a str> {
+    let common = path.iter().zip(start).take_while(|(a, b)| a == b).count();
+    let mut out = vec![".."; start.len() - common];
+    out.extend_from_slice(&path[common..]);
+    out
+}
+
+/// Python-equivalent os.path.abspath(path).
+pub fn abspath<P: PathLike>(path: P) -> PyResult<String> {
+    let path = path.fspath();
+    if isabs(&path) {
+        return Ok(normpath(path));
+    }
+    let cwd = std::env::current_dir()?;
+    Ok(normpath(format!("{}/{}", cwd.to_string_lossy(), path)))
+}
+
+/// Python-equivalent os.path.relpath(path, start=os.curdir).
+pub fn relpath<P: PathLike>(path: P, start: Option<&str>) -> PyResult<String> {
+    let path = path.fspath();
+    if path.is_empty() {
+        return Err(PyException::value_error("no path specified"));
+    }
+    let path = abspath(path)?;
+    let start = abspath(start.unwrap_or("."))?;
+    let path: Vec<&str> = path.split(is_sep).filter(|s| !s.is_empty()).collect();
+    let start: Vec<&str> = start.split(is_sep).filter(|s| !s.is_empty()).collect();
+    let rel = walk_up(&path, &start);
+    if rel.is_empty() {
+        Ok(".".into())
+    } else {
+        Ok(rel.join(std::path::MAIN_SEPARATOR_STR))
+    }
+}
+
+/// Python-equivalent os.path.expanduser(path): a leading `~` or `~user`
+/// becomes that user's home directory. The path comes back unchanged when
+/// the home directory cannot be found.
+pub fn expanduser<P: PathLike>(path: P) -> String {
+    let path = path.fspath();
+    if !path.starts_with('~') {
+        return path;
+    }
+    let end = path.find(is_sep).unwrap_or(path.len());
+    let home = match &path[1..end] {
+        "" => crate::stdlib::pathlib::Path::home().ok(),
+        user => crate::stdlib::pathlib::user_home(user),
+    };
+    let Some(home) = home else {
+        return path;
+    };
+    let home = home.to_string();
+    let expanded = home.trim_end_matches(is_sep).to_string() + &path[end..];
+    if expanded.is_empty() {
+        "/".into()
+    } else {
+        expanded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normpath_matches_posixpath() {
+        let cases = [
+            ("a//b/./c/..", "a/b"),
+            ("/../x", "/x"),
+            ("../../a/..", "../.."),
+            ("", "."),
+            ("//a", "//a"),
+            ("///a", "/a"),
+            ("./", "."),
+            ("a/../..", ".."),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(normpath(path), expected, "{:?}", path);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn relpath_walks_up() {
+        assert_eq!(relpath("/a/b/c", Some("/a/d")).unwrap(), "../b/c");
+        assert_eq!(relpath("/a", Some("/a")).unwrap(), ".");
+        assert_eq!(relpath("/a/b", Some("/")).unwrap(), "a/b");
+        assert_eq!(relpath("/", Some("/a/b")).unwrap(), "../..");
+        assert_eq!(relpath("x/../y", Some("y")).unwrap(), ".");
+        assert!(relpath("", None).unwrap_err().is("ValueError"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn expanduser_uses_home() {
+        let home = std::env::var("HOME").unwrap();
+        let home = home.trim_end_matches('/');
+        assert_eq!(expanduser("~/x/y"), format!("{}/x/y", home));
+        assert_eq!(expanduser("a/~"), "a/~");
+        assert_eq!(expanduser("~no-such-user-xyz/f"), "~no-such-user-xyz/f");
+        if std::path::Path::new("/etc/passwd").exists() {
+            assert_eq!(
+                expanduser("~root"),
+                super::super::fspath(crate::stdlib::pathlib::user_home("root").unwrap())
+            );
+        }
+    }
+}
@@ -0,0 +1,1261 @@
+//! Python-equivalent pathlib module.
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{BufReader, BufWriter, Write};
+use std::ops::{Deref, Div};
+use std::path::{Component, PathBuf};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::{fnmatch, glob, io, os};
+use crate::types::format::{format_object, PyFormat};
+
+/// Python-equivalent pathlib.PurePath: path manipulation without filesystem access.
+///
+/// The path is normalized on construction as CPython does: repeated
+/// separators and `.` parts are dropped (`..` is kept, since it may cross a
+/// symlink), and on POSIX exactly two leading slashes are preserved.
+/// Equality, hashing and ordering compare [`parts`](PurePath::parts), case-
+/// insensitively on Windows.
+#[derive(Debug, Clone)]
+pub struct PurePath {
+    inner: PathBuf,
+}
+
+/// Whether a POSIX path starts with exactly two slashes, which POSIX leaves
+/// implementation-defined and CPython keeps as the root.
+fn has_double_slash_root(path: &std::path::Path) -> bool {
+    let bytes = path.as_os_str().as_encoded_bytes();
+    cfg!(unix) && bytes.starts_with(b"//") && bytes.get(2) != Some(&b'/')
+}
+
+/// `path` rebuilt from its components: redundant separators and `.` parts
+/// are dropped, and Windows forward slashes become backslashes.
+fn normalized(path: PathBuf) -> PathBuf {
+    let double_slash = has_double_slash_root(&path);
+    let mut out = if double_slash {
+        PathBuf::from("//")
+    } else {
+        PathBuf::new()
+    };
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::RootDir if double_slash => {}
+            Component::RootDir => out.push(std::path::MAIN_SEPARATOR_STR),
+            other => out.push(other),
+        }
+    }
+    if out.as_os_str().is_empty() {
+        out.push(".");
+    }
+    out
+}
+
+impl PurePath {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        PurePath {
+            inner: normalized(path.into()),
+        }
+    }
+
+    /// The parts compared by `==`, hash() and sorting: case-folded on Windows.
+    fn parts_normcase(&self) -> Vec<String> {
+        let parts = self.parts();
+        if cfg!(windows) {
+            parts.into_iter().map(|p| p.to_lowercase()).collect()
+        } else {
+            parts
+        }
+    }
+
+    pub fn as_std_path(&self) -> &std::path::Path {
+        &self.inner
+    }
+
+    /// Python-equivalent PurePath.as_posix().
+    pub fn as_posix(&self) -> String {
+        self.to_string().replace('\\', "/")
+    }
+
+    /// Python-equivalent PurePath.parts: the anchor (if any) as one part,
+    /// then each name.
+    pub fn parts(&self) -> Vec<String> {
+        let anchor = self.anchor();
+        let names = self.inner.components().filter_map(|c| match c {
+            Component::Normal(_) | Component::ParentDir => {
+                Some(c.as_os_str().to_string_lossy().into_owned())
+            }
+            _ => None,
+        });
+        (!anchor.is_empty())
+            .then_some(anchor)
+            .into_iter()
+            .chain(names)
+            .collect()
+    }
+
+    /// Python-equivalent PurePath.drive (only non-empty on Windows).
+    pub fn drive(&self) -> String {
+        match self.inner.components().next() {
+            Some(Component::Prefix(prefix)) => prefix.as_os_str().to_string_lossy().into_owned(),
+            _ => String::new(),
+        }
+    }
+
+    /// Python-equivalent PurePath.root.
+    pub fn root(&self) -> String {
+        if has_double_slash_root(&self.inner) {
+            "//".to_string()
+        } else if self.inner.has_root() {
+            std::path::MAIN_SEPARATOR.to_string()
+        } else {
+            String::new()
+        }
+    }
+
+    /// Python-equivalent PurePath.anchor.
+    pub fn anchor(&self) -> String {
+        self.drive() + &self.root()
+    }
+
+    /// Python-equivalent PurePath.name.
+    pub fn name(&self) -> String {
+        self.inner
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Python-equivalent PurePath.suffix.
+    pub fn suffix(&self) -> String {
+        let name = self.name();
+        match name.rfind('.') {
+            Some(i) if i > 0 && i < name.len() - 1 => name[i..].to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Python-equivalent PurePath.suffixes.
+    pub fn suffixes(&self) -> Vec<String> {
+        let name = self.name();
+        if name.ends_with('.') {
+            return Vec::new();
+        }
+        let name = name.trim_start_matches('.');
+        name.split('.').skip(1).map(|s| format!(".{}", s)).collect()
+    }
+
+    /// Python-equivalent PurePath.stem.
+    pub fn stem(&self) -> String {
+        let name = self.name();
+        let suffix = self.suffix();
+        name[..name.len() - suffix.len()].to_string()
+    }
+
+    /// Python-equivalent PurePath.parent; the parent of an anchor or "." is itself.
+    pub fn parent(&self) -> PurePath {
+        match self.inner.parent() {
+            Some(p) => PurePath::new(p),
+            None => self.clone(),
+        }
+    }
+
+    /// Python-equivalent PurePath.parents.
+    pub fn parents(&self) -> Vec<PurePath> {
+        let mut out = Vec::new();
+        let mut current = self.clone();
+        loop {
+            let parent = current.parent();
+            if parent == current {
+                break;
+            }
+            out.push(parent.clone());
+            current = parent;
+        }
+        out
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        self.inner.is_absolute()
+    }
+
+    /// Python-equivalent PurePath(*pathsegments): the segments joined in
+    /// order, an absolute segment discarding everything before it.
+    pub fn new_from_parts<I, P>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<std::path::Path>,
+    {
+        PurePath::new(
+            segments
+                .into_iter()
+                .fold(PathBuf::new(), |acc, p| acc.join(p)),
+        )
+    }
+
+    /// Python-equivalent PurePath.joinpath(other); an absolute `other`
+    /// replaces this path (on Windows, keeping the drive unless `other` has one).
+    pub fn joinpath<P: AsRef<std::path::Path>>(&self, other: P) -> PurePath {
+        PurePath::new(self.inner.join(other))
+    }
+
+    /// Python-equivalent PurePath.with_name(name).
+    pub fn with_name(&self, name: &str) -> PyResult<PurePath> {
+        if self.name().is_empty() {
+            return Err(PyException::value_error(format!(
+                "{} has an empty name",
+                self.repr()
+            )));
+        }
+        if name.is_empty() || name == "." || name.contains(['/', std::path::MAIN_SEPARATOR]) {
+            return Err(PyException::value_error(format!("Invalid name '{}'", name)));
+        }
+        Ok(PurePath::new(self.inner.with_file_name(name)))
+    }
+
+    /// Python-equivalent PurePath.with_suffix(suffix).
+    pub fn with_suffix(&self, suffix: &str) -> PyResult<PurePath> {
+        if !suffix.is_empty() && (!suffix.starts_with('.') || suffix == ".") {
+            return Err(PyException::value_error(format!(
+                "Invalid suffix '{}'",
+                suffix
+            )));
+        }
+        if self.name().is_empty() {
+            return Err(PyException::value_error(format!(
+                "{} has an empty name",
+                self.repr()
+            )));
+        }
+        self.with_name(&(self.stem() + suffix))
+    }
+
+    /// Python-equivalent PurePath.with_stem(stem).
+    pub fn with_stem(&self, stem: &str) -> PyResult<PurePath> {
+        self.with_name(&(stem.to_string() + &self.suffix()))
+    }
+
+    /// Python-equivalent PurePath.is_relative_to(other).
+    pub fn is_relative_to<P: AsRef<std::path::Path>>(&self, other: P) -> bool {
+        self.inner.starts_with(other)
+    }
+
+    /// Python-equivalent PurePath.relative_to(other, walk_up=...). With
+    /// `walk_up`, paths outside `other` are reached through `..` parts, as
+    /// long as the anchors match and the walked part of `other` has no `..`.
+    pub fn relative_to<P: AsRef<std::path::Path>>(
+        &self,
+        other: P,
+        walk_up: bool,
+    ) -> PyResult<PurePath> {
+        let other = PurePath::new(other.as_ref());
+        let fail = |msg: String| Err(PyException::value_error(msg));
+        let ours = self.parts();
+        let theirs = other.parts();
+        let ours: Vec<&str> = ours.iter().map(String::as_str).collect();
+        let theirs: Vec<&str> = theirs.iter().map(String::as_str).collect();
+        let rel = os::path::walk_up(&ours, &theirs);
+        let climbed = rel.iter().take_while(|p| **p == "..").count();
+        let inside = self.anchor() == other.anchor() && climbed == 0;
+        if !inside && !walk_up {
+            return fail(format!("'{}' is not in the subpath of '{}'", self, other));
+        }
+        if self.anchor() != other.anchor() {
+            return fail(format!("'{}' and '{}' have different anchors", self, other));
+        }
+        if theirs[theirs.len() - climbed..].contains(&"..") {
+            return fail(format!("'..' segment in '{}' cannot be walked", other));
+        }
+        Ok(PurePath::new(rel.join(std::path::MAIN_SEPARATOR_STR)))
+    }
+
+    /// Lexically resolves `.` and `..` parts, like os.path.normpath();
+    /// PurePath itself never does this, since `..` may cross a symlink.
+    pub fn normalize(&self) -> PurePath {
+        PurePath::new(os::path::normpath(self))
+    }
+
+    /// Python-equivalent PurePath.match(pattern): relative patterns match from the right.
+    pub fn match_glob(&self, pattern: &str) -> PyResult<bool> {
+        let pattern = PurePath::new(pattern);
+        let pat_parts = pattern.parts();
+        let path_parts = self.parts();
+        if pat_parts.is_empty() {
+            return Err(PyException::value_error("empty pattern"));
+        }
+        if pattern.is_absolute() && pat_parts.len() != path_parts.len() {
+            return Ok(false);
+        }
+        if pat_parts.len() > path_parts.len() {
+            return Ok(false);
+        }
+        Ok(path_parts
+            .iter()
+            .rev()
+            .zip(pat_parts.iter().rev())
+            .all(|(part, pat)| fnmatch::fnmatch(part, pat)))
+    }
+
+    /// Python-equivalent PurePath.full_match(pattern): the pattern must
+    /// match every part, and a `**` part matches any number of parts.
+    pub fn full_match(&self, pattern: &str) -> bool {
+        fn matches(parts: &[String], pats: &[String]) -> bool {
+            match pats.split_first() {
+                None => parts.is_empty(),
+                Some((pat, rest)) if pat == "**" => {
+                    (0..=parts.len()).any(|skip| matches(&parts[skip..], rest))
+                }
+                Some((pat, rest)) => parts
+                    .split_first()
+                    .is_some_and(|(part, tail)| fnmatch::fnmatch(part, pat) && matches(tail, rest)),
+            }
+        }
+        matches(&self.parts(), &PurePath::new(pattern).parts())
+    }
+
+    fn repr(&self) -> String {
+        format!("PurePath('{}')", self)
+    }
+}
+
+impl PartialEq for PurePath {
+    fn eq(&self, other: &Self) -> bool {
+        self.parts_normcase() == other.parts_normcase()
+    }
+}
+
+impl Eq for PurePath {}
+
+impl Hash for PurePath {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.parts_normcase().hash(state);
+    }
+}
+
+impl PartialOrd for PurePath {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PurePath {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.parts_normcase().cmp(&other.parts_normcase())
+    }
+}
+
+impl fmt::Display for PurePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.inner.display())
+    }
+}
+
+impl PyFormat for PurePath {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        let name = if cfg!(windows) {
+            "PureWindowsPath"
+        } else {
+            "PurePosixPath"
+        };
+        format_object(self, spec, name)
+    }
+}
+
+impl AsRef<std::path::Path> for PurePath {
+    fn as_ref(&self) -> &std::path::Path {
+        &self.inner
+    }
+}
+
+impl From<&str> for PurePath {
+    fn from(s: &str) -> Self {
+        PurePath::new(s)
+    }
+}
+
+/// Python-equivalent os.stat_result, returned by Path.stat() and os.stat(),
+/// plus the file-type booleans. Times are seconds since the Unix epoch; the
+/// `_ns` fields hold the same instants as integer nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FileStats {
+    pub st_mode: u32,
+    pub st_ino: u64,
+    pub st_dev: u64,
+    pub st_nlink: u64,
+    pub st_uid: u32,
+    pub st_gid: u32,
+    pub st_size: u64,
+    pub st_atime: f64,
+    pub st_mtime: f64,
+    pub st_ctime: f64,
+    pub st_atime_ns: i64,
+    pub st_mtime_ns: i64,
+    pub st_ctime_ns: i64,
+    pub is_dir: bool,
+    pub is_file: bool,
+    pub is_symlink: bool,
+    pub readonly: bool,
+}
+
+impl FileStats {
+    /// Builds the result from `meta`; `is_symlink` comes from an lstat of
+    /// the same path, since a followed `meta` never reports a link.
+    pub(crate) fn from_metadata(meta: &fs::Metadata, is_symlink: bool) -> FileStats {
+        let (st_ino, st_dev, st_nlink) = ids(meta);
+        let (st_uid, st_gid) = owner(meta);
+        let (st_atime_ns, st_mtime_ns, st_ctime_ns) = times_ns(meta);
+        let seconds = |ns: i64| ns as f64 / 1e9;
+        FileStats {
+            st_mode: mode(meta),
+            st_ino,
+            st_dev,
+            st_nlink,
+            st_uid,
+            st_gid,
+            st_size: meta.len(),
+            st_atime: seconds(st_atime_ns),
+            st_mtime: seconds(st_mtime_ns),
+            st_ctime: seconds(st_ctime_ns),
+            st_atime_ns,
+            st_mtime_ns,
+            st_ctime_ns,
+            is_dir: meta.is_dir(),
+            is_file: meta.is_file(),
+            is_symlink,
+            readonly: meta.permissions().readonly(),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn mode(meta: &fs::Metadata) -> u32 {
+    std::os::unix::fs::MetadataExt::mode(meta)
+}
+
+/// Windows has no mode bits; CPython synthesizes them from the file type
+/// and the read-only attribute.
+#[cfg(not(unix))]
+fn mode(meta: &fs::Metadata) -> u32 {
+    use crate::stdlib::stat;
+    let kind = if meta.file_type().is_symlink() {
+        stat::S_IFLNK
+    } else if meta.is_dir() {
+        stat::S_IFDIR | 0o111
+    } else {
+        stat::S_IFREG
+    };
+    kind | if meta.permissions().readonly() {
+        0o444
+    } else {
+        0o666
+    }
+}
+
+/// `(st_ino, st_dev, st_nlink)`.
+#[cfg(unix)]
+fn ids(meta: &fs::Metadata) -> (u64, u64, u64) {
+    use std::os::unix::fs::MetadataExt;
+    (meta.ino(), meta.dev(), meta.nlink())
+}
+
+#[cfg(not(unix))]
+fn ids(_meta: &fs::Metadata) -> (u64, u64, u64) {
+    (0, 0, 1)
+}
+
+/// `(st_uid, st_gid)`.
+#[cfg(unix)]
+fn owner(meta: &fs::Metadata) -> (u32, u32) {
+    use std::os::unix::fs::MetadataExt;
+    (meta.uid(), meta.gid())
+}
+
+#[cfg(not(unix))]
+fn owner(_meta: &fs::Metadata) -> (u32, u32) {
+    (0, 0)
+}
+
+/// `(atime, mtime, ctime)` in nanoseconds; ctime is the inode change time
+/// on Unix and the creation time elsewhere, as in CPython.
+#[cfg(unix)]
+fn times_ns(meta: &fs::Metadata) -> (i64, i64, i64) {
+    use std::os::unix::fs::MetadataExt;
+    let ns = |secs: i64, nsec: i64| secs * 1_000_000_000 + nsec;
+    (
+        ns(meta.atime(), meta.atime_nsec()),
+        ns(meta.mtime(), meta.mtime_nsec()),
+        ns(meta.ctime(), meta.ctime_nsec()),
+    )
+}
+
+#[cfg(not(unix))]
+fn times_ns(meta: &fs::Metadata) -> (i64, i64, i64) {
+    let ns = |time: std::io::Result<std::time::SystemTime>| {
+        time.ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_nanos() as i64)
+    };
+    (ns(meta.accessed()), ns(meta.modified()), ns(meta.created()))
+}
+
+/// Python-equivalent pathlib.Path: a PurePath with filesystem operations.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Path {
+    pure: PurePath,
+}
+
+impl Deref for Path {
+    type Target = PurePath;
+
+    fn deref(&self) -> &PurePath {
+        &self.pure
+    }
+}
+
+impl From<PurePath> for Path {
+    fn from(pure: PurePath) -> Self {
+        Path { pure }
+    }
+}
+
+impl From<&str> for Path {
+    fn from(s: &str) -> Self {
+        Path::new(s)
+    }
+}
+
+/// `path / other` as Python's `__truediv__`, and `"str" / path` as its
+/// `__rtruediv__`, for owned and borrowed paths.
+macro_rules! impl_div {
+    ($($path:ty),*) => {$(
+        impl_div!(@rhs $path, $path; &str, String, &String, PurePath, &PurePath, Path, &Path);
+        impl_div!(@rhs $path, &$path; &str, String, &String, PurePath, &PurePath, Path, &Path);
+
+        impl Div<$path> for &str {
+            type Output = $path;
+
+            fn div(self, rhs: $path) -> $path {
+                <$path>::new(self).joinpath(rhs)
+            }
+        }
+
+        impl Div<&$path> for &str {
+            type Output = $path;
+
+            fn div(self, rhs: &$path) -> $path {
+                <$path>::new(self).joinpath(rhs)
+            }
+        }
+    )*};
+    (@rhs $out:ty, $lhs:ty; $($rhs:ty),*) => {$(
+        impl Div<$rhs> for $lhs {
+            type Output = $out;
+
+            fn div(self, rhs: $rhs) -> $out {
+                self.joinpath(rhs)
+            }
+        }
+    )*};
+}
+
+impl_div!(PurePath, Path);
+
+impl AsRef<std::path::Path> for Path {
+    fn as_ref(&self) -> &std::path::Path {
+        self.pure.as_std_path()
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.pure.fmt(f)
+    }
+}
+
+impl PyFormat for Path {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        let name = if cfg!(windows) {
+            "WindowsPath"
+        } else {
+            "PosixPath"
+        };
+        format_object(self, spec, name)
+    }
+}
+
+impl Path {
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Path {
+            pure: PurePath::new(path),
+        }
+    }
+
+    /// Python-equivalent Path.cwd().
+    pub fn cwd() -> PyResult<Path> {
+        Ok(Path::new(std::env::current_dir()?))
+    }
+
+    /// Python-equivalent Path.home().
+    pub fn home() -> PyResult<Path> {
+        let var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+        std::env::var_os(var)
+            .map(Path::new)
+            .ok_or_else(|| PyException::runtime_error("Could not determine home directory."))
+    }
+
+    pub fn parent(&self) -> Path {
+        self.pure.parent().into()
+    }
+
+    pub fn parents(&self) -> Vec<Path> {
+        self.pure.parents().into_iter().map(Path::from).collect()
+    }
+
+    /// Python-equivalent Path(*pathsegments).
+    pub fn new_from_parts<I, P>(segments: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<std::path::Path>,
+    {
+        PurePath::new_from_parts(segments).into()
+    }
+
+    pub fn joinpath<P: AsRef<std::path::Path>>(&self, other: P) -> Path {
+        self.pure.joinpath(other).into()
+    }
+
+    pub fn with_name(&self, name: &str) -> PyResult<Path> {
+        self.pure.with_name(name).map(Path::from)
+    }
+
+    pub fn with_suffix(&self, suffix: &str) -> PyResult<Path> {
+        self.pure.with_suffix(suffix).map(Path::from)
+    }
+
+    pub fn with_stem(&self, stem: &str) -> PyResult<Path> {
+        self.pure.with_stem(stem).map(Path::from)
+    }
+
+    pub fn relative_to<P: AsRef<std::path::Path>>(
+        &self,
+        other: P,
+        walk_up: bool,
+    ) -> PyResult<Path> {
+        self.pure.relative_to(other, walk_up).map(Path::from)
+    }
+
+    /// Python-equivalent Path.expanduser(): a leading `~` or `~user` becomes
+    /// that user's home directory.
+    pub fn expanduser(&self) -> PyResult<Path> {
+        let parts = self.parts();
+        let Some(first) = parts.first().filter(|p| p.starts_with('~')) else {
+            return Ok(self.clone());
+        };
+        let home = match &first[1..] {
+            "" => Path::home()?,
+            user => user_home(user)
+                .ok_or_else(|| PyException::runtime_error("Could not determine home directory."))?,
+        };
+        Ok(parts[1..]
+            .iter()
+            .fold(home, |path, part| path.joinpath(part)))
+    }
+
+    pub fn exists(&self) -> bool {
+        self.as_std_path().exists()
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.as_std_path().is_file()
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.as_std_path().is_dir()
+    }
+
+    pub fn is_symlink(&self) -> bool {
+        self.as_std_path().is_symlink()
+    }
+
+    /// Python-equivalent Path.absolute(): joins with the cwd without resolving symlinks.
+    pub fn absolute(&self) -> PyResult<Path> {
+        if self.is_absolute() {
+            return Ok(self.clone());
+        }
+        Ok(Path::cwd()?.joinpath(self))
+    }
+
+    /// Python-equivalent Path.resolve(); non-existent paths fall back to absolute().
+    pub fn resolve(&self) -> PyResult<Path> {
+        match fs::canonicalize(self) {
+            Ok(p) => Ok(Path::new(p)),
+            Err(_) => self.absolute(),
+        }
+    }
+
+    /// Python-equivalent Path.stat().
+    pub fn stat(&self) -> PyResult<FileStats> {
+        os::stat(self, true)
+    }
+
+    /// Python-equivalent Path.lstat(): a symlink's own stats.
+    pub fn lstat(&self) -> PyResult<FileStats> {
+        os::stat(self, false)
+    }
+
+    /// Python-equivalent Path.iterdir().
+    pub fn iterdir(&self) -> PyResult<Vec<Path>> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(self)? {
+            entries.push(self.joinpath(entry?.file_name()));
+        }
+        Ok(entries)
+    }
+
+    /// Python-equivalent Path.glob(pattern); `**` matches any number of directories.
+    pub fn glob(&self, pattern: &str) -> PyResult<Vec<Path>> {
+        if pattern.is_empty() {
+            return Err(PyException::value_error(format!(
+                "Unacceptable pattern: '{}'",
+                pattern
+            )));
+        }
+        if PurePath::new(pattern).is_absolute() {
+            return Err(PyException::new(
+                "NotImplementedError",
+                "Non-relative patterns are unsupported",
+            ));
+        }
+        let root = self.to_string();
+        Ok(glob::iglob(pattern, Some(&root), true, true)
+            .map(|rel| self.joinpath(rel))
+            .collect())
+    }
+
+    /// Python-equivalent Path.rglob(pattern).
+    pub fn rglob(&self, pattern: &str) -> PyResult<Vec<Path>> {
+        self.glob(&format!("**/{}", pattern))
+    }
+
+    pub fn read_text(&self) -> PyResult<String> {
+        Ok(fs::read_to_string(self)?)
+    }
+
+    pub fn read_bytes(&self) -> PyResult<Vec<u8>> {
+        Ok(fs::read(self)?)
+    }
+
+    /// Python-equivalent Path.write_text(data); returns the number of characters written.
+    pub fn write_text(&self, data: &str) -> PyResult<usize> {
+        fs::write(self, data)?;
+        Ok(data.chars().count())
+    }
+
+    pub fn write_bytes(&self, data: &[u8]) -> PyResult<usize> {
+        fs::write(self, data)?;
+        Ok(data.len())
+    }
+
+    /// Python-equivalent Path.open(mode).
+    pub fn open(&self, mode: &str) -> PyResult<io::PyFile> {
+        io::open(self, mode)
+    }
+
+    /// The file's lines without their endings, read lazily: a streaming
+    /// `read_text().splitlines()` for "\n", "\r\n" and "\r" endings.
+    pub fn read_lines(&self) -> PyResult<ReadLines> {
+        let file = BufReader::new(fs::File::open(self)?);
+        Ok(ReadLines(io::Lines::with_newline(
+            file,
+            io::Newline::Universal,
+        )))
+    }
+
+    /// Writes each line followed by "\n", replacing the file, and returns
+    /// the number of lines written.
+    pub fn write_lines<I>(&self, lines: I) -> PyResult<usize>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let mut out = BufWriter::new(fs::File::create(self)?);
+        let mut count = 0;
+        for line in lines {
+            out.write_all(line.as_ref().as_bytes())?;
+            out.write_all(b"\n")?;
+            count += 1;
+        }
+        out.flush()?;
+        Ok(count)
+    }
+
+    /// Appends `data`, creating the file if needed, as
+    /// `open(path, 'a').write(data)`; returns the number of characters.
+    pub fn append_text(&self, data: &str) -> PyResult<usize> {
+        self.append_bytes(data.as_bytes())?;
+        Ok(data.chars().count())
+    }
+
+    pub fn append_bytes(&self, data: &[u8]) -> PyResult<usize> {
+        let mut file = fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(self)?;
+        file.write_all(data)?;
+        Ok(data.len())
+    }
+
+    /// Like [`write_text`](Self::write_text), but readers see either the
+    /// old contents or the new, never a partial write; see
+    /// [`atomic_write`](Self::atomic_write).
+    pub fn write_text_atomic(&self, data: &str) -> PyResult<usize> {
+        self.write_bytes_atomic(data.as_bytes())?;
+        Ok(data.chars().count())
+    }
+
+    /// Like [`write_bytes`](Self::write_bytes), but atomic as
+    /// [`atomic_write`](Self::atomic_write) is.
+    pub fn write_bytes_atomic(&self, data: &[u8]) -> PyResult<usize> {
+        self.atomic_write(|file| {
+            file.write_all(data)?;
+            Ok(data.len())
+        })
+    }
+
+    /// The `with atomic_write(path) as f:` pattern: `f` writes a temporary
+    /// file beside this path, which then replaces it in one rename. If `f`
+    /// fails, the temporary file is removed and the path left alone.
+    ///
+    /// The data is flushed to disk before the rename and the directory
+    /// after it, so after a crash the path holds the old contents or the
+    /// new. An existing file's permission bits carry over, and a symlink is
+    /// followed so that the link itself survives.
+    pub fn atomic_write<R, F>(&self, f: F) -> PyResult<R>
+    where
+        F: FnOnce(&mut fs::File) -> PyResult<R>,
+    {
+        let target = match fs::symlink_metadata(self) {
+            Ok(meta) if meta.file_type().is_symlink() => fs::canonicalize(self)?,
+            _ => self.as_ref().to_path_buf(),
+        };
+        let tmp = os::temp_sibling(&target);
+        let mut file = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp)?;
+        let written = f(&mut file).and_then(|value| {
+            if let Ok(meta) = fs::metadata(&target) {
+                file.set_permissions(meta.permissions())?;
+            }
+            file.sync_all()?;
+            drop(file);
+            os::replace_path(&tmp, &target)?;
+            os::sync_parent(&target)?;
+            Ok(value)
+        });
+        if written.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        written
+    }
+
+    /// Python-equivalent Path.mkdir(parents=..., exist_ok=...).
+    pub fn mkdir(&self, parents: bool, exist_ok: bool) -> PyResult<()> {
+        if parents && !exist_ok && self.exists() {
+            return Err(PyException::new(
+                "FileExistsError",
+                format!("File exists: '{}'", self),
+            ));
+        }
+        let result = if parents {
+            fs::create_dir_all(self)
+        } else {
+            fs::create_dir(self)
+        };
+        match result {
+            Err(err)
+                if err.kind() == std::io::ErrorKind::AlreadyExists && exist_ok && self.is_dir() =>
+            {
+                Ok(())
+            }
+            other => other.map_err(PyException::from),
+        }
+    }
+
+    /// Python-equivalent Path.touch(exist_ok=...).
+    pub fn touch(&self, exist_ok: bool) -> PyResult<()> {
+        if self.exists() {
+            if !exist_ok {
+                return Err(PyException::new(
+                    "FileExistsError",
+                    format!("File exists: '{}'", self),
+                ));
+            }
+            fs::OpenOptions::new().append(true).open(self)?;
+            return Ok(());
+        }
+        fs::File::create(self)?;
+        Ok(())
+    }
+
+    /// Python-equivalent Path.unlink(missing_ok=...).
+    pub fn unlink(&self, missing_ok: bool) -> PyResult<()> {
+        match fs::remove_file(self) {
+            Err(err) if missing_ok && err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            other => other.map_err(PyException::from),
+        }
+    }
+
+    pub fn rmdir(&self) -> PyResult<()> {
+        Ok(fs::remove_dir(self)?)
+    }
+
+    /// Python-equivalent Path.hardlink_to(target): makes this path a hard link
+    /// to `target`.
+    pub fn hardlink_to<P: AsRef<std::path::Path>>(&self, target: P) -> PyResult<()> {
+        Ok(fs::hard_link(target, self)?)
+    }
+
+    /// Python-equivalent Path.walk(top_down, follow_symlinks): lazily yields
+    /// `(dirpath, dirnames, filenames)`. Unfollowed symlinks to directories
+    /// are listed as files, as in CPython.
+    pub fn walk(&self, top_down: bool, follow_symlinks: bool) -> PathWalk {
+        PathWalk(os::walk(self, top_down, follow_symlinks).path_semantics())
+    }
+
+    /// Python-equivalent Path.rename(target); returns the new path.
+    pub fn rename<P: AsRef<std::path::Path>>(&self, target: P) -> PyResult<Path> {
+        fs::rename(self, target.as_ref())?;
+        Ok(Path::new(target.as_ref()))
+    }
+
+    /// Python-equivalent Path.replace(target): renames over any existing
+    /// `target`, falling back to a copy across filesystems as
+    /// [`os::replace`] does. Returns the new path.
+    pub fn replace<P: AsRef<std::path::Path>>(&self, target: P) -> PyResult<Path> {
+        os::replace_path(self.as_ref(), target.as_ref())?;
+        Ok(Path::new(target.as_ref()))
+    }
+
+    pub fn samefile<P: AsRef<std::path::Path>>(&self, other: P) -> PyResult<bool> {
+        Ok(fs::canonicalize(self)? == fs::canonicalize(other)?)
+    }
+}
+
+/// Iterator returned by [`Path::walk`].
+pub struct PathWalk(os::Walk);
+
+impl PathWalk {
+    /// Keeps only the subdirectories of the last yielded directory for which
+    /// `keep` holds, like editing `dirnames` in place in a top-down walk.
+    pub fn prune<F: FnMut(&str) -> bool>(&mut self, keep: F) {
+        self.0.prune(keep)
+    }
+}
+
+impl Iterator for PathWalk {
+    type Item = (Path, Vec<String>, Vec<String>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .next()
+            .map(|(dir, dirs, files)| (Path::new(dir), dirs, files))
+    }
+}
+
+/// Iterator returned by [`Path::read_lines`].
+pub struct ReadLines(io::Lines<BufReader<fs::File>>);
+
+impl Iterator for ReadLines {
+    type Item = PyResult<String>;
+
+    fn next(&mut self) -> Option<PyResult<String>> {
+        self.0.next().map(|line| {
+            line.map(|mut line| {
+                if line.ends_with('\n') {
+                    line.pop();
+                }
+                line
+            })
+        })
+    }
+}
+
+/// Home directory of `user` from the password database, read as
+/// /etc/passwd since there is no libc binding here.
+#[cfg(unix)]
+pub(crate) fn user_home(user: &str) -> Option<Path> {
+    let passwd = fs::read_to_string("/etc/passwd").ok()?;
+    passwd.lines().find_map(|line| {
+        let fields: Vec<&str> = line.split(':').collect();
+        (fields.len() >= 7 && fields[0] == user).then(|| Path::new(fields[5]))
+    })
+}
+
+/// On Windows, another user's profile sits beside the current one.
+#[cfg(not(unix))]
+pub(crate) fn user_home(user: &str) -> Option<Path> {
+    Path::home().ok().map(|home| home.parent().joinpath(user))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_parts() {
+        let p = PurePath::new("/usr/lib/archive.tar.gz");
+        assert_eq!(p.name(), "archive.tar.gz");
+        assert_eq!(p.suffix(), ".gz");
+        assert_eq!(p.suffixes(), vec![".tar", ".gz"]);
+        assert_eq!(p.stem(), "archive.tar");
+        assert_eq!(p.parent(), PurePath::new("/usr/lib"));
+        assert_eq!(p.parents().len(), 3);
+        assert_eq!(PurePath::new(".bashrc").suffix(), "");
+        assert_eq!(
+            p.with_suffix(".bz2").unwrap(),
+            PurePath::new("/usr/lib/archive.tar.bz2")
+        );
+        assert!(p.with_suffix("bz2").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn normalized_equality_and_ordering() {
+        let p = PurePath::new("./a//b/.");
+        assert_eq!(p.to_string(), "a/b");
+        assert_eq!(p, PurePath::new("a/./b/"));
+        assert_eq!(p.parts(), vec!["a", "b"]);
+        assert_eq!(
+            PurePath::new("a/b/..").parents(),
+            vec![PurePath::new("a/b"), PurePath::new("a"), PurePath::new(".")]
+        );
+        assert!(PurePath::new(".").parts().is_empty());
+
+        assert_eq!(PurePath::new("//a").parts(), vec!["//", "a"]);
+        assert_eq!(PurePath::new("///a").parts(), vec!["/", "a"]);
+        assert_ne!(PurePath::new("//a"), PurePath::new("/a"));
+
+        let mut paths: Vec<Path> = ["a-b", "a/b", "/z", "b", "A"].map(Path::from).to_vec();
+        paths.sort();
+        let sorted: Vec<String> = paths.iter().map(Path::to_string).collect();
+        assert_eq!(sorted, vec!["/z", "A", "a/b", "a-b", "b"]);
+        assert!(PurePath::new("a") < PurePath::new("a/b"));
+
+        let set: std::collections::HashSet<PurePath> =
+            ["a/b", "a//b", "./a/b"].map(PurePath::from).into();
+        assert_eq!(set.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn div_operator_and_multi_part_constructor() {
+        let base = Path::new("a");
+        let p = &base / "b" / String::from("c");
+        assert_eq!(p, Path::new("a/b/c"));
+        assert_eq!(base / PurePath::new("d"), Path::new("a/d"));
+        assert_eq!(
+            PurePath::new("a") / "/etc" / "hosts",
+            PurePath::new("/etc/hosts")
+        );
+        assert_eq!("/srv" / Path::new("app"), Path::new("/srv/app"));
+        assert_eq!(
+            Path::new_from_parts(["a", "b", "c"]),
+            Path::new("a") / "b" / "c"
+        );
+        assert_eq!(
+            PurePath::new_from_parts(["usr", "/etc", "passwd"]).to_string(),
+            "/etc/passwd"
+        );
+        assert_eq!(
+            PurePath::new_from_parts(Vec::<&str>::new()).to_string(),
+            "."
+        );
+    }
+
+    #[test]
+    fn match_glob_patterns() {
+        let p = PurePath::new("/a/b/c.py");
+        assert!(p.match_glob("*.py").unwrap());
+        assert!(p.match_glob("b/*.py").unwrap());
+        assert!(!p.match_glob("a/*.py").unwrap());
+        assert!(p.match_glob("/a/*/*.py").unwrap());
+        assert!(!p.match_glob("/*.py").unwrap());
+        assert!(p.match_glob("[a-c].py").unwrap());
+        assert!(!p.match_glob("[!c].py").unwrap());
+        assert!(p.match_glob("").unwrap_err().is("ValueError"));
+    }
+
+    #[test]
+    fn filesystem_glob() {
+        let root = Path::new(std::env::temp_dir())
+            .joinpath(format!("stdpython_pathlib_{}", std::process::id()));
+        root.joinpath("pkg").mkdir(true, true).unwrap();
+        root.joinpath("pkg/mod.py").write_text("x = 1").unwrap();
+        root.joinpath(".hidden.py").write_text("").unwrap();
+        let mut found: Vec<String> = root
+            .rglob("*.py")
+            .unwrap()
+            .iter()
+            .map(|p| p.name())
+            .collect();
+        found.sort();
+        assert_eq!(found, vec![".hidden.py", "mod.py"]);
+        assert_eq!(root.joinpath("pkg/mod.py").read_text().unwrap(), "x = 1");
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn stem_relative_and_full_match() {
+        let p = PurePath::new("/src/pkg/mod.tar.gz");
+        assert_eq!(
+            p.with_stem("lib").unwrap(),
+            PurePath::new("/src/pkg/lib.gz")
+        );
+        assert!(p.is_relative_to("/src"));
+        assert!(!p.is_relative_to("/sr"));
+        assert!(p.full_match("/src/**/*.gz"));
+        assert!(p.full_match("**/mod.*"));
+        assert!(!p.full_match("*.gz"));
+        assert!(!p.full_match("/src/*.gz"));
+        assert!(PurePath::new("a/b").full_match("a/**/b"));
+    }
+
+    #[test]
+    fn expanduser_and_walk() {
+        let home = Path::home().unwrap();
+        assert_eq!(Path::new("~/x").expanduser().unwrap(), home.joinpath("x"));
+        assert_eq!(Path::new("a/~").expanduser().unwrap(), Path::new("a/~"));
+        #[cfg(unix)]
+        assert_eq!(Path::new("~root").expanduser().unwrap().name(), "root");
+
+        let root = Path::new(std::env::temp_dir())
+            .joinpath(format!("stdpython_walk_{}", std::process::id()));
+        root.joinpath("sub").mkdir(true, true).unwrap();
+        root.joinpath("sub/f.txt").write_text("x").unwrap();
+        root.joinpath("link.txt")
+            .hardlink_to(root.joinpath("sub/f.txt"))
+            .unwrap();
+        let walked: Vec<_> = root.walk(true, false).collect();
+        assert_eq!(walked[0].0, root);
+        assert_eq!(walked[0].1, vec!["sub"]);
+        assert_eq!(walked[0].2, vec!["link.txt"]);
+        assert_eq!(walked[1].2, vec!["f.txt"]);
+        assert_eq!(root.walk(false, false).last().unwrap().0, root);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn line_and_append_helpers() {
+        let path = Path::new(std::env::temp_dir())
+            .joinpath(format!("stdpython_lines_{}.txt", std::process::id()));
+        assert_eq!(path.write_lines(["a", "b"]).unwrap(), 2);
+        path.append_text("c\r\nd\re").unwrap();
+        let lines: Vec<String> = path.read_lines().unwrap().map(Result::unwrap).collect();
+        assert_eq!(lines, ["a", "b", "c", "d", "e"]);
+        assert_eq!(path.append_bytes(b"!").unwrap(), 1);
+        assert_eq!(path.write_text_atomic("new\n").unwrap(), 4);
+        assert_eq!(path.read_text().unwrap(), "new\n");
+        let leftovers = path
+            .parent()
+            .iterdir()
+            .unwrap()
+            .into_iter()
+            .filter(|p| p.name().starts_with(&format!(".{}", path.name())))
+            .count();
+        assert_eq!(leftovers, 0);
+        let mut f = path.open("a").unwrap();
+        io::FileLike::write_str(&mut f, "more").unwrap();
+        drop(f);
+        assert_eq!(path.read_text().unwrap(), "new\nmore");
+        fs::remove_file(&path).unwrap();
+        assert!(path.read_lines().err().unwrap().is("FileNotFoundError"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn atomic_write_keeps_mode_and_links() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = Path::new(std::env::temp_dir())
+            .joinpath(format!("stdpython_atomic_{}", std::process::id()));
+        dir.mkdir(true, true).unwrap();
+        let target = dir.joinpath("config.toml");
+        target.write_text("old").unwrap();
+        fs::set_permissions(&target, fs::Permissions::from_mode(0o640)).unwrap();
+        let link = dir.joinpath("link.toml");
+        std::os::unix::fs::symlink("config.toml", &link).unwrap();
+
+        let err = link
+            .atomic_write(|f| {
+                f.write_all(b"partial")?;
+                Err::<(), _>(PyException::value_error("bad config"))
+            })
+            .unwrap_err();
+        assert!(err.is("ValueError"));
+        assert_eq!(target.read_text().unwrap(), "old");
+        assert_eq!(link.write_bytes_atomic(b"new").unwrap(), 3);
+        assert!(link.is_symlink());
+        assert_eq!(target.read_text().unwrap(), "new");
+        let mode = fs::metadata(&target).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+        assert_eq!(dir.iterdir().unwrap().len(), 2);
+
+        let moved = target.replace(dir.joinpath("final.toml")).unwrap();
+        assert_eq!(moved.read_text().unwrap(), "new");
+        assert!(!target.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn relative_to_walk_up() {
+        let p = PurePath::new("/a/b");
+        assert_eq!(p.relative_to("/a", false).unwrap(), PurePath::new("b"));
+        assert_eq!(p.relative_to("/a/b", false).unwrap(), PurePath::new("."));
+        assert_eq!(
+            PurePath::new("a/b").relative_to(".", false).unwrap(),
+            PurePath::new("a/b")
+        );
+        assert!(p.relative_to("/a/c", false).unwrap_err().is("ValueError"));
+        assert_eq!(p.relative_to("/a/c", true).unwrap(), PurePath::new("../b"));
+        assert_eq!(
+            p.relative_to("/x/y", true).unwrap(),
+            PurePath::new("../../a/b")
+        );
+        assert_eq!(
+            PurePath::new("a/b").relative_to("x", true).unwrap(),
+            PurePath::new("../a/b")
+        );
+        assert!(PurePath::new("a/b").relative_to("/x", true).is_err());
+        assert!(p.relative_to("/a/../c", true).is_err());
+        assert_eq!(
+            PurePath::new("/a/./b/../c").normalize(),
+            PurePath::new("/a/c")
+        );
+    }
+
+    #[test]
+    fn stat_exposes_posix_fields() {
+        let path = Path::new(std::env::temp_dir())
+            .joinpath(format!("stdpython_stat_{}", std::process::id()));
+        path.write_text("hello").unwrap();
+        let st = path.stat().unwrap();
+        assert_eq!(st.st_size, 5);
+        assert!(st.is_file && crate::stdlib::stat::s_isreg(st.st_mode));
+        assert_eq!(st.st_nlink, 1);
+        assert!(st.st_mtime > 1.6e9);
+        assert_eq!((st.st_mtime_ns as f64 / 1e9), st.st_mtime);
+        #[cfg(unix)]
+        {
+            let link = path.with_suffix(".lnk").unwrap();
+            std::os::unix::fs::symlink(&path, &link).unwrap();
+            let lst = link.lstat().unwrap();
+            assert!(lst.is_symlink && crate::stdlib::stat::s_islnk(lst.st_mode));
+            assert_eq!(link.stat().unwrap().st_ino, st.st_ino);
+            link.unlink(false).unwrap();
+        }
+        path.unlink(false).unwrap();
+    }
+}
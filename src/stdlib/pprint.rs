@@ -0,0 +1,620 @@
+//! Python-equivalent pprint module for [`PyValue`]s: pformat() lays out
+//! nested dicts, lists, tuples and sets one item per line once their repr
+//! no longer fits the width, and wraps long strings into adjacent literals,
+//! matching CPython's output character for character.
+//!
+//! Values are trees, so the recursion guard CPython needs for
+//! self-referencing containers has no counterpart here.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::{PyKwargs, PyRepr, PyValue};
+
+/// Python-equivalent pprint.PrettyPrinter, configured builder-style.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrettyPrinter {
+    indent: usize,
+    width: i64,
+    depth: Option<usize>,
+    compact: bool,
+    sort_dicts: bool,
+    underscore_numbers: bool,
+}
+
+impl Default for PrettyPrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Python's `len()` of a repr.
+fn width_of(s: &str) -> i64 {
+    s.chars().count() as i64
+}
+
+/// pprint's `_safe_key` ordering: values that Python can order compare
+/// normally, anything else by type name.
+fn safe_cmp(a: &PyValue, b: &PyValue) -> Ordering {
+    a.partial_cmp(b)
+        .unwrap_or_else(|| a.type_name().cmp(b.type_name()))
+}
+
+fn sorted_items(
+    dict: &crate::types::PyDictionary<PyValue, PyValue>,
+    sort: bool,
+) -> Vec<(&PyValue, &PyValue)> {
+    let mut items: Vec<_> = dict.iter().collect();
+    if sort {
+        items.sort_by(|(k1, v1), (k2, v2)| safe_cmp(k1, k2).then_with(|| safe_cmp(v1, v2)));
+    }
+    items
+}
+
+/// str.splitlines(keepends=True).
+fn split_lines(s: &str) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let end = match c {
+            '\r' if chars.peek().is_some_and(|&(_, n)| n == '\n') => {
+                chars.next();
+                i + 2
+            }
+            '\n'
+            | '\r'
+            | '\x0b'
+            | '\x0c'
+            | '\x1c'..='\x1e'
+            | '\u{85}'
+            | '\u{2028}'
+            | '\u{2029}' => i + c.len_utf8(),
+            _ => continue,
+        };
+        lines.push(&s[start..end]);
+        start = end;
+    }
+    if start < s.len() {
+        lines.push(&s[start..]);
+    }
+    lines
+}
+
+/// `re.findall(r'\S*\s*', line)` without the empty match at the end.
+fn words_with_spaces(line: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        let word = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let space = rest[word..]
+            .find(|c: char| !c.is_whitespace())
+            .map_or(rest.len(), |n| word + n);
+        parts.push(&rest[..space]);
+        rest = &rest[space..];
+    }
+    parts
+}
+
+fn group_digits(n: i64) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push('_');
+        }
+        out.push(c);
+    }
+    if n < 0 {
+        out.insert(0, '-');
+    }
+    out
+}
+
+impl PrettyPrinter {
+    /// PrettyPrinter() with CPython's defaults: indent 1, width 80, no
+    /// depth limit, not compact, dicts sorted.
+    pub fn new() -> Self {
+        PrettyPrinter {
+            indent: 1,
+            width: 80,
+            depth: None,
+            compact: false,
+            sort_dicts: true,
+            underscore_numbers: false,
+        }
+    }
+
+    /// PrettyPrinter(**kwargs): indent, width, depth, compact, sort_dicts
+    /// and underscore_numbers, validated as CPython does.
+    pub fn from_kwargs(kwargs: PyKwargs) -> PyResult<Self> {
+        let mut kwargs = kwargs.for_call("PrettyPrinter");
+        let mut printer = PrettyPrinter::new();
+        if let Some(indent) = kwargs.get_int("indent")? {
+            if indent < 0 {
+                return Err(PyException::value_error("indent must be >= 0"));
+            }
+            printer.indent = indent as usize;
+        }
+        if let Some(depth) = kwargs.get_int("depth")? {
+            if depth <= 0 {
+                return Err(PyException::value_error("depth must be > 0"));
+            }
+            printer.depth = Some(depth as usize);
+        }
+        if let Some(width) = kwargs.get_int("width")? {
+            if width == 0 {
+                return Err(PyException::value_error("width must be != 0"));
+            }
+            printer.width = width;
+        }
+        if let Some(compact) = kwargs.get_bool("compact") {
+            printer.compact = compact;
+        }
+        if let Some(sort_dicts) = kwargs.get_bool("sort_dicts") {
+            printer.sort_dicts = sort_dicts;
+        }
+        if let Some(underscore_numbers) = kwargs.get_bool("underscore_numbers") {
+            printer.underscore_numbers = underscore_numbers;
+        }
+        kwargs.reject_unknown()?;
+        Ok(printer)
+    }
+
+    /// `indent=n`: extra spaces per nesting level.
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = indent;
+        self
+    }
+
+    /// `width=n`: the line length to fit output into where possible.
+    pub fn width(mut self, width: usize) -> Self {
+        self.width = width as i64;
+        self
+    }
+
+    /// `depth=n`: containers nested deeper print as `[...]`, `{...}` or
+    /// `(...)`.
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.depth = Some(depth);
+        self
+    }
+
+    /// `compact=True`: as many sequence items per line as fit.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// `sort_dicts=False`: dict items in insertion order.
+    pub fn sort_dicts(mut self, sort_dicts: bool) -> Self {
+        self.sort_dicts = sort_dicts;
+        self
+    }
+
+    /// `underscore_numbers=True`: ints grouped as `1_000_000`.
+    pub fn underscore_numbers(mut self, underscore_numbers: bool) -> Self {
+        self.underscore_numbers = underscore_numbers;
+        self
+    }
+
+    /// Python-equivalent PrettyPrinter.pformat(object).
+    pub fn pformat(&self, object: &PyValue) -> String {
+        let mut out = String::new();
+        self.format(object, &mut out, 0, 0, 0);
+        out
+    }
+
+    /// Python-equivalent PrettyPrinter.pprint(object), to sys.stdout.
+    #[cfg(feature = "std")]
+    pub fn pprint(&self, object: &PyValue) {
+        crate::print(self.pformat(object));
+    }
+
+    /// Python-equivalent PrettyPrinter.isreadable(object): false when the
+    /// value holds one whose repr cannot be read back. As in CPython,
+    /// `depth` is not applied here.
+    pub fn isreadable(&self, object: &PyValue) -> bool {
+        let unlimited = PrettyPrinter {
+            depth: None,
+            ..self.clone()
+        };
+        unlimited.safe_repr(object, 0).1
+    }
+
+    /// `_safe_repr`: the one-line repr honouring `depth`, `sort_dicts` and
+    /// `underscore_numbers`, and whether it reads back as the value.
+    fn safe_repr(&self, object: &PyValue, level: usize) -> (String, bool) {
+        let cut = self.depth.is_some_and(|depth| level >= depth);
+        match object {
+            PyValue::Int(n) if self.underscore_numbers => (group_digits(*n), true),
+            PyValue::Dict(dict) if !dict.is_empty() => {
+                if cut {
+                    return ("{...}".to_string(), false);
+                }
+                let mut readable = true;
+                let parts: Vec<String> = sorted_items(dict, self.sort_dicts)
+                    .into_iter()
+                    .map(|(k, v)| {
+                        let (krepr, kreadable) = self.safe_repr(k, level + 1);
+                        let (vrepr, vreadable) = self.safe_repr(v, level + 1);
+                        readable &= kreadable && vreadable;
+                        format!("{}: {}", krepr, vrepr)
+                    })
+                    .collect();
+                (format!("{{{}}}", parts.join(", ")), readable)
+            }
+            PyValue::List(_) | PyValue::Tuple(_) => {
+                let (items, open, close) = match object {
+                    PyValue::List(list) => (list.as_slice(), "[", "]"),
+                    PyValue::Tuple(tuple) if tuple.len() == 1 => (tuple.as_slice(), "(", ",)"),
+                    PyValue::Tuple(tuple) => (tuple.as_slice(), "(", ")"),
+                    _ => unreachable!(),
+                };
+                if items.is_empty() {
+                    return (format!("{}{}", open, close), true);
+                }
+                if cut {
+                    return (format!("{}...{}", open, close), false);
+                }
+                let mut readable = true;
+                let parts: Vec<String> = items
+                    .iter()
+                    .map(|item| {
+                        let (rep, item_readable) = self.safe_repr(item, level + 1);
+                        readable &= item_readable;
+                        rep
+                    })
+                    .collect();
+                (format!("{}{}{}", open, parts.join(", "), close), readable)
+            }
+            _ => {
+                let rep = object.py_repr();
+                let readable = !rep.is_empty() && !rep.starts_with('<');
+                (rep, readable)
+            }
+        }
+    }
+
+    fn format(
+        &self,
+        object: &PyValue,
+        out: &mut String,
+        indent: i64,
+        allowance: i64,
+        level: usize,
+    ) {
+        let rep = self.safe_repr(object, level).0;
+        let max_width = self.width - indent - allowance;
+        if width_of(&rep) <= max_width {
+            out.push_str(&rep);
+            return;
+        }
+        let level = level + 1;
+        match object {
+            PyValue::Dict(dict) => {
+                out.push('{');
+                self.pad(out);
+                if !dict.is_empty() {
+                    let items = sorted_items(dict, self.sort_dicts);
+                    self.format_dict_items(&items, out, indent, allowance + 1, level);
+                }
+                out.push('}');
+            }
+            PyValue::List(list) => {
+                out.push('[');
+                self.format_items(list.iter(), out, indent, allowance + 1, level);
+                out.push(']');
+            }
+            PyValue::Tuple(tuple) => {
+                let end = if tuple.len() == 1 { ",)" } else { ")" };
+                out.push('(');
+                self.format_items(
+                    tuple.iter(),
+                    out,
+                    indent,
+                    allowance + end.len() as i64,
+                    level,
+                );
+                out.push_str(end);
+            }
+            PyValue::Set(set) if !set.is_empty() => {
+                let mut items: Vec<&PyValue> = set.iter().collect();
+                items.sort_by(|a, b| safe_cmp(a, b));
+                out.push('{');
+                self.format_items(items.into_iter(), out, indent, allowance + 1, level);
+                out.push('}');
+            }
+            PyValue::Str(s) if !s.is_empty() => {
+                self.format_str(s.as_str(), out, indent, allowance, level)
+            }
+            _ => out.push_str(&rep),
+        }
+    }
+
+    /// The extra spaces after an opening bracket when `indent` > 1.
+    fn pad(&self, out: &mut String) {
+        for _ in 1..self.indent {
+            out.push(' ');
+        }
+    }
+
+    fn format_dict_items(
+        &self,
+        items: &[(&PyValue, &PyValue)],
+        out: &mut String,
+        indent: i64,
+        allowance: i64,
+        level: usize,
+    ) {
+        let indent = indent + self.indent as i64;
+        let delimnl = format!(",\n{}", " ".repeat(indent as usize));
+        for (i, (key, value)) in items.iter().enumerate() {
+            let last = i == items.len() - 1;
+            let rep = self.safe_repr(key, level).0;
+            out.push_str(&rep);
+            out.push_str(": ");
+            let allowance = if last { allowance } else { 1 };
+            self.format(value, out, indent + width_of(&rep) + 2, allowance, level);
+            if !last {
+                out.push_str(&delimnl);
+            }
+        }
+    }
+
+    fn format_items<'a, I: Iterator<Item = &'a PyValue>>(
+        &self,
+        items: I,
+        out: &mut String,
+        indent: i64,
+        allowance: i64,
+        level: usize,
+    ) {
+        let indent = indent + self.indent as i64;
+        self.pad(out);
+        let delimnl = format!(",\n{}", " ".repeat(indent as usize));
+        let mut delim = "";
+        let mut max_width = self.width - indent + 1;
+        let mut width = max_width;
+        let mut items = items.peekable();
+        while let Some(item) = items.next() {
+            let last = items.peek().is_none();
+            if last {
+                max_width -= allowance;
+                width -= allowance;
+            }
+            if self.compact {
+                let rep = self.safe_repr(item, level).0;
+                let w = width_of(&rep) + 2;
+                if width < w {
+                    width = max_width;
+                    if !delim.is_empty() {
+                        delim = &delimnl;
+                    }
+                }
+                if width >= w {
+                    width -= w;
+                    out.push_str(delim);
+                    delim = ", ";
+                    out.push_str(&rep);
+                    continue;
+                }
+            }
+            out.push_str(delim);
+            delim = &delimnl;
+            self.format(item, out, indent, if last { allowance } else { 1 }, level);
+        }
+    }
+
+    /// A long str as adjacent literals split at word boundaries, in
+    /// parentheses at the top level.
+    fn format_str(
+        &self,
+        s: &str,
+        out: &mut String,
+        mut indent: i64,
+        mut allowance: i64,
+        level: usize,
+    ) {
+        let lines = split_lines(s);
+        if level == 1 {
+            indent += 1;
+            allowance += 1;
+        }
+        let max_width = self.width - indent;
+        let mut max_width1 = max_width;
+        let mut chunks = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            let last_line = i == lines.len() - 1;
+            let rep = line.py_repr();
+            if last_line {
+                max_width1 -= allowance;
+            }
+            if width_of(&rep) <= max_width1 {
+                chunks.push(rep);
+                continue;
+            }
+            let parts = words_with_spaces(line);
+            let mut max_width2 = max_width;
+            let mut current = String::new();
+            for (j, part) in parts.iter().enumerate() {
+                let candidate = format!("{}{}", current, part);
+                if j == parts.len() - 1 && last_line {
+                    max_width2 -= allowance;
+                }
+                if width_of(&candidate.py_repr()) > max_width2 {
+                    if !current.is_empty() {
+                        chunks.push(current.py_repr());
+                    }
+                    current = part.to_string();
+                } else {
+                    current = candidate;
+                }
+            }
+            if !current.is_empty() {
+                chunks.push(current.py_repr());
+            }
+        }
+        if chunks.len() == 1 {
+            out.push_str(&chunks[0]);
+            return;
+        }
+        if level == 1 {
+            out.push('(');
+        }
+        let delim = format!("\n{}", " ".repeat(indent as usize));
+        out.push_str(&chunks.join(&delim));
+        if level == 1 {
+            out.push(')');
+        }
+    }
+}
+
+/// Python-equivalent pprint.pformat(object) with default settings.
+pub fn pformat(object: &PyValue) -> String {
+    PrettyPrinter::new().pformat(object)
+}
+
+/// Python-equivalent pprint.pformat(object, **kwargs); see
+/// [`PrettyPrinter::from_kwargs`] for the accepted keywords.
+pub fn pformat_with(object: &PyValue, kwargs: PyKwargs) -> PyResult<String> {
+    Ok(PrettyPrinter::from_kwargs(kwargs)?.pformat(object))
+}
+
+/// Python-equivalent pprint.pprint(object) with default settings.
+#[cfg(feature = "std")]
+pub fn pprint(object: &PyValue) {
+    PrettyPrinter::new().pprint(object)
+}
+
+/// Python-equivalent pprint.pprint(object, **kwargs).
+#[cfg(feature = "std")]
+pub fn pprint_with(object: &PyValue, kwargs: PyKwargs) -> PyResult<()> {
+    PrettyPrinter::from_kwargs(kwargs)?.pprint(object);
+    Ok(())
+}
+
+/// Python-equivalent pprint.pp(object): pprint() keeping dict order.
+#[cfg(feature = "std")]
+pub fn pp(object: &PyValue) {
+    PrettyPrinter::new().sort_dicts(false).pprint(object)
+}
+
+/// Python-equivalent pprint.saferepr(object): the one-line form, with
+/// dicts sorted.
+pub fn saferepr(object: &PyValue) -> String {
+    PrettyPrinter::new().safe_repr(object, 0).0
+}
+
+/// Python-equivalent pprint.isreadable(object).
+pub fn isreadable(object: &PyValue) -> bool {
+    PrettyPrinter::new().isreadable(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PyDictionary, PyList, PySet, PyTuple};
+    use alloc::vec;
+
+    fn list(items: Vec<PyValue>) -> PyValue {
+        PyValue::List(PyList::from_vec(items))
+    }
+
+    fn dict(items: Vec<(&str, PyValue)>) -> PyValue {
+        let d: PyDictionary<PyValue, PyValue> = items
+            .into_iter()
+            .map(|(k, v)| (PyValue::from(k), v))
+            .collect();
+        PyValue::Dict(d)
+    }
+
+    fn ints(range: core::ops::Range<i64>) -> PyValue {
+        list(range.map(PyValue::Int).collect())
+    }
+
+    fn sample() -> PyValue {
+        dict(vec![
+            ("zeta", ints(0..12)),
+            (
+                "alpha",
+                PyValue::Tuple(PyTuple::from_vec(vec![PyValue::from("x")])),
+            ),
+            (
+                "mid",
+                dict(vec![
+                    ("b", PyValue::from("a fairly long string value here")),
+                    ("a", PyValue::None),
+                ]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn short_values_stay_on_one_line() {
+        assert_eq!(pformat(&ints(0..3)), "[0, 1, 2]");
+        assert_eq!(
+            pformat(&dict(vec![("b", PyValue::Int(1)), ("a", PyValue::Int(2))])),
+            "{'a': 2, 'b': 1}"
+        );
+        assert_eq!(
+            saferepr(&dict(vec![("b", PyValue::Int(1)), ("a", PyValue::Int(2))])),
+            "{'a': 2, 'b': 1}"
+        );
+    }
+
+    #[test]
+    fn wraps_like_cpython() {
+        let printer = PrettyPrinter::new().width(40);
+        assert_eq!(
+            printer.pformat(&sample()),
+            "{'alpha': ('x',),\n 'mid': {'a': None,\n         'b': 'a fairly long string '\n              'value here'},\n 'zeta': [0,\n          1,\n          2,\n          3,\n          4,\n          5,\n          6,\n          7,\n          8,\n          9,\n          10,\n          11]}"
+        );
+        let printer = printer.compact(true).sort_dicts(false);
+        assert_eq!(
+            printer.pformat(&sample()),
+            "{'zeta': [0, 1, 2, 3, 4, 5, 6, 7, 8, 9,\n          10, 11],\n 'alpha': ('x',),\n 'mid': {'b': 'a fairly long string '\n              'value here',\n         'a': None}}"
+        );
+    }
+
+    #[test]
+    fn depth_indent_and_sets() {
+        let nested = list(vec![
+            PyValue::Int(1),
+            list(vec![PyValue::Int(2), ints(3..5)]),
+        ]);
+        let kwargs = PyKwargs::new().with("depth", 2);
+        assert_eq!(pformat_with(&nested, kwargs).unwrap(), "[1, [2, [...]]]");
+        assert!(PrettyPrinter::new().depth(2).isreadable(&nested));
+        assert!(isreadable(&nested));
+
+        let set: PySet<PyValue> = (0..30).rev().map(PyValue::Int).collect();
+        let out = PrettyPrinter::new()
+            .width(20)
+            .indent(4)
+            .pformat(&PyValue::Set(set));
+        assert!(out.starts_with("{   0,\n    1,\n"));
+        assert!(out.ends_with("\n    29}"));
+        assert!(pformat_with(&nested, PyKwargs::new().with("width", 0)).is_err());
+        assert_eq!(
+            pformat_with(
+                &PyValue::Int(1234567),
+                PyKwargs::new().with("underscore_numbers", true)
+            )
+            .unwrap(),
+            "1_234_567"
+        );
+    }
+
+    #[test]
+    fn top_level_strings_wrap_in_parentheses() {
+        let text = PyValue::from("The quick brown fox jumps over the lazy dog.\nSecond line");
+        assert_eq!(
+            PrettyPrinter::new().width(30).pformat(&text),
+            "('The quick brown fox jumps '\n 'over the lazy dog.\\n'\n 'Second line')"
+        );
+    }
+}
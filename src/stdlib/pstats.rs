@@ -0,0 +1,290 @@
+//! Python-equivalent pstats module: sorting and printing the totals a
+//! [`cprofile::Profile`](crate::stdlib::cprofile::Profile) collects.
+
+use std::cmp::Ordering;
+use std::fmt::Write;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::cprofile::{FunctionStats, Profile};
+use crate::stdlib::inspect::Code;
+use crate::stdlib::io::write_stdout;
+
+/// One sort_stats() key: what it compares and how print_stats() names it.
+struct SortKey {
+    names: &'static [&'static str],
+    description: &'static str,
+    compare: fn(&(Code, FunctionStats), &(Code, FunctionStats)) -> Ordering,
+}
+
+fn descending(a: f64, b: f64) -> Ordering {
+    b.partial_cmp(&a).unwrap_or(Ordering::Equal)
+}
+
+/// pstats.Stats.sort_arg_dict_default: numbers sort largest first, names
+/// alphabetically.
+const SORT_KEYS: &[SortKey] = &[
+    SortKey {
+        names: &["calls", "ncalls"],
+        description: "call count",
+        compare: |a, b| b.1.ncalls.cmp(&a.1.ncalls),
+    },
+    SortKey {
+        names: &["cumulative", "cumtime"],
+        description: "cumulative time",
+        compare: |a, b| descending(a.1.cumtime, b.1.cumtime),
+    },
+    SortKey {
+        names: &["filename", "file", "module"],
+        description: "file name",
+        compare: |a, b| a.0.co_filename.cmp(b.0.co_filename),
+    },
+    SortKey {
+        names: &["line"],
+        description: "line number",
+        compare: |a, b| a.0.co_firstlineno.cmp(&b.0.co_firstlineno),
+    },
+    SortKey {
+        names: &["name"],
+        description: "function name",
+        compare: |a, b| a.0.co_name.cmp(b.0.co_name),
+    },
+    SortKey {
+        names: &["nfl"],
+        description: "name/file/line",
+        compare: |a, b| {
+            (a.0.co_name, a.0.co_filename, a.0.co_firstlineno).cmp(&(
+                b.0.co_name,
+                b.0.co_filename,
+                b.0.co_firstlineno,
+            ))
+        },
+    },
+    SortKey {
+        names: &["pcalls"],
+        description: "primitive call count",
+        compare: |a, b| b.1.pcalls.cmp(&a.1.pcalls),
+    },
+    SortKey {
+        names: &["stdname"],
+        description: "standard name",
+        compare: |a, b| std_string(&a.0).cmp(&std_string(&b.0)),
+    },
+    SortKey {
+        names: &["time", "tottime"],
+        description: "internal time",
+        compare: |a, b| descending(a.1.tottime, b.1.tottime),
+    },
+];
+
+/// `filename:lineno(function)`, as pstats prints a function.
+fn std_string(code: &Code) -> String {
+    format!(
+        "{}:{}({})",
+        code.co_filename, code.co_firstlineno, code.co_name
+    )
+}
+
+/// Python-equivalent pstats.Stats.
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    entries: Vec<(Code, FunctionStats)>,
+    sort_type: Option<String>,
+    strip_dirs: bool,
+}
+
+impl Stats {
+    /// Python-equivalent pstats.Stats(profile).
+    pub fn new(profile: &Profile) -> Self {
+        let mut stats = Stats::default();
+        stats.add(profile);
+        stats
+    }
+
+    /// Python-equivalent Stats.add(profile): sums another run's totals in.
+    pub fn add(&mut self, profile: &Profile) -> &mut Self {
+        for (code, new) in profile.stats() {
+            match self.entries.iter_mut().find(|(c, _)| *c == code) {
+                Some((_, old)) => {
+                    old.ncalls += new.ncalls;
+                    old.pcalls += new.pcalls;
+                    old.tottime += new.tottime;
+                    old.cumtime += new.cumtime;
+                }
+                None => self.entries.push((code, new)),
+            }
+        }
+        self
+    }
+
+    /// Python-equivalent Stats.strip_dirs(): prints file names without
+    /// their directories.
+    pub fn strip_dirs(&mut self) -> &mut Self {
+        self.strip_dirs = true;
+        self
+    }
+
+    /// Python-equivalent Stats.sort_stats(*keys). Later keys break ties in
+    /// earlier ones.
+    pub fn sort_stats(&mut self, keys: &[&str]) -> PyResult<&mut Self> {
+        let keys = keys
+            .iter()
+            .map(|key| {
+                SORT_KEYS
+                    .iter()
+                    .find(|k| k.names.contains(key))
+                    .ok_or_else(|| PyException::key_error(key))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        self.entries.sort_by(|a, b| {
+            keys.iter()
+                .map(|k| (k.compare)(a, b))
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        let descriptions: Vec<&str> = keys.iter().map(|k| k.description).collect();
+        self.sort_type = Some(descriptions.join(", "));
+        Ok(self)
+    }
+
+    /// The functions in the current order.
+    pub fn entries(&self) -> &[(Code, FunctionStats)] {
+        &self.entries
+    }
+
+    fn function_name(&self, code: &Code) -> String {
+        if !self.strip_dirs {
+            return std_string(code);
+        }
+        let file = code.co_filename.rsplit(['/', '\\']).next();
+        format!(
+            "{}:{}({})",
+            file.unwrap_or(code.co_filename),
+            code.co_firstlineno,
+            code.co_name
+        )
+    }
+
+    /// The report Stats.print_stats(amount) prints, where `amount` limits
+    /// how many functions are listed.
+    pub fn format_stats(&self, amount: Option<usize>) -> String {
+        let total_calls: u64 = self.entries.iter().map(|(_, s)| s.ncalls).sum();
+        let prim_calls: u64 = self.entries.iter().map(|(_, s)| s.pcalls).sum();
+        let total_tt: f64 = self.entries.iter().map(|(_, s)| s.tottime).sum();
+        let mut out = format!("         {} function calls ", total_calls);
+        if total_calls != prim_calls {
+            let _ = write!(out, "({} primitive calls) ", prim_calls);
+        }
+        let _ = writeln!(out, "in {:.3} seconds\n", total_tt);
+        let listed = match amount {
+            Some(n) => &self.entries[..n.min(self.entries.len())],
+            None => &self.entries[..],
+        };
+        match &self.sort_type {
+            Some(sort_type) => {
+                let _ = writeln!(out, "   Ordered by: {}", sort_type);
+            }
+            None => out.push_str("   Random listing order was used\n"),
+        }
+        if listed.len() < self.entries.len() {
+            let _ = writeln!(
+                out,
+                "   List reduced from {} to {} due to restriction <{}>",
+                self.entries.len(),
+                listed.len(),
+                listed.len()
+            );
+        }
+        if listed.is_empty() {
+            return out;
+        }
+        out.push_str("\n   ncalls  tottime  percall  cumtime  percall filename:lineno(function)\n");
+        let per_call = |total: f64, calls: u64| match calls {
+            0 => String::from("        "),
+            n => format!("{:8.3}", total / n as f64),
+        };
+        for (code, stats) in listed {
+            let mut calls = stats.ncalls.to_string();
+            if stats.ncalls != stats.pcalls {
+                let _ = write!(calls, "/{}", stats.pcalls);
+            }
+            let _ = writeln!(
+                out,
+                "{:>9} {:8.3} {} {:8.3} {} {}",
+                calls,
+                stats.tottime,
+                per_call(stats.tottime, stats.ncalls),
+                stats.cumtime,
+                per_call(stats.cumtime, stats.pcalls),
+                self.function_name(code)
+            );
+        }
+        out.push_str("\n\n");
+        out
+    }
+
+    /// Python-equivalent Stats.print_stats(amount), to sys.stdout.
+    pub fn print_stats(&self, amount: Option<usize>) -> PyResult<&Self> {
+        write_stdout(&self.format_stats(amount))?;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::inspect::enter_frame;
+
+    fn fib(n: u32) -> u32 {
+        let _frame = enter_frame("fib", "/tmp/prof.py", 2);
+        if n < 2 {
+            n
+        } else {
+            fib(n - 1) + fib(n - 2)
+        }
+    }
+
+    fn main() -> u32 {
+        let _frame = enter_frame("main", "/tmp/prof.py", 3);
+        fib(5)
+    }
+
+    /// Times vary run to run; blank them out of a report.
+    fn without_times(report: &str) -> String {
+        report
+            .split(' ')
+            .map(|word| {
+                if word.contains('.') && word.parse::<f64>().is_ok() {
+                    "T"
+                } else {
+                    word
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    #[test]
+    fn reports_like_cpython() {
+        let profile = Profile::new();
+        profile.runcall(main);
+        let mut stats = Stats::new(&profile);
+        stats.sort_stats(&["cumulative"]).unwrap();
+        assert_eq!(
+            without_times(&stats.format_stats(None)),
+            without_times(
+                "         16 function calls (2 primitive calls) in 0.000 seconds\n\n   \
+                 Ordered by: cumulative time\n\n   \
+                 ncalls  tottime  percall  cumtime  percall filename:lineno(function)\n        \
+                 1    0.000    0.000    0.000    0.000 /tmp/prof.py:3(main)\n     \
+                 15/1    0.000    0.000    0.000    0.000 /tmp/prof.py:2(fib)\n\n\n"
+            )
+        );
+        stats.strip_dirs().sort_stats(&["ncalls"]).unwrap();
+        let report = stats.format_stats(Some(1));
+        assert!(report.contains(
+            "   Ordered by: call count\n   List reduced from 2 to 1 due to restriction <1>\n"
+        ));
+        assert!(report.ends_with(" prof.py:2(fib)\n\n\n"));
+        assert!(stats.sort_stats(&["bogus"]).unwrap_err().is("KeyError"));
+    }
+}
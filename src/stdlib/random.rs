@@ -0,0 +1,480 @@
+//! Python-equivalent random module.
+//!
+//! [`Random`] is CPython's Mersenne Twister, seeded the way `random.seed(n)`
+//! seeds it for a non-negative integer, so a seeded program draws the same
+//! numbers as under CPython. The distributions use the standard algorithms:
+//! inverse CDF for the exponential, Marsaglia–Tsang for gamma, Best–Fisher for
+//! von Mises and BTRS for the binomial.
+
+use std::collections::hash_map::RandomState;
+use std::f64::consts::{PI, TAU};
+use std::hash::{BuildHasher, Hasher};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::sync::Lock;
+use crate::types::PyBytes;
+
+const N: usize = 624;
+const M: usize = 397;
+
+/// `4 * exp(-0.5) / sqrt(2.0)`, the Kinderman–Monahan bound.
+const NV_MAGICCONST: f64 = 1.7155277699214135;
+
+/// Python-equivalent random.Random.
+#[derive(Clone)]
+pub struct Random {
+    state: [u32; N],
+    index: usize,
+}
+
+impl Random {
+    /// Python-equivalent random.Random(seed).
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Random {
+            state: [0; N],
+            index: N,
+        };
+        rng.seed(seed);
+        rng
+    }
+
+    /// A generator seeded from OS entropy, like random.Random().
+    pub fn from_entropy() -> Self {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u32(std::process::id());
+        Random::new(hasher.finish())
+    }
+
+    /// Python-equivalent seed(n) for an integer seed.
+    pub fn seed(&mut self, seed: u64) {
+        let low = seed as u32;
+        let high = (seed >> 32) as u32;
+        if high == 0 {
+            self.init_by_array(&[low]);
+        } else {
+            self.init_by_array(&[low, high]);
+        }
+    }
+
+    fn init_genrand(&mut self, s: u32) {
+        self.state[0] = s;
+        for i in 1..N {
+            let prev = self.state[i - 1];
+            self.state[i] = 1_812_433_253u32
+                .wrapping_mul(prev ^ (prev >> 30))
+                .wrapping_add(i as u32);
+        }
+        self.index = N;
+    }
+
+    fn init_by_array(&mut self, key: &[u32]) {
+        self.init_genrand(19_650_218);
+        let (mut i, mut j) = (1, 0);
+        for _ in 0..N.max(key.len()) {
+            let prev = self.state[i - 1];
+            self.state[i] = (self.state[i] ^ (prev ^ (prev >> 30)).wrapping_mul(1_664_525))
+                .wrapping_add(key[j])
+                .wrapping_add(j as u32);
+            i += 1;
+            j += 1;
+            if i >= N {
+                self.state[0] = self.state[N - 1];
+                i = 1;
+            }
+            if j >= key.len() {
+                j = 0;
+            }
+        }
+        for _ in 0..N - 1 {
+            let prev = self.state[i - 1];
+            self.state[i] = (self.state[i] ^ (prev ^ (prev >> 30)).wrapping_mul(1_566_083_941))
+                .wrapping_sub(i as u32);
+            i += 1;
+            if i >= N {
+                self.state[0] = self.state[N - 1];
+                i = 1;
+            }
+        }
+        self.state[0] = 0x8000_0000;
+    }
+
+    fn twist(&mut self) {
+        for i in 0..N {
+            let y = (self.state[i] & 0x8000_0000) | (self.state[(i + 1) % N] & 0x7fff_ffff);
+            let mut next = self.state[(i + M) % N] ^ (y >> 1);
+            if y & 1 != 0 {
+                next ^= 0x9908_b0df;
+            }
+            self.state[i] = next;
+        }
+        self.index = 0;
+    }
+
+    fn next_u32(&mut self) -> u32 {
+        if self.index >= N {
+            self.twist();
+        }
+        let mut y = self.state[self.index];
+        self.index += 1;
+        y ^= y >> 11;
+        y ^= (y << 7) & 0x9d2c_5680;
+        y ^= (y << 15) & 0xefc6_0000;
+        y ^ (y >> 18)
+    }
+
+    /// Python-equivalent random(): a float in [0.0, 1.0) with 53 random bits.
+    pub fn random(&mut self) -> f64 {
+        let a = (self.next_u32() >> 5) as f64;
+        let b = (self.next_u32() >> 6) as f64;
+        (a * 67_108_864.0 + b) * (1.0 / 9_007_199_254_740_992.0)
+    }
+
+    /// Python-equivalent getrandbits(k) for k up to 64.
+    pub fn getrandbits(&mut self, k: u32) -> PyResult<u64> {
+        if k > 64 {
+            return Err(PyException::new(
+                "OverflowError",
+                "getrandbits() supports at most 64 bits here",
+            ));
+        }
+        let mut result = 0u64;
+        let mut shift = 0;
+        while shift < k {
+            let remaining = k - shift;
+            let mut word = self.next_u32();
+            if remaining < 32 {
+                word >>= 32 - remaining;
+            }
+            result |= u64::from(word) << shift;
+            shift += 32;
+        }
+        Ok(result)
+    }
+
+    /// Python-equivalent randbytes(n).
+    pub fn randbytes(&mut self, n: usize) -> PyBytes {
+        let mut out = Vec::with_capacity(n);
+        while out.len() < n {
+            let take = (n - out.len()).min(4);
+            let mut word = self.next_u32();
+            if take < 4 {
+                word >>= 32 - 8 * take as u32;
+            }
+            out.extend_from_slice(&word.to_le_bytes()[..take]);
+        }
+        PyBytes::new(out)
+    }
+
+    /// Python-equivalent uniform(a, b).
+    pub fn uniform(&mut self, a: f64, b: f64) -> f64 {
+        a + (b - a) * self.random()
+    }
+
+    /// Python-equivalent expovariate(lambd): `-log(1 - random()) / lambd`.
+    pub fn expovariate(&mut self, lambd: f64) -> PyResult<f64> {
+        if lambd == 0.0 {
+            return Err(PyException::new(
+                "ZeroDivisionError",
+                "float division by zero",
+            ));
+        }
+        Ok(-(1.0 - self.random()).ln() / lambd)
+    }
+
+    /// Python-equivalent normalvariate(mu, sigma), by Kinderman–Monahan.
+    pub fn normalvariate(&mut self, mu: f64, sigma: f64) -> f64 {
+        loop {
+            let u1 = self.random();
+            let u2 = 1.0 - self.random();
+            let z = NV_MAGICCONST * (u1 - 0.5) / u2;
+            if z * z / 4.0 <= -u2.ln() {
+                return mu + z * sigma;
+            }
+        }
+    }
+
+    /// Python-equivalent gammavariate(alpha, beta): shape `alpha`, scale `beta`.
+    pub fn gammavariate(&mut self, alpha: f64, beta: f64) -> PyResult<f64> {
+        if !(alpha > 0.0 && beta > 0.0) {
+            return Err(PyException::value_error(
+                "gammavariate: alpha and beta must be > 0.0",
+            ));
+        }
+        if alpha < 1.0 {
+            // Gamma(a) = Gamma(a + 1) * U^(1/a).
+            let u = 1.0 - self.random();
+            return Ok(self.gammavariate(alpha + 1.0, beta)? * u.powf(1.0 / alpha));
+        }
+        // Marsaglia and Tsang, "A Simple Method for Generating Gamma Variables".
+        let d = alpha - 1.0 / 3.0;
+        let c = 1.0 / (9.0 * d).sqrt();
+        loop {
+            let x = self.normalvariate(0.0, 1.0);
+            let v = 1.0 + c * x;
+            if v <= 0.0 {
+                continue;
+            }
+            let v = v * v * v;
+            let u = 1.0 - self.random();
+            let x2 = x * x;
+            if u < 1.0 - 0.0331 * x2 * x2 || u.ln() < 0.5 * x2 + d * (1.0 - v + v.ln()) {
+                return Ok(d * v * beta);
+            }
+        }
+    }
+
+    /// Python-equivalent vonmisesvariate(mu, kappa), by Best and Fisher.
+    pub fn vonmisesvariate(&mut self, mu: f64, kappa: f64) -> f64 {
+        if kappa <= 1e-6 {
+            return TAU * self.random();
+        }
+        let s = 0.5 / kappa;
+        let r = s + (1.0 + s * s).sqrt();
+        let z = loop {
+            let z = (PI * self.random()).cos();
+            let d = z / (r + z);
+            let u2 = self.random();
+            if u2 < 1.0 - d * d || u2 <= (1.0 - d) * d.exp() {
+                break z;
+            }
+        };
+        let q = 1.0 / r;
+        let f = (q + z) / (1.0 + q * z);
+        let theta = if self.random() > 0.5 {
+            mu + f.acos()
+        } else {
+            mu - f.acos()
+        };
+        theta.rem_euclid(TAU)
+    }
+
+    /// Python-equivalent binomialvariate(n, p): successes in `n` trials.
+    pub fn binomialvariate(&mut self, n: i64, p: f64) -> PyResult<i64> {
+        if n < 0 {
+            return Err(PyException::value_error("n must be non-negative"));
+        }
+        if !(0.0..=1.0).contains(&p) {
+            return Err(PyException::value_error(
+                "p must be in the range 0.0 <= p <= 1.0",
+            ));
+        }
+        if p == 0.0 {
+            return Ok(0);
+        }
+        if p == 1.0 {
+            return Ok(n);
+        }
+        if n == 1 {
+            return Ok(i64::from(self.random() < p));
+        }
+        if p > 0.5 {
+            return Ok(n - self.binomialvariate(n, 1.0 - p)?);
+        }
+        let nf = n as f64;
+        if nf * p < 10.0 {
+            // Count geometric gaps between successes.
+            let c = (1.0 - p).ln();
+            let (mut x, mut y) = (0, 0i64);
+            if c == 0.0 {
+                // p is too small for 1.0 - p to differ from 1.0.
+                return Ok(x);
+            }
+            loop {
+                let gap = (self.random().ln() / c).floor() as i64;
+                y = y.saturating_add(gap.saturating_add(1));
+                if y > n {
+                    return Ok(x);
+                }
+                x += 1;
+            }
+        }
+        // Hörmann's BTRS: transformed rejection with squeeze.
+        let spq = (nf * p * (1.0 - p)).sqrt();
+        let b = 1.15 + 2.53 * spq;
+        let a = -0.0873 + 0.0248 * b + 0.01 * p;
+        let c = nf * p + 0.5;
+        let vr = 0.92 - 4.2 / b;
+        let alpha = (2.83 + 5.1 / b) * spq;
+        let lpq = (p / (1.0 - p)).ln();
+        let m = ((nf + 1.0) * p).floor();
+        let h = ln_gamma(m + 1.0) + ln_gamma(nf - m + 1.0);
+        loop {
+            let u = self.random() - 0.5;
+            let us = 0.5 - u.abs();
+            let k = ((2.0 * a / us + b) * u + c).floor();
+            if k < 0.0 || k > nf {
+                continue;
+            }
+            let v = self.random();
+            if us >= 0.07 && v <= vr {
+                return Ok(k as i64);
+            }
+            let v = v * alpha / (a / (us * us) + b);
+            if v.ln() <= h - ln_gamma(k + 1.0) - ln_gamma(nf - k + 1.0) + (k - m) * lpq {
+                return Ok(k as i64);
+            }
+        }
+    }
+}
+
+/// log Γ(x) for x ≥ 1, by the Lanczos approximation (g = 7).
+fn ln_gamma(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEF: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    let x = x - 1.0;
+    let t = x + G + 0.5;
+    let sum = COEF[1..]
+        .iter()
+        .enumerate()
+        .fold(COEF[0], |acc, (i, c)| acc + c / (x + i as f64 + 1.0));
+    0.5 * TAU.ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
+
+/// The generator behind the module-level functions, seeded on first use.
+static INSTANCE: Lock<Option<Random>> = Lock::new(None);
+
+fn with_instance<R>(f: impl FnOnce(&mut Random) -> R) -> R {
+    let mut instance = INSTANCE.lock();
+    f(instance.get_or_insert_with(Random::from_entropy))
+}
+
+/// Python-equivalent random.seed(n).
+pub fn seed(n: u64) {
+    with_instance(|rng| rng.seed(n))
+}
+
+/// Python-equivalent random.random().
+pub fn random() -> f64 {
+    with_instance(Random::random)
+}
+
+/// Python-equivalent random.getrandbits(k).
+pub fn getrandbits(k: u32) -> PyResult<u64> {
+    with_instance(|rng| rng.getrandbits(k))
+}
+
+/// Python-equivalent random.randbytes(n).
+pub fn randbytes(n: usize) -> PyBytes {
+    with_instance(|rng| rng.randbytes(n))
+}
+
+/// Python-equivalent random.uniform(a, b).
+pub fn uniform(a: f64, b: f64) -> f64 {
+    with_instance(|rng| rng.uniform(a, b))
+}
+
+/// Python-equivalent random.expovariate(lambd).
+pub fn expovariate(lambd: f64) -> PyResult<f64> {
+    with_instance(|rng| rng.expovariate(lambd))
+}
+
+/// Python-equivalent random.normalvariate(mu, sigma).
+pub fn normalvariate(mu: f64, sigma: f64) -> f64 {
+    with_instance(|rng| rng.normalvariate(mu, sigma))
+}
+
+/// Python-equivalent random.gammavariate(alpha, beta).
+pub fn gammavariate(alpha: f64, beta: f64) -> PyResult<f64> {
+    with_instance(|rng| rng.gammavariate(alpha, beta))
+}
+
+/// Python-equivalent random.vonmisesvariate(mu, kappa).
+pub fn vonmisesvariate(mu: f64, kappa: f64) -> f64 {
+    with_instance(|rng| rng.vonmisesvariate(mu, kappa))
+}
+
+/// Python-equivalent random.binomialvariate(n, p).
+pub fn binomialvariate(n: i64, p: f64) -> PyResult<i64> {
+    with_instance(|rng| rng.binomialvariate(n, p))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mean_var(xs: &[f64]) -> (f64, f64) {
+        let n = xs.len() as f64;
+        let mean = xs.iter().sum::<f64>() / n;
+        let var = xs.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / n;
+        (mean, var)
+    }
+
+    #[test]
+    fn matches_cpython_stream() {
+        let mut rng = Random::new(42);
+        assert_eq!(rng.random(), 0.6394267984578837);
+        assert_eq!(rng.random(), 0.025010755222666936);
+        let mut rng = Random::new(42);
+        assert_eq!(rng.randbytes(6).as_bytes(), b"\x9dy\xb1\xa3\x80\x1c");
+        let mut rng = Random::new(2u64.pow(40) + 7);
+        assert_eq!(rng.getrandbits(40).unwrap(), 822974591194);
+        let mut rng = Random::new(5);
+        assert_eq!(rng.getrandbits(20).unwrap(), 653159);
+        assert_eq!(rng.getrandbits(64).unwrap(), 13683554807294777401);
+    }
+
+    #[test]
+    fn distributions_have_expected_moments() {
+        let mut rng = Random::new(7);
+        let n = 40_000;
+
+        let xs: Vec<f64> = (0..n).map(|_| rng.expovariate(2.0).unwrap()).collect();
+        let (mean, var) = mean_var(&xs);
+        assert!((mean - 0.5).abs() < 0.02 && (var - 0.25).abs() < 0.02);
+        assert!(xs.iter().all(|&x| x >= 0.0));
+
+        for (alpha, beta) in [(0.5, 1.0), (3.0, 2.0), (9.0, 0.5)] {
+            let xs: Vec<f64> = (0..n)
+                .map(|_| rng.gammavariate(alpha, beta).unwrap())
+                .collect();
+            let (mean, var) = mean_var(&xs);
+            assert!((mean - alpha * beta).abs() < 0.05 * alpha * beta);
+            assert!((var - alpha * beta * beta).abs() < 0.1 * alpha * beta * beta);
+        }
+
+        // For von Mises, E[cos(x - mu)] = I1(kappa) / I0(kappa); 0.6978 at kappa = 2.
+        let xs: Vec<f64> = (0..n).map(|_| rng.vonmisesvariate(1.0, 2.0)).collect();
+        assert!(xs.iter().all(|&x| (0.0..TAU).contains(&x)));
+        let mean_cos = xs.iter().map(|x| (x - 1.0).cos()).sum::<f64>() / n as f64;
+        assert!((mean_cos - 0.6978).abs() < 0.02);
+
+        for (trials, p) in [(20, 0.2), (1000, 0.3), (500, 0.9)] {
+            let xs: Vec<f64> = (0..n)
+                .map(|_| rng.binomialvariate(trials, p).unwrap() as f64)
+                .collect();
+            let (mean, var) = mean_var(&xs);
+            let t = trials as f64;
+            assert!((mean - t * p).abs() < 0.02 * t * p);
+            assert!((var - t * p * (1.0 - p)).abs() < 0.1 * t * p * (1.0 - p));
+            assert!(xs.iter().all(|&x| (0.0..=t).contains(&x)));
+        }
+    }
+
+    #[test]
+    fn invalid_parameters_raise() {
+        let mut rng = Random::new(1);
+        assert!(rng.expovariate(0.0).unwrap_err().is("ZeroDivisionError"));
+        assert!(rng.gammavariate(0.0, 1.0).unwrap_err().is("ValueError"));
+        assert!(rng.binomialvariate(-1, 0.5).unwrap_err().is("ValueError"));
+        assert!(rng.binomialvariate(5, 1.5).unwrap_err().is("ValueError"));
+        for n in [1, 50] {
+            let err = rng.binomialvariate(n, f64::NAN).unwrap_err();
+            assert!(err.is("ValueError"));
+        }
+        assert_eq!(rng.binomialvariate(5, 0.0).unwrap(), 0);
+        assert_eq!(rng.binomialvariate(5, 1.0).unwrap(), 5);
+        assert_eq!(rng.binomialvariate(100, 1e-17).unwrap(), 0);
+        assert_eq!(rng.binomialvariate(i64::MAX, 1e-300).unwrap(), 0);
+        assert_eq!(randbytes(3).len(), 3);
+    }
+}
@@ -0,0 +1,172 @@
+//! Python-equivalent readline module: the history input() keeps of lines
+//! typed at a terminal.
+//!
+//! Editing within a line is left to the terminal's own line discipline, so
+//! there is no key binding or completion support.
+
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::sync::Lock;
+
+struct History {
+    items: Vec<String>,
+    /// Entries kept by write_history_file(); negative means all of them.
+    length: i64,
+    auto: bool,
+}
+
+static HISTORY: Lock<History> = Lock::new(History {
+    items: Vec::new(),
+    length: -1,
+    auto: true,
+});
+
+/// What input() does with a line read from a terminal: record it unless it
+/// is empty or repeats the previous entry, as CPython does.
+pub(crate) fn record_input(line: &str) {
+    let mut history = HISTORY.lock();
+    if history.auto && !line.is_empty() && history.items.last().map(String::as_str) != Some(line) {
+        history.items.push(line.to_string());
+    }
+}
+
+/// Python-equivalent readline.set_auto_history(enabled).
+pub fn set_auto_history(enabled: bool) {
+    HISTORY.lock().auto = enabled;
+}
+
+/// Python-equivalent readline.add_history(line).
+pub fn add_history(line: &str) {
+    HISTORY.lock().items.push(line.to_string());
+}
+
+/// Python-equivalent readline.get_current_history_length().
+pub fn get_current_history_length() -> usize {
+    HISTORY.lock().items.len()
+}
+
+/// Python-equivalent readline.get_history_item(index). `index` is 1-based.
+pub fn get_history_item(index: usize) -> Option<String> {
+    let history = HISTORY.lock();
+    index
+        .checked_sub(1)
+        .and_then(|i| history.items.get(i))
+        .cloned()
+}
+
+fn no_item(pos: usize) -> PyException {
+    PyException::value_error(format!("No history item at position {}", pos))
+}
+
+/// Python-equivalent readline.remove_history_item(pos). `pos` is 0-based.
+pub fn remove_history_item(pos: usize) -> PyResult<()> {
+    let mut history = HISTORY.lock();
+    if pos >= history.items.len() {
+        return Err(no_item(pos));
+    }
+    history.items.remove(pos);
+    Ok(())
+}
+
+/// Python-equivalent readline.replace_history_item(pos, line). `pos` is
+/// 0-based.
+pub fn replace_history_item(pos: usize, line: &str) -> PyResult<()> {
+    let mut history = HISTORY.lock();
+    let item = history.items.get_mut(pos).ok_or_else(|| no_item(pos))?;
+    *item = line.to_string();
+    Ok(())
+}
+
+/// Python-equivalent readline.clear_history().
+pub fn clear_history() {
+    HISTORY.lock().items.clear();
+}
+
+/// Python-equivalent readline.get_history_length().
+pub fn get_history_length() -> i64 {
+    HISTORY.lock().length
+}
+
+/// Python-equivalent readline.set_history_length(length). A negative length
+/// keeps every entry when writing the history file.
+pub fn set_history_length(length: i64) {
+    HISTORY.lock().length = length;
+}
+
+/// The history file used when none is given: `~/.history`.
+fn history_file(filename: Option<&str>) -> PathBuf {
+    match filename {
+        Some(name) => PathBuf::from(name),
+        None => env::var_os("HOME")
+            .map(PathBuf::from)
+            .unwrap_or_default()
+            .join(".history"),
+    }
+}
+
+/// Python-equivalent readline.read_history_file(filename): appends each line
+/// of the file to the history.
+pub fn read_history_file(filename: Option<&str>) -> PyResult<()> {
+    let text = fs::read_to_string(history_file(filename))?;
+    HISTORY
+        .lock()
+        .items
+        .extend(text.lines().map(str::to_string));
+    Ok(())
+}
+
+/// Python-equivalent readline.write_history_file(filename): saves the
+/// history, truncated to the last get_history_length() entries.
+pub fn write_history_file(filename: Option<&str>) -> PyResult<()> {
+    let history = HISTORY.lock();
+    let skip = match usize::try_from(history.length) {
+        Ok(length) => history.items.len().saturating_sub(length),
+        Err(_) => 0,
+    };
+    let mut file = fs::File::create(history_file(filename))?;
+    for line in &history.items[skip..] {
+        writeln!(file, "{}", line)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One test: the history is process-wide.
+    #[test]
+    fn history_round_trips_through_a_file() {
+        clear_history();
+        record_input("first");
+        record_input("first");
+        record_input("");
+        add_history("second");
+        assert_eq!(get_current_history_length(), 2);
+        assert_eq!(get_history_item(1).as_deref(), Some("first"));
+        assert_eq!(get_history_item(0), None);
+        replace_history_item(1, "2nd").unwrap();
+        assert!(remove_history_item(5).unwrap_err().is("ValueError"));
+
+        let path = env::temp_dir().join(format!("rython-history-{}", std::process::id()));
+        let path = path.to_str().unwrap();
+        set_history_length(1);
+        write_history_file(Some(path)).unwrap();
+        set_history_length(-1);
+        clear_history();
+        read_history_file(Some(path)).unwrap();
+        fs::remove_file(path).unwrap();
+        assert_eq!(get_current_history_length(), 1);
+        assert_eq!(get_history_item(1).as_deref(), Some("2nd"));
+
+        set_auto_history(false);
+        record_input("ignored");
+        set_auto_history(true);
+        remove_history_item(0).unwrap();
+        assert_eq!(get_current_history_length(), 0);
+    }
+}
@@ -0,0 +1,165 @@
+//! Python-equivalent resource module, plus the psutil-style memory_info()
+//! benchmarks use to watch the current process.
+//!
+//! getrusage() calls the C library on Linux, Android and Apple platforms;
+//! memory_info() reads /proc on Linux and Android. Elsewhere both raise
+//! OSError rather than failing to build.
+
+use crate::exceptions::{PyException, PyResult};
+
+/// Python-equivalent resource.RUSAGE_SELF.
+pub const RUSAGE_SELF: i32 = 0;
+/// Python-equivalent resource.RUSAGE_CHILDREN: terminated, waited-for
+/// children.
+pub const RUSAGE_CHILDREN: i32 = -1;
+/// Python-equivalent resource.RUSAGE_THREAD (Linux only).
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub const RUSAGE_THREAD: i32 = 1;
+
+/// Python-equivalent resource.struct_rusage. Times are in seconds;
+/// `ru_maxrss` is in kilobytes on Linux and bytes on Apple platforms, as
+/// the C library reports it.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct StructRusage {
+    pub ru_utime: f64,
+    pub ru_stime: f64,
+    pub ru_maxrss: i64,
+    pub ru_ixrss: i64,
+    pub ru_idrss: i64,
+    pub ru_isrss: i64,
+    pub ru_minflt: i64,
+    pub ru_majflt: i64,
+    pub ru_nswap: i64,
+    pub ru_inblock: i64,
+    pub ru_oublock: i64,
+    pub ru_msgsnd: i64,
+    pub ru_msgrcv: i64,
+    pub ru_nsignals: i64,
+    pub ru_nvcsw: i64,
+    pub ru_nivcsw: i64,
+}
+
+/// psutil-style Process().memory_info(), in bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryInfo {
+    /// Resident set size: physical memory in use.
+    pub rss: u64,
+    /// Virtual memory size.
+    pub vms: u64,
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+mod sys {
+    use std::ffi::{c_int, c_long};
+
+    #[cfg(target_vendor = "apple")]
+    type Usec = i32;
+    #[cfg(not(target_vendor = "apple"))]
+    type Usec = c_long;
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct Timeval {
+        pub tv_sec: i64,
+        pub tv_usec: Usec,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    pub struct Rusage {
+        pub ru_utime: Timeval,
+        pub ru_stime: Timeval,
+        /// maxrss through nivcsw, in `struct rusage` order.
+        pub counters: [c_long; 14],
+    }
+
+    extern "C" {
+        pub fn getrusage(who: c_int, usage: *mut Rusage) -> c_int;
+    }
+}
+
+/// Python-equivalent resource.getrusage(who).
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+pub fn getrusage(who: i32) -> PyResult<StructRusage> {
+    let mut usage = sys::Rusage::default();
+    if unsafe { sys::getrusage(who, &mut usage) } < 0 {
+        let err = std::io::Error::last_os_error();
+        if err.kind() == std::io::ErrorKind::InvalidInput {
+            return Err(PyException::value_error("invalid who parameter"));
+        }
+        return Err(err.into());
+    }
+    let seconds = |tv: &sys::Timeval| tv.tv_sec as f64 + tv.tv_usec as f64 * 1e-6;
+    let c = usage.counters.map(i64::from);
+    Ok(StructRusage {
+        ru_utime: seconds(&usage.ru_utime),
+        ru_stime: seconds(&usage.ru_stime),
+        ru_maxrss: c[0],
+        ru_ixrss: c[1],
+        ru_idrss: c[2],
+        ru_isrss: c[3],
+        ru_minflt: c[4],
+        ru_majflt: c[5],
+        ru_nswap: c[6],
+        ru_inblock: c[7],
+        ru_oublock: c[8],
+        ru_msgsnd: c[9],
+        ru_msgrcv: c[10],
+        ru_nsignals: c[11],
+        ru_nvcsw: c[12],
+        ru_nivcsw: c[13],
+    })
+}
+
+/// Python-equivalent resource.getrusage(who).
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+pub fn getrusage(_who: i32) -> PyResult<StructRusage> {
+    Err(PyException::os_error(
+        "getrusage() is not supported on this platform",
+    ))
+}
+
+/// The current process's memory use, from the VmRSS and VmSize lines of
+/// /proc/self/status.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+pub fn memory_info() -> PyResult<MemoryInfo> {
+    let status = std::fs::read_to_string("/proc/self/status")?;
+    let field = |name: &str| -> PyResult<u64> {
+        status
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|rest| rest.trim().strip_suffix("kB"))
+            .and_then(|kb| kb.trim().parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+            .ok_or_else(|| PyException::os_error(format!("no {} in /proc/self/status", name)))
+    };
+    Ok(MemoryInfo {
+        rss: field("VmRSS:")?,
+        vms: field("VmSize:")?,
+    })
+}
+
+/// The current process's memory use.
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+pub fn memory_info() -> PyResult<MemoryInfo> {
+    Err(PyException::os_error(
+        "memory_info() is not supported on this platform",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[test]
+    fn reports_the_current_process() {
+        let usage = getrusage(RUSAGE_SELF).unwrap();
+        assert!(usage.ru_maxrss > 0);
+        assert!(usage.ru_utime + usage.ru_stime >= 0.0);
+        assert!(getrusage(RUSAGE_CHILDREN).is_ok());
+        assert!(getrusage(42).unwrap_err().is("ValueError"));
+        let info = memory_info().unwrap();
+        assert!(info.rss > 0 && info.vms >= info.rss);
+    }
+}
@@ -0,0 +1,305 @@
+//! Python-equivalent select module: select() and poll objects over file
+//! descriptors, pipes, sockets and open files.
+//!
+//! Both are built on poll(2), so select() has no FD_SETSIZE limit. They
+//! call the C library on Linux, Android and Apple platforms and raise
+//! OSError elsewhere. Calls interrupted by a signal are retried with the
+//! remaining timeout (PEP 475).
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use crate::exceptions::{PyException, PyResult};
+
+pub const POLLIN: u16 = 0x1;
+pub const POLLPRI: u16 = 0x2;
+pub const POLLOUT: u16 = 0x4;
+pub const POLLERR: u16 = 0x8;
+pub const POLLHUP: u16 = 0x10;
+pub const POLLNVAL: u16 = 0x20;
+
+/// Anything select() accepts: a raw descriptor or an object with a
+/// fileno().
+pub trait FileDescriptor {
+    fn fileno(&self) -> PyResult<i32>;
+}
+
+impl FileDescriptor for i32 {
+    fn fileno(&self) -> PyResult<i32> {
+        if *self < 0 {
+            return Err(PyException::value_error(format!(
+                "file descriptor cannot be a negative integer ({})",
+                self
+            )));
+        }
+        Ok(*self)
+    }
+}
+
+impl<T: FileDescriptor + ?Sized> FileDescriptor for &T {
+    fn fileno(&self) -> PyResult<i32> {
+        (**self).fileno()
+    }
+}
+
+impl FileDescriptor for crate::stdlib::io::PyFile {
+    fn fileno(&self) -> PyResult<i32> {
+        crate::stdlib::io::PyFile::fileno(self)
+    }
+}
+
+macro_rules! raw_fd_types {
+    ($($ty:ty),*) => {
+        $(
+            #[cfg(unix)]
+            impl FileDescriptor for $ty {
+                fn fileno(&self) -> PyResult<i32> {
+                    Ok(std::os::unix::io::AsRawFd::as_raw_fd(self))
+                }
+            }
+        )*
+    };
+}
+
+raw_fd_types!(
+    std::fs::File,
+    std::net::TcpStream,
+    std::net::TcpListener,
+    std::net::UdpSocket,
+    std::process::ChildStdin,
+    std::process::ChildStdout,
+    std::process::ChildStderr
+);
+
+/// When a timeout in seconds runs out; None never does.
+fn deadline(timeout: Option<f64>) -> PyResult<Option<Instant>> {
+    match timeout {
+        None => Ok(None),
+        Some(t) if t.is_nan() => Err(PyException::value_error("Invalid value NaN (not a number)")),
+        Some(t) if t < 0.0 => Err(PyException::value_error("timeout must be non-negative")),
+        Some(t) => Duration::try_from_secs_f64(t)
+            .ok()
+            .and_then(|wait| Instant::now().checked_add(wait))
+            .map(Some)
+            .ok_or_else(|| {
+                PyException::new(
+                    "OverflowError",
+                    "timestamp out of range for platform time_t",
+                )
+            }),
+    }
+}
+
+/// Time left as a poll(2) timeout in milliseconds, rounded up so a short
+/// wait is not a busy loop; -1 blocks.
+fn remaining_ms(deadline: Option<Instant>) -> i32 {
+    match deadline {
+        None => -1,
+        Some(at) => {
+            let left = at.saturating_duration_since(Instant::now());
+            left.as_nanos().div_ceil(1_000_000).min(i32::MAX as u128) as i32
+        }
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+mod sys {
+    use std::ffi::{c_int, c_short};
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct PollFd {
+        pub fd: c_int,
+        pub events: c_short,
+        pub revents: c_short,
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub type Nfds = std::ffi::c_ulong;
+    #[cfg(target_vendor = "apple")]
+    pub type Nfds = std::ffi::c_uint;
+
+    extern "C" {
+        pub fn poll(fds: *mut PollFd, nfds: Nfds, timeout: c_int) -> c_int;
+    }
+}
+
+/// Polls `(fd, events)` pairs until one is ready or the deadline passes,
+/// returning each descriptor's revents.
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+fn poll_fds(fds: &[(i32, u16)], deadline: Option<Instant>) -> PyResult<Vec<u16>> {
+    let mut pollfds: Vec<sys::PollFd> = fds
+        .iter()
+        .map(|&(fd, events)| sys::PollFd {
+            fd,
+            events: events as i16,
+            revents: 0,
+        })
+        .collect();
+    loop {
+        let timeout = remaining_ms(deadline);
+        let n = unsafe { sys::poll(pollfds.as_mut_ptr(), pollfds.len() as sys::Nfds, timeout) };
+        if n >= 0 {
+            return Ok(pollfds.iter().map(|p| p.revents as u16).collect());
+        }
+        let err = std::io::Error::last_os_error();
+        if err.kind() != std::io::ErrorKind::Interrupted {
+            return Err(err.into());
+        }
+        crate::stdlib::signal::check_signals()?;
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+fn poll_fds(_fds: &[(i32, u16)], _deadline: Option<Instant>) -> PyResult<Vec<u16>> {
+    Err(PyException::os_error(
+        "select is not supported on this platform",
+    ))
+}
+
+/// Python-equivalent select.select(rlist, wlist, xlist, timeout): the
+/// subsets ready for reading, ready for writing and with an exceptional
+/// condition. `timeout=None` blocks; `Some(0.0)` just checks.
+pub fn select<T: FileDescriptor + Clone>(
+    rlist: &[T],
+    wlist: &[T],
+    xlist: &[T],
+    timeout: Option<f64>,
+) -> PyResult<(Vec<T>, Vec<T>, Vec<T>)> {
+    let deadline = deadline(timeout)?;
+    let lists = [(rlist, POLLIN), (wlist, POLLOUT), (xlist, POLLPRI)];
+    let mut fds = Vec::new();
+    for (list, events) in lists {
+        for item in list {
+            fds.push((item.fileno()?, events));
+        }
+    }
+    let revents = poll_fds(&fds, deadline)?;
+    if let Some(i) = revents.iter().position(|r| r & POLLNVAL != 0) {
+        return Err(PyException::os_error(format!(
+            "[Errno 9] Bad file descriptor: {}",
+            fds[i].0
+        )));
+    }
+    let mut revents = revents.into_iter();
+    let mut ready = |list: &[T], wanted: u16| -> Vec<T> {
+        list.iter()
+            .zip(revents.by_ref())
+            .filter(|(_, r)| r & wanted != 0)
+            .map(|(item, _)| item.clone())
+            .collect()
+    };
+    let r = ready(rlist, POLLIN | POLLHUP | POLLERR);
+    let w = ready(wlist, POLLOUT | POLLERR);
+    let x = ready(xlist, POLLPRI);
+    Ok((r, w, x))
+}
+
+/// Python-equivalent select.poll(): a set of registered descriptors that
+/// can be waited on repeatedly.
+#[derive(Debug, Clone, Default)]
+pub struct Poll {
+    fds: BTreeMap<i32, u16>,
+}
+
+/// Python-equivalent select.poll().
+pub fn poll() -> Poll {
+    Poll::default()
+}
+
+impl Poll {
+    /// Python-equivalent poll.register(fd, eventmask); `None` waits for
+    /// POLLIN, POLLPRI and POLLOUT. Registering again replaces the mask.
+    pub fn register<F: FileDescriptor>(&mut self, fd: F, eventmask: Option<u16>) -> PyResult<()> {
+        let fd = fd.fileno()?;
+        self.fds
+            .insert(fd, eventmask.unwrap_or(POLLIN | POLLPRI | POLLOUT));
+        Ok(())
+    }
+
+    /// Python-equivalent poll.modify(fd, eventmask).
+    pub fn modify<F: FileDescriptor>(&mut self, fd: F, eventmask: u16) -> PyResult<()> {
+        let fd = fd.fileno()?;
+        match self.fds.get_mut(&fd) {
+            Some(mask) => {
+                *mask = eventmask;
+                Ok(())
+            }
+            None => Err(PyException::new(
+                "FileNotFoundError",
+                "[Errno 2] No such file or directory",
+            )),
+        }
+    }
+
+    /// Python-equivalent poll.unregister(fd).
+    pub fn unregister<F: FileDescriptor>(&mut self, fd: F) -> PyResult<()> {
+        let fd = fd.fileno()?;
+        self.fds
+            .remove(&fd)
+            .map(|_| ())
+            .ok_or_else(|| PyException::key_error(fd))
+    }
+
+    /// Python-equivalent poll.poll(timeout): `(fd, revents)` for each ready
+    /// descriptor. The timeout is in milliseconds; None or a negative value
+    /// blocks.
+    pub fn poll(&self, timeout: Option<f64>) -> PyResult<Vec<(i32, u16)>> {
+        let timeout = timeout.filter(|ms| *ms >= 0.0).map(|ms| ms / 1000.0);
+        let fds: Vec<(i32, u16)> = self.fds.iter().map(|(&fd, &mask)| (fd, mask)).collect();
+        let revents = poll_fds(&fds, deadline(timeout)?)?;
+        Ok(fds
+            .iter()
+            .zip(revents)
+            .filter(|(_, r)| *r != 0)
+            .map(|(&(fd, _), r)| (fd, r))
+            .collect())
+    }
+}
+
+#[cfg(all(
+    test,
+    any(target_os = "linux", target_os = "android", target_vendor = "apple")
+))]
+mod tests {
+    use super::*;
+    use crate::exceptions::ExceptionArg;
+    use crate::stdlib::os;
+
+    #[test]
+    fn select_reports_ready_pipes() {
+        let (r, w) = os::pipe().unwrap();
+        let (ready, writable, _) = select(&[r], &[w], &[], Some(0.0)).unwrap();
+        assert!(ready.is_empty());
+        assert_eq!(writable, [w]);
+        os::write(w, b"x").unwrap();
+        let (ready, _, _) = select(&[r], &[], &[r], Some(1.0)).unwrap();
+        assert_eq!(ready, [r]);
+        assert!(select(&[r], &[], &[], Some(-1.0))
+            .unwrap_err()
+            .is("ValueError"));
+        for huge in [1e20, f64::INFINITY] {
+            assert!(select(&[r], &[], &[], Some(huge))
+                .unwrap_err()
+                .is("OverflowError"));
+        }
+        assert!(select(&[-1], &[], &[], None).unwrap_err().is("ValueError"));
+
+        let mut p = poll();
+        p.register(r, Some(POLLIN)).unwrap();
+        p.register(w, None).unwrap();
+        let mut events = p.poll(Some(0.0)).unwrap();
+        events.sort();
+        assert_eq!(events, [(r, POLLIN), (w, POLLOUT)]);
+        p.unregister(w).unwrap();
+        let err = p.unregister(w).unwrap_err();
+        assert!(err.is("KeyError"));
+        assert_eq!(err.args, [ExceptionArg::Int(w.into())]);
+        os::read(r, 1).unwrap();
+        assert!(p.poll(Some(10.0)).unwrap().is_empty());
+        os::close(w).unwrap();
+        assert_eq!(p.poll(None).unwrap(), [(r, POLLHUP)]);
+        assert!(p.modify(w, POLLIN).unwrap_err().is("FileNotFoundError"));
+        os::close(r).unwrap();
+    }
+}
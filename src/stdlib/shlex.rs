@@ -0,0 +1,110 @@
+//! Python-equivalent shlex module, in POSIX mode: quoting arguments for a
+//! shell and splitting a command line back into them.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::exceptions::{PyException, PyResult};
+
+/// Characters shlex.quote() leaves bare: `[\w@%+=:,./-]` in ASCII.
+fn is_safe(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_@%+=:,./-".contains(c)
+}
+
+/// Python-equivalent shlex.quote(s): `s` as one shell word, single-quoted
+/// unless it is made only of safe characters.
+pub fn quote(s: &str) -> String {
+    if s.is_empty() {
+        return String::from("''");
+    }
+    if s.chars().all(is_safe) {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', "'\"'\"'"))
+}
+
+/// Python-equivalent shlex.join(split_command).
+pub fn join<S: AsRef<str>>(split_command: &[S]) -> String {
+    let words: Vec<String> = split_command.iter().map(|s| quote(s.as_ref())).collect();
+    words.join(" ")
+}
+
+/// Python-equivalent shlex.split(s) with its defaults: POSIX rules, no
+/// comments.
+pub fn split(s: &str) -> PyResult<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\r' | '\n' => {
+                words.extend(word.take());
+            }
+            '\\' => {
+                let escaped = chars
+                    .next()
+                    .ok_or_else(|| PyException::value_error("No escaped character"))?;
+                word.get_or_insert_with(String::new).push(escaped);
+            }
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(PyException::value_error("No closing quotation")),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        // Inside double quotes a backslash only escapes
+                        // another backslash or the quote.
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('\\' | '"')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(PyException::value_error("No closing quotation")),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(PyException::value_error("No closing quotation")),
+                    }
+                }
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quote_join_and_split_round_trip() {
+        let args = ["echo", "it's", "a b", "", "ok@x.y/z-1"];
+        let line = join(&args);
+        assert_eq!(line, r#"echo 'it'"'"'s' 'a b' '' ok@x.y/z-1"#);
+        assert_eq!(split(&line).unwrap(), args);
+        assert_eq!(
+            split(r#"a\ b "c d" 'e\f' "g\"h\i" j"k"l"#).unwrap(),
+            ["a b", "c d", r"e\f", r#"g"h\i"#, "jkl"]
+        );
+        assert_eq!(
+            split("\"abc").unwrap_err().to_string(),
+            "ValueError: No closing quotation"
+        );
+        assert_eq!(
+            split("a\\").unwrap_err().to_string(),
+            "ValueError: No escaped character"
+        );
+    }
+}
@@ -0,0 +1,236 @@
+//! Python-equivalent signal module. As in CPython, the C-level handler only
+//! records that a signal arrived; the Python-level handler runs later, at
+//! the next [`py_check_signals`](crate::py_check_signals) checkpoint the
+//! compiler places on loop back-edges. SIGINT's default handler raises
+//! KeyboardInterrupt there, so `except KeyboardInterrupt` blocks run.
+//!
+//! Handlers are functions, which are not `Send`, so they live on the
+//! thread that installs handlers first, normally `main` through
+//! [`run_python_main`](crate::run_python_main), and only its checkpoints run
+//! them. As in CPython, signal.signal() raises ValueError on any other
+//! thread, and checkpoints there leave pending signals for the main thread.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::thread::{self, ThreadId};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::{PyCallable, PyFunction, PyValue};
+
+pub const SIGINT: i32 = 2;
+pub const SIGTERM: i32 = 15;
+
+/// Python-equivalent signal.NSIG: one more than the highest signal number.
+pub const NSIG: i32 = 65;
+
+/// What happens when a signal arrives, as signal.signal() takes it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Handler {
+    /// signal.SIG_DFL: the operating system default, e.g. terminating.
+    Default,
+    /// signal.SIG_IGN.
+    Ignore,
+    /// signal.default_int_handler: raises KeyboardInterrupt.
+    DefaultInt,
+    /// A function called as `handler(signum, frame)`; the frame is None.
+    Function(PyFunction),
+}
+
+/// Set by the C-level handler, one flag per signal number, plus one for
+/// any, so checkpoints with nothing pending cost a single load.
+static PENDING: [AtomicBool; NSIG as usize] = [const { AtomicBool::new(false) }; NSIG as usize];
+static ANY_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// The thread that installed the SIGINT handler, and the only one that runs
+/// handlers.
+static MAIN_THREAD: OnceLock<ThreadId> = OnceLock::new();
+
+thread_local! {
+    static HANDLERS: RefCell<BTreeMap<i32, Handler>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+extern "C" fn record_signal(signum: i32) {
+    if let Some(flag) = PENDING.get(signum as usize) {
+        flag.store(true, Ordering::SeqCst);
+        ANY_PENDING.store(true, Ordering::SeqCst);
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+mod sys {
+    use std::ffi::c_int;
+
+    pub const SIG_DFL: usize = 0;
+    pub const SIG_IGN: usize = 1;
+    pub const SIG_ERR: usize = !0;
+
+    extern "C" {
+        pub fn signal(signum: c_int, handler: usize) -> usize;
+        pub fn raise(signum: c_int) -> c_int;
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+fn install(signum: i32, handler: &Handler) -> PyResult<()> {
+    let action = match handler {
+        Handler::Default => sys::SIG_DFL,
+        Handler::Ignore => sys::SIG_IGN,
+        Handler::DefaultInt | Handler::Function(_) => record_signal as extern "C" fn(i32) as usize,
+    };
+    if unsafe { sys::signal(signum, action) } == sys::SIG_ERR {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+fn install(_signum: i32, _handler: &Handler) -> PyResult<()> {
+    Err(PyException::os_error(
+        "signal handlers are not supported on this platform",
+    ))
+}
+
+fn check_signum(signum: i32) -> PyResult<()> {
+    if (1..NSIG).contains(&signum) {
+        Ok(())
+    } else {
+        Err(PyException::value_error("signal number out of range"))
+    }
+}
+
+/// Python-equivalent signal.getsignal(signum).
+pub fn getsignal(signum: i32) -> PyResult<Handler> {
+    check_signum(signum)?;
+    let handler = HANDLERS.with(|handlers| handlers.borrow().get(&signum).cloned());
+    Ok(handler.unwrap_or(match signum {
+        SIGINT => Handler::DefaultInt,
+        _ => Handler::Default,
+    }))
+}
+
+/// Python-equivalent signal.signal(signum, handler): the previous handler.
+pub fn signal(signum: i32, handler: Handler) -> PyResult<Handler> {
+    check_signum(signum)?;
+    // So a later first checkpoint does not replace this handler.
+    install_default_int_handler();
+    if !on_main_thread() {
+        return Err(PyException::value_error(
+            "signal only works in main thread of the main interpreter",
+        ));
+    }
+    let previous = getsignal(signum)?;
+    install(signum, &handler)?;
+    HANDLERS.with(|handlers| handlers.borrow_mut().insert(signum, handler));
+    Ok(previous)
+}
+
+/// Python-equivalent signal.default_int_handler.
+pub fn default_int_handler() -> PyResult<()> {
+    Err(PyException::new("KeyboardInterrupt", ""))
+}
+
+/// Python-equivalent signal.raise_signal(signum): sends the signal to this
+/// process.
+#[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+pub fn raise_signal(signum: i32) -> PyResult<()> {
+    check_signum(signum)?;
+    if unsafe { sys::raise(signum) } != 0 {
+        return Err(std::io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+/// Installs default_int_handler for SIGINT once, as CPython does at startup,
+/// making the calling thread the one that runs handlers.
+pub(crate) fn install_default_int_handler() {
+    MAIN_THREAD.get_or_init(|| {
+        let _ = install(SIGINT, &Handler::DefaultInt);
+        thread::current().id()
+    });
+}
+
+fn on_main_thread() -> bool {
+    MAIN_THREAD.get() == Some(&thread::current().id())
+}
+
+/// Runs the handlers of signals that arrived since the last check. The
+/// first handler to raise stops the check; signals still pending are
+/// handled at the next one. Off the main thread this does nothing.
+pub(crate) fn check_signals() -> PyResult<()> {
+    if !ANY_PENDING.load(Ordering::SeqCst) || !on_main_thread() {
+        return Ok(());
+    }
+    ANY_PENDING.store(false, Ordering::SeqCst);
+    for signum in 1..NSIG {
+        if !PENDING[signum as usize].swap(false, Ordering::SeqCst) {
+            continue;
+        }
+        let result = match getsignal(signum)? {
+            Handler::DefaultInt => default_int_handler(),
+            Handler::Function(func) => func
+                .call_positional(&[PyValue::Int(signum as i64), PyValue::None])
+                .map(drop),
+            Handler::Default | Handler::Ignore => Ok(()),
+        };
+        if let Err(err) = result {
+            // Leave the rest for the next checkpoint.
+            if PENDING.iter().any(|flag| flag.load(Ordering::SeqCst)) {
+                ANY_PENDING.store(true, Ordering::SeqCst);
+            }
+            return Err(err);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(any(target_os = "linux", target_os = "android", target_vendor = "apple"))]
+    #[test]
+    fn handlers_run_at_main_thread_checkpoints() {
+        crate::py_check_signals().unwrap();
+        assert_eq!(getsignal(SIGINT).unwrap(), Handler::DefaultInt);
+        raise_signal(SIGINT).unwrap();
+        let err = crate::py_check_signals().unwrap_err();
+        assert!(err.is("KeyboardInterrupt"));
+        assert_eq!(err.to_string(), "KeyboardInterrupt");
+        crate::py_check_signals().unwrap();
+
+        let handler = PyFunction::binary("handler", |signum, _frame| {
+            Err(PyException::value_error(format!("got {}", signum)))
+        });
+        let previous = signal(SIGINT, Handler::Function(handler)).unwrap();
+        assert_eq!(previous, Handler::DefaultInt);
+        raise_signal(SIGINT).unwrap();
+        assert_eq!(
+            crate::py_check_signals().unwrap_err().to_string(),
+            "ValueError: got 2"
+        );
+        signal(SIGINT, Handler::DefaultInt).unwrap();
+        assert!(signal(0, Handler::Default).unwrap_err().is("ValueError"));
+
+        // Only this thread, which installed the handlers, runs them.
+        let handler = PyFunction::binary("handler", |signum, _frame| {
+            Err(PyException::value_error(format!("got {}", signum)))
+        });
+        signal(SIGTERM, Handler::Function(handler)).unwrap();
+        raise_signal(SIGTERM).unwrap();
+        std::thread::spawn(|| {
+            crate::py_check_signals().unwrap();
+            assert!(signal(SIGTERM, Handler::Default)
+                .unwrap_err()
+                .is("ValueError"));
+        })
+        .join()
+        .unwrap();
+        assert_eq!(
+            crate::py_check_signals().unwrap_err().to_string(),
+            "ValueError: got 15"
+        );
+        signal(SIGTERM, Handler::Default).unwrap();
+    }
+}
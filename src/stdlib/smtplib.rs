@@ -0,0 +1,671 @@
+//! Python-equivalent smtplib module: a blocking SMTP client over std::net.
+//!
+//! STARTTLS and SMTP-over-SSL are only available with the `tls` feature.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::base64;
+use crate::stdlib::email::EmailMessage;
+
+pub const SMTP_PORT: u16 = 25;
+pub const SMTP_SSL_PORT: u16 = 465;
+
+const CRLF: &str = "\r\n";
+
+fn smtp_error(type_name: &str, code: u16, message: &str) -> PyException {
+    PyException::new(type_name, format!("({}, '{}')", code, message))
+}
+
+fn disconnected(message: &str) -> PyException {
+    PyException::new("SMTPServerDisconnected", message)
+}
+
+enum Transport {
+    Plain(TcpStream),
+    #[cfg(feature = "tls")]
+    Tls(Box<native_tls::TlsStream<TcpStream>>),
+}
+
+impl Read for Transport {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Transport::Plain(s) => s.read(buf),
+            #[cfg(feature = "tls")]
+            Transport::Tls(s) => s.read(buf),
+        }
+    }
+}
+
+impl Write for Transport {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Transport::Plain(s) => s.write(buf),
+            #[cfg(feature = "tls")]
+            Transport::Tls(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Transport::Plain(s) => s.flush(),
+            #[cfg(feature = "tls")]
+            Transport::Tls(s) => s.flush(),
+        }
+    }
+}
+
+#[cfg(feature = "tls")]
+fn tls_error(err: impl std::fmt::Display) -> PyException {
+    PyException::new("ssl.SSLError", err.to_string())
+}
+
+/// Python-equivalent smtplib.SMTP.
+#[allow(clippy::upper_case_acronyms)]
+pub struct SMTP {
+    conn: Option<Transport>,
+    buffer: Vec<u8>,
+    host: String,
+    timeout: Option<Duration>,
+    pub local_hostname: String,
+    pub debuglevel: u8,
+    pub does_esmtp: bool,
+    pub esmtp_features: HashMap<String, String>,
+    helo_resp: Option<String>,
+    ehlo_resp: Option<String>,
+}
+
+impl SMTP {
+    /// Python-equivalent SMTP(host, port); an empty host leaves the client unconnected.
+    pub fn new(host: &str, port: u16) -> PyResult<Self> {
+        Self::with_options(host, port, None, None)
+    }
+
+    /// Python-equivalent SMTP(host, port, local_hostname, timeout).
+    pub fn with_options(
+        host: &str,
+        port: u16,
+        local_hostname: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> PyResult<Self> {
+        let mut smtp = SMTP {
+            conn: None,
+            buffer: Vec::new(),
+            host: String::new(),
+            timeout,
+            local_hostname: local_hostname
+                .map(str::to_string)
+                .unwrap_or_else(default_local_hostname),
+            debuglevel: 0,
+            does_esmtp: false,
+            esmtp_features: HashMap::new(),
+            helo_resp: None,
+            ehlo_resp: None,
+        };
+        if !host.is_empty() {
+            let (code, msg) = smtp.connect(host, port)?;
+            if code != 220 {
+                smtp.close();
+                return Err(smtp_error("SMTPConnectError", code, &msg));
+            }
+        }
+        Ok(smtp)
+    }
+
+    /// Python-equivalent SMTP_SSL(host, port): TLS from the first byte.
+    #[cfg(feature = "tls")]
+    pub fn new_ssl(host: &str, port: u16) -> PyResult<Self> {
+        let mut smtp = Self::with_options("", 0, None, None)?;
+        let port = if port == 0 { SMTP_SSL_PORT } else { port };
+        let tcp = smtp.open_tcp(host, port)?;
+        let connector = native_tls::TlsConnector::new().map_err(tls_error)?;
+        let stream = connector.connect(host, tcp).map_err(tls_error)?;
+        smtp.conn = Some(Transport::Tls(Box::new(stream)));
+        smtp.host = host.to_string();
+        let (code, msg) = smtp.getreply()?;
+        if code != 220 {
+            smtp.close();
+            return Err(smtp_error("SMTPConnectError", code, &msg));
+        }
+        Ok(smtp)
+    }
+
+    pub fn set_debuglevel(&mut self, level: u8) {
+        self.debuglevel = level;
+    }
+
+    fn open_tcp(&self, host: &str, port: u16) -> PyResult<TcpStream> {
+        let stream = TcpStream::connect((host, port))?;
+        stream.set_read_timeout(self.timeout)?;
+        stream.set_write_timeout(self.timeout)?;
+        Ok(stream)
+    }
+
+    /// Python-equivalent SMTP.connect(host, port); accepts "host:port" when port is 0.
+    pub fn connect(&mut self, host: &str, port: u16) -> PyResult<(u16, String)> {
+        let (host, port) = match (port, host.rsplit_once(':')) {
+            (0, Some((h, p))) => (
+                h,
+                p.parse::<u16>()
+                    .map_err(|_| PyException::os_error("nonnumeric port"))?,
+            ),
+            (0, None) => (host, SMTP_PORT),
+            _ => (host, port),
+        };
+        let stream = self.open_tcp(host, port)?;
+        self.conn = Some(Transport::Plain(stream));
+        self.buffer.clear();
+        self.host = host.to_string();
+        self.getreply()
+    }
+
+    fn send(&mut self, data: &[u8]) -> PyResult<()> {
+        if self.debuglevel > 0 {
+            eprintln!("send: {:?}", String::from_utf8_lossy(data));
+        }
+        let conn = self
+            .conn
+            .as_mut()
+            .ok_or_else(|| disconnected("please run connect() first"))?;
+        if let Err(err) = conn.write_all(data).and_then(|_| conn.flush()) {
+            self.close();
+            return Err(disconnected(&format!("Server not connected: {}", err)));
+        }
+        Ok(())
+    }
+
+    fn putcmd(&mut self, cmd: &str, args: &str) -> PyResult<()> {
+        let line = if args.is_empty() {
+            format!("{}{}", cmd, CRLF)
+        } else {
+            format!("{} {}{}", cmd, args, CRLF)
+        };
+        self.send(line.as_bytes())
+    }
+
+    fn read_line(&mut self) -> PyResult<String> {
+        loop {
+            if let Some(pos) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+                return Ok(String::from_utf8_lossy(&line).into_owned());
+            }
+            let conn = self
+                .conn
+                .as_mut()
+                .ok_or_else(|| disconnected("please run connect() first"))?;
+            let mut chunk = [0u8; 4096];
+            let n = match conn.read(&mut chunk) {
+                Ok(n) => n,
+                Err(err) => {
+                    self.close();
+                    return Err(disconnected(&format!(
+                        "Connection unexpectedly closed: {}",
+                        err
+                    )));
+                }
+            };
+            if n == 0 {
+                self.close();
+                return Err(disconnected("Connection unexpectedly closed"));
+            }
+            self.buffer.extend_from_slice(&chunk[..n]);
+        }
+    }
+
+    /// Python-equivalent SMTP.getreply(): reads a (possibly multi-line) server response.
+    pub fn getreply(&mut self) -> PyResult<(u16, String)> {
+        let mut lines = Vec::new();
+        loop {
+            let line = self.read_line()?;
+            if self.debuglevel > 0 {
+                eprintln!("reply: {:?}", line);
+            }
+            let line = line.trim_end_matches(['\r', '\n']);
+            let code = line
+                .get(..3)
+                .and_then(|c| c.parse::<u16>().ok())
+                .ok_or_else(|| {
+                    PyException::new("SMTPResponseException", format!("(-1, '{}')", line))
+                })?;
+            lines.push(line.get(4..).unwrap_or("").to_string());
+            if line.as_bytes().get(3) != Some(&b'-') {
+                return Ok((code, lines.join("\n")));
+            }
+        }
+    }
+
+    /// Python-equivalent SMTP.docmd(cmd, args).
+    pub fn docmd(&mut self, cmd: &str, args: &str) -> PyResult<(u16, String)> {
+        self.putcmd(cmd, args)?;
+        self.getreply()
+    }
+
+    pub fn helo(&mut self, name: Option<&str>) -> PyResult<(u16, String)> {
+        let name = name.unwrap_or(&self.local_hostname).to_string();
+        let (code, msg) = self.docmd("helo", &name)?;
+        self.helo_resp = Some(msg.clone());
+        Ok((code, msg))
+    }
+
+    /// Python-equivalent SMTP.ehlo(): records the advertised ESMTP extensions.
+    pub fn ehlo(&mut self, name: Option<&str>) -> PyResult<(u16, String)> {
+        let name = name.unwrap_or(&self.local_hostname).to_string();
+        self.esmtp_features.clear();
+        let (code, msg) = self.docmd("ehlo", &name)?;
+        self.ehlo_resp = Some(msg.clone());
+        if code != 250 {
+            return Ok((code, msg));
+        }
+        self.does_esmtp = true;
+        for line in msg.lines().skip(1) {
+            let mut words = line.splitn(2, ' ');
+            let feature = words.next().unwrap_or("").to_ascii_lowercase();
+            let params = words.next().unwrap_or("").trim().to_string();
+            if feature.is_empty() {
+                continue;
+            }
+            if feature == "auth" {
+                let existing = self.esmtp_features.entry(feature).or_default();
+                if !existing.is_empty() {
+                    existing.push(' ');
+                }
+                existing.push_str(&params);
+            } else {
+                self.esmtp_features.insert(feature, params);
+            }
+        }
+        Ok((code, msg))
+    }
+
+    /// Python-equivalent SMTP.ehlo_or_helo_if_needed().
+    pub fn ehlo_or_helo_if_needed(&mut self) -> PyResult<()> {
+        if self.helo_resp.is_none() && self.ehlo_resp.is_none() {
+            let (code, _) = self.ehlo(None)?;
+            if !(200..=299).contains(&code) {
+                let (code, msg) = self.helo(None)?;
+                if code != 250 {
+                    return Err(smtp_error("SMTPHeloError", code, &msg));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn has_extn(&self, name: &str) -> bool {
+        self.esmtp_features.contains_key(&name.to_ascii_lowercase())
+    }
+
+    /// Python-equivalent SMTP.login(user, password) using AUTH PLAIN or AUTH LOGIN.
+    pub fn login(&mut self, user: &str, password: &str) -> PyResult<(u16, String)> {
+        self.ehlo_or_helo_if_needed()?;
+        if !self.has_extn("auth") {
+            return Err(PyException::new(
+                "SMTPNotSupportedError",
+                "SMTP AUTH extension not supported by server.",
+            ));
+        }
+        let advertised: Vec<String> = self.esmtp_features["auth"]
+            .split_whitespace()
+            .map(|m| m.to_ascii_uppercase())
+            .collect();
+        let mut last_error = None;
+        for mechanism in ["PLAIN", "LOGIN"] {
+            if !advertised.iter().any(|m| m == mechanism) {
+                continue;
+            }
+            let (code, msg) = match mechanism {
+                "PLAIN" => self.auth_plain(user, password)?,
+                _ => self.auth_login(user, password)?,
+            };
+            if code == 235 || code == 503 {
+                return Ok((code, msg));
+            }
+            last_error = Some(smtp_error("SMTPAuthenticationError", code, &msg));
+        }
+        Err(last_error.unwrap_or_else(|| {
+            PyException::new("SMTPException", "No suitable authentication method found.")
+        }))
+    }
+
+    fn auth_plain(&mut self, user: &str, password: &str) -> PyResult<(u16, String)> {
+        let token = format!("\0{}\0{}", user, password);
+        let encoded = String::from_utf8_lossy(&base64::b64encode(token.as_bytes())).into_owned();
+        self.docmd("AUTH", &format!("PLAIN {}", encoded))
+    }
+
+    fn auth_login(&mut self, user: &str, password: &str) -> PyResult<(u16, String)> {
+        let encoded_user =
+            String::from_utf8_lossy(&base64::b64encode(user.as_bytes())).into_owned();
+        let (code, msg) = self.docmd("AUTH", &format!("LOGIN {}", encoded_user))?;
+        if code != 334 {
+            return Ok((code, msg));
+        }
+        let encoded_pass =
+            String::from_utf8_lossy(&base64::b64encode(password.as_bytes())).into_owned();
+        self.docmd(&encoded_pass, "")
+    }
+
+    /// Python-equivalent SMTP.starttls(): upgrades the connection and forgets prior EHLO state.
+    #[cfg(feature = "tls")]
+    pub fn starttls(&mut self) -> PyResult<(u16, String)> {
+        self.ehlo_or_helo_if_needed()?;
+        if !self.has_extn("starttls") {
+            return Err(PyException::new(
+                "SMTPNotSupportedError",
+                "STARTTLS extension not supported by server.",
+            ));
+        }
+        let (code, msg) = self.docmd("STARTTLS", "")?;
+        if code == 220 {
+            let stream = match self.conn.take() {
+                Some(Transport::Plain(stream)) => stream,
+                other => {
+                    self.conn = other;
+                    return Err(PyException::value_error("connection is already using TLS"));
+                }
+            };
+            let connector = native_tls::TlsConnector::new().map_err(tls_error)?;
+            let tls = connector.connect(&self.host, stream).map_err(tls_error)?;
+            self.conn = Some(Transport::Tls(Box::new(tls)));
+            self.buffer.clear();
+            self.helo_resp = None;
+            self.ehlo_resp = None;
+            self.esmtp_features.clear();
+            self.does_esmtp = false;
+        } else {
+            return Err(smtp_error("SMTPResponseException", code, &msg));
+        }
+        Ok((code, msg))
+    }
+
+    /// Python-equivalent SMTP.sendmail(); returns the recipients the server refused.
+    pub fn sendmail(
+        &mut self,
+        from_addr: &str,
+        to_addrs: &[&str],
+        msg: &[u8],
+    ) -> PyResult<HashMap<String, (u16, String)>> {
+        self.ehlo_or_helo_if_needed()?;
+        let (code, resp) = self.docmd("mail", &format!("FROM:<{}>", from_addr))?;
+        if code != 250 {
+            if code == 421 {
+                self.close();
+            } else {
+                let _ = self.rset();
+            }
+            return Err(PyException::new(
+                "SMTPSenderRefused",
+                format!("({}, '{}', '{}')", code, resp, from_addr),
+            ));
+        }
+
+        let mut refused = HashMap::new();
+        for addr in to_addrs {
+            let (code, resp) = self.docmd("rcpt", &format!("TO:<{}>", addr))?;
+            if code != 250 && code != 251 {
+                refused.insert(addr.to_string(), (code, resp));
+            }
+            if code == 421 {
+                self.close();
+                return Err(PyException::new(
+                    "SMTPRecipientsRefused",
+                    format!("{:?}", refused),
+                ));
+            }
+        }
+        if refused.len() == to_addrs.len() {
+            let _ = self.rset();
+            return Err(PyException::new(
+                "SMTPRecipientsRefused",
+                format!("{:?}", refused),
+            ));
+        }
+
+        let (code, resp) = self.data(msg)?;
+        if code != 250 {
+            if code == 421 {
+                self.close();
+            } else {
+                let _ = self.rset();
+            }
+            return Err(smtp_error("SMTPDataError", code, &resp));
+        }
+        Ok(refused)
+    }
+
+    /// Python-equivalent SMTP.data(): sends the body with CRLF normalization and dot-stuffing.
+    pub fn data(&mut self, msg: &[u8]) -> PyResult<(u16, String)> {
+        let (code, resp) = self.docmd("data", "")?;
+        if code != 354 {
+            return Err(smtp_error("SMTPDataError", code, &resp));
+        }
+        let mut payload = Vec::with_capacity(msg.len() + 16);
+        for line in split_lines(msg) {
+            if line.first() == Some(&b'.') {
+                payload.push(b'.');
+            }
+            payload.extend_from_slice(line);
+            payload.extend_from_slice(CRLF.as_bytes());
+        }
+        payload.extend_from_slice(b".\r\n");
+        self.send(&payload)?;
+        self.getreply()
+    }
+
+    /// Python-equivalent SMTP.send_message(); addresses default to the message headers.
+    pub fn send_message(
+        &mut self,
+        msg: &EmailMessage,
+        from_addr: Option<&str>,
+        to_addrs: Option<&[&str]>,
+    ) -> PyResult<HashMap<String, (u16, String)>> {
+        let from = match from_addr {
+            Some(addr) => addr.to_string(),
+            None => msg
+                .get("Sender")
+                .or_else(|| msg.get("From"))
+                .and_then(|v| parse_addresses(v).into_iter().next())
+                .ok_or_else(|| PyException::value_error("No From or Sender header in message"))?,
+        };
+        let recipients: Vec<String> = match to_addrs {
+            Some(addrs) => addrs.iter().map(|a| a.to_string()).collect(),
+            None => ["To", "Cc", "Bcc"]
+                .iter()
+                .flat_map(|h| msg.get_all(h))
+                .flat_map(parse_addresses)
+                .collect(),
+        };
+        let mut outgoing = msg.clone();
+        outgoing.del_item("Bcc");
+        outgoing.del_item("Resent-Bcc");
+        let refs: Vec<&str> = recipients.iter().map(String::as_str).collect();
+        self.sendmail(&from, &refs, &outgoing.as_bytes())
+    }
+
+    pub fn noop(&mut self) -> PyResult<(u16, String)> {
+        self.docmd("noop", "")
+    }
+
+    pub fn rset(&mut self) -> PyResult<(u16, String)> {
+        self.docmd("rset", "")
+    }
+
+    /// Python-equivalent SMTP.quit(): sends QUIT and closes the connection.
+    pub fn quit(&mut self) -> PyResult<(u16, String)> {
+        let result = self.docmd("quit", "");
+        self.ehlo_resp = None;
+        self.helo_resp = None;
+        self.esmtp_features.clear();
+        self.does_esmtp = false;
+        self.close();
+        result
+    }
+
+    pub fn close(&mut self) {
+        self.conn = None;
+        self.buffer.clear();
+    }
+}
+
+impl Drop for SMTP {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+fn default_local_hostname() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+/// Splits on "\r\n", "\n" or "\r" without keeping the terminators.
+fn split_lines(data: &[u8]) -> Vec<&[u8]> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < data.len() {
+        match data[i] {
+            b'\r' => {
+                lines.push(&data[start..i]);
+                if data.get(i + 1) == Some(&b'\n') {
+                    i += 1;
+                }
+                start = i + 1;
+            }
+            b'\n' => {
+                lines.push(&data[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    if start < data.len() {
+        lines.push(&data[start..]);
+    }
+    lines
+}
+
+/// Extracts bare addresses from a header such as `"A" <a@x>, b@y`.
+fn parse_addresses(header: &str) -> Vec<String> {
+    let mut addresses = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut depth = 0;
+    for c in header.chars().chain(std::iter::once(',')) {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            '<' if !in_quotes => depth += 1,
+            '>' if !in_quotes => depth -= 1,
+            ',' if !in_quotes && depth == 0 => {
+                let entry = std::mem::take(&mut current);
+                let addr = match (entry.rfind('<'), entry.rfind('>')) {
+                    (Some(start), Some(end)) if start < end => {
+                        entry[start + 1..end].trim().to_string()
+                    }
+                    _ => entry.trim().to_string(),
+                };
+                if !addr.is_empty() {
+                    addresses.push(addr);
+                }
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn address_parsing() {
+        assert_eq!(
+            parse_addresses("\"Doe, Jane\" <jane@example.com>, bob@example.com"),
+            vec!["jane@example.com", "bob@example.com"]
+        );
+    }
+
+    #[test]
+    fn line_splitting() {
+        assert_eq!(
+            split_lines(b"a\r\nb\nc\rd"),
+            vec![&b"a"[..], b"b", b"c", b"d"]
+        );
+    }
+
+    #[test]
+    fn sends_message_to_server() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut transcript = Vec::new();
+            writer.write_all(b"220 test ESMTP\r\n").unwrap();
+            let mut in_data = false;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    break;
+                }
+                transcript.push(line.clone());
+                if in_data {
+                    if line == ".\r\n" {
+                        in_data = false;
+                        writer.write_all(b"250 queued\r\n").unwrap();
+                    }
+                    continue;
+                }
+                let cmd = line.to_ascii_lowercase();
+                let reply: &[u8] = if cmd.starts_with("ehlo") {
+                    b"250-test\r\n250-AUTH LOGIN PLAIN\r\n250 SIZE 1000\r\n"
+                } else if cmd.starts_with("auth plain") {
+                    b"235 ok\r\n"
+                } else if cmd.starts_with("data") {
+                    in_data = true;
+                    b"354 go ahead\r\n"
+                } else if cmd.starts_with("quit") {
+                    writer.write_all(b"221 bye\r\n").unwrap();
+                    break;
+                } else {
+                    b"250 ok\r\n"
+                };
+                writer.write_all(reply).unwrap();
+            }
+            transcript
+        });
+
+        let mut msg = EmailMessage::new();
+        msg.set_item("From", "Ops <ops@example.com>").unwrap();
+        msg.set_item("To", "dev@example.com").unwrap();
+        msg.set_item("Bcc", "audit@example.com").unwrap();
+        msg.set_item("Subject", "Nightly").unwrap();
+        msg.set_content(".hidden line\nok");
+
+        let mut smtp = SMTP::new("127.0.0.1", port).unwrap();
+        assert_eq!(smtp.login("user", "pw").unwrap().0, 235);
+        assert!(smtp.has_extn("size"));
+        let refused = smtp.send_message(&msg, None, None).unwrap();
+        assert!(refused.is_empty());
+        assert_eq!(smtp.quit().unwrap().0, 221);
+
+        let transcript = server.join().unwrap();
+        assert!(transcript.contains(&"mail FROM:<ops@example.com>\r\n".to_string()));
+        assert!(transcript.contains(&"rcpt TO:<audit@example.com>\r\n".to_string()));
+        assert!(transcript.contains(&"..hidden line\r\n".to_string()));
+        assert!(!transcript.iter().any(|l| l.starts_with("Bcc:")));
+    }
+}
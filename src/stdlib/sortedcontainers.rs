@@ -0,0 +1,767 @@
+//! sortedcontainers-equivalent SortedList and SortedDict.
+//!
+//! SortedList keeps its items in a list of sorted sublists of bounded
+//! length, as sortedcontainers does: add() and remove() binary-search for
+//! the sublist and shift at most a sublist's worth of items. A Fenwick tree
+//! over the sublist lengths, sortedcontainers' positional `_index`, turns
+//! positions into sublist offsets and back in O(log n), so indexing, pop(),
+//! bisect(), index() and the start of islice()/irange() are all O(log n).
+
+use alloc::collections::btree_map::{self, BTreeMap};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::{self, Vec};
+use core::fmt;
+use core::iter::{Chain, Flatten};
+use core::slice;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::list::normalize_index;
+use crate::types::{PyContains, PyLen, PyRepr, PyTruthy};
+
+/// Sublists split once they grow past twice this length.
+const LOAD: usize = 1000;
+
+/// sortedcontainers-equivalent SortedList: a list kept in ascending order,
+/// with duplicates allowed.
+#[derive(Debug, Clone)]
+pub struct SortedList<T> {
+    lists: Vec<Vec<T>>,
+    /// Fenwick tree of `lists` lengths: entry `k - 1` sums the lengths of
+    /// sublists `k - lowbit(k)..k`. Adds and removes update it in place; it
+    /// is rebuilt, in one pass over the sublists, only when one is split or
+    /// dropped.
+    index: Vec<usize>,
+    len: usize,
+}
+
+impl<T> Default for SortedList<T> {
+    fn default() -> Self {
+        SortedList {
+            lists: Vec::new(),
+            index: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+fn lowbit(k: usize) -> usize {
+    k & k.wrapping_neg()
+}
+
+impl<T> SortedList<T> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn iter(&self) -> Flatten<slice::Iter<'_, Vec<T>>> {
+        self.lists.iter().flatten()
+    }
+
+    pub fn clear(&mut self) {
+        self.lists.clear();
+        self.index.clear();
+        self.len = 0;
+    }
+
+    /// Rebuilds the positional index after sublists were added or dropped.
+    fn build_index(&mut self) {
+        self.index = self.lists.iter().map(Vec::len).collect();
+        for k in 1..=self.index.len() {
+            let parent = k + lowbit(k);
+            if parent <= self.index.len() {
+                self.index[parent - 1] += self.index[k - 1];
+            }
+        }
+    }
+
+    /// Records that sublist `i` gained or lost one item.
+    fn resize_sublist(&mut self, i: usize, grew: bool) {
+        let mut k = i + 1;
+        while k <= self.index.len() {
+            if grew {
+                self.index[k - 1] += 1;
+            } else {
+                self.index[k - 1] -= 1;
+            }
+            k += lowbit(k);
+        }
+    }
+
+    /// Sublist and offset of the item at position `pos` (0 <= pos < len).
+    fn locate(&self, mut pos: usize) -> (usize, usize) {
+        let mut i = 0;
+        let mut step = self.index.len().checked_ilog2().map_or(0, |b| 1 << b);
+        while step > 0 {
+            if i + step <= self.index.len() && self.index[i + step - 1] <= pos {
+                i += step;
+                pos -= self.index[i - 1];
+            }
+            step >>= 1;
+        }
+        (i, pos)
+    }
+
+    /// Position of the first item of sublist `i`.
+    fn offset(&self, i: usize) -> usize {
+        let mut k = i;
+        let mut total = 0;
+        while k > 0 {
+            total += self.index[k - 1];
+            k -= lowbit(k);
+        }
+        total
+    }
+
+    fn remove_at(&mut self, i: usize, j: usize) -> T {
+        let value = self.lists[i].remove(j);
+        self.len -= 1;
+        if self.lists[i].is_empty() {
+            self.lists.remove(i);
+            self.build_index();
+        } else {
+            self.resize_sublist(i, false);
+        }
+        value
+    }
+
+    /// SortedList[index], with negative indices counting from the end.
+    pub fn get(&self, index: i64) -> PyResult<&T> {
+        let index = normalize_index(index, self.len)
+            .ok_or_else(|| PyException::index_error("list index out of range"))?;
+        let (i, j) = self.locate(index);
+        Ok(&self.lists[i][j])
+    }
+
+    /// SortedList.pop(index=-1).
+    pub fn pop(&mut self, index: Option<i64>) -> PyResult<T> {
+        if self.is_empty() {
+            return Err(PyException::index_error("pop index out of range"));
+        }
+        let index = normalize_index(index.unwrap_or(-1), self.len)
+            .ok_or_else(|| PyException::index_error("pop index out of range"))?;
+        let (i, j) = self.locate(index);
+        Ok(self.remove_at(i, j))
+    }
+
+    /// SortedList.islice(start, stop, reverse=...): items at positions
+    /// `start..stop`, clamped to the list, borrowed straight from the
+    /// sublists.
+    pub fn islice(&self, start: usize, stop: usize, reverse: bool) -> ISlice<'_, T> {
+        let stop = stop.min(self.len);
+        let empty: &[T] = &[];
+        let (first, middle, last) = if start >= stop {
+            (empty, &self.lists[..0], empty)
+        } else {
+            let (i, j) = self.locate(start);
+            let (k, l) = self.locate(stop - 1);
+            if i == k {
+                (&self.lists[i][j..=l], &self.lists[..0], empty)
+            } else {
+                (
+                    &self.lists[i][j..],
+                    &self.lists[i + 1..k],
+                    &self.lists[k][..=l],
+                )
+            }
+        };
+        ISlice {
+            items: first
+                .iter()
+                .chain(middle.iter().flatten())
+                .chain(last.iter()),
+            reverse,
+        }
+    }
+}
+
+/// The items of a [`SortedList::islice`] or [`SortedList::irange`], in
+/// order or reversed.
+#[derive(Debug, Clone)]
+pub struct ISlice<'a, T> {
+    #[allow(clippy::type_complexity)]
+    items: Chain<Chain<slice::Iter<'a, T>, Flatten<slice::Iter<'a, Vec<T>>>>, slice::Iter<'a, T>>,
+    reverse: bool,
+}
+
+impl<'a, T> Iterator for ISlice<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.reverse {
+            self.items.next_back()
+        } else {
+            self.items.next()
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.items.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for ISlice<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.reverse {
+            self.items.next()
+        } else {
+            self.items.next_back()
+        }
+    }
+}
+
+impl<T: Ord> SortedList<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// SortedList.add(value): after any equal items.
+    pub fn add(&mut self, value: T) {
+        self.len += 1;
+        if self.lists.is_empty() {
+            self.lists.push(alloc::vec![value]);
+            self.build_index();
+            return;
+        }
+        let i = self
+            .lists
+            .partition_point(|list| list.last().is_some_and(|last| *last <= value))
+            .min(self.lists.len() - 1);
+        let list = &mut self.lists[i];
+        let j = list.partition_point(|item| *item <= value);
+        list.insert(j, value);
+        if list.len() > 2 * LOAD {
+            let half = list.split_off(LOAD);
+            self.lists.insert(i + 1, half);
+            self.build_index();
+        } else {
+            self.resize_sublist(i, true);
+        }
+    }
+
+    /// SortedList.update(iterable): adds a few values one at a time, and
+    /// re-sorts everything only when they are at least a quarter of the
+    /// list, as sortedcontainers does.
+    pub fn update<I: IntoIterator<Item = T>>(&mut self, iterable: I) {
+        let mut values: Vec<T> = iterable.into_iter().collect();
+        if values.len() * 4 < self.len {
+            values.sort();
+            for value in values {
+                self.add(value);
+            }
+            return;
+        }
+        let mut items: Vec<T> = self.lists.drain(..).flatten().collect();
+        items.append(&mut values);
+        items.sort();
+        self.len = items.len();
+        while items.len() > LOAD {
+            let rest = items.split_off(LOAD);
+            self.lists.push(items);
+            items = rest;
+        }
+        if !items.is_empty() {
+            self.lists.push(items);
+        }
+        self.build_index();
+    }
+
+    /// SortedList.bisect_left(value): where `value` would go before any
+    /// equal items.
+    pub fn bisect_left(&self, value: &T) -> usize {
+        let i = self
+            .lists
+            .partition_point(|list| list.last().is_some_and(|last| last < value));
+        if i == self.lists.len() {
+            return self.len;
+        }
+        self.offset(i) + self.lists[i].partition_point(|item| item < value)
+    }
+
+    /// SortedList.bisect_right(value): where `value` would go after any
+    /// equal items.
+    pub fn bisect_right(&self, value: &T) -> usize {
+        let i = self
+            .lists
+            .partition_point(|list| list.last().is_some_and(|last| last <= value));
+        if i == self.lists.len() {
+            return self.len;
+        }
+        self.offset(i) + self.lists[i].partition_point(|item| item <= value)
+    }
+
+    /// SortedList.bisect(value), an alias of bisect_right().
+    pub fn bisect(&self, value: &T) -> usize {
+        self.bisect_right(value)
+    }
+
+    /// Sublist and offset of the first item equal to `value`.
+    fn find(&self, value: &T) -> Option<(usize, usize)> {
+        let i = self
+            .lists
+            .partition_point(|list| list.last().is_some_and(|last| last < value));
+        let list = self.lists.get(i)?;
+        let j = list.partition_point(|item| item < value);
+        (list.get(j) == Some(value)).then_some((i, j))
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        self.find(value).is_some()
+    }
+
+    /// SortedList.count(value).
+    pub fn count(&self, value: &T) -> usize {
+        self.bisect_right(value) - self.bisect_left(value)
+    }
+
+    /// SortedList.discard(value): removes one equal item, if any, and says
+    /// whether it did.
+    pub fn discard(&mut self, value: &T) -> bool {
+        match self.find(value) {
+            Some((i, j)) => {
+                self.remove_at(i, j);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// SortedList.remove(value): like discard(), but raises ValueError when
+    /// there is nothing to remove.
+    pub fn remove(&mut self, value: &T) -> PyResult<()>
+    where
+        T: PyRepr,
+    {
+        if self.discard(value) {
+            Ok(())
+        } else {
+            Err(PyException::value_error(format!(
+                "{} not in list",
+                value.py_repr()
+            )))
+        }
+    }
+
+    /// SortedList.index(value): position of the first equal item.
+    pub fn index(&self, value: &T) -> PyResult<usize>
+    where
+        T: PyRepr,
+    {
+        match self.find(value) {
+            Some((i, j)) => Ok(self.offset(i) + j),
+            None => Err(PyException::value_error(format!(
+                "{} is not in list",
+                value.py_repr()
+            ))),
+        }
+    }
+
+    /// SortedList.irange(minimum, maximum, inclusive, reverse): items
+    /// between the bounds, where `None` leaves that side open and
+    /// `inclusive` says whether each bound itself is included.
+    pub fn irange(
+        &self,
+        minimum: Option<&T>,
+        maximum: Option<&T>,
+        inclusive: (bool, bool),
+        reverse: bool,
+    ) -> ISlice<'_, T> {
+        let start = match minimum {
+            None => 0,
+            Some(min) if inclusive.0 => self.bisect_left(min),
+            Some(min) => self.bisect_right(min),
+        };
+        let stop = match maximum {
+            None => self.len,
+            Some(max) if inclusive.1 => self.bisect_right(max),
+            Some(max) => self.bisect_left(max),
+        };
+        self.islice(start, stop, reverse)
+    }
+}
+
+impl<T: Ord> FromIterator<T> for SortedList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut list = SortedList::new();
+        list.update(iter);
+        list
+    }
+}
+
+impl<T: Ord> Extend<T> for SortedList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<'a, T> IntoIterator for &'a SortedList<T> {
+    type Item = &'a T;
+    type IntoIter = Flatten<slice::Iter<'a, Vec<T>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<T> IntoIterator for SortedList<T> {
+    type Item = T;
+    type IntoIter = Flatten<vec::IntoIter<Vec<T>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.lists.into_iter().flatten()
+    }
+}
+
+impl<T: PartialEq> PartialEq for SortedList<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq> Eq for SortedList<T> {}
+
+impl<T> PyLen for SortedList<T> {
+    fn py_len(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T> PyTruthy for SortedList<T> {
+    fn py_bool(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl<T: Ord> PyContains<T> for SortedList<T> {
+    fn py_contains(&self, item: &T) -> bool {
+        self.contains(item)
+    }
+}
+
+/// `SortedList([1, 2, 3])`.
+impl<T: PyRepr> PyRepr for SortedList<T> {
+    fn py_repr(&self) -> String {
+        let parts: Vec<String> = self.iter().map(PyRepr::py_repr).collect();
+        format!("SortedList([{}])", parts.join(", "))
+    }
+}
+
+impl<T: PyRepr> fmt::Display for SortedList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.py_repr())
+    }
+}
+
+/// sortedcontainers-equivalent SortedDict: a mapping iterated in key
+/// order. Values live in a B-tree; a SortedList of the keys answers
+/// positional queries such as peekitem() and index().
+#[derive(Debug, Clone)]
+pub struct SortedDict<K, V> {
+    map: BTreeMap<K, V>,
+    keys: SortedList<K>,
+}
+
+impl<K, V> Default for SortedDict<K, V> {
+    fn default() -> Self {
+        SortedDict {
+            map: BTreeMap::new(),
+            keys: SortedList::default(),
+        }
+    }
+}
+
+impl<K, V> SortedDict<K, V> {
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// `(key, value)` pairs in key order.
+    pub fn iter(&self) -> btree_map::Iter<'_, K, V> {
+        self.map.iter()
+    }
+
+    pub fn keys(&self) -> btree_map::Keys<'_, K, V> {
+        self.map.keys()
+    }
+
+    pub fn values(&self) -> btree_map::Values<'_, K, V> {
+        self.map.values()
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.keys.clear();
+    }
+}
+
+impl<K: Ord + Clone, V> SortedDict<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.map.get(key)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.map.get_mut(key)
+    }
+
+    /// SortedDict[key], raising KeyError for a missing key.
+    pub fn get_item(&self, key: &K) -> PyResult<&V>
+    where
+        K: PyRepr,
+    {
+        self.map.get(key).ok_or_else(|| PyException::key_error(key))
+    }
+
+    /// SortedDict[key] = value.
+    pub fn set_item(&mut self, key: K, value: V) {
+        if !self.map.contains_key(&key) {
+            self.keys.add(key.clone());
+        }
+        self.map.insert(key, value);
+    }
+
+    /// SortedDict.update(pairs).
+    pub fn update<I: IntoIterator<Item = (K, V)>>(&mut self, pairs: I) {
+        for (key, value) in pairs {
+            self.set_item(key, value);
+        }
+    }
+
+    /// Removes `key`, returning its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let value = self.map.remove(key)?;
+        self.keys.discard(key);
+        Some(value)
+    }
+
+    /// SortedDict.pop(key), raising KeyError for a missing key.
+    pub fn pop(&mut self, key: &K) -> PyResult<V>
+    where
+        K: PyRepr,
+    {
+        self.remove(key).ok_or_else(|| PyException::key_error(key))
+    }
+
+    /// SortedDict.peekitem(index=-1): the pair at a position in key order.
+    pub fn peekitem(&self, index: Option<i64>) -> PyResult<(&K, &V)> {
+        let key = self.keys.get(index.unwrap_or(-1))?;
+        Ok((key, &self.map[key]))
+    }
+
+    /// SortedDict.popitem(index=-1): removes and returns the pair at a
+    /// position in key order, the greatest key by default.
+    pub fn popitem(&mut self, index: Option<i64>) -> PyResult<(K, V)> {
+        if self.is_empty() {
+            return Err(PyException::key_error("popitem(): dictionary is empty"));
+        }
+        let key = self.keys.pop(index)?;
+        let value = self.map.remove(&key).expect("keys mirror the map");
+        Ok((key, value))
+    }
+
+    /// SortedDict.index(key): the key's position in key order.
+    pub fn index(&self, key: &K) -> PyResult<usize>
+    where
+        K: PyRepr,
+    {
+        self.keys.index(key)
+    }
+
+    pub fn bisect_left(&self, key: &K) -> usize {
+        self.keys.bisect_left(key)
+    }
+
+    pub fn bisect_right(&self, key: &K) -> usize {
+        self.keys.bisect_right(key)
+    }
+
+    /// SortedDict.irange(): keys between the bounds, as
+    /// [`SortedList::irange`].
+    pub fn irange(
+        &self,
+        minimum: Option<&K>,
+        maximum: Option<&K>,
+        inclusive: (bool, bool),
+        reverse: bool,
+    ) -> ISlice<'_, K> {
+        self.keys.irange(minimum, maximum, inclusive, reverse)
+    }
+}
+
+impl<K: Ord + Clone, V> FromIterator<(K, V)> for SortedDict<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let map: BTreeMap<K, V> = iter.into_iter().collect();
+        let keys = map.keys().cloned().collect();
+        SortedDict { map, keys }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a SortedDict<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = btree_map::Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.map.iter()
+    }
+}
+
+impl<K: PartialEq, V: PartialEq> PartialEq for SortedDict<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<K, V> PyLen for SortedDict<K, V> {
+    fn py_len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K, V> PyTruthy for SortedDict<K, V> {
+    fn py_bool(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl<K: Ord + Clone, V> PyContains<K> for SortedDict<K, V> {
+    fn py_contains(&self, item: &K) -> bool {
+        self.contains_key(item)
+    }
+}
+
+/// `SortedDict({1: 'a', 2: 'b'})`.
+impl<K: PyRepr, V: PyRepr> PyRepr for SortedDict<K, V> {
+    fn py_repr(&self) -> String {
+        let parts: Vec<String> = self
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k.py_repr(), v.py_repr()))
+            .collect();
+        format!("SortedDict({{{}}})", parts.join(", "))
+    }
+}
+
+impl<K: PyRepr, V: PyRepr> fmt::Display for SortedDict<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.py_repr())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sorted_list_bisects_across_sublists() {
+        let mut list: SortedList<i64> = (0..5000).rev().map(|i| i / 2).collect();
+        assert_eq!(list.len(), 5000);
+        assert_eq!(list.bisect_left(&1200), 2400);
+        assert_eq!(list.bisect_right(&1200), 2402);
+        assert_eq!(list.count(&1200), 2);
+        assert_eq!(list.index(&2499).unwrap(), 4998);
+        assert_eq!(*list.get(-1).unwrap(), 2499);
+        for i in 0..3000 {
+            list.add(i % 7);
+        }
+        assert!(list.iter().zip(list.iter().skip(1)).all(|(a, b)| a <= b));
+        assert_eq!(list.count(&3), 2 + 429);
+        assert!(list.discard(&3));
+        assert_eq!(list.count(&3), 430);
+        assert!(list.remove(&-1).unwrap_err().is("ValueError"));
+        assert_eq!(list.pop(None).unwrap(), 2499);
+        assert_eq!(list.pop(Some(0)).unwrap(), 0);
+        assert_eq!(list.len(), 7997);
+
+        let list: SortedList<i64> = [5, 1, 3, 3, 9].into_iter().collect();
+        assert_eq!(list.py_repr(), "SortedList([1, 3, 3, 5, 9])");
+        let between: Vec<i64> = list
+            .irange(Some(&3), Some(&9), (false, true), true)
+            .copied()
+            .collect();
+        assert_eq!(between, [9, 5]);
+        let upto: Vec<i64> = list
+            .irange(None, Some(&5), (true, false), false)
+            .copied()
+            .collect();
+        assert_eq!(upto, [1, 3, 3]);
+        assert!(list.get(5).unwrap_err().is("IndexError"));
+        assert!(SortedList::<i64>::new()
+            .pop(None)
+            .unwrap_err()
+            .is("IndexError"));
+    }
+
+    #[test]
+    fn positions_survive_splits_and_removals() {
+        let mut seed: i64 = 1;
+        let mut next = move || {
+            seed = (seed * 1103515245 + 12345) % 2147483648;
+            seed % 10000
+        };
+        let mut list = SortedList::new();
+        let mut expected = Vec::new();
+        for _ in 0..6000 {
+            let value = next();
+            list.add(value);
+            expected.push(value);
+        }
+        let batch: Vec<i64> = (0..100).map(|_| next()).collect();
+        list.update(batch.iter().copied());
+        expected.extend(batch);
+        expected.sort();
+        assert!(list.iter().eq(expected.iter()));
+        for k in (0..expected.len()).step_by(997) {
+            let pos = (next() as usize) % expected.len();
+            assert_eq!(list.pop(Some(pos as i64)).unwrap(), expected.remove(pos));
+            assert_eq!(*list.get(k as i64).unwrap(), expected[k]);
+            assert_eq!(
+                list.bisect_left(&expected[k]),
+                expected.partition_point(|v| *v < expected[k])
+            );
+        }
+        for (start, stop) in [(0, 10), (990, 3020), (2999, 3001), (5000, 9000), (7, 7)] {
+            let got: Vec<i64> = list.islice(start, stop, false).copied().collect();
+            let want = &expected[start.min(expected.len())..stop.min(expected.len())];
+            assert_eq!(got, want);
+            let mut back: Vec<i64> = list.islice(start, stop, true).copied().collect();
+            back.reverse();
+            assert_eq!(back, want);
+        }
+        while !list.is_empty() {
+            assert_eq!(list.pop(Some(0)).unwrap(), expected.remove(0));
+        }
+        assert!(expected.is_empty());
+    }
+
+    #[test]
+    fn sorted_dict_keeps_key_order() {
+        let mut d: SortedDict<String, i64> =
+            [("b".into(), 2), ("a".into(), 1)].into_iter().collect();
+        d.set_item("c".into(), 3);
+        d.set_item("a".into(), 10);
+        assert_eq!(d.py_repr(), "SortedDict({'a': 10, 'b': 2, 'c': 3})");
+        assert_eq!(d.peekitem(Some(0)).unwrap(), (&"a".into(), &10));
+        assert_eq!(d.index(&"c".into()).unwrap(), 2);
+        assert_eq!(d.bisect_left(&"bb".into()), 2);
+        assert_eq!(d.popitem(None).unwrap(), ("c".into(), 3));
+        assert_eq!(d.pop(&"a".into()).unwrap(), 10);
+        assert!(d.pop(&"a".into()).unwrap_err().is("KeyError"));
+        assert_eq!(d.keys().collect::<Vec<_>>(), ["b"]);
+        d.clear();
+        assert!(d.popitem(None).unwrap_err().is("KeyError"));
+    }
+}
@@ -0,0 +1,143 @@
+//! Python-equivalent stat module: file type and permission bits of an
+//! `st_mode`, with the same values on every Unix.
+
+use alloc::string::String;
+
+/// Indices into an os.stat_result tuple.
+pub const ST_MODE: usize = 0;
+pub const ST_INO: usize = 1;
+pub const ST_DEV: usize = 2;
+pub const ST_NLINK: usize = 3;
+pub const ST_UID: usize = 4;
+pub const ST_GID: usize = 5;
+pub const ST_SIZE: usize = 6;
+pub const ST_ATIME: usize = 7;
+pub const ST_MTIME: usize = 8;
+pub const ST_CTIME: usize = 9;
+
+/// Mask of the file-type bits.
+pub const S_IFMT: u32 = 0o170000;
+pub const S_IFSOCK: u32 = 0o140000;
+pub const S_IFLNK: u32 = 0o120000;
+pub const S_IFREG: u32 = 0o100000;
+pub const S_IFBLK: u32 = 0o060000;
+pub const S_IFDIR: u32 = 0o040000;
+pub const S_IFCHR: u32 = 0o020000;
+pub const S_IFIFO: u32 = 0o010000;
+
+pub const S_ISUID: u32 = 0o4000;
+pub const S_ISGID: u32 = 0o2000;
+pub const S_ISVTX: u32 = 0o1000;
+
+pub const S_IRWXU: u32 = 0o700;
+pub const S_IRUSR: u32 = 0o400;
+pub const S_IWUSR: u32 = 0o200;
+pub const S_IXUSR: u32 = 0o100;
+pub const S_IRWXG: u32 = 0o070;
+pub const S_IRGRP: u32 = 0o040;
+pub const S_IWGRP: u32 = 0o020;
+pub const S_IXGRP: u32 = 0o010;
+pub const S_IRWXO: u32 = 0o007;
+pub const S_IROTH: u32 = 0o004;
+pub const S_IWOTH: u32 = 0o002;
+pub const S_IXOTH: u32 = 0o001;
+
+/// Python-equivalent stat.S_IREAD, the Unix V7 synonym of S_IRUSR.
+pub const S_IREAD: u32 = S_IRUSR;
+pub const S_IWRITE: u32 = S_IWUSR;
+pub const S_IEXEC: u32 = S_IXUSR;
+
+/// Python-equivalent stat.S_IMODE(mode): the bits chmod() can set.
+pub fn s_imode(mode: u32) -> u32 {
+    mode & 0o7777
+}
+
+/// Python-equivalent stat.S_IFMT(mode): just the file-type bits.
+pub fn s_ifmt(mode: u32) -> u32 {
+    mode & S_IFMT
+}
+
+/// Python-equivalent stat.S_ISDIR(mode).
+pub fn s_isdir(mode: u32) -> bool {
+    s_ifmt(mode) == S_IFDIR
+}
+
+/// Python-equivalent stat.S_ISREG(mode).
+pub fn s_isreg(mode: u32) -> bool {
+    s_ifmt(mode) == S_IFREG
+}
+
+/// Python-equivalent stat.S_ISLNK(mode).
+pub fn s_islnk(mode: u32) -> bool {
+    s_ifmt(mode) == S_IFLNK
+}
+
+/// Python-equivalent stat.S_ISCHR(mode).
+pub fn s_ischr(mode: u32) -> bool {
+    s_ifmt(mode) == S_IFCHR
+}
+
+/// Python-equivalent stat.S_ISBLK(mode).
+pub fn s_isblk(mode: u32) -> bool {
+    s_ifmt(mode) == S_IFBLK
+}
+
+/// Python-equivalent stat.S_ISFIFO(mode).
+pub fn s_isfifo(mode: u32) -> bool {
+    s_ifmt(mode) == S_IFIFO
+}
+
+/// Python-equivalent stat.S_ISSOCK(mode).
+pub fn s_issock(mode: u32) -> bool {
+    s_ifmt(mode) == S_IFSOCK
+}
+
+/// Python-equivalent stat.filemode(mode): `ls -l` style, e.g. `-rw-r--r--`.
+pub fn filemode(mode: u32) -> String {
+    let kind = match s_ifmt(mode) {
+        S_IFLNK => 'l',
+        S_IFSOCK => 's',
+        S_IFREG => '-',
+        S_IFBLK => 'b',
+        S_IFDIR => 'd',
+        S_IFCHR => 'c',
+        S_IFIFO => 'p',
+        _ => '?',
+    };
+    let mut out = String::with_capacity(10);
+    out.push(kind);
+    // (read, write, execute, special bit, its letter)
+    let triads = [
+        (S_IRUSR, S_IWUSR, S_IXUSR, S_ISUID, 's'),
+        (S_IRGRP, S_IWGRP, S_IXGRP, S_ISGID, 's'),
+        (S_IROTH, S_IWOTH, S_IXOTH, S_ISVTX, 't'),
+    ];
+    for (r, w, x, special, letter) in triads {
+        out.push(if mode & r != 0 { 'r' } else { '-' });
+        out.push(if mode & w != 0 { 'w' } else { '-' });
+        out.push(match (mode & x != 0, mode & special != 0) {
+            (true, true) => letter,
+            (false, true) => letter.to_ascii_uppercase(),
+            (true, false) => 'x',
+            (false, false) => '-',
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predicates_and_filemode_match_cpython() {
+        assert!(s_isreg(0o100644) && !s_isdir(0o100644));
+        assert!(s_isdir(0o40755) && s_islnk(0o120777));
+        assert_eq!(s_imode(0o104755), 0o4755);
+        assert_eq!(filemode(0o100644), "-rw-r--r--");
+        assert_eq!(filemode(0o41755), "drwxr-xr-t");
+        assert_eq!(filemode(0o104755), "-rwsr-xr-x");
+        assert_eq!(filemode(0o120777), "lrwxrwxrwx");
+        assert_eq!(filemode(0o2070), "?---rws---");
+    }
+}
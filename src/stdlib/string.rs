@@ -0,0 +1,907 @@
+//! Python-equivalent string module: character-class constants, capwords(),
+//! Template and Formatter.
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt::{self, Display, Write};
+#[cfg(feature = "std")]
+use core::hash::Hash;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+
+use crate::exceptions::{PyException, PyResult};
+#[cfg(feature = "heapless")]
+use crate::types::FixedStr;
+use crate::types::{Conversion, PyDictionary, PyFormat, PyKwargs, PyValue};
+
+pub const ASCII_LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+pub const ASCII_UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub const ASCII_LETTERS: &str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+pub const DIGITS: &str = "0123456789";
+pub const HEXDIGITS: &str = "0123456789abcdefABCDEF";
+pub const OCTDIGITS: &str = "01234567";
+pub const PUNCTUATION: &str = "!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+pub const WHITESPACE: &str = " \t\n\r\x0b\x0c";
+pub const PRINTABLE: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ!\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~ \t\n\r\x0b\x0c";
+
+/// Python-equivalent string.capwords(s, sep=None).
+pub fn capwords(s: &str, sep: Option<&str>) -> String {
+    let mut out = String::with_capacity(s.len());
+    capwords_into(s, sep, &mut out).expect("writing to a String cannot fail");
+    out
+}
+
+/// string.capwords() written into any formatter sink, so callers can supply
+/// their own (possibly fixed-size) buffer.
+pub fn capwords_into<W: Write + ?Sized>(s: &str, sep: Option<&str>, out: &mut W) -> fmt::Result {
+    let (words, joiner): (&mut dyn Iterator<Item = &str>, &str) = match sep {
+        None => (&mut s.split_whitespace(), " "),
+        Some(sep) => (&mut s.split(sep), sep),
+    };
+    for (i, word) in words.enumerate() {
+        if i > 0 {
+            out.write_str(joiner)?;
+        }
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            for c in first
+                .to_uppercase()
+                .chain(chars.flat_map(char::to_lowercase))
+            {
+                out.write_char(c)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// string.capwords() into a [`FixedStr`]; MemoryError if the result does
+/// not fit.
+#[cfg(feature = "heapless")]
+pub fn capwords_fixed<const N: usize>(s: &str, sep: Option<&str>) -> PyResult<FixedStr<N>> {
+    let mut out = FixedStr::new();
+    capwords_into(s, sep, &mut out)
+        .map_err(|_| PyException::memory_error(format!("str capacity of {} exceeded", N)))?;
+    Ok(out)
+}
+
+/// Something Template can look placeholder names up in: the `mapping`
+/// argument or keyword arguments of substitute().
+pub trait TemplateMapping {
+    fn lookup(&self, key: &str) -> Option<String>;
+}
+
+#[cfg(feature = "std")]
+impl<K: Borrow<str> + Hash + Eq, V: Display> TemplateMapping for HashMap<K, V> {
+    fn lookup(&self, key: &str) -> Option<String> {
+        self.get(key).map(ToString::to_string)
+    }
+}
+
+impl<K: Borrow<str> + Ord, V: Display> TemplateMapping for BTreeMap<K, V> {
+    fn lookup(&self, key: &str) -> Option<String> {
+        self.get(key).map(ToString::to_string)
+    }
+}
+
+impl<V: Display> TemplateMapping for PyDictionary<String, V> {
+    fn lookup(&self, key: &str) -> Option<String> {
+        self.get(&key.to_string()).map(ToString::to_string)
+    }
+}
+
+/// Keyword-style pairs, e.g. `&[("who", "tim"), ("what", "kung pao")]`.
+impl<K: AsRef<str>, V: Display> TemplateMapping for [(K, V)] {
+    fn lookup(&self, key: &str) -> Option<String> {
+        self.iter()
+            .find(|(k, _)| k.as_ref() == key)
+            .map(|(_, v)| v.to_string())
+    }
+}
+
+impl<K: AsRef<str>, V: Display, const N: usize> TemplateMapping for [(K, V); N] {
+    fn lookup(&self, key: &str) -> Option<String> {
+        self[..].lookup(key)
+    }
+}
+
+impl<T: TemplateMapping + ?Sized> TemplateMapping for &T {
+    fn lookup(&self, key: &str) -> Option<String> {
+        (**self).lookup(key)
+    }
+}
+
+/// Returns the byte length of the identifier at the start of the input, or 0
+/// when there is none. Plays the role of a Template subclass's `idpattern`.
+pub type IdPattern = fn(&str) -> usize;
+
+/// The default `idpattern`, `(?a:[_a-z][_a-z0-9]*)` with re.IGNORECASE.
+pub fn default_idpattern(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    match bytes.first() {
+        Some(b) if b.is_ascii_alphabetic() || *b == b'_' => {
+            1 + bytes[1..]
+                .iter()
+                .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_')
+                .count()
+        }
+        _ => 0,
+    }
+}
+
+/// One parsed piece of a template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token<'a> {
+    Text(&'a str),
+    /// `$$`: a literal delimiter.
+    Escaped,
+    /// `$name` or `${name}`.
+    Named(&'a str),
+    /// A delimiter not followed by anything valid; the offset just after it.
+    Invalid(usize),
+}
+
+/// Python-equivalent string.Template.
+#[derive(Debug, Clone)]
+pub struct Template {
+    template: String,
+    delimiter: String,
+    idpattern: IdPattern,
+    braceidpattern: Option<IdPattern>,
+}
+
+impl Template {
+    pub fn new<S: Into<String>>(template: S) -> Self {
+        Template {
+            template: template.into(),
+            delimiter: "$".to_string(),
+            idpattern: default_idpattern,
+            braceidpattern: None,
+        }
+    }
+
+    /// Overrides the class attribute `delimiter`.
+    pub fn delimiter<S: Into<String>>(mut self, delimiter: S) -> Self {
+        self.delimiter = delimiter.into();
+        self
+    }
+
+    /// Overrides the class attribute `idpattern`.
+    pub fn idpattern(mut self, idpattern: IdPattern) -> Self {
+        self.idpattern = idpattern;
+        self
+    }
+
+    /// Overrides the class attribute `braceidpattern`; defaults to `idpattern`.
+    pub fn braceidpattern(mut self, idpattern: IdPattern) -> Self {
+        self.braceidpattern = Some(idpattern);
+        self
+    }
+
+    /// Python-equivalent Template.template.
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// Splits the template the way CPython's compiled pattern scans it:
+    /// escaped, then named, then braced, else invalid.
+    /// Each token is paired with the source text it was parsed from.
+    fn tokens(&self) -> Vec<(Token<'_>, &str)> {
+        let s = self.template.as_str();
+        let delim = self.delimiter.as_str();
+        let brace = self.braceidpattern.unwrap_or(self.idpattern);
+        let mut tokens = Vec::new();
+        let mut text_start = 0;
+        let mut pos = 0;
+        while let Some(found) = s[pos..].find(delim) {
+            let at = pos + found;
+            if at > text_start {
+                tokens.push((Token::Text(&s[text_start..at]), &s[text_start..at]));
+            }
+            let after = at + delim.len();
+            let rest = &s[after..];
+            let (token, end) = if rest.starts_with(delim) {
+                (Token::Escaped, after + delim.len())
+            } else {
+                let n = (self.idpattern)(rest);
+                if n > 0 {
+                    (Token::Named(&rest[..n]), after + n)
+                } else {
+                    let inner = rest.strip_prefix('{').unwrap_or("");
+                    let n = if rest.starts_with('{') {
+                        brace(inner)
+                    } else {
+                        0
+                    };
+                    if n > 0 && inner[n..].starts_with('}') {
+                        (Token::Named(&inner[..n]), after + n + 2)
+                    } else {
+                        (Token::Invalid(after), after)
+                    }
+                }
+            };
+            tokens.push((token, &s[at..end]));
+            text_start = end;
+            pos = end;
+            if delim.is_empty() {
+                break;
+            }
+        }
+        if text_start < s.len() {
+            tokens.push((Token::Text(&s[text_start..]), &s[text_start..]));
+        }
+        tokens
+    }
+
+    fn invalid_placeholder(&self, offset: usize) -> PyException {
+        let before = &self.template[..offset];
+        let lineno = before.split_inclusive('\n').count().max(1);
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let colno = if before.is_empty() {
+            1
+        } else {
+            before[line_start..].chars().count()
+        };
+        PyException::value_error(format!(
+            "Invalid placeholder in string: line {}, col {}",
+            lineno, colno
+        ))
+    }
+
+    /// Python-equivalent Template.substitute(mapping): KeyError for missing
+    /// names, ValueError for malformed placeholders.
+    pub fn substitute<M: TemplateMapping + ?Sized>(&self, mapping: &M) -> PyResult<String> {
+        let mut out = String::with_capacity(self.template.len());
+        for (token, _) in self.tokens() {
+            match token {
+                Token::Text(text) => out.push_str(text),
+                Token::Escaped => out.push_str(&self.delimiter),
+                Token::Named(name) => match mapping.lookup(name) {
+                    Some(value) => out.push_str(&value),
+                    None => return Err(PyException::key_error(name)),
+                },
+                Token::Invalid(offset) => return Err(self.invalid_placeholder(offset)),
+            }
+        }
+        Ok(out)
+    }
+
+    /// Python-equivalent Template.safe_substitute(mapping): unknown or
+    /// malformed placeholders are left as written.
+    pub fn safe_substitute<M: TemplateMapping + ?Sized>(&self, mapping: &M) -> String {
+        let mut out = String::with_capacity(self.template.len());
+        for (token, source) in self.tokens() {
+            match token {
+                Token::Named(name) => match mapping.lookup(name) {
+                    Some(value) => out.push_str(&value),
+                    None => out.push_str(source),
+                },
+                Token::Escaped => out.push_str(&self.delimiter),
+                Token::Text(_) | Token::Invalid(_) => out.push_str(source),
+            }
+        }
+        out
+    }
+
+    /// Python-equivalent Template.get_identifiers(): valid placeholder names
+    /// in order of first appearance.
+    pub fn get_identifiers(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for (token, _) in self.tokens() {
+            if let Token::Named(name) = token {
+                if !ids.iter().any(|id| id == name) {
+                    ids.push(name.to_string());
+                }
+            }
+        }
+        ids
+    }
+
+    /// Python-equivalent Template.is_valid(): false if substitute() would
+    /// raise ValueError.
+    pub fn is_valid(&self) -> bool {
+        !self
+            .tokens()
+            .iter()
+            .any(|(t, _)| matches!(t, Token::Invalid(_)))
+    }
+}
+
+/// One piece of a format string as Formatter.parse() yields it: literal text
+/// followed by at most one replacement field. `field_name` is None for
+/// trailing text; otherwise `format_spec` is Some, possibly empty.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatItem {
+    pub literal_text: String,
+    pub field_name: Option<String>,
+    pub format_spec: Option<String>,
+    pub conversion: Option<char>,
+}
+
+/// The first part of a field name, which picks an argument: `0` in `{0.x}`
+/// or `name` in `{name[1]}`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FieldKey {
+    Index(usize),
+    Name(String),
+}
+
+impl FieldKey {
+    fn new(s: &str) -> Self {
+        match s.parse() {
+            Ok(i) if s.bytes().all(|b| b.is_ascii_digit()) => FieldKey::Index(i),
+            _ => FieldKey::Name(s.to_string()),
+        }
+    }
+}
+
+/// One `.attribute` or `[index]` step after a field name's first part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldAccess {
+    Attribute(String),
+    Item(FieldKey),
+}
+
+/// Python-equivalent `_string.formatter_parser`: what Formatter.parse()
+/// yields, with CPython's errors for unbalanced braces and conversions.
+pub fn parse(format_string: &str) -> PyResult<Vec<FormatItem>> {
+    let s = format_string;
+    let mut items = Vec::new();
+    let mut literal = String::new();
+    let mut pos = 0;
+    while let Some(found) = s[pos..].find(['{', '}']) {
+        let at = pos + found;
+        literal.push_str(&s[pos..at]);
+        let brace = s.as_bytes()[at];
+        if s.as_bytes().get(at + 1) == Some(&brace) {
+            // `{{` or `}}` ends the literal with one brace.
+            literal.push(brace as char);
+            items.push(FormatItem {
+                literal_text: core::mem::take(&mut literal),
+                field_name: None,
+                format_spec: None,
+                conversion: None,
+            });
+            pos = at + 2;
+            continue;
+        }
+        if brace == b'}' {
+            return Err(PyException::value_error(
+                "Single '}' encountered in format string",
+            ));
+        }
+        let mut depth = 1;
+        let end = s[at + 1..]
+            .char_indices()
+            .find(|&(_, c)| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+                depth == 0
+            })
+            .map(|(i, _)| at + 1 + i);
+        let Some(end) = end else {
+            let message = if at + 1 == s.len() {
+                "Single '{' encountered in format string"
+            } else {
+                "expected '}' before end of string"
+            };
+            return Err(PyException::value_error(message));
+        };
+        let (field_name, format_spec, conversion) = parse_field(&s[at + 1..end])?;
+        items.push(FormatItem {
+            literal_text: core::mem::take(&mut literal),
+            field_name: Some(field_name.to_string()),
+            format_spec: Some(format_spec.to_string()),
+            conversion,
+        });
+        pos = end + 1;
+    }
+    literal.push_str(&s[pos..]);
+    if !literal.is_empty() {
+        items.push(FormatItem {
+            literal_text: literal,
+            field_name: None,
+            format_spec: None,
+            conversion: None,
+        });
+    }
+    Ok(items)
+}
+
+/// Splits the inside of `{...}` into name, spec and conversion. A `:` or
+/// `!` inside `[...]` belongs to the name.
+fn parse_field(field: &str) -> PyResult<(&str, &str, Option<char>)> {
+    let mut in_brackets = false;
+    let mut name_end = field.len();
+    for (i, c) in field.char_indices() {
+        match c {
+            '{' if !in_brackets => {
+                return Err(PyException::value_error("unexpected '{' in field name"))
+            }
+            '[' => in_brackets = true,
+            ']' => in_brackets = false,
+            ':' | '!' if !in_brackets => {
+                name_end = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+    let (name, rest) = field.split_at(name_end);
+    let Some(rest) = rest.strip_prefix('!') else {
+        return Ok((name, rest.strip_prefix(':').unwrap_or(rest), None));
+    };
+    let mut chars = rest.chars();
+    let conversion = chars.next().ok_or_else(|| {
+        PyException::value_error("end of string while looking for conversion specifier")
+    })?;
+    let spec = chars.as_str();
+    match spec.strip_prefix(':') {
+        Some(spec) => Ok((name, spec, Some(conversion))),
+        None if spec.is_empty() => Ok((name, spec, Some(conversion))),
+        None => Err(PyException::value_error(
+            "expected ':' after conversion specifier",
+        )),
+    }
+}
+
+/// Python-equivalent `_string.formatter_field_name_split`: `a.b[0]` as
+/// `a` and its accesses. The first part is empty for `{}` or `{.b}`.
+pub fn field_name_split(field_name: &str) -> PyResult<(&str, Vec<FieldAccess>)> {
+    let first_end = field_name.find(['.', '[']).unwrap_or(field_name.len());
+    let (first, mut rest) = field_name.split_at(first_end);
+    let mut accesses = Vec::new();
+    let empty = || PyException::value_error("Empty attribute in format string");
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[1..];
+        if c == '.' {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            if end == 0 {
+                return Err(empty());
+            }
+            accesses.push(FieldAccess::Attribute(rest[..end].to_string()));
+            rest = &rest[end..];
+        } else if c == '[' {
+            let end = rest
+                .find(']')
+                .ok_or_else(|| PyException::value_error("Missing ']' in format string"))?;
+            if end == 0 {
+                return Err(empty());
+            }
+            accesses.push(FieldAccess::Item(FieldKey::new(&rest[..end])));
+            rest = &rest[end + 1..];
+            if !rest.is_empty() && !rest.starts_with(['.', '[']) {
+                return Err(PyException::value_error(
+                    "Only '.' or '[' may follow ']' in format field specifier",
+                ));
+            }
+        }
+    }
+    Ok((first, accesses))
+}
+
+/// Python-equivalent getattr() on a formatted value, for `{x.real}`.
+fn field_attribute(value: &PyValue, name: &str) -> PyResult<PyValue> {
+    match (value, name) {
+        (PyValue::Bool(b), "real") => Ok(PyValue::Int(*b as i64)),
+        (PyValue::Int(_) | PyValue::Bool(_), "imag") => Ok(PyValue::Int(0)),
+        (PyValue::Int(_) | PyValue::Float(_), "real") => Ok(value.clone()),
+        (PyValue::Float(_), "imag") => Ok(PyValue::Float(0.0)),
+        _ => Err(PyException::attribute_error(format!(
+            "'{}' object has no attribute '{}'",
+            value.type_name(),
+            name
+        ))),
+    }
+}
+
+/// Python-equivalent `value[key]` on a formatted value, for `{x[0]}`.
+fn field_item(value: &PyValue, key: &FieldKey) -> PyResult<PyValue> {
+    match (value, key) {
+        (PyValue::List(list), FieldKey::Index(i)) => list.get(*i as i64).cloned(),
+        (PyValue::Tuple(tuple), FieldKey::Index(i)) => tuple.get(*i as i64).cloned(),
+        (PyValue::Str(s), FieldKey::Index(i)) => Ok(PyValue::from(s.char_at(*i as i64)?)),
+        (PyValue::Dict(dict), key) => {
+            let key = match key {
+                FieldKey::Index(i) => PyValue::Int(*i as i64),
+                FieldKey::Name(name) => PyValue::from(name.as_str()),
+            };
+            dict.get(&key)
+                .cloned()
+                .ok_or_else(|| PyException::key_error(key))
+        }
+        (PyValue::List(_) | PyValue::Tuple(_) | PyValue::Str(_), FieldKey::Name(_)) => {
+            Err(PyException::type_error(format!(
+                "{} indices must be integers or slices, not str",
+                value.type_name()
+            )))
+        }
+        _ => Err(PyException::type_error(format!(
+            "'{}' object is not subscriptable",
+            value.type_name()
+        ))),
+    }
+}
+
+/// Python-equivalent string.Formatter. Every method is provided, so a type
+/// overrides only what it needs, as a subclass would; the free functions
+/// [`vformat`] and [`parse`] play the role of `super()` for those two.
+/// `str.format` is [`DefaultFormatter`].
+pub trait Formatter {
+    /// Python-equivalent Formatter.format(format_string, *args, **kwargs).
+    fn format(&self, format_string: &str, args: &[PyValue], kwargs: &PyKwargs) -> PyResult<String> {
+        self.vformat(format_string, args, kwargs)
+    }
+
+    /// Python-equivalent Formatter.vformat(format_string, args, kwargs).
+    fn vformat(
+        &self,
+        format_string: &str,
+        args: &[PyValue],
+        kwargs: &PyKwargs,
+    ) -> PyResult<String> {
+        vformat(self, format_string, args, kwargs)
+    }
+
+    /// Python-equivalent Formatter.parse(format_string).
+    fn parse(&self, format_string: &str) -> PyResult<Vec<FormatItem>> {
+        parse(format_string)
+    }
+
+    /// Python-equivalent Formatter.get_field(field_name, args, kwargs): the
+    /// object a field refers to and the key of the argument it came from.
+    fn get_field(
+        &self,
+        field_name: &str,
+        args: &[PyValue],
+        kwargs: &PyKwargs,
+    ) -> PyResult<(PyValue, FieldKey)> {
+        let (first, accesses) = field_name_split(field_name)?;
+        let key = FieldKey::new(first);
+        let mut obj = self.get_value(&key, args, kwargs)?;
+        for access in &accesses {
+            obj = match access {
+                FieldAccess::Attribute(name) => field_attribute(&obj, name)?,
+                FieldAccess::Item(item) => field_item(&obj, item)?,
+            };
+        }
+        Ok((obj, key))
+    }
+
+    /// Python-equivalent Formatter.get_value(key, args, kwargs).
+    fn get_value(&self, key: &FieldKey, args: &[PyValue], kwargs: &PyKwargs) -> PyResult<PyValue> {
+        match key {
+            FieldKey::Index(i) => args.get(*i).cloned().ok_or_else(|| {
+                PyException::index_error(format!(
+                    "Replacement index {} out of range for positional args tuple",
+                    i
+                ))
+            }),
+            FieldKey::Name(name) => kwargs
+                .get(name)
+                .cloned()
+                .ok_or_else(|| PyException::key_error(name)),
+        }
+    }
+
+    /// Python-equivalent Formatter.check_unused_args(used_args, args,
+    /// kwargs): accepts everything unless overridden.
+    fn check_unused_args(
+        &self,
+        used_args: &[FieldKey],
+        args: &[PyValue],
+        kwargs: &PyKwargs,
+    ) -> PyResult<()> {
+        let _ = (used_args, args, kwargs);
+        Ok(())
+    }
+
+    /// Python-equivalent Formatter.format_field(value, format_spec), i.e.
+    /// format(value, format_spec).
+    fn format_field(&self, value: &PyValue, format_spec: &str) -> PyResult<String> {
+        value.py_format(format_spec)
+    }
+
+    /// Python-equivalent Formatter.convert_field(value, conversion) for
+    /// `!s`, `!r` and `!a`.
+    fn convert_field(&self, value: PyValue, conversion: Option<char>) -> PyResult<PyValue> {
+        Ok(match conversion {
+            None => value,
+            Some(c) => PyValue::from(Conversion::from_char(c)?.apply(&value)),
+        })
+    }
+}
+
+/// string.Formatter itself, which is also what `str.format` uses.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultFormatter;
+
+impl Formatter for DefaultFormatter {}
+
+/// Where `{}` fields get their index from: unset until the first field,
+/// then either counting or forbidden.
+enum Numbering {
+    Unset,
+    Auto(usize),
+    Manual,
+}
+
+/// The body of Formatter.vformat(), calling back into `formatter` for each
+/// step.
+pub fn vformat<F: Formatter + ?Sized>(
+    formatter: &F,
+    format_string: &str,
+    args: &[PyValue],
+    kwargs: &PyKwargs,
+) -> PyResult<String> {
+    let mut used = Vec::new();
+    let mut numbering = Numbering::Unset;
+    let out = vformat_nested(
+        formatter,
+        format_string,
+        args,
+        kwargs,
+        &mut used,
+        &mut numbering,
+        2,
+    )?;
+    formatter.check_unused_args(&used, args, kwargs)?;
+    Ok(out)
+}
+
+fn vformat_nested<F: Formatter + ?Sized>(
+    formatter: &F,
+    format_string: &str,
+    args: &[PyValue],
+    kwargs: &PyKwargs,
+    used: &mut Vec<FieldKey>,
+    numbering: &mut Numbering,
+    // CPython allows one level of fields nested in a spec.
+    depth: usize,
+) -> PyResult<String> {
+    let mut out = String::with_capacity(format_string.len());
+    for item in formatter.parse(format_string)? {
+        out.push_str(&item.literal_text);
+        let Some(field_name) = item.field_name else {
+            continue;
+        };
+        // Number `{}` and `{.attr}` fields, or check none were numbered.
+        let field_name = if field_name.starts_with(['.', '[']) || field_name.is_empty() {
+            let index = match numbering {
+                Numbering::Manual => return Err(PyException::value_error(
+                    "cannot switch from manual field specification to automatic field numbering",
+                )),
+                Numbering::Unset => 0,
+                Numbering::Auto(next) => *next,
+            };
+            *numbering = Numbering::Auto(index + 1);
+            format!("{}{}", index, field_name)
+        } else {
+            if field_name.starts_with(|c: char| c.is_ascii_digit()) {
+                if let Numbering::Auto(_) = numbering {
+                    return Err(PyException::value_error(
+                        "cannot switch from automatic field numbering to manual field specification",
+                    ));
+                }
+                *numbering = Numbering::Manual;
+            }
+            field_name
+        };
+        let (obj, key) = formatter.get_field(&field_name, args, kwargs)?;
+        if !used.contains(&key) {
+            used.push(key);
+        }
+        let obj = formatter.convert_field(obj, item.conversion)?;
+        let spec = item.format_spec.unwrap_or_default();
+        let spec = if spec.contains('{') {
+            if depth <= 1 {
+                return Err(PyException::value_error("Max string recursion exceeded"));
+            }
+            vformat_nested(formatter, &spec, args, kwargs, used, numbering, depth - 1)?
+        } else {
+            spec
+        };
+        out.push_str(&formatter.format_field(&obj, &spec)?);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PyStr;
+
+    #[test]
+    fn substitution_is_identifier_aware() {
+        let t = Template::new("$a and $ab and ${a}b cost $$5");
+        let out = t.substitute(&[("a", "1"), ("ab", "2")]).unwrap();
+        assert_eq!(out, "1 and 2 and 1b cost $5");
+        assert_eq!(t.get_identifiers(), vec!["a", "ab"]);
+        assert!(t.is_valid());
+    }
+
+    #[test]
+    fn errors_match_cpython() {
+        let err = Template::new("$who likes $what")
+            .substitute(&[("who", "tim")])
+            .unwrap_err();
+        assert_eq!(err.to_string(), "KeyError: 'what'");
+        let t = Template::new("ok\nbad $ here");
+        let err = t
+            .substitute(&BTreeMap::<String, String>::new())
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ValueError: Invalid placeholder in string: line 2, col 5"
+        );
+        assert!(!t.is_valid());
+        assert!(!Template::new("${}").is_valid());
+    }
+
+    #[test]
+    fn safe_substitute_keeps_unknowns() {
+        let t = Template::new("$who likes ${what} for $$ $");
+        let mut mapping = BTreeMap::new();
+        mapping.insert("who", "tim");
+        assert_eq!(t.safe_substitute(&mapping), "tim likes ${what} for $ $");
+    }
+
+    #[test]
+    fn custom_delimiter_and_idpattern() {
+        fn lower_only(s: &str) -> usize {
+            s.bytes().take_while(u8::is_ascii_lowercase).count()
+        }
+        let t = Template::new("%name is %%%Age")
+            .delimiter("%")
+            .idpattern(lower_only);
+        assert_eq!(t.get_identifiers(), vec!["name"]);
+        assert!(!t.is_valid());
+        assert_eq!(t.safe_substitute(&[("name", "x")]), "x is %%Age");
+    }
+
+    fn args() -> Vec<PyValue> {
+        vec![
+            PyValue::from(3.5),
+            PyValue::from("spam"),
+            PyValue::from(vec![10, 20]),
+        ]
+    }
+
+    #[test]
+    fn formatter_parse_matches_cpython() {
+        let items = DefaultFormatter.parse("a{{b{0!r:>{w}}c").unwrap();
+        let fields: Vec<_> = items
+            .iter()
+            .map(|i| {
+                (
+                    i.literal_text.as_str(),
+                    i.field_name.as_deref(),
+                    i.format_spec.as_deref(),
+                    i.conversion,
+                )
+            })
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("a{", None, None, None),
+                ("b", Some("0"), Some(">{w}"), Some('r')),
+                ("c", None, None, None),
+            ]
+        );
+        for (bad, message) in [
+            ("}", "Single '}' encountered in format string"),
+            ("{", "Single '{' encountered in format string"),
+            ("{0", "expected '}' before end of string"),
+            (
+                "{!}",
+                "end of string while looking for conversion specifier",
+            ),
+            ("{!rx}", "expected ':' after conversion specifier"),
+        ] {
+            assert_eq!(parse(bad).unwrap_err().message, message, "{:?}", bad);
+        }
+        assert_eq!(
+            field_name_split("a.b[0][k]").unwrap(),
+            (
+                "a",
+                vec![
+                    FieldAccess::Attribute("b".to_string()),
+                    FieldAccess::Item(FieldKey::Index(0)),
+                    FieldAccess::Item(FieldKey::Name("k".to_string())),
+                ]
+            )
+        );
+        assert!(field_name_split("a[0]x").unwrap_err().is("ValueError"));
+    }
+
+    #[test]
+    fn str_format_fields() {
+        let kwargs = PyKwargs::new().with("w", 6).with("name", "eggs");
+        let f = |s: &str| DefaultFormatter.format(s, &args(), &kwargs);
+        assert_eq!(f("{} and {}").unwrap(), "3.5 and spam");
+        assert_eq!(f("{1!r:>{w}}|{0:.2f}").unwrap(), "'spam'|3.50");
+        assert_eq!(f("{2[1]} {0.real} {name}").unwrap(), "20 3.5 eggs");
+        assert_eq!(f("{0:{w}}|{2[0]:x}").unwrap(), "   3.5|a");
+        assert_eq!(
+            f("{} {1}").unwrap_err().message,
+            "cannot switch from automatic field numbering to manual field specification"
+        );
+        assert_eq!(
+            f("{3}").unwrap_err().message,
+            "Replacement index 3 out of range for positional args tuple"
+        );
+        assert_eq!(f("{nope}").unwrap_err().to_string(), "KeyError: 'nope'");
+        assert!(f("{0.imag.x}").unwrap_err().is("AttributeError"));
+        assert!(f("{0:{1:{2}}}").unwrap_err().message.contains("recursion"));
+        assert!(f("{0!x}").unwrap_err().is("ValueError"));
+
+        let s = PyStr::new("{0}-{0}");
+        assert_eq!(s.format(&args(), &PyKwargs::new()).unwrap(), "3.5-3.5");
+    }
+
+    /// A subclass-style Formatter: defaulted lookups and a strict unused check.
+    struct Strict;
+
+    impl Formatter for Strict {
+        fn get_value(
+            &self,
+            key: &FieldKey,
+            args: &[PyValue],
+            kwargs: &PyKwargs,
+        ) -> PyResult<PyValue> {
+            match key {
+                FieldKey::Name(name) if kwargs.get(name).is_none() => Ok(PyValue::from("?")),
+                _ => DefaultFormatter.get_value(key, args, kwargs),
+            }
+        }
+
+        fn check_unused_args(
+            &self,
+            used_args: &[FieldKey],
+            args: &[PyValue],
+            _kwargs: &PyKwargs,
+        ) -> PyResult<()> {
+            match (0..args.len()).find(|i| !used_args.contains(&FieldKey::Index(*i))) {
+                Some(i) => Err(PyException::value_error(format!("unused argument {}", i))),
+                None => Ok(()),
+            }
+        }
+    }
+
+    #[test]
+    fn formatter_overrides_are_used_by_vformat() {
+        let kwargs = PyKwargs::new();
+        assert_eq!(
+            Strict.format("{0}{1}{2} {who}", &args(), &kwargs).unwrap(),
+            "3.5spam[10, 20] ?"
+        );
+        assert_eq!(
+            Strict.format("{0}", &args(), &kwargs).unwrap_err().message,
+            "unused argument 1"
+        );
+    }
+
+    #[test]
+    fn capwords_matches_python() {
+        assert_eq!(capwords("  hello   wORLD ", None), "Hello World");
+        assert_eq!(capwords("a-b-c", Some("-")), "A-B-C");
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn capwords_into_fixed_buffer() {
+        assert_eq!(
+            capwords_fixed::<11>("hello world", None).unwrap(),
+            "Hello World"
+        );
+        let err = capwords_fixed::<4>("hello", None).unwrap_err();
+        assert!(err.is("MemoryError"));
+    }
+}
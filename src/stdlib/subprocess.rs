@@ -0,0 +1,376 @@
+//! Python-equivalent subprocess module: run(), plus line streaming for
+//! long-running children whose output should be shown as it arrives.
+
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread::{self, JoinHandle};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::types::PyKwargs;
+
+/// Python-equivalent subprocess.PIPE.
+pub const PIPE: i64 = -1;
+/// Python-equivalent subprocess.STDOUT: as `stderr=`, merges it into stdout.
+pub const STDOUT: i64 = -2;
+
+/// Which pipe a streamed line came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Python-equivalent subprocess.CompletedProcess.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletedProcess {
+    pub args: Vec<String>,
+    pub returncode: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CompletedProcess {
+    /// Python-equivalent CompletedProcess.check_returncode(): raises
+    /// CalledProcessError for a non-zero exit status.
+    pub fn check_returncode(&self) -> PyResult<()> {
+        if self.returncode == 0 {
+            return Ok(());
+        }
+        Err(PyException::new(
+            "CalledProcessError",
+            format!(
+                "Command '{}' returned non-zero exit status {}.",
+                self.args.join(" "),
+                self.returncode
+            ),
+        ))
+    }
+}
+
+/// Python-equivalent subprocess.list2cmdline(seq): one Windows command
+/// line that the MS C runtime splits back into `seq`. Arguments with spaces
+/// or tabs, or empty ones, are double-quoted; backslashes are doubled only
+/// where they precede a double quote.
+pub fn list2cmdline<S: AsRef<str>>(seq: &[S]) -> String {
+    let mut result = String::new();
+    for arg in seq {
+        let arg = arg.as_ref();
+        if !result.is_empty() {
+            result.push(' ');
+        }
+        let needquote = arg.is_empty() || arg.contains([' ', '\t']);
+        if needquote {
+            result.push('"');
+        }
+        let mut backslashes = 0;
+        for c in arg.chars() {
+            match c {
+                '\\' => backslashes += 1,
+                '"' => {
+                    result.push_str(&"\\".repeat(backslashes * 2));
+                    result.push_str("\\\"");
+                    backslashes = 0;
+                }
+                c => {
+                    result.push_str(&"\\".repeat(backslashes));
+                    backslashes = 0;
+                    result.push(c);
+                }
+            }
+        }
+        result.push_str(&"\\".repeat(backslashes));
+        if needquote {
+            // Doubled, so the closing quote is not escaped.
+            result.push_str(&"\\".repeat(backslashes));
+            result.push('"');
+        }
+    }
+    result
+}
+
+/// `args` as one command line for this platform's conventions:
+/// [`list2cmdline`] on Windows, shlex.join() elsewhere.
+pub fn command_line<S: AsRef<str>>(args: &[S]) -> String {
+    if cfg!(windows) {
+        list2cmdline(args)
+    } else {
+        crate::stdlib::shlex::join(args)
+    }
+}
+
+/// Python-equivalent subprocess.run(args, capture_output=True, text=True).
+pub fn run<S: AsRef<str>>(args: &[S]) -> PyResult<CompletedProcess> {
+    run_streaming(args, false, |_, _| {})
+}
+
+/// Python-equivalent subprocess.run(args, **kwargs), accepting `check`,
+/// `cwd` and `stderr` (PIPE or STDOUT). Output is always captured as text,
+/// so `capture_output` and `text` may only be true.
+pub fn run_with<S: AsRef<str>>(args: &[S], kwargs: PyKwargs) -> PyResult<CompletedProcess> {
+    let mut kwargs = kwargs.for_call("run");
+    let check = kwargs.get_bool("check").unwrap_or(false);
+    let cwd = kwargs.get_str("cwd")?;
+    let merge_stderr = match kwargs.get_int("stderr")? {
+        None | Some(PIPE) => false,
+        Some(STDOUT) => true,
+        Some(other) => {
+            return Err(PyException::value_error(format!(
+                "unsupported stderr={}: only PIPE and STDOUT are",
+                other
+            )))
+        }
+    };
+    for key in ["capture_output", "text"] {
+        if kwargs.get_bool(key) == Some(false) {
+            return Err(PyException::value_error(format!(
+                "run() always captures output as text; {}=False is unsupported",
+                key
+            )));
+        }
+    }
+    kwargs.reject_unknown()?;
+    let done = collect(
+        Lines::spawn_in(args, merge_stderr, cwd.as_deref())?,
+        |_, _| {},
+    )?;
+    if check {
+        done.check_returncode()?;
+    }
+    Ok(done)
+}
+
+/// Runs `args`, calling `on_line` with each output line (newline included)
+/// as soon as the child writes it, and also capturing everything like
+/// [`run`]. With `merge_stderr`, stderr lines are reported and captured as
+/// stdout, the way `stderr=subprocess.STDOUT` behaves; lines from the two
+/// pipes interleave in arrival order.
+pub fn run_streaming<S, F>(args: &[S], merge_stderr: bool, on_line: F) -> PyResult<CompletedProcess>
+where
+    S: AsRef<str>,
+    F: FnMut(Stream, &str),
+{
+    collect(Lines::spawn(args, merge_stderr)?, on_line)
+}
+
+/// Reads everything the child writes, then waits for it.
+fn collect<F: FnMut(Stream, &str)>(mut lines: Lines, mut on_line: F) -> PyResult<CompletedProcess> {
+    let mut stdout = String::new();
+    let mut stderr = String::new();
+    for (stream, line) in lines.by_ref() {
+        on_line(stream, &line);
+        match stream {
+            Stream::Stdout => stdout.push_str(&line),
+            Stream::Stderr => stderr.push_str(&line),
+        }
+    }
+    let returncode = lines.wait()?;
+    Ok(CompletedProcess {
+        args: lines.args,
+        returncode,
+        stdout,
+        stderr,
+    })
+}
+
+/// A running child whose output is read lazily, one line at a time, in the
+/// order lines arrive from either pipe.
+pub struct Lines {
+    args: Vec<String>,
+    child: Child,
+    merge_stderr: bool,
+    receiver: Receiver<(Stream, String)>,
+    readers: Vec<JoinHandle<std::io::Result<()>>>,
+}
+
+impl Lines {
+    /// Starts `args` with both pipes captured; a reader thread per pipe
+    /// forwards lines as the child writes them.
+    pub fn spawn<S: AsRef<str>>(args: &[S], merge_stderr: bool) -> PyResult<Lines> {
+        Self::spawn_in(args, merge_stderr, None)
+    }
+
+    fn spawn_in<S: AsRef<str>>(
+        args: &[S],
+        merge_stderr: bool,
+        cwd: Option<&str>,
+    ) -> PyResult<Lines> {
+        let args: Vec<String> = args.iter().map(|a| a.as_ref().to_string()).collect();
+        let (program, rest) = args
+            .split_first()
+            .ok_or_else(|| PyException::value_error("args must not be empty"))?;
+        let mut command = Command::new(program);
+        if let Some(cwd) = cwd {
+            command.current_dir(cwd);
+        }
+        // Quote as CPython does rather than as std does, so children see the
+        // same argv either way.
+        #[cfg(windows)]
+        std::os::windows::process::CommandExt::raw_arg(&mut command, list2cmdline(rest));
+        #[cfg(not(windows))]
+        command.args(rest);
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let (sender, receiver) = mpsc::channel();
+        let mut readers = Vec::with_capacity(2);
+        if let Some(out) = child.stdout.take() {
+            let sender = sender.clone();
+            readers.push(thread::spawn(move || forward(out, Stream::Stdout, &sender)));
+        }
+        if let Some(err) = child.stderr.take() {
+            readers.push(thread::spawn(move || forward(err, Stream::Stderr, &sender)));
+        }
+        Ok(Lines {
+            args,
+            child,
+            merge_stderr,
+            receiver,
+            readers,
+        })
+    }
+
+    /// Drains any unread output and waits for the child, returning its exit
+    /// status (negative signal number when killed, as in Python).
+    pub fn wait(&mut self) -> PyResult<i32> {
+        for _ in self.by_ref() {}
+        for reader in self.readers.drain(..) {
+            reader
+                .join()
+                .map_err(|_| PyException::runtime_error("output reader thread panicked"))??;
+        }
+        Ok(returncode(self.child.wait()?))
+    }
+}
+
+impl Iterator for Lines {
+    type Item = (Stream, String);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (stream, line) = self.receiver.recv().ok()?;
+        if self.merge_stderr {
+            Some((Stream::Stdout, line))
+        } else {
+            Some((stream, line))
+        }
+    }
+}
+
+/// Sends each line of `pipe` until EOF; undecodable bytes become U+FFFD.
+fn forward<R: Read>(
+    pipe: R,
+    stream: Stream,
+    sender: &mpsc::Sender<(Stream, String)>,
+) -> std::io::Result<()> {
+    let mut reader = BufReader::new(pipe);
+    let mut buf = Vec::new();
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            return Ok(());
+        }
+        let line = String::from_utf8_lossy(&buf).into_owned();
+        if sender.send((stream, line)).is_err() {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(unix)]
+fn returncode(status: ExitStatus) -> i32 {
+    use std::os::unix::process::ExitStatusExt;
+    status
+        .code()
+        .or_else(|| status.signal().map(|s| -s))
+        .unwrap_or(-1)
+}
+
+#[cfg(not(unix))]
+fn returncode(status: ExitStatus) -> i32 {
+    status.code().unwrap_or(-1)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list2cmdline_follows_msvcrt_rules() {
+        assert_eq!(list2cmdline(&["a b", "c"]), r#""a b" c"#);
+        assert_eq!(
+            list2cmdline(&["x\"y", r"back\slash\", "", "tab\there", r#"q\""#]),
+            "x\\\"y back\\slash\\ \"\" \"tab\there\" q\\\\\\\""
+        );
+        assert_eq!(list2cmdline(&[r"dir\"]), r"dir\");
+        assert_eq!(list2cmdline(&[r"my dir\"]), r#""my dir\\""#);
+        if cfg!(unix) {
+            assert_eq!(command_line(&["echo", "a b"]), "echo 'a b'");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn arguments_round_trip_through_the_shell() {
+        let args = ["it's", "a b", "", "$HOME", "x\"y"];
+        let line = format!("printf '%s|' {}", command_line(&args));
+        let out = run(&["sh", "-c", &line]).unwrap();
+        assert_eq!(out.stdout, "it's|a b||$HOME|x\"y|");
+    }
+
+    #[test]
+    fn streams_lines_in_order_and_captures() {
+        let script = "echo one; echo two >&2; sleep 0.05; echo three; exit 3";
+        let mut seen = Vec::new();
+        let done = run_streaming(&["sh", "-c", script], false, |stream, line| {
+            seen.push((stream, line.to_string()))
+        })
+        .unwrap();
+        assert_eq!(done.stdout, "one\nthree\n");
+        assert_eq!(done.stderr, "two\n");
+        assert_eq!(done.returncode, 3);
+        assert!(done
+            .check_returncode()
+            .unwrap_err()
+            .is("CalledProcessError"));
+        assert_eq!(seen.last(), Some(&(Stream::Stdout, "three\n".to_string())));
+        assert_eq!(seen.len(), 3);
+    }
+
+    #[test]
+    fn merged_stderr_and_lazy_lines() {
+        let done = run_streaming(&["sh", "-c", "echo a >&2; printf b"], true, |s, _| {
+            assert_eq!(s, Stream::Stdout)
+        })
+        .unwrap();
+        assert_eq!(done.stderr, "");
+        assert_eq!(done.stdout.len(), 3);
+
+        let mut lines = Lines::spawn(&["sh", "-c", "echo x; echo y"], false).unwrap();
+        assert_eq!(lines.next(), Some((Stream::Stdout, "x\n".to_string())));
+        assert_eq!(lines.wait().unwrap(), 0);
+        assert!(run(&["/nonexistent/program"]).is_err());
+    }
+
+    #[test]
+    fn run_with_keyword_arguments() {
+        let kwargs = PyKwargs::new()
+            .with("cwd", "/")
+            .with("stderr", STDOUT)
+            .with("capture_output", true)
+            .with("text", true);
+        let done = run_with(&["sh", "-c", "pwd >&2"], kwargs).unwrap();
+        assert_eq!((done.stdout.as_str(), done.stderr.as_str()), ("/\n", ""));
+
+        let kwargs = PyKwargs::new().with("check", true);
+        assert!(run_with(&["false"], kwargs)
+            .unwrap_err()
+            .is("CalledProcessError"));
+        let err = run_with(&["true"], PyKwargs::new().with("shell", true)).unwrap_err();
+        assert_eq!(
+            err.message,
+            "run() got an unexpected keyword argument 'shell'"
+        );
+    }
+}
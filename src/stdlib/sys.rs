@@ -0,0 +1,464 @@
+//! Python-equivalent sys module: interpreter and platform information.
+
+use std::cell::{Cell, RefCell};
+use std::env;
+use std::fmt;
+use std::io::{self, IsTerminal, StdinLock};
+use std::rc::Rc;
+
+use crate::exceptions::{ExceptionArg, PyException, PyResult};
+use crate::stdlib::inspect::{frame_at, Frame};
+use crate::stdlib::io::{flush_stdout, readline, write_stdout, Lines};
+use crate::types::hash::{hash_seed, set_hash_seed, HashSeed};
+use crate::types::PyValue;
+
+/// The Python language version this runtime implements.
+pub const VERSION_INFO: (u32, u32, u32, &str, u32) = (3, 12, 0, "final", 0);
+
+/// Python-equivalent sys.version (without the build/compiler suffix).
+pub const VERSION: &str = "3.12.0 (rython)";
+
+/// Python-equivalent sys.maxsize.
+pub const MAXSIZE: i64 = isize::MAX as i64;
+
+/// Python-equivalent sys.byteorder.
+pub const BYTEORDER: &str = if cfg!(target_endian = "little") {
+    "little"
+} else {
+    "big"
+};
+
+/// Python-equivalent sys.abiflags (empty on modern CPython release builds).
+pub const ABIFLAGS: &str = "";
+
+/// Python-equivalent sys.platlibdir.
+pub const PLATLIBDIR: &str = "lib";
+
+/// Python-equivalent sys.platform.
+pub fn platform() -> &'static str {
+    if cfg!(target_os = "linux") {
+        "linux"
+    } else if cfg!(target_os = "macos") {
+        "darwin"
+    } else if cfg!(windows) {
+        "win32"
+    } else if cfg!(target_os = "freebsd") {
+        "freebsd"
+    } else if cfg!(target_os = "wasi") {
+        "wasi"
+    } else {
+        env::consts::OS
+    }
+}
+
+/// "3.12"-style short version string.
+pub fn version_short() -> String {
+    format!("{}.{}", VERSION_INFO.0, VERSION_INFO.1)
+}
+
+/// Python-equivalent sys.executable: the path of the running binary.
+pub fn executable() -> String {
+    env::current_exe()
+        .map(|p| p.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn default_prefix() -> String {
+    if let Some(home) = env::var_os("PYTHONHOME").filter(|h| !h.is_empty()) {
+        let home = home.to_string_lossy().into_owned();
+        // PYTHONHOME may be "prefix:exec_prefix".
+        return home
+            .split(if cfg!(windows) { ';' } else { ':' })
+            .next()
+            .unwrap_or("")
+            .to_string();
+    }
+    if cfg!(windows) {
+        env::current_exe()
+            .ok()
+            .and_then(|p| p.parent().map(|d| d.to_string_lossy().into_owned()))
+            .unwrap_or_default()
+    } else {
+        "/usr/local".to_string()
+    }
+}
+
+/// Python-equivalent sys.prefix: the active virtual environment if any.
+pub fn prefix() -> String {
+    match env::var_os("VIRTUAL_ENV").filter(|v| !v.is_empty()) {
+        Some(venv) => venv.to_string_lossy().into_owned(),
+        None => default_prefix(),
+    }
+}
+
+/// Python-equivalent sys.exec_prefix.
+pub fn exec_prefix() -> String {
+    prefix()
+}
+
+/// Python-equivalent sys.base_prefix: the installation a virtual environment was made from.
+pub fn base_prefix() -> String {
+    if let Some(venv) = env::var_os("VIRTUAL_ENV").filter(|v| !v.is_empty()) {
+        let cfg = std::path::Path::new(&venv).join("pyvenv.cfg");
+        if let Ok(text) = std::fs::read_to_string(cfg) {
+            for line in text.lines() {
+                if let Some((key, value)) = line.split_once('=') {
+                    if key.trim() == "home" {
+                        let home = std::path::Path::new(value.trim());
+                        // `home` is the interpreter's directory; on POSIX that is prefix/bin.
+                        let base = if cfg!(windows) {
+                            Some(home)
+                        } else {
+                            home.parent()
+                        };
+                        if let Some(base) = base {
+                            return base.to_string_lossy().into_owned();
+                        }
+                    }
+                }
+            }
+        }
+    }
+    default_prefix()
+}
+
+/// Python-equivalent sys.base_exec_prefix.
+pub fn base_exec_prefix() -> String {
+    base_prefix()
+}
+
+/// Python-equivalent sys._getframe(depth): the frame `depth` calls up from
+/// the current one, from the stack stdlib::inspect keeps.
+pub fn _getframe(depth: usize) -> PyResult<Frame> {
+    frame_at(depth).ok_or_else(|| PyException::value_error("call stack is not deep enough"))
+}
+
+/// What a trace or profile hook is told about: the event names CPython
+/// passes as the hook's `event` argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TraceEvent {
+    Call,
+    Line,
+    Return,
+}
+
+impl TraceEvent {
+    pub fn name(self) -> &'static str {
+        match self {
+            TraceEvent::Call => "call",
+            TraceEvent::Line => "line",
+            TraceEvent::Return => "return",
+        }
+    }
+}
+
+/// A sys.settrace() or sys.setprofile() hook, called with the innermost
+/// frame and the event.
+pub type HookFunction = Rc<dyn Fn(&Frame, TraceEvent)>;
+
+thread_local! {
+    static TRACE: RefCell<Option<HookFunction>> = const { RefCell::new(None) };
+    static PROFILE: RefCell<Option<HookFunction>> = const { RefCell::new(None) };
+    /// Whether either hook is set, so unhooked calls cost one flag check.
+    static HOOKED: Cell<bool> = const { Cell::new(false) };
+    /// Set while a hook runs, so calls it makes are not reported back to it.
+    static IN_HOOK: Cell<bool> = const { Cell::new(false) };
+}
+
+fn set_hook(
+    slot: &'static std::thread::LocalKey<RefCell<Option<HookFunction>>>,
+    hook: Option<HookFunction>,
+) {
+    slot.with(|slot| *slot.borrow_mut() = hook);
+    let hooked = TRACE.with(|t| t.borrow().is_some()) || PROFILE.with(|p| p.borrow().is_some());
+    HOOKED.with(|flag| flag.set(hooked));
+}
+
+/// Python-equivalent sys.settrace(func) for this thread: `func` sees every
+/// call, line and return of functions that keep an inspect frame.
+pub fn settrace(hook: Option<HookFunction>) {
+    set_hook(&TRACE, hook);
+}
+
+/// Python-equivalent sys.gettrace().
+pub fn gettrace() -> Option<HookFunction> {
+    TRACE.with(|t| t.borrow().clone())
+}
+
+/// Python-equivalent sys.setprofile(func) for this thread: like
+/// [`settrace`] without line events.
+pub fn setprofile(hook: Option<HookFunction>) {
+    set_hook(&PROFILE, hook);
+}
+
+/// Python-equivalent sys.getprofile().
+pub fn getprofile() -> Option<HookFunction> {
+    PROFILE.with(|p| p.borrow().clone())
+}
+
+/// Reports `event` for the innermost frame to the hooks, if any are set.
+#[inline]
+pub(crate) fn call_hooks(event: TraceEvent) {
+    if HOOKED.with(Cell::get) && !IN_HOOK.with(Cell::get) {
+        run_hooks(event);
+    }
+}
+
+#[cold]
+fn run_hooks(event: TraceEvent) {
+    let Some(frame) = frame_at(0) else { return };
+    let profile = match event {
+        TraceEvent::Line => None,
+        _ => getprofile(),
+    };
+    IN_HOOK.with(|flag| flag.set(true));
+    for hook in profile.into_iter().chain(gettrace()) {
+        hook(&frame, event);
+    }
+    IN_HOOK.with(|flag| flag.set(false));
+}
+
+/// Python-equivalent sys.stdout. Writes go to the innermost
+/// `stdlib::io::push_stdout` writer on this thread, else the process stdout,
+/// so they interleave with print() as in CPython.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stdout;
+
+/// Python-equivalent sys.stdout.
+pub fn stdout() -> Stdout {
+    Stdout
+}
+
+impl Stdout {
+    /// Python-equivalent sys.stdout.write(s): the number of characters
+    /// written.
+    pub fn write(&self, s: &str) -> PyResult<usize> {
+        write_stdout(s)?;
+        Ok(s.chars().count())
+    }
+
+    /// Python-equivalent sys.stdout.flush().
+    pub fn flush(&self) -> PyResult<()> {
+        flush_stdout()
+    }
+
+    /// Python-equivalent sys.stdout.isatty().
+    pub fn isatty(&self) -> bool {
+        io::stdout().is_terminal()
+    }
+}
+
+impl fmt::Write for Stdout {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        write_stdout(s).map_err(|_| fmt::Error)
+    }
+}
+
+/// Python-equivalent sys.stdin, read as UTF-8 text. `for line in sys.stdin:`
+/// is `for line in sys::stdin()`, yielding `PyResult<String>` lines.
+pub struct Stdin {
+    inner: io::Stdin,
+}
+
+/// Python-equivalent sys.stdin.
+pub fn stdin() -> Stdin {
+    Stdin { inner: io::stdin() }
+}
+
+impl Stdin {
+    /// Python-equivalent sys.stdin.readline(): "" at end of input.
+    pub fn readline(&self) -> PyResult<String> {
+        readline(&mut self.inner.lock())
+    }
+
+    /// Python-equivalent sys.stdin.readlines().
+    pub fn readlines(&self) -> PyResult<Vec<String>> {
+        self.lines().collect()
+    }
+
+    /// Iterates the remaining lines, holding the stdin lock until dropped.
+    pub fn lines(&self) -> Lines<StdinLock<'static>> {
+        Lines::new(self.inner.lock())
+    }
+
+    /// Python-equivalent sys.stdin.isatty().
+    pub fn isatty(&self) -> bool {
+        self.inner.is_terminal()
+    }
+}
+
+impl IntoIterator for Stdin {
+    type Item = PyResult<String>;
+    type IntoIter = Lines<StdinLock<'static>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Lines::new(self.inner.lock())
+    }
+}
+
+impl IntoIterator for &Stdin {
+    type Item = PyResult<String>;
+    type IntoIter = Lines<StdinLock<'static>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.lines()
+    }
+}
+
+/// Python-equivalent sys.argv: the program path, then its arguments.
+/// Arguments that are not valid Unicode are decoded lossily.
+pub fn argv() -> Vec<String> {
+    env::args_os()
+        .map(|arg| arg.to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Python-equivalent sys.exit(code): the SystemExit to return as the error.
+/// `()` is sys.exit() with no argument.
+///
+/// ```ignore
+/// return Err(sys::exit(2));
+/// ```
+pub fn exit<C: Into<PyValue>>(code: C) -> PyException {
+    match code.into() {
+        PyValue::None => PyException::new("SystemExit", ""),
+        code => PyException::with_args("SystemExit", [code]),
+    }
+}
+
+/// Python-equivalent sys.excepthook(type, value, tb), writing to `out`.
+/// Compiled code keeps no frames for a raised exception, so the traceback
+/// is its header and the `Type: message` line.
+pub fn excepthook_to<W: io::Write + ?Sized>(err: &PyException, out: &mut W) {
+    let _ = writeln!(out, "Traceback (most recent call last):\n{}", err);
+}
+
+/// The exit status of a program whose main raised `err`, writing what
+/// CPython writes to stderr to `out`: a SystemExit's integer code (0 for
+/// None), or its message with status 1; for KeyboardInterrupt the
+/// traceback and 130, the status of a shell-reported SIGINT; for anything
+/// else the traceback and 1.
+pub fn exit_status<W: io::Write + ?Sized>(err: &PyException, out: &mut W) -> i32 {
+    if err.is("SystemExit") {
+        return match err.args.as_slice() {
+            [] | [ExceptionArg::None] => 0,
+            [ExceptionArg::Int(code)] => *code as i32,
+            [ExceptionArg::Bool(code)] => i32::from(*code),
+            _ => {
+                let _ = writeln!(out, "{}", err.message);
+                1
+            }
+        };
+    }
+    excepthook_to(err, out);
+    if err.is("KeyboardInterrupt") {
+        130
+    } else {
+        1
+    }
+}
+
+/// Parses a `PYTHONHASHSEED` value: "random" or an integer in
+/// [0, 4294967295].
+pub fn parse_hash_seed(value: &str) -> PyResult<HashSeed> {
+    if value == "random" {
+        return Ok(HashSeed::Random);
+    }
+    value.parse().map(HashSeed::Fixed).map_err(|_| {
+        PyException::value_error(
+            "PYTHONHASHSEED must be \"random\" or an integer in range [0; 4294967295]",
+        )
+    })
+}
+
+/// Applies `PYTHONHASHSEED` the way interpreter startup does. Call it before
+/// building any dict or set. When the variable is unset or empty the crate
+/// default ([`DEFAULT_HASH_SEED`](crate::types::hash::DEFAULT_HASH_SEED)) stays in effect.
+pub fn init_hash_seed() -> PyResult<HashSeed> {
+    match env::var("PYTHONHASHSEED") {
+        Ok(value) if !value.is_empty() => {
+            let seed = parse_hash_seed(&value)?;
+            set_hash_seed(seed)?;
+            Ok(seed)
+        }
+        _ => Ok(hash_seed()),
+    }
+}
+
+/// Python-equivalent sys.flags.hash_randomization.
+pub fn hash_randomization() -> bool {
+    hash_seed() != HashSeed::Fixed(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::inspect::{enter_frame, set_lineno};
+
+    #[test]
+    fn trace_hooks_see_calls_lines_and_returns() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let seen = events.clone();
+        settrace(Some(Rc::new(move |frame: &Frame, event| {
+            // Calls made by the hook itself are not traced.
+            let _nested = enter_frame("hook", "trace.py", 1);
+            seen.borrow_mut().push(format!(
+                "{} {}:{}",
+                event.name(),
+                frame.f_code.co_name,
+                frame.f_lineno
+            ));
+        })));
+        assert!(gettrace().is_some() && getprofile().is_none());
+        {
+            let _frame = enter_frame("work", "trace.py", 10);
+            set_lineno(11);
+        }
+        settrace(None);
+        let _untraced = enter_frame("after", "trace.py", 20);
+        assert_eq!(
+            *events.borrow(),
+            ["call work:10", "line work:11", "return work:11"]
+        );
+    }
+
+    #[test]
+    fn version_strings() {
+        assert_eq!(version_short(), "3.12");
+        assert!(VERSION.starts_with("3.12.0"));
+        assert!(!platform().is_empty());
+    }
+
+    #[test]
+    fn hash_seed_values() {
+        assert_eq!(parse_hash_seed("random").unwrap(), HashSeed::Random);
+        assert_eq!(
+            parse_hash_seed("4294967295").unwrap(),
+            HashSeed::Fixed(u32::MAX)
+        );
+        assert!(parse_hash_seed("-1").unwrap_err().is("ValueError"));
+        assert!(parse_hash_seed("4294967296").is_err());
+    }
+
+    #[test]
+    fn exit_statuses() {
+        let status = |err: PyException| {
+            let mut out = Vec::new();
+            let code = exit_status(&err, &mut out);
+            (code, String::from_utf8(out).unwrap())
+        };
+        assert_eq!(status(exit(())), (0, String::new()));
+        assert_eq!(status(exit(3)), (3, String::new()));
+        assert_eq!(status(exit(true)), (1, String::new()));
+        assert_eq!(status(exit("bad config")), (1, "bad config\n".to_string()));
+        assert_eq!(
+            status(PyException::value_error("x")),
+            (
+                1,
+                "Traceback (most recent call last):\nValueError: x\n".to_string()
+            )
+        );
+        assert_eq!(status(PyException::new("KeyboardInterrupt", "")).0, 130);
+        assert!(!argv().is_empty());
+    }
+}
@@ -0,0 +1,494 @@
+//! Python-equivalent sysconfig module.
+//!
+//! Values describe the host platform and, when `VIRTUAL_ENV` is set, the active virtual
+//! environment, laid out as CPython would install them.
+
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path as StdPath;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::sys;
+
+const SCHEME_KEYS: &[&str] = &[
+    "stdlib",
+    "platstdlib",
+    "purelib",
+    "platlib",
+    "include",
+    "platinclude",
+    "scripts",
+    "data",
+];
+
+const POSIX_PREFIX: &[(&str, &str)] = &[
+    (
+        "stdlib",
+        "{installed_base}/{platlibdir}/python{py_version_short}",
+    ),
+    (
+        "platstdlib",
+        "{platbase}/{platlibdir}/python{py_version_short}",
+    ),
+    (
+        "purelib",
+        "{base}/lib/python{py_version_short}/site-packages",
+    ),
+    (
+        "platlib",
+        "{platbase}/{platlibdir}/python{py_version_short}/site-packages",
+    ),
+    (
+        "include",
+        "{installed_base}/include/python{py_version_short}{abiflags}",
+    ),
+    (
+        "platinclude",
+        "{installed_platbase}/include/python{py_version_short}{abiflags}",
+    ),
+    ("scripts", "{base}/bin"),
+    ("data", "{base}"),
+];
+
+const POSIX_HOME: &[(&str, &str)] = &[
+    ("stdlib", "{installed_base}/lib/python"),
+    ("platstdlib", "{base}/lib/python"),
+    ("purelib", "{base}/lib/python"),
+    ("platlib", "{base}/lib/python"),
+    ("include", "{installed_base}/include/python"),
+    ("platinclude", "{installed_base}/include/python"),
+    ("scripts", "{base}/bin"),
+    ("data", "{base}"),
+];
+
+const NT: &[(&str, &str)] = &[
+    ("stdlib", "{installed_base}/Lib"),
+    ("platstdlib", "{base}/Lib"),
+    ("purelib", "{base}/Lib/site-packages"),
+    ("platlib", "{base}/Lib/site-packages"),
+    ("include", "{installed_base}/Include"),
+    ("platinclude", "{installed_base}/Include"),
+    ("scripts", "{base}/Scripts"),
+    ("data", "{base}"),
+];
+
+const POSIX_USER: &[(&str, &str)] = &[
+    ("stdlib", "{userbase}/{platlibdir}/python{py_version_short}"),
+    (
+        "platstdlib",
+        "{userbase}/{platlibdir}/python{py_version_short}",
+    ),
+    (
+        "purelib",
+        "{userbase}/lib/python{py_version_short}/site-packages",
+    ),
+    (
+        "platlib",
+        "{userbase}/lib/python{py_version_short}/site-packages",
+    ),
+    ("include", "{userbase}/include/python{py_version_short}"),
+    ("scripts", "{userbase}/bin"),
+    ("data", "{userbase}"),
+];
+
+const NT_USER: &[(&str, &str)] = &[
+    ("stdlib", "{userbase}/Python{py_version_nodot_plat}"),
+    ("platstdlib", "{userbase}/Python{py_version_nodot_plat}"),
+    (
+        "purelib",
+        "{userbase}/Python{py_version_nodot_plat}/site-packages",
+    ),
+    (
+        "platlib",
+        "{userbase}/Python{py_version_nodot_plat}/site-packages",
+    ),
+    (
+        "include",
+        "{userbase}/Python{py_version_nodot_plat}/Include",
+    ),
+    (
+        "scripts",
+        "{userbase}/Python{py_version_nodot_plat}/Scripts",
+    ),
+    ("data", "{userbase}"),
+];
+
+const OSX_FRAMEWORK_USER: &[(&str, &str)] = &[
+    ("stdlib", "{userbase}/lib/python"),
+    ("platstdlib", "{userbase}/lib/python"),
+    ("purelib", "{userbase}/lib/python/site-packages"),
+    ("platlib", "{userbase}/lib/python/site-packages"),
+    ("include", "{userbase}/include/python{py_version_short}"),
+    ("scripts", "{userbase}/bin"),
+    ("data", "{userbase}"),
+];
+
+fn scheme_table(name: &str) -> Option<&'static [(&'static str, &'static str)]> {
+    match name {
+        // The venv schemes are the platform schemes evaluated against the venv's base.
+        "posix_prefix" | "posix_venv" => Some(POSIX_PREFIX),
+        "posix_home" => Some(POSIX_HOME),
+        "nt" | "nt_venv" => Some(NT),
+        "posix_user" => Some(POSIX_USER),
+        "nt_user" => Some(NT_USER),
+        "osx_framework_user" => Some(OSX_FRAMEWORK_USER),
+        "venv" => scheme_table(if cfg!(windows) {
+            "nt_venv"
+        } else {
+            "posix_venv"
+        }),
+        _ => None,
+    }
+}
+
+/// Python-equivalent sysconfig.get_scheme_names().
+pub fn get_scheme_names() -> Vec<&'static str> {
+    vec![
+        "nt",
+        "nt_user",
+        "nt_venv",
+        "osx_framework_user",
+        "posix_home",
+        "posix_prefix",
+        "posix_user",
+        "posix_venv",
+        "venv",
+    ]
+}
+
+/// Python-equivalent sysconfig.get_path_names().
+pub fn get_path_names() -> Vec<&'static str> {
+    SCHEME_KEYS.to_vec()
+}
+
+/// Python-equivalent sysconfig.get_python_version(): "X.Y".
+pub fn get_python_version() -> String {
+    sys::version_short()
+}
+
+fn in_venv() -> bool {
+    sys::prefix() != sys::base_prefix()
+}
+
+/// Python-equivalent sysconfig.get_default_scheme().
+pub fn get_default_scheme() -> &'static str {
+    if in_venv() {
+        "venv"
+    } else {
+        get_preferred_scheme("prefix").unwrap_or("posix_prefix")
+    }
+}
+
+/// Python-equivalent sysconfig.get_preferred_scheme(key) for "prefix", "home" or "user".
+pub fn get_preferred_scheme(key: &str) -> PyResult<&'static str> {
+    let scheme = match (key, cfg!(windows)) {
+        ("prefix", true) | ("home", true) => "nt",
+        ("prefix", false) => "posix_prefix",
+        ("home", false) => "posix_home",
+        ("user", true) => "nt_user",
+        ("user", false)
+            if cfg!(target_os = "macos") && env::var_os("PYTHONFRAMEWORK").is_some() =>
+        {
+            "osx_framework_user"
+        }
+        ("user", false) => "posix_user",
+        _ => {
+            return Err(PyException::value_error(format!(
+                "{:?} is not a valid key, must be one of ['prefix', 'home', 'user']",
+                key
+            )))
+        }
+    };
+    Ok(scheme)
+}
+
+fn user_base() -> String {
+    if let Some(base) = env::var_os("PYTHONUSERBASE").filter(|b| !b.is_empty()) {
+        return base.to_string_lossy().into_owned();
+    }
+    let home = env::var(if cfg!(windows) { "USERPROFILE" } else { "HOME" }).unwrap_or_default();
+    if cfg!(windows) {
+        let appdata = env::var("APPDATA").unwrap_or_else(|_| format!("{}\\AppData\\Roaming", home));
+        format!("{}\\Python", appdata)
+    } else if cfg!(target_os = "macos") && env::var_os("PYTHONFRAMEWORK").is_some() {
+        format!("{}/Library/Python/{}", home, sys::version_short())
+    } else {
+        format!("{}/.local", home)
+    }
+}
+
+/// Python-equivalent sysconfig.get_config_vars().
+pub fn get_config_vars() -> BTreeMap<String, String> {
+    let (major, minor, micro, _, _) = sys::VERSION_INFO;
+    let prefix = sys::prefix();
+    let exec_prefix = sys::exec_prefix();
+    let base_prefix = sys::base_prefix();
+    let py_version_short = format!("{}.{}", major, minor);
+    let nodot = format!("{}{}", major, minor);
+    let projectbase = env::var("_PYTHON_PROJECT_BASE").unwrap_or_else(|_| {
+        StdPath::new(&sys::executable())
+            .parent()
+            .map(|p| p.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    let (exe, ext_suffix, so) = if cfg!(windows) {
+        (
+            ".exe",
+            format!(".cp{}-{}.pyd", nodot, get_platform().replace('-', "_")),
+            ".pyd",
+        )
+    } else if cfg!(target_os = "macos") {
+        ("", format!(".cpython-{}-darwin.so", nodot), ".so")
+    } else {
+        (
+            "",
+            format!(
+                ".cpython-{}-{}-{}.so",
+                nodot,
+                env::consts::ARCH,
+                if cfg!(target_os = "linux") {
+                    "linux-gnu"
+                } else {
+                    env::consts::OS
+                }
+            ),
+            ".so",
+        )
+    };
+
+    let mut vars = BTreeMap::new();
+    let mut set = |k: &str, v: String| {
+        vars.insert(k.to_string(), v);
+    };
+    set("prefix", prefix.clone());
+    set("exec_prefix", exec_prefix.clone());
+    set("base", prefix.clone());
+    set("platbase", exec_prefix.clone());
+    set("installed_base", base_prefix.clone());
+    set("installed_platbase", sys::base_exec_prefix());
+    set("base_prefix", base_prefix.clone());
+    set("projectbase", projectbase.clone());
+    set("srcdir", projectbase);
+    set("py_version", format!("{}.{}.{}", major, minor, micro));
+    set("py_version_short", py_version_short.clone());
+    set("py_version_nodot", nodot.clone());
+    set(
+        "py_version_nodot_plat",
+        if cfg!(windows) {
+            nodot.clone()
+        } else {
+            String::new()
+        },
+    );
+    set("abiflags", sys::ABIFLAGS.to_string());
+    set("platlibdir", sys::PLATLIBDIR.to_string());
+    set("userbase", user_base());
+    set("VERSION", py_version_short.clone());
+    set("EXE", exe.to_string());
+    set("EXT_SUFFIX", ext_suffix.clone());
+    set("SO", so.to_string());
+    set(
+        "SOABI",
+        ext_suffix
+            .trim_start_matches('.')
+            .trim_end_matches(so)
+            .to_string(),
+    );
+    set("SIZEOF_VOID_P", std::mem::size_of::<usize>().to_string());
+    set(
+        "HOST_GNU_TYPE",
+        format!("{}-{}", env::consts::ARCH, env::consts::OS),
+    );
+    set("MACHDEP", sys::platform().to_string());
+    if cfg!(windows) {
+        set("BINDIR", base_prefix.clone());
+        set("LIBDEST", format!("{}\\Lib", base_prefix));
+        set("INCLUDEPY", format!("{}\\Include", base_prefix));
+    } else {
+        set("BINDIR", format!("{}/bin", base_prefix));
+        set("LIBDIR", format!("{}/{}", base_prefix, sys::PLATLIBDIR));
+        set(
+            "LIBDEST",
+            format!(
+                "{}/{}/python{}",
+                base_prefix,
+                sys::PLATLIBDIR,
+                py_version_short
+            ),
+        );
+        set(
+            "INCLUDEPY",
+            format!(
+                "{}/include/python{}{}",
+                base_prefix,
+                py_version_short,
+                sys::ABIFLAGS
+            ),
+        );
+        set(
+            "LDLIBRARY",
+            format!("libpython{}{}.a", py_version_short, sys::ABIFLAGS),
+        );
+        set("CC", env::var("CC").unwrap_or_else(|_| "cc".to_string()));
+        set("Py_ENABLE_SHARED", "0".to_string());
+        if cfg!(target_os = "macos") {
+            set(
+                "MACOSX_DEPLOYMENT_TARGET",
+                env::var("MACOSX_DEPLOYMENT_TARGET").unwrap_or_else(|_| "11.0".to_string()),
+            );
+        }
+    }
+    vars
+}
+
+/// Python-equivalent sysconfig.get_config_var(name).
+pub fn get_config_var(name: &str) -> Option<String> {
+    get_config_vars().remove(name)
+}
+
+/// Expands `{name}` placeholders from `vars`, falling back to the environment like CPython.
+fn subst_vars(template: &str, vars: &BTreeMap<String, String>) -> PyResult<String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|e| start + e)
+            .ok_or_else(|| PyException::value_error(format!("unmatched '{{' in {}", template)))?;
+        let name = &rest[start + 1..end];
+        match vars.get(name).cloned().or_else(|| env::var(name).ok()) {
+            Some(value) => out.push_str(&value),
+            None => return Err(PyException::new("AttributeError", format!("{{{}}}", name))),
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn normpath(path: &str) -> String {
+    if cfg!(windows) {
+        path.replace('/', "\\")
+    } else {
+        path.to_string()
+    }
+}
+
+/// Python-equivalent sysconfig.get_paths(scheme, vars, expand).
+///
+/// `vars` override the config variables used for expansion (e.g. "base" for a target prefix).
+pub fn get_paths(
+    scheme: Option<&str>,
+    vars: Option<&BTreeMap<String, String>>,
+    expand: bool,
+) -> PyResult<BTreeMap<String, String>> {
+    let scheme: &str = match scheme {
+        Some(scheme) => scheme,
+        None => get_default_scheme(),
+    };
+    let table = scheme_table(scheme).ok_or_else(|| PyException::key_error(scheme))?;
+    let mut config = get_config_vars();
+    if let Some(vars) = vars {
+        config.extend(vars.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+    let mut paths = BTreeMap::new();
+    for (key, template) in table {
+        let value = if expand {
+            normpath(&subst_vars(template, &config)?)
+        } else {
+            template.to_string()
+        };
+        paths.insert(key.to_string(), value);
+    }
+    Ok(paths)
+}
+
+/// Python-equivalent sysconfig.get_path(name, scheme, vars, expand).
+pub fn get_path(
+    name: &str,
+    scheme: Option<&str>,
+    vars: Option<&BTreeMap<String, String>>,
+    expand: bool,
+) -> PyResult<String> {
+    get_paths(scheme, vars, expand)?
+        .remove(name)
+        .ok_or_else(|| PyException::key_error(name))
+}
+
+/// Python-equivalent sysconfig.get_platform(), e.g. "linux-x86_64", "macosx-11.0-arm64",
+/// "win-amd64".
+pub fn get_platform() -> String {
+    let arch = env::consts::ARCH;
+    if cfg!(windows) {
+        return match arch {
+            "x86_64" => "win-amd64".to_string(),
+            "aarch64" => "win-arm64".to_string(),
+            "x86" => "win32".to_string(),
+            other => format!("win-{}", other),
+        };
+    }
+    if cfg!(target_os = "macos") {
+        let target = env::var("MACOSX_DEPLOYMENT_TARGET").unwrap_or_else(|_| "11.0".to_string());
+        let machine = if arch == "aarch64" { "arm64" } else { arch };
+        return format!("macosx-{}-{}", target, machine);
+    }
+    let machine = match arch {
+        "x86" => "i686",
+        other => other,
+    };
+    format!("{}-{}", sys::platform(), machine)
+}
+
+/// Python-equivalent sysconfig.is_python_build(): true only inside a CPython source checkout.
+pub fn is_python_build() -> bool {
+    let base = match env::var("_PYTHON_PROJECT_BASE") {
+        Ok(base) => base,
+        Err(_) => return false,
+    };
+    ["Setup", "Setup.local"]
+        .iter()
+        .any(|f| StdPath::new(&base).join("Modules").join(f).is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scheme_expansion() {
+        let mut vars = BTreeMap::new();
+        for key in ["base", "platbase", "installed_base", "installed_platbase"] {
+            vars.insert(key.to_string(), "/opt/py".to_string());
+        }
+        let paths = get_paths(Some("posix_prefix"), Some(&vars), true).unwrap();
+        assert_eq!(
+            paths["purelib"],
+            normpath("/opt/py/lib/python3.12/site-packages")
+        );
+        assert_eq!(paths["scripts"], normpath("/opt/py/bin"));
+        assert_eq!(paths.len(), 8);
+        let raw = get_path("data", Some("nt"), None, false).unwrap();
+        assert_eq!(raw, "{base}");
+        assert!(get_paths(Some("bogus"), None, true)
+            .unwrap_err()
+            .is("KeyError"));
+    }
+
+    #[test]
+    fn config_vars() {
+        let vars = get_config_vars();
+        assert_eq!(vars["py_version_short"], "3.12");
+        assert_eq!(get_config_var("VERSION").as_deref(), Some("3.12"));
+        assert!(get_config_var("NOPE").is_none());
+        assert!(get_scheme_names().contains(&get_default_scheme()));
+        assert!(get_preferred_scheme("bogus").is_err());
+    }
+
+    #[test]
+    fn platform_string() {
+        let platform = get_platform();
+        assert!(platform.contains('-') || platform == "win32");
+        assert!(!is_python_build() || env::var_os("_PYTHON_PROJECT_BASE").is_some());
+    }
+}
@@ -0,0 +1,941 @@
+//! Python-equivalent tempfile module.
+//!
+//! Files are created with `create_new` (O_EXCL) and, on Unix, mode 0o600 / 0o700, so a
+//! name is never reused or opened a second time by path.
+
+use std::cell::Cell;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path as StdPath, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::io::{BytesIO, FileLike};
+use crate::stdlib::os::PathLike;
+use crate::sync::Lock;
+use crate::types::repr::repr_str;
+use crate::types::PyKwargs;
+
+/// Python-equivalent tempfile.TMP_MAX: attempts before giving up on finding a free name.
+pub const TMP_MAX: usize = 10000;
+
+/// Python-equivalent tempfile.template: the default name prefix.
+pub const TEMPLATE: &str = "tmp";
+
+const NAME_CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789_";
+
+thread_local! {
+    static NAME_STATE: Cell<u64> = Cell::new(seed());
+}
+
+fn seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let local = 0u8;
+    nanos ^ (u64::from(std::process::id()) << 32) ^ (&local as *const u8 as u64)
+}
+
+/// Eight random characters from the same alphabet CPython's _RandomNameSequence uses.
+fn random_name() -> String {
+    NAME_STATE.with(|state| {
+        // splitmix64
+        let mut x = state.get().wrapping_add(0x9E37_79B9_7F4A_7C15);
+        state.set(x);
+        x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        x ^= x >> 31;
+        (0..8)
+            .map(|i| NAME_CHARS[((x >> (i * 8)) % NAME_CHARS.len() as u64) as usize] as char)
+            .collect()
+    })
+}
+
+/// Python-equivalent tempfile.tempdir: what gettempdir() returns once it
+/// has been set or computed.
+static TEMPDIR: Lock<Option<String>> = Lock::new(None);
+
+/// Python-equivalent assignment to tempfile.tempdir; None makes the next
+/// gettempdir() search again.
+pub fn set_tempdir(dir: Option<&str>) {
+    *TEMPDIR.lock() = dir.map(str::to_string);
+}
+
+/// Python-equivalent tempfile.gettempprefix().
+pub fn gettempprefix() -> String {
+    TEMPLATE.to_string()
+}
+
+/// Python-equivalent tempfile.gettempprefixb().
+pub fn gettempprefixb() -> Vec<u8> {
+    TEMPLATE.as_bytes().to_vec()
+}
+
+/// The directories CPython's _candidate_tempdir_list() tries, in order:
+/// TMPDIR, TEMP and TMP, then platform locations, then the current directory.
+fn candidate_tempdirs() -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = ["TMPDIR", "TEMP", "TMP"]
+        .into_iter()
+        .filter_map(std::env::var_os)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    if cfg!(windows) {
+        if let Some(home) = std::env::var_os("USERPROFILE") {
+            dirs.push(
+                PathBuf::from(home)
+                    .join("AppData")
+                    .join("Local")
+                    .join("Temp"),
+            );
+        }
+        if let Some(root) = std::env::var_os("SYSTEMROOT") {
+            dirs.push(PathBuf::from(root).join("Temp"));
+        }
+        dirs.extend([r"c:\temp", r"c:\tmp", r"\temp", r"\tmp"].map(PathBuf::from));
+    } else {
+        dirs.extend(["/tmp", "/var/tmp", "/usr/tmp"].map(PathBuf::from));
+    }
+    dirs.extend(std::env::current_dir().ok());
+    dirs
+}
+
+/// The first candidate directory a file can actually be created in, as
+/// CPython's _get_default_tempdir() probes it.
+fn default_tempdir() -> PyResult<String> {
+    let candidates = candidate_tempdirs();
+    for dir in &candidates {
+        let dir = std::path::absolute(dir).unwrap_or_else(|_| dir.clone());
+        for _ in 0..100 {
+            let path = dir.join(random_name());
+            match open_new_file(&path).and_then(|mut file| file.write_all(b"blat")) {
+                Ok(()) => {
+                    let _ = fs::remove_file(&path);
+                    return Ok(dir.to_string_lossy().into_owned());
+                }
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err)
+                    if cfg!(windows)
+                        && err.kind() == io::ErrorKind::PermissionDenied
+                        && dir.is_dir() =>
+                {
+                    continue
+                }
+                Err(_) => {
+                    // A failed write leaves the file behind.
+                    let _ = fs::remove_file(&path);
+                    break;
+                }
+            }
+        }
+    }
+    let tried: Vec<String> = candidates
+        .iter()
+        .map(|dir| repr_str(&dir.to_string_lossy()))
+        .collect();
+    Err(PyException::new(
+        "FileNotFoundError",
+        format!(
+            "[Errno 2] No usable temporary directory found in [{}]",
+            tried.join(", ")
+        ),
+    ))
+}
+
+/// Python-equivalent tempfile.gettempdir(): tempfile.tempdir if set, else
+/// the first usable directory from TMPDIR, TEMP, TMP, the platform defaults
+/// and the current directory. The result is cached in tempfile.tempdir.
+pub fn gettempdir() -> PyResult<String> {
+    let mut tempdir = TEMPDIR.lock();
+    if let Some(dir) = tempdir.as_ref() {
+        return Ok(dir.clone());
+    }
+    let dir = default_tempdir()?;
+    *tempdir = Some(dir.clone());
+    Ok(dir)
+}
+
+/// Python-equivalent tempfile.gettempdirb().
+pub fn gettempdirb() -> PyResult<Vec<u8>> {
+    gettempdir().map(String::into_bytes)
+}
+
+fn resolve_dir(dir: Option<&str>) -> PyResult<PathBuf> {
+    match dir {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => gettempdir().map(PathBuf::from),
+    }
+}
+
+/// Tries fresh random names in `dir` until `create` succeeds or TMP_MAX names are taken.
+fn create_unique<T>(
+    suffix: Option<&str>,
+    prefix: Option<&str>,
+    dir: Option<&str>,
+    kind: &str,
+    mut create: impl FnMut(&StdPath) -> io::Result<T>,
+) -> PyResult<(T, String)> {
+    let dir = resolve_dir(dir)?;
+    let prefix = prefix.unwrap_or(TEMPLATE);
+    let suffix = suffix.unwrap_or("");
+    for _ in 0..TMP_MAX {
+        let path = dir.join(format!("{}{}{}", prefix, random_name(), suffix));
+        match create(&path) {
+            Ok(value) => return Ok((value, path.to_string_lossy().into_owned())),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            // Windows reports a name held by a directory as access denied.
+            Err(err)
+                if cfg!(windows)
+                    && err.kind() == io::ErrorKind::PermissionDenied
+                    && dir.is_dir() =>
+            {
+                continue
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+    Err(PyException::new(
+        "FileExistsError",
+        format!("No usable temporary {} name found", kind),
+    ))
+}
+
+fn open_new_file(path: &StdPath) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.read(true).write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Python-equivalent tempfile.mkstemp(): returns the owned, open file and its path.
+///
+/// The caller is responsible for deleting the file.
+pub fn mkstemp(
+    suffix: Option<&str>,
+    prefix: Option<&str>,
+    dir: Option<&str>,
+) -> PyResult<(File, String)> {
+    create_unique(suffix, prefix, dir, "file", open_new_file)
+}
+
+/// Python-equivalent tempfile.mkdtemp(): creates a directory readable only by the owner.
+pub fn mkdtemp(suffix: Option<&str>, prefix: Option<&str>, dir: Option<&str>) -> PyResult<String> {
+    let ((), name) = create_unique(suffix, prefix, dir, "directory", |path| {
+        let mut builder = fs::DirBuilder::new();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::DirBuilderExt;
+            builder.mode(0o700);
+        }
+        builder.create(path)
+    })?;
+    Ok(name)
+}
+
+fn closed_error() -> PyException {
+    PyException::value_error("I/O operation on closed file.")
+}
+
+/// Options shared by TemporaryFile and NamedTemporaryFile, mirroring their keyword arguments.
+#[derive(Debug, Clone)]
+pub struct TempFileBuilder {
+    suffix: Option<String>,
+    prefix: Option<String>,
+    dir: Option<String>,
+    delete: bool,
+    delete_on_close: bool,
+    text: bool,
+}
+
+impl Default for TempFileBuilder {
+    fn default() -> Self {
+        TempFileBuilder {
+            suffix: None,
+            prefix: None,
+            dir: None,
+            delete: true,
+            delete_on_close: true,
+            text: false,
+        }
+    }
+}
+
+impl TempFileBuilder {
+    /// The options of a TemporaryFile/NamedTemporaryFile(**kwargs) call:
+    /// mode ("w+b" or "w+"), suffix, prefix, dir, delete and
+    /// delete_on_close. `func` names the caller in TypeErrors.
+    pub fn from_kwargs(func: &str, kwargs: PyKwargs) -> PyResult<Self> {
+        let mut kwargs = kwargs.for_call(func);
+        let mut builder = TempFileBuilder::default();
+        match kwargs.get_str("mode")?.as_deref() {
+            None | Some("w+b") => {}
+            Some("w+") => builder = builder.text(true),
+            Some(other) => {
+                return Err(PyException::value_error(format!(
+                    "unsupported temporary file mode: '{}'",
+                    other
+                )))
+            }
+        }
+        builder.suffix = kwargs.get_str("suffix")?;
+        builder.prefix = kwargs.get_str("prefix")?;
+        builder.dir = kwargs.get_str("dir")?;
+        if let Some(delete) = kwargs.get_bool("delete") {
+            builder = builder.delete(delete);
+        }
+        if let Some(delete_on_close) = kwargs.get_bool("delete_on_close") {
+            builder = builder.delete_on_close(delete_on_close);
+        }
+        kwargs.reject_unknown()?;
+        Ok(builder)
+    }
+
+    pub fn suffix(mut self, suffix: &str) -> Self {
+        self.suffix = Some(suffix.to_string());
+        self
+    }
+
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_string());
+        self
+    }
+
+    pub fn dir<P: PathLike>(mut self, dir: P) -> Self {
+        self.dir = Some(dir.fspath());
+        self
+    }
+
+    /// delete=: remove the file automatically (default true).
+    pub fn delete(mut self, delete: bool) -> Self {
+        self.delete = delete;
+        self
+    }
+
+    /// delete_on_close= (3.12): with delete=true, remove on close() rather than on drop.
+    pub fn delete_on_close(mut self, delete_on_close: bool) -> Self {
+        self.delete_on_close = delete_on_close;
+        self
+    }
+
+    /// mode="w+" instead of the default "w+b": byte-level reads must then be valid UTF-8.
+    pub fn text(mut self, text: bool) -> Self {
+        self.text = text;
+        self
+    }
+
+    /// Creates a NamedTemporaryFile with these options.
+    pub fn named(self) -> PyResult<NamedTemporaryFile> {
+        let (file, name) = mkstemp(
+            self.suffix.as_deref(),
+            self.prefix.as_deref(),
+            self.dir.as_deref(),
+        )?;
+        Ok(NamedTemporaryFile {
+            file: Some(file),
+            name,
+            delete: self.delete,
+            delete_on_close: self.delete_on_close,
+            text: self.text,
+            deleted: false,
+        })
+    }
+
+    /// Creates a TemporaryFile with these options.
+    pub fn unnamed(self) -> PyResult<TemporaryFile> {
+        let (file, name) = mkstemp(
+            self.suffix.as_deref(),
+            self.prefix.as_deref(),
+            self.dir.as_deref(),
+        )?;
+        if cfg!(unix) {
+            // The open handle keeps the data alive; nothing else can ever reach it.
+            fs::remove_file(&name)?;
+            Ok(TemporaryFile {
+                file: Some(file),
+                text: self.text,
+                unlinked: None,
+            })
+        } else {
+            Ok(TemporaryFile {
+                file: Some(file),
+                text: self.text,
+                unlinked: Some(name),
+            })
+        }
+    }
+}
+
+/// Python-equivalent tempfile.NamedTemporaryFile.
+#[derive(Debug)]
+pub struct NamedTemporaryFile {
+    file: Option<File>,
+    name: String,
+    delete: bool,
+    delete_on_close: bool,
+    text: bool,
+    deleted: bool,
+}
+
+impl NamedTemporaryFile {
+    /// NamedTemporaryFile() with default arguments.
+    pub fn new() -> PyResult<Self> {
+        Self::builder().named()
+    }
+
+    /// Python-equivalent NamedTemporaryFile(**kwargs); see
+    /// [`TempFileBuilder::from_kwargs`] for the accepted keywords.
+    pub fn with_kwargs(kwargs: PyKwargs) -> PyResult<Self> {
+        TempFileBuilder::from_kwargs("NamedTemporaryFile", kwargs)?.named()
+    }
+
+    pub fn builder() -> TempFileBuilder {
+        TempFileBuilder::default()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn closed(&self) -> bool {
+        self.file.is_none()
+    }
+
+    /// The underlying file; fails with ValueError once closed.
+    pub fn file(&mut self) -> PyResult<&mut File> {
+        self.file.as_mut().ok_or_else(closed_error)
+    }
+
+    /// Text-mode write of a str; returns the number of characters written.
+    pub fn write_text(&mut self, text: &str) -> PyResult<usize> {
+        self.file()?.write_all(text.as_bytes())?;
+        Ok(text.chars().count())
+    }
+
+    /// Text-mode read of the rest of the file as a str.
+    pub fn read_text(&mut self) -> PyResult<String> {
+        read_text_from(self.file()?)
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.text
+    }
+
+    /// Python-equivalent close(); deletes the file when delete and delete_on_close are set.
+    pub fn close(&mut self) -> PyResult<()> {
+        if let Some(file) = self.file.take() {
+            drop(file);
+            if self.delete && self.delete_on_close {
+                self.remove()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn remove(&mut self) -> PyResult<()> {
+        if !self.deleted {
+            self.deleted = true;
+            match fs::remove_file(&self.name) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for NamedTemporaryFile {
+    fn drop(&mut self) {
+        self.file = None;
+        if self.delete {
+            let _ = self.remove();
+        }
+    }
+}
+
+/// Python-equivalent tempfile.TemporaryFile: on Unix the file has no name once created.
+#[derive(Debug)]
+pub struct TemporaryFile {
+    file: Option<File>,
+    text: bool,
+    /// Path still to remove on platforms that cannot unlink open files.
+    unlinked: Option<String>,
+}
+
+impl TemporaryFile {
+    /// TemporaryFile() with default arguments.
+    pub fn new() -> PyResult<Self> {
+        TempFileBuilder::default().unnamed()
+    }
+
+    pub fn closed(&self) -> bool {
+        self.file.is_none()
+    }
+
+    pub fn file(&mut self) -> PyResult<&mut File> {
+        self.file.as_mut().ok_or_else(closed_error)
+    }
+
+    pub fn write_text(&mut self, text: &str) -> PyResult<usize> {
+        self.file()?.write_all(text.as_bytes())?;
+        Ok(text.chars().count())
+    }
+
+    pub fn read_text(&mut self) -> PyResult<String> {
+        read_text_from(self.file()?)
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.text
+    }
+
+    pub fn close(&mut self) -> PyResult<()> {
+        self.file = None;
+        if let Some(name) = self.unlinked.take() {
+            fs::remove_file(name)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for TemporaryFile {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+fn read_text_from(file: &mut File) -> PyResult<String> {
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    String::from_utf8(data).map_err(|e| {
+        PyException::new(
+            "UnicodeDecodeError",
+            format!("'utf-8' codec can't decode: {}", e),
+        )
+    })
+}
+
+macro_rules! forward_io {
+    ($ty:ty) => {
+        impl Read for $ty {
+            fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+                self.file.as_mut().ok_or_else(closed_io_error)?.read(buf)
+            }
+        }
+
+        impl Write for $ty {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.file.as_mut().ok_or_else(closed_io_error)?.write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                self.file.as_mut().ok_or_else(closed_io_error)?.flush()
+            }
+        }
+
+        impl Seek for $ty {
+            fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+                self.file.as_mut().ok_or_else(closed_io_error)?.seek(pos)
+            }
+        }
+    };
+}
+
+fn closed_io_error() -> io::Error {
+    io::Error::other("I/O operation on closed file.")
+}
+
+forward_io!(NamedTemporaryFile);
+forward_io!(TemporaryFile);
+
+fn truncate_file(file: &mut File, size: Option<u64>) -> PyResult<u64> {
+    FileLike::truncate(file, size)
+}
+
+impl FileLike for NamedTemporaryFile {
+    fn truncate(&mut self, size: Option<u64>) -> PyResult<u64> {
+        truncate_file(self.file()?, size)
+    }
+
+    fn closed(&self) -> bool {
+        self.file.is_none()
+    }
+
+    fn name(&self) -> Option<String> {
+        Some(self.name.clone())
+    }
+}
+
+impl FileLike for TemporaryFile {
+    fn truncate(&mut self, size: Option<u64>) -> PyResult<u64> {
+        truncate_file(self.file()?, size)
+    }
+
+    fn closed(&self) -> bool {
+        self.file.is_none()
+    }
+
+    fn name(&self) -> Option<String> {
+        self.unlinked
+            .clone()
+            .or_else(|| self.fileno().map(|fd| fd.to_string()))
+    }
+}
+
+impl TemporaryFile {
+    /// The OS file descriptor (Unix only); on Unix this doubles as the file's name.
+    pub fn fileno(&self) -> Option<i32> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::io::AsRawFd;
+            self.file.as_ref().map(|f| f.as_raw_fd())
+        }
+        #[cfg(not(unix))]
+        {
+            None
+        }
+    }
+}
+
+enum Spool {
+    Memory(BytesIO),
+    Disk(TemporaryFile),
+}
+
+/// Python-equivalent tempfile.SpooledTemporaryFile: kept in memory until it grows past
+/// `max_size` bytes (or fileno()/rollover() is called), then moved to a TemporaryFile.
+pub struct SpooledTemporaryFile {
+    spool: Spool,
+    max_size: usize,
+    options: TempFileBuilder,
+    closed: bool,
+}
+
+impl SpooledTemporaryFile {
+    /// SpooledTemporaryFile(max_size); 0 means never roll over automatically.
+    pub fn new(max_size: usize) -> Self {
+        Self::with_options(max_size, TempFileBuilder::default())
+    }
+
+    /// SpooledTemporaryFile(max_size, ...) with the TemporaryFile arguments used on rollover.
+    pub fn with_options(max_size: usize, options: TempFileBuilder) -> Self {
+        SpooledTemporaryFile {
+            spool: Spool::Memory(BytesIO::new()),
+            max_size,
+            options,
+            closed: false,
+        }
+    }
+
+    /// Python-equivalent _rolled: true once the data lives on disk.
+    pub fn rolled(&self) -> bool {
+        matches!(self.spool, Spool::Disk(_))
+    }
+
+    /// Python-equivalent rollover(): copies the buffer to disk, keeping the position.
+    pub fn rollover(&mut self) -> PyResult<()> {
+        self.check_open()?;
+        if let Spool::Memory(buffer) = &mut self.spool {
+            let position = buffer.tell()?;
+            let mut file = self.options.clone().unnamed()?;
+            file.write_all(buffer.getvalue())?;
+            file.seek(SeekFrom::Start(position))?;
+            self.spool = Spool::Disk(file);
+        }
+        Ok(())
+    }
+
+    /// Python-equivalent fileno(): forces a rollover so a real descriptor exists.
+    pub fn fileno(&mut self) -> PyResult<Option<i32>> {
+        self.rollover()?;
+        match &self.spool {
+            Spool::Disk(file) => Ok(file.fileno()),
+            Spool::Memory(_) => Ok(None),
+        }
+    }
+
+    pub fn close(&mut self) -> PyResult<()> {
+        if !self.closed {
+            self.closed = true;
+            if let Spool::Disk(file) = &mut self.spool {
+                file.close()?;
+            }
+            self.spool = Spool::Memory(BytesIO::new());
+        }
+        Ok(())
+    }
+
+    pub fn write_text(&mut self, text: &str) -> PyResult<usize> {
+        self.write_str(text)
+    }
+
+    pub fn read_text(&mut self) -> PyResult<String> {
+        self.read_str()
+    }
+
+    fn check_open(&self) -> PyResult<()> {
+        if self.closed {
+            Err(closed_error())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn stream(&mut self) -> io::Result<&mut dyn FileLike> {
+        if self.closed {
+            return Err(closed_io_error());
+        }
+        Ok(match &mut self.spool {
+            Spool::Memory(buffer) => buffer,
+            Spool::Disk(file) => file,
+        })
+    }
+
+    fn check_rollover(&mut self) -> PyResult<()> {
+        if let Spool::Memory(buffer) = &self.spool {
+            if self.max_size > 0 && buffer.len() > self.max_size {
+                self.rollover()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Read for SpooledTemporaryFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stream()?.read(buf)
+    }
+}
+
+impl Write for SpooledTemporaryFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.stream()?.write(buf)?;
+        self.check_rollover().map_err(io::Error::other)?;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream()?.flush()
+    }
+}
+
+impl Seek for SpooledTemporaryFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.stream()?.seek(pos)
+    }
+}
+
+impl FileLike for SpooledTemporaryFile {
+    /// Growing past max_size through truncate() rolls over first, as CPython does.
+    fn truncate(&mut self, size: Option<u64>) -> PyResult<u64> {
+        self.check_open()?;
+        if let Some(size) = size {
+            if self.max_size > 0 && size > self.max_size as u64 {
+                self.rollover()?;
+            }
+        }
+        match &mut self.spool {
+            Spool::Memory(buffer) => buffer.truncate(size),
+            Spool::Disk(file) => file.truncate(size),
+        }
+    }
+
+    fn closed(&self) -> bool {
+        self.closed
+    }
+
+    fn name(&self) -> Option<String> {
+        match &self.spool {
+            Spool::Disk(file) => file.name(),
+            Spool::Memory(_) => None,
+        }
+    }
+}
+
+/// Python-equivalent tempfile.TemporaryDirectory: removed recursively on cleanup() or drop.
+#[derive(Debug)]
+pub struct TemporaryDirectory {
+    name: String,
+    delete: bool,
+    cleaned: bool,
+}
+
+impl TemporaryDirectory {
+    pub fn new(suffix: Option<&str>, prefix: Option<&str>, dir: Option<&str>) -> PyResult<Self> {
+        Ok(TemporaryDirectory {
+            name: mkdtemp(suffix, prefix, dir)?,
+            delete: true,
+            cleaned: false,
+        })
+    }
+
+    /// delete=False (3.12): keep the directory when dropped.
+    pub fn delete(mut self, delete: bool) -> Self {
+        self.delete = delete;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn cleanup(&mut self) -> PyResult<()> {
+        if !self.cleaned {
+            self.cleaned = true;
+            fs::remove_dir_all(&self.name)?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for TemporaryDirectory {
+    fn drop(&mut self) {
+        if self.delete {
+            let _ = self.cleanup();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mkstemp_owns_file() {
+        let (mut file, name) = mkstemp(Some(".dat"), Some("pre_"), None).unwrap();
+        let base = StdPath::new(&name)
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .into_owned();
+        assert!(base.starts_with("pre_") && base.ends_with(".dat"));
+        assert_eq!(base.len(), "pre_".len() + 8 + ".dat".len());
+        file.write_all(b"hello").unwrap();
+        drop(file);
+        assert_eq!(fs::read(&name).unwrap(), b"hello");
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            assert_eq!(
+                fs::metadata(&name).unwrap().permissions().mode() & 0o777,
+                0o600
+            );
+        }
+        fs::remove_file(&name).unwrap();
+    }
+
+    #[test]
+    fn named_delete_semantics() {
+        let mut tmp = NamedTemporaryFile::new().unwrap();
+        let name = tmp.name().to_string();
+        tmp.write_text("abc").unwrap();
+        tmp.close().unwrap();
+        assert!(!StdPath::new(&name).exists());
+        assert!(tmp.write_text("x").unwrap_err().is("ValueError"));
+
+        let mut keep_until_drop = NamedTemporaryFile::builder()
+            .delete_on_close(false)
+            .named()
+            .unwrap();
+        let name = keep_until_drop.name().to_string();
+        keep_until_drop.close().unwrap();
+        assert!(StdPath::new(&name).exists());
+        drop(keep_until_drop);
+        assert!(!StdPath::new(&name).exists());
+
+        let kept = NamedTemporaryFile::builder().delete(false).named().unwrap();
+        let name = kept.name().to_string();
+        drop(kept);
+        assert!(StdPath::new(&name).exists());
+        fs::remove_file(&name).unwrap();
+    }
+
+    #[test]
+    fn named_from_keyword_arguments() {
+        let kwargs = PyKwargs::new()
+            .with("suffix", ".txt")
+            .with("delete", false)
+            .with("mode", "w+");
+        let tmp = NamedTemporaryFile::with_kwargs(kwargs).unwrap();
+        let name = tmp.name().to_string();
+        drop(tmp);
+        assert!(name.ends_with(".txt") && StdPath::new(&name).exists());
+        fs::remove_file(&name).unwrap();
+
+        let err = NamedTemporaryFile::with_kwargs(PyKwargs::new().with("suffix", 1)).unwrap_err();
+        assert_eq!(
+            err.message,
+            "NamedTemporaryFile() argument 'suffix' must be str, not int"
+        );
+        let kwargs = PyKwargs::new().with("mode", "r");
+        assert!(NamedTemporaryFile::with_kwargs(kwargs)
+            .unwrap_err()
+            .is("ValueError"));
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let mut tmp = TemporaryFile::new().unwrap();
+        assert_eq!(tmp.write_text("héllo").unwrap(), 5);
+        tmp.seek(SeekFrom::Start(0)).unwrap();
+        assert_eq!(tmp.read_text().unwrap(), "héllo");
+    }
+
+    #[test]
+    fn temporary_directory_cleanup() {
+        let dir = TemporaryDirectory::new(None, None, None).unwrap();
+        let name = dir.name().to_string();
+        fs::write(StdPath::new(&name).join("f"), b"x").unwrap();
+        drop(dir);
+        assert!(!StdPath::new(&name).exists());
+    }
+
+    #[test]
+    fn spooled_rolls_over_past_max_size() {
+        let mut spool = SpooledTemporaryFile::new(8);
+        spool.write_all(b"1234").unwrap();
+        assert!(!spool.rolled());
+        assert_eq!(spool.name(), None);
+        spool.write_all(b"56789").unwrap();
+        assert!(spool.rolled());
+        assert_eq!(spool.tell().unwrap(), 9);
+        spool.seek_whence(0, 0).unwrap();
+        assert_eq!(spool.read_text().unwrap(), "123456789");
+        spool.truncate(Some(3)).unwrap();
+        spool.seek(SeekFrom::Start(0)).unwrap();
+        assert_eq!(spool.read_text().unwrap(), "123");
+        spool.close().unwrap();
+        assert!(spool.closed());
+        assert!(spool.write_all(b"x").is_err());
+    }
+
+    #[test]
+    fn spooled_explicit_rollover_keeps_position() {
+        let mut spool = SpooledTemporaryFile::new(0);
+        spool.write_text("abcdef").unwrap();
+        spool.seek(SeekFrom::Start(2)).unwrap();
+        spool.rollover().unwrap();
+        assert!(spool.rolled());
+        assert_eq!(spool.read_text().unwrap(), "cdef");
+        #[cfg(unix)]
+        assert!(spool.name().is_some());
+    }
+
+    #[test]
+    fn tempdir_is_probed_then_cached() {
+        assert_eq!(gettempprefix(), "tmp");
+        assert_eq!(gettempprefixb(), b"tmp");
+        let dir = default_tempdir().unwrap();
+        assert!(StdPath::new(&dir).is_absolute() && StdPath::new(&dir).is_dir());
+        let cached = gettempdir().unwrap();
+        assert_eq!(gettempdirb().unwrap(), cached.as_bytes());
+        // Point tempdir at a directory every other test can also use.
+        set_tempdir(Some(&dir));
+        assert_eq!(gettempdir().unwrap(), dir);
+        set_tempdir(Some(&cached));
+    }
+}
@@ -0,0 +1,315 @@
+//! Python-equivalent time module: struct_time, gmtime(), asctime() and the
+//! strftime() formatter that datetime's strftime() methods share.
+//!
+//! Formatting follows the C locale of glibc, so %c, %x and %X do not depend
+//! on the host's locale settings.
+
+use alloc::format;
+use alloc::string::String;
+use core::fmt;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::datetime::{civil_from_days, days_from_civil, format_offset, is_leap};
+
+const WEEKDAYS: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// Python-equivalent time.struct_time. `tm_wday` counts from Monday = 0 and
+/// `tm_yday` from January 1st = 1, as in Python rather than C.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StructTime {
+    pub tm_year: i32,
+    pub tm_mon: u32,
+    pub tm_mday: u32,
+    pub tm_hour: u32,
+    pub tm_min: u32,
+    pub tm_sec: u32,
+    pub tm_wday: u32,
+    pub tm_yday: u32,
+    pub tm_isdst: i32,
+    pub tm_zone: Option<String>,
+    pub tm_gmtoff: Option<i32>,
+}
+
+impl fmt::Display for StructTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "time.struct_time(tm_year={}, tm_mon={}, tm_mday={}, tm_hour={}, tm_min={}, tm_sec={}, tm_wday={}, tm_yday={}, tm_isdst={})",
+            self.tm_year,
+            self.tm_mon,
+            self.tm_mday,
+            self.tm_hour,
+            self.tm_min,
+            self.tm_sec,
+            self.tm_wday,
+            self.tm_yday,
+            self.tm_isdst
+        )
+    }
+}
+
+/// Python-equivalent time.gmtime(secs): UTC broken-down time. Fractions of
+/// a second are floored, as CPython does.
+pub fn gmtime(secs: f64) -> StructTime {
+    let secs = libm_floor(secs) as i64;
+    let days = secs.div_euclid(86400);
+    let rem = secs.rem_euclid(86400) as u32;
+    let (year, month, day) = civil_from_days(days);
+    StructTime {
+        tm_year: year,
+        tm_mon: month,
+        tm_mday: day,
+        tm_hour: rem / 3600,
+        tm_min: rem / 60 % 60,
+        tm_sec: rem % 60,
+        // 1970-01-01 was a Thursday.
+        tm_wday: (days + 3).rem_euclid(7) as u32,
+        tm_yday: (days - days_from_civil(year, 1, 1) + 1) as u32,
+        tm_isdst: 0,
+        tm_zone: Some("GMT".into()),
+        tm_gmtoff: Some(0),
+    }
+}
+
+/// `f64::floor`, which core lacks.
+fn libm_floor(x: f64) -> f64 {
+    let t = x as i64 as f64;
+    if t > x {
+        t - 1.0
+    } else {
+        t
+    }
+}
+
+/// Python-equivalent time.asctime(t).
+pub fn asctime(t: &StructTime) -> PyResult<String> {
+    strftime("%c", t)
+}
+
+/// Python-equivalent time.strftime(format, t). Out-of-range fields raise
+/// ValueError; `%f` is left as written, as glibc does.
+pub fn strftime(format: &str, t: &StructTime) -> PyResult<String> {
+    let checks = [
+        (t.tm_mon, 1, 12, "month out of range"),
+        (t.tm_mday, 1, 31, "day of month out of range"),
+        (t.tm_hour, 0, 23, "hour out of range"),
+        (t.tm_min, 0, 59, "minute out of range"),
+        (t.tm_sec, 0, 61, "seconds out of range"),
+        (t.tm_wday, 0, 6, "day of week out of range"),
+        (t.tm_yday, 1, 366, "day of year out of range"),
+    ];
+    for (value, lo, hi, msg) in checks {
+        if !(lo..=hi).contains(&value) {
+            return Err(PyException::value_error(msg));
+        }
+    }
+    Ok(format_time(format, t, None))
+}
+
+/// ISO 8601 (year, week) of a struct_time, for %G and %V.
+fn iso_week(t: &StructTime) -> (i32, u32) {
+    let weeks_in = |year: i32| {
+        let jan1 = (days_from_civil(year, 1, 1) + 3).rem_euclid(7);
+        if jan1 == 3 || (jan1 == 2 && is_leap(year)) {
+            53
+        } else {
+            52
+        }
+    };
+    let week = (t.tm_yday as i64 - t.tm_wday as i64 + 9) / 7;
+    if week < 1 {
+        (t.tm_year - 1, weeks_in(t.tm_year - 1))
+    } else if week > weeks_in(t.tm_year) as i64 {
+        (t.tm_year + 1, 1)
+    } else {
+        (t.tm_year, week as u32)
+    }
+}
+
+/// The strftime() engine: one pass over `format`, so text produced by one
+/// directive is never rescanned for another. `microsecond` is datetime's
+/// `%f`; without it `%f` is copied through. Unknown directives are copied
+/// through as written.
+pub(crate) fn format_time(format: &str, t: &StructTime, microsecond: Option<u32>) -> String {
+    let mut out = String::with_capacity(format.len() + 16);
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        let Some(d) = chars.next() else {
+            out.push('%');
+            break;
+        };
+        let hour12 = match t.tm_hour % 12 {
+            0 => 12,
+            h => h,
+        };
+        let sunday_wday = (t.tm_wday + 1) % 7;
+        let yday0 = t.tm_yday.saturating_sub(1);
+        let weekday = WEEKDAYS[t.tm_wday as usize % 7];
+        let month = MONTHS[(t.tm_mon as usize + 11) % 12];
+        let piece = match d {
+            'a' => weekday[..3].into(),
+            'A' => weekday.into(),
+            'b' | 'h' => month[..3].into(),
+            'B' => month.into(),
+            'c' => format_time("%a %b %e %H:%M:%S %Y", t, microsecond),
+            'x' => format_time("%m/%d/%y", t, microsecond),
+            'X' => format_time("%H:%M:%S", t, microsecond),
+            'd' => format!("{:02}", t.tm_mday),
+            'e' => format!("{:>2}", t.tm_mday),
+            'f' => match microsecond {
+                Some(us) => format!("{:06}", us),
+                None => "%f".into(),
+            },
+            'H' => format!("{:02}", t.tm_hour),
+            'I' => format!("{:02}", hour12),
+            'j' => format!("{:03}", t.tm_yday),
+            'm' => format!("{:02}", t.tm_mon),
+            'M' => format!("{:02}", t.tm_min),
+            'p' => if t.tm_hour < 12 { "AM" } else { "PM" }.into(),
+            'S' => format!("{:02}", t.tm_sec),
+            'U' => format!("{:02}", (yday0 + 7 - sunday_wday) / 7),
+            'W' => format!("{:02}", (yday0 + 7 - t.tm_wday) / 7),
+            'w' => format!("{}", sunday_wday),
+            'u' => format!("{}", t.tm_wday + 1),
+            'y' => format!("{:02}", t.tm_year.rem_euclid(100)),
+            'Y' => format!("{}", t.tm_year),
+            'G' => format!("{}", iso_week(t).0),
+            'V' => format!("{:02}", iso_week(t).1),
+            'z' => t
+                .tm_gmtoff
+                .map(|o| format_offset(o, ""))
+                .unwrap_or_default(),
+            'Z' => t.tm_zone.clone().unwrap_or_default(),
+            '%' => "%".into(),
+            other => format!("%{}", other),
+        };
+        out.push_str(&piece);
+    }
+    out
+}
+
+/// Python-equivalent time.time(): seconds since the Unix epoch.
+#[cfg(feature = "std")]
+pub fn time() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |d| d.as_secs_f64())
+}
+
+/// Python-equivalent time.time_ns().
+#[cfg(feature = "std")]
+pub fn time_ns() -> u128 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos())
+}
+
+/// Python-equivalent time.sleep(secs).
+#[cfg(feature = "std")]
+pub fn sleep(secs: f64) -> PyResult<()> {
+    if secs.is_nan() || secs < 0.0 {
+        return Err(PyException::value_error(
+            "sleep length must be non-negative",
+        ));
+    }
+    let duration = std::time::Duration::try_from_secs_f64(secs).map_err(|_| {
+        PyException::new(
+            "OverflowError",
+            "timestamp too large to convert to C _PyTime_t",
+        )
+    })?;
+    std::thread::sleep(duration);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gmtime_matches_cpython() {
+        let t = gmtime(1320365123.5);
+        assert_eq!(
+            t.to_string(),
+            "time.struct_time(tm_year=2011, tm_mon=11, tm_mday=4, tm_hour=0, tm_min=5, tm_sec=23, tm_wday=4, tm_yday=308, tm_isdst=0)"
+        );
+        let t = gmtime(-1.5);
+        assert_eq!(
+            (t.tm_year, t.tm_yday, t.tm_sec, t.tm_wday),
+            (1969, 365, 58, 2)
+        );
+    }
+
+    #[test]
+    fn c_locale_formats() {
+        assert_eq!(asctime(&gmtime(0.0)).unwrap(), "Thu Jan  1 00:00:00 1970");
+        assert_eq!(
+            strftime("%c", &gmtime(1e9)).unwrap(),
+            "Sun Sep  9 01:46:40 2001"
+        );
+        assert_eq!(strftime("%Z %z", &gmtime(0.0)).unwrap(), "GMT +0000");
+        assert_eq!(
+            strftime("%f x %q 100%", &gmtime(0.0)).unwrap(),
+            "%f x %q 100%"
+        );
+    }
+
+    #[test]
+    fn week_numbers() {
+        // datetime(2023, 12, 31).strftime("%U %W %j %G-%V-%u")
+        let t = gmtime(1703980800.0);
+        assert_eq!(
+            strftime("%U %W %j %G-%V-%u", &t).unwrap(),
+            "53 52 365 2023-52-7"
+        );
+        // 2021-01-03 belongs to ISO week 53 of 2020.
+        let t = gmtime(1609632000.0);
+        assert_eq!(strftime("%G-%V %w", &t).unwrap(), "2020-53 0");
+    }
+
+    #[test]
+    fn percent_literals_are_not_rescanned() {
+        let mut t = gmtime(0.0);
+        t.tm_zone = Some("%Y".into());
+        assert_eq!(strftime("%%Y %Z %%%d", &t).unwrap(), "%Y %Y %01");
+        t.tm_mon = 13;
+        assert!(strftime("%d", &t).unwrap_err().is("ValueError"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn sleep_rejects_bad_lengths() {
+        assert!(sleep(-1.0).unwrap_err().is("ValueError"));
+        assert!(sleep(f64::NAN).unwrap_err().is("ValueError"));
+        assert!(sleep(f64::INFINITY).unwrap_err().is("OverflowError"));
+        assert!(sleep(1e20).unwrap_err().is("OverflowError"));
+        sleep(0.0).unwrap();
+    }
+}
@@ -0,0 +1,542 @@
+//! Python-equivalent typing module: the parts with runtime behaviour.
+//!
+//! Annotations are checked at compile time, so most of typing only has to
+//! exist for imports to resolve. What remains is [`TypeHint`] for
+//! isinstance() against Optional and Union, [`cast`], [`NewType`],
+//! [`TypedDict`] (a plain dict at runtime) and
+//! [`py_namedtuple!`](crate::py_namedtuple) for `class P(NamedTuple)`.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::dataclasses::Field;
+use crate::types::object::no_attribute;
+use crate::types::{PyDictionary, PyKwargs, PyTuple, PyTypeId, PyTyped, PyValue};
+
+/// Python-equivalent typing.TYPE_CHECKING: false whenever code runs.
+pub const TYPE_CHECKING: bool = false;
+
+/// A type expression evaluated at runtime: a class, typing.Any, or a Union
+/// (which Optional[X] and `X | None` are spellings of).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TypeHint {
+    Any,
+    Type(PyTypeId),
+    Union(Vec<TypeHint>),
+}
+
+impl TypeHint {
+    /// Python-equivalent Union[a, b, ...]: nested unions are flattened and
+    /// duplicates dropped, and a union of one member is that member.
+    pub fn union<I: IntoIterator<Item = TypeHint>>(members: I) -> TypeHint {
+        let mut flat: Vec<TypeHint> = Vec::new();
+        for member in members {
+            let parts = match member {
+                TypeHint::Union(parts) => parts,
+                other => alloc::vec![other],
+            };
+            for part in parts {
+                if !flat.contains(&part) {
+                    flat.push(part);
+                }
+            }
+        }
+        if flat.len() == 1 {
+            flat.pop().unwrap()
+        } else {
+            TypeHint::Union(flat)
+        }
+    }
+
+    /// Python-equivalent Optional[hint], i.e. Union[hint, None].
+    pub fn optional<H: Into<TypeHint>>(hint: H) -> TypeHint {
+        Self::union([hint.into(), TypeHint::Type(PyTypeId::NoneType)])
+    }
+
+    /// Python-equivalent typing.get_args(): a union's members, or nothing.
+    pub fn args(&self) -> &[TypeHint] {
+        match self {
+            TypeHint::Union(members) => members,
+            _ => &[],
+        }
+    }
+
+    /// True for a union that admits None.
+    pub fn is_optional(&self) -> bool {
+        self.args().contains(&TypeHint::Type(PyTypeId::NoneType))
+    }
+
+    /// Python-equivalent isinstance(obj, hint). Any cannot be checked and
+    /// raises TypeError, as in CPython.
+    pub fn check<T: PyTyped + ?Sized>(&self, obj: &T) -> PyResult<bool> {
+        self.check_type(obj.py_type())
+    }
+
+    fn check_type(&self, ty: PyTypeId) -> PyResult<bool> {
+        match self {
+            TypeHint::Any => Err(PyException::type_error(
+                "typing.Any cannot be used with isinstance()",
+            )),
+            TypeHint::Type(cls) => Ok(ty.is_subclass_of(*cls)),
+            TypeHint::Union(members) => {
+                let mut found = false;
+                for member in members {
+                    found |= member.check_type(ty)?;
+                }
+                Ok(found)
+            }
+        }
+    }
+
+    /// The name a union shows for a member: `None` for NoneType.
+    fn member_name(&self) -> String {
+        match self {
+            TypeHint::Type(PyTypeId::NoneType) => "None".to_string(),
+            TypeHint::Type(cls) => cls.name().to_string(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl From<PyTypeId> for TypeHint {
+    fn from(cls: PyTypeId) -> Self {
+        TypeHint::Type(cls)
+    }
+}
+
+/// The hint's repr: `<class 'int'>`, `typing.Any`, `typing.Optional[int]`
+/// or `typing.Union[int, str]`.
+impl fmt::Display for TypeHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeHint::Any => f.write_str("typing.Any"),
+            TypeHint::Type(cls) => write!(f, "{}", cls),
+            TypeHint::Union(members) => {
+                let none = TypeHint::Type(PyTypeId::NoneType);
+                if members.len() == 2 && members[1] == none {
+                    return write!(f, "typing.Optional[{}]", members[0].member_name());
+                }
+                let names: Vec<String> = members.iter().map(|m| m.member_name()).collect();
+                write!(f, "typing.Union[{}]", names.join(", "))
+            }
+        }
+    }
+}
+
+/// Python-equivalent isinstance(obj, hint) for a typing hint.
+pub fn isinstance<T: PyTyped + ?Sized>(obj: &T, hint: &TypeHint) -> PyResult<bool> {
+    hint.check(obj)
+}
+
+/// Python-equivalent typing.cast(typ, value): returns `value` unchanged.
+pub fn cast<T>(value: T) -> T {
+    value
+}
+
+/// Python-equivalent typing.get_type_hints(cls) for a class whose fields()
+/// metadata the compiler has: each field's annotation, as written in the
+/// Rust declaration.
+pub fn get_type_hints(fields: &[Field]) -> PyDictionary<String, String> {
+    let mut hints = PyDictionary::new();
+    for field in fields {
+        hints.set_item(field.name.to_string(), field.type_name.to_string());
+    }
+    hints
+}
+
+/// Python-equivalent typing.NewType(name, tp). Calling it returns its
+/// argument unchanged; the distinct type only exists for the checker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NewType {
+    name: &'static str,
+    supertype: PyTypeId,
+}
+
+impl NewType {
+    pub const fn new(name: &'static str, supertype: PyTypeId) -> Self {
+        NewType { name, supertype }
+    }
+
+    /// UserId(value).
+    pub fn call<T>(&self, value: T) -> T {
+        value
+    }
+
+    /// Python-equivalent `__name__`.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Python-equivalent `__supertype__`.
+    pub fn supertype(&self) -> PyTypeId {
+        self.supertype
+    }
+}
+
+impl fmt::Display for NewType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "__main__.{}", self.name)
+    }
+}
+
+/// A `class Movie(TypedDict)` definition. Instances are plain dicts; the
+/// class only records which keys it declares and whether they are required.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypedDict {
+    name: &'static str,
+    keys: Vec<(&'static str, TypeHint, bool)>,
+    total: bool,
+}
+
+impl TypedDict {
+    pub fn new(name: &'static str) -> Self {
+        TypedDict {
+            name,
+            keys: Vec::new(),
+            total: true,
+        }
+    }
+
+    /// `class Movie(TypedDict, total=False)`: keys default to NotRequired.
+    pub fn total(mut self, total: bool) -> Self {
+        self.total = total;
+        self
+    }
+
+    /// `key: hint`, required unless the class is not total.
+    pub fn key<H: Into<TypeHint>>(mut self, key: &'static str, hint: H) -> Self {
+        let required = self.total;
+        self.keys.push((key, hint.into(), required));
+        self
+    }
+
+    /// `key: Required[hint]` or `key: NotRequired[hint]`.
+    pub fn key_required<H: Into<TypeHint>>(
+        mut self,
+        key: &'static str,
+        hint: H,
+        required: bool,
+    ) -> Self {
+        self.keys.push((key, hint.into(), required));
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Python-equivalent `__total__`.
+    pub fn is_total(&self) -> bool {
+        self.total
+    }
+
+    /// Python-equivalent `__annotations__`, in declaration order.
+    pub fn annotations(&self) -> impl Iterator<Item = (&'static str, &TypeHint)> {
+        self.keys.iter().map(|(key, hint, _)| (*key, hint))
+    }
+
+    /// Python-equivalent `__required_keys__`.
+    pub fn required_keys(&self) -> Vec<&'static str> {
+        self.keys
+            .iter()
+            .filter(|(_, _, required)| *required)
+            .map(|(key, _, _)| *key)
+            .collect()
+    }
+
+    /// Python-equivalent `__optional_keys__`.
+    pub fn optional_keys(&self) -> Vec<&'static str> {
+        self.keys
+            .iter()
+            .filter(|(_, _, required)| !*required)
+            .map(|(key, _, _)| *key)
+            .collect()
+    }
+
+    /// Movie(key=value, ...): a dict of the arguments, unchecked like
+    /// CPython's.
+    pub fn call(&self, kwargs: PyKwargs) -> PyDictionary<PyValue, PyValue> {
+        let mut out = PyDictionary::new();
+        for (key, value) in kwargs.iter() {
+            out.set_item(PyValue::from(key.as_str()), value.clone());
+        }
+        out
+    }
+}
+
+/// Implemented by [`py_namedtuple!`](crate::py_namedtuple) for each
+/// compiled NamedTuple class.
+pub trait PyNamedTuple: PyTyped {
+    const NAME: &'static str;
+    const FIELDS: &'static [Field];
+
+    /// The instance as the tuple it is at runtime.
+    fn to_tuple(&self) -> PyTuple<PyValue>;
+}
+
+/// Python-equivalent `nt._fields`.
+pub fn fields<T: PyNamedTuple>() -> Vec<&'static str> {
+    T::FIELDS.iter().map(|f| f.name).collect()
+}
+
+/// Python-equivalent `nt._asdict()`.
+pub fn asdict<T: PyNamedTuple + ?Sized>(obj: &T) -> PyDictionary<String, PyValue> {
+    let mut out = PyDictionary::new();
+    for (field, value) in T::FIELDS.iter().zip(obj.to_tuple().into_vec()) {
+        out.set_item(field.name.to_string(), value);
+    }
+    out
+}
+
+/// Python-equivalent `nt[index]`, with negative indices counting from the end.
+pub fn getitem<T: PyNamedTuple + ?Sized>(obj: &T, index: i64) -> PyResult<PyValue> {
+    obj.to_tuple().get(index).cloned()
+}
+
+/// The generated `__setattr__`/`__delattr__`: fields are read-only and
+/// there is no `__dict__` for anything else.
+pub fn reject_setattr<T: PyNamedTuple + ?Sized>(name: &str) -> PyResult<()> {
+    if T::FIELDS.iter().any(|f| f.name == name) {
+        Err(PyException::attribute_error("can't set attribute"))
+    } else {
+        Err(no_attribute(T::NAME, name))
+    }
+}
+
+/// The generated `__repr__`: `Point(x=1, y=2)`.
+pub fn repr_namedtuple<T: PyNamedTuple + ?Sized>(obj: &T) -> String {
+    let parts: Vec<String> = T::FIELDS
+        .iter()
+        .zip(obj.to_tuple().iter())
+        .map(|(field, value)| format!("{}={}", field.name, crate::types::PyRepr::py_repr(value)))
+        .collect();
+    format!("{}({})", T::NAME, parts.join(", "))
+}
+
+/// Declares a compiled `class Name(NamedTuple)`.
+///
+/// Fields are written `name: Type` or `name: Type = default`, and `new()`
+/// takes `Option<Type>` for the defaulted ones. Instances are tuples to
+/// isinstance(), compare and hash as tuples, and convert to
+/// `PyValue::Tuple`. Field types need `Clone + PartialEq + PartialOrd` and
+/// `Into<PyValue>`.
+#[macro_export]
+macro_rules! py_namedtuple {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$fmeta:meta])*
+                $fvis:vis $field:ident : $ty:ty $(= $default:expr)?
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Clone)]
+        $vis struct $name {
+            $($(#[$fmeta])* $fvis $field: $ty,)*
+        }
+
+        impl $name {
+            /// The generated `__new__`.
+            #[allow(clippy::too_many_arguments)]
+            pub fn new(
+                $($field: $crate::py_dataclass!(@param $ty $(= $default)?)),*
+            ) -> Self {
+                $name {
+                    $($field: $crate::py_dataclass!(@value $field $(= $default)?),)*
+                }
+            }
+        }
+
+        impl $crate::stdlib::typing::PyNamedTuple for $name {
+            const NAME: &'static str = stringify!($name);
+            const FIELDS: &'static [$crate::stdlib::dataclasses::Field] = &[$(
+                $crate::stdlib::dataclasses::Field {
+                    name: stringify!($field),
+                    type_name: stringify!($ty),
+                    default: $crate::py_dataclass!(@default $(= $default)?),
+                }
+            ),*];
+
+            fn to_tuple(&self) -> $crate::types::PyTuple<$crate::types::PyValue> {
+                $crate::types::PyTuple::from_vec($crate::stdlib::typing::Values::from([
+                    $($crate::types::PyValue::from(self.$field.clone())),*
+                ]))
+            }
+        }
+
+        impl $crate::types::PyTyped for $name {
+            fn py_type(&self) -> $crate::types::PyTypeId {
+                $crate::types::register_class(stringify!($name), &[$crate::types::PyTypeId::Tuple])
+            }
+        }
+
+        impl $crate::types::PyRepr for $name {
+            fn py_repr(&self) -> $crate::stdlib::dataclasses::ReprString {
+                $crate::stdlib::typing::repr_namedtuple(self)
+            }
+        }
+
+        impl ::core::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.write_str(&$crate::types::PyRepr::py_repr(self))
+            }
+        }
+
+        impl PartialEq for $name {
+            fn eq(&self, other: &Self) -> bool {
+                true $(&& self.$field == other.$field)*
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<::core::cmp::Ordering> {
+                $(
+                    match self.$field.partial_cmp(&other.$field)? {
+                        ::core::cmp::Ordering::Equal => {}
+                        ord => return Some(ord),
+                    }
+                )*
+                Some(::core::cmp::Ordering::Equal)
+            }
+        }
+
+        impl $crate::types::PyHash for $name {
+            fn py_hash(&self) -> $crate::exceptions::PyResult<i64> {
+                $crate::types::PyHash::py_hash(
+                    &$crate::stdlib::typing::PyNamedTuple::to_tuple(self),
+                )
+            }
+        }
+
+        impl $crate::types::PyLen for $name {
+            fn py_len(&self) -> usize {
+                <$name as $crate::stdlib::typing::PyNamedTuple>::FIELDS.len()
+            }
+        }
+
+        impl From<$name> for $crate::types::PyValue {
+            fn from(obj: $name) -> Self {
+                $crate::types::PyValue::Tuple($crate::stdlib::typing::PyNamedTuple::to_tuple(&obj))
+            }
+        }
+
+        impl $crate::types::PyObjectProtocol for $name {
+            fn get_attr(&self, name: &str) -> $crate::exceptions::PyResult<$crate::types::PyValue> {
+                match name {
+                    $(stringify!($field) => Ok($crate::types::PyValue::from(self.$field.clone())),)*
+                    _ => Err($crate::types::object::no_attribute(stringify!($name), name)),
+                }
+            }
+
+            fn set_attr(&mut self, name: &str, _value: $crate::types::PyValue) -> $crate::exceptions::PyResult<()> {
+                $crate::stdlib::typing::reject_setattr::<Self>(name)
+            }
+
+            fn del_attr(&mut self, name: &str) -> $crate::exceptions::PyResult<()> {
+                $crate::stdlib::typing::reject_setattr::<Self>(name)
+            }
+
+            fn dir(&self) -> $crate::stdlib::dataclasses::Names {
+                $crate::stdlib::dataclasses::Names::from([$(stringify!($field).into()),*])
+            }
+        }
+    };
+}
+
+/// The field values [`py_namedtuple!`](crate::py_namedtuple) collects,
+/// named here so the expansion compiles without `alloc` in scope.
+pub type Values = Vec<PyValue>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{PyHash, PyLen, PyObjectProtocol, PyRepr};
+
+    crate::py_namedtuple! {
+        struct TypingPoint {
+            x: i64,
+            y: i64 = 0,
+        }
+    }
+
+    #[test]
+    fn optional_and_union_checks() {
+        let hint = TypeHint::optional(PyTypeId::Int);
+        assert!(hint.is_optional());
+        assert_eq!(hint.to_string(), "typing.Optional[int]");
+        assert!(isinstance(&3i64, &hint).unwrap());
+        assert!(isinstance(&PyValue::None, &hint).unwrap());
+        assert!(isinstance(&true, &hint).unwrap());
+        assert!(!isinstance("x", &hint).unwrap());
+
+        let nested = TypeHint::union([
+            TypeHint::from(PyTypeId::Str),
+            TypeHint::union([PyTypeId::Int.into(), PyTypeId::Str.into()]),
+        ]);
+        assert_eq!(nested.args().len(), 2);
+        assert_eq!(nested.to_string(), "typing.Union[str, int]");
+        assert_eq!(
+            TypeHint::union([PyTypeId::Int.into()]),
+            PyTypeId::Int.into()
+        );
+        assert_eq!(
+            TypeHint::union([PyTypeId::Int.into(), TypeHint::Any])
+                .check(&1i64)
+                .unwrap_err()
+                .to_string(),
+            "TypeError: typing.Any cannot be used with isinstance()"
+        );
+    }
+
+    #[test]
+    fn passthrough_shims() {
+        const USER_ID: NewType = NewType::new("UserId", PyTypeId::Int);
+        assert_eq!(USER_ID.call(5i64), 5);
+        assert_eq!(USER_ID.supertype(), PyTypeId::Int);
+        assert_eq!(USER_ID.to_string(), "__main__.UserId");
+        assert_eq!(cast::<&str>("s"), "s");
+
+        let movie = TypedDict::new("Movie")
+            .key("name", PyTypeId::Str)
+            .key_required("year", PyTypeId::Int, false);
+        assert_eq!(movie.required_keys(), ["name"]);
+        assert_eq!(movie.optional_keys(), ["year"]);
+        let value = movie.call(PyKwargs::new().with("name", "Blade Runner"));
+        assert_eq!(PyValue::Dict(value).py_repr(), "{'name': 'Blade Runner'}");
+    }
+
+    #[test]
+    fn namedtuple_behaves_as_tuple() {
+        let p = TypingPoint::new(1, None);
+        assert_eq!(p.py_repr(), "TypingPoint(x=1, y=0)");
+        assert_eq!(fields::<TypingPoint>(), ["x", "y"]);
+        assert_eq!(p.py_len(), 2);
+        assert_eq!(getitem(&p, -2).unwrap(), PyValue::Int(1));
+        assert!(p < TypingPoint::new(1, Some(1)));
+        assert_eq!(
+            p.py_hash(),
+            PyTuple::from_vec(alloc::vec![PyValue::Int(1), PyValue::Int(0)]).py_hash()
+        );
+        assert!(crate::builtins::isinstance(&p, PyTypeId::Tuple));
+        assert_eq!(asdict(&p).get("y"), Some(&PyValue::Int(0)));
+        assert_eq!(
+            get_type_hints(TypingPoint::FIELDS)
+                .get("x")
+                .map(String::as_str),
+            Some("i64")
+        );
+
+        let mut p = p;
+        assert_eq!(p.get_attr("x").unwrap(), PyValue::Int(1));
+        assert_eq!(
+            p.set_attr("x", PyValue::Int(2)).unwrap_err().to_string(),
+            "AttributeError: can't set attribute"
+        );
+        assert!(p.del_attr("z").unwrap_err().is("AttributeError"));
+    }
+}
@@ -0,0 +1,665 @@
+//! Python-equivalent unittest module, enough to compile and run a test file:
+//! TestCase assertions, a registry test functions add themselves to, and a
+//! runner reporting as unittest's text runner or as TAP.
+//!
+//! A compiled `class TestMath(unittest.TestCase)` registers each `test_*`
+//! method with [`register`]; `unittest.main()` runs them sorted by name, as
+//! unittest's loader does. A test passes by returning `Ok`, fails with an
+//! AssertionError, is skipped with [`SkipTest`](TestCase::skip_test), and
+//! errors with any other exception or a panic.
+
+use std::any::Any;
+use std::io::{self, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Instant;
+
+use crate::exceptions::{PyException, PyResult};
+use crate::sync::Lock;
+use crate::types::{PyRepr, PyTruthy};
+
+/// The exception type name TestCase::skip_test() raises.
+pub const SKIP_TEST: &str = "unittest.SkipTest";
+
+/// A test method: takes its TestCase the way a method takes `self`.
+pub type TestFn = fn(&mut TestCase) -> PyResult<()>;
+
+/// Python-equivalent unittest.TestCase, as seen from inside one test.
+#[derive(Debug, Clone)]
+pub struct TestCase {
+    class: String,
+    method: String,
+    /// Python-equivalent TestCase.longMessage: append `msg` to the standard
+    /// failure message instead of replacing it.
+    pub long_message: bool,
+}
+
+impl TestCase {
+    pub fn new(class: &str, method: &str) -> Self {
+        TestCase {
+            class: class.to_string(),
+            method: method.to_string(),
+            long_message: true,
+        }
+    }
+
+    /// Python-equivalent TestCase.id(): `Class.method`.
+    pub fn id(&self) -> String {
+        format!("{}.{}", self.class, self.method)
+    }
+
+    /// The AssertionError for a failed check, combining the standard message
+    /// with `msg` as TestCase._formatMessage does.
+    fn failure(&self, standard: String, msg: Option<&str>) -> PyException {
+        PyException::assertion_error(match msg {
+            None => standard,
+            Some(msg) if self.long_message => format!("{} : {}", standard, msg),
+            Some(msg) => msg.to_string(),
+        })
+    }
+
+    /// Python-equivalent TestCase.fail(msg).
+    pub fn fail(&self, msg: Option<&str>) -> PyResult<()> {
+        Err(PyException::assertion_error(msg.unwrap_or_default()))
+    }
+
+    /// Python-equivalent TestCase.skipTest(reason).
+    pub fn skip_test(&self, reason: &str) -> PyResult<()> {
+        Err(PyException::new(SKIP_TEST, reason))
+    }
+
+    /// Python-equivalent TestCase.assertEqual(first, second, msg).
+    pub fn assert_equal<A, B>(&self, first: &A, second: &B, msg: Option<&str>) -> PyResult<()>
+    where
+        A: PartialEq<B> + PyRepr + ?Sized,
+        B: PyRepr + ?Sized,
+    {
+        if first == second {
+            return Ok(());
+        }
+        let standard = format!("{} != {}", first.py_repr(), second.py_repr());
+        Err(self.failure(standard, msg))
+    }
+
+    /// Python-equivalent TestCase.assertNotEqual(first, second, msg).
+    pub fn assert_not_equal<A, B>(&self, first: &A, second: &B, msg: Option<&str>) -> PyResult<()>
+    where
+        A: PartialEq<B> + PyRepr + ?Sized,
+        B: PyRepr + ?Sized,
+    {
+        if first != second {
+            return Ok(());
+        }
+        let standard = format!("{} == {}", first.py_repr(), second.py_repr());
+        Err(self.failure(standard, msg))
+    }
+
+    /// Python-equivalent TestCase.assertTrue(expr, msg).
+    pub fn assert_true<T: PyTruthy + PyRepr + ?Sized>(
+        &self,
+        expr: &T,
+        msg: Option<&str>,
+    ) -> PyResult<()> {
+        if expr.py_bool() {
+            return Ok(());
+        }
+        Err(self.failure(format!("{} is not true", expr.py_repr()), msg))
+    }
+
+    /// Python-equivalent TestCase.assertFalse(expr, msg).
+    pub fn assert_false<T: PyTruthy + PyRepr + ?Sized>(
+        &self,
+        expr: &T,
+        msg: Option<&str>,
+    ) -> PyResult<()> {
+        if !expr.py_bool() {
+            return Ok(());
+        }
+        Err(self.failure(format!("{} is not false", expr.py_repr()), msg))
+    }
+
+    /// Python-equivalent TestCase.assertRaises(exception, callable): the
+    /// exception `f` raised if it has type `exception`. Any other exception
+    /// propagates; success is a failure.
+    pub fn assert_raises<T, F>(&self, exception: &str, f: F) -> PyResult<PyException>
+    where
+        F: FnOnce() -> PyResult<T>,
+    {
+        match f() {
+            Err(err) if err.is(exception) => Ok(err),
+            Err(err) => Err(err),
+            Ok(_) => Err(PyException::assertion_error(format!(
+                "{} not raised",
+                exception
+            ))),
+        }
+    }
+
+    /// Python-equivalent TestCase.assertAlmostEqual(first, second, places,
+    /// msg, delta): equal after rounding the difference to `places` decimal
+    /// places (default 7), or within `delta` when given.
+    pub fn assert_almost_equal(
+        &self,
+        first: f64,
+        second: f64,
+        places: Option<u32>,
+        msg: Option<&str>,
+        delta: Option<f64>,
+    ) -> PyResult<()> {
+        if places.is_some() && delta.is_some() {
+            return Err(PyException::type_error("specify delta or places not both"));
+        }
+        if first == second {
+            return Ok(());
+        }
+        let diff = (first - second).abs();
+        let standard = match delta {
+            Some(delta) if diff <= delta => return Ok(()),
+            Some(delta) => format!(
+                "{} != {} within {} delta ({} difference)",
+                first.py_repr(),
+                second.py_repr(),
+                delta.py_repr(),
+                diff.py_repr()
+            ),
+            None => {
+                let places = places.unwrap_or(7);
+                let scale = 10f64.powi(places as i32);
+                if (diff * scale).round() == 0.0 {
+                    return Ok(());
+                }
+                format!(
+                    "{} != {} within {} places ({} difference)",
+                    first.py_repr(),
+                    second.py_repr(),
+                    places,
+                    diff.py_repr()
+                )
+            }
+        };
+        Err(self.failure(standard, msg))
+    }
+}
+
+/// One registered test method.
+#[derive(Debug, Clone)]
+struct Test {
+    class: String,
+    method: String,
+    func: TestFn,
+}
+
+static REGISTRY: Lock<Vec<Test>> = Lock::new(Vec::new());
+
+/// Registers `Class.method` so [`main`] and [`TestSuite::discover`] find it.
+pub fn register(class: &str, method: &str, func: TestFn) {
+    REGISTRY.lock().push(Test {
+        class: class.to_string(),
+        method: method.to_string(),
+        func,
+    });
+}
+
+/// Python-equivalent unittest.TestSuite: tests in the order they run.
+#[derive(Debug, Clone, Default)]
+pub struct TestSuite {
+    tests: Vec<Test>,
+}
+
+impl TestSuite {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every registered test, sorted by class and then method name as
+    /// unittest.TestLoader orders them.
+    pub fn discover() -> Self {
+        let mut tests = REGISTRY.lock().clone();
+        tests.sort_by(|a, b| (&a.class, &a.method).cmp(&(&b.class, &b.method)));
+        TestSuite { tests }
+    }
+
+    /// Python-equivalent TestSuite.addTest(Class('method')).
+    pub fn add_test(&mut self, class: &str, method: &str, func: TestFn) {
+        self.tests.push(Test {
+            class: class.to_string(),
+            method: method.to_string(),
+            func,
+        });
+    }
+
+    /// Python-equivalent TestSuite.countTestCases().
+    pub fn count_test_cases(&self) -> usize {
+        self.tests.len()
+    }
+}
+
+/// How one test ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Success,
+    /// An AssertionError, with its message.
+    Failure(String),
+    /// Any other exception or a panic, formatted as `Type: message`.
+    Error(String),
+    /// The skip reason.
+    Skipped(String),
+}
+
+/// Python-equivalent unittest.TestResult: every test id with its outcome.
+#[derive(Debug, Clone, Default)]
+pub struct TestResult {
+    pub outcomes: Vec<(String, Outcome)>,
+}
+
+impl TestResult {
+    /// Python-equivalent TestResult.testsRun.
+    pub fn tests_run(&self) -> usize {
+        self.outcomes.len()
+    }
+
+    fn count(&self, f: fn(&Outcome) -> bool) -> usize {
+        self.outcomes.iter().filter(|(_, o)| f(o)).count()
+    }
+
+    pub fn failures(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Failure(_)))
+    }
+
+    pub fn errors(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Error(_)))
+    }
+
+    pub fn skipped(&self) -> usize {
+        self.count(|o| matches!(o, Outcome::Skipped(_)))
+    }
+
+    /// Python-equivalent TestResult.wasSuccessful().
+    pub fn was_successful(&self) -> bool {
+        self.failures() == 0 && self.errors() == 0
+    }
+}
+
+/// The message a panic was raised with, e.g. by [`PyException::raise`].
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<String>()
+        .cloned()
+        .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+        .unwrap_or_else(|| "panic".to_string())
+}
+
+fn run_test(test: &Test) -> Outcome {
+    let mut case = TestCase::new(&test.class, &test.method);
+    match panic::catch_unwind(AssertUnwindSafe(|| (test.func)(&mut case))) {
+        Ok(Ok(())) => Outcome::Success,
+        Ok(Err(err)) if err.is("AssertionError") => Outcome::Failure(err.to_string()),
+        Ok(Err(err)) if err.is(SKIP_TEST) => Outcome::Skipped(err.message),
+        Ok(Err(err)) => Outcome::Error(err.to_string()),
+        Err(payload) => Outcome::Error(panic_message(payload.as_ref())),
+    }
+}
+
+/// The report a runner writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Report {
+    /// unittest's TextTestRunner output.
+    #[default]
+    Text,
+    /// Test Anything Protocol, version 13.
+    Tap,
+}
+
+/// Python-equivalent unittest.TextTestRunner, also able to write TAP.
+#[derive(Debug, Clone, Copy)]
+pub struct TextTestRunner {
+    verbosity: u32,
+    report: Report,
+}
+
+impl Default for TextTestRunner {
+    fn default() -> Self {
+        TextTestRunner {
+            verbosity: 1,
+            report: Report::Text,
+        }
+    }
+}
+
+impl TextTestRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 0 prints only the summary, 1 a character per test, 2 a line per test.
+    pub fn verbosity(mut self, verbosity: u32) -> Self {
+        self.verbosity = verbosity;
+        self
+    }
+
+    pub fn report(mut self, report: Report) -> Self {
+        self.report = report;
+        self
+    }
+
+    /// Python-equivalent TextTestRunner.run(suite), reporting to stderr.
+    pub fn run(&self, suite: &TestSuite) -> PyResult<TestResult> {
+        self.run_to(suite, &mut io::stderr().lock())
+    }
+
+    /// Runs `suite`, writing the report to `out` as each test finishes.
+    pub fn run_to<W: Write + ?Sized>(
+        &self,
+        suite: &TestSuite,
+        out: &mut W,
+    ) -> PyResult<TestResult> {
+        let start = Instant::now();
+        let mut result = TestResult::default();
+        if self.report == Report::Tap {
+            writeln!(out, "TAP version 13")?;
+            writeln!(out, "1..{}", suite.tests.len())?;
+        }
+        for (n, test) in suite.tests.iter().enumerate() {
+            let id = format!("{}.{}", test.class, test.method);
+            if self.report == Report::Text && self.verbosity > 1 {
+                write!(out, "{} ({}) ... ", test.method, id)?;
+                out.flush()?;
+            }
+            let outcome = run_test(test);
+            match self.report {
+                Report::Text => self.write_progress(out, &outcome)?,
+                Report::Tap => write_tap_line(out, n + 1, &id, &outcome)?,
+            }
+            result.outcomes.push((id, outcome));
+        }
+        if self.report == Report::Text {
+            self.write_summary(out, &result, start.elapsed().as_secs_f64())?;
+        }
+        Ok(result)
+    }
+
+    fn write_progress<W: Write + ?Sized>(&self, out: &mut W, outcome: &Outcome) -> io::Result<()> {
+        match self.verbosity {
+            0 => Ok(()),
+            1 => {
+                let c = match outcome {
+                    Outcome::Success => '.',
+                    Outcome::Failure(_) => 'F',
+                    Outcome::Error(_) => 'E',
+                    Outcome::Skipped(_) => 's',
+                };
+                write!(out, "{}", c)?;
+                out.flush()
+            }
+            _ => match outcome {
+                Outcome::Success => writeln!(out, "ok"),
+                Outcome::Failure(_) => writeln!(out, "FAIL"),
+                Outcome::Error(_) => writeln!(out, "ERROR"),
+                Outcome::Skipped(reason) => writeln!(out, "skipped {}", reason.py_repr()),
+            },
+        }
+    }
+
+    fn write_summary<W: Write + ?Sized>(
+        &self,
+        out: &mut W,
+        result: &TestResult,
+        seconds: f64,
+    ) -> io::Result<()> {
+        if self.verbosity == 1 {
+            writeln!(out)?;
+        }
+        let rule = "=".repeat(70);
+        let thin = "-".repeat(70);
+        for (id, outcome) in &result.outcomes {
+            let (kind, detail) = match outcome {
+                Outcome::Failure(detail) => ("FAIL", detail),
+                Outcome::Error(detail) => ("ERROR", detail),
+                _ => continue,
+            };
+            let method = id.rsplit('.').next().unwrap_or(id);
+            writeln!(out, "{}", rule)?;
+            writeln!(out, "{}: {} ({})", kind, method, id)?;
+            writeln!(out, "{}", thin)?;
+            writeln!(out, "{}", detail)?;
+            writeln!(out)?;
+        }
+        writeln!(out, "{}", thin)?;
+        let n = result.tests_run();
+        writeln!(
+            out,
+            "Ran {} test{} in {:.3}s",
+            n,
+            if n == 1 { "" } else { "s" },
+            seconds
+        )?;
+        writeln!(out)?;
+        let mut counts = Vec::new();
+        for (name, count) in [
+            ("failures", result.failures()),
+            ("errors", result.errors()),
+            ("skipped", result.skipped()),
+        ] {
+            if count > 0 {
+                counts.push(format!("{}={}", name, count));
+            }
+        }
+        let status = if result.was_successful() {
+            "OK"
+        } else {
+            "FAILED"
+        };
+        if counts.is_empty() {
+            writeln!(out, "{}", status)
+        } else {
+            writeln!(out, "{} ({})", status, counts.join(", "))
+        }
+    }
+}
+
+fn write_tap_line<W: Write + ?Sized>(
+    out: &mut W,
+    n: usize,
+    id: &str,
+    outcome: &Outcome,
+) -> io::Result<()> {
+    match outcome {
+        Outcome::Success => writeln!(out, "ok {} - {}", n, id),
+        Outcome::Skipped(reason) => writeln!(out, "ok {} - {} # SKIP {}", n, id, reason),
+        Outcome::Failure(detail) | Outcome::Error(detail) => {
+            writeln!(out, "not ok {} - {}", n, id)?;
+            detail
+                .lines()
+                .try_for_each(|line| writeln!(out, "# {}", line))
+        }
+    }
+}
+
+/// Python-equivalent unittest.main(): runs every registered test with the
+/// text runner and exits with status 0 if all passed, 1 otherwise. Set
+/// `UNITTEST_TAP=1` to report as TAP on stdout instead.
+pub fn main() -> ! {
+    let suite = TestSuite::discover();
+    let result = if std::env::var_os("UNITTEST_TAP").is_some_and(|v| v == "1") {
+        TextTestRunner::new()
+            .report(Report::Tap)
+            .run_to(&suite, &mut io::stdout().lock())
+    } else {
+        TextTestRunner::new().run(&suite)
+    };
+    let ok = match result {
+        Ok(result) => result.was_successful(),
+        Err(err) => {
+            eprintln!("{}", err);
+            false
+        }
+    };
+    std::process::exit(if ok { 0 } else { 1 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::py_assert;
+    use crate::types::PyList;
+
+    fn checked_div(a: i64, b: i64) -> PyResult<i64> {
+        py_assert!(b != 0, format!("cannot divide {} by zero", a));
+        Ok(a / b)
+    }
+
+    fn all_positive(xs: &[i64]) -> PyResult<()> {
+        py_assert!(xs.iter().all(|x| *x > 0));
+        Ok(())
+    }
+
+    #[test]
+    fn py_assert_raises_assertion_error() {
+        assert_eq!(checked_div(6, 3).unwrap(), 2);
+        assert_eq!(
+            checked_div(6, 0).unwrap_err().to_string(),
+            "AssertionError: cannot divide 6 by zero"
+        );
+        assert_eq!(
+            all_positive(&[1, -1]).unwrap_err().to_string(),
+            "AssertionError"
+        );
+    }
+
+    #[test]
+    fn assertion_messages_match_cpython() {
+        let mut t = TestCase::new("T", "test_x");
+        let msg = |r: PyResult<()>| r.unwrap_err().message;
+        assert_eq!(msg(t.assert_equal(&1, &2, None)), "1 != 2");
+        assert_eq!(
+            msg(t.assert_equal("a", "b", Some("why"))),
+            "'a' != 'b' : why"
+        );
+        t.long_message = false;
+        assert_eq!(msg(t.assert_equal(&1, &2, Some("why"))), "why");
+        assert_eq!(msg(t.assert_not_equal(&1, &1, None)), "1 == 1");
+        assert_eq!(msg(t.assert_true(&0, None)), "0 is not true");
+        assert_eq!(msg(t.assert_false(&"x", None)), "'x' is not false");
+        t.assert_almost_equal(0.1 + 0.2, 0.3, None, None, None)
+            .unwrap();
+        assert_eq!(
+            msg(t.assert_almost_equal(1.0, 1.1, Some(3), None, None)),
+            "1.0 != 1.1 within 3 places (0.10000000000000009 difference)"
+        );
+        t.assert_almost_equal(1.0, 1.5, None, None, Some(0.5))
+            .unwrap();
+        assert!(t
+            .assert_almost_equal(1.0, 2.0, Some(1), None, Some(0.5))
+            .unwrap_err()
+            .is("TypeError"));
+        let err = t.assert_raises("ValueError", || crate::int("x")).unwrap();
+        assert!(err.message.starts_with("invalid literal"));
+        assert_eq!(
+            t.assert_raises("KeyError", || crate::int("1"))
+                .unwrap_err()
+                .message,
+            "KeyError not raised"
+        );
+        assert!(t
+            .assert_raises("KeyError", || crate::int("x"))
+            .unwrap_err()
+            .is("ValueError"));
+    }
+
+    fn passes(t: &mut TestCase) -> PyResult<()> {
+        t.assert_equal(&t.id(), "Sample.test_a", None)
+    }
+
+    fn fails(t: &mut TestCase) -> PyResult<()> {
+        t.assert_equal(
+            &PyList::from_vec(vec![1, 2]),
+            &PyList::from_vec(vec![1, 3]),
+            None,
+        )
+    }
+
+    fn errors(_: &mut TestCase) -> PyResult<()> {
+        crate::int("nope")?;
+        Ok(())
+    }
+
+    fn skips(t: &mut TestCase) -> PyResult<()> {
+        t.skip_test("not today")
+    }
+
+    fn panics(_: &mut TestCase) -> PyResult<()> {
+        PyException::index_error("list index out of range").raise()
+    }
+
+    fn sample_suite() -> TestSuite {
+        let mut suite = TestSuite::new();
+        suite.add_test("Sample", "test_a", passes);
+        suite.add_test("Sample", "test_b", fails);
+        suite.add_test("Sample", "test_c", errors);
+        suite.add_test("Sample", "test_d", skips);
+        suite.add_test("Sample", "test_e", panics);
+        suite
+    }
+
+    #[test]
+    fn text_report_matches_unittest() {
+        let mut out = Vec::new();
+        let result = TextTestRunner::new()
+            .run_to(&sample_suite(), &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(result.tests_run(), 5);
+        assert_eq!(
+            (result.failures(), result.errors(), result.skipped()),
+            (1, 2, 1)
+        );
+        assert!(!result.was_successful());
+        assert!(out.starts_with(".FEsE\n"));
+        assert!(out.contains("FAIL: test_b (Sample.test_b)\n"));
+        assert!(out.contains("AssertionError: [1, 2] != [1, 3]\n"));
+        assert!(out.contains("ERROR: test_e (Sample.test_e)\n"));
+        assert!(out.contains("IndexError: list index out of range\n"));
+        assert!(out.contains("Ran 5 tests in "));
+        assert!(out.ends_with("\nFAILED (failures=1, errors=2, skipped=1)\n"));
+
+        let mut out = Vec::new();
+        TextTestRunner::new()
+            .verbosity(2)
+            .run_to(&sample_suite(), &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("test_a (Sample.test_a) ... ok\ntest_b (Sample.test_b) ... FAIL\n"));
+        assert!(out.contains("test_d (Sample.test_d) ... skipped 'not today'\n"));
+    }
+
+    #[test]
+    fn tap_report() {
+        let mut out = Vec::new();
+        TextTestRunner::new()
+            .report(Report::Tap)
+            .run_to(&sample_suite(), &mut out)
+            .unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let expected = "TAP version 13\n1..5\nok 1 - Sample.test_a\n\
+                        not ok 2 - Sample.test_b\n# AssertionError: [1, 2] != [1, 3]\n\
+                        not ok 3 - Sample.test_c\n\
+                        # ValueError: invalid literal for int() with base 10: 'nope'\n\
+                        ok 4 - Sample.test_d # SKIP not today\n\
+                        not ok 5 - Sample.test_e\n# IndexError: list index out of range\n";
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn discover_sorts_registered_tests() {
+        register("Zeta", "test_b", passes);
+        register("Zeta", "test_a", passes);
+        register("Alpha", "test_z", passes);
+        let suite = TestSuite::discover();
+        let ids: Vec<String> = suite
+            .tests
+            .iter()
+            .map(|t| format!("{}.{}", t.class, t.method))
+            .collect();
+        assert_eq!(ids, ["Alpha.test_z", "Zeta.test_a", "Zeta.test_b"]);
+        assert_eq!(suite.count_test_cases(), 3);
+    }
+}
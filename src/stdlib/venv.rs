@@ -0,0 +1,522 @@
+//! Python-equivalent venv module: creates CPython virtual environments.
+//!
+//! The base interpreter is the first `python3`/`python` on PATH unless one is given
+//! explicitly with [`EnvBuilder::executable`].
+
+use std::env;
+use std::fs;
+use std::path::{Path as StdPath, PathBuf};
+use std::process::Command;
+
+use crate::exceptions::{PyException, PyResult};
+
+const CONFIG_NAME: &str = "pyvenv.cfg";
+
+/// Paths inside a created environment, like the context object venv passes to its hooks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VenvContext {
+    pub env_dir: PathBuf,
+    pub env_name: String,
+    pub prompt: String,
+    pub executable: PathBuf,
+    pub python_version: String,
+    pub bin_path: PathBuf,
+    pub inc_path: PathBuf,
+    pub site_packages: PathBuf,
+    pub env_exe: PathBuf,
+}
+
+/// Python-equivalent venv.EnvBuilder.
+#[derive(Debug, Clone, Default)]
+pub struct EnvBuilder {
+    pub system_site_packages: bool,
+    pub clear: bool,
+    pub symlinks: bool,
+    pub upgrade: bool,
+    pub with_pip: bool,
+    pub prompt: Option<String>,
+    pub upgrade_deps: bool,
+    pub executable: Option<PathBuf>,
+}
+
+impl EnvBuilder {
+    pub fn new() -> Self {
+        EnvBuilder {
+            // `python -m venv` symlinks by default everywhere but Windows.
+            symlinks: !cfg!(windows),
+            ..Default::default()
+        }
+    }
+
+    pub fn system_site_packages(mut self, value: bool) -> Self {
+        self.system_site_packages = value;
+        self
+    }
+
+    pub fn clear(mut self, value: bool) -> Self {
+        self.clear = value;
+        self
+    }
+
+    pub fn symlinks(mut self, value: bool) -> Self {
+        self.symlinks = value;
+        self
+    }
+
+    pub fn upgrade(mut self, value: bool) -> Self {
+        self.upgrade = value;
+        self
+    }
+
+    pub fn with_pip(mut self, value: bool) -> Self {
+        self.with_pip = value;
+        self
+    }
+
+    pub fn prompt(mut self, prompt: &str) -> Self {
+        self.prompt = Some(prompt.to_string());
+        self
+    }
+
+    pub fn upgrade_deps(mut self, value: bool) -> Self {
+        self.upgrade_deps = value;
+        self
+    }
+
+    /// The base interpreter to copy or link into the environment.
+    pub fn executable<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.executable = Some(path.into());
+        self
+    }
+
+    /// Python-equivalent EnvBuilder.create(env_dir).
+    pub fn create<P: AsRef<StdPath>>(&self, env_dir: P) -> PyResult<VenvContext> {
+        let env_dir = absolute(env_dir.as_ref())?;
+        let context = self.ensure_directories(&env_dir)?;
+        self.create_configuration(&context)?;
+        self.setup_python(&context)?;
+        self.setup_scripts(&context)?;
+        if self.with_pip {
+            run(
+                &context.env_exe,
+                &["-Im", "ensurepip", "--upgrade", "--default-pip"],
+            )?;
+        }
+        if self.upgrade_deps {
+            run(
+                &context.env_exe,
+                &["-m", "pip", "install", "--upgrade", "pip", "setuptools"],
+            )?;
+        }
+        Ok(context)
+    }
+
+    /// Python-equivalent EnvBuilder.ensure_directories(env_dir).
+    pub fn ensure_directories(&self, env_dir: &StdPath) -> PyResult<VenvContext> {
+        let sep = if cfg!(windows) { ';' } else { ':' };
+        if env_dir.to_string_lossy().contains(sep) {
+            return Err(PyException::value_error(format!(
+                "Refusing to create a venv in {} because it contains the PATH separator {}.",
+                env_dir.display(),
+                sep
+            )));
+        }
+        if env_dir.exists() && self.clear {
+            clear_directory(env_dir)?;
+        }
+        let executable = match &self.executable {
+            Some(exe) => absolute(exe)?,
+            None => find_interpreter().ok_or_else(|| {
+                PyException::value_error(
+                    "Unable to determine path to the running Python interpreter. Provide an \
+                     explicit path or check that your PATH environment variable is correctly set.",
+                )
+            })?,
+        };
+        let python_version = interpreter_version(&executable)?;
+        let short_version: String = python_version
+            .split('.')
+            .take(2)
+            .collect::<Vec<_>>()
+            .join(".");
+
+        let env_name = env_dir
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let prompt = self.prompt.clone().unwrap_or_else(|| env_name.clone());
+
+        let (bin_path, inc_path, site_packages) = if cfg!(windows) {
+            (
+                env_dir.join("Scripts"),
+                env_dir.join("Include"),
+                env_dir.join("Lib").join("site-packages"),
+            )
+        } else {
+            (
+                env_dir.join("bin"),
+                env_dir.join("include"),
+                env_dir
+                    .join("lib")
+                    .join(format!("python{}", short_version))
+                    .join("site-packages"),
+            )
+        };
+        for dir in [env_dir, &inc_path, &site_packages, &bin_path] {
+            create_if_needed(dir)?;
+        }
+        #[cfg(all(unix, target_pointer_width = "64", not(target_os = "macos")))]
+        {
+            let link = env_dir.join("lib64");
+            if fs::symlink_metadata(&link).is_err() {
+                std::os::unix::fs::symlink("lib", &link)?;
+            }
+        }
+
+        let exe_name = executable
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "python".to_string());
+        let env_exe = bin_path.join(if cfg!(windows) {
+            "python.exe".to_string()
+        } else {
+            exe_name
+        });
+        Ok(VenvContext {
+            env_dir: env_dir.to_path_buf(),
+            env_name,
+            prompt,
+            executable,
+            python_version,
+            bin_path,
+            inc_path,
+            site_packages,
+            env_exe,
+        })
+    }
+
+    /// Writes pyvenv.cfg, which is what makes the directory a virtual environment.
+    pub fn create_configuration(&self, context: &VenvContext) -> PyResult<()> {
+        let home = context
+            .executable
+            .parent()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        let mut cfg = format!(
+            "home = {}\ninclude-system-site-packages = {}\nversion = {}\n",
+            home, self.system_site_packages, context.python_version
+        );
+        if let Some(prompt) = &self.prompt {
+            cfg.push_str(&format!("prompt = '{}'\n", prompt));
+        }
+        cfg.push_str(&format!("executable = {}\n", context.executable.display()));
+        cfg.push_str(&format!(
+            "command = {} -m venv {}\n",
+            context.executable.display(),
+            context.env_dir.display()
+        ));
+        fs::write(context.env_dir.join(CONFIG_NAME), cfg)?;
+        Ok(())
+    }
+
+    /// Links or copies the interpreter into the environment's bin directory.
+    pub fn setup_python(&self, context: &VenvContext) -> PyResult<()> {
+        let mut names = vec![context.env_exe.clone()];
+        if !cfg!(windows) {
+            let short: String = context
+                .python_version
+                .split('.')
+                .take(2)
+                .collect::<Vec<_>>()
+                .join(".");
+            for alias in [
+                "python".to_string(),
+                "python3".to_string(),
+                format!("python{}", short),
+            ] {
+                let path = context.bin_path.join(alias);
+                if !names.contains(&path) {
+                    names.push(path);
+                }
+            }
+        }
+        for target in names {
+            if fs::symlink_metadata(&target).is_ok() {
+                if !self.upgrade {
+                    continue;
+                }
+                fs::remove_file(&target)?;
+            }
+            self.symlink_or_copy(&context.executable, &target)?;
+        }
+        Ok(())
+    }
+
+    fn symlink_or_copy(&self, src: &StdPath, dst: &StdPath) -> PyResult<()> {
+        #[cfg(unix)]
+        if self.symlinks && std::os::unix::fs::symlink(src, dst).is_ok() {
+            return Ok(());
+        }
+        fs::copy(src, dst)?;
+        Ok(())
+    }
+
+    /// Installs a POSIX-shell activate script.
+    pub fn setup_scripts(&self, context: &VenvContext) -> PyResult<()> {
+        if cfg!(windows) {
+            let script = format!(
+                "@echo off\r\nset \"VIRTUAL_ENV={}\"\r\nset \"VIRTUAL_ENV_PROMPT=({}) \"\r\n\
+                 set \"PATH=%VIRTUAL_ENV%\\Scripts;%PATH%\"\r\nset \"PROMPT=({}) %PROMPT%\"\r\n",
+                context.env_dir.display(),
+                context.prompt,
+                context.prompt
+            );
+            fs::write(context.bin_path.join("activate.bat"), script)?;
+            return Ok(());
+        }
+        let script = format!(
+            r#"# This file must be used with "source bin/activate"; you cannot run it directly.
+deactivate () {{
+    if [ -n "${{_OLD_VIRTUAL_PATH:-}}" ]; then
+        PATH="$_OLD_VIRTUAL_PATH"
+        export PATH
+        unset _OLD_VIRTUAL_PATH
+    fi
+    if [ -n "${{_OLD_VIRTUAL_PS1:-}}" ]; then
+        PS1="$_OLD_VIRTUAL_PS1"
+        export PS1
+        unset _OLD_VIRTUAL_PS1
+    fi
+    unset VIRTUAL_ENV
+    unset VIRTUAL_ENV_PROMPT
+    if [ ! "${{1:-}}" = "nondestructive" ]; then
+        unset -f deactivate
+    fi
+}}
+
+deactivate nondestructive
+
+VIRTUAL_ENV="{env}"
+export VIRTUAL_ENV
+
+_OLD_VIRTUAL_PATH="$PATH"
+PATH="$VIRTUAL_ENV/bin:$PATH"
+export PATH
+
+if [ -z "${{VIRTUAL_ENV_DISABLE_PROMPT:-}}" ]; then
+    _OLD_VIRTUAL_PS1="${{PS1:-}}"
+    PS1="({prompt}) ${{PS1:-}}"
+    export PS1
+    VIRTUAL_ENV_PROMPT="({prompt}) "
+    export VIRTUAL_ENV_PROMPT
+fi
+
+hash -r 2> /dev/null
+"#,
+            env = context.env_dir.display(),
+            prompt = context.prompt
+        );
+        fs::write(context.bin_path.join("activate"), script)?;
+        Ok(())
+    }
+}
+
+/// Python-equivalent venv.create(env_dir, system_site_packages, clear, symlinks, with_pip, prompt).
+pub fn create<P: AsRef<StdPath>>(
+    env_dir: P,
+    system_site_packages: bool,
+    clear: bool,
+    symlinks: bool,
+    with_pip: bool,
+    prompt: Option<&str>,
+) -> PyResult<VenvContext> {
+    let mut builder = EnvBuilder::new()
+        .system_site_packages(system_site_packages)
+        .clear(clear)
+        .symlinks(symlinks)
+        .with_pip(with_pip);
+    if let Some(prompt) = prompt {
+        builder = builder.prompt(prompt);
+    }
+    builder.create(env_dir)
+}
+
+/// Parsed pyvenv.cfg of an existing environment.
+pub fn read_config<P: AsRef<StdPath>>(env_dir: P) -> PyResult<Vec<(String, String)>> {
+    let text = fs::read_to_string(env_dir.as_ref().join(CONFIG_NAME))?;
+    Ok(text
+        .lines()
+        .filter_map(|line| line.split_once('='))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect())
+}
+
+/// Interpreter path inside an environment directory.
+pub fn env_python(env_dir: &StdPath) -> PathBuf {
+    if cfg!(windows) {
+        env_dir.join("Scripts").join("python.exe")
+    } else {
+        env_dir.join("bin").join("python")
+    }
+}
+
+/// Returns a usable environment at `env_dir`, creating (or recreating) it when the
+/// directory is missing, has no pyvenv.cfg, or its interpreter no longer runs.
+pub fn ensure_venv_ready<P: AsRef<StdPath>>(env_dir: P, with_pip: bool) -> PyResult<VenvContext> {
+    let env_dir = absolute(env_dir.as_ref())?;
+    if read_config(&env_dir).is_ok() && interpreter_version(&env_python(&env_dir)).is_ok() {
+        let config = read_config(&env_dir)?;
+        let executable = config
+            .iter()
+            .find(|(k, _)| k == "executable")
+            .map(|(_, v)| PathBuf::from(v))
+            .unwrap_or_else(|| env_python(&env_dir));
+        let builder = EnvBuilder::new().executable(executable);
+        let context = builder.ensure_directories(&env_dir)?;
+        if !with_pip || pip_available(&context.env_exe) {
+            return Ok(context);
+        }
+    }
+    EnvBuilder::new()
+        .clear(true)
+        .with_pip(with_pip)
+        .create(&env_dir)
+}
+
+fn pip_available(python: &StdPath) -> bool {
+    Command::new(python)
+        .args(["-m", "pip", "--version"])
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn absolute(path: &StdPath) -> PyResult<PathBuf> {
+    if path.is_absolute() {
+        Ok(path.to_path_buf())
+    } else {
+        Ok(env::current_dir()?.join(path))
+    }
+}
+
+fn create_if_needed(dir: &StdPath) -> PyResult<()> {
+    match fs::symlink_metadata(dir) {
+        Err(_) => Ok(fs::create_dir_all(dir)?),
+        Ok(meta) if meta.is_dir() => Ok(()),
+        Ok(_) => Err(PyException::value_error(format!(
+            "Unable to create directory '{}'",
+            dir.display()
+        ))),
+    }
+}
+
+fn clear_directory(dir: &StdPath) -> PyResult<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if fs::symlink_metadata(&path)?.is_dir() {
+            fs::remove_dir_all(&path)?;
+        } else {
+            fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// The first Python 3 interpreter on PATH.
+fn find_interpreter() -> Option<PathBuf> {
+    let names: &[&str] = if cfg!(windows) {
+        &["python.exe", "python3.exe"]
+    } else {
+        &["python3", "python"]
+    };
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// "X.Y.Z" of an interpreter, by asking it.
+fn interpreter_version(python: &StdPath) -> PyResult<String> {
+    let output = Command::new(python)
+        .args(["-c", "import sys; print('%d.%d.%d' % sys.version_info[:3])"])
+        .output()?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if !output.status.success() || version.split('.').count() != 3 {
+        return Err(PyException::runtime_error(format!(
+            "could not determine the version of {}",
+            python.display()
+        )));
+    }
+    Ok(version)
+}
+
+fn run(python: &StdPath, args: &[&str]) -> PyResult<()> {
+    let status = Command::new(python).args(args).status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(PyException::new(
+            "CalledProcessError",
+            format!(
+                "Command '{} {}' returned non-zero exit status {}.",
+                python.display(),
+                args.join(" "),
+                status.code().unwrap_or(-1)
+            ),
+        ))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn fake_python(dir: &StdPath) -> PathBuf {
+        fs::create_dir_all(dir).unwrap();
+        let exe = dir.join("python3");
+        fs::write(&exe, "#!/bin/sh\necho 3.12.1\n").unwrap();
+        fs::set_permissions(&exe, fs::Permissions::from_mode(0o755)).unwrap();
+        exe
+    }
+
+    #[test]
+    fn creates_layout_and_config() {
+        let root = env::temp_dir().join(format!("stdpython_venv_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let exe = fake_python(&root.join("base"));
+        let env_dir = root.join("env");
+
+        let context = EnvBuilder::new()
+            .executable(&exe)
+            .prompt("bench")
+            .system_site_packages(true)
+            .create(&env_dir)
+            .unwrap();
+        assert_eq!(context.python_version, "3.12.1");
+        assert!(env_dir.join("lib/python3.12/site-packages").is_dir());
+        assert!(env_dir.join("bin/python").exists());
+        assert!(env_dir.join("bin/python3.12").exists());
+        assert!(env_dir.join("bin/activate").is_file());
+
+        let config = read_config(&env_dir).unwrap();
+        assert!(config.contains(&(
+            "include-system-site-packages".to_string(),
+            "true".to_string()
+        )));
+        assert!(config.contains(&("prompt".to_string(), "'bench'".to_string())));
+        assert!(config.contains(&("version".to_string(), "3.12.1".to_string())));
+
+        let again = ensure_venv_ready(&env_dir, false).unwrap();
+        assert_eq!(again.env_dir, env_dir);
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn rejects_path_separator() {
+        let err = EnvBuilder::new()
+            .ensure_directories(StdPath::new("/tmp/a:b"))
+            .unwrap_err();
+        assert!(err.is("ValueError"));
+    }
+}
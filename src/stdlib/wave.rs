@@ -0,0 +1,694 @@
+//! Python-equivalent wave module: reading and writing uncompressed PCM WAV
+//! files. Only the RIFF container is handled; samples are passed through
+//! as bytes.
+//!
+//! One [`Wave`] type plays both Wave_read and Wave_write, like [`PyFile`]
+//! does for readable and writable files; methods of the other mode raise
+//! AttributeError as the missing Python method would.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::io::{open as open_file, FileLike, PyFile};
+use crate::stdlib::os::PathLike;
+use crate::types::PyBytes;
+
+/// Python-equivalent wave.WAVE_FORMAT_PCM.
+pub const WAVE_FORMAT_PCM: u16 = 0x0001;
+
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// The SubFormat GUID of a WAVE_FORMAT_EXTENSIBLE file holding PCM samples.
+const KSDATAFORMAT_SUBTYPE_PCM: [u8; 16] = [
+    0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00, 0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71,
+];
+
+fn error(msg: &str) -> PyException {
+    PyException::new("wave.Error", msg)
+}
+
+/// A header field that must fit in `T`, failing the way struct.pack does
+/// in CPython's wave module.
+fn header_field<T: TryFrom<u64>>(value: u64, format: char) -> PyResult<T> {
+    let max = match format {
+        'H' => u64::from(u16::MAX),
+        _ => u64::from(u32::MAX),
+    };
+    T::try_from(value).map_err(|_| {
+        error(&format!(
+            "'{}' format requires 0 <= number <= {}",
+            format, max
+        ))
+    })
+}
+
+/// Python-equivalent wave._wave_params, as getparams() returns it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WaveParams {
+    pub nchannels: u16,
+    pub sampwidth: u16,
+    pub framerate: u32,
+    pub nframes: u32,
+    pub comptype: String,
+    pub compname: String,
+}
+
+/// Python-equivalent wave.open(file, mode) for the file at `file`: mode
+/// "r" or "rb" reads it, "w" or "wb" creates it.
+pub fn open<P: PathLike>(file: P, mode: &str) -> PyResult<Wave<PyFile>> {
+    match mode {
+        "r" | "rb" => Wave::read_from(open_file(file, "rb")?),
+        "w" | "wb" => Ok(Wave::write_to(open_file(file, "wb")?)),
+        _ => Err(error("mode must be 'r', 'rb', 'w', or 'wb'")),
+    }
+}
+
+/// Python-equivalent wave.Wave_read and wave.Wave_write.
+#[derive(Debug)]
+pub struct Wave<F: FileLike> {
+    /// None once closed.
+    file: Option<F>,
+    writing: bool,
+    nchannels: u16,
+    sampwidth: u16,
+    framerate: u32,
+    nframes: u32,
+    // Reading.
+    data_start: u64,
+    data_size: u64,
+    soundpos: u32,
+    // Writing.
+    header_written: bool,
+    form_length_pos: u64,
+    data_length_pos: u64,
+    datalength: u64,
+    datawritten: u64,
+    nframeswritten: u32,
+}
+
+impl<F: FileLike> Wave<F> {
+    fn new(file: F, writing: bool) -> Self {
+        Wave {
+            file: Some(file),
+            writing,
+            nchannels: 0,
+            sampwidth: 0,
+            framerate: 0,
+            nframes: 0,
+            data_start: 0,
+            data_size: 0,
+            soundpos: 0,
+            header_written: false,
+            form_length_pos: 0,
+            data_length_pos: 0,
+            datalength: 0,
+            datawritten: 0,
+            nframeswritten: 0,
+        }
+    }
+
+    /// Python-equivalent wave.open(f, "rb") on a file object: reads the
+    /// header, leaving the stream at the first frame.
+    pub fn read_from(file: F) -> PyResult<Self> {
+        let mut wave = Wave::new(file, false);
+        wave.read_header()?;
+        Ok(wave)
+    }
+
+    /// Python-equivalent wave.open(f, "wb") on a file object. The header
+    /// is written with the first frames, or on close().
+    pub fn write_to(file: F) -> Self {
+        Wave::new(file, true)
+    }
+
+    fn class_name(&self) -> &'static str {
+        if self.writing {
+            "Wave_write"
+        } else {
+            "Wave_read"
+        }
+    }
+
+    fn check_mode(&self, writing: bool, method: &str) -> PyResult<()> {
+        if self.writing == writing {
+            return Ok(());
+        }
+        Err(PyException::attribute_error(format!(
+            "'{}' object has no attribute '{}'",
+            self.class_name(),
+            method
+        )))
+    }
+
+    fn file(&mut self) -> PyResult<&mut F> {
+        self.file
+            .as_mut()
+            .ok_or_else(|| PyException::value_error("I/O operation on closed file."))
+    }
+
+    fn framesize(&self) -> u64 {
+        u64::from(self.nchannels) * u64::from(self.sampwidth)
+    }
+
+    fn read_header(&mut self) -> PyResult<()> {
+        let file = self.file()?;
+        let mut riff = [0; 12];
+        read_exact(file, &mut riff)?;
+        if &riff[..4] != b"RIFF" {
+            return Err(error("file does not start with RIFF id"));
+        }
+        // Chunks are read from within the RIFF chunk, as CPython does.
+        let riff_end = 8 + u64::from(u32::from_le_bytes(riff[4..8].try_into().unwrap()));
+        if riff_end < 12 || &riff[8..] != b"WAVE" {
+            return Err(error("not a WAVE file"));
+        }
+        let mut fmt_read = false;
+        loop {
+            let file = self.file()?;
+            let mut header = [0; 8];
+            if file.stream_position()? + 8 > riff_end || read_up_to(file, &mut header)? < 8 {
+                break;
+            }
+            let size = u64::from(u32::from_le_bytes(header[4..].try_into().unwrap()));
+            match &header[..4] {
+                b"fmt " => {
+                    // Only the first 40 bytes (WAVE_FORMAT_EXTENSIBLE's) matter.
+                    let mut body = vec![0; size.min(40) as usize];
+                    let got = read_up_to(file, &mut body)?;
+                    body.truncate(got);
+                    skip(file, size - got as u64 + size % 2)?;
+                    self.read_fmt_chunk(&body)?;
+                    fmt_read = true;
+                }
+                b"data" => {
+                    if !fmt_read {
+                        return Err(error("data chunk before fmt chunk"));
+                    }
+                    self.data_start = file.stream_position()?;
+                    self.data_size = size.min(riff_end.saturating_sub(self.data_start));
+                    self.nframes = (size / self.framesize()) as u32;
+                    return Ok(());
+                }
+                _ => skip(file, size + size % 2)?,
+            }
+        }
+        Err(error("fmt chunk and/or data chunk missing"))
+    }
+
+    fn read_fmt_chunk(&mut self, body: &[u8]) -> PyResult<()> {
+        if body.len() < 16 {
+            return Err(PyException::eof_error(""));
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([body[i], body[i + 1]]);
+        let format_tag = u16_at(0);
+        self.nchannels = u16_at(2);
+        self.framerate = u32::from_le_bytes(body[4..8].try_into().unwrap());
+        if format_tag != WAVE_FORMAT_PCM && format_tag != WAVE_FORMAT_EXTENSIBLE {
+            return Err(error(&format!("unknown format: {}", format_tag)));
+        }
+        self.sampwidth = u16_at(14).div_ceil(8);
+        if self.sampwidth == 0 {
+            return Err(error("bad sample width"));
+        }
+        if format_tag == WAVE_FORMAT_EXTENSIBLE {
+            match body.get(24..40) {
+                Some(sub_format) if sub_format == KSDATAFORMAT_SUBTYPE_PCM => {}
+                Some(_) => return Err(error("unknown extended format")),
+                None => return Err(PyException::eof_error("")),
+            }
+        }
+        if self.nchannels == 0 {
+            return Err(error("bad # of channels"));
+        }
+        Ok(())
+    }
+
+    /// Python-equivalent getnchannels().
+    pub fn getnchannels(&self) -> u16 {
+        self.nchannels
+    }
+
+    /// Python-equivalent getsampwidth(): bytes per sample.
+    pub fn getsampwidth(&self) -> u16 {
+        self.sampwidth
+    }
+
+    /// Python-equivalent getframerate().
+    pub fn getframerate(&self) -> u32 {
+        self.framerate
+    }
+
+    /// Python-equivalent getnframes(): the frames in the file when reading,
+    /// the frames written so far when writing.
+    pub fn getnframes(&self) -> u32 {
+        if self.writing {
+            self.nframeswritten
+        } else {
+            self.nframes
+        }
+    }
+
+    /// Python-equivalent getcomptype(): always "NONE".
+    pub fn getcomptype(&self) -> &'static str {
+        "NONE"
+    }
+
+    /// Python-equivalent getcompname().
+    pub fn getcompname(&self) -> &'static str {
+        "not compressed"
+    }
+
+    /// Python-equivalent getparams().
+    pub fn getparams(&self) -> WaveParams {
+        WaveParams {
+            nchannels: self.nchannels,
+            sampwidth: self.sampwidth,
+            framerate: self.framerate,
+            nframes: self.getnframes(),
+            comptype: self.getcomptype().to_string(),
+            compname: self.getcompname().to_string(),
+        }
+    }
+
+    /// Python-equivalent tell(): the current frame.
+    pub fn tell(&self) -> u32 {
+        if self.writing {
+            self.nframeswritten
+        } else {
+            self.soundpos
+        }
+    }
+
+    /// Python-equivalent Wave_read.readframes(n): up to `n` frames as
+    /// little-endian samples (native order on big-endian hosts, as in
+    /// CPython).
+    pub fn readframes(&mut self, nframes: usize) -> PyResult<PyBytes> {
+        self.check_mode(false, "readframes")?;
+        let framesize = self.framesize();
+        let offset = u64::from(self.soundpos) * framesize;
+        let wanted = (nframes as u64)
+            .saturating_mul(framesize)
+            .min(self.data_size.saturating_sub(offset));
+        let start = self.data_start + offset;
+        let file = self.file()?;
+        file.seek(SeekFrom::Start(start))?;
+        let mut data = vec![0; wanted as usize];
+        let got = read_up_to(file, &mut data)?;
+        data.truncate(got);
+        if cfg!(target_endian = "big") && self.sampwidth > 1 {
+            for sample in data.chunks_exact_mut(usize::from(self.sampwidth)) {
+                sample.reverse();
+            }
+        }
+        self.soundpos += (got as u64 / framesize) as u32;
+        Ok(PyBytes::new(data))
+    }
+
+    /// Python-equivalent Wave_read.setpos(pos).
+    pub fn setpos(&mut self, pos: u32) -> PyResult<()> {
+        self.check_mode(false, "setpos")?;
+        if pos > self.nframes {
+            return Err(error("position not in range"));
+        }
+        self.soundpos = pos;
+        Ok(())
+    }
+
+    /// Python-equivalent Wave_read.rewind().
+    pub fn rewind(&mut self) -> PyResult<()> {
+        self.setpos(0)
+    }
+
+    fn check_unwritten(&self, method: &str) -> PyResult<()> {
+        self.check_mode(true, method)?;
+        if self.datawritten > 0 {
+            return Err(error("cannot change parameters after starting to write"));
+        }
+        Ok(())
+    }
+
+    /// Python-equivalent Wave_write.setnchannels(n).
+    pub fn setnchannels(&mut self, nchannels: u16) -> PyResult<()> {
+        self.check_unwritten("setnchannels")?;
+        if nchannels < 1 {
+            return Err(error("bad # of channels"));
+        }
+        self.nchannels = nchannels;
+        Ok(())
+    }
+
+    /// Python-equivalent Wave_write.setsampwidth(n): 1 to 4 bytes.
+    pub fn setsampwidth(&mut self, sampwidth: u16) -> PyResult<()> {
+        self.check_unwritten("setsampwidth")?;
+        if !(1..=4).contains(&sampwidth) {
+            return Err(error("bad sample width"));
+        }
+        self.sampwidth = sampwidth;
+        Ok(())
+    }
+
+    /// Python-equivalent Wave_write.setframerate(n).
+    pub fn setframerate(&mut self, framerate: u32) -> PyResult<()> {
+        self.check_unwritten("setframerate")?;
+        if framerate == 0 {
+            return Err(error("bad frame rate"));
+        }
+        self.framerate = framerate;
+        Ok(())
+    }
+
+    /// Python-equivalent Wave_write.setnframes(n): the frame count to put in
+    /// the header, corrected on close() if different.
+    pub fn setnframes(&mut self, nframes: u32) -> PyResult<()> {
+        self.check_unwritten("setnframes")?;
+        self.nframes = nframes;
+        Ok(())
+    }
+
+    /// Python-equivalent Wave_write.setcomptype(comptype, compname): only
+    /// "NONE" is supported.
+    pub fn setcomptype(&mut self, comptype: &str, compname: &str) -> PyResult<()> {
+        self.check_unwritten("setcomptype")?;
+        let _ = compname;
+        if comptype != "NONE" {
+            return Err(error("unsupported compression type"));
+        }
+        Ok(())
+    }
+
+    /// Python-equivalent Wave_write.setparams(params).
+    pub fn setparams(&mut self, params: &WaveParams) -> PyResult<()> {
+        self.check_unwritten("setparams")?;
+        self.setnchannels(params.nchannels)?;
+        self.setsampwidth(params.sampwidth)?;
+        self.setframerate(params.framerate)?;
+        self.setnframes(params.nframes)?;
+        self.setcomptype(&params.comptype, &params.compname)
+    }
+
+    /// Python-equivalent Wave_write.writeframesraw(data): writes frames
+    /// without fixing up the header's lengths.
+    pub fn writeframesraw(&mut self, data: &[u8]) -> PyResult<()> {
+        self.check_mode(true, "writeframesraw")?;
+        self.ensure_header_written(data.len() as u64)?;
+        let nframes = data.len() as u64 / self.framesize();
+        let sampwidth = usize::from(self.sampwidth);
+        let file = self.file()?;
+        if cfg!(target_endian = "big") && sampwidth > 1 {
+            let mut swapped = data.to_vec();
+            for sample in swapped.chunks_exact_mut(sampwidth) {
+                sample.reverse();
+            }
+            file.write_all(&swapped)?;
+        } else {
+            file.write_all(data)?;
+        }
+        self.datawritten += data.len() as u64;
+        self.nframeswritten += nframes as u32;
+        Ok(())
+    }
+
+    /// Python-equivalent Wave_write.writeframes(data).
+    pub fn writeframes(&mut self, data: &[u8]) -> PyResult<()> {
+        self.writeframesraw(data)?;
+        if self.datalength != self.datawritten {
+            self.patch_header()?;
+        }
+        Ok(())
+    }
+
+    fn ensure_header_written(&mut self, datasize: u64) -> PyResult<()> {
+        if self.header_written {
+            return Ok(());
+        }
+        if self.nchannels == 0 {
+            return Err(error("# channels not specified"));
+        }
+        if self.sampwidth == 0 {
+            return Err(error("sample width not specified"));
+        }
+        if self.framerate == 0 {
+            return Err(error("sampling rate not specified"));
+        }
+        let framesize = self.framesize();
+        let nframes = match self.nframes {
+            0 => header_field(datasize / framesize, 'I')?,
+            n => n,
+        };
+        let datalength = u64::from(nframes) * framesize;
+        let (nchannels, sampwidth, framerate) = (self.nchannels, self.sampwidth, self.framerate);
+        // Everything is checked before anything is written.
+        let form_length: u32 = header_field(36 + datalength, 'I')?;
+        let byte_rate: u32 = header_field(u64::from(framerate) * framesize, 'I')?;
+        let block_align: u16 = header_field(framesize, 'H')?;
+        let data_length: u32 = header_field(datalength, 'I')?;
+        self.nframes = nframes;
+        self.datalength = datalength;
+        let file = self.file()?;
+        file.write_all(b"RIFF")?;
+        let form_length_pos = file.stream_position()?;
+        let mut header = Vec::with_capacity(40);
+        header.extend(form_length.to_le_bytes());
+        header.extend(b"WAVEfmt ");
+        header.extend(16u32.to_le_bytes());
+        header.extend(WAVE_FORMAT_PCM.to_le_bytes());
+        header.extend(nchannels.to_le_bytes());
+        header.extend(framerate.to_le_bytes());
+        header.extend(byte_rate.to_le_bytes());
+        header.extend(block_align.to_le_bytes());
+        header.extend((sampwidth * 8).to_le_bytes());
+        header.extend(b"data");
+        file.write_all(&header)?;
+        let data_length_pos = file.stream_position()?;
+        file.write_all(&data_length.to_le_bytes())?;
+        self.form_length_pos = form_length_pos;
+        self.data_length_pos = data_length_pos;
+        self.header_written = true;
+        Ok(())
+    }
+
+    /// Rewrites the RIFF and data lengths to match what was written.
+    fn patch_header(&mut self) -> PyResult<()> {
+        let (form_length_pos, data_length_pos) = (self.form_length_pos, self.data_length_pos);
+        let form_length: u32 = header_field(36 + self.datawritten, 'I')?;
+        let data_length: u32 = header_field(self.datawritten, 'I')?;
+        let file = self.file()?;
+        let curpos = file.stream_position()?;
+        file.seek(SeekFrom::Start(form_length_pos))?;
+        file.write_all(&form_length.to_le_bytes())?;
+        file.seek(SeekFrom::Start(data_length_pos))?;
+        file.write_all(&data_length.to_le_bytes())?;
+        file.seek(SeekFrom::Start(curpos))?;
+        self.datalength = self.datawritten;
+        Ok(())
+    }
+
+    /// When writing, writes the header if no frames were and corrects its
+    /// lengths.
+    fn finish(&mut self) -> PyResult<()> {
+        if !self.writing || self.file.is_none() {
+            return Ok(());
+        }
+        self.ensure_header_written(0)?;
+        if self.datalength != self.datawritten {
+            self.patch_header()?;
+        }
+        Ok(self.file()?.flush()?)
+    }
+
+    /// Python-equivalent close(). The file is released even if finishing
+    /// the header fails.
+    pub fn close(&mut self) -> PyResult<()> {
+        let result = self.finish();
+        self.file = None;
+        result
+    }
+
+    /// Finishes the file as close() does and gives back the file object,
+    /// e.g. a BytesIO to take the WAV data from.
+    pub fn into_inner(mut self) -> PyResult<F> {
+        self.finish()?;
+        self.file
+            .take()
+            .ok_or_else(|| PyException::value_error("I/O operation on closed file."))
+    }
+}
+
+impl<F: FileLike> Drop for Wave<F> {
+    fn drop(&mut self) {
+        let _ = self.close();
+    }
+}
+
+/// Reads until `buf` is full or the stream ends, returning how much was read.
+fn read_up_to<R: Read + ?Sized>(reader: &mut R, buf: &mut [u8]) -> PyResult<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(filled)
+}
+
+fn read_exact<R: Read + ?Sized>(reader: &mut R, buf: &mut [u8]) -> PyResult<()> {
+    if read_up_to(reader, buf)? < buf.len() {
+        return Err(PyException::eof_error(""));
+    }
+    Ok(())
+}
+
+fn skip<S: Seek + ?Sized>(stream: &mut S, n: u64) -> PyResult<()> {
+    stream.seek(SeekFrom::Current(n as i64))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::io::BytesIO;
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn writes_the_same_bytes_as_cpython() {
+        let mut w = Wave::write_to(BytesIO::new());
+        w.setnchannels(2).unwrap();
+        w.setsampwidth(2).unwrap();
+        w.setframerate(8000).unwrap();
+        w.writeframes(&[0, 1, 2, 3, 4, 5, 6, 7]).unwrap();
+        w.writeframes(&[8, 9, 10, 11]).unwrap();
+        assert_eq!(w.getnframes(), 3);
+        assert!(w.setnchannels(1).unwrap_err().is("wave.Error"));
+        assert!(w.readframes(1).unwrap_err().is("AttributeError"));
+        assert_eq!(
+            w.into_inner().unwrap().getvalue(),
+            unhex(
+                "524946463000000057415645666d74201000000001000200401f0000007d00000400\
+                 1000646174610c000000000102030405060708090a0b"
+            )
+        );
+
+        // A declared frame count that turns out wrong is patched on close.
+        let mut w = Wave::write_to(BytesIO::new());
+        w.setparams(&WaveParams {
+            nchannels: 1,
+            sampwidth: 1,
+            framerate: 11025,
+            nframes: 10,
+            comptype: "NONE".to_string(),
+            compname: "not compressed".to_string(),
+        })
+        .unwrap();
+        w.writeframesraw(b"\x80\x81\x82").unwrap();
+        assert_eq!(
+            w.into_inner().unwrap().getvalue(),
+            unhex(
+                "524946462700000057415645666d74201000000001000100112b0000112b00000100\
+                 08006461746103000000808182"
+            )
+        );
+        let err = Wave::write_to(BytesIO::new()).close().unwrap_err();
+        assert_eq!(err.message, "# channels not specified");
+
+        // Header fields that do not fit raise instead of wrapping.
+        let mut w = Wave::write_to(BytesIO::new());
+        w.setnchannels(40000).unwrap();
+        w.setsampwidth(2).unwrap();
+        w.setframerate(60000).unwrap();
+        let err = w.writeframes(&[0; 4]).unwrap_err();
+        assert!(err.is("wave.Error"));
+        assert_eq!(err.message, "'I' format requires 0 <= number <= 4294967295");
+        let mut w = Wave::write_to(BytesIO::new());
+        w.setnchannels(40000).unwrap();
+        w.setsampwidth(2).unwrap();
+        w.setframerate(1).unwrap();
+        let err = w.close().unwrap_err();
+        assert_eq!(err.message, "'H' format requires 0 <= number <= 65535");
+        let mut w = Wave::write_to(BytesIO::new());
+        w.setnchannels(1).unwrap();
+        w.setsampwidth(1).unwrap();
+        w.setframerate(8000).unwrap();
+        w.setnframes(u32::MAX).unwrap();
+        assert!(w.writeframes(&[0]).unwrap_err().is("wave.Error"));
+    }
+
+    #[test]
+    fn reads_frames_past_other_chunks() {
+        // A LIST chunk of odd length, padded, before fmt.
+        let data = unhex(
+            "524946463c000000574156454c4953540300000061626300666d7420100000000100\
+             0200401f0000007d000004001000646174610c000000000102030405060708090a0b",
+        );
+        let mut r = Wave::read_from(BytesIO::with_bytes(&data)).unwrap();
+        assert_eq!(
+            r.getparams(),
+            WaveParams {
+                nchannels: 2,
+                sampwidth: 2,
+                framerate: 8000,
+                nframes: 3,
+                comptype: "NONE".to_string(),
+                compname: "not compressed".to_string(),
+            }
+        );
+        assert_eq!(
+            r.readframes(2).unwrap().as_bytes(),
+            [0, 1, 2, 3, 4, 5, 6, 7]
+        );
+        assert_eq!(r.tell(), 2);
+        assert_eq!(r.readframes(10).unwrap().as_bytes(), [8, 9, 10, 11]);
+        assert_eq!(r.tell(), 3);
+        assert!(r.readframes(1).unwrap().is_empty());
+        r.setpos(1).unwrap();
+        assert_eq!(r.readframes(1).unwrap().as_bytes(), [4, 5, 6, 7]);
+        assert_eq!(r.setpos(7).unwrap_err().message, "position not in range");
+        assert!(r.writeframes(b"").unwrap_err().is("AttributeError"));
+
+        let fmt_float =
+            "RIFF\x28\0\0\0WAVEfmt \x10\0\0\0\x03\0\x01\0\x40\x1f\0\0\0\x7d\0\0\x04\0\x20\0";
+        for (bad, msg) in [
+            ("RIFX....WAVE", "file does not start with RIFF id"),
+            ("RIFF....AVI ", "not a WAVE file"),
+            ("RIFF\0\0\0\0WAVE", "not a WAVE file"),
+            (
+                "RIFF\x0c\0\0\0WAVEdata\0\0\0\0",
+                "data chunk before fmt chunk",
+            ),
+            ("RIFF\x04\0\0\0WAVE", "fmt chunk and/or data chunk missing"),
+            (fmt_float, "unknown format: 3"),
+        ] {
+            let err = Wave::read_from(BytesIO::with_bytes(bad.as_bytes()))
+                .err()
+                .unwrap();
+            assert!(err.is("wave.Error"));
+            assert_eq!(err.message, msg);
+        }
+    }
+
+    #[test]
+    fn open_round_trips_a_file() {
+        let path = std::env::temp_dir().join(format!("stdpython-wave-{}.wav", std::process::id()));
+        let mut w = open(&path, "wb").unwrap();
+        w.setnchannels(1).unwrap();
+        w.setsampwidth(3).unwrap();
+        w.setframerate(48000).unwrap();
+        w.writeframes(&[1, 2, 3, 4, 5, 6]).unwrap();
+        drop(w);
+        let mut r = open(&path, "rb").unwrap();
+        assert_eq!((r.getsampwidth(), r.getnframes()), (3, 2));
+        assert_eq!(r.readframes(5).unwrap().as_bytes(), [1, 2, 3, 4, 5, 6]);
+        r.close().unwrap();
+        assert!(r.readframes(1).unwrap_err().is("ValueError"));
+        std::fs::remove_file(&path).unwrap();
+        assert!(open(&path, "a").unwrap_err().is("wave.Error"));
+    }
+}
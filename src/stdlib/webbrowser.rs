@@ -0,0 +1,147 @@
+//! Python-equivalent webbrowser module: open a URL in the user's browser.
+//!
+//! As in CPython, the `BROWSER` environment variable (a list of commands
+//! separated like `PATH`) is tried first; each command gets the URL in place
+//! of `%s`, or appended. Without it the platform opener is used: `xdg-open`,
+//! macOS `open`, or the Windows URL protocol handler.
+
+use std::env;
+use std::process::{Command, Stdio};
+
+use crate::exceptions::{PyException, PyResult};
+
+/// Checks open()'s `new` argument, which picks the window or tab.
+fn check_new(new: i64) -> PyResult<()> {
+    if (0..=2).contains(&new) {
+        Ok(())
+    } else {
+        Err(PyException::new(
+            "webbrowser.Error",
+            format!(
+                "Bad 'new' parameter to open(); expected 0, 1, or 2, got {}",
+                new
+            ),
+        ))
+    }
+}
+
+/// The argument list for one `BROWSER` entry. Firefox- and Chromium-family
+/// commands are asked for a new window or tab the way CPython's
+/// Mozilla and Chrome controllers ask; other commands just get the URL.
+fn command_line(entry: &str, url: &str, new: i64) -> Vec<String> {
+    let mut args: Vec<String> = entry.split_whitespace().map(str::to_string).collect();
+    let program = args
+        .first()
+        .and_then(|p| p.rsplit(['/', '\\']).next())
+        .unwrap_or("");
+    let flag = match (program, new) {
+        ("firefox" | "mozilla" | "iceweasel", 1) => Some("-new-window"),
+        ("firefox" | "mozilla" | "iceweasel", 2) => Some("-new-tab"),
+        ("google-chrome" | "chrome" | "chromium" | "chromium-browser", 1) => Some("--new-window"),
+        _ => None,
+    };
+    let substituted = args.iter().any(|a| a.contains("%s"));
+    for arg in &mut args {
+        *arg = arg.replace("%s", url);
+    }
+    if let Some(flag) = flag {
+        args.insert(1, flag.to_string());
+    }
+    if !substituted {
+        args.push(url.to_string());
+    }
+    args
+}
+
+/// Runs a `BROWSER` command to completion, as CPython's GenericBrowser does;
+/// it succeeded if it exited with status 0.
+fn run_generic(args: &[String]) -> bool {
+    let Some((program, rest)) = args.split_first() else {
+        return false;
+    };
+    Command::new(program)
+        .args(rest)
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Starts the platform opener without waiting for it, as CPython's
+/// BackgroundBrowser does.
+fn open_platform(url: &str, autoraise: bool) -> bool {
+    let mut command = if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        if !autoraise {
+            command.arg("-g");
+        }
+        command
+    } else if cfg!(windows) {
+        // `start` would need the URL escaped for cmd.exe; the protocol
+        // handler gets it as a plain argument.
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .is_ok()
+}
+
+/// Python-equivalent webbrowser.open(url, new=0, autoraise=True): `new` is
+/// 0 for the same window, 1 for a new window and 2 for a new tab, where the
+/// browser supports it. Returns whether a browser was launched.
+pub fn open(url: &str, new: i64, autoraise: bool) -> PyResult<bool> {
+    check_new(new)?;
+    if let Some(browsers) = env::var_os("BROWSER") {
+        for entry in env::split_paths(&browsers) {
+            let entry = entry.to_string_lossy();
+            if !entry.trim().is_empty() && run_generic(&command_line(&entry, url, new)) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(open_platform(url, autoraise))
+}
+
+/// Python-equivalent webbrowser.open_new(url).
+pub fn open_new(url: &str) -> PyResult<bool> {
+    open(url, 1, true)
+}
+
+/// Python-equivalent webbrowser.open_new_tab(url).
+pub fn open_new_tab(url: &str) -> PyResult<bool> {
+    open(url, 2, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn browser_command_lines() {
+        let url = "https://example.com/?a=1&b=2";
+        assert_eq!(command_line("lynx", url, 0), ["lynx", url]);
+        assert_eq!(
+            command_line("/usr/bin/firefox", url, 2),
+            ["/usr/bin/firefox", "-new-tab", url]
+        );
+        assert_eq!(
+            command_line("chromium --incognito %s", url, 1),
+            ["chromium", "--new-window", "--incognito", url]
+        );
+        assert!(open(url, 3, true).unwrap_err().is("webbrowser.Error"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn generic_browser_reports_exit_status() {
+        assert!(run_generic(&command_line("true", "x", 0)));
+        assert!(!run_generic(&command_line("false", "x", 0)));
+        assert!(!run_generic(&command_line("/nonexistent/browser", "x", 0)));
+    }
+}
@@ -0,0 +1,803 @@
+//! Python-equivalent zoneinfo module: IANA time zones read from the
+//! system's compiled tz database (TZif files under TZPATH), with the
+//! POSIX TZ rule at the end of each file covering dates past its last
+//! transition.
+//!
+//! With the `tzdata` feature, keys missing from the system database fall
+//! back to built-in current rules for common zones, for targets that ship
+//! without /usr/share/zoneinfo. Those carry no history, so dates before a
+//! zone's present rules were adopted come out with today's offsets.
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::datetime::{civil_from_days, days_from_civil, is_leap, DateTime, TzInfo};
+use crate::sync::Lock;
+use crate::types::PyRepr;
+
+const DEFAULT_TZPATH: [&str; 4] = [
+    "/usr/share/zoneinfo",
+    "/usr/lib/zoneinfo",
+    "/usr/share/lib/zoneinfo",
+    "/etc/zoneinfo",
+];
+
+static TZPATH: Lock<Option<Vec<PathBuf>>> = Lock::new(None);
+
+/// ZoneInfo objects by key; entries live for the rest of the process, as
+/// datetimes may point at them.
+static CACHE: Lock<Vec<&'static ZoneInfo>> = Lock::new(Vec::new());
+
+fn not_found(key: &str) -> PyException {
+    PyException::new(
+        "zoneinfo.ZoneInfoNotFoundError",
+        format!("No time zone found with key {}", key),
+    )
+}
+
+fn invalid(what: &str) -> PyException {
+    PyException::value_error(format!("Invalid TZif file: {}", what))
+}
+
+/// Python-equivalent zoneinfo.TZPATH: PYTHONTZPATH if set, else the
+/// usual system locations.
+pub fn tzpath() -> Vec<PathBuf> {
+    TZPATH
+        .lock()
+        .get_or_insert_with(|| match env::var_os("PYTHONTZPATH") {
+            // Relative entries are ignored, as CPython ignores them with a
+            // warning.
+            Some(paths) => env::split_paths(&paths)
+                .filter(|p| p.is_absolute())
+                .collect(),
+            None => DEFAULT_TZPATH.iter().map(PathBuf::from).collect(),
+        })
+        .clone()
+}
+
+/// Python-equivalent zoneinfo.reset_tzpath(to=None): replaces the search
+/// path, or with None recomputes it from the environment.
+pub fn reset_tzpath(to: Option<&[&str]>) -> PyResult<()> {
+    let paths = match to {
+        Some(paths) => {
+            if let Some(bad) = paths.iter().find(|p| !Path::new(p).is_absolute()) {
+                return Err(PyException::value_error(format!(
+                    "TZPATHs must be absolute paths, got: {}",
+                    bad
+                )));
+            }
+            Some(paths.iter().map(PathBuf::from).collect())
+        }
+        None => None,
+    };
+    *TZPATH.lock() = paths;
+    Ok(())
+}
+
+/// Rejects keys that would escape the tz directories.
+fn validate_key(key: &str) -> PyResult<()> {
+    if Path::new(key).is_absolute() || key.starts_with('/') {
+        return Err(PyException::value_error(format!(
+            "ZoneInfo keys may not be absolute paths, got: {}",
+            key
+        )));
+    }
+    if key.is_empty() || key.split('/').any(|part| matches!(part, "" | "." | "..")) {
+        return Err(PyException::value_error(format!(
+            "ZoneInfo keys must be normalized relative paths, got: {}",
+            key
+        )));
+    }
+    Ok(())
+}
+
+/// One local time type: its offset, how much of that is daylight saving,
+/// and its abbreviation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LocalType {
+    offset: i32,
+    dst: i32,
+    abbr: String,
+}
+
+/// When a POSIX TZ rule switches, as a day of the year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RuleDay {
+    /// `Jn`: day 1..=365, never counting February 29.
+    Julian(u16),
+    /// `n`: day 0..=365, counting February 29.
+    Ordinal(u16),
+    /// `Mm.w.d`: weekday `d` (0 = Sunday) of week `w` (5 = last) of `m`.
+    Month(u32, u32, u32),
+}
+
+impl RuleDay {
+    /// Days since the epoch of this rule day in `year`.
+    fn days(self, year: i32) -> i64 {
+        let jan1 = days_from_civil(year, 1, 1);
+        match self {
+            RuleDay::Julian(n) => jan1 + n as i64 - 1 + i64::from(is_leap(year) && n >= 60),
+            RuleDay::Ordinal(n) => jan1 + n as i64,
+            RuleDay::Month(month, week, weekday) => {
+                let first = days_from_civil(year, month, 1);
+                // 1970-01-01 was a Thursday.
+                let first_weekday = (first + 4).rem_euclid(7);
+                let mut day =
+                    first + (weekday as i64 - first_weekday).rem_euclid(7) + (week as i64 - 1) * 7;
+                let next_month = if month == 12 {
+                    days_from_civil(year + 1, 1, 1)
+                } else {
+                    days_from_civil(year, month + 1, 1)
+                };
+                while day >= next_month {
+                    day -= 7;
+                }
+                day
+            }
+        }
+    }
+}
+
+/// A switch between standard and daylight time: the day, and the local
+/// time of day in seconds.
+type Switch = (RuleDay, i32);
+
+/// A POSIX TZ string such as `EST5EDT,M3.2.0,M11.1.0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    std: LocalType,
+    /// The daylight type and when it starts and ends.
+    dst: Option<(LocalType, Switch, Switch)>,
+}
+
+struct RuleParser<'a> {
+    rest: &'a str,
+}
+
+impl RuleParser<'_> {
+    fn name(&mut self) -> Option<String> {
+        if let Some(quoted) = self.rest.strip_prefix('<') {
+            let end = quoted.find('>')?;
+            self.rest = &quoted[end + 1..];
+            return Some(quoted[..end].to_string());
+        }
+        let end = self
+            .rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(self.rest.len());
+        (end >= 3).then(|| {
+            let name = self.rest[..end].to_string();
+            self.rest = &self.rest[end..];
+            name
+        })
+    }
+
+    fn number(&mut self) -> Option<i32> {
+        let end = self
+            .rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        let value = self.rest[..end].parse().ok()?;
+        self.rest = &self.rest[end..];
+        Some(value)
+    }
+
+    /// `[+-]hh[:mm[:ss]]` in seconds.
+    fn time(&mut self) -> Option<i32> {
+        let sign = if let Some(rest) = self.rest.strip_prefix('-') {
+            self.rest = rest;
+            -1
+        } else {
+            self.rest = self.rest.strip_prefix('+').unwrap_or(self.rest);
+            1
+        };
+        let mut seconds = self.number()? * 3600;
+        for scale in [60, 1] {
+            match self.rest.strip_prefix(':') {
+                Some(rest) => {
+                    self.rest = rest;
+                    seconds += self.number()? * scale;
+                }
+                None => break,
+            }
+        }
+        Some(sign * seconds)
+    }
+
+    /// `,date[/time]`, with the time defaulting to 02:00.
+    fn switch(&mut self) -> Option<Switch> {
+        self.rest = self.rest.strip_prefix(',')?;
+        let day = if let Some(rest) = self.rest.strip_prefix('M') {
+            self.rest = rest;
+            let month = self.number()? as u32;
+            self.rest = self.rest.strip_prefix('.')?;
+            let week = self.number()? as u32;
+            self.rest = self.rest.strip_prefix('.')?;
+            let weekday = self.number()? as u32;
+            if !(1..=12).contains(&month) || !(1..=5).contains(&week) || weekday > 6 {
+                return None;
+            }
+            RuleDay::Month(month, week, weekday)
+        } else if let Some(rest) = self.rest.strip_prefix('J') {
+            self.rest = rest;
+            RuleDay::Julian(
+                u16::try_from(self.number()?)
+                    .ok()
+                    .filter(|n| (1..=365).contains(n))?,
+            )
+        } else {
+            RuleDay::Ordinal(u16::try_from(self.number()?).ok().filter(|&n| n <= 365)?)
+        };
+        let time = match self.rest.strip_prefix('/') {
+            Some(rest) => {
+                self.rest = rest;
+                self.time()?
+            }
+            None => 7200,
+        };
+        Some((day, time))
+    }
+}
+
+impl Rule {
+    fn parse(tz: &str) -> PyResult<Rule> {
+        let bad = || PyException::value_error(format!("Invalid TZ string: {}", tz));
+        let mut p = RuleParser { rest: tz };
+        let std_abbr = p.name().ok_or_else(bad)?;
+        // POSIX offsets count hours west of Greenwich.
+        let std_offset = -p.time().ok_or_else(bad)?;
+        let std = LocalType {
+            offset: std_offset,
+            dst: 0,
+            abbr: std_abbr,
+        };
+        if p.rest.is_empty() {
+            return Ok(Rule { std, dst: None });
+        }
+        let dst_abbr = p.name().ok_or_else(bad)?;
+        let dst_offset = if p.rest.starts_with(',') {
+            std_offset + 3600
+        } else {
+            -p.time().ok_or_else(bad)?
+        };
+        let start = p.switch().ok_or_else(bad)?;
+        let end = p.switch().ok_or_else(bad)?;
+        if !p.rest.is_empty() {
+            return Err(bad());
+        }
+        let dst = LocalType {
+            offset: dst_offset,
+            dst: dst_offset - std_offset,
+            abbr: dst_abbr,
+        };
+        Ok(Rule {
+            std,
+            dst: Some((dst, start, end)),
+        })
+    }
+
+    /// The local type in effect at `t` seconds since the epoch (UTC).
+    fn at(&self, t: i64) -> &LocalType {
+        let Some((dst, (start_day, start_time), (end_day, end_time))) = &self.dst else {
+            return &self.std;
+        };
+        let (year, _, _) = civil_from_days((t + self.std.offset as i64).div_euclid(86400));
+        let start = start_day.days(year) * 86400 + (start_time - self.std.offset) as i64;
+        let end = end_day.days(year) * 86400 + (end_time - dst.offset) as i64;
+        let in_dst = if start < end {
+            start <= t && t < end
+        } else {
+            // Southern hemisphere: daylight time spans the new year.
+            !(end <= t && t < start)
+        };
+        if in_dst {
+            dst
+        } else {
+            &self.std
+        }
+    }
+}
+
+/// Big-endian reader over a TZif file.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> PyResult<&'a [u8]> {
+        if self.data.len() < n {
+            return Err(invalid("unexpected end of data"));
+        }
+        let (head, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(head)
+    }
+
+    fn int(&mut self, width: usize) -> PyResult<i64> {
+        let bytes = self.take(width)?;
+        Ok(if width == 8 {
+            i64::from_be_bytes(bytes.try_into().unwrap())
+        } else {
+            i32::from_be_bytes(bytes.try_into().unwrap()) as i64
+        })
+    }
+
+    /// A header: the version byte and the six counts.
+    fn header(&mut self) -> PyResult<(u8, [usize; 6])> {
+        if self.take(4)? != b"TZif" {
+            return Err(invalid("bad magic"));
+        }
+        let version = self.take(16)?[0];
+        let mut counts = [0; 6];
+        for count in &mut counts {
+            *count = self.int(4)? as usize;
+        }
+        Ok((version, counts))
+    }
+}
+
+/// Python-equivalent zoneinfo.ZoneInfo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneInfo {
+    key: Option<String>,
+    /// Transition instants (UTC seconds), ascending.
+    transitions: Vec<i64>,
+    /// The local type index taking effect at each transition.
+    indices: Vec<usize>,
+    types: Vec<LocalType>,
+    /// The type before the first transition.
+    before: usize,
+    /// Covers instants after the last transition (all of them, if none).
+    rule: Option<Rule>,
+}
+
+impl ZoneInfo {
+    /// Python-equivalent ZoneInfo(key): the zone from the cache, or loaded
+    /// from the first TZPATH directory that has it. The same key always
+    /// gives the same object, as in CPython.
+    pub fn new(key: &str) -> PyResult<&'static ZoneInfo> {
+        if let Some(&zone) = CACHE.lock().iter().find(|z| z.key() == Some(key)) {
+            return Ok(zone);
+        }
+        let zone: &'static ZoneInfo = Box::leak(Box::new(Self::no_cache(key)?));
+        let mut cache = CACHE.lock();
+        // Another thread may have loaded it meanwhile; keep theirs.
+        if let Some(&existing) = cache.iter().find(|z| z.key() == Some(key)) {
+            return Ok(existing);
+        }
+        cache.push(zone);
+        Ok(zone)
+    }
+
+    /// Python-equivalent ZoneInfo.no_cache(key): a fresh load.
+    pub fn no_cache(key: &str) -> PyResult<ZoneInfo> {
+        validate_key(key)?;
+        for dir in tzpath() {
+            let path = dir.join(key);
+            if !path.is_file() {
+                continue;
+            }
+            let data = fs::read(&path)?;
+            return Self::from_file(&data, Some(key));
+        }
+        #[cfg(feature = "tzdata")]
+        if let Some(&(_, tz)) = EMBEDDED.iter().find(|(k, _)| *k == key) {
+            return Self::from_rule(tz, Some(key));
+        }
+        Err(not_found(key))
+    }
+
+    /// Python-equivalent ZoneInfo.from_file(fobj, key=None), from the
+    /// file's bytes (TZif versions 1 to 4).
+    pub fn from_file(data: &[u8], key: Option<&str>) -> PyResult<ZoneInfo> {
+        let mut r = Reader { data };
+        let (version, counts) = r.header()?;
+        let mut width = 4;
+        let mut counts = counts;
+        if version >= b'2' {
+            // Skip the 32-bit block for the 64-bit one that follows.
+            let [isut, isstd, leap, time, types, chars] = counts;
+            r.take(time * 5 + types * 6 + chars + leap * 8 + isstd + isut)?;
+            counts = r.header()?.1;
+            width = 8;
+        }
+        let [isut, isstd, leap, time, type_count, chars] = counts;
+        if type_count == 0 {
+            return Err(invalid("no local time types"));
+        }
+        let transitions = (0..time)
+            .map(|_| r.int(width))
+            .collect::<PyResult<Vec<_>>>()?;
+        let indices: Vec<usize> = r.take(time)?.iter().map(|&i| i as usize).collect();
+        let mut raw_types = Vec::with_capacity(type_count);
+        for _ in 0..type_count {
+            let offset = r.int(4)? as i32;
+            let isdst = r.take(1)?[0] != 0;
+            let abbr_at = r.take(1)?[0] as usize;
+            raw_types.push((offset, isdst, abbr_at));
+        }
+        let abbrs = r.take(chars)?;
+        r.take(leap * (width + 4) + isstd + isut)?;
+        if indices.iter().any(|&i| i >= type_count) {
+            return Err(invalid("transition to an unknown type"));
+        }
+        let mut types: Vec<LocalType> = raw_types
+            .iter()
+            .map(|&(offset, _, at)| {
+                let abbr = abbrs.get(at..).unwrap_or_default();
+                let end = abbr.iter().position(|&b| b == 0).unwrap_or(abbr.len());
+                LocalType {
+                    offset,
+                    dst: 0,
+                    abbr: String::from_utf8_lossy(&abbr[..end]).into_owned(),
+                }
+            })
+            .collect();
+        // TZif records only whether a type is DST; the DST amount is the
+        // offset over the neighbouring standard time, as CPython infers it.
+        for (i, &index) in indices.iter().enumerate() {
+            let (offset, isdst, _) = raw_types[index];
+            if !isdst || types[index].dst != 0 {
+                continue;
+            }
+            let standard = [i.checked_sub(1), Some(i + 1)]
+                .into_iter()
+                .flatten()
+                .filter_map(|j| indices.get(j))
+                .find(|&&j| !raw_types[j].1);
+            types[index].dst = match standard {
+                Some(&j) => offset - raw_types[j].0,
+                None => 3600,
+            };
+        }
+        for (ty, &(offset, isdst, _)) in types.iter_mut().zip(&raw_types) {
+            if isdst && ty.dst == 0 {
+                ty.dst = if offset == 0 {
+                    3600
+                } else {
+                    offset.signum() * 3600
+                };
+            }
+        }
+        let before = raw_types.iter().position(|t| !t.1).unwrap_or(0);
+        let rule = if width == 8 {
+            let footer = String::from_utf8_lossy(r.data);
+            match footer.trim_matches('\n') {
+                "" => None,
+                tz => Some(Rule::parse(tz)?),
+            }
+        } else {
+            None
+        };
+        Ok(ZoneInfo {
+            key: key.map(str::to_string),
+            transitions,
+            indices,
+            types,
+            before,
+            rule,
+        })
+    }
+
+    /// A zone with no history, following the POSIX TZ rule `tz` (e.g.
+    /// `CET-1CEST,M3.5.0,M10.5.0/3`) at all times.
+    pub fn from_rule(tz: &str, key: Option<&str>) -> PyResult<ZoneInfo> {
+        let rule = Rule::parse(tz)?;
+        Ok(ZoneInfo {
+            key: key.map(str::to_string),
+            transitions: Vec::new(),
+            indices: Vec::new(),
+            types: vec![rule.std.clone()],
+            before: 0,
+            rule: Some(rule),
+        })
+    }
+
+    /// Python-equivalent ZoneInfo.clear_cache(only_keys=None). Zones already
+    /// handed out stay valid; later lookups load afresh.
+    pub fn clear_cache(only_keys: Option<&[&str]>) {
+        let mut cache = CACHE.lock();
+        match only_keys {
+            Some(keys) => cache.retain(|z| !z.key().is_some_and(|k| keys.contains(&k))),
+            None => cache.clear(),
+        }
+    }
+
+    /// Python-equivalent ZoneInfo.key.
+    pub fn key(&self) -> Option<&str> {
+        self.key.as_deref()
+    }
+
+    /// The local type in effect at `t` seconds since the epoch (UTC).
+    fn at(&self, t: i64) -> &LocalType {
+        let after = self.transitions.partition_point(|&x| x <= t);
+        match (after, &self.rule) {
+            (n, Some(rule)) if n == self.transitions.len() => rule.at(t),
+            (0, _) => &self.types[self.before],
+            (n, _) => &self.types[self.indices[n - 1]],
+        }
+    }
+
+    /// The local type for wall-clock time `dt`. In a gap or a repeated
+    /// hour, fold 0 means the type from before the transition and fold 1
+    /// the one after, as PEP 495 specifies.
+    fn local(&self, dt: &DateTime) -> &LocalType {
+        let wall = dt.wall_seconds();
+        let before = self.at(wall - 86400);
+        let after = self.at(wall + 86400);
+        let fits = |ty: &LocalType| self.at(wall - ty.offset as i64).offset == ty.offset;
+        match (fits(before), fits(after)) {
+            (true, false) => before,
+            (false, true) => after,
+            _ if dt.fold() => after,
+            _ => before,
+        }
+    }
+}
+
+impl TzInfo for ZoneInfo {
+    fn utcoffset(&self, dt: &DateTime) -> Option<i32> {
+        Some(self.local(dt).offset)
+    }
+
+    fn dst(&self, dt: &DateTime) -> Option<i32> {
+        Some(self.local(dt).dst)
+    }
+
+    fn tzname(&self, dt: &DateTime) -> Option<String> {
+        Some(self.local(dt).abbr.clone())
+    }
+
+    fn fromutc(&self, utc: &DateTime) -> DateTime {
+        let offset = self.at(utc.wall_seconds()).offset;
+        let wall = utc.shift(offset as i64);
+        // The second pass through a repeated hour is the one fold 0 misses.
+        let fold = self.local(&wall).offset != offset;
+        wall.replace_fold(fold)
+    }
+}
+
+/// `str(zone)`: the key.
+impl fmt::Display for ZoneInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.key {
+            Some(key) => f.write_str(key),
+            None => f.write_str(&self.py_repr()),
+        }
+    }
+}
+
+impl PyRepr for ZoneInfo {
+    fn py_repr(&self) -> String {
+        match &self.key {
+            Some(key) => format!("zoneinfo.ZoneInfo(key={})", key.py_repr()),
+            None => "zoneinfo.ZoneInfo.from_file(None)".to_string(),
+        }
+    }
+}
+
+/// Python-equivalent zoneinfo.available_timezones(): every key with a TZif
+/// file on TZPATH (plus the built-in rules with `tzdata`).
+pub fn available_timezones() -> BTreeSet<String> {
+    fn walk(root: &Path, dir: &Path, keys: &mut BTreeSet<String>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name();
+            if path.is_dir() {
+                // Leap-second and POSIX-only variants duplicate the zones.
+                if name != "right" && name != "posix" {
+                    walk(root, &path, keys);
+                }
+                continue;
+            }
+            let is_tzif = fs::File::open(&path).is_ok_and(|mut f| {
+                let mut magic = [0; 4];
+                std::io::Read::read_exact(&mut f, &mut magic).is_ok() && &magic == b"TZif"
+            });
+            if is_tzif && name != "posixrules" && name != "localtime" {
+                if let Ok(key) = path.strip_prefix(root) {
+                    keys.insert(key.to_string_lossy().replace('\\', "/"));
+                }
+            }
+        }
+    }
+
+    let mut keys = BTreeSet::new();
+    for root in tzpath() {
+        walk(&root, &root, &mut keys);
+    }
+    #[cfg(feature = "tzdata")]
+    keys.extend(EMBEDDED.iter().map(|(key, _)| key.to_string()));
+    keys
+}
+
+/// Current rules for widely used zones, for systems without a tz database.
+#[cfg(feature = "tzdata")]
+const EMBEDDED: &[(&str, &str)] = &[
+    ("UTC", "UTC0"),
+    ("Etc/UTC", "UTC0"),
+    ("GMT", "GMT0"),
+    ("America/New_York", "EST5EDT,M3.2.0,M11.1.0"),
+    ("America/Chicago", "CST6CDT,M3.2.0,M11.1.0"),
+    ("America/Denver", "MST7MDT,M3.2.0,M11.1.0"),
+    ("America/Phoenix", "MST7"),
+    ("America/Los_Angeles", "PST8PDT,M3.2.0,M11.1.0"),
+    ("America/Anchorage", "AKST9AKDT,M3.2.0,M11.1.0"),
+    ("Pacific/Honolulu", "HST10"),
+    ("America/Toronto", "EST5EDT,M3.2.0,M11.1.0"),
+    ("America/Vancouver", "PST8PDT,M3.2.0,M11.1.0"),
+    ("America/Mexico_City", "CST6"),
+    ("America/Sao_Paulo", "<-03>3"),
+    ("America/Argentina/Buenos_Aires", "<-03>3"),
+    ("Europe/London", "GMT0BST,M3.5.0/1,M10.5.0"),
+    ("Europe/Dublin", "IST-1GMT0,M10.5.0,M3.5.0/1"),
+    ("Europe/Lisbon", "WET0WEST,M3.5.0/1,M10.5.0"),
+    ("Europe/Paris", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Berlin", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Madrid", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Rome", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Amsterdam", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Stockholm", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Warsaw", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Athens", "EET-2EEST,M3.5.0/3,M10.5.0/4"),
+    ("Europe/Helsinki", "EET-2EEST,M3.5.0/3,M10.5.0/4"),
+    ("Europe/Kyiv", "EET-2EEST,M3.5.0/3,M10.5.0/4"),
+    ("Europe/Istanbul", "<+03>-3"),
+    ("Europe/Moscow", "MSK-3"),
+    ("Africa/Cairo", "EET-2EEST,M4.5.5/0,M10.5.4/24"),
+    ("Africa/Johannesburg", "SAST-2"),
+    ("Africa/Lagos", "WAT-1"),
+    ("Africa/Nairobi", "EAT-3"),
+    ("Asia/Dubai", "<+04>-4"),
+    ("Asia/Karachi", "PKT-5"),
+    ("Asia/Kolkata", "IST-5:30"),
+    ("Asia/Dhaka", "<+06>-6"),
+    ("Asia/Bangkok", "<+07>-7"),
+    ("Asia/Jakarta", "WIB-7"),
+    ("Asia/Shanghai", "CST-8"),
+    ("Asia/Hong_Kong", "HKT-8"),
+    ("Asia/Singapore", "<+08>-8"),
+    ("Asia/Taipei", "CST-8"),
+    ("Asia/Seoul", "KST-9"),
+    ("Asia/Tokyo", "JST-9"),
+    ("Australia/Perth", "AWST-8"),
+    ("Australia/Adelaide", "ACST-9:30ACDT,M10.1.0,M4.1.0/3"),
+    ("Australia/Brisbane", "AEST-10"),
+    ("Australia/Sydney", "AEST-10AEDT,M10.1.0,M4.1.0/3"),
+    ("Australia/Melbourne", "AEST-10AEDT,M10.1.0,M4.1.0/3"),
+    ("Pacific/Auckland", "NZST-12NZDT,M9.5.0,M4.1.0/3"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::datetime::{Date, Time};
+
+    fn dt(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime {
+        DateTime::new(
+            Date::new(y, mo, d).unwrap(),
+            Time::new(h, mi, 0, 0).unwrap(),
+        )
+    }
+
+    fn new_york() -> ZoneInfo {
+        ZoneInfo::from_rule("EST5EDT,M3.2.0,M11.1.0", Some("America/New_York")).unwrap()
+    }
+
+    #[test]
+    fn posix_rules() {
+        let ny = new_york();
+        let summer = dt(2024, 7, 1, 12, 0);
+        assert_eq!(ny.utcoffset(&summer), Some(-4 * 3600));
+        assert_eq!(ny.dst(&summer), Some(3600));
+        assert_eq!(ny.tzname(&summer).as_deref(), Some("EDT"));
+        assert_eq!(ny.tzname(&dt(2024, 1, 1, 0, 0)).as_deref(), Some("EST"));
+
+        let sydney = ZoneInfo::from_rule("AEST-10AEDT,M10.1.0,M4.1.0/3", None).unwrap();
+        assert_eq!(sydney.utcoffset(&dt(2024, 1, 15, 12, 0)), Some(11 * 3600));
+        assert_eq!(
+            sydney.tzname(&dt(2024, 7, 15, 12, 0)).as_deref(),
+            Some("AEST")
+        );
+
+        let kolkata = ZoneInfo::from_rule("<+0530>-5:30", None).unwrap();
+        assert_eq!(kolkata.utcoffset(&summer), Some(19800));
+        assert_eq!(kolkata.tzname(&summer).as_deref(), Some("+0530"));
+
+        assert_eq!(RuleDay::Julian(60).days(2024), days_from_civil(2024, 3, 1));
+        assert_eq!(
+            RuleDay::Ordinal(59).days(2024),
+            days_from_civil(2024, 2, 29)
+        );
+        assert_eq!(
+            RuleDay::Month(2, 5, 4).days(2024),
+            days_from_civil(2024, 2, 29)
+        );
+        for bad in ["", "E5", "EST", "EST5EDT,M13.1.0,M11.1.0", "EST5EDT,M3.2.0"] {
+            assert!(ZoneInfo::from_rule(bad, None).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn folds_in_gaps_and_repeats() {
+        let ny = new_york();
+        // 01:30 on 2024-11-03 happens twice; 02:30 on 2024-03-10 never.
+        let repeated = dt(2024, 11, 3, 1, 30);
+        assert_eq!(ny.utcoffset(&repeated), Some(-4 * 3600));
+        assert_eq!(ny.utcoffset(&repeated.replace_fold(true)), Some(-5 * 3600));
+        let skipped = dt(2024, 3, 10, 2, 30);
+        assert_eq!(ny.tzname(&skipped).as_deref(), Some("EST"));
+        assert_eq!(
+            ny.tzname(&skipped.replace_fold(true)).as_deref(),
+            Some("EDT")
+        );
+
+        let ny: &'static ZoneInfo = Box::leak(Box::new(ny));
+        let first = dt(2024, 11, 3, 5, 30).astimezone(ny);
+        assert_eq!(first.to_string(), "2024-11-03 01:30:00-04:00");
+        assert!(!first.fold());
+        let second = dt(2024, 11, 3, 6, 30).astimezone(ny);
+        assert_eq!(second.to_string(), "2024-11-03 01:30:00-05:00");
+        assert!(second.fold());
+        assert_eq!(second.strftime("%H:%M %Z %z"), "01:30 EST -0500");
+        assert_eq!(second.timestamp(), 1730615400.0);
+        let aware = dt(2024, 7, 1, 0, 0).replace_tzinfo(Some(ny));
+        assert_eq!(aware.timestamp(), 1719806400.0);
+        assert_eq!(aware.timetuple().tm_isdst, 1);
+    }
+
+    #[test]
+    fn keys_and_tzpath() {
+        for key in ["/etc/passwd", "../x", "America/../../x", ""] {
+            assert!(
+                ZoneInfo::no_cache(key).unwrap_err().is("ValueError"),
+                "{}",
+                key
+            );
+        }
+        assert!(reset_tzpath(Some(&["relative"])).is_err());
+        let err = ZoneInfo::no_cache("Not/AZone").unwrap_err();
+        assert!(err.is("zoneinfo.ZoneInfoNotFoundError"));
+        assert_eq!(err.message, "No time zone found with key Not/AZone");
+        assert_eq!(
+            new_york().py_repr(),
+            "zoneinfo.ZoneInfo(key='America/New_York')"
+        );
+    }
+
+    /// Against the system database, when there is one.
+    #[test]
+    fn system_tzif_files() {
+        let Ok(ny) = ZoneInfo::new("America/New_York") else {
+            return;
+        };
+        if ny.transitions.is_empty() {
+            // Built-in rules stood in for the missing database.
+            return;
+        }
+        assert!(core::ptr::eq(
+            ny,
+            ZoneInfo::new("America/New_York").unwrap()
+        ));
+        assert_eq!(ny.to_string(), "America/New_York");
+        // Far past and future use the transitions and the footer rule.
+        assert_eq!(ny.tzname(&dt(1900, 1, 1, 0, 0)).as_deref(), Some("EST"));
+        assert_eq!(ny.utcoffset(&dt(2100, 7, 1, 0, 0)), Some(-4 * 3600));
+        let second = dt(2024, 11, 3, 6, 30).astimezone(ny);
+        assert_eq!(second.isoformat('T'), "2024-11-03T01:30:00-05:00");
+        if let Ok(kolkata) = ZoneInfo::new("Asia/Kolkata") {
+            let war = dt(1945, 1, 1, 0, 0);
+            assert_eq!(kolkata.utcoffset(&war), Some(23400));
+            assert_eq!(kolkata.dst(&war), Some(3600));
+            assert_eq!(kolkata.tzname(&war).as_deref(), Some("+0630"));
+        }
+        assert!(available_timezones().contains("America/New_York"));
+    }
+}
@@ -0,0 +1,138 @@
+//! A minimal mutex for global runtime state that also works without std.
+
+#[cfg(feature = "std")]
+pub(crate) use std::sync::MutexGuard as LockGuard;
+
+/// A lock around global state. With `std` this is a `Mutex` that ignores
+/// poisoning; without it, a spin lock.
+pub(crate) struct Lock<T> {
+    #[cfg(feature = "std")]
+    inner: std::sync::Mutex<T>,
+    #[cfg(not(feature = "std"))]
+    locked: core::sync::atomic::AtomicBool,
+    #[cfg(not(feature = "std"))]
+    value: core::cell::UnsafeCell<T>,
+}
+
+#[cfg(feature = "std")]
+impl<T> Lock<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Lock {
+            inner: std::sync::Mutex::new(value),
+        }
+    }
+
+    pub(crate) fn lock(&self) -> LockGuard<'_, T> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod spin {
+    use core::cell::UnsafeCell;
+    use core::ops::{Deref, DerefMut};
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    use super::Lock;
+
+    // SAFETY: access to `value` is serialized by `locked`.
+    unsafe impl<T: Send> Sync for Lock<T> {}
+
+    pub(crate) struct LockGuard<'a, T> {
+        lock: &'a Lock<T>,
+    }
+
+    impl<T> Lock<T> {
+        pub(crate) const fn new(value: T) -> Self {
+            Lock {
+                locked: AtomicBool::new(false),
+                value: UnsafeCell::new(value),
+            }
+        }
+
+        pub(crate) fn lock(&self) -> LockGuard<'_, T> {
+            while self
+                .locked
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                core::hint::spin_loop();
+            }
+            LockGuard { lock: self }
+        }
+    }
+
+    impl<T> Deref for LockGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            // SAFETY: the guard holds the lock.
+            unsafe { &*self.lock.value.get() }
+        }
+    }
+
+    impl<T> DerefMut for LockGuard<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            // SAFETY: the guard holds the lock exclusively.
+            unsafe { &mut *self.lock.value.get() }
+        }
+    }
+
+    impl<T> Drop for LockGuard<'_, T> {
+        fn drop(&mut self) {
+            self.lock.locked.store(false, Ordering::Release);
+        }
+    }
+}
+
+/// A value written at most once and read freely afterwards.
+pub(crate) struct Once<T> {
+    state: core::sync::atomic::AtomicU8,
+    value: core::cell::UnsafeCell<core::mem::MaybeUninit<T>>,
+}
+
+const UNSET: u8 = 0;
+const WRITING: u8 = 1;
+const READY: u8 = 2;
+
+// SAFETY: `value` is written once, by whoever moves `state` off UNSET, and
+// only read after `state` is READY.
+unsafe impl<T: Send + Sync> Sync for Once<T> {}
+
+impl<T> Once<T> {
+    pub(crate) const fn new() -> Self {
+        Once {
+            state: core::sync::atomic::AtomicU8::new(UNSET),
+            value: core::cell::UnsafeCell::new(core::mem::MaybeUninit::uninit()),
+        }
+    }
+
+    /// Stores `value` unless one is already stored or being stored.
+    pub(crate) fn set(&self, value: T) -> Result<(), T> {
+        use core::sync::atomic::Ordering;
+        if self
+            .state
+            .compare_exchange(UNSET, WRITING, Ordering::Acquire, Ordering::Acquire)
+            .is_err()
+        {
+            return Err(value);
+        }
+        // SAFETY: winning the exchange gives exclusive access.
+        unsafe { (*self.value.get()).write(value) };
+        self.state.store(READY, Ordering::Release);
+        Ok(())
+    }
+
+    /// The stored value, storing `init()` first if there is none.
+    pub(crate) fn get_or_init(&self, init: impl FnOnce() -> T) -> &T {
+        use core::sync::atomic::Ordering;
+        if self.state.load(Ordering::Acquire) != READY {
+            let _ = self.set(init());
+            while self.state.load(Ordering::Acquire) != READY {
+                core::hint::spin_loop();
+            }
+        }
+        // SAFETY: READY means `value` was written and is never written again.
+        unsafe { (*self.value.get()).assume_init_ref() }
+    }
+}
@@ -0,0 +1,100 @@
+//! Python bytes.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Deref;
+
+use crate::exceptions::{PyException, PyResult};
+
+/// Python-equivalent bytes: an immutable byte string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct PyBytes {
+    data: Vec<u8>,
+}
+
+impl PyBytes {
+    pub fn new<B: Into<Vec<u8>>>(data: B) -> Self {
+        PyBytes { data: data.into() }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_vec(self) -> Vec<u8> {
+        self.data
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Python-equivalent bytes.decode() with the utf-8 codec.
+    pub fn decode(&self) -> PyResult<String> {
+        String::from_utf8(self.data.clone()).map_err(|e| {
+            let pos = e.utf8_error().valid_up_to();
+            PyException::new(
+                "UnicodeDecodeError",
+                format!(
+                    "'utf-8' codec can't decode byte 0x{:02x} in position {}: invalid start byte",
+                    self.data[pos], pos
+                ),
+            )
+        })
+    }
+
+    /// Python-equivalent bytes.hex().
+    pub fn hex(&self) -> String {
+        self.data.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+impl Deref for PyBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl From<Vec<u8>> for PyBytes {
+    fn from(data: Vec<u8>) -> Self {
+        PyBytes { data }
+    }
+}
+
+impl From<&[u8]> for PyBytes {
+    fn from(data: &[u8]) -> Self {
+        PyBytes::new(data)
+    }
+}
+
+/// Formats as a `b'...'` literal, escaping non-printable bytes.
+impl fmt::Display for PyBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let quote = if self.data.contains(&b'\'') && !self.data.contains(&b'"') {
+            '"'
+        } else {
+            '\''
+        };
+        write!(f, "b{}", quote)?;
+        for &b in &self.data {
+            match b {
+                b'\\' => f.write_str("\\\\")?,
+                b'\t' => f.write_str("\\t")?,
+                b'\n' => f.write_str("\\n")?,
+                b'\r' => f.write_str("\\r")?,
+                _ if b as char == quote => write!(f, "\\{}", quote)?,
+                0x20..=0x7e => write!(f, "{}", b as char)?,
+                _ => write!(f, "\\x{:02x}", b)?,
+            }
+        }
+        write!(f, "{}", quote)
+    }
+}
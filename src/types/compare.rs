@@ -0,0 +1,353 @@
+//! Rich comparison: `==`, `!=`, `<`, `<=`, `>` and `>=` with Python's
+//! two-sided dispatch.
+//!
+//! Each type answers [`PyRichCompare::py_richcompare`] with a result or
+//! [`PyValue::NotImplemented`]. [`richcompare`] tries the left operand,
+//! then the right operand's reflected method, then falls back the way
+//! CPython's `do_richcompare` does: identity for `==` and `!=`, TypeError
+//! for orderings.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::hash::Hash;
+
+use super::ops::unorderable;
+use super::{PyBytes, PyDictionary, PyList, PySet, PyStr, PyTruthy, PyTuple, PyTyped, PyValue};
+use crate::exceptions::PyResult;
+
+/// A comparison operator, as passed to `__richcmp__`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Eq,
+    Ne,
+    Gt,
+    Ge,
+}
+
+impl CompareOp {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+        }
+    }
+
+    /// The operator the right operand is asked with: `a < b` becomes
+    /// `b > a`, while `==` and `!=` are their own reflection.
+    pub fn reflected(self) -> CompareOp {
+        match self {
+            CompareOp::Lt => CompareOp::Gt,
+            CompareOp::Le => CompareOp::Ge,
+            CompareOp::Gt => CompareOp::Lt,
+            CompareOp::Ge => CompareOp::Le,
+            op => op,
+        }
+    }
+
+    /// Whether two operands ordered as `ord` satisfy the operator.
+    pub fn holds(self, ord: Ordering) -> bool {
+        match self {
+            CompareOp::Lt => ord == Ordering::Less,
+            CompareOp::Le => ord != Ordering::Greater,
+            CompareOp::Eq => ord == Ordering::Equal,
+            CompareOp::Ne => ord != Ordering::Equal,
+            CompareOp::Gt => ord == Ordering::Greater,
+            CompareOp::Ge => ord != Ordering::Less,
+        }
+    }
+
+    /// The result for operands ordered as `ord`, where `None` means
+    /// unordered like NaN: only `!=` holds.
+    pub fn result(self, ord: Option<Ordering>) -> PyValue {
+        PyValue::Bool(match ord {
+            Some(ord) => self.holds(ord),
+            None => self == CompareOp::Ne,
+        })
+    }
+}
+
+/// Python-equivalent `__lt__`/`__le__`/`__eq__`/`__ne__`/`__gt__`/`__ge__`.
+/// Returns [`PyValue::NotImplemented`] for operand types it does not
+/// handle, so the other operand gets a turn.
+pub trait PyRichCompare<Rhs: ?Sized = Self> {
+    fn py_richcompare(&self, other: &Rhs, op: CompareOp) -> PyResult<PyValue>;
+}
+
+/// Python-equivalent `a <op> b`. A right operand whose type is a proper
+/// subclass of the left's is asked first, as in CPython.
+pub fn richcompare<A, B>(a: &A, b: &B, op: CompareOp) -> PyResult<PyValue>
+where
+    A: PyRichCompare<B> + PyTyped + ?Sized,
+    B: PyRichCompare<A> + PyTyped + ?Sized,
+{
+    let (ta, tb) = (a.py_type(), b.py_type());
+    let reflected_first = ta != tb && tb.is_subclass_of(ta);
+    if reflected_first {
+        let result = b.py_richcompare(a, op.reflected())?;
+        if !result.is_not_implemented() {
+            return Ok(result);
+        }
+    }
+    let result = a.py_richcompare(b, op)?;
+    if !result.is_not_implemented() {
+        return Ok(result);
+    }
+    if !reflected_first {
+        let result = b.py_richcompare(a, op.reflected())?;
+        if !result.is_not_implemented() {
+            return Ok(result);
+        }
+    }
+    let same = a.py_id() == b.py_id();
+    match op {
+        CompareOp::Eq => Ok(PyValue::Bool(same)),
+        CompareOp::Ne => Ok(PyValue::Bool(!same)),
+        _ => Err(unorderable(op.symbol(), a, b)),
+    }
+}
+
+macro_rules! compare_fn {
+    ($($name:ident $op:ident $doc:literal),*) => {
+        $(
+            #[doc = $doc]
+            pub fn $name<A, B>(a: &A, b: &B) -> PyResult<bool>
+            where
+                A: PyRichCompare<B> + PyTyped + ?Sized,
+                B: PyRichCompare<A> + PyTyped + ?Sized,
+            {
+                richcompare(a, b, CompareOp::$op).map(|result| result.py_bool())
+            }
+        )*
+    };
+}
+
+compare_fn!(
+    py_eq Eq "Python-equivalent `a == b`.",
+    py_ne Ne "Python-equivalent `a != b`.",
+    py_lt Lt "Python-equivalent `a < b`.",
+    py_le Le "Python-equivalent `a <= b`.",
+    py_gt Gt "Python-equivalent `a > b`.",
+    py_ge Ge "Python-equivalent `a >= b`."
+);
+
+/// list and tuple comparison: the first pair of items that differ decides,
+/// and if there is none, the shorter sequence is the smaller.
+pub fn compare_sequences<A, B>(a: &[A], b: &[B], op: CompareOp) -> PyResult<PyValue>
+where
+    A: PyRichCompare<B> + PyTyped,
+    B: PyRichCompare<A> + PyTyped,
+{
+    if a.len() != b.len() && matches!(op, CompareOp::Eq | CompareOp::Ne) {
+        return Ok(PyValue::Bool(op == CompareOp::Ne));
+    }
+    for (x, y) in a.iter().zip(b) {
+        if !py_eq(x, y)? {
+            return match op {
+                CompareOp::Eq => Ok(PyValue::Bool(false)),
+                CompareOp::Ne => Ok(PyValue::Bool(true)),
+                _ => richcompare(x, y, op),
+            };
+        }
+    }
+    Ok(op.result(Some(a.len().cmp(&b.len()))))
+}
+
+macro_rules! impl_ordered {
+    ($($t:ty),*) => {
+        $(impl PyRichCompare for $t {
+            fn py_richcompare(&self, other: &Self, op: CompareOp) -> PyResult<PyValue> {
+                Ok(op.result(self.partial_cmp(other)))
+            }
+        })*
+    };
+}
+
+impl_ordered!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_ordered!(f32, f64, bool, str, String, PyStr, PyBytes);
+
+impl<T: PyRichCompare + PyTyped> PyRichCompare for PyList<T> {
+    fn py_richcompare(&self, other: &Self, op: CompareOp) -> PyResult<PyValue> {
+        compare_sequences(self.as_slice(), other.as_slice(), op)
+    }
+}
+
+impl<T: PyRichCompare + PyTyped> PyRichCompare for Vec<T> {
+    fn py_richcompare(&self, other: &Self, op: CompareOp) -> PyResult<PyValue> {
+        compare_sequences(self, other, op)
+    }
+}
+
+impl<T: PyRichCompare + PyTyped> PyRichCompare for PyTuple<T> {
+    fn py_richcompare(&self, other: &Self, op: CompareOp) -> PyResult<PyValue> {
+        compare_sequences(self.as_slice(), other.as_slice(), op)
+    }
+}
+
+/// dicts only support `==` and `!=`: the same keys, each mapped to equal
+/// values, in any order.
+impl<K: Hash + Eq, V: PyRichCompare + PyTyped> PyRichCompare for PyDictionary<K, V> {
+    fn py_richcompare(&self, other: &Self, op: CompareOp) -> PyResult<PyValue> {
+        if !matches!(op, CompareOp::Eq | CompareOp::Ne) {
+            return Ok(PyValue::NotImplemented);
+        }
+        let mut equal = self.len() == other.len();
+        if equal {
+            for (key, value) in self.iter() {
+                match other.get(key) {
+                    Some(theirs) if py_eq(value, theirs)? => {}
+                    _ => {
+                        equal = false;
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(PyValue::Bool(equal == (op == CompareOp::Eq)))
+    }
+}
+
+/// Sets order by inclusion: `a <= b` is a.issubset(b).
+impl<T: Hash + Eq> PyRichCompare for PySet<T> {
+    fn py_richcompare(&self, other: &Self, op: CompareOp) -> PyResult<PyValue> {
+        Ok(PyValue::Bool(set_compare(self, other, op)))
+    }
+}
+
+fn set_compare<T: Hash + Eq>(a: &PySet<T>, b: &PySet<T>, op: CompareOp) -> bool {
+    match op {
+        CompareOp::Eq => a.len() == b.len() && a.issubset(b),
+        CompareOp::Ne => !(a.len() == b.len() && a.issubset(b)),
+        CompareOp::Le => a.issubset(b),
+        CompareOp::Lt => a.len() < b.len() && a.issubset(b),
+        CompareOp::Ge => a.issuperset(b),
+        CompareOp::Gt => a.len() > b.len() && a.issuperset(b),
+    }
+}
+
+impl PyRichCompare for PyValue {
+    fn py_richcompare(&self, other: &Self, op: CompareOp) -> PyResult<PyValue> {
+        use PyValue::*;
+        match (self, other) {
+            (None, None) | (NotImplemented, NotImplemented) => Ok(match op {
+                CompareOp::Eq | CompareOp::Ne => op.result(Some(Ordering::Equal)),
+                _ => NotImplemented,
+            }),
+            (Str(a), Str(b)) => a.py_richcompare(b, op),
+            (Bytes(a), Bytes(b)) => a.py_richcompare(b, op),
+            (List(a), List(b)) => a.py_richcompare(b, op),
+            (Tuple(a), Tuple(b)) => a.py_richcompare(b, op),
+            (Dict(a), Dict(b)) => a.py_richcompare(b, op),
+            (Set(a), Set(b)) => a.py_richcompare(b, op),
+            (Function(a), Function(b)) => Ok(match op {
+                CompareOp::Eq => Bool(a == b),
+                CompareOp::Ne => Bool(a != b),
+                _ => NotImplemented,
+            }),
+            (Bool(_) | Int(_) | Float(_), Bool(_) | Int(_) | Float(_)) => {
+                Ok(op.result(self.partial_cmp(other)))
+            }
+            _ => Ok(NotImplemented),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PyTypeId;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    fn list(items: Vec<PyValue>) -> PyValue {
+        PyValue::List(PyList::from_vec(items))
+    }
+
+    #[test]
+    fn lexicographic_sequences() {
+        let a = PyList::from_vec(vec![1, 2, 3]);
+        let b = PyList::from_vec(vec![1, 3]);
+        assert!(py_lt(&a, &b).unwrap());
+        assert!(py_le(&a, &a).unwrap());
+        assert!(py_gt(&PyTuple::from_vec(vec![1, 2]), &PyTuple::from_vec(vec![1])).unwrap());
+        assert!(py_ne(&a, &b).unwrap());
+
+        let mixed = list(vec![PyValue::Int(1), PyValue::from("x")]);
+        let other = list(vec![PyValue::Float(1.0), PyValue::Int(2)]);
+        assert!(py_eq(
+            &list(vec![PyValue::Int(1)]),
+            &list(vec![PyValue::Float(1.0)])
+        )
+        .unwrap());
+        assert_eq!(
+            py_lt(&mixed, &other).unwrap_err().to_string(),
+            "TypeError: '<' not supported between instances of 'str' and 'int'"
+        );
+        assert!(!py_eq(&mixed, &other).unwrap());
+        assert!(py_lt(&f64::NAN, &1.0).is_ok_and(|lt| !lt));
+        assert!(py_ne(&f64::NAN, &f64::NAN).unwrap());
+    }
+
+    #[test]
+    fn dict_and_set_comparisons() {
+        let a: PyDictionary<&str, i64> = [("x", 1), ("y", 2)].into_iter().collect();
+        let b: PyDictionary<&str, i64> = [("y", 2), ("x", 1)].into_iter().collect();
+        assert!(py_eq(&a, &b).unwrap());
+        assert_eq!(
+            py_lt(
+                &PyValue::Dict(PyDictionary::new()),
+                &PyValue::Dict(PyDictionary::new())
+            )
+            .unwrap_err()
+            .to_string(),
+            "TypeError: '<' not supported between instances of 'dict' and 'dict'"
+        );
+
+        let small: PySet<i64> = [1].into_iter().collect();
+        let big: PySet<i64> = [1, 2].into_iter().collect();
+        assert!(py_lt(&small, &big).unwrap() && py_le(&small, &small).unwrap());
+        assert!(!py_lt(&small, &small).unwrap() && py_ge(&big, &small).unwrap());
+    }
+
+    #[test]
+    fn rust_operators_agree() {
+        let a = PyList::from_vec(vec![1, 2, 3]);
+        assert!(a < PyList::from_vec(vec![1, 3]) && a > PyList::from_vec(vec![1, 2]));
+        assert!(PyTuple::from_vec(vec!["a", "b"]) < PyTuple::from_vec(vec!["b"]));
+        let mixed = list(vec![PyValue::Int(1), PyValue::from("x")]);
+        assert!(mixed
+            .partial_cmp(&list(vec![PyValue::Int(1), PyValue::Int(2)]))
+            .is_none());
+        assert!(list(vec![PyValue::Int(1)]) < list(vec![PyValue::Float(1.5)]));
+
+        let small: PySet<i64> = [1].into_iter().collect();
+        let big: PySet<i64> = [1, 2].into_iter().collect();
+        let other: PySet<i64> = [3].into_iter().collect();
+        assert!(small < big && small <= small.clone() && big >= small);
+        assert_eq!(small.partial_cmp(&other), None);
+    }
+
+    #[test]
+    fn mismatched_types_fall_back() {
+        let one = PyValue::Int(1);
+        let s = PyValue::from("1");
+        assert!(!py_eq(&one, &s).unwrap());
+        assert!(py_ne(&one, &s).unwrap());
+        assert_eq!(
+            py_ge(&PyValue::None, &one).unwrap_err().to_string(),
+            "TypeError: '>=' not supported between instances of 'NoneType' and 'int'"
+        );
+        assert!(py_eq(&PyValue::NotImplemented, &PyValue::NotImplemented).unwrap());
+        assert_eq!(
+            PyValue::NotImplemented.py_type(),
+            PyTypeId::NotImplementedType
+        );
+        assert_eq!(PyValue::NotImplemented.to_string(), "NotImplemented");
+    }
+}
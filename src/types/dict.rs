@@ -0,0 +1,629 @@
+//! Python dict: a hash map that remembers insertion order.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::Hash;
+use core::ops::{BitOr, BitOrAssign, Index, IndexMut};
+
+use super::function::KwargsMap;
+use super::hash::hash_key;
+use super::repr::PyRepr;
+use super::table::IndexTable;
+use super::{PyKwargs, PyValue};
+use crate::exceptions::{PyException, PyResult};
+
+#[derive(Clone)]
+struct Entry<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+}
+
+/// Python-equivalent dict. Iteration follows insertion order; re-assigning an
+/// existing key keeps its original position, as in CPython.
+#[derive(Clone)]
+pub struct PyDictionary<K, V> {
+    entries: Vec<Option<Entry<K, V>>>,
+    table: IndexTable,
+    len: usize,
+}
+
+impl<K, V> Default for PyDictionary<K, V> {
+    fn default() -> Self {
+        PyDictionary {
+            entries: Vec::new(),
+            table: IndexTable::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<K, V> PyDictionary<K, V> {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Iterates `(key, value)` pairs in insertion order.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            entries: self.entries.iter(),
+        }
+    }
+
+    /// Iterates keys in insertion order: what `for key in d` visits.
+    pub fn iter_keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            entries: self.entries.iter(),
+        }
+    }
+
+    /// Python-equivalent dict.keys(): a borrowing view; collect with
+    /// [`to_list`](KeysView::to_list).
+    pub fn keys(&self) -> KeysView<Iter<'_, K, V>> {
+        KeysView(self.iter())
+    }
+
+    /// Python-equivalent dict.values().
+    pub fn values(&self) -> ValuesView<Iter<'_, K, V>> {
+        ValuesView(self.iter())
+    }
+
+    /// Python-equivalent dict.items().
+    pub fn items(&self) -> ItemsView<Iter<'_, K, V>> {
+        ItemsView(self.iter())
+    }
+}
+
+impl<K: Hash + Eq, V> PyDictionary<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn position<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let entries = &self.entries;
+        self.table.find(hash_key(key), |i| {
+            entries[i].as_ref().is_some_and(|e| e.key.borrow() == key)
+        })
+    }
+
+    /// Python-equivalent key in dict.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.position(key).is_some()
+    }
+
+    /// Python-equivalent dict.get(key).
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.position(key)?;
+        self.entries[i].as_ref().map(|e| &e.value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let i = self.position(key)?;
+        self.entries[i].as_mut().map(|e| &mut e.value)
+    }
+
+    /// Python-equivalent dict[key] for in-place updates like `d[k] += 1`:
+    /// raises KeyError when missing.
+    pub fn get_item_mut<Q>(&mut self, key: &Q) -> PyResult<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + PyRepr + ?Sized,
+    {
+        match self.position(key) {
+            Some(i) => Ok(&mut self.entries[i]
+                .as_mut()
+                .expect("indexed entry is occupied")
+                .value),
+            None => Err(PyException::key_error(key)),
+        }
+    }
+
+    /// Python-equivalent dict[key] = value.
+    pub fn set_item(&mut self, key: K, value: V) {
+        let hash = hash_key(&key);
+        let entries = &self.entries;
+        let found = self
+            .table
+            .find(hash, |i| entries[i].as_ref().is_some_and(|e| e.key == key));
+        match found {
+            Some(i) => {
+                if let Some(e) = self.entries[i].as_mut() {
+                    e.value = value;
+                }
+            }
+            None => {
+                self.table.insert(hash, self.entries.len());
+                self.entries.push(Some(Entry { hash, key, value }));
+                self.len += 1;
+            }
+        }
+    }
+
+    /// Makes room for `additional` more keys.
+    pub fn reserve(&mut self, additional: usize) {
+        self.entries.reserve(additional);
+        self.table.reserve(additional);
+    }
+
+    /// Python-equivalent dict.update(other), where `other` is a dict or any
+    /// iterable of key/value pairs.
+    pub fn update<I: IntoIterator<Item = (K, V)>>(&mut self, pairs: I) {
+        for (key, value) in pairs {
+            self.set_item(key, value);
+        }
+    }
+
+    /// Python-equivalent dict.update(**kwargs).
+    pub fn update_kwargs(&mut self, kwargs: PyKwargs)
+    where
+        K: From<String>,
+        V: From<PyValue>,
+    {
+        let items: KwargsMap = kwargs.into();
+        self.update(
+            items
+                .into_iter()
+                .map(|(key, value)| (K::from(key), V::from(value))),
+        );
+    }
+
+    /// Python-equivalent dict(**kwargs).
+    pub fn from_kwargs(kwargs: PyKwargs) -> Self
+    where
+        K: From<String>,
+        V: From<PyValue>,
+    {
+        let mut dict = PyDictionary::new();
+        dict.update_kwargs(kwargs);
+        dict
+    }
+
+    /// Python-equivalent dict.clear().
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.table.clear();
+        self.len = 0;
+    }
+
+    /// Python-equivalent dict.pop(key): raises KeyError when missing.
+    pub fn pop(&mut self, key: &K) -> PyResult<V>
+    where
+        K: PyRepr,
+    {
+        self.remove(key).ok_or_else(|| PyException::key_error(key))
+    }
+
+    /// Removes `key`, returning its value if it was present.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let entries = &self.entries;
+        let i = self.table.remove(hash_key(key), |i| {
+            entries[i].as_ref().is_some_and(|e| e.key.borrow() == key)
+        })?;
+        let entry = self.entries[i].take()?;
+        self.len -= 1;
+        if self.entries.len() > 8 && self.len * 2 < self.entries.len() {
+            self.compact();
+        }
+        Some(entry.value)
+    }
+
+    /// Python-equivalent dict.popitem(): removes the most recently inserted pair.
+    pub fn popitem(&mut self) -> PyResult<(K, V)> {
+        while let Some(slot) = self.entries.pop() {
+            if let Some(entry) = slot {
+                let index = self.entries.len();
+                self.table.remove(entry.hash, |i| i == index);
+                self.len -= 1;
+                return Ok((entry.key, entry.value));
+            }
+        }
+        Err(PyException::key_error("popitem(): dictionary is empty"))
+    }
+
+    fn compact(&mut self) {
+        self.entries.retain(Option::is_some);
+        let hashes = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, e)| e.as_ref().map(|e| (e.hash, i)));
+        self.table.rebuild(hashes, self.len);
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> PyDictionary<K, V> {
+    /// Python-equivalent dict[key]: raises KeyError when missing.
+    pub fn get_item(&self, key: &K) -> PyResult<V>
+    where
+        K: PyRepr,
+    {
+        self.get(key)
+            .cloned()
+            .ok_or_else(|| PyException::key_error(key))
+    }
+
+    /// Python-equivalent dict.setdefault(key, default).
+    pub fn setdefault(&mut self, key: K, default: V) -> V {
+        if let Some(value) = self.get(&key) {
+            return value.clone();
+        }
+        self.set_item(key, default.clone());
+        default
+    }
+}
+
+/// Borrowing iterator over a dict's keys, in insertion order.
+pub struct Keys<'a, K, V> {
+    entries: core::slice::Iter<'a, Option<Entry<K, V>>>,
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<&'a K> {
+        self.entries
+            .by_ref()
+            .find_map(|e| e.as_ref().map(|e| &e.key))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Keys<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries
+            .by_ref()
+            .rev()
+            .find_map(|e| e.as_ref().map(|e| &e.key))
+    }
+}
+
+/// Borrowing iterator over a dict's `(key, value)` pairs, in insertion order.
+pub struct Iter<'a, K, V> {
+    entries: core::slice::Iter<'a, Option<Entry<K, V>>>,
+}
+
+impl<K, V> Clone for Iter<'_, K, V> {
+    fn clone(&self) -> Self {
+        Iter {
+            entries: self.entries.clone(),
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.entries
+            .by_ref()
+            .find_map(|e| e.as_ref().map(|e| (&e.key, &e.value)))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.entries
+            .by_ref()
+            .rev()
+            .find_map(|e| e.as_ref().map(|e| (&e.key, &e.value)))
+    }
+}
+
+/// Python-equivalent dict_keys over any mapping's `(key, value)` iterator:
+/// iterates lazily, in either direction, without copying the keys.
+#[derive(Clone)]
+pub struct KeysView<I>(pub(crate) I);
+
+/// Python-equivalent dict_values; see [`KeysView`].
+#[derive(Clone)]
+pub struct ValuesView<I>(pub(crate) I);
+
+/// Python-equivalent dict_items; see [`KeysView`].
+#[derive(Clone)]
+pub struct ItemsView<I>(pub(crate) I);
+
+impl<'a, K: 'a, V: 'a, I: Iterator<Item = (&'a K, &'a V)>> KeysView<I> {
+    /// Python-equivalent list(d.keys()).
+    pub fn to_list(self) -> Vec<K>
+    where
+        K: Clone,
+    {
+        self.cloned().collect()
+    }
+}
+
+impl<'a, K: 'a, V: 'a, I: Iterator<Item = (&'a K, &'a V)>> ValuesView<I> {
+    /// Python-equivalent list(d.values()).
+    pub fn to_list(self) -> Vec<V>
+    where
+        V: Clone,
+    {
+        self.cloned().collect()
+    }
+}
+
+impl<'a, K: 'a, V: 'a, I: Iterator<Item = (&'a K, &'a V)>> ItemsView<I> {
+    /// Python-equivalent list(d.items()).
+    pub fn to_list(self) -> Vec<(K, V)>
+    where
+        K: Clone,
+        V: Clone,
+    {
+        self.map(|(k, v)| (k.clone(), v.clone())).collect()
+    }
+}
+
+macro_rules! view_iterator {
+    ($view:ident, $item:ty, |$pair:ident| $project:expr) => {
+        impl<'a, K: 'a, V: 'a, I: Iterator<Item = (&'a K, &'a V)>> Iterator for $view<I> {
+            type Item = $item;
+
+            fn next(&mut self) -> Option<$item> {
+                self.0.next().map(|$pair| $project)
+            }
+
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                self.0.size_hint()
+            }
+        }
+
+        impl<'a, K: 'a, V: 'a, I> DoubleEndedIterator for $view<I>
+        where
+            I: DoubleEndedIterator<Item = (&'a K, &'a V)>,
+        {
+            fn next_back(&mut self) -> Option<$item> {
+                self.0.next_back().map(|$pair| $project)
+            }
+        }
+
+        impl<'a, K: 'a, V: 'a, I> ExactSizeIterator for $view<I> where
+            I: ExactSizeIterator<Item = (&'a K, &'a V)>
+        {
+        }
+    };
+}
+
+view_iterator!(KeysView, &'a K, |pair| pair.0);
+view_iterator!(ValuesView, &'a V, |pair| pair.1);
+view_iterator!(ItemsView, (&'a K, &'a V), |pair| pair);
+
+/// Owning iterator over a dict's `(key, value)` pairs, in insertion order.
+pub struct IntoIter<K, V> {
+    entries: alloc::vec::IntoIter<Option<Entry<K, V>>>,
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<(K, V)> {
+        self.entries
+            .by_ref()
+            .flatten()
+            .next()
+            .map(|e| (e.key, e.value))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<(K, V)> {
+        self.entries
+            .by_ref()
+            .rev()
+            .flatten()
+            .next()
+            .map(|e| (e.key, e.value))
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a PyDictionary<K, V> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K, V> IntoIterator for PyDictionary<K, V> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            entries: self.entries.into_iter(),
+        }
+    }
+}
+
+/// Dicts compare equal when they hold the same pairs, regardless of order.
+impl<K: Hash + Eq, V: PartialEq> PartialEq for PyDictionary<K, V> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: Hash + Eq, V: Eq> Eq for PyDictionary<K, V> {}
+
+/// `dict[key]`, raising KeyError as a panic; [`PyDictionary::get_item`] is
+/// the checked form. Assigning a new key goes through
+/// [`set_item`](PyDictionary::set_item), since IndexMut can only update.
+impl<K, V, Q> Index<&Q> for PyDictionary<K, V>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + PyRepr + ?Sized,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key)
+            .unwrap_or_else(|| PyException::key_error(key).raise())
+    }
+}
+
+impl<K, V, Q> IndexMut<&Q> for PyDictionary<K, V>
+where
+    K: Hash + Eq + Borrow<Q>,
+    Q: Hash + Eq + PyRepr + ?Sized,
+{
+    fn index_mut(&mut self, key: &Q) -> &mut V {
+        self.get_item_mut(key).unwrap_or_else(|err| err.raise())
+    }
+}
+
+impl<K: Hash + Eq, V> FromIterator<(K, V)> for PyDictionary<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut dict = PyDictionary::new();
+        dict.update(iter);
+        dict
+    }
+}
+
+impl<K: Hash + Eq, V> Extend<(K, V)> for PyDictionary<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+/// Python-equivalent `d | other`: a new dict where `other`'s values win.
+impl<K: Hash + Eq, V> BitOr for PyDictionary<K, V> {
+    type Output = PyDictionary<K, V>;
+
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        self.update(rhs);
+        self
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> BitOr for &PyDictionary<K, V> {
+    type Output = PyDictionary<K, V>;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.clone() | rhs.clone()
+    }
+}
+
+/// Python-equivalent `d |= other`, which like update() takes any iterable of
+/// pairs.
+impl<K: Hash + Eq, V, I: IntoIterator<Item = (K, V)>> BitOrAssign<I> for PyDictionary<K, V> {
+    fn bitor_assign(&mut self, rhs: I) {
+        self.update(rhs);
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for PyDictionary<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Python-equivalent str(dict), which shows each key and value's repr.
+impl<K: Hash + Eq + PyRepr, V: PyRepr> fmt::Display for PyDictionary<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.py_repr())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_operators() {
+        let mut counts: PyDictionary<String, i64> = PyDictionary::new();
+        counts.set_item("a".into(), 1);
+        counts["a"] += 1;
+        assert_eq!(counts["a"], 2);
+        assert_eq!(
+            counts.get_item_mut("b").unwrap_err().to_string(),
+            "KeyError: 'b'"
+        );
+        let missing = std::panic::catch_unwind(|| counts["b"]).unwrap_err();
+        assert_eq!(missing.downcast_ref::<String>().unwrap(), "KeyError: 'b'");
+    }
+
+    #[test]
+    fn preserves_insertion_order_across_removals() {
+        let mut d = PyDictionary::new();
+        for i in 0..20 {
+            d.set_item(i, i * 10);
+        }
+        for i in 0..15 {
+            d.remove(&i);
+        }
+        d.set_item(17, 0);
+        d.set_item(3, 30);
+        assert_eq!(d.keys().to_list(), vec![15, 16, 17, 18, 19, 3]);
+        assert_eq!(d.get(&17), Some(&0));
+        assert_eq!(d.popitem().unwrap(), (3, 30));
+    }
+
+    #[test]
+    fn views_borrow_and_reverse() {
+        let mut d: PyDictionary<&str, i64> = [("a", 1), ("b", 2), ("c", 3)].into_iter().collect();
+        d.remove("b");
+        assert_eq!(d.keys().rev().copied().collect::<Vec<_>>(), ["c", "a"]);
+        assert_eq!(d.values().sum::<i64>(), 4);
+        assert_eq!(d.items().next_back(), Some((&"c", &3)));
+        let keys = d.keys();
+        assert_eq!(keys.clone().count(), 2);
+        assert_eq!(keys.to_list(), ["a", "c"]);
+        assert_eq!(
+            crate::builtins::reversed(d.items()).collect::<Vec<_>>(),
+            [(&"c", &3), (&"a", &1)]
+        );
+    }
+
+    #[test]
+    fn equality_ignores_order() {
+        let a: PyDictionary<_, _> = [("a", 1), ("b", 2)].into_iter().collect();
+        let b: PyDictionary<_, _> = [("b", 2), ("a", 1)].into_iter().collect();
+        assert_eq!(a, b);
+        assert!(a.get_item(&"c").unwrap_err().is("KeyError"));
+    }
+
+    #[test]
+    fn update_from_pairs_kwargs_and_operators() {
+        let mut d: PyDictionary<String, PyValue> =
+            PyDictionary::from_kwargs(PyKwargs::new().with("a", 1).with("b", 2));
+        d.update([
+            ("c".to_string(), PyValue::from(3)),
+            ("a".to_string(), PyValue::from(0)),
+        ]);
+        d.update_kwargs(PyKwargs::new().with("d", "x"));
+        assert_eq!(d.keys().to_list(), ["a", "b", "c", "d"]);
+        assert_eq!(d["a"], PyValue::Int(0));
+
+        let left: PyDictionary<_, _> = [("a", 1), ("b", 2)].into_iter().collect();
+        let right: PyDictionary<_, _> = [("b", 3), ("c", 4)].into_iter().collect();
+        let merged = &left | &right;
+        assert_eq!(merged.items().to_list(), [("a", 1), ("b", 3), ("c", 4)]);
+        let mut d = left;
+        d |= [("z", 0), ("a", 9)];
+        assert_eq!(d.items().to_list(), [("a", 9), ("b", 2), ("z", 0)]);
+    }
+}
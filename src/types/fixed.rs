@@ -0,0 +1,540 @@
+//! Fixed-capacity str and list for builds that must not touch the heap.
+//!
+//! Storage lives inline, so growing past the capacity raises MemoryError
+//! instead of reallocating.
+
+use alloc::string::String;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+
+use super::hash::{hash_str, unhashable, PyHash};
+use super::list::normalize_index;
+use super::repr::{repr_str, PyRepr};
+use super::{PyTypeId, PyTyped};
+use crate::exceptions::{PyException, PyResult};
+
+fn capacity_exceeded(kind: &str, capacity: usize) -> PyException {
+    PyException::memory_error(alloc::format!("{} capacity of {} exceeded", kind, capacity))
+}
+
+/// Python-equivalent str holding at most `N` bytes of UTF-8.
+#[derive(Clone, Copy)]
+pub struct FixedStr<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> FixedStr<N> {
+    pub const fn new() -> Self {
+        FixedStr {
+            buf: [0; N],
+            len: 0,
+        }
+    }
+
+    /// Copies `s` in, raising MemoryError if it does not fit.
+    pub fn try_from_str(s: &str) -> PyResult<Self> {
+        let mut out = Self::new();
+        out.push_str(s)?;
+        Ok(out)
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Only whole `&str`s and encoded chars are ever copied in.
+        core::str::from_utf8(&self.buf[..self.len]).expect("FixedStr holds valid UTF-8")
+    }
+
+    /// Python-equivalent len(s): the number of code points.
+    pub fn len(&self) -> usize {
+        self.as_str().chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The capacity in bytes.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn push(&mut self, c: char) -> PyResult<()> {
+        self.push_str(c.encode_utf8(&mut [0; 4]))
+    }
+
+    pub fn push_str(&mut self, s: &str) -> PyResult<()> {
+        let end = self.len + s.len();
+        if end > N {
+            return Err(capacity_exceeded("str", N));
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Python-equivalent str.upper(); MemoryError if case mapping grows the
+    /// text past the capacity.
+    pub fn upper(&self) -> PyResult<Self> {
+        self.map_chars(char::to_uppercase)
+    }
+
+    /// Python-equivalent str.lower().
+    pub fn lower(&self) -> PyResult<Self> {
+        self.map_chars(char::to_lowercase)
+    }
+
+    fn map_chars<I, F>(&self, f: F) -> PyResult<Self>
+    where
+        I: Iterator<Item = char>,
+        F: Fn(char) -> I,
+    {
+        let mut out = Self::new();
+        for c in self.as_str().chars().flat_map(f) {
+            out.push(c)?;
+        }
+        Ok(out)
+    }
+
+    /// Python-equivalent str.strip().
+    pub fn strip(&self) -> Self {
+        let mut out = Self::new();
+        out.push_str(self.as_str().trim())
+            .expect("a substring always fits");
+        out
+    }
+
+    /// Python-equivalent str.split(sep), yielding borrowed pieces rather than
+    /// allocating a list.
+    pub fn split<'a>(
+        &'a self,
+        sep: Option<&'a str>,
+    ) -> PyResult<impl Iterator<Item = &'a str> + 'a> {
+        let s = self.as_str();
+        match sep {
+            None => Ok(Split::Whitespace(s.split_whitespace())),
+            Some("") => Err(PyException::value_error("empty separator")),
+            Some(sep) => Ok(Split::Sep(s.split(sep))),
+        }
+    }
+}
+
+enum Split<'a> {
+    Whitespace(core::str::SplitWhitespace<'a>),
+    Sep(core::str::Split<'a, &'a str>),
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        match self {
+            Split::Whitespace(it) => it.next(),
+            Split::Sep(it) => it.next(),
+        }
+    }
+}
+
+impl<const N: usize> Default for FixedStr<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Deref for FixedStr<N> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> AsRef<str> for FixedStr<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize> PartialEq for FixedStr<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl<const N: usize> Eq for FixedStr<N> {}
+
+impl<const N: usize> PartialEq<str> for FixedStr<N> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize> PartialEq<&str> for FixedStr<N> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl<const N: usize> core::hash::Hash for FixedStr<N> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+/// Lets `write!` and the string module format straight into the buffer.
+impl<const N: usize> fmt::Write for FixedStr<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl<const N: usize> PyRepr for FixedStr<N> {
+    fn py_repr(&self) -> String {
+        repr_str(self.as_str())
+    }
+}
+
+impl<const N: usize> PyHash for FixedStr<N> {
+    fn py_hash(&self) -> PyResult<i64> {
+        Ok(hash_str(self.as_str()))
+    }
+}
+
+impl<const N: usize> PyTyped for FixedStr<N> {
+    fn py_type(&self) -> PyTypeId {
+        PyTypeId::Str
+    }
+}
+
+/// Python-equivalent list holding at most `N` items inline.
+pub struct FixedList<T, const N: usize> {
+    items: [MaybeUninit<T>; N],
+    len: usize,
+}
+
+impl<T, const N: usize> FixedList<T, N> {
+    pub const fn new() -> Self {
+        FixedList {
+            items: [const { MaybeUninit::uninit() }; N],
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        // The first `len` slots are initialized.
+        unsafe { &*(&self.items[..self.len] as *const [MaybeUninit<T>] as *const [T]) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { &mut *(&mut self.items[..self.len] as *mut [MaybeUninit<T>] as *mut [T]) }
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.as_slice().iter()
+    }
+
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    /// Python-equivalent list[index].
+    pub fn get(&self, index: i64) -> PyResult<&T> {
+        normalize_index(index, self.len)
+            .map(|i| &self.as_slice()[i])
+            .ok_or_else(|| PyException::index_error("list index out of range"))
+    }
+
+    /// Python-equivalent list[index] = value.
+    pub fn set(&mut self, index: i64, value: T) -> PyResult<()> {
+        let i = normalize_index(index, self.len)
+            .ok_or_else(|| PyException::index_error("list assignment index out of range"))?;
+        self.as_mut_slice()[i] = value;
+        Ok(())
+    }
+
+    /// Python-equivalent list.append(x); MemoryError when full.
+    pub fn append(&mut self, value: T) -> PyResult<()> {
+        if self.len == N {
+            return Err(capacity_exceeded("list", N));
+        }
+        self.items[self.len].write(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Python-equivalent list.extend(iterable); items up to the capacity are
+    /// kept when it raises MemoryError.
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) -> PyResult<()> {
+        for value in iter {
+            self.append(value)?;
+        }
+        Ok(())
+    }
+
+    /// Python-equivalent list.insert(i, x): out-of-range indices clamp to the ends.
+    pub fn insert(&mut self, index: i64, value: T) -> PyResult<()> {
+        let len = self.len as i64;
+        let i = if index < 0 {
+            (index + len).max(0)
+        } else {
+            index.min(len)
+        } as usize;
+        self.append(value)?;
+        self.as_mut_slice()[i..].rotate_right(1);
+        Ok(())
+    }
+
+    /// Python-equivalent list.pop([i]).
+    pub fn pop(&mut self, index: Option<i64>) -> PyResult<T> {
+        if self.len == 0 {
+            return Err(PyException::index_error("pop from empty list"));
+        }
+        let i = normalize_index(index.unwrap_or(-1), self.len)
+            .ok_or_else(|| PyException::index_error("pop index out of range"))?;
+        self.as_mut_slice()[i..].rotate_left(1);
+        self.len -= 1;
+        // Slot `len` was initialized and is now outside the live range.
+        Ok(unsafe { self.items[self.len].assume_init_read() })
+    }
+
+    /// Python-equivalent list.clear().
+    pub fn clear(&mut self) {
+        let live = self.as_mut_slice() as *mut [T];
+        self.len = 0;
+        unsafe { core::ptr::drop_in_place(live) };
+    }
+
+    /// Python-equivalent list.reverse().
+    pub fn reverse(&mut self) {
+        self.as_mut_slice().reverse();
+    }
+
+    /// Python-equivalent list.sort(key=..., reverse=...); equal keys keep
+    /// their order.
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut key: F, reverse: bool) {
+        insertion_sort(self.as_mut_slice(), |a, b| {
+            let (a, b) = (key(a), key(b));
+            if reverse {
+                a < b
+            } else {
+                a > b
+            }
+        });
+    }
+}
+
+impl<T: PartialEq, const N: usize> FixedList<T, N> {
+    /// Python-equivalent x in list.
+    pub fn contains(&self, value: &T) -> bool {
+        self.as_slice().contains(value)
+    }
+
+    /// Python-equivalent list.index(x).
+    pub fn index(&self, value: &T) -> PyResult<usize> {
+        self.iter()
+            .position(|v| v == value)
+            .ok_or_else(|| PyException::value_error("list.index(x): x not in list"))
+    }
+
+    /// Python-equivalent list.count(x).
+    pub fn count(&self, value: &T) -> usize {
+        self.iter().filter(|v| *v == value).count()
+    }
+
+    /// Python-equivalent list.remove(x): removes the first occurrence.
+    pub fn remove(&mut self, value: &T) -> PyResult<()> {
+        let i = self
+            .iter()
+            .position(|v| v == value)
+            .ok_or_else(|| PyException::value_error("list.remove(x): x not in list"))?;
+        self.pop(Some(i as i64)).map(drop)
+    }
+}
+
+impl<T: Ord, const N: usize> FixedList<T, N> {
+    /// Python-equivalent list.sort(reverse=...).
+    pub fn sort(&mut self, reverse: bool) {
+        insertion_sort(
+            self.as_mut_slice(),
+            |a, b| if reverse { a < b } else { a > b },
+        );
+    }
+}
+
+/// A stable in-place sort that never allocates, fine for the small
+/// capacities these lists are meant for.
+fn insertion_sort<T, F: FnMut(&T, &T) -> bool>(items: &mut [T], mut out_of_order: F) {
+    for i in 1..items.len() {
+        let mut j = i;
+        while j > 0 && out_of_order(&items[j - 1], &items[j]) {
+            items.swap(j - 1, j);
+            j -= 1;
+        }
+    }
+}
+
+impl<T, const N: usize> Drop for FixedList<T, N> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+impl<T, const N: usize> Default for FixedList<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for FixedList<T, N> {
+    fn clone(&self) -> Self {
+        let mut out = Self::new();
+        for value in self.iter() {
+            out.append(value.clone()).expect("same capacity");
+        }
+        out
+    }
+}
+
+impl<T: PartialEq, const N: usize> PartialEq for FixedList<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for FixedList<T, N> {}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for FixedList<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PyRepr, const N: usize> PyRepr for FixedList<T, N> {
+    fn py_repr(&self) -> String {
+        super::repr::repr_items("[", self.iter(), "]")
+    }
+}
+
+/// Python-equivalent str(list), which shows each element's repr.
+impl<T: PyRepr, const N: usize> fmt::Display for FixedList<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.py_repr())
+    }
+}
+
+impl<T, const N: usize> PyHash for FixedList<T, N> {
+    fn py_hash(&self) -> PyResult<i64> {
+        Err(unhashable("list"))
+    }
+}
+
+impl<T, const N: usize> PyTyped for FixedList<T, N> {
+    fn py_type(&self) -> PyTypeId {
+        PyTypeId::List
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a FixedList<T, N> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use core::fmt::Write;
+
+    #[test]
+    fn fixed_str_raises_memory_error_when_full() {
+        let mut s = FixedStr::<8>::try_from_str("héllo").unwrap();
+        assert_eq!(s.len(), 5);
+        assert!(s.push_str("!!").is_ok());
+        let err = s.push('x').unwrap_err();
+        assert!(err.is("MemoryError"));
+        assert_eq!(s, "héllo!!");
+        assert!(write!(s, "{}", 1).is_err());
+        assert_eq!(
+            FixedStr::<4>::try_from_str("ß").unwrap().upper().unwrap(),
+            "SS"
+        );
+        assert_eq!(s.py_repr(), "'héllo!!'");
+        assert_eq!(s.py_hash(), "héllo!!".py_hash());
+    }
+
+    #[test]
+    fn fixed_str_split_borrows() {
+        let s = FixedStr::<16>::try_from_str(" a b  c ").unwrap();
+        let parts: FixedList<&str, 4> = {
+            let mut l = FixedList::new();
+            l.extend(s.split(None).unwrap()).unwrap();
+            l
+        };
+        assert_eq!(parts.as_slice(), ["a", "b", "c"]);
+        assert!(s.split(Some("")).is_err());
+    }
+
+    #[test]
+    fn fixed_list_behaves_like_list() {
+        let mut l: FixedList<i32, 4> = FixedList::new();
+        l.extend([3, 1, 2]).unwrap();
+        l.insert(0, 5).unwrap();
+        assert!(l.append(9).unwrap_err().is("MemoryError"));
+        assert_eq!(l.as_slice(), [5, 3, 1, 2]);
+        l.sort(false);
+        assert_eq!(l.to_string(), "[1, 2, 3, 5]");
+        l.sort_by_key(|v| *v % 2, true);
+        assert_eq!(l.as_slice(), [1, 3, 5, 2]);
+        assert_eq!(l.pop(Some(0)).unwrap(), 1);
+        l.remove(&5).unwrap();
+        assert_eq!(l.as_slice(), [3, 2]);
+        assert!(l.get(-3).is_err());
+        assert!(l.py_hash().is_err());
+    }
+
+    #[test]
+    fn fixed_list_drops_its_items() {
+        use alloc::rc::Rc;
+        let shared = Rc::new(());
+        {
+            let mut l: FixedList<Rc<()>, 3> = FixedList::new();
+            l.append(shared.clone()).unwrap();
+            l.append(shared.clone()).unwrap();
+            drop(l.pop(None).unwrap());
+            assert_eq!(Rc::strong_count(&shared), 2);
+        }
+        assert_eq!(Rc::strong_count(&shared), 1);
+    }
+}
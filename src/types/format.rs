@@ -0,0 +1,755 @@
+//! format() and the format-spec mini-language: what `__format__` sees for
+//! `f"{value:spec}"`.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt::Display;
+use core::hash::Hash;
+
+use super::repr::{ascii_escape, repr_float};
+use super::{
+    PyBytes, PyDictionary, PyList, PyRange, PyRepr, PySet, PyStr, PyTuple, PyTypeId, PyValue,
+};
+use crate::exceptions::{PyException, PyResult};
+
+/// Python-equivalent `__format__`. The provided method is
+/// `object.__format__`: `str(self)` for an empty spec, TypeError otherwise,
+/// so a user type opts in with `impl PyFormat for T {}`.
+pub trait PyFormat: Display {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_object(self, spec, short_type_name::<Self>())
+    }
+}
+
+/// `object.__format__` for a value whose type is called `type_name`.
+pub fn format_object<T: Display + ?Sized>(
+    value: &T,
+    spec: &str,
+    type_name: &str,
+) -> PyResult<String> {
+    if spec.is_empty() {
+        Ok(value.to_string())
+    } else {
+        Err(PyException::type_error(format!(
+            "unsupported format string passed to {}.__format__",
+            type_name
+        )))
+    }
+}
+
+/// `Name` from a Rust type path such as `my_crate::shapes::Name<T>`.
+fn short_type_name<T: ?Sized>() -> &'static str {
+    let name = core::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// An f-string or str.format conversion flag: `!s`, `!r` or `!a`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    Str,
+    Repr,
+    Ascii,
+}
+
+impl Conversion {
+    /// The flag after `!`, raising CPython's ValueError for any other.
+    pub fn from_char(c: char) -> PyResult<Conversion> {
+        match c {
+            's' => Ok(Conversion::Str),
+            'r' => Ok(Conversion::Repr),
+            'a' => Ok(Conversion::Ascii),
+            _ => Err(PyException::value_error(format!(
+                "Unknown conversion specifier {}",
+                c
+            ))),
+        }
+    }
+
+    /// str(), repr() or ascii() of `value`.
+    pub fn apply<T: PyFormat + PyRepr + ?Sized>(self, value: &T) -> String {
+        match self {
+            Conversion::Str => value.py_format("").unwrap_or_else(|_| value.to_string()),
+            Conversion::Repr => value.py_repr(),
+            Conversion::Ascii => ascii_escape(&value.py_repr()),
+        }
+    }
+}
+
+/// One replacement field, `{value!conversion:spec}`: the conversion runs
+/// first and the spec then formats its str result.
+pub fn format_field<T: PyFormat + PyRepr + ?Sized>(
+    value: &T,
+    conversion: Option<Conversion>,
+    spec: &str,
+) -> PyResult<String> {
+    match conversion {
+        None => value.py_format(spec),
+        Some(conversion) => format_str(&conversion.apply(value), spec),
+    }
+}
+
+/// A self-documenting field, `f"{x=}"`, which lowers to
+/// `py_fstring_debug("x=", &x)`. `expr_text` is the field's source up to
+/// and including the `=` and any spaces after it, which Python keeps.
+pub fn py_fstring_debug<T: PyFormat + PyRepr + ?Sized>(expr_text: &str, value: &T) -> String {
+    format!("{}{}", expr_text, value.py_repr())
+}
+
+/// `f"{x=!s:>8}"` and the like. Without a conversion the value is shown by
+/// repr(), unless a spec is given, in which case format() applies.
+pub fn py_fstring_debug_with<T: PyFormat + PyRepr + ?Sized>(
+    expr_text: &str,
+    value: &T,
+    conversion: Option<Conversion>,
+    spec: &str,
+) -> PyResult<String> {
+    let shown = match conversion {
+        None if spec.is_empty() => value.py_repr(),
+        conversion => format_field(value, conversion, spec)?,
+    };
+    Ok(format!("{}{}", expr_text, shown))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Right,
+    Center,
+    /// `=`: padding goes between the sign and the digits.
+    AfterSign,
+}
+
+/// A parsed `[[fill]align][sign]["z"]["#"]["0"][width][grouping]["." precision][type]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormatSpec {
+    pub fill: char,
+    align: Option<Align>,
+    pub sign: Option<char>,
+    /// `z`: show negative zero as zero.
+    pub coerce_zero: bool,
+    /// `#`: the alternate form.
+    pub alternate: bool,
+    pub width: usize,
+    /// `,` or `_`.
+    pub grouping: Option<char>,
+    pub precision: Option<usize>,
+    pub kind: Option<char>,
+}
+
+impl FormatSpec {
+    /// Parses `spec` for a value of type `type_name`, which only appears in
+    /// error messages.
+    pub fn parse(spec: &str, type_name: &str) -> PyResult<FormatSpec> {
+        let invalid = || {
+            PyException::value_error(format!(
+                "Invalid format specifier '{}' for object of type '{}'",
+                spec, type_name
+            ))
+        };
+        let align_of = |c: char| match c {
+            '<' => Some(Align::Left),
+            '>' => Some(Align::Right),
+            '^' => Some(Align::Center),
+            '=' => Some(Align::AfterSign),
+            _ => None,
+        };
+        let mut out = FormatSpec {
+            fill: ' ',
+            align: None,
+            sign: None,
+            coerce_zero: false,
+            alternate: false,
+            width: 0,
+            grouping: None,
+            precision: None,
+            kind: None,
+        };
+        let mut rest = spec;
+        let mut chars = rest.chars();
+        let first = chars.next();
+        let second = chars.next();
+        if let (Some(fill), Some(align)) = (first, second.and_then(align_of)) {
+            out.fill = fill;
+            out.align = Some(align);
+            rest = &rest[fill.len_utf8() + 1..];
+        } else if let Some(align) = first.and_then(align_of) {
+            out.align = Some(align);
+            rest = &rest[1..];
+        }
+        if let Some(sign @ ('+' | '-' | ' ')) = rest.chars().next() {
+            out.sign = Some(sign);
+            rest = &rest[1..];
+        }
+        if let Some(r) = rest.strip_prefix('z') {
+            out.coerce_zero = true;
+            rest = r;
+        }
+        if let Some(r) = rest.strip_prefix('#') {
+            out.alternate = true;
+            rest = r;
+        }
+        if let Some(r) = rest.strip_prefix('0') {
+            if out.align.is_none() {
+                out.fill = '0';
+                out.align = Some(Align::AfterSign);
+            }
+            rest = r;
+        }
+        let (width, r) = split_number(rest);
+        out.width = width.unwrap_or(0);
+        rest = r;
+        if let Some(sep @ (',' | '_')) = rest.chars().next() {
+            out.grouping = Some(sep);
+            rest = &rest[1..];
+        }
+        if let Some(r) = rest.strip_prefix('.') {
+            let (precision, r) = split_number(r);
+            out.precision =
+                Some(precision.ok_or_else(|| {
+                    PyException::value_error("Format specifier missing precision")
+                })?);
+            rest = r;
+        }
+        let mut kind = rest.chars();
+        out.kind = kind.next();
+        if kind.next().is_some() {
+            return Err(invalid());
+        }
+        Ok(out)
+    }
+
+    fn unknown_kind(&self, type_name: &str) -> PyException {
+        PyException::value_error(format!(
+            "Unknown format code '{}' for object of type '{}'",
+            self.kind.unwrap_or('s'),
+            type_name
+        ))
+    }
+
+    /// Pads `body` to the width, aligning it as the spec says or by `default`.
+    fn pad(&self, body: &str, default: Align) -> String {
+        let len = body.chars().count();
+        if len >= self.width {
+            return body.to_string();
+        }
+        let total = self.width - len;
+        let (before, after) = match self.align.unwrap_or(default) {
+            Align::Left => (0, total),
+            Align::Center => (total / 2, total - total / 2),
+            Align::Right | Align::AfterSign => (total, 0),
+        };
+        let fill = |n: usize| core::iter::repeat_n(self.fill, n);
+        fill(before)
+            .chain(body.chars())
+            .chain(fill(after))
+            .collect()
+    }
+
+    /// Lays out a number: sign, then `prefix` (e.g. `0x`), then `int_part`
+    /// grouped every `interval` digits, then `rest` (fraction, exponent, %).
+    fn number(
+        &self,
+        negative: bool,
+        prefix: &str,
+        int_part: &str,
+        rest: &str,
+        interval: usize,
+    ) -> String {
+        let sign = if negative {
+            "-"
+        } else {
+            match self.sign {
+                Some('+') => "+",
+                Some(' ') => " ",
+                _ => "",
+            }
+        };
+        let head_len = sign.len() + prefix.len();
+        let mut digits = String::from(int_part);
+        let mut body = self.group(&digits, interval);
+        if self.align == Some(Align::AfterSign) {
+            if self.fill == '0' && self.grouping.is_some() {
+                // Zero padding is grouped along with the digits.
+                while head_len + body.chars().count() + rest.chars().count() < self.width {
+                    digits.insert(0, '0');
+                    body = self.group(&digits, interval);
+                }
+            } else {
+                let len = head_len + body.chars().count() + rest.chars().count();
+                let padding: String =
+                    core::iter::repeat_n(self.fill, self.width.saturating_sub(len)).collect();
+                body.insert_str(0, &padding);
+            }
+        }
+        self.pad(&format!("{}{}{}{}", sign, prefix, body, rest), Align::Right)
+    }
+
+    fn group(&self, digits: &str, interval: usize) -> String {
+        let Some(sep) = self.grouping else {
+            return digits.to_string();
+        };
+        let mut out = String::with_capacity(digits.len() + digits.len() / interval);
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(interval) {
+                out.push(sep);
+            }
+            out.push(c);
+        }
+        out
+    }
+}
+
+/// The decimal number at the start of `s`, and what follows it.
+fn split_number(s: &str) -> (Option<usize>, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    (s[..end].parse().ok(), &s[end..])
+}
+
+/// `str.__format__`: only the `s` type, with precision truncating.
+pub fn format_str(s: &str, spec: &str) -> PyResult<String> {
+    if spec.is_empty() {
+        return Ok(s.to_string());
+    }
+    let mut spec = FormatSpec::parse(spec, "str")?;
+    if !matches!(spec.kind, None | Some('s')) {
+        return Err(spec.unknown_kind("str"));
+    }
+    if spec.sign.is_some() {
+        return Err(PyException::value_error(
+            "Sign not allowed in string format specifier",
+        ));
+    }
+    if spec.alternate {
+        return Err(PyException::value_error(
+            "Alternate form (#) not allowed in string format specifier",
+        ));
+    }
+    if spec.grouping.is_some() {
+        return Err(PyException::value_error(format!(
+            "Cannot specify '{}' with 's'.",
+            spec.grouping.unwrap_or(',')
+        )));
+    }
+    if spec.align == Some(Align::AfterSign) {
+        if spec.fill == '0' {
+            // A bare `0` flag pads strings on the right.
+            spec.align = Some(Align::Left);
+        } else {
+            return Err(PyException::value_error(
+                "'=' alignment not allowed in string format specifier",
+            ));
+        }
+    }
+    let body = match spec.precision {
+        Some(precision) => match s.char_indices().nth(precision) {
+            Some((end, _)) => &s[..end],
+            None => s,
+        },
+        None => s,
+    };
+    Ok(spec.pad(body, Align::Left))
+}
+
+/// `int.__format__` for the integer `-magnitude` or `magnitude`.
+pub fn format_int(negative: bool, magnitude: u128, spec: &str) -> PyResult<String> {
+    if spec.is_empty() {
+        let sign = if negative { "-" } else { "" };
+        return Ok(format!("{}{}", sign, magnitude));
+    }
+    let spec = FormatSpec::parse(spec, "int")?;
+    let kind = spec.kind.unwrap_or('d');
+    if matches!(kind, 'e' | 'E' | 'f' | 'F' | 'g' | 'G' | '%') {
+        let value = magnitude as f64;
+        return format_float_spec(if negative { -value } else { value }, &spec);
+    }
+    if spec.precision.is_some() {
+        return Err(PyException::value_error(
+            "Precision not allowed in integer format specifier",
+        ));
+    }
+    let (prefix, digits, interval) = match kind {
+        'd' | 'n' => ("", format!("{}", magnitude), 3),
+        'b' => ("0b", format!("{:b}", magnitude), 4),
+        'o' => ("0o", format!("{:o}", magnitude), 4),
+        'x' => ("0x", format!("{:x}", magnitude), 4),
+        'X' => ("0X", format!("{:X}", magnitude), 4),
+        'c' => {
+            if spec.sign.is_some() {
+                return Err(PyException::value_error(
+                    "Sign not allowed with integer format specifier 'c'",
+                ));
+            }
+            let c = u32::try_from(magnitude)
+                .ok()
+                .filter(|_| !negative)
+                .and_then(char::from_u32)
+                .ok_or_else(|| {
+                    PyException::new("OverflowError", "%c arg not in range(0x110000)")
+                })?;
+            return Ok(spec.pad(c.encode_utf8(&mut [0; 4]), Align::Right));
+        }
+        _ => return Err(spec.unknown_kind("int")),
+    };
+    if spec.grouping == Some(',') && kind != 'd' {
+        return Err(PyException::value_error(format!(
+            "Cannot specify ',' with '{}'.",
+            kind
+        )));
+    }
+    let prefix = if spec.alternate { prefix } else { "" };
+    Ok(spec.number(negative, prefix, &digits, "", interval))
+}
+
+/// `float.__format__`.
+pub fn format_float(value: f64, spec: &str) -> PyResult<String> {
+    if spec.is_empty() {
+        return Ok(repr_float(value));
+    }
+    format_float_spec(value, &FormatSpec::parse(spec, "float")?)
+}
+
+fn format_float_spec(value: f64, spec: &FormatSpec) -> PyResult<String> {
+    let kind = spec.kind.unwrap_or('r');
+    let upper = matches!(kind, 'E' | 'F' | 'G');
+    let alternate = spec.alternate;
+    let magnitude = value.abs();
+    let body = if !value.is_finite() {
+        let word = if value.is_nan() { "nan" } else { "inf" };
+        let word = if upper {
+            word.to_ascii_uppercase()
+        } else {
+            word.to_string()
+        };
+        if kind == '%' {
+            word + "%"
+        } else {
+            word
+        }
+    } else {
+        match kind {
+            'f' | 'F' => fixed(magnitude, spec.precision.unwrap_or(6), alternate),
+            'e' | 'E' => scientific(magnitude, spec.precision.unwrap_or(6), alternate, upper),
+            'g' | 'G' | 'n' => general(
+                magnitude,
+                spec.precision.unwrap_or(6),
+                alternate,
+                upper,
+                false,
+            ),
+            '%' => fixed(magnitude * 100.0, spec.precision.unwrap_or(6), alternate) + "%",
+            'r' => match spec.precision {
+                Some(precision) => general(magnitude, precision, alternate, false, true),
+                None => repr_float(magnitude),
+            },
+            _ => return Err(spec.unknown_kind("float")),
+        }
+    };
+    let mut negative = value.is_sign_negative() && !value.is_nan();
+    if spec.coerce_zero && body.chars().all(|c| matches!(c, '0' | '.' | '%')) {
+        negative = false;
+    }
+    let split = body
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(body.len());
+    let (int_part, rest) = body.split_at(split);
+    Ok(spec.number(negative, "", int_part, rest, 3))
+}
+
+fn fixed(value: f64, precision: usize, alternate: bool) -> String {
+    let mut out = format!("{:.*}", precision, value);
+    if alternate && precision == 0 {
+        out.push('.');
+    }
+    out
+}
+
+/// `d.ddde+XX`, with at least two exponent digits as in C.
+fn scientific(value: f64, precision: usize, alternate: bool, upper: bool) -> String {
+    let sci = format!("{:.*e}", precision, value);
+    let (mantissa, exp) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let exp: i32 = exp.parse().unwrap_or(0);
+    let point = if alternate && precision == 0 { "." } else { "" };
+    let e = if upper { 'E' } else { 'e' };
+    let exp_sign = if exp < 0 { '-' } else { '+' };
+    format!("{}{}{}{}{:02}", mantissa, point, e, exp_sign, exp.abs())
+}
+
+/// `%g`: fixed or scientific by the decimal exponent, trailing zeros dropped
+/// unless `alternate`. With `add_dot_0` (a precision but no type), a fixed
+/// result keeps a `.0` and scientific takes over one exponent sooner, as
+/// CPython's `Py_DTSF_ADD_DOT_0` does.
+fn general(value: f64, precision: usize, alternate: bool, upper: bool, add_dot_0: bool) -> String {
+    let precision = precision.max(1);
+    let exp = if value == 0.0 {
+        0
+    } else {
+        let sci = format!("{:.*e}", precision - 1, value);
+        sci.split_once('e')
+            .and_then(|(_, exp)| exp.parse::<i64>().ok())
+            .unwrap_or(0)
+    };
+    let fixed_below = precision as i64 - i64::from(add_dot_0);
+    let mut out = if (-4..fixed_below).contains(&exp) {
+        fixed(value, (precision as i64 - 1 - exp) as usize, alternate)
+    } else {
+        scientific(value, precision - 1, alternate, upper)
+    };
+    if !alternate {
+        let end = out.find(['e', 'E']).unwrap_or(out.len());
+        let (mantissa, exponent) = out.split_at(end);
+        if mantissa.contains('.') {
+            let mantissa = mantissa.trim_end_matches('0').trim_end_matches('.');
+            out = format!("{}{}", mantissa, exponent);
+        }
+    }
+    if add_dot_0 && !out.contains(['.', 'e', 'E']) {
+        out.push_str(".0");
+    }
+    out
+}
+
+macro_rules! impl_format_signed {
+    ($($t:ty),*) => {
+        $(impl PyFormat for $t {
+            fn py_format(&self, spec: &str) -> PyResult<String> {
+                format_int(*self < 0, self.unsigned_abs() as u128, spec)
+            }
+        })*
+    };
+}
+
+macro_rules! impl_format_unsigned {
+    ($($t:ty),*) => {
+        $(impl PyFormat for $t {
+            fn py_format(&self, spec: &str) -> PyResult<String> {
+                format_int(false, *self as u128, spec)
+            }
+        })*
+    };
+}
+
+impl_format_signed!(i8, i16, i32, i64, i128, isize);
+impl_format_unsigned!(u8, u16, u32, u64, u128, usize);
+
+impl PyFormat for f64 {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_float(*self, spec)
+    }
+}
+
+impl PyFormat for f32 {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_float(*self as f64, spec)
+    }
+}
+
+/// `bool` formats as "True"/"False" only with an empty spec; any other spec
+/// formats it as the int it is.
+impl PyFormat for bool {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        if spec.is_empty() {
+            Ok(String::from(if *self { "True" } else { "False" }))
+        } else {
+            format_int(false, *self as u128, spec)
+        }
+    }
+}
+
+impl PyFormat for str {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_str(self, spec)
+    }
+}
+
+impl PyFormat for String {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_str(self, spec)
+    }
+}
+
+impl PyFormat for PyStr {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_str(self.as_str(), spec)
+    }
+}
+
+impl PyFormat for char {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_str(self.encode_utf8(&mut [0; 4]), spec)
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> PyFormat for super::FixedStr<N> {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_str(self.as_str(), spec)
+    }
+}
+
+impl<T: PyFormat + ?Sized> PyFormat for &T {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        (**self).py_format(spec)
+    }
+}
+
+macro_rules! impl_format_object {
+    ($($t:ty => $name:literal),*) => {
+        $(impl PyFormat for $t {
+            fn py_format(&self, spec: &str) -> PyResult<String> {
+                format_object(self, spec, $name)
+            }
+        })*
+    };
+}
+
+impl_format_object!(PyBytes => "bytes", PyRange => "range", PyTypeId => "type");
+
+impl<T: PyRepr> PyFormat for PyList<T> {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_object(self, spec, "list")
+    }
+}
+
+impl<T: PyRepr> PyFormat for PyTuple<T> {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_object(self, spec, "tuple")
+    }
+}
+
+impl<K: Hash + Eq + PyRepr, V: PyRepr> PyFormat for PyDictionary<K, V> {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_object(self, spec, "dict")
+    }
+}
+
+impl<T: Hash + Eq + PyRepr> PyFormat for PySet<T> {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        format_object(self, spec, "set")
+    }
+}
+
+impl PyFormat for PyValue {
+    fn py_format(&self, spec: &str) -> PyResult<String> {
+        match self {
+            PyValue::Bool(b) => b.py_format(spec),
+            PyValue::Int(i) => i.py_format(spec),
+            PyValue::Float(f) => f.py_format(spec),
+            PyValue::Str(s) => s.py_format(spec),
+            other => format_object(other, spec, other.type_name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f<T: PyFormat + ?Sized>(value: &T, spec: &str) -> String {
+        value.py_format(spec).unwrap()
+    }
+
+    #[test]
+    fn integers_match_cpython() {
+        assert_eq!(f(&42, ""), "42");
+        assert_eq!(f(&42, ">6"), "    42");
+        assert_eq!(f(&-42, "06"), "-00042");
+        assert_eq!(f(&-42, "*^+9"), "***-42***");
+        assert_eq!(f(&1234567, ","), "1,234,567");
+        assert_eq!(f(&1234, "010,"), "00,001,234");
+        assert_eq!(f(&255, "#x"), "0xff");
+        assert_eq!(f(&255, "#010b"), "0b11111111");
+        assert_eq!(f(&0xdeadbeefu32, "_X"), "DEAD_BEEF");
+        assert_eq!(f(&65, "c"), "A");
+        assert_eq!(f(&3, ".2f"), "3.00");
+        assert_eq!(f(&true, ""), "True");
+        assert_eq!(f(&true, ">3"), "  1");
+        assert_eq!(f(&i64::MIN, "x"), "-8000000000000000");
+        assert!(42.py_format(".2").unwrap_err().is("ValueError"));
+        assert!(42.py_format(",x").unwrap_err().is("ValueError"));
+        assert!(42.py_format("s").unwrap_err().is("ValueError"));
+    }
+
+    #[test]
+    fn floats_match_cpython() {
+        assert_eq!(f(&1.23456, ".3f"), "1.235");
+        assert_eq!(f(&2.5, ".0f"), "2");
+        assert_eq!(f(&1234.5, ",.2f"), "1,234.50");
+        assert_eq!(f(&0.000123456, "e"), "1.234560e-04");
+        assert_eq!(f(&12345.678, ".3E"), "1.235E+04");
+        assert_eq!(f(&0.0001, "g"), "0.0001");
+        assert_eq!(f(&0.00001, "g"), "1e-05");
+        assert_eq!(f(&123456789.0, "g"), "1.23457e+08");
+        assert_eq!(f(&100.0, "#g"), "100.000");
+        assert_eq!(f(&0.25, ".1%"), "25.0%");
+        assert_eq!(f(&1.0, ".3"), "1.0");
+        assert_eq!(f(&1234.5, ".2"), "1.2e+03");
+        assert_eq!(f(&100.0, ".3"), "1e+02");
+        assert_eq!(f(&12.0, ".2"), "1.2e+01");
+        assert_eq!(f(&1.0, ".1"), "1e+00");
+        assert_eq!(f(&1.5, ".2"), "1.5");
+        assert_eq!(f(&100.0, "#.3"), "1.00e+02");
+        assert_eq!(f(&99.0, ".3"), "99.0");
+        assert_eq!(f(&0.0, ".1"), "0e+00");
+        assert_eq!(f(&0.1, ""), "0.1");
+        assert_eq!(f(&-1.5, "=+8.2f"), "-   1.50");
+        assert_eq!(f(&-0.0, "z.1f"), "0.0");
+        assert_eq!(f(&-0.0, ".1f"), "-0.0");
+        assert_eq!(f(&f64::INFINITY, "F"), "INF");
+        assert_eq!(f(&f64::NAN, "+"), "+nan");
+        assert!(1.5.py_format("d").unwrap_err().is("ValueError"));
+    }
+
+    #[test]
+    fn strings_and_objects() {
+        assert_eq!(f("abc", "<5"), "abc  ");
+        assert_eq!(f("abc", "^7"), "  abc  ");
+        assert_eq!(f("abcdef", ".2"), "ab");
+        assert_eq!(f("ab", "05"), "ab000");
+        assert_eq!(f(&PyStr::new("é"), "*>3"), "**é");
+        assert!("x".py_format("+").unwrap_err().is("ValueError"));
+        assert!("x".py_format("=5").unwrap_err().is("ValueError"));
+        let err = "x".py_format("5.").unwrap_err();
+        assert_eq!(err.message, "Format specifier missing precision");
+
+        let list = PyList::from_vec(alloc::vec![1, 2]);
+        assert_eq!(f(&list, ""), "[1, 2]");
+        let err = list.py_format(">10").unwrap_err();
+        assert!(err.is("TypeError"));
+        assert_eq!(
+            err.message,
+            "unsupported format string passed to list.__format__"
+        );
+        assert_eq!(f(&PyValue::Float(0.5), ".0%"), "50%");
+        assert!(PyValue::None.py_format("x").unwrap_err().is("TypeError"));
+    }
+
+    #[test]
+    fn conversions_and_debug_fields() {
+        let name = PyStr::from("é\n");
+        assert_eq!(
+            format_field(&name, Some(Conversion::Repr), ">8").unwrap(),
+            "   'é\\n'"
+        );
+        assert_eq!(
+            format_field(&name, Some(Conversion::Ascii), "").unwrap(),
+            "'\\xe9\\n'"
+        );
+        assert_eq!(format_field(&1.5, None, "06.2f").unwrap(), "001.50");
+        assert!(Conversion::from_char('x').unwrap_err().is("ValueError"));
+
+        let x = 0.1 + 0.2;
+        assert_eq!(py_fstring_debug("x=", &x), "x=0.30000000000000004");
+        assert_eq!(py_fstring_debug("name = ", &name), "name = 'é\\n'");
+        let debug = |conversion, spec| py_fstring_debug_with("x=", &x, conversion, spec);
+        assert_eq!(debug(None, ".2f").unwrap(), "x=0.30");
+        assert_eq!(
+            debug(Some(Conversion::Str), ">5").unwrap(),
+            "x=0.30000000000000004"
+        );
+        assert_eq!(
+            debug(Some(Conversion::Str), "").unwrap(),
+            "x=0.30000000000000004"
+        );
+    }
+}
@@ -0,0 +1,813 @@
+//! First-class functions: anything callable with positional and keyword
+//! arguments, including bound methods.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::{FromKwarg, PyDictionary, PyKwargs, PyTypeId, PyTyped, PyValue};
+use crate::exceptions::{PyException, PyResult};
+use crate::sync::Lock;
+
+/// Keyword arguments as passed to a call.
+pub type KwargsMap = PyDictionary<String, PyValue>;
+
+/// Python-equivalent `__call__`.
+pub trait PyCallable {
+    fn call(&self, args: &[PyValue], kwargs: &KwargsMap) -> PyResult<PyValue>;
+
+    /// Calls with positional arguments only.
+    fn call_positional(&self, args: &[PyValue]) -> PyResult<PyValue> {
+        self.call(args, &KwargsMap::new())
+    }
+}
+
+impl<F> PyCallable for F
+where
+    F: Fn(&[PyValue], &KwargsMap) -> PyResult<PyValue>,
+{
+    fn call(&self, args: &[PyValue], kwargs: &KwargsMap) -> PyResult<PyValue> {
+        self(args, kwargs)
+    }
+}
+
+/// A named, reference-counted callable, optionally bound to a receiver.
+///
+/// Cloning shares the underlying function, so equality and hashing follow
+/// object identity as they do for Python functions.
+#[derive(Clone)]
+pub struct PyFunction {
+    name: Rc<str>,
+    func: Rc<dyn PyCallable>,
+    receiver: Option<Box<PyValue>>,
+}
+
+fn arity_error(name: &str, expected: usize, given: usize) -> PyException {
+    let plural = if expected == 1 { "" } else { "s" };
+    PyException::type_error(format!(
+        "{}() takes {} positional argument{} but {} were given",
+        name, expected, plural, given
+    ))
+}
+
+pub(crate) fn reject_kwargs(name: &str, kwargs: &KwargsMap) -> PyResult<()> {
+    match kwargs.iter().next() {
+        Some((key, _)) => Err(PyException::type_error(format!(
+            "{}() got an unexpected keyword argument '{}'",
+            name, key
+        ))),
+        None => Ok(()),
+    }
+}
+
+impl PyFunction {
+    /// Wraps any [`PyCallable`] under `name` (`__name__`).
+    pub fn new<C: PyCallable + 'static>(name: &str, func: C) -> Self {
+        PyFunction {
+            name: Rc::from(name),
+            func: Rc::new(func),
+            receiver: None,
+        }
+    }
+
+    /// A `lambda:` taking no arguments.
+    pub fn nullary<F>(name: &str, f: F) -> Self
+    where
+        F: Fn() -> PyResult<PyValue> + 'static,
+    {
+        let fname = name.to_string();
+        Self::new(name, move |args: &[PyValue], kwargs: &KwargsMap| {
+            reject_kwargs(&fname, kwargs)?;
+            match args {
+                [] => f(),
+                _ => Err(arity_error(&fname, 0, args.len())),
+            }
+        })
+    }
+
+    /// A function of exactly one positional argument, e.g. a sort key.
+    pub fn unary<F>(name: &str, f: F) -> Self
+    where
+        F: Fn(&PyValue) -> PyResult<PyValue> + 'static,
+    {
+        let fname = name.to_string();
+        Self::new(name, move |args: &[PyValue], kwargs: &KwargsMap| {
+            reject_kwargs(&fname, kwargs)?;
+            match args {
+                [a] => f(a),
+                _ => Err(arity_error(&fname, 1, args.len())),
+            }
+        })
+    }
+
+    /// A function of exactly two positional arguments.
+    pub fn binary<F>(name: &str, f: F) -> Self
+    where
+        F: Fn(&PyValue, &PyValue) -> PyResult<PyValue> + 'static,
+    {
+        let fname = name.to_string();
+        Self::new(name, move |args: &[PyValue], kwargs: &KwargsMap| {
+            reject_kwargs(&fname, kwargs)?;
+            match args {
+                [a, b] => f(a, b),
+                _ => Err(arity_error(&fname, 2, args.len())),
+            }
+        })
+    }
+
+    /// Python-equivalent function.__name__.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Python-equivalent descriptor binding: `obj.method` yields a bound
+    /// method that passes `receiver` as the first argument.
+    pub fn bind(&self, receiver: PyValue) -> PyFunction {
+        PyFunction {
+            name: self.name.clone(),
+            func: self.func.clone(),
+            receiver: Some(Box::new(receiver)),
+        }
+    }
+
+    /// Python-equivalent method.__self__.
+    pub fn receiver(&self) -> Option<&PyValue> {
+        self.receiver.as_deref()
+    }
+
+    pub fn is_bound(&self) -> bool {
+        self.receiver.is_some()
+    }
+
+    /// Calls with a single positional argument.
+    pub fn call1(&self, arg: PyValue) -> PyResult<PyValue> {
+        self.call_positional(&[arg])
+    }
+
+    /// The address identifying this function object.
+    pub fn addr(&self) -> usize {
+        Rc::as_ptr(&self.func).cast::<()>() as usize
+    }
+}
+
+impl PyCallable for PyFunction {
+    fn call(&self, args: &[PyValue], kwargs: &KwargsMap) -> PyResult<PyValue> {
+        match &self.receiver {
+            Some(receiver) => {
+                let mut full = Vec::with_capacity(args.len() + 1);
+                full.push((**receiver).clone());
+                full.extend_from_slice(args);
+                self.func.call(&full, kwargs)
+            }
+            None => self.func.call(args, kwargs),
+        }
+    }
+}
+
+impl PartialEq for PyFunction {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.func, &other.func) && self.receiver == other.receiver
+    }
+}
+
+impl PyTyped for PyFunction {
+    fn py_type(&self) -> PyTypeId {
+        if self.is_bound() {
+            PyTypeId::Method
+        } else {
+            PyTypeId::Function
+        }
+    }
+
+    fn py_id(&self) -> usize {
+        self.addr()
+    }
+}
+
+impl fmt::Debug for PyFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+/// Python-equivalent repr of a function or bound method.
+impl fmt::Display for PyFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.receiver {
+            Some(receiver) => write!(
+                f,
+                "<bound method {} of {}>",
+                self.name,
+                super::PyRepr::py_repr(&**receiver)
+            ),
+            None => write!(f, "<function {} at {:#x}>", self.name, self.addr()),
+        }
+    }
+}
+
+/// What the generated `signature()` returns, named here so the expansion
+/// compiles in no_std crates without `alloc` in scope.
+pub type SignatureString = String;
+
+/// Joins parameters as str(inspect.signature()) does, with `*` before the
+/// keyword-only ones.
+pub fn format_signature(positional: &[&str], keyword_only: &[&str]) -> SignatureString {
+    let mut params = positional.to_vec();
+    if !keyword_only.is_empty() {
+        params.push("*");
+        params.extend_from_slice(keyword_only);
+    }
+    format!("({})", params.join(", "))
+}
+
+/// What a `python_function!` declaration records about itself: where it
+/// lives, its parameters, and an adapter callable over `PyValue`s.
+#[derive(Debug)]
+pub struct FunctionEntry {
+    /// `module_path!()` of the declaration.
+    pub module: &'static str,
+    pub name: &'static str,
+    pub file: &'static str,
+    pub line: u32,
+    /// Positional parameters, defaults as written (`base=None`).
+    pub positional: &'static [&'static str],
+    pub keyword_only: &'static [&'static str],
+    pub call: fn(&[PyValue], &KwargsMap) -> PyResult<PyValue>,
+}
+
+impl FunctionEntry {
+    /// `module::name`.
+    pub fn path(&self) -> String {
+        format!("{}::{}", self.module, self.name)
+    }
+
+    /// str(inspect.signature(...)).
+    pub fn signature(&self) -> SignatureString {
+        format_signature(self.positional, self.keyword_only)
+    }
+
+    /// The Python module it belongs to: the module path without the crate
+    /// name or a leading `stdlib`, dotted, so `stdpython::stdlib::math`
+    /// is `math` and `stdpython::stdlib::enum_` is `enum`.
+    pub fn python_module(&self) -> String {
+        let mut segments = self.module.split("::").skip(1).peekable();
+        if segments.peek() == Some(&"stdlib") {
+            segments.next();
+        }
+        segments
+            .map(|segment| segment.strip_suffix('_').unwrap_or(segment))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+
+    /// The function as a first-class value.
+    pub fn to_function(&self) -> PyFunction {
+        PyFunction::new(self.name, self.call)
+    }
+}
+
+static FUNCTIONS: Lock<Vec<&'static FunctionEntry>> = Lock::new(Vec::new());
+
+/// Adds `entry` to the registry; `python_function!` does this at startup.
+/// Registering the same path again replaces the earlier entry.
+pub fn register_function(entry: &'static FunctionEntry) {
+    let mut functions = FUNCTIONS.lock();
+    functions.retain(|known| known.module != entry.module || known.name != entry.name);
+    functions.push(entry);
+}
+
+/// The entry for `name` declared in `module` (a `module_path!()`).
+pub fn registered_function(module: &str, name: &str) -> Option<&'static FunctionEntry> {
+    FUNCTIONS
+        .lock()
+        .iter()
+        .find(|entry| entry.module == module && entry.name == name)
+        .copied()
+}
+
+/// The entry for a bare name or a `module::name` path; the most recent
+/// registration wins when a bare name is ambiguous.
+pub fn find_function(name: &str) -> Option<&'static FunctionEntry> {
+    let (module, name) = match name.rsplit_once("::") {
+        Some((module, name)) => (Some(module), name),
+        None => (None, name),
+    };
+    FUNCTIONS
+        .lock()
+        .iter()
+        .rev()
+        .find(|entry| entry.name == name && module.is_none_or(|m| m == entry.module))
+        .copied()
+}
+
+/// Every function registered from `module`, in registration order.
+pub fn module_functions(module: &str) -> Vec<&'static FunctionEntry> {
+    FUNCTIONS
+        .lock()
+        .iter()
+        .filter(|entry| entry.module == module)
+        .copied()
+        .collect()
+}
+
+/// Looks up a registered function as a callable value.
+pub fn get_function(module: &str, name: &str) -> Option<PyFunction> {
+    registered_function(module, name).map(FunctionEntry::to_function)
+}
+
+/// Python-equivalent getattr(importlib.import_module(module), name)(*args,
+/// **kwargs) for a registered function. Raises ModuleNotFoundError for a
+/// module with no registered functions and AttributeError for a name it
+/// does not have.
+pub fn call(module: &str, name: &str, args: &[PyValue], kwargs: &PyKwargs) -> PyResult<PyValue> {
+    let entry = lookup(module, name)?;
+    (entry.call)(args, &KwargsMap::from(kwargs.clone()))
+}
+
+/// [`call`] with a dotted name, e.g. `math.sqrt`.
+pub fn call_function(path: &str, args: &[PyValue], kwargs: &PyKwargs) -> PyResult<PyValue> {
+    match path.rsplit_once('.') {
+        Some((module, name)) => call(module, name, args, kwargs),
+        None => Err(PyException::value_error(format!(
+            "expected a dotted function name, got {:?}",
+            path
+        ))),
+    }
+}
+
+/// The registered function `module.name`, with the errors [`call`] raises.
+pub fn lookup(module: &str, name: &str) -> PyResult<&'static FunctionEntry> {
+    let functions = FUNCTIONS.lock();
+    let mut in_module = functions
+        .iter()
+        .filter(|entry| entry.python_module() == module)
+        .peekable();
+    if in_module.peek().is_none() {
+        return Err(PyException::new(
+            "ModuleNotFoundError",
+            format!("No module named '{}'", module),
+        ));
+    }
+    in_module
+        .rev()
+        .find(|entry| entry.name == name)
+        .copied()
+        .ok_or_else(|| {
+            PyException::new(
+                "AttributeError",
+                format!("module '{}' has no attribute '{}'", module, name),
+            )
+        })
+}
+
+/// The Python modules with registered functions, sorted.
+pub fn list_modules() -> Vec<String> {
+    let mut modules: Vec<String> = FUNCTIONS
+        .lock()
+        .iter()
+        .map(|entry| entry.python_module())
+        .collect();
+    modules.sort();
+    modules.dedup();
+    modules
+}
+
+/// The names registered in Python module `module`, sorted.
+pub fn list_functions(module: &str) -> Vec<&'static str> {
+    let mut names: Vec<&'static str> = FUNCTIONS
+        .lock()
+        .iter()
+        .filter(|entry| entry.python_module() == module)
+        .map(|entry| entry.name)
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    names
+}
+
+/// Takes the next positional argument for `param`, or else its keyword.
+#[doc(hidden)]
+pub fn bind_argument<T: FromKwarg>(
+    positional: &mut core::slice::Iter<'_, PyValue>,
+    param: &str,
+    kwargs: &mut PyKwargs,
+) -> PyResult<Option<T>> {
+    match positional.next() {
+        Some(value) => {
+            if kwargs.contains(param) {
+                return Err(PyException::type_error(format!(
+                    "{}() got multiple values for argument '{}'",
+                    kwargs.func(),
+                    param
+                )));
+            }
+            T::from_kwarg(value)
+                .map(Some)
+                .ok_or_else(|| kwargs.type_error(param, T::EXPECTED, value))
+        }
+        None => kwargs.extract(param),
+    }
+}
+
+/// TypeError for more positional arguments than `func` takes.
+#[doc(hidden)]
+pub fn check_positional(func: &str, required: usize, total: usize, given: usize) -> PyResult<()> {
+    if given <= total {
+        return Ok(());
+    }
+    if required == total {
+        return Err(arity_error(func, total, given));
+    }
+    Err(PyException::type_error(format!(
+        "{}() takes from {} to {} positional arguments but {} were given",
+        func, required, total, given
+    )))
+}
+
+/// TypeError for a required argument given neither way.
+#[doc(hidden)]
+pub fn missing_argument(func: &str, param: &str) -> PyException {
+    PyException::type_error(format!(
+        "{}() missing 1 required positional argument: '{}'",
+        func, param
+    ))
+}
+
+/// The module part of a `module_path!()` inside a generated module.
+#[doc(hidden)]
+pub const fn parent_module(path: &'static str) -> &'static str {
+    let bytes = path.as_bytes();
+    let mut i = bytes.len();
+    while i >= 2 {
+        if bytes[i - 1] == b':' && bytes[i - 2] == b':' {
+            let (head, _) = bytes.split_at(i - 2);
+            if let Ok(head) = core::str::from_utf8(head) {
+                return head;
+            }
+        }
+        i -= 1;
+    }
+    path
+}
+
+/// Return types a `python_function!` adapter can hand back as a `PyValue`.
+pub trait IntoPyReturn {
+    fn into_py_return(self) -> PyResult<PyValue>;
+}
+
+impl<T: Into<PyValue>> IntoPyReturn for T {
+    fn into_py_return(self) -> PyResult<PyValue> {
+        Ok(self.into())
+    }
+}
+
+impl<T: Into<PyValue>> IntoPyReturn for PyResult<T> {
+    fn into_py_return(self) -> PyResult<PyValue> {
+        self.map(Into::into)
+    }
+}
+
+/// Declares a compiled Python function together with the wrappers call
+/// sites use when they leave out defaulted arguments.
+///
+/// Parameters are written `name: Type` or `name: Type = default`, Python
+/// order, with `*,` before keyword-only ones (which need defaults). The
+/// function itself takes every parameter; a module of the same name holds:
+///
+/// - `callN`, for each N from the required count to the positional count:
+///   the first N positional arguments, defaults for the rest;
+/// - `call_kwargs(required..., kwargs)`: the required arguments, then the
+///   others by keyword from a [`PyKwargs`](crate::types::PyKwargs), each of
+///   a [`FromKwarg`](crate::types::FromKwarg) type;
+/// - `signature()`: the signature as inspect.signature() would print it;
+/// - `call_values(args, kwargs)`: a [`PyCallable`] adapter over `PyValue`s,
+///   for a return type that is [`IntoPyReturn`];
+/// - `ENTRY`: the function's [`FunctionEntry`], which is registered before
+///   `main` on platforms with constructor sections (ELF, Mach-O, PE) and
+///   otherwise by calling the module's `register()`. Look entries up with
+///   [`registered_function`], [`find_function`] or [`module_functions`].
+///
+/// ```ignore
+/// python_function! {
+///     pub fn log(x: f64, base: Option<f64> = None, *, exact: bool = false) -> f64 { ... }
+/// }
+/// log::call1(8.0);                                        // log(8)
+/// log::call_kwargs(8.0, PyKwargs::new().with("base", 2))?; // log(8, base=2)
+/// ```
+#[macro_export]
+macro_rules! python_function {
+    (
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident ( $($params:tt)* ) $(-> $ret:ty)? $body:block
+    ) => {
+        $crate::python_function!(@pos [$(#[$meta])*] [$vis] $name [$($ret)?] $body
+            [] [] ; $($params)*);
+    };
+
+    // Positional parameters: required ones, then defaulted ones.
+    (@pos $meta:tt $vis:tt $name:ident $ret:tt $body:block $req:tt $opt:tt ;
+        * , $($rest:tt)*) => {
+        $crate::python_function!(@kw $meta $vis $name $ret $body $req $opt [] ; $($rest)*);
+    };
+    (@pos $meta:tt $vis:tt $name:ident $ret:tt $body:block [$($req:tt)*] [$($opt:tt)*] ;
+        $p:ident : $t:ty = $d:expr $(, $($rest:tt)*)?) => {
+        $crate::python_function!(@pos $meta $vis $name $ret $body [$($req)*]
+            [$($opt)* ($p : $t = $d)] ; $($($rest)*)?);
+    };
+    (@pos $meta:tt $vis:tt $name:ident $ret:tt $body:block [$($req:tt)*] [] ;
+        $p:ident : $t:ty $(, $($rest:tt)*)?) => {
+        $crate::python_function!(@pos $meta $vis $name $ret $body [$($req)* ($p : $t)] [] ;
+            $($($rest)*)?);
+    };
+    (@pos $meta:tt $vis:tt $name:ident $ret:tt $body:block $req:tt [$($opt:tt)+] ;
+        $p:ident : $t:ty $(, $($rest:tt)*)?) => {
+        compile_error!("non-default argument follows default argument");
+    };
+    (@pos $meta:tt $vis:tt $name:ident $ret:tt $body:block $req:tt $opt:tt ;) => {
+        $crate::python_function!(@emit $meta $vis $name $ret $body $req $opt []);
+    };
+
+    // Keyword-only parameters.
+    (@kw $meta:tt $vis:tt $name:ident $ret:tt $body:block $req:tt $opt:tt [$($kw:tt)*] ;
+        $k:ident : $t:ty = $d:expr $(, $($rest:tt)*)?) => {
+        $crate::python_function!(@kw $meta $vis $name $ret $body $req $opt
+            [$($kw)* ($k : $t = $d)] ; $($($rest)*)?);
+    };
+    (@kw $meta:tt $vis:tt $name:ident $ret:tt $body:block $req:tt $opt:tt $kw:tt ;
+        $k:ident : $t:ty $(, $($rest:tt)*)?) => {
+        compile_error!("keyword-only parameters of python_function! need a default");
+    };
+    (@kw $meta:tt $vis:tt $name:ident $ret:tt $body:block $req:tt $opt:tt $kw:tt ;) => {
+        $crate::python_function!(@emit $meta $vis $name $ret $body $req $opt $kw);
+    };
+
+    (@emit [$(#[$meta:meta])*] [$vis:vis] $name:ident [$($ret:ty)?] $body:block
+        [$(($rp:ident : $rt:ty))*]
+        [$(($op:ident : $ot:ty = $od:expr))*]
+        [$(($kp:ident : $kt:ty = $kd:expr))*]
+    ) => {
+        $(#[$meta])*
+        #[allow(clippy::too_many_arguments)]
+        $vis fn $name($($rp: $rt,)* $($op: $ot,)* $($kp: $kt,)*) $(-> $ret)? $body
+
+        #[doc = concat!("Call wrappers for [`", stringify!($name), "()`](fn@", stringify!($name), ").")]
+        #[allow(unused_imports, dead_code, clippy::too_many_arguments)]
+        $vis mod $name {
+            use super::*;
+
+            $crate::python_function!(@arity $name [$($ret)?] [$(($rp : $rt))*]
+                [$(($op : $ot = $od))*] [$($kd),*] [$(($rp))*]
+                [call0 call1 call2 call3 call4 call5 call6 call7 call8 call9 call10
+                 call11 call12 call13 call14 call15 call16]);
+
+            /// The required arguments positionally, the rest by keyword.
+            pub fn call_kwargs(
+                $($rp: $rt,)*
+                kwargs: $crate::types::PyKwargs,
+            ) -> $crate::exceptions::PyResult<$crate::python_function!(@ret $($ret)?)> {
+                let kwargs = &mut kwargs.for_call(stringify!($name));
+                $(let $op: $ot = match kwargs.extract(stringify!($op))? {
+                    Some(value) => value,
+                    None => $od,
+                };)*
+                $(let $kp: $kt = match kwargs.extract(stringify!($kp))? {
+                    Some(value) => value,
+                    None => $kd,
+                };)*
+                kwargs.reject_unknown()?;
+                Ok(super::$name($($rp,)* $($op,)* $($kp,)*))
+            }
+
+            /// str(inspect.signature(...)), defaults as written.
+            pub fn signature() -> $crate::types::function::SignatureString {
+                ENTRY.signature()
+            }
+
+            /// Calls with Python arguments, converting each to its
+            /// parameter's type and the result to a `PyValue`.
+            pub fn call_values(
+                args: &[$crate::types::PyValue],
+                kwargs: &$crate::types::KwargsMap,
+            ) -> $crate::exceptions::PyResult<$crate::types::PyValue> {
+                use $crate::types::function::{bind_argument, missing_argument, IntoPyReturn};
+                let func = stringify!($name);
+                $crate::types::function::check_positional(
+                    func,
+                    [$(stringify!($rp)),*].len(),
+                    [$(stringify!($rp),)* $(stringify!($op)),*].len(),
+                    args.len(),
+                )?;
+                let kwargs = &mut $crate::types::PyKwargs::from(kwargs.clone()).for_call(func);
+                let positional = &mut args.iter();
+                $(let $rp: $rt = match bind_argument(&mut *positional, stringify!($rp), &mut *kwargs)? {
+                    Some(value) => value,
+                    None => return Err(missing_argument(func, stringify!($rp))),
+                };)*
+                $(let $op: $ot = match bind_argument(&mut *positional, stringify!($op), &mut *kwargs)? {
+                    Some(value) => value,
+                    None => $od,
+                };)*
+                $(let $kp: $kt = match kwargs.extract(stringify!($kp))? {
+                    Some(value) => value,
+                    None => $kd,
+                };)*
+                kwargs.reject_unknown()?;
+                super::$name($($rp,)* $($op,)* $($kp,)*).into_py_return()
+            }
+
+            /// The registry entry, added at startup where the platform runs
+            /// constructors and by [`register`] elsewhere.
+            pub static ENTRY: $crate::types::function::FunctionEntry =
+                $crate::types::function::FunctionEntry {
+                    module: $crate::types::function::parent_module(module_path!()),
+                    name: stringify!($name),
+                    file: file!(),
+                    line: line!(),
+                    positional: &[
+                        $(stringify!($rp),)*
+                        $(concat!(stringify!($op), "=", stringify!($od)),)*
+                    ],
+                    keyword_only: &[$(concat!(stringify!($kp), "=", stringify!($kd))),*],
+                    call: call_values,
+                };
+
+            pub fn register() {
+                $crate::types::function::register_function(&ENTRY);
+            }
+
+            extern "C" fn register_at_startup() {
+                register();
+            }
+
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd",
+                target_os = "dragonfly",
+                target_os = "illumos",
+            ))]
+            #[used]
+            #[link_section = ".init_array"]
+            static REGISTER: extern "C" fn() = register_at_startup;
+
+            #[cfg(any(target_os = "macos", target_os = "ios"))]
+            #[used]
+            #[link_section = "__DATA,__mod_init_func"]
+            static REGISTER: extern "C" fn() = register_at_startup;
+
+            #[cfg(windows)]
+            #[used]
+            #[link_section = ".CRT$XCU"]
+            static REGISTER: extern "C" fn() = register_at_startup;
+        }
+    };
+
+    (@ret) => { () };
+    (@ret $ret:ty) => { $ret };
+
+    // Drops one wrapper name per required parameter, so the first wrapper
+    // is named for the required count.
+    (@arity $name:ident $ret:tt $req:tt $opt:tt $kd:tt [($skip:ident) $($more:tt)*]
+        [$f:ident $($pool:ident)*]) => {
+        $crate::python_function!(@arity $name $ret $req $opt $kd [$($more)*] [$($pool)*]);
+    };
+    (@arity $name:ident [$($ret:ty)?] [$(($rp:ident : $rt:ty))*] [] [$($kd:expr),*] []
+        [$f:ident $($pool:ident)*]) => {
+        pub fn $f($($rp: $rt),*) $(-> $ret)? {
+            super::$name($($rp,)* $($kd,)*)
+        }
+    };
+    (@arity $name:ident [$($ret:ty)?] [$(($rp:ident : $rt:ty))*]
+        [($op:ident : $ot:ty = $od:expr) $(($op2:ident : $ot2:ty = $od2:expr))*]
+        [$($kd:expr),*] [] [$f:ident $($pool:ident)*]) => {
+        pub fn $f($($rp: $rt),*) $(-> $ret)? {
+            super::$name($($rp,)* $od, $($od2,)* $($kd,)*)
+        }
+        $crate::python_function!(@arity $name [$($ret)?] [$(($rp : $rt))* ($op : $ot)]
+            [$(($op2 : $ot2 = $od2))*] [$($kd),*] [] [$($pool)*]);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::PyKwargs;
+
+    crate::python_function! {
+        fn log(x: f64, base: Option<f64> = None, *, floor: bool = false) -> f64 {
+            let value = match base {
+                Some(base) => x.ln() / base.ln(),
+                None => x.ln(),
+            };
+            if floor { value.floor() } else { value }
+        }
+    }
+
+    #[test]
+    fn adapters_check_arity_and_kwargs() {
+        let double = PyFunction::unary("double", |x| match x {
+            PyValue::Int(i) => Ok(PyValue::Int(i * 2)),
+            _ => Err(PyException::type_error("expected int")),
+        });
+        assert_eq!(double.call1(PyValue::Int(4)).unwrap(), PyValue::Int(8));
+        let err = double.call_positional(&[]).unwrap_err();
+        assert_eq!(
+            err.message,
+            "double() takes 1 positional argument but 0 were given"
+        );
+        let mut kwargs = KwargsMap::new();
+        kwargs.set_item("x".to_string(), PyValue::Int(1));
+        let err = double.call(&[PyValue::Int(1)], &kwargs).unwrap_err();
+        assert_eq!(
+            err.message,
+            "double() got an unexpected keyword argument 'x'"
+        );
+    }
+
+    #[test]
+    fn bound_methods_prepend_receiver() {
+        let add = PyFunction::binary("add", |a, b| match (a, b) {
+            (PyValue::Int(a), PyValue::Int(b)) => Ok(PyValue::Int(a + b)),
+            _ => Err(PyException::type_error("expected ints")),
+        });
+        let add_ten = add.bind(PyValue::Int(10));
+        assert_eq!(add_ten.call1(PyValue::Int(5)).unwrap(), PyValue::Int(15));
+        assert_eq!(add_ten.py_type(), PyTypeId::Method);
+        assert_eq!(add_ten.to_string(), "<bound method add of 10>");
+        assert_eq!(add.clone(), add);
+        assert_ne!(add, add_ten);
+    }
+
+    #[test]
+    fn closures_with_kwargs() {
+        let greet = PyFunction::new("greet", |args: &[PyValue], kwargs: &KwargsMap| {
+            let name = args.first().cloned().unwrap_or_default();
+            let punct = kwargs.get(&"punct".to_string()).cloned();
+            Ok(PyValue::from(format!(
+                "hi {}{}",
+                name,
+                punct.map_or(String::new(), |p| p.to_string())
+            )))
+        });
+        let mut kwargs = KwargsMap::new();
+        kwargs.set_item("punct".to_string(), PyValue::from("!"));
+        assert_eq!(
+            greet.call(&[PyValue::from("bo")], &kwargs).unwrap(),
+            PyValue::from("hi bo!")
+        );
+    }
+
+    #[test]
+    fn python_function_defaults_and_keyword_only() {
+        assert_eq!(log::call1(1.0), 0.0);
+        assert_eq!(log::call2(8.0, Some(2.0)), 3.0);
+        assert_eq!(log(1000.0, Some(10.0), true), 2.0);
+        let kwargs = PyKwargs::new().with("base", 2).with("floor", true);
+        assert_eq!(log::call_kwargs(10.0, kwargs).unwrap(), 3.0);
+        let kwargs = PyKwargs::new().with("base", ());
+        assert_eq!(log::call_kwargs(1.0, kwargs).unwrap(), 0.0);
+        let err = log::call_kwargs(1.0, PyKwargs::new().with("bass", 2)).unwrap_err();
+        assert_eq!(
+            err.message,
+            "log() got an unexpected keyword argument 'bass'"
+        );
+        let err = log::call_kwargs(1.0, PyKwargs::new().with("base", "e")).unwrap_err();
+        assert!(err.is("TypeError"));
+        assert_eq!(log::signature(), "(x, base=None, *, floor=false)");
+    }
+
+    #[test]
+    fn python_function_registers_an_adapter() {
+        let entry = registered_function(module_path!(), "log").unwrap();
+        assert_eq!(entry.path(), format!("{}::log", module_path!()));
+        assert_eq!(entry.signature(), "(x, base=None, *, floor=false)");
+        assert!(core::ptr::eq(find_function("log").unwrap(), entry));
+        assert_eq!(module_functions(module_path!()).len(), 1);
+
+        let log = get_function(module_path!(), "log").unwrap();
+        let args = [PyValue::Int(8), PyValue::Float(2.0)];
+        assert_eq!(log.call_positional(&args).unwrap(), PyValue::Float(3.0));
+        let mut kwargs = KwargsMap::new();
+        kwargs.set_item("base".to_string(), PyValue::Int(2));
+        let err = log.call(&args, &kwargs).unwrap_err();
+        assert_eq!(err.message, "log() got multiple values for argument 'base'");
+        let err = log.call_positional(&[]).unwrap_err();
+        assert_eq!(
+            err.message,
+            "log() missing 1 required positional argument: 'x'"
+        );
+        let err = log
+            .call_positional(&[PyValue::Int(1), PyValue::Int(2), PyValue::Int(3)])
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            "log() takes from 1 to 2 positional arguments but 3 were given"
+        );
+    }
+}
@@ -0,0 +1,490 @@
+//! hash() following CPython's rules: numerically equal ints, floats and bools
+//! hash alike, and mutable containers are unhashable.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::hash::{Hash, Hasher};
+
+use super::{PyBytes, PyDictionary, PyList, PySet, PyStr, PyTuple};
+use crate::exceptions::{PyException, PyResult};
+use crate::sync::Once;
+
+/// The Mersenne prime CPython reduces numeric hashes by (`sys.hash_info.modulus`).
+pub const HASH_MODULUS: u64 = (1 << 61) - 1;
+/// `sys.hash_info.inf`.
+pub const HASH_INF: i64 = 314159;
+
+const HASH_BITS: u32 = 61;
+
+/// Python-equivalent `__hash__`.
+pub trait PyHash {
+    fn py_hash(&self) -> PyResult<i64>;
+}
+
+/// CPython never returns -1 from a hash; it is reserved for errors.
+fn fix_minus_one(h: i64) -> i64 {
+    if h == -1 {
+        -2
+    } else {
+        h
+    }
+}
+
+/// hash() of an integer: its value reduced modulo [`HASH_MODULUS`], keeping the sign.
+pub fn hash_int(value: i128) -> i64 {
+    let reduced = (value.unsigned_abs() % HASH_MODULUS as u128) as i64;
+    fix_minus_one(if value < 0 { -reduced } else { reduced })
+}
+
+/// Splits a finite, non-zero float into a mantissa in [0.5, 1) and an exponent.
+fn frexp(value: f64) -> (f64, i32) {
+    let bits = value.to_bits();
+    let exp = ((bits >> 52) & 0x7ff) as i32;
+    if exp == 0 {
+        let (m, e) = frexp(value * f64::from_bits(0x4350_0000_0000_0000)); // 2**54
+        return (m, e - 54);
+    }
+    let mantissa = f64::from_bits((bits & !(0x7ff << 52)) | (1022 << 52));
+    (mantissa, exp - 1022)
+}
+
+/// hash() of a float, bit-for-bit CPython's `_Py_HashDouble`. NaN hashes to 0,
+/// the pre-3.10 behavior, since values here have no stable object identity.
+pub fn hash_float(value: f64) -> i64 {
+    if value.is_nan() {
+        return 0;
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { HASH_INF } else { -HASH_INF };
+    }
+    if value == 0.0 {
+        return 0;
+    }
+    let (mut m, mut e) = frexp(value);
+    let sign: i64 = if m < 0.0 {
+        m = -m;
+        -1
+    } else {
+        1
+    };
+    let mut x: u64 = 0;
+    while m != 0.0 {
+        x = ((x << 28) & HASH_MODULUS) | (x >> (HASH_BITS - 28));
+        m *= 268435456.0; // 2**28
+        e -= 28;
+        let y = m as u64;
+        m -= y as f64;
+        x += y;
+        if x >= HASH_MODULUS {
+            x -= HASH_MODULUS;
+        }
+    }
+    let e = if e >= 0 {
+        e as u32 % HASH_BITS
+    } else {
+        HASH_BITS - 1 - ((-1 - e) as u32 % HASH_BITS)
+    };
+    x = ((x << e) & HASH_MODULUS) | (x >> (HASH_BITS - e));
+    fix_minus_one(x as i64 * sign)
+}
+
+/// SipHash-1-3, the string hash CPython has used since 3.11.
+#[derive(Debug, Clone)]
+pub struct SipHasher13 {
+    v: [u64; 4],
+    tail: u64,
+    ntail: usize,
+    length: usize,
+}
+
+impl SipHasher13 {
+    pub fn new_with_keys(k0: u64, k1: u64) -> Self {
+        SipHasher13 {
+            v: [
+                k0 ^ 0x736f6d6570736575,
+                k1 ^ 0x646f72616e646f6d,
+                k0 ^ 0x6c7967656e657261,
+                k1 ^ 0x7465646279746573,
+            ],
+            tail: 0,
+            ntail: 0,
+            length: 0,
+        }
+    }
+
+    fn round(&mut self) {
+        let [v0, v1, v2, v3] = &mut self.v;
+        *v0 = v0.wrapping_add(*v1);
+        *v1 = v1.rotate_left(13) ^ *v0;
+        *v0 = v0.rotate_left(32);
+        *v2 = v2.wrapping_add(*v3);
+        *v3 = v3.rotate_left(16) ^ *v2;
+        *v0 = v0.wrapping_add(*v3);
+        *v3 = v3.rotate_left(21) ^ *v0;
+        *v2 = v2.wrapping_add(*v1);
+        *v1 = v1.rotate_left(17) ^ *v2;
+        *v2 = v2.rotate_left(32);
+    }
+
+    fn compress(&mut self, m: u64) {
+        self.v[3] ^= m;
+        self.round();
+        self.v[0] ^= m;
+    }
+}
+
+impl Hasher for SipHasher13 {
+    fn write(&mut self, bytes: &[u8]) {
+        self.length += bytes.len();
+        let mut rest = bytes;
+        while self.ntail != 0 && !rest.is_empty() {
+            self.tail |= (rest[0] as u64) << (8 * self.ntail);
+            self.ntail = (self.ntail + 1) % 8;
+            rest = &rest[1..];
+            if self.ntail == 0 {
+                let m = core::mem::take(&mut self.tail);
+                self.compress(m);
+            }
+        }
+        let mut chunks = rest.chunks_exact(8);
+        for chunk in &mut chunks {
+            let mut block = [0u8; 8];
+            block.copy_from_slice(chunk);
+            self.compress(u64::from_le_bytes(block));
+        }
+        for (i, &b) in chunks.remainder().iter().enumerate() {
+            self.tail |= (b as u64) << (8 * i);
+            self.ntail += 1;
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut state = self.clone();
+        let b = ((self.length as u64 & 0xff) << 56) | self.tail;
+        state.compress(b);
+        state.v[2] ^= 0xff;
+        for _ in 0..3 {
+            state.round();
+        }
+        let [v0, v1, v2, v3] = state.v;
+        v0 ^ v1 ^ v2 ^ v3
+    }
+}
+
+/// Keys str and bytes hashes and container lookups, like CPython's
+/// `PYTHONHASHSEED`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashSeed {
+    /// `PYTHONHASHSEED=n`: the same hashes on every run, and the same ones
+    /// CPython gives under that seed. `Fixed(0)` disables randomization.
+    Fixed(u32),
+    /// `PYTHONHASHSEED=random`: a fresh key per process, so attackers cannot
+    /// precompute colliding dict keys.
+    #[cfg(feature = "std")]
+    Random,
+}
+
+/// The seed used when none is set before the first hash.
+#[cfg(feature = "hash-randomization")]
+pub const DEFAULT_HASH_SEED: HashSeed = HashSeed::Random;
+/// The seed used when none is set before the first hash; enable the
+/// `hash-randomization` feature to randomize instead.
+#[cfg(not(feature = "hash-randomization"))]
+pub const DEFAULT_HASH_SEED: HashSeed = HashSeed::Fixed(0);
+
+struct HashSecret {
+    seed: HashSeed,
+    k0: u64,
+    k1: u64,
+}
+
+static HASH_SECRET: Once<HashSecret> = Once::new();
+
+impl HashSecret {
+    fn new(seed: HashSeed) -> Self {
+        let (k0, k1) = match seed {
+            HashSeed::Fixed(n) => seed_keys(n),
+            #[cfg(feature = "std")]
+            HashSeed::Random => {
+                use core::hash::BuildHasher;
+                let state = std::collections::hash_map::RandomState::new();
+                (state.hash_one(0u8), state.hash_one(1u8))
+            }
+        };
+        HashSecret { seed, k0, k1 }
+    }
+}
+
+/// CPython's `lcg_urandom`: the SipHash key it derives from a fixed
+/// `PYTHONHASHSEED`, or all zeros for seed 0.
+fn seed_keys(seed: u32) -> (u64, u64) {
+    if seed == 0 {
+        return (0, 0);
+    }
+    let mut x = seed;
+    let mut key = [0u8; 16];
+    for byte in &mut key {
+        x = x.wrapping_mul(214013).wrapping_add(2531011);
+        *byte = (x >> 16) as u8;
+    }
+    let (k0, k1) = key.split_at(8);
+    (
+        u64::from_le_bytes(k0.try_into().unwrap()),
+        u64::from_le_bytes(k1.try_into().unwrap()),
+    )
+}
+
+fn hash_secret() -> &'static HashSecret {
+    HASH_SECRET.get_or_init(|| HashSecret::new(DEFAULT_HASH_SEED))
+}
+
+/// Chooses the hash seed. Like `PYTHONHASHSEED` it must be decided before
+/// anything is hashed: existing dicts and sets hold hashes made with the
+/// old key, so once one exists this raises RuntimeError.
+pub fn set_hash_seed(seed: HashSeed) -> PyResult<()> {
+    HASH_SECRET
+        .set(HashSecret::new(seed))
+        .map_err(|_| PyException::runtime_error("hash seed already in use"))
+}
+
+/// The seed in effect; asking fixes it, as hashing would.
+pub fn hash_seed() -> HashSeed {
+    hash_secret().seed
+}
+
+fn hash_bytes_keyed((k0, k1): (u64, u64), data: &[u8]) -> i64 {
+    if data.is_empty() {
+        return 0;
+    }
+    let mut hasher = SipHasher13::new_with_keys(k0, k1);
+    hasher.write(data);
+    fix_minus_one(hasher.finish() as i64)
+}
+
+/// hash() of bytes, matching CPython run with the same [`HashSeed`].
+pub fn hash_bytes(data: &[u8]) -> i64 {
+    let secret = hash_secret();
+    hash_bytes_keyed((secret.k0, secret.k1), data)
+}
+
+/// hash() of str, matching CPython run with the same [`HashSeed`]. CPython
+/// hashes a string's compact representation, so the width of the widest
+/// code point decides the bytes hashed.
+pub fn hash_str(s: &str) -> i64 {
+    let widest = s.chars().map(|c| c as u32).max().unwrap_or(0);
+    if widest < 0x80 {
+        return hash_bytes(s.as_bytes());
+    }
+    let data: Vec<u8> = if widest < 0x100 {
+        s.chars().map(|c| c as u8).collect()
+    } else if widest < 0x10000 {
+        s.chars().flat_map(|c| (c as u16).to_le_bytes()).collect()
+    } else {
+        s.chars().flat_map(|c| (c as u32).to_le_bytes()).collect()
+    };
+    hash_bytes(&data)
+}
+
+/// Hashes a container key. This is independent of [`PyHash`]: it only has to
+/// agree with `Eq` for the key type.
+pub(crate) fn hash_key<K: Hash + ?Sized>(key: &K) -> u64 {
+    let secret = hash_secret();
+    let mut hasher = SipHasher13::new_with_keys(secret.k0, secret.k1);
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// hash() of a tuple from its element hashes, CPython's xxHash-based `tuplehash`.
+pub fn hash_sequence<I: IntoIterator<Item = i64>>(hashes: I) -> i64 {
+    const PRIME_1: u64 = 11400714785074694791;
+    const PRIME_2: u64 = 14029467366897019727;
+    const PRIME_5: u64 = 2870177450012600261;
+    let mut acc = PRIME_5;
+    let mut len: u64 = 0;
+    for lane in hashes {
+        acc = acc.wrapping_add((lane as u64).wrapping_mul(PRIME_2));
+        acc = acc.rotate_left(31);
+        acc = acc.wrapping_mul(PRIME_1);
+        len += 1;
+    }
+    acc = acc.wrapping_add(len ^ (PRIME_5 ^ 3527539));
+    if acc == u64::MAX {
+        return 1546275796;
+    }
+    acc as i64
+}
+
+pub(crate) fn unhashable(type_name: &str) -> PyException {
+    PyException::type_error(format!("unhashable type: '{}'", type_name))
+}
+
+macro_rules! impl_py_hash_int {
+    ($($t:ty),*) => {
+        $(impl PyHash for $t {
+            fn py_hash(&self) -> PyResult<i64> {
+                Ok(hash_int(*self as i128))
+            }
+        })*
+    };
+}
+
+impl_py_hash_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, usize);
+
+impl PyHash for u128 {
+    fn py_hash(&self) -> PyResult<i64> {
+        Ok(fix_minus_one((*self % HASH_MODULUS as u128) as i64))
+    }
+}
+
+impl PyHash for bool {
+    fn py_hash(&self) -> PyResult<i64> {
+        Ok(*self as i64)
+    }
+}
+
+impl PyHash for f64 {
+    fn py_hash(&self) -> PyResult<i64> {
+        Ok(hash_float(*self))
+    }
+}
+
+impl PyHash for f32 {
+    fn py_hash(&self) -> PyResult<i64> {
+        Ok(hash_float(*self as f64))
+    }
+}
+
+impl PyHash for () {
+    fn py_hash(&self) -> PyResult<i64> {
+        // CPython 3.12 hashes None to a constant.
+        Ok(0xFCA86420)
+    }
+}
+
+impl PyHash for str {
+    fn py_hash(&self) -> PyResult<i64> {
+        Ok(hash_str(self))
+    }
+}
+
+impl PyHash for String {
+    fn py_hash(&self) -> PyResult<i64> {
+        self.as_str().py_hash()
+    }
+}
+
+impl PyHash for PyStr {
+    fn py_hash(&self) -> PyResult<i64> {
+        self.as_str().py_hash()
+    }
+}
+
+impl PyHash for PyBytes {
+    fn py_hash(&self) -> PyResult<i64> {
+        Ok(hash_bytes(self.as_bytes()))
+    }
+}
+
+impl<T: PyHash> PyHash for PyTuple<T> {
+    fn py_hash(&self) -> PyResult<i64> {
+        let hashes = self
+            .iter()
+            .map(PyHash::py_hash)
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(hash_sequence(hashes))
+    }
+}
+
+impl<T: PyHash> PyHash for Option<T> {
+    fn py_hash(&self) -> PyResult<i64> {
+        match self {
+            Some(value) => value.py_hash(),
+            None => ().py_hash(),
+        }
+    }
+}
+
+impl<T: PyHash + ?Sized> PyHash for &T {
+    fn py_hash(&self) -> PyResult<i64> {
+        (**self).py_hash()
+    }
+}
+
+impl<T> PyHash for PyList<T> {
+    fn py_hash(&self) -> PyResult<i64> {
+        Err(unhashable("list"))
+    }
+}
+
+impl<K: Hash + Eq, V> PyHash for PyDictionary<K, V> {
+    fn py_hash(&self) -> PyResult<i64> {
+        Err(unhashable("dict"))
+    }
+}
+
+impl<T: Hash + Eq> PyHash for PySet<T> {
+    fn py_hash(&self) -> PyResult<i64> {
+        Err(unhashable("set"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_hashes_match_cpython() {
+        assert_eq!(1i64.py_hash().unwrap(), 1.0f64.py_hash().unwrap());
+        assert_eq!(true.py_hash().unwrap(), 1);
+        assert_eq!((-1i64).py_hash().unwrap(), -2);
+        assert_eq!(hash_int(1 << 61), 1);
+        assert_eq!(hash_float(0.5), 1152921504606846976);
+        assert_eq!(hash_float(-2.5), -1152921504606846978);
+        assert_eq!(hash_float(1e300), 1224995262755759164);
+        assert_eq!(hash_float(f64::INFINITY), 314159);
+    }
+
+    #[test]
+    fn fixed_seeds_match_cpython() {
+        // PYTHONHASHSEED=n python3 -c "print(hash(b'abc'), hash('é'))"
+        assert_eq!(hash_bytes_keyed(seed_keys(1), b"abc"), -4667308735975688587);
+        assert_eq!(hash_bytes_keyed(seed_keys(1), &[0xe9]), 8133168473585362875);
+        assert_eq!(hash_bytes_keyed(seed_keys(42), b"abc"), 3869580338025362921);
+        assert_eq!(hash_seed(), DEFAULT_HASH_SEED);
+        assert!(set_hash_seed(HashSeed::Fixed(7))
+            .unwrap_err()
+            .is("RuntimeError"));
+    }
+
+    #[cfg(not(feature = "hash-randomization"))]
+    #[test]
+    fn string_hashes_match_cpython_with_zero_seed() {
+        assert_eq!(hash_str("abc"), -4594863902769663758);
+        assert_eq!(hash_bytes(b"abc"), -4594863902769663758);
+        assert_eq!(hash_str(""), 0);
+        assert_eq!(hash_str("é"), 6047309291227476195);
+        assert_eq!(hash_str("€"), -5529981157763016009);
+        assert_eq!(hash_str("😀"), -3536540696076613844);
+        let long = "the quick brown fox jumps over the lazy dog";
+        let mut split = SipHasher13::new_with_keys(0, 0);
+        split.write(&long.as_bytes()[..5]);
+        split.write(&long.as_bytes()[5..]);
+        assert_eq!(split.finish() as i64, hash_bytes(long.as_bytes()));
+    }
+
+    #[test]
+    fn tuple_hash_matches_cpython() {
+        let empty: PyTuple<i64> = PyTuple::new();
+        assert_eq!(empty.py_hash().unwrap(), 5740354900026072187);
+        let pair = PyTuple::from_vec(vec![1i64, 2]);
+        assert_eq!(pair.py_hash().unwrap(), -3550055125485641917);
+    }
+
+    #[test]
+    fn containers_are_unhashable() {
+        let err = PyList::<i64>::new().py_hash().unwrap_err();
+        assert!(err.is("TypeError"));
+        assert_eq!(err.message, "unhashable type: 'list'");
+    }
+}
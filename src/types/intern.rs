@@ -0,0 +1,193 @@
+//! Interned attribute and method names.
+//!
+//! A [`Symbol`] is a numbered name, so compiled code can dispatch on
+//! attribute names by integer comparison instead of comparing heap strings.
+//! Symbols share [`PyStr::intern`](super::PyStr::intern)'s table: a name's
+//! symbol carries the same interned text, and reading it takes no lock.
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cmp::Ordering as CmpOrdering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
+
+use super::string::with_interned;
+
+/// An interned name. Equal symbols always mean equal names.
+#[derive(Clone, Copy)]
+pub struct Symbol(&'static Name);
+
+/// What a symbol points at; one per interned name, never freed, like the
+/// interned text itself.
+#[derive(Debug)]
+struct Name {
+    id: u32,
+    text: Arc<str>,
+}
+
+/// The next symbol number; only advanced under the intern table's lock.
+static NEXT_ID: AtomicU32 = AtomicU32::new(0);
+
+/// The symbol for `name`, interning the text with
+/// [`PyStr::intern`](super::PyStr::intern) if it is new.
+pub fn intern(name: &str) -> Symbol {
+    with_interned(name, |text, symbol| {
+        *symbol.get_or_insert_with(|| {
+            Symbol(Box::leak(Box::new(Name {
+                id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+                text: text.clone(),
+            })))
+        })
+    })
+}
+
+impl Symbol {
+    /// The interned name.
+    pub fn as_str(self) -> &'static str {
+        &self.0.text
+    }
+
+    /// The symbol's number, dense from 0 in interning order.
+    pub fn id(self) -> u32 {
+        self.0.id
+    }
+
+    pub(crate) fn text(self) -> &'static Arc<str> {
+        &self.0.text
+    }
+}
+
+impl PartialEq for Symbol {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.id == other.0.id
+    }
+}
+
+impl Eq for Symbol {}
+
+impl Hash for Symbol {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.id.hash(state);
+    }
+}
+
+impl PartialOrd for Symbol {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Symbol {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        self.0.id.cmp(&other.0.id)
+    }
+}
+
+impl fmt::Debug for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Symbol({}, {:?})", self.0.id, self.as_str())
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for Symbol {
+    fn from(name: &str) -> Self {
+        intern(name)
+    }
+}
+
+/// A table from symbols to values indexed by symbol number, so a lookup is
+/// one bounds-checked load: how a compiled class maps attribute names to
+/// its fields.
+#[derive(Debug, Clone)]
+pub struct SymbolMap<T> {
+    slots: Vec<Option<T>>,
+}
+
+impl<T> SymbolMap<T> {
+    pub fn get(&self, symbol: Symbol) -> Option<&T> {
+        self.slots.get(symbol.id() as usize)?.as_ref()
+    }
+}
+
+impl<'a, T> FromIterator<(&'a str, T)> for SymbolMap<T> {
+    fn from_iter<I: IntoIterator<Item = (&'a str, T)>>(iter: I) -> Self {
+        let mut slots: Vec<Option<T>> = Vec::new();
+        for (name, value) in iter {
+            let id = intern(name).id() as usize;
+            if slots.len() <= id {
+                slots.resize_with(id + 1, || None);
+            }
+            slots[id] = Some(value);
+        }
+        SymbolMap { slots }
+    }
+}
+
+/// Per-call-site cache behind [`py_intern!`](crate::py_intern): interns its
+/// name on first use and afterwards costs one atomic load.
+pub struct SymbolCell(AtomicPtr<Name>);
+
+impl SymbolCell {
+    pub const fn new() -> Self {
+        SymbolCell(AtomicPtr::new(ptr::null_mut()))
+    }
+
+    pub fn get(&self, name: &str) -> Symbol {
+        let cached = self.0.load(Ordering::Acquire);
+        if cached.is_null() {
+            let symbol = intern(name);
+            self.0
+                .store(ptr::from_ref(symbol.0).cast_mut(), Ordering::Release);
+            return symbol;
+        }
+        // SAFETY: the cell only ever holds pointers to leaked, never-freed names.
+        Symbol(unsafe { &*cached })
+    }
+}
+
+impl Default for SymbolCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The [`Symbol`] for a literal name, interned once per call site:
+/// `py_intern!("append")`.
+#[macro_export]
+macro_rules! py_intern {
+    ($name:literal) => {{
+        static SYMBOL: $crate::types::intern::SymbolCell = $crate::types::intern::SymbolCell::new();
+        SYMBOL.get($name)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interning_is_stable() {
+        let a = intern("intern_test_append");
+        assert_eq!(intern("intern_test_append"), a);
+        assert_ne!(intern("intern_test_extend"), a);
+        assert_eq!(a.as_str(), "intern_test_append");
+        let site = || crate::py_intern!("intern_test_append");
+        assert_eq!(site(), a);
+        assert_eq!(site(), a);
+        assert_eq!(
+            Symbol::from("intern_test_extend").to_string(),
+            "intern_test_extend"
+        );
+        let text = crate::types::PyStr::intern("intern_test_append");
+        assert!(text.ptr_eq(&a.into()));
+    }
+}
@@ -0,0 +1,287 @@
+//! Keyword arguments as a calling convention: call sites with `**kwargs` or
+//! optional keywords lower to one [`PyKwargs`] argument, and the callee
+//! pulls out the names it knows before rejecting the rest.
+
+use alloc::format;
+use alloc::string::{String, ToString};
+
+use super::function::{reject_kwargs, KwargsMap};
+use super::{PyStr, PyTruthy, PyValue};
+use crate::exceptions::{PyException, PyResult};
+
+/// Keyword arguments in call order. Extractors remove what they read, so
+/// [`reject_unknown`](PyKwargs::reject_unknown) sees only names the callee
+/// does not accept.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PyKwargs {
+    func: String,
+    items: KwargsMap,
+}
+
+impl PyKwargs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `key=value`, replacing an earlier value for the same name.
+    pub fn with<V: Into<PyValue>>(mut self, key: &str, value: V) -> Self {
+        self.set(key, value);
+        self
+    }
+
+    pub fn set<V: Into<PyValue>>(&mut self, key: &str, value: V) {
+        self.items.set_item(key.to_string(), value.into());
+    }
+
+    /// Names the called function for error messages, e.g. `dumps`.
+    pub fn for_call(mut self, func: &str) -> Self {
+        self.func = func.to_string();
+        self
+    }
+
+    /// The called function's name, as given to [`for_call`](Self::for_call).
+    pub fn func(&self) -> &str {
+        &self.func
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.items.contains_key(key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&PyValue> {
+        self.items.get(key)
+    }
+
+    /// Iterates `(name, value)` pairs in call order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (&String, &PyValue)> {
+        self.items.iter()
+    }
+
+    /// Removes and returns `key`'s value, if given.
+    pub fn pop(&mut self, key: &str) -> Option<PyValue> {
+        self.items.remove(key)
+    }
+
+    /// Python-equivalent kwargs.pop(key, default).
+    pub fn pop_with_default<V: Into<PyValue>>(&mut self, key: &str, default: V) -> PyValue {
+        self.pop(key).unwrap_or_else(|| default.into())
+    }
+
+    /// TypeError for `key` holding a value of the wrong type.
+    pub fn type_error(&self, key: &str, expected: &str, got: &PyValue) -> PyException {
+        PyException::type_error(format!(
+            "{}() argument '{}' must be {}, not {}",
+            self.func,
+            key,
+            expected,
+            got.type_name()
+        ))
+    }
+
+    /// Pops a str argument; an explicit None counts as absent.
+    pub fn get_str(&mut self, key: &str) -> PyResult<Option<String>> {
+        match self.pop(key) {
+            None | Some(PyValue::None) => Ok(None),
+            Some(PyValue::Str(s)) => Ok(Some(s.into_string())),
+            Some(other) => Err(self.type_error(key, "str", &other)),
+        }
+    }
+
+    /// Pops an int argument; bools count as 0 and 1, as in Python, and an
+    /// explicit None counts as absent.
+    pub fn get_int(&mut self, key: &str) -> PyResult<Option<i64>> {
+        match self.pop(key) {
+            None | Some(PyValue::None) => Ok(None),
+            Some(PyValue::Int(i)) => Ok(Some(i)),
+            Some(PyValue::Bool(b)) => Ok(Some(b as i64)),
+            Some(other) => Err(self.type_error(key, "int", &other)),
+        }
+    }
+
+    /// Pops a flag argument, which like any Python condition accepts every
+    /// value by its truthiness.
+    pub fn get_bool(&mut self, key: &str) -> Option<bool> {
+        self.pop(key).map(|value| value.py_bool())
+    }
+
+    /// Pops an argument of any [`FromKwarg`] type, raising TypeError for a
+    /// value of the wrong type.
+    pub fn extract<T: FromKwarg>(&mut self, key: &str) -> PyResult<Option<T>> {
+        match self.pop(key) {
+            None => Ok(None),
+            Some(value) => match T::from_kwarg(&value) {
+                Some(v) => Ok(Some(v)),
+                None => Err(self.type_error(key, T::EXPECTED, &value)),
+            },
+        }
+    }
+
+    /// TypeError naming the first keyword nobody extracted, as Python raises
+    /// for a name the signature does not have.
+    pub fn reject_unknown(&self) -> PyResult<()> {
+        reject_kwargs(&self.func, &self.items)
+    }
+}
+
+/// Conversion of a keyword argument to a parameter's Rust type, as used by
+/// the wrappers [`python_function!`](crate::python_function) generates.
+pub trait FromKwarg: Sized {
+    /// What the parameter accepts, for TypeError messages, e.g. `int`.
+    const EXPECTED: &'static str;
+
+    /// None for a value of the wrong type.
+    fn from_kwarg(value: &PyValue) -> Option<Self>;
+}
+
+impl FromKwarg for PyValue {
+    const EXPECTED: &'static str = "object";
+
+    fn from_kwarg(value: &PyValue) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+/// Any value, by truthiness, as for a Python flag.
+impl FromKwarg for bool {
+    const EXPECTED: &'static str = "bool";
+
+    fn from_kwarg(value: &PyValue) -> Option<Self> {
+        Some(value.py_bool())
+    }
+}
+
+impl FromKwarg for i64 {
+    const EXPECTED: &'static str = "int";
+
+    fn from_kwarg(value: &PyValue) -> Option<Self> {
+        match value {
+            PyValue::Int(i) => Some(*i),
+            PyValue::Bool(b) => Some(*b as i64),
+            _ => None,
+        }
+    }
+}
+
+/// Ints widen to float, as in Python arithmetic.
+impl FromKwarg for f64 {
+    const EXPECTED: &'static str = "float";
+
+    fn from_kwarg(value: &PyValue) -> Option<Self> {
+        match value {
+            PyValue::Float(f) => Some(*f),
+            PyValue::Int(i) => Some(*i as f64),
+            PyValue::Bool(b) => Some(*b as i64 as f64),
+            _ => None,
+        }
+    }
+}
+
+impl FromKwarg for String {
+    const EXPECTED: &'static str = "str";
+
+    fn from_kwarg(value: &PyValue) -> Option<Self> {
+        match value {
+            PyValue::Str(s) => Some(s.as_str().to_string()),
+            _ => None,
+        }
+    }
+}
+
+impl FromKwarg for PyStr {
+    const EXPECTED: &'static str = "str";
+
+    fn from_kwarg(value: &PyValue) -> Option<Self> {
+        match value {
+            PyValue::Str(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// An explicit None is `Some(None)`: the caller passed None.
+impl<T: FromKwarg> FromKwarg for Option<T> {
+    const EXPECTED: &'static str = T::EXPECTED;
+
+    fn from_kwarg(value: &PyValue) -> Option<Self> {
+        match value {
+            PyValue::None => Some(None),
+            other => T::from_kwarg(other).map(Some),
+        }
+    }
+}
+
+impl From<KwargsMap> for PyKwargs {
+    fn from(items: KwargsMap) -> Self {
+        PyKwargs {
+            func: String::new(),
+            items,
+        }
+    }
+}
+
+impl From<PyKwargs> for KwargsMap {
+    fn from(kwargs: PyKwargs) -> Self {
+        kwargs.items
+    }
+}
+
+impl<S: Into<String>, V: Into<PyValue>> FromIterator<(S, V)> for PyKwargs {
+    fn from_iter<I: IntoIterator<Item = (S, V)>>(iter: I) -> Self {
+        let items: KwargsMap = iter
+            .into_iter()
+            .map(|(k, v)| (k.into(), v.into()))
+            .collect();
+        PyKwargs::from(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extractors_consume_and_type_check() {
+        let mut kw = PyKwargs::new()
+            .with("indent", 4)
+            .with("sep", "; ")
+            .with("sort", PyValue::List(Default::default()))
+            .with("encoding", ())
+            .for_call("dumps");
+        assert_eq!(kw.get_int("indent").unwrap(), Some(4));
+        assert_eq!(kw.get_str("sep").unwrap().as_deref(), Some("; "));
+        assert_eq!(kw.get_str("encoding").unwrap(), None);
+        assert_eq!(kw.get_bool("sort"), Some(false));
+        assert_eq!(kw.get_int("missing").unwrap(), None);
+        assert!(kw.is_empty());
+        kw.reject_unknown().unwrap();
+
+        let mut kw = PyKwargs::new()
+            .with("indent", "x")
+            .with("b", 1)
+            .for_call("f");
+        let err = kw.get_int("indent").unwrap_err();
+        assert_eq!(err.message, "f() argument 'indent' must be int, not str");
+        assert_eq!(kw.pop_with_default("c", true), PyValue::Bool(true));
+        assert_eq!(
+            kw.reject_unknown().unwrap_err().to_string(),
+            "TypeError: f() got an unexpected keyword argument 'b'"
+        );
+    }
+
+    #[test]
+    fn keeps_call_order() {
+        let kw: PyKwargs = [("z", 1), ("a", 2), ("z", 3)].into_iter().collect();
+        let names: alloc::vec::Vec<&str> = kw.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(names, ["z", "a"]);
+        assert_eq!(kw.get("z"), Some(&PyValue::Int(3)));
+        assert_eq!(KwargsMap::from(kw).len(), 2);
+    }
+}
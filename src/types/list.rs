@@ -0,0 +1,252 @@
+//! Python list.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Index, IndexMut};
+
+use super::repr::PyRepr;
+use crate::exceptions::{PyException, PyResult};
+
+/// Python-equivalent list. Lists order lexicographically, like Python's:
+/// the first unequal pair of items decides, else the shorter list is less.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct PyList<T> {
+    items: Vec<T>,
+}
+
+/// Resolves a possibly-negative Python index against `len`.
+pub(crate) fn normalize_index(index: i64, len: usize) -> Option<usize> {
+    let len = len as i64;
+    let index = if index < 0 { index + len } else { index };
+    (0..len).contains(&index).then_some(index as usize)
+}
+
+impl<T> PyList<T> {
+    pub fn new() -> Self {
+        PyList { items: Vec::new() }
+    }
+
+    pub fn from_vec(items: Vec<T>) -> Self {
+        PyList { items }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        PyList {
+            items: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.items
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> core::slice::IterMut<'_, T> {
+        self.items.iter_mut()
+    }
+
+    /// Python-equivalent list[index], with negative indices counting from the end.
+    pub fn get(&self, index: i64) -> PyResult<&T> {
+        normalize_index(index, self.len())
+            .map(|i| &self.items[i])
+            .ok_or_else(|| PyException::index_error("list index out of range"))
+    }
+
+    /// Python-equivalent list[index] for in-place updates like `l[i] += 1`.
+    pub fn get_mut(&mut self, index: i64) -> PyResult<&mut T> {
+        normalize_index(index, self.len())
+            .map(|i| &mut self.items[i])
+            .ok_or_else(|| PyException::index_error("list index out of range"))
+    }
+
+    /// Python-equivalent list[index] = value.
+    pub fn set(&mut self, index: i64, value: T) -> PyResult<()> {
+        let i = normalize_index(index, self.len())
+            .ok_or_else(|| PyException::index_error("list assignment index out of range"))?;
+        self.items[i] = value;
+        Ok(())
+    }
+
+    /// Python-equivalent list.append(x).
+    pub fn append(&mut self, value: T) {
+        self.items.push(value);
+    }
+
+    /// Python-equivalent list.extend(iterable).
+    pub fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+
+    /// Python-equivalent list.insert(i, x): out-of-range indices clamp to the ends.
+    pub fn insert(&mut self, index: i64, value: T) {
+        let len = self.len() as i64;
+        let i = if index < 0 {
+            (index + len).max(0)
+        } else {
+            index.min(len)
+        };
+        self.items.insert(i as usize, value);
+    }
+
+    /// Python-equivalent list.pop([i]).
+    pub fn pop(&mut self, index: Option<i64>) -> PyResult<T> {
+        if self.items.is_empty() {
+            return Err(PyException::index_error("pop from empty list"));
+        }
+        let i = normalize_index(index.unwrap_or(-1), self.len())
+            .ok_or_else(|| PyException::index_error("pop index out of range"))?;
+        Ok(self.items.remove(i))
+    }
+
+    /// Python-equivalent list.clear().
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Python-equivalent list.reverse().
+    pub fn reverse(&mut self) {
+        self.items.reverse();
+    }
+}
+
+impl<T: PartialEq> PyList<T> {
+    /// Python-equivalent x in list.
+    pub fn contains(&self, value: &T) -> bool {
+        self.items.contains(value)
+    }
+
+    /// Python-equivalent list.index(x).
+    pub fn index(&self, value: &T) -> PyResult<usize> {
+        self.items
+            .iter()
+            .position(|v| v == value)
+            .ok_or_else(|| PyException::value_error("list.index(x): x not in list"))
+    }
+
+    /// Python-equivalent list.count(x).
+    pub fn count(&self, value: &T) -> usize {
+        self.items.iter().filter(|v| *v == value).count()
+    }
+
+    /// Python-equivalent list.remove(x): removes the first occurrence.
+    pub fn remove(&mut self, value: &T) -> PyResult<()> {
+        let i = self
+            .items
+            .iter()
+            .position(|v| v == value)
+            .ok_or_else(|| PyException::value_error("list.remove(x): x not in list"))?;
+        self.items.remove(i);
+        Ok(())
+    }
+}
+
+impl<T: Ord> PyList<T> {
+    /// Python-equivalent list.sort(reverse=...); stable like CPython.
+    pub fn sort(&mut self, reverse: bool) {
+        if reverse {
+            self.items.sort_by(|a, b| b.cmp(a));
+        } else {
+            self.items.sort();
+        }
+    }
+}
+
+impl<T> PyList<T> {
+    /// Python-equivalent list.sort(key=..., reverse=...); equal keys keep their order.
+    pub fn sort_by_key<K: Ord, F: FnMut(&T) -> K>(&mut self, mut key: F, reverse: bool) {
+        if reverse {
+            self.items.sort_by_key(|v| core::cmp::Reverse(key(v)));
+        } else {
+            self.items.sort_by_key(key);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PyList<T> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl<T> IntoIterator for PyList<T> {
+    type Item = T;
+    type IntoIter = alloc::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a mut PyList<T> {
+    type Item = &'a mut T;
+    type IntoIter = core::slice::IterMut<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter_mut()
+    }
+}
+
+impl<T> FromIterator<T> for PyList<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        PyList {
+            items: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> Extend<T> for PyList<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.items.extend(iter);
+    }
+}
+
+impl<T> From<Vec<T>> for PyList<T> {
+    fn from(items: Vec<T>) -> Self {
+        PyList { items }
+    }
+}
+
+/// `list[i]`, raising IndexError as a panic; [`PyList::get`] is the
+/// checked form.
+impl<T> Index<i64> for PyList<T> {
+    type Output = T;
+
+    fn index(&self, index: i64) -> &T {
+        self.get(index).unwrap_or_else(|err| err.raise())
+    }
+}
+
+impl<T> IndexMut<i64> for PyList<T> {
+    fn index_mut(&mut self, index: i64) -> &mut T {
+        self.get_mut(index).unwrap_or_else(|err| err.raise())
+    }
+}
+
+/// Python-equivalent str(list), which shows each element's repr.
+impl<T: PyRepr> fmt::Display for PyList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.py_repr())
+    }
+}
@@ -0,0 +1,234 @@
+//! Python memoryview: zero-copy views into a shared byte buffer.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::list::normalize_index;
+use super::{PyBytes, PyList, PySlice};
+use crate::exceptions::{PyException, PyResult};
+
+/// Python-equivalent memoryview over a byte buffer. Slicing returns another
+/// view of the same buffer, so parsers can cut up large inputs without
+/// copying; [`tobytes`](Self::tobytes) copies out when needed.
+#[derive(Clone)]
+pub struct PyMemoryView {
+    buf: Arc<[u8]>,
+    start: i64,
+    step: i64,
+    len: usize,
+}
+
+impl PyMemoryView {
+    pub fn new<B: Into<Arc<[u8]>>>(buf: B) -> Self {
+        let buf = buf.into();
+        let len = buf.len();
+        PyMemoryView {
+            buf,
+            start: 0,
+            step: 1,
+            len,
+        }
+    }
+
+    /// Python-equivalent len(view): the number of bytes the view selects.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Python-equivalent view.nbytes.
+    pub fn nbytes(&self) -> usize {
+        self.len
+    }
+
+    /// Python-equivalent view.c_contiguous: whether the view is one run of
+    /// the buffer rather than a strided selection.
+    pub fn c_contiguous(&self) -> bool {
+        self.step == 1 || self.len <= 1
+    }
+
+    fn offset(&self, i: usize) -> usize {
+        (self.start + i as i64 * self.step) as usize
+    }
+
+    /// Python-equivalent view[i].
+    pub fn get(&self, index: i64) -> PyResult<u8> {
+        normalize_index(index, self.len)
+            .map(|i| self.buf[self.offset(i)])
+            .ok_or_else(|| PyException::index_error("index out of bounds on dimension 1"))
+    }
+
+    /// Python-equivalent view[start:stop:step]. The result shares this
+    /// view's buffer.
+    pub fn slice(&self, slice: &PySlice) -> PyResult<PyMemoryView> {
+        let sub = slice.indices(self.len)?;
+        Ok(PyMemoryView {
+            buf: self.buf.clone(),
+            start: self.start + sub.start * self.step,
+            step: self.step * sub.step,
+            len: sub.len,
+        })
+    }
+
+    /// The viewed bytes as one borrowed slice, when the view is contiguous.
+    pub fn as_contiguous(&self) -> Option<&[u8]> {
+        match self.len {
+            0 => Some(&[]),
+            _ if self.c_contiguous() => {
+                let start = self.start as usize;
+                Some(&self.buf[start..start + self.len])
+            }
+            _ => None,
+        }
+    }
+
+    /// The viewed bytes for writing, as readinto() needs. Like a view of
+    /// bytes, a view is read-only once its buffer is shared with another
+    /// view.
+    pub fn as_mut_slice(&mut self) -> PyResult<&mut [u8]> {
+        if !self.c_contiguous() {
+            return Err(PyException::new(
+                "BufferError",
+                "memoryview: underlying buffer is not C-contiguous",
+            ));
+        }
+        let (start, len) = (self.start.max(0) as usize, self.len);
+        match Arc::get_mut(&mut self.buf) {
+            Some(buf) if len == 0 => Ok(&mut buf[..0]),
+            Some(buf) => Ok(&mut buf[start..start + len]),
+            None => Err(PyException::type_error("cannot modify read-only memory")),
+        }
+    }
+
+    /// The viewed bytes in order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = u8> + '_ {
+        (0..self.len).map(move |i| self.buf[self.offset(i)])
+    }
+
+    /// Python-equivalent view.tobytes(), which copies.
+    pub fn tobytes(&self) -> PyBytes {
+        match self.as_contiguous() {
+            Some(bytes) => PyBytes::from(bytes),
+            None => PyBytes::from(self.iter().collect::<Vec<u8>>()),
+        }
+    }
+
+    /// Python-equivalent view.tolist().
+    pub fn tolist(&self) -> PyList<i64> {
+        self.iter().map(i64::from).collect()
+    }
+
+    /// Python-equivalent view.hex().
+    pub fn hex(&self) -> String {
+        self.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+}
+
+impl From<PyBytes> for PyMemoryView {
+    fn from(bytes: PyBytes) -> Self {
+        PyMemoryView::new(bytes.into_vec())
+    }
+}
+
+impl From<Vec<u8>> for PyMemoryView {
+    fn from(data: Vec<u8>) -> Self {
+        PyMemoryView::new(data)
+    }
+}
+
+impl From<&[u8]> for PyMemoryView {
+    fn from(data: &[u8]) -> Self {
+        PyMemoryView::new(data)
+    }
+}
+
+/// Python-equivalent bytes(view).
+impl From<&PyMemoryView> for PyBytes {
+    fn from(view: &PyMemoryView) -> Self {
+        view.tobytes()
+    }
+}
+
+/// Views compare by the bytes they select, as in Python.
+impl PartialEq for PyMemoryView {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl PartialEq<[u8]> for PyMemoryView {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.len == other.len() && self.iter().eq(other.iter().copied())
+    }
+}
+
+impl PartialEq<PyBytes> for PyMemoryView {
+    fn eq(&self, other: &PyBytes) -> bool {
+        *self == *other.as_bytes()
+    }
+}
+
+impl fmt::Debug for PyMemoryView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PyMemoryView({})", self.tobytes())
+    }
+}
+
+/// Formats as `<memory at 0x...>`, like repr(view).
+impl fmt::Display for PyMemoryView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<memory at {:p}>", self.buf.as_ptr())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slices_share_the_buffer() {
+        let data = PyBytes::from(b"header:payload".to_vec());
+        let view = PyMemoryView::from(data);
+        let payload = view.slice(&PySlice::new(Some(7), None, None)).unwrap();
+        assert_eq!(payload, *b"payload".as_slice());
+        assert_eq!(payload.as_contiguous().unwrap().as_ptr(), unsafe {
+            view.as_contiguous().unwrap().as_ptr().add(7)
+        });
+        let odd = payload.slice(&PySlice::new(None, None, Some(-2))).unwrap();
+        assert_eq!(odd.tobytes(), PyBytes::from(b"doyp".to_vec()));
+        assert!(odd.as_contiguous().is_none());
+        assert_eq!(odd.get(-1).unwrap(), b'p');
+        assert_eq!(
+            odd.get(4).unwrap_err().to_string(),
+            "IndexError: index out of bounds on dimension 1"
+        );
+        assert_eq!(
+            PyBytes::from(&odd.slice(&PySlice::new(Some(1), Some(3), None)).unwrap()),
+            PyBytes::from(b"oy".to_vec())
+        );
+        assert_eq!(
+            view.slice(&PySlice::new(Some(0), Some(2), None))
+                .unwrap()
+                .hex(),
+            "6865"
+        );
+    }
+
+    #[test]
+    fn writable_until_shared() {
+        let mut view = PyMemoryView::from(vec![0u8; 4]);
+        view.as_mut_slice().unwrap().copy_from_slice(b"abcd");
+        let head = view.slice(&PySlice::new(None, Some(2), None)).unwrap();
+        assert_eq!(
+            view.as_mut_slice().unwrap_err().to_string(),
+            "TypeError: cannot modify read-only memory"
+        );
+        assert_eq!(head.tolist().into_vec(), [97, 98]);
+    }
+}
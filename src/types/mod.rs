@@ -0,0 +1,50 @@
+//! Rust representations of Python's built-in types.
+
+pub mod bytes;
+pub mod compare;
+pub mod dict;
+#[cfg(feature = "heapless")]
+pub mod fixed;
+pub mod format;
+pub mod function;
+pub mod hash;
+pub mod intern;
+pub mod kwargs;
+pub mod list;
+pub mod memoryview;
+pub mod object;
+pub mod ops;
+pub mod protocol;
+pub mod range;
+pub mod repr;
+pub mod set;
+pub mod slice;
+pub mod string;
+pub(crate) mod table;
+pub mod tuple;
+pub mod typeobj;
+pub mod value;
+
+pub use bytes::PyBytes;
+pub use compare::{CompareOp, PyRichCompare};
+pub use dict::{ItemsView, KeysView, PyDictionary, ValuesView};
+#[cfg(feature = "heapless")]
+pub use fixed::{FixedList, FixedStr};
+pub use format::{Conversion, PyFormat};
+pub use function::{FunctionEntry, IntoPyReturn, KwargsMap, PyCallable, PyFunction};
+pub use hash::{hash_seed, set_hash_seed, HashSeed, PyHash};
+pub use intern::{intern, Symbol};
+pub use kwargs::{FromKwarg, PyKwargs};
+pub use list::PyList;
+pub use memoryview::PyMemoryView;
+pub use object::{InstanceDict, PyObjectProtocol};
+pub use ops::{PyAdd, PyZero};
+pub use protocol::{PyContains, PyIterable, PyLen, PyMapping, PySequence, PyTruthy};
+pub use range::PyRange;
+pub use repr::PyRepr;
+pub use set::PySet;
+pub use slice::{PySlice, SliceIndices, SliceIter};
+pub use string::PyStr;
+pub use tuple::PyTuple;
+pub use typeobj::{lookup_class, register_class, PyTypeId, PyTyped};
+pub use value::PyValue;
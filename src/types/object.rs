@@ -0,0 +1,84 @@
+//! The attribute protocol compiled classes implement: getattr(), setattr(),
+//! delattr() and dir().
+
+use super::{PyDictionary, PyTyped, PyValue, Symbol};
+use crate::exceptions::{PyException, PyResult};
+use alloc::format;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+/// Per-instance attribute storage, the equivalent of an object's `__dict__`.
+pub type InstanceDict = PyDictionary<String, PyValue>;
+
+/// AttributeError with CPython's wording for a missing instance attribute.
+pub fn no_attribute(type_name: &str, name: &str) -> PyException {
+    PyException::attribute_error(format!(
+        "'{}' object has no attribute '{}'",
+        type_name, name
+    ))
+}
+
+/// Python-equivalent `__getattr__`/`__setattr__`/`__delattr__`/`__dir__`.
+///
+/// Compiler-generated structs override the methods to expose their declared
+/// fields and fall through to the default implementations, which consult
+/// [`instance_dict`](PyObjectProtocol::instance_dict). A class without a
+/// `__dict__` (like one with `__slots__`) keeps the default `None` and rejects
+/// unknown attributes.
+///
+/// The `*_sym` forms take an interned [`Symbol`], so compiled code can match
+/// field names by integer; they default to the string forms.
+pub trait PyObjectProtocol: PyTyped {
+    fn instance_dict(&self) -> Option<&InstanceDict> {
+        None
+    }
+
+    fn instance_dict_mut(&mut self) -> Option<&mut InstanceDict> {
+        None
+    }
+
+    fn get_attr(&self, name: &str) -> PyResult<PyValue> {
+        self.instance_dict()
+            .and_then(|dict| dict.get(&name.to_string()).cloned())
+            .ok_or_else(|| no_attribute(self.py_type().name(), name))
+    }
+
+    fn set_attr(&mut self, name: &str, value: PyValue) -> PyResult<()> {
+        let type_name = self.py_type().name();
+        match self.instance_dict_mut() {
+            Some(dict) => {
+                dict.set_item(name.to_string(), value);
+                Ok(())
+            }
+            None => Err(no_attribute(type_name, name)),
+        }
+    }
+
+    fn del_attr(&mut self, name: &str) -> PyResult<()> {
+        let type_name = self.py_type().name();
+        self.instance_dict_mut()
+            .and_then(|dict| dict.remove(&name.to_string()))
+            .map(|_| ())
+            .ok_or_else(|| no_attribute(type_name, name))
+    }
+
+    fn get_attr_sym(&self, name: Symbol) -> PyResult<PyValue> {
+        self.get_attr(name.as_str())
+    }
+
+    fn set_attr_sym(&mut self, name: Symbol, value: PyValue) -> PyResult<()> {
+        self.set_attr(name.as_str(), value)
+    }
+
+    fn del_attr_sym(&mut self, name: Symbol) -> PyResult<()> {
+        self.del_attr(name.as_str())
+    }
+
+    /// Attribute names, in any order; dir() sorts and deduplicates them.
+    fn dir(&self) -> Vec<String> {
+        self.instance_dict()
+            .map(|d| d.keys().to_list())
+            .unwrap_or_default()
+    }
+}
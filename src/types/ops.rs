@@ -0,0 +1,360 @@
+//! Binary operators with Python semantics.
+
+use super::{PyList, PyStr, PyTuple, PyTyped, PyValue};
+use crate::exceptions::{PyException, PyResult};
+use alloc::format;
+use alloc::string::String;
+
+/// Python-equivalent `__add__`.
+pub trait PyAdd: Sized {
+    fn py_add(self, rhs: Self) -> PyResult<Self>;
+
+    /// sum(items, start). Adds left to right by default; numbers override it
+    /// to sum exactly or with compensation.
+    fn py_sum<I: Iterator<Item = Self>>(start: Self, mut items: I) -> PyResult<Self> {
+        items.try_fold(start, Self::py_add)
+    }
+}
+
+/// Types whose additive identity is what sum() starts from by default
+/// (Python's `start=0`).
+pub trait PyZero {
+    fn py_zero() -> Self;
+}
+
+/// TypeError for an operator applied to unsupported operand types.
+pub fn unsupported_operands<A: PyTyped + ?Sized, B: PyTyped + ?Sized>(
+    op: &str,
+    lhs: &A,
+    rhs: &B,
+) -> PyException {
+    PyException::type_error(format!(
+        "unsupported operand type(s) for {}: '{}' and '{}'",
+        op,
+        lhs.py_type().name(),
+        rhs.py_type().name()
+    ))
+}
+
+/// TypeError for an ordering comparison between unorderable values.
+pub fn unorderable<A: PyTyped + ?Sized, B: PyTyped + ?Sized>(
+    op: &str,
+    lhs: &A,
+    rhs: &B,
+) -> PyException {
+    PyException::type_error(format!(
+        "'{}' not supported between instances of '{}' and '{}'",
+        op,
+        lhs.py_type().name(),
+        rhs.py_type().name()
+    ))
+}
+
+fn overflow() -> PyException {
+    PyException::new("OverflowError", "integer addition overflowed")
+}
+
+/// A running float sum with Neumaier compensation, as CPython 3.12's sum()
+/// keeps for floats: the low-order bits each addition rounds away are
+/// collected separately and added back at the end.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FloatSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl FloatSum {
+    pub fn new(start: f64) -> Self {
+        FloatSum {
+            sum: start,
+            compensation: 0.0,
+        }
+    }
+
+    pub fn add(&mut self, x: f64) {
+        let t = self.sum + x;
+        if self.sum.abs() >= x.abs() {
+            self.compensation += (self.sum - t) + x;
+        } else {
+            self.compensation += (x - t) + self.sum;
+        }
+        self.sum = t;
+    }
+
+    pub fn total(&self) -> f64 {
+        // An infinite or NaN sum has no meaningful correction.
+        if self.compensation != 0.0 && self.compensation.is_finite() {
+            self.sum + self.compensation
+        } else {
+            self.sum
+        }
+    }
+}
+
+macro_rules! impl_py_add_int {
+    // Narrow enough to sum exactly in an i128, so only the total has to fit.
+    (wide: $($t:ty),*) => {
+        $(impl PyAdd for $t {
+            fn py_add(self, rhs: Self) -> PyResult<Self> {
+                self.checked_add(rhs).ok_or_else(overflow)
+            }
+
+            fn py_sum<I: Iterator<Item = Self>>(start: Self, items: I) -> PyResult<Self> {
+                let total = items.fold(start as i128, |acc, item| acc + item as i128);
+                <$t>::try_from(total).map_err(|_| overflow())
+            }
+        }
+
+        impl PyZero for $t {
+            fn py_zero() -> Self {
+                0
+            }
+        })*
+    };
+    ($($t:ty),*) => {
+        $(impl PyAdd for $t {
+            fn py_add(self, rhs: Self) -> PyResult<Self> {
+                self.checked_add(rhs).ok_or_else(overflow)
+            }
+        }
+
+        impl PyZero for $t {
+            fn py_zero() -> Self {
+                0
+            }
+        })*
+    };
+}
+
+impl_py_add_int!(wide: i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+impl_py_add_int!(i128, u128);
+
+impl PyAdd for f64 {
+    fn py_add(self, rhs: Self) -> PyResult<Self> {
+        Ok(self + rhs)
+    }
+
+    fn py_sum<I: Iterator<Item = Self>>(start: Self, items: I) -> PyResult<Self> {
+        let mut total = FloatSum::new(start);
+        items.for_each(|x| total.add(x));
+        Ok(total.total())
+    }
+}
+
+impl PyZero for f64 {
+    fn py_zero() -> Self {
+        0.0
+    }
+}
+
+impl PyAdd for f32 {
+    fn py_add(self, rhs: Self) -> PyResult<Self> {
+        Ok(self + rhs)
+    }
+
+    fn py_sum<I: Iterator<Item = Self>>(start: Self, items: I) -> PyResult<Self> {
+        f64::py_sum(start as f64, items.map(f64::from)).map(|total| total as f32)
+    }
+}
+
+impl PyZero for f32 {
+    fn py_zero() -> Self {
+        0.0
+    }
+}
+
+impl PyAdd for String {
+    fn py_add(mut self, rhs: Self) -> PyResult<Self> {
+        self.push_str(&rhs);
+        Ok(self)
+    }
+}
+
+impl PyAdd for PyStr {
+    fn py_add(self, rhs: Self) -> PyResult<Self> {
+        let mut out = self;
+        out.push_str(&rhs);
+        Ok(out)
+    }
+}
+
+impl<T> PyAdd for PyList<T> {
+    fn py_add(mut self, rhs: Self) -> PyResult<Self> {
+        self.extend(rhs.into_vec());
+        Ok(self)
+    }
+}
+
+impl<T> PyAdd for PyTuple<T> {
+    fn py_add(self, rhs: Self) -> PyResult<Self> {
+        let mut items = self.into_vec();
+        items.extend(rhs.into_vec());
+        Ok(PyTuple::from_vec(items))
+    }
+}
+
+fn value_int(v: &PyValue) -> Option<i64> {
+    match v {
+        PyValue::Bool(b) => Some(*b as i64),
+        PyValue::Int(i) => Some(*i),
+        _ => None,
+    }
+}
+
+impl PyAdd for PyValue {
+    fn py_add(self, rhs: Self) -> PyResult<Self> {
+        let int = value_int;
+        match (int(&self), int(&rhs)) {
+            (Some(a), Some(b)) => return a.py_add(b).map(PyValue::Int),
+            (Some(a), None) if matches!(rhs, PyValue::Float(_)) => {
+                return PyValue::Float(a as f64).py_add(rhs)
+            }
+            (None, Some(b)) if matches!(self, PyValue::Float(_)) => {
+                return self.py_add(PyValue::Float(b as f64))
+            }
+            _ => {}
+        }
+        match (self, rhs) {
+            (PyValue::Float(a), PyValue::Float(b)) => Ok(PyValue::Float(a + b)),
+            (PyValue::Str(a), PyValue::Str(b)) => a.py_add(b).map(PyValue::Str),
+            (PyValue::Bytes(a), PyValue::Bytes(b)) => {
+                let mut data = a.into_vec();
+                data.extend_from_slice(&b);
+                Ok(PyValue::Bytes(data.into()))
+            }
+            (PyValue::List(a), PyValue::List(b)) => a.py_add(b).map(PyValue::List),
+            (PyValue::Tuple(a), PyValue::Tuple(b)) => a.py_add(b).map(PyValue::Tuple),
+            (a, b) => Err(unsupported_operands("+", &a, &b)),
+        }
+    }
+
+    /// CPython's fast paths: a run of ints is summed exactly, and a run of
+    /// floats (ints included) with compensation. Anything else falls back
+    /// to adding one item at a time.
+    fn py_sum<I: Iterator<Item = Self>>(start: Self, mut items: I) -> PyResult<Self> {
+        let mut acc = start;
+        if let Some(start) = value_int(&acc) {
+            let mut total = start as i128;
+            let mut summed = false;
+            loop {
+                let Some(item) = items.next() else {
+                    return if summed {
+                        i64::try_from(total)
+                            .map(PyValue::Int)
+                            .map_err(|_| overflow())
+                    } else {
+                        Ok(acc)
+                    };
+                };
+                match value_int(&item) {
+                    Some(i) => {
+                        total += i as i128;
+                        summed = true;
+                    }
+                    None => {
+                        if summed {
+                            acc = PyValue::Int(i64::try_from(total).map_err(|_| overflow())?);
+                        }
+                        acc = acc.py_add(item)?;
+                        break;
+                    }
+                }
+            }
+        }
+        if let PyValue::Float(start) = acc {
+            let mut total = FloatSum::new(start);
+            loop {
+                match items.next() {
+                    Some(PyValue::Float(x)) => total.add(x),
+                    Some(item) => match value_int(&item) {
+                        Some(i) => total.add(i as f64),
+                        None => {
+                            acc = PyValue::Float(total.total()).py_add(item)?;
+                            break;
+                        }
+                    },
+                    None => return Ok(PyValue::Float(total.total())),
+                }
+            }
+        }
+        items.try_fold(acc, PyValue::py_add)
+    }
+}
+
+impl PyZero for PyValue {
+    fn py_zero() -> Self {
+        PyValue::Int(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn value_addition_promotes_like_python() {
+        assert_eq!(
+            PyValue::Int(1).py_add(PyValue::Bool(true)).unwrap(),
+            PyValue::Int(2)
+        );
+        assert_eq!(
+            PyValue::Int(1).py_add(PyValue::Float(0.5)).unwrap(),
+            PyValue::Float(1.5)
+        );
+        assert_eq!(
+            PyValue::from("a").py_add(PyValue::from("b")).unwrap(),
+            PyValue::from("ab")
+        );
+        let err = PyValue::Int(1).py_add(PyValue::from("b")).unwrap_err();
+        assert_eq!(
+            err.message,
+            "unsupported operand type(s) for +: 'int' and 'str'"
+        );
+        assert!(PyValue::Int(i64::MAX)
+            .py_add(PyValue::Int(1))
+            .unwrap_err()
+            .is("OverflowError"));
+    }
+
+    #[test]
+    fn sums_are_exact_or_compensated() {
+        let ints = [i64::MAX, 1, -1];
+        assert_eq!(i64::py_sum(0, ints.into_iter()).unwrap(), i64::MAX);
+        assert!(i64::py_sum(i64::MAX, [1].into_iter())
+            .unwrap_err()
+            .is("OverflowError"));
+        assert!(u8::py_sum(0, [200, 100, 200].into_iter()).is_err());
+        assert_eq!(
+            f64::py_sum(0.0, [0.1; 10].into_iter()).unwrap(),
+            1.0,
+            "naive summation gives 0.9999999999999999"
+        );
+        assert_eq!(
+            f64::py_sum(0.0, [1e100, 1.0, -1e100].into_iter()).unwrap(),
+            1.0
+        );
+
+        let values = [PyValue::Int(i64::MAX), PyValue::Int(1), PyValue::Int(-1)];
+        assert_eq!(
+            PyValue::py_sum(PyValue::Int(0), values.into_iter()).unwrap(),
+            PyValue::Int(i64::MAX)
+        );
+        let mixed = [PyValue::Int(1), PyValue::Float(0.1), PyValue::Bool(true)]
+            .into_iter()
+            .chain(core::iter::repeat_n(PyValue::Float(0.1), 9));
+        assert_eq!(
+            PyValue::py_sum(PyValue::Int(0), mixed).unwrap(),
+            PyValue::Float(3.0)
+        );
+        assert_eq!(
+            PyValue::py_sum(PyValue::Bool(true), core::iter::empty()).unwrap(),
+            PyValue::Bool(true)
+        );
+        assert!(PyValue::py_sum(
+            PyValue::Int(0),
+            [PyValue::Float(1.0), PyValue::from("a")].into_iter()
+        )
+        .unwrap_err()
+        .is("TypeError"));
+    }
+}
@@ -0,0 +1,430 @@
+//! The container protocols behind truth testing, len(), `in`, iteration and
+//! subscripting.
+//!
+//! Each trait stands for one group of dunder methods, so builtins and
+//! generated code can take any container generically instead of matching on
+//! concrete types: `len(x)` becomes [`PyLen::py_len`], `y in x` becomes
+//! [`PyContains::py_contains`] and `for v in x` walks [`PyIterable::py_iter`].
+
+use core::hash::Hash;
+use core::str::Chars;
+
+use super::dict::Keys;
+use super::range::RangeIter;
+use super::{
+    PyBytes, PyDictionary, PyList, PyMemoryView, PyRange, PyRepr, PySet, PyStr, PyTuple, PyValue,
+};
+use crate::exceptions::{PyException, PyResult};
+
+/// Python `__bool__`: what `if x:`, `not x` and bool(x) test.
+pub trait PyTruthy {
+    fn py_bool(&self) -> bool;
+}
+
+/// Python `__len__`.
+pub trait PyLen {
+    fn py_len(&self) -> usize;
+}
+
+/// Python `__contains__` for needles of type `Q`: elements for sequences and
+/// sets, keys for mappings, substrings for str and bytes.
+pub trait PyContains<Q: ?Sized> {
+    fn py_contains(&self, item: &Q) -> bool;
+}
+
+/// Python `__iter__`. Mappings iterate their keys, str its characters,
+/// bytes its integer values.
+pub trait PyIterable {
+    type Item<'a>
+    where
+        Self: 'a;
+    type Iter<'a>: Iterator<Item = Self::Item<'a>>
+    where
+        Self: 'a;
+
+    fn py_iter(&self) -> Self::Iter<'_>;
+}
+
+/// Integer-indexed containers: `x[i]` with negative indices counting from
+/// the end. Items come back the way [`PyIterable`] yields them.
+pub trait PySequence: PyLen + PyIterable {
+    fn py_getitem(&self, index: i64) -> PyResult<Self::Item<'_>>;
+}
+
+/// Key-indexed containers: `x[key]` raises KeyError when the key is missing.
+pub trait PyMapping: PyLen + PyIterable {
+    type Key;
+    type Value;
+
+    fn py_getitem(&self, key: &Self::Key) -> PyResult<&Self::Value>;
+}
+
+impl<T: PyTruthy + ?Sized> PyTruthy for &T {
+    fn py_bool(&self) -> bool {
+        (**self).py_bool()
+    }
+}
+
+impl<T: PyLen + ?Sized> PyLen for &T {
+    fn py_len(&self) -> usize {
+        (**self).py_len()
+    }
+}
+
+macro_rules! impl_py_truthy_nonzero {
+    ($($t:ty),*) => {
+        $(impl PyTruthy for $t {
+            fn py_bool(&self) -> bool {
+                *self != 0 as $t
+            }
+        })*
+    };
+}
+
+impl_py_truthy_nonzero!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64);
+
+impl PyTruthy for bool {
+    fn py_bool(&self) -> bool {
+        *self
+    }
+}
+
+impl PyTruthy for () {
+    fn py_bool(&self) -> bool {
+        false
+    }
+}
+
+impl<T: PyTruthy> PyTruthy for Option<T> {
+    fn py_bool(&self) -> bool {
+        self.as_ref().is_some_and(PyTruthy::py_bool)
+    }
+}
+
+impl PyTruthy for PyValue {
+    fn py_bool(&self) -> bool {
+        match self {
+            PyValue::None => false,
+            PyValue::Bool(b) => *b,
+            PyValue::Int(i) => *i != 0,
+            PyValue::Float(f) => *f != 0.0,
+            PyValue::Str(s) => !s.is_empty(),
+            PyValue::Bytes(b) => !b.is_empty(),
+            PyValue::List(l) => !l.is_empty(),
+            PyValue::Tuple(t) => !t.is_empty(),
+            PyValue::Dict(d) => !d.is_empty(),
+            PyValue::Set(s) => !s.is_empty(),
+            PyValue::Function(_) | PyValue::NotImplemented => true,
+        }
+    }
+}
+
+/// Containers are true when non-empty.
+macro_rules! impl_py_len {
+    ($([$($gen:tt)*] $t:ty),* $(,)?) => {
+        $(
+            impl<$($gen)*> PyLen for $t {
+                fn py_len(&self) -> usize {
+                    self.len()
+                }
+            }
+
+            impl<$($gen)*> PyTruthy for $t {
+                fn py_bool(&self) -> bool {
+                    !self.is_empty()
+                }
+            }
+        )*
+    };
+}
+
+impl_py_len!(
+    [] PyStr,
+    [] PyBytes,
+    [] PyMemoryView,
+    [] PyRange,
+    [T] PyList<T>,
+    [T] PyTuple<T>,
+    [T] PySet<T>,
+    [K, V] PyDictionary<K, V>,
+);
+
+/// len(s) counts code points, not UTF-8 bytes.
+impl PyLen for str {
+    fn py_len(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl PyTruthy for str {
+    fn py_bool(&self) -> bool {
+        !self.is_empty()
+    }
+}
+
+impl PyContains<str> for str {
+    fn py_contains(&self, item: &str) -> bool {
+        self.contains(item)
+    }
+}
+
+impl PyContains<str> for PyStr {
+    fn py_contains(&self, item: &str) -> bool {
+        self.as_str().contains(item)
+    }
+}
+
+impl PyContains<PyStr> for PyStr {
+    fn py_contains(&self, item: &PyStr) -> bool {
+        self.as_str().contains(item.as_str())
+    }
+}
+
+/// `b in data` for a single byte value.
+impl PyContains<u8> for PyBytes {
+    fn py_contains(&self, item: &u8) -> bool {
+        self.as_bytes().contains(item)
+    }
+}
+
+/// `sub in data` for a byte string, matched as a contiguous run.
+impl PyContains<[u8]> for PyBytes {
+    fn py_contains(&self, item: &[u8]) -> bool {
+        item.is_empty() || self.as_bytes().windows(item.len()).any(|w| w == item)
+    }
+}
+
+impl PyContains<i64> for PyRange {
+    fn py_contains(&self, item: &i64) -> bool {
+        self.contains(item)
+    }
+}
+
+impl<T: PartialEq> PyContains<T> for PyList<T> {
+    fn py_contains(&self, item: &T) -> bool {
+        self.contains(item)
+    }
+}
+
+impl<T: PartialEq> PyContains<T> for PyTuple<T> {
+    fn py_contains(&self, item: &T) -> bool {
+        self.contains(item)
+    }
+}
+
+impl<T: Hash + Eq> PyContains<T> for PySet<T> {
+    fn py_contains(&self, item: &T) -> bool {
+        self.contains(item)
+    }
+}
+
+impl<K: Hash + Eq, V> PyContains<K> for PyDictionary<K, V> {
+    fn py_contains(&self, item: &K) -> bool {
+        self.contains_key(item)
+    }
+}
+
+impl PyIterable for PyStr {
+    type Item<'a> = char;
+    type Iter<'a> = Chars<'a>;
+
+    fn py_iter(&self) -> Chars<'_> {
+        self.as_str().chars()
+    }
+}
+
+impl PyIterable for PyBytes {
+    type Item<'a> = u8;
+    type Iter<'a> = core::iter::Copied<core::slice::Iter<'a, u8>>;
+
+    fn py_iter(&self) -> Self::Iter<'_> {
+        self.as_bytes().iter().copied()
+    }
+}
+
+impl PyIterable for PyRange {
+    type Item<'a> = i64;
+    type Iter<'a> = RangeIter;
+
+    fn py_iter(&self) -> RangeIter {
+        self.iter()
+    }
+}
+
+impl<T> PyIterable for PyList<T> {
+    type Item<'a>
+        = &'a T
+    where
+        T: 'a;
+    type Iter<'a>
+        = core::slice::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn py_iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+}
+
+impl<T> PyIterable for PyTuple<T> {
+    type Item<'a>
+        = &'a T
+    where
+        T: 'a;
+    type Iter<'a>
+        = core::slice::Iter<'a, T>
+    where
+        T: 'a;
+
+    fn py_iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+}
+
+impl<T> PyIterable for PySet<T> {
+    type Item<'a>
+        = &'a T
+    where
+        T: 'a;
+    type Iter<'a>
+        = Keys<'a, T, ()>
+    where
+        T: 'a;
+
+    fn py_iter(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+}
+
+impl<K, V> PyIterable for PyDictionary<K, V> {
+    type Item<'a>
+        = &'a K
+    where
+        Self: 'a;
+    type Iter<'a>
+        = Keys<'a, K, V>
+    where
+        Self: 'a;
+
+    fn py_iter(&self) -> Self::Iter<'_> {
+        self.iter_keys()
+    }
+}
+
+impl PySequence for PyStr {
+    fn py_getitem(&self, index: i64) -> PyResult<char> {
+        let len = self.len() as i64;
+        let i = if index < 0 { index + len } else { index };
+        usize::try_from(i)
+            .ok()
+            .and_then(|i| self.as_str().chars().nth(i))
+            .ok_or_else(|| PyException::index_error("string index out of range"))
+    }
+}
+
+impl PySequence for PyBytes {
+    fn py_getitem(&self, index: i64) -> PyResult<u8> {
+        super::list::normalize_index(index, self.len())
+            .map(|i| self.as_bytes()[i])
+            .ok_or_else(|| PyException::index_error("index out of range"))
+    }
+}
+
+impl PySequence for PyRange {
+    fn py_getitem(&self, index: i64) -> PyResult<i64> {
+        self.get(index)
+    }
+}
+
+impl<T> PySequence for PyList<T> {
+    fn py_getitem(&self, index: i64) -> PyResult<&T> {
+        self.get(index)
+    }
+}
+
+impl<T> PySequence for PyTuple<T> {
+    fn py_getitem(&self, index: i64) -> PyResult<&T> {
+        self.get(index)
+    }
+}
+
+impl<K: Hash + Eq + PyRepr, V> PyMapping for PyDictionary<K, V> {
+    type Key = K;
+    type Value = V;
+
+    fn py_getitem(&self, key: &K) -> PyResult<&V> {
+        self.get(key).ok_or_else(|| PyException::key_error(key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn count_true<C>(container: &C) -> usize
+    where
+        C: PyIterable,
+        for<'a> C::Item<'a>: PyTruthy,
+    {
+        container.py_iter().filter(|v| v.py_bool()).count()
+    }
+
+    #[test]
+    fn index_operators_take_negative_indices() {
+        let mut l = PyList::from_vec(vec![1, 2, 3]);
+        l[-1] += 10;
+        l[0] = 0;
+        assert_eq!(l.as_slice(), [0, 2, 13]);
+        let t = PyTuple::from_vec(vec!["a", "b"]);
+        assert_eq!((t[0], t[-1]), ("a", "b"));
+        assert!(l.get_mut(3).unwrap_err().is("IndexError"));
+        let err = std::panic::catch_unwind(|| t[2]).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<alloc::string::String>().unwrap(),
+            "IndexError: tuple index out of range"
+        );
+    }
+
+    #[test]
+    fn truthiness_follows_python() {
+        assert!(!0i64.py_bool() && !0.0f64.py_bool() && !().py_bool());
+        assert!(!PyList::<i64>::new().py_bool() && !"".py_bool());
+        assert!(PyRange::from(3..4).py_bool() && !PyRange::new(4, 3, 1).unwrap().py_bool());
+        assert!(!Some(PyValue::Int(0)).py_bool() && !None::<i64>.py_bool());
+        assert!(PyValue::from(vec![PyValue::None]).py_bool());
+        let flags = PyList::from_vec(vec![0, 2, 0, 5]);
+        assert_eq!(count_true(&flags), 2);
+    }
+
+    #[test]
+    fn iteration_and_membership_are_uniform() {
+        let mut d = PyDictionary::new();
+        d.set_item("b", 1);
+        d.set_item("a", 2);
+        assert_eq!(d.py_iter().copied().collect::<Vec<_>>(), ["b", "a"]);
+        assert_eq!(d.py_iter().rev().copied().collect::<Vec<_>>(), ["a", "b"]);
+        assert!(d.py_contains(&"a") && !d.py_contains(&"z"));
+        assert_eq!(*PyMapping::py_getitem(&d, &"a").unwrap(), 2);
+        assert_eq!(
+            PyMapping::py_getitem(&d, &"z").unwrap_err().to_string(),
+            "KeyError: 'z'"
+        );
+
+        let s = PyStr::new("héllo");
+        assert_eq!(s.py_len(), 5);
+        assert_eq!(s.py_getitem(-4).unwrap(), 'é');
+        assert!(s.py_getitem(5).unwrap_err().is("IndexError"));
+        assert!(s.py_contains("ll") && s.py_contains(""));
+
+        let b = PyBytes::new(*b"abc");
+        assert_eq!(b.py_iter().map(u32::from).sum::<u32>(), 294);
+        assert!(b.py_contains(&b'c') && b.py_contains(&b"bc"[..]) && !b.py_contains(&b"ac"[..]));
+        assert_eq!(b.py_getitem(-1).unwrap(), b'c');
+
+        let set: PySet<i64> = [3, 1].into_iter().collect();
+        assert_eq!(set.py_iter().copied().collect::<Vec<_>>(), [3, 1]);
+        assert_eq!(PyTuple::from_vec(vec![1, 2]).py_getitem(-2), Ok(&1));
+    }
+}
@@ -0,0 +1,337 @@
+//! Python range.
+
+use alloc::format;
+use core::fmt;
+use core::iter::FusedIterator;
+
+use super::list::normalize_index;
+use super::{PySlice, PyValue};
+use crate::exceptions::{PyException, PyResult};
+
+/// Python-equivalent range: an arithmetic progression computed on demand.
+#[derive(Debug, Clone, Copy)]
+pub struct PyRange {
+    start: i64,
+    stop: i64,
+    step: i64,
+}
+
+impl PyRange {
+    /// Python-equivalent range(start, stop, step); a zero step raises ValueError.
+    pub fn new(start: i64, stop: i64, step: i64) -> PyResult<Self> {
+        if step == 0 {
+            return Err(PyException::value_error("range() arg 3 must not be zero"));
+        }
+        Ok(PyRange { start, stop, step })
+    }
+
+    /// Python-equivalent range(*args) on dynamic values: one to three
+    /// arguments, each converted with [`index`](crate::builtins::index).
+    pub fn from_values(args: &[PyValue]) -> PyResult<Self> {
+        use crate::builtins::index;
+        match args {
+            [] => Err(PyException::type_error(
+                "range expected at least 1 argument, got 0",
+            )),
+            [stop] => PyRange::new(0, index(stop)?, 1),
+            [start, stop] => PyRange::new(index(start)?, index(stop)?, 1),
+            [start, stop, step] => PyRange::new(index(start)?, index(stop)?, index(step)?),
+            _ => Err(PyException::type_error(format!(
+                "range expected at most 3 arguments, got {}",
+                args.len()
+            ))),
+        }
+    }
+
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    pub fn stop(&self) -> i64 {
+        self.stop
+    }
+
+    pub fn step(&self) -> i64 {
+        self.step
+    }
+
+    /// Python-equivalent len(range).
+    pub fn len(&self) -> usize {
+        let (lo, hi, step) = if self.step > 0 {
+            (self.start as i128, self.stop as i128, self.step as i128)
+        } else {
+            (self.stop as i128, self.start as i128, -(self.step as i128))
+        };
+        if lo >= hi {
+            0
+        } else {
+            ((hi - lo - 1) / step + 1) as usize
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Python-equivalent range[index], with negative indices counting from the end.
+    pub fn get(&self, index: i64) -> PyResult<i64> {
+        normalize_index(index, self.len())
+            .map(|i| self.nth(i))
+            .ok_or_else(|| PyException::index_error("range object index out of range"))
+    }
+
+    /// Python-equivalent x in range, answered without iterating.
+    pub fn contains(&self, value: &i64) -> bool {
+        let in_bounds = if self.step > 0 {
+            self.start <= *value && *value < self.stop
+        } else {
+            self.stop < *value && *value <= self.start
+        };
+        in_bounds && (*value as i128 - self.start as i128) % self.step as i128 == 0
+    }
+
+    /// Python-equivalent range.index(value).
+    pub fn index(&self, value: i64) -> PyResult<usize> {
+        if self.contains(&value) {
+            Ok(((value as i128 - self.start as i128) / self.step as i128) as usize)
+        } else {
+            Err(PyException::value_error(format!(
+                "{} is not in range",
+                value
+            )))
+        }
+    }
+
+    /// Python-equivalent range.count(value): 0 or 1.
+    pub fn count(&self, value: i64) -> usize {
+        self.contains(&value) as usize
+    }
+
+    /// Python-equivalent range[start:stop:step]: another range, computed
+    /// without materializing either one.
+    pub fn slice(&self, slice: &PySlice) -> PyResult<PyRange> {
+        let indices = slice.indices(self.len())?;
+        let at = |i: i64| self.start as i128 + i as i128 * self.step as i128;
+        let fit = |v: i128| {
+            i64::try_from(v).map_err(|_| {
+                PyException::new("OverflowError", "range slice bound does not fit in i64")
+            })
+        };
+        Ok(PyRange {
+            start: fit(at(indices.start))?,
+            stop: fit(at(indices.stop))?,
+            step: fit(self.step as i128 * indices.step as i128)?,
+        })
+    }
+
+    pub fn iter(&self) -> RangeIter {
+        RangeIter {
+            next: self.start,
+            step: self.step,
+            remaining: self.len(),
+        }
+    }
+
+    fn nth(&self, i: usize) -> i64 {
+        (self.start as i128 + i as i128 * self.step as i128) as i64
+    }
+}
+
+impl From<core::ops::Range<i64>> for PyRange {
+    fn from(range: core::ops::Range<i64>) -> Self {
+        PyRange {
+            start: range.start,
+            stop: range.end,
+            step: 1,
+        }
+    }
+}
+
+/// Ranges are equal when they produce the same values, as in CPython:
+/// `range(0) == range(2, 1)` and `range(0, 3, 5) == range(0, 1)`.
+impl PartialEq for PyRange {
+    fn eq(&self, other: &Self) -> bool {
+        let len = self.len();
+        len == other.len()
+            && (len == 0 || self.start == other.start)
+            && (len < 2 || self.step == other.step)
+    }
+}
+
+impl Eq for PyRange {}
+
+/// Formats as the range's repr, e.g. `range(0, 10, 2)`.
+impl fmt::Display for PyRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.step == 1 {
+            write!(f, "range({}, {})", self.start, self.stop)
+        } else {
+            write!(f, "range({}, {}, {})", self.start, self.stop, self.step)
+        }
+    }
+}
+
+/// Iterator over the values of a [`PyRange`].
+#[derive(Debug, Clone)]
+pub struct RangeIter {
+    next: i64,
+    step: i64,
+    remaining: usize,
+}
+
+impl Iterator for RangeIter {
+    type Item = i64;
+
+    fn next(&mut self) -> Option<i64> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let value = self.next;
+        self.remaining -= 1;
+        if self.remaining > 0 {
+            self.next += self.step;
+        }
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+
+    fn nth(&mut self, n: usize) -> Option<i64> {
+        if n >= self.remaining {
+            self.remaining = 0;
+            return None;
+        }
+        self.next = (self.next as i128 + n as i128 * self.step as i128) as i64;
+        self.remaining -= n;
+        self.next()
+    }
+}
+
+/// Walking from the back is what reversed(range) does.
+impl DoubleEndedIterator for RangeIter {
+    fn next_back(&mut self) -> Option<i64> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some((self.next as i128 + self.remaining as i128 * self.step as i128) as i64)
+    }
+}
+
+impl ExactSizeIterator for RangeIter {}
+
+impl FusedIterator for RangeIter {}
+
+impl IntoIterator for PyRange {
+    type Item = i64;
+    type IntoIter = RangeIter;
+
+    fn into_iter(self) -> RangeIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for &PyRange {
+    type Item = i64;
+    type IntoIter = RangeIter;
+
+    fn into_iter(self) -> RangeIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn length_indexing_and_membership() {
+        let r = PyRange::new(10, 0, -3).unwrap();
+        assert_eq!(r.iter().collect::<Vec<_>>(), [10, 7, 4, 1]);
+        assert_eq!(r.len(), 4);
+        assert_eq!(r.get(-1).unwrap(), 1);
+        assert!(r.get(4).unwrap_err().is("IndexError"));
+        assert!(r.contains(&7) && !r.contains(&6) && !r.contains(&0));
+        assert!(PyRange::new(0, 1, 0).unwrap_err().is("ValueError"));
+
+        let huge = PyRange::new(i64::MIN, i64::MAX, i64::MAX).unwrap();
+        assert_eq!(
+            huge.iter().collect::<Vec<_>>(),
+            [i64::MIN, -1, i64::MAX - 1]
+        );
+    }
+
+    #[test]
+    fn from_dynamic_arguments() {
+        let r = PyRange::from_values(&[PyValue::Bool(true), PyValue::Int(7), PyValue::Int(3)]);
+        assert_eq!(r.unwrap().iter().collect::<Vec<_>>(), [1, 4]);
+        assert_eq!(PyRange::from_values(&[PyValue::Int(3)]).unwrap().len(), 3);
+        let err = PyRange::from_values(&[PyValue::Float(1.0)]).unwrap_err();
+        assert_eq!(
+            err.message,
+            "'float' object cannot be interpreted as an integer"
+        );
+        let err = PyRange::from_values(&[]).unwrap_err();
+        assert_eq!(err.message, "range expected at least 1 argument, got 0");
+        assert!(PyRange::from_values(&[
+            PyValue::Int(0),
+            PyValue::Int(1),
+            PyValue::Int(2),
+            PyValue::Int(3)
+        ])
+        .unwrap_err()
+        .is("TypeError"));
+    }
+
+    #[test]
+    fn slicing_and_reversal_stay_lazy() {
+        let r = PyRange::new(0, 20, 3).unwrap();
+        let s = |start, stop, step| PySlice::new(start, stop, step);
+        assert_eq!(
+            r.slice(&s(Some(1), Some(-1), None)).unwrap(),
+            PyRange::new(3, 18, 3).unwrap()
+        );
+        let back = r.slice(&s(None, None, Some(-2))).unwrap();
+        assert_eq!(back.iter().collect::<Vec<_>>(), [18, 12, 6, 0]);
+        assert_eq!(back.to_string(), "range(18, -3, -6)");
+        assert!(r.slice(&s(Some(50), None, None)).unwrap().is_empty());
+        assert!(r
+            .slice(&s(None, None, Some(0)))
+            .unwrap_err()
+            .is("ValueError"));
+
+        let huge = PyRange::new(0, 1_000_000_000_000, 7).unwrap();
+        assert_eq!(
+            huge.slice(&s(Some(-2), None, None))
+                .unwrap()
+                .iter()
+                .collect::<Vec<_>>(),
+            [999_999_999_992, 999_999_999_999]
+        );
+        assert_eq!(crate::reversed(&r).take(2).collect::<Vec<_>>(), [18, 15]);
+        let mut it = r.iter();
+        assert_eq!(
+            (it.nth(2), it.next_back(), it.len()),
+            (Some(6), Some(18), 3)
+        );
+        assert_eq!(r.index(9).unwrap(), 3);
+        assert!(r.index(10).unwrap_err().is("ValueError"));
+        assert_eq!((r.count(9), r.count(10)), (1, 0));
+    }
+
+    #[test]
+    fn equality_and_repr_match_cpython() {
+        assert_eq!(PyRange::from(0..0), PyRange::new(2, 1, 1).unwrap());
+        assert_eq!(PyRange::new(0, 3, 5).unwrap(), PyRange::from(0..1));
+        assert_ne!(PyRange::from(0..3), PyRange::new(0, 3, 2).unwrap());
+        assert_eq!(PyRange::from(0..5).to_string(), "range(0, 5)");
+        assert_eq!(
+            PyRange::new(0, 10, 2).unwrap().to_string(),
+            "range(0, 10, 2)"
+        );
+    }
+}
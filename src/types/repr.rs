@@ -0,0 +1,385 @@
+//! repr() and ascii(): the unambiguous, round-trippable text form of values.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::hash::Hash;
+
+use crate::exceptions::ExceptionArg;
+
+use super::{PyBytes, PyDictionary, PyList, PyRange, PySet, PyStr, PyTuple, PyTypeId, PyValue};
+
+/// Python-equivalent `__repr__`.
+pub trait PyRepr {
+    fn py_repr(&self) -> String;
+
+    /// This value as an exception argument, as KeyError carries the missing
+    /// key. Values that are not plain data are kept as their repr.
+    fn exception_arg(&self) -> ExceptionArg {
+        ExceptionArg::Repr(self.py_repr())
+    }
+}
+
+/// Quotes `s` the way CPython's `str.__repr__` does: single quotes unless the
+/// string contains a single quote and no double quote.
+pub fn repr_str(s: &str) -> String {
+    let quote = if s.contains('\'') && !s.contains('"') {
+        '"'
+    } else {
+        '\''
+    };
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push(quote);
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c == quote => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if is_printable(c) => out.push(c),
+            c => push_escape(&mut out, c),
+        }
+    }
+    out.push(quote);
+    out
+}
+
+/// Close to `str.isprintable()`: control, format and separator characters
+/// other than the ASCII space are escaped.
+fn is_printable(c: char) -> bool {
+    if c.is_ascii() {
+        return (' '..='~').contains(&c);
+    }
+    !(c.is_control()
+        || c.is_whitespace()
+        || matches!(c, '\u{ad}' | '\u{200b}'..='\u{200f}' | '\u{2028}'..='\u{202e}' | '\u{2060}'..='\u{2064}' | '\u{feff}')
+        || ('\u{e000}'..='\u{f8ff}').contains(&c))
+}
+
+fn push_escape(out: &mut String, c: char) {
+    let code = c as u32;
+    if code < 0x100 {
+        out.push_str(&format!("\\x{:02x}", code));
+    } else if code < 0x10000 {
+        out.push_str(&format!("\\u{:04x}", code));
+    } else {
+        out.push_str(&format!("\\U{:08x}", code));
+    }
+}
+
+/// Python-equivalent ascii(): repr with every non-ASCII character escaped.
+pub fn ascii_escape(repr: &str) -> String {
+    let mut out = String::with_capacity(repr.len());
+    for c in repr.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else {
+            push_escape(&mut out, c);
+        }
+    }
+    out
+}
+
+/// Formats a float as CPython's `float.__repr__`: the shortest digits that
+/// round-trip, positional for exponents in [-4, 16), scientific otherwise.
+pub fn repr_float(value: f64) -> String {
+    if value.is_nan() {
+        return "nan".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "inf" } else { "-inf" }.to_string();
+    }
+    // `{:e}` yields the shortest round-trip digits, e.g. "-1.2345e-7".
+    let sci = format!("{:e}", value);
+    let (mantissa, exp) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let exp: i32 = exp.parse().unwrap_or(0);
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(m) => ("-", m),
+        None => ("", mantissa),
+    };
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    if (-4..16).contains(&exp) {
+        let point = exp + 1;
+        let body = if point <= 0 {
+            format!("0.{}{}", "0".repeat((-point) as usize), digits)
+        } else if point as usize >= digits.len() {
+            format!("{}{}.0", digits, "0".repeat(point as usize - digits.len()))
+        } else {
+            let (int, frac) = digits.split_at(point as usize);
+            format!("{}.{}", int, frac)
+        };
+        format!("{}{}", sign, body)
+    } else {
+        let exp_sign = if exp < 0 { '-' } else { '+' };
+        format!("{}{}e{}{:02}", sign, mantissa, exp_sign, exp.abs())
+    }
+}
+
+macro_rules! impl_py_repr_display {
+    ($($t:ty),*) => {
+        $(impl PyRepr for $t {
+            fn py_repr(&self) -> String {
+                self.to_string()
+            }
+        })*
+    };
+}
+
+macro_rules! impl_py_repr_int {
+    ($($t:ty),*) => {
+        $(impl PyRepr for $t {
+            fn py_repr(&self) -> String {
+                self.to_string()
+            }
+
+            fn exception_arg(&self) -> ExceptionArg {
+                i64::try_from(*self).map_or_else(|_| ExceptionArg::Repr(self.py_repr()), ExceptionArg::Int)
+            }
+        })*
+    };
+}
+
+impl_py_repr_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_py_repr_display!(PyRange);
+
+impl PyRepr for PyBytes {
+    fn py_repr(&self) -> String {
+        self.to_string()
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        ExceptionArg::Bytes(self.clone())
+    }
+}
+
+impl PyRepr for bool {
+    fn py_repr(&self) -> String {
+        if *self { "True" } else { "False" }.to_string()
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        ExceptionArg::Bool(*self)
+    }
+}
+
+impl PyRepr for () {
+    fn py_repr(&self) -> String {
+        "None".to_string()
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        ExceptionArg::None
+    }
+}
+
+impl PyRepr for f64 {
+    fn py_repr(&self) -> String {
+        repr_float(*self)
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        ExceptionArg::Float(*self)
+    }
+}
+
+impl PyRepr for f32 {
+    fn py_repr(&self) -> String {
+        repr_float(*self as f64)
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        ExceptionArg::Float(*self as f64)
+    }
+}
+
+impl PyRepr for str {
+    fn py_repr(&self) -> String {
+        repr_str(self)
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        ExceptionArg::from(self)
+    }
+}
+
+impl PyRepr for String {
+    fn py_repr(&self) -> String {
+        repr_str(self)
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        ExceptionArg::from(self.as_str())
+    }
+}
+
+impl PyRepr for PyStr {
+    fn py_repr(&self) -> String {
+        repr_str(self.as_str())
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        ExceptionArg::from(self.as_str())
+    }
+}
+
+impl PyRepr for char {
+    fn py_repr(&self) -> String {
+        repr_str(self.encode_utf8(&mut [0; 4]))
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        ExceptionArg::Str(self.to_string())
+    }
+}
+
+impl PyRepr for PyTypeId {
+    fn py_repr(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl<T: PyRepr + ?Sized> PyRepr for &T {
+    fn py_repr(&self) -> String {
+        (**self).py_repr()
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        (**self).exception_arg()
+    }
+}
+
+impl<T: PyRepr> PyRepr for Option<T> {
+    fn py_repr(&self) -> String {
+        match self {
+            Some(value) => value.py_repr(),
+            None => "None".to_string(),
+        }
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        match self {
+            Some(value) => value.exception_arg(),
+            None => ExceptionArg::None,
+        }
+    }
+}
+
+/// Joins element reprs with ", " between the given brackets.
+pub(crate) fn repr_items<'a, T, I>(open: &str, items: I, close: &str) -> String
+where
+    T: PyRepr + 'a,
+    I: IntoIterator<Item = &'a T>,
+{
+    let parts: Vec<String> = items.into_iter().map(PyRepr::py_repr).collect();
+    format!("{}{}{}", open, parts.join(", "), close)
+}
+
+impl<T: PyRepr> PyRepr for Vec<T> {
+    fn py_repr(&self) -> String {
+        repr_items("[", self, "]")
+    }
+}
+
+impl<T: PyRepr> PyRepr for PyList<T> {
+    fn py_repr(&self) -> String {
+        repr_items("[", self.iter(), "]")
+    }
+}
+
+impl<T: PyRepr> PyRepr for PyTuple<T> {
+    fn py_repr(&self) -> String {
+        if self.len() == 1 {
+            return repr_items("(", self.iter(), ",)");
+        }
+        repr_items("(", self.iter(), ")")
+    }
+}
+
+impl<T: PyRepr + Hash + Eq> PyRepr for PySet<T> {
+    fn py_repr(&self) -> String {
+        if self.is_empty() {
+            return "set()".to_string();
+        }
+        repr_items("{", self.iter(), "}")
+    }
+}
+
+impl<K: PyRepr + Hash + Eq, V: PyRepr> PyRepr for PyDictionary<K, V> {
+    fn py_repr(&self) -> String {
+        let parts: Vec<String> = self
+            .iter()
+            .map(|(k, v)| format!("{}: {}", k.py_repr(), v.py_repr()))
+            .collect();
+        format!("{{{}}}", parts.join(", "))
+    }
+}
+
+impl PyRepr for PyValue {
+    fn py_repr(&self) -> String {
+        match self {
+            PyValue::None => "None".to_string(),
+            PyValue::Bool(b) => b.py_repr(),
+            PyValue::Int(i) => i.to_string(),
+            PyValue::Float(f) => repr_float(*f),
+            PyValue::Str(s) => s.py_repr(),
+            PyValue::Bytes(b) => b.py_repr(),
+            PyValue::List(l) => l.py_repr(),
+            PyValue::Tuple(t) => t.py_repr(),
+            PyValue::Dict(d) => d.py_repr(),
+            PyValue::Set(s) => s.py_repr(),
+            PyValue::Function(func) => func.to_string(),
+            PyValue::NotImplemented => "NotImplemented".to_string(),
+        }
+    }
+
+    fn exception_arg(&self) -> ExceptionArg {
+        ExceptionArg::from(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn string_quoting() {
+        assert_eq!(repr_str("abc"), "'abc'");
+        assert_eq!(repr_str("it's"), "\"it's\"");
+        assert_eq!(repr_str("it's \"x\""), "'it\\'s \"x\"'");
+        assert_eq!(repr_str("a\tb\n\\\x07"), "'a\\tb\\n\\\\\\x07'");
+        assert_eq!(repr_str("héllo \u{200b}"), "'héllo \\u200b'");
+        assert_eq!(
+            ascii_escape(&repr_str("héllo 😀")),
+            "'h\\xe9llo \\U0001f600'"
+        );
+    }
+
+    #[test]
+    fn float_repr_matches_cpython() {
+        assert_eq!(repr_float(3.0), "3.0");
+        assert_eq!(repr_float(0.1), "0.1");
+        assert_eq!(repr_float(-0.0), "-0.0");
+        assert_eq!(repr_float(1e16), "1e+16");
+        assert_eq!(repr_float(1234567890123456.0), "1234567890123456.0");
+        assert_eq!(repr_float(0.0001), "0.0001");
+        assert_eq!(repr_float(0.00001), "1e-05");
+        assert_eq!(repr_float(1.5e-7), "1.5e-07");
+        assert_eq!(repr_float(1.2345678901234568e17), "1.2345678901234568e+17");
+        assert_eq!(repr_float(1e300), "1e+300");
+    }
+
+    #[test]
+    fn containers_repr_elements() {
+        let v = PyValue::from(vec![PyValue::from("a"), PyValue::Int(1), PyValue::None]);
+        assert_eq!(v.py_repr(), "['a', 1, None]");
+        let t = PyTuple::from_vec(vec![PyStr::new("x")]);
+        assert_eq!(t.py_repr(), "('x',)");
+        let d: PyDictionary<_, _> = [("k", 1.5)].into_iter().collect();
+        assert_eq!(d.py_repr(), "{'k': 1.5}");
+    }
+}
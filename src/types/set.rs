@@ -0,0 +1,215 @@
+//! Python set.
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::Hash;
+
+use super::dict::{self, Keys};
+use super::repr::PyRepr;
+use super::PyDictionary;
+use crate::exceptions::{PyException, PyResult};
+
+/// Python-equivalent set. Elements iterate in insertion order, which is one
+/// of the orders CPython is free to use.
+#[derive(Clone)]
+pub struct PySet<T> {
+    items: PyDictionary<T, ()>,
+}
+
+impl<T> Default for PySet<T> {
+    fn default() -> Self {
+        PySet {
+            items: PyDictionary::default(),
+        }
+    }
+}
+
+impl<T> PySet<T> {
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn iter(&self) -> Keys<'_, T, ()> {
+        self.items.iter_keys()
+    }
+}
+
+impl<T: Hash + Eq> PySet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Python-equivalent x in set.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.items.contains_key(value)
+    }
+
+    /// Python-equivalent set.add(x).
+    pub fn add(&mut self, value: T) {
+        self.items.set_item(value, ());
+    }
+
+    /// Python-equivalent set.remove(x): raises KeyError when missing.
+    pub fn remove(&mut self, value: &T) -> PyResult<()>
+    where
+        T: PyRepr,
+    {
+        self.items
+            .remove(value)
+            .ok_or_else(|| PyException::key_error(value))
+    }
+
+    /// Python-equivalent set.discard(x).
+    pub fn discard<Q>(&mut self, value: &Q)
+    where
+        T: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.items.remove(value);
+    }
+
+    /// Python-equivalent set.pop(): removes an arbitrary element.
+    pub fn pop(&mut self) -> PyResult<T> {
+        self.items
+            .popitem()
+            .map(|(k, _)| k)
+            .map_err(|_| PyException::key_error("pop from an empty set"))
+    }
+
+    /// Python-equivalent set.clear().
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Python-equivalent set.issubset(other).
+    pub fn issubset(&self, other: &PySet<T>) -> bool {
+        self.len() <= other.len() && self.iter().all(|x| other.contains(x))
+    }
+
+    /// Python-equivalent set.issuperset(other).
+    pub fn issuperset(&self, other: &PySet<T>) -> bool {
+        other.issubset(self)
+    }
+
+    /// Python-equivalent set.isdisjoint(other).
+    pub fn isdisjoint(&self, other: &PySet<T>) -> bool {
+        !self.iter().any(|x| other.contains(x))
+    }
+
+    /// Python-equivalent set.update(iterable).
+    pub fn update<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.add(value);
+        }
+    }
+}
+
+impl<T: Hash + Eq + Clone> PySet<T> {
+    /// Python-equivalent set.union(other).
+    pub fn union(&self, other: &PySet<T>) -> PySet<T> {
+        self.iter().chain(other.iter()).cloned().collect()
+    }
+
+    /// Python-equivalent set.intersection(other).
+    pub fn intersection(&self, other: &PySet<T>) -> PySet<T> {
+        self.iter()
+            .filter(|x| other.contains(*x))
+            .cloned()
+            .collect()
+    }
+
+    /// Python-equivalent set.difference(other).
+    pub fn difference(&self, other: &PySet<T>) -> PySet<T> {
+        self.iter()
+            .filter(|x| !other.contains(*x))
+            .cloned()
+            .collect()
+    }
+
+    /// Python-equivalent set.symmetric_difference(other).
+    pub fn symmetric_difference(&self, other: &PySet<T>) -> PySet<T> {
+        self.iter()
+            .filter(|x| !other.contains(*x))
+            .chain(other.iter().filter(|x| !self.contains(*x)))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Sets compare equal when they hold the same elements, regardless of order.
+impl<T: Hash + Eq> PartialEq for PySet<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.items == other.items
+    }
+}
+
+impl<T: Hash + Eq> Eq for PySet<T> {}
+
+/// Sets are partially ordered by inclusion, so `a <= b` is a.issubset(b) and
+/// `a < b` a proper subset; sets that overlap only partly are unordered.
+impl<T: Hash + Eq> PartialOrd for PySet<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.len().cmp(&other.len()) {
+            Ordering::Equal => self.issubset(other).then_some(Ordering::Equal),
+            Ordering::Less => self.issubset(other).then_some(Ordering::Less),
+            Ordering::Greater => self.issuperset(other).then_some(Ordering::Greater),
+        }
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for PySet<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = PySet::new();
+        set.update(iter);
+        set
+    }
+}
+
+impl<T: Hash + Eq> Extend<T> for PySet<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.update(iter);
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PySet<T> {
+    type Item = &'a T;
+    type IntoIter = Keys<'a, T, ()>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Owning iterator over a set's elements, in insertion order.
+pub type IntoIter<T> = core::iter::Map<dict::IntoIter<T, ()>, fn((T, ())) -> T>;
+
+impl<T> IntoIterator for PySet<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter().map(|(item, ())| item)
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for PySet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Python-equivalent str(set), which shows each element's repr.
+impl<T: Hash + Eq + PyRepr> fmt::Display for PySet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.py_repr())
+    }
+}
@@ -0,0 +1,312 @@
+//! slice objects and CPython's index normalization for `seq[start:stop:step]`.
+
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::{PyBytes, PyList, PyStr, PyTuple};
+use crate::exceptions::{PyException, PyResult};
+
+/// Python-equivalent slice(start, stop, step). `None` bounds are omitted ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct PySlice {
+    pub start: Option<i64>,
+    pub stop: Option<i64>,
+    pub step: Option<i64>,
+}
+
+/// Concrete bounds for a slice applied to a sequence of known length, as
+/// returned by slice.indices(len) plus the resulting length.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SliceIndices {
+    pub start: i64,
+    pub stop: i64,
+    pub step: i64,
+    pub len: usize,
+}
+
+impl PySlice {
+    pub fn new(start: Option<i64>, stop: Option<i64>, step: Option<i64>) -> Self {
+        PySlice { start, stop, step }
+    }
+
+    /// Resolves the slice against a sequence of `len` items exactly as
+    /// CPython's `PySlice_AdjustIndices` does.
+    pub fn indices(&self, len: usize) -> PyResult<SliceIndices> {
+        let step = self.step.unwrap_or(1);
+        if step == 0 {
+            return Err(PyException::value_error("slice step cannot be zero"));
+        }
+        // As PySlice_Unpack does, so that -step cannot overflow.
+        let step = step.max(-i64::MAX);
+        let len = len as i64;
+        let adjust = |bound: Option<i64>, default: i64| match bound {
+            None => default,
+            Some(i) if i < 0 => {
+                let i = i + len;
+                if i >= 0 {
+                    i
+                } else if step < 0 {
+                    -1
+                } else {
+                    0
+                }
+            }
+            Some(i) if i >= len => {
+                if step < 0 {
+                    len - 1
+                } else {
+                    len
+                }
+            }
+            Some(i) => i,
+        };
+        let (start, stop) = if step < 0 {
+            (adjust(self.start, len - 1), adjust(self.stop, -1))
+        } else {
+            (adjust(self.start, 0), adjust(self.stop, len))
+        };
+        let count = if step < 0 {
+            if stop < start {
+                (start - stop - 1) / -step + 1
+            } else {
+                0
+            }
+        } else if start < stop {
+            (stop - start - 1) / step + 1
+        } else {
+            0
+        };
+        Ok(SliceIndices {
+            start,
+            stop,
+            step,
+            len: count as usize,
+        })
+    }
+}
+
+impl SliceIndices {
+    /// The selected positions, in slice order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> {
+        let SliceIndices {
+            start, step, len, ..
+        } = *self;
+        (0..len as i64).map(move |i| (start + i * step) as usize)
+    }
+
+    /// Borrows the selected elements of `items` without copying them.
+    pub fn iter_over<'a, T>(&self, items: &'a [T]) -> SliceIter<'a, T> {
+        SliceIter {
+            items,
+            front: self.start,
+            step: self.step,
+            remaining: self.len,
+        }
+    }
+
+    /// Collects the selected elements of `items`.
+    pub fn select<T: Clone>(&self, items: &[T]) -> Vec<T> {
+        if self.step == 1 {
+            let start = self.start as usize;
+            return items[start..start + self.len].to_vec();
+        }
+        self.iter_over(items).cloned().collect()
+    }
+}
+
+/// The elements of a sequence picked out by a slice, borrowed in slice order.
+#[derive(Debug, Clone)]
+pub struct SliceIter<'a, T> {
+    items: &'a [T],
+    front: i64,
+    step: i64,
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for SliceIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let item = &self.items[self.front as usize];
+        self.remaining -= 1;
+        // Past the last element the next position may not fit in an i64.
+        if self.remaining > 0 {
+            self.front += self.step;
+        }
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for SliceIter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        Some(&self.items[(self.front + self.remaining as i64 * self.step) as usize])
+    }
+}
+
+impl<T> ExactSizeIterator for SliceIter<'_, T> {}
+
+impl fmt::Display for PySlice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |v: Option<i64>| v.map_or("None".to_string(), |i| i.to_string());
+        write!(
+            f,
+            "slice({}, {}, {})",
+            show(self.start),
+            show(self.stop),
+            show(self.step)
+        )
+    }
+}
+
+impl<T> PyList<T> {
+    /// Iterates list[start:stop:step] by reference, without building the
+    /// sliced list.
+    pub fn iter_slice(&self, slice: &PySlice) -> PyResult<SliceIter<'_, T>> {
+        Ok(slice.indices(self.len())?.iter_over(self.as_slice()))
+    }
+}
+
+impl<T> PyTuple<T> {
+    /// Iterates tuple[start:stop:step] by reference.
+    pub fn iter_slice(&self, slice: &PySlice) -> PyResult<SliceIter<'_, T>> {
+        Ok(slice.indices(self.len())?.iter_over(self.as_slice()))
+    }
+}
+
+impl<T: Clone> PyList<T> {
+    /// Python-equivalent list[start:stop:step].
+    pub fn slice(&self, slice: &PySlice) -> PyResult<PyList<T>> {
+        Ok(PyList::from_vec(
+            slice.indices(self.len())?.select(self.as_slice()),
+        ))
+    }
+}
+
+impl<T: Clone> PyTuple<T> {
+    /// Python-equivalent tuple[start:stop:step].
+    pub fn slice(&self, slice: &PySlice) -> PyResult<PyTuple<T>> {
+        Ok(PyTuple::from_vec(
+            slice.indices(self.len())?.select(self.as_slice()),
+        ))
+    }
+}
+
+impl PyStr {
+    /// Python-equivalent str[start:stop:step], indexing by code point.
+    pub fn slice(&self, slice: &PySlice) -> PyResult<PyStr> {
+        if self.is_ascii() {
+            let bytes = slice.indices(self.as_str().len())?.select(self.as_bytes());
+            return Ok(PyStr::from(String::from_utf8(bytes).unwrap_or_default()));
+        }
+        let chars: Vec<char> = self.chars().collect();
+        let picked = slice.indices(chars.len())?.select(&chars);
+        Ok(PyStr::from(picked.into_iter().collect::<String>()))
+    }
+}
+
+impl PyBytes {
+    /// Python-equivalent bytes[start:stop:step].
+    pub fn slice(&self, slice: &PySlice) -> PyResult<PyBytes> {
+        Ok(PyBytes::from(
+            slice.indices(self.len())?.select(self.as_bytes()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sl(start: Option<i64>, stop: Option<i64>, step: Option<i64>) -> PySlice {
+        PySlice::new(start, stop, step)
+    }
+
+    #[test]
+    fn indices_match_cpython() {
+        // slice(...).indices(5) in CPython.
+        let cases = [
+            (sl(None, None, None), (0, 5, 1, 5)),
+            (sl(Some(-3), None, None), (2, 5, 1, 3)),
+            (sl(None, None, Some(-1)), (4, -1, -1, 5)),
+            (sl(Some(-10), Some(10), Some(2)), (0, 5, 2, 3)),
+            (sl(Some(10), Some(-10), Some(-2)), (4, -1, -2, 3)),
+            (sl(Some(3), Some(1), None), (3, 1, 1, 0)),
+            (sl(Some(-1), Some(-4), Some(-1)), (4, 1, -1, 3)),
+            (sl(None, None, Some(i64::MIN)), (4, -1, -i64::MAX, 1)),
+            (sl(Some(3), None, Some(i64::MAX)), (3, 5, i64::MAX, 1)),
+        ];
+        for (slice, (start, stop, step, len)) in cases {
+            let got = slice.indices(5).unwrap();
+            assert_eq!(
+                (got.start, got.stop, got.step, got.len),
+                (start, stop, step, len),
+                "{}",
+                slice
+            );
+        }
+        assert!(sl(None, None, Some(0))
+            .indices(5)
+            .unwrap_err()
+            .is("ValueError"));
+    }
+
+    #[test]
+    fn sequences_slice_alike() {
+        let list = PyList::from_vec(vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(
+            list.slice(&sl(Some(-3), None, None)).unwrap().into_vec(),
+            vec![3, 4, 5]
+        );
+        assert_eq!(
+            list.slice(&sl(None, None, Some(-2))).unwrap().into_vec(),
+            vec![5, 3, 1]
+        );
+        let tuple = PyTuple::from_vec(vec!['a', 'b', 'c']);
+        assert_eq!(
+            tuple.slice(&sl(Some(1), None, None)).unwrap().into_vec(),
+            vec!['b', 'c']
+        );
+        assert_eq!(
+            PyStr::new("héllo")
+                .slice(&sl(None, None, Some(-1)))
+                .unwrap(),
+            "olléh"
+        );
+        assert_eq!(
+            PyStr::new("hello")
+                .slice(&sl(Some(-4), Some(-1), None))
+                .unwrap(),
+            "ell"
+        );
+        let mut picked = list.iter_slice(&sl(Some(-1), Some(0), Some(-2))).unwrap();
+        assert_eq!(picked.len(), 3);
+        assert_eq!(picked.next_back(), Some(&1));
+        assert_eq!(picked.collect::<Vec<_>>(), [&5, &3]);
+        assert_eq!(
+            list.iter_slice(&sl(Some(9), None, None)).unwrap().count(),
+            0
+        );
+        let huge = sl(Some(1), None, Some(i64::MAX));
+        assert_eq!(list.slice(&huge).unwrap().into_vec(), vec![1]);
+        assert_eq!(tuple.slice(&huge).unwrap().into_vec(), vec!['b']);
+        assert_eq!(list.iter_slice(&huge).unwrap().collect::<Vec<_>>(), [&1]);
+        let bytes = PyBytes::new(b"abcdef".to_vec());
+        assert_eq!(
+            bytes.slice(&sl(Some(1), None, Some(2))).unwrap().as_bytes(),
+            b"bdf"
+        );
+    }
+}
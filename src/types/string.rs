@@ -0,0 +1,379 @@
+//! Python str.
+
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::borrow::Borrow;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::ops::{
+    Bound, Deref, Index, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+};
+
+use super::{PyKwargs, PyValue, Symbol};
+use crate::exceptions::{PyException, PyResult};
+use crate::stdlib::string::{DefaultFormatter, Formatter};
+use crate::sync::Lock;
+
+/// Strings passed through [`PyStr::intern`], each with its [`Symbol`] once
+/// [`intern`](super::intern) has given it one.
+static INTERNED: Lock<BTreeMap<Arc<str>, Option<Symbol>>> = Lock::new(BTreeMap::new());
+
+/// Interns `value`, then hands `entry` the shared text and its symbol slot
+/// while the table is locked.
+pub(crate) fn with_interned<R>(
+    value: &str,
+    entry: impl FnOnce(&Arc<str>, &mut Option<Symbol>) -> R,
+) -> R {
+    let mut table = INTERNED.lock();
+    if !table.contains_key(value) {
+        table.insert(Arc::from(value), None);
+    }
+    let (text, symbol) = table
+        .range_mut::<str, _>((Bound::Included(value), Bound::Included(value)))
+        .next()
+        .expect("inserted above");
+    entry(text, symbol)
+}
+
+/// Python-equivalent str. Lengths and indices count code points, not bytes.
+///
+/// Like CPython's str it is immutable and shared: cloning bumps a reference
+/// count, and methods that would return an unchanged string return `self`.
+#[derive(Debug, Clone, PartialOrd, Ord, Default)]
+pub struct PyStr {
+    value: Arc<str>,
+}
+
+impl PyStr {
+    pub fn new<S: AsRef<str>>(value: S) -> Self {
+        PyStr {
+            value: Arc::from(value.as_ref()),
+        }
+    }
+
+    /// Python-equivalent sys.intern(s): every interned copy of the same text
+    /// shares one allocation, so comparing them is a pointer check.
+    pub fn intern(value: &str) -> Self {
+        with_interned(value, |text, _| PyStr {
+            value: text.clone(),
+        })
+    }
+
+    /// Python-equivalent `a is b`: true if both share the same storage.
+    pub fn ptr_eq(&self, other: &PyStr) -> bool {
+        Arc::ptr_eq(&self.value, &other.value)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.value
+    }
+
+    pub fn into_string(self) -> String {
+        String::from(&*self.value)
+    }
+
+    /// Python-equivalent len(s): the number of code points.
+    pub fn len(&self) -> usize {
+        self.value.chars().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Python-equivalent s[index], borrowing the one-character str.
+    pub fn char_at(&self, index: i64) -> PyResult<&str> {
+        let s: &str = &self.value;
+        let position = if index < 0 {
+            usize::try_from(index.unsigned_abs())
+                .ok()
+                .and_then(|back| s.char_indices().rev().nth(back - 1))
+        } else {
+            usize::try_from(index)
+                .ok()
+                .and_then(|i| s.char_indices().nth(i))
+        };
+        position
+            .map(|(start, c)| &s[start..start + c.len_utf8()])
+            .ok_or_else(|| PyException::index_error("string index out of range"))
+    }
+
+    /// Appends `s` in place, like `s += other`. Other handles to the old
+    /// value are unaffected.
+    pub fn push_str(&mut self, s: &str) {
+        if s.is_empty() {
+            return;
+        }
+        let mut out = String::with_capacity(self.value.len() + s.len());
+        out.push_str(&self.value);
+        out.push_str(s);
+        self.value = Arc::from(out);
+    }
+
+    /// Returns `self` when `f` would leave every character alone.
+    fn map_case<I, F>(&self, f: F) -> PyStr
+    where
+        I: Iterator<Item = char>,
+        F: Fn(char) -> I,
+    {
+        let unchanged = self.value.chars().all(|c| {
+            let mut mapped = f(c);
+            mapped.next() == Some(c) && mapped.next().is_none()
+        });
+        if unchanged {
+            return self.clone();
+        }
+        PyStr::from(self.value.chars().flat_map(f).collect::<String>())
+    }
+
+    /// Python-equivalent str.upper().
+    pub fn upper(&self) -> PyStr {
+        self.map_case(char::to_uppercase)
+    }
+
+    /// Python-equivalent str.lower().
+    pub fn lower(&self) -> PyStr {
+        self.map_case(char::to_lowercase)
+    }
+
+    /// Python-equivalent str.strip().
+    pub fn strip(&self) -> PyStr {
+        let trimmed = self.value.trim();
+        if trimmed.len() == self.value.len() {
+            return self.clone();
+        }
+        PyStr::new(trimmed)
+    }
+
+    /// Python-equivalent str.split(sep): whitespace splitting when `sep` is None.
+    pub fn split(&self, sep: Option<&str>) -> PyResult<Vec<PyStr>> {
+        match sep {
+            None => Ok(self.value.split_whitespace().map(PyStr::new).collect()),
+            Some("") => Err(PyException::value_error("empty separator")),
+            Some(sep) => Ok(self.value.split(sep).map(PyStr::new).collect()),
+        }
+    }
+
+    /// Python-equivalent sep.join(iterable).
+    pub fn join<I, S>(&self, parts: I) -> PyStr
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut out = String::new();
+        for (i, part) in parts.into_iter().enumerate() {
+            if i > 0 {
+                out.push_str(&self.value);
+            }
+            out.push_str(part.as_ref());
+        }
+        PyStr::from(out)
+    }
+
+    /// Python-equivalent str.replace(old, new).
+    pub fn replace(&self, old: &str, new: &str) -> PyStr {
+        if old == new || !self.value.contains(old) {
+            return self.clone();
+        }
+        PyStr::from(self.value.replace(old, new))
+    }
+
+    /// Python-equivalent str.startswith(prefix).
+    pub fn startswith(&self, prefix: &str) -> bool {
+        self.value.starts_with(prefix)
+    }
+
+    /// Python-equivalent str.endswith(suffix).
+    pub fn endswith(&self, suffix: &str) -> bool {
+        self.value.ends_with(suffix)
+    }
+
+    /// Python-equivalent str.find(sub): the code-point index, or -1.
+    pub fn find(&self, sub: &str) -> i64 {
+        match self.value.find(sub) {
+            Some(byte) => self.value[..byte].chars().count() as i64,
+            None => -1,
+        }
+    }
+
+    /// Python-equivalent str.format(*args, **kwargs), sharing its parsing and
+    /// field lookup with `string::Formatter`.
+    pub fn format(&self, args: &[PyValue], kwargs: &PyKwargs) -> PyResult<PyStr> {
+        DefaultFormatter
+            .vformat(&self.value, args, kwargs)
+            .map(PyStr::from)
+    }
+}
+
+impl Deref for PyStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.value
+    }
+}
+
+impl Borrow<str> for PyStr {
+    fn borrow(&self) -> &str {
+        &self.value
+    }
+}
+
+impl AsRef<str> for PyStr {
+    fn as_ref(&self) -> &str {
+        &self.value
+    }
+}
+
+impl From<&str> for PyStr {
+    fn from(value: &str) -> Self {
+        PyStr::new(value)
+    }
+}
+
+impl From<String> for PyStr {
+    fn from(value: String) -> Self {
+        PyStr {
+            value: Arc::from(value),
+        }
+    }
+}
+
+/// The symbol's interned text, shared rather than copied.
+impl From<Symbol> for PyStr {
+    fn from(symbol: Symbol) -> Self {
+        PyStr {
+            value: symbol.text().clone(),
+        }
+    }
+}
+
+impl PartialEq for PyStr {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other) || self.value == other.value
+    }
+}
+
+impl Eq for PyStr {}
+
+/// Hashes the text alone, agreeing with `Borrow<str>`.
+impl Hash for PyStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state)
+    }
+}
+
+impl PartialEq<str> for PyStr {
+    fn eq(&self, other: &str) -> bool {
+        &*self.value == other
+    }
+}
+
+impl PartialEq<&str> for PyStr {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.value == *other
+    }
+}
+
+/// `s[i]` as a borrowed one-character str, raising IndexError as a panic;
+/// [`PyStr::char_at`] is the checked form.
+impl Index<i64> for PyStr {
+    type Output = str;
+
+    fn index(&self, index: i64) -> &str {
+        self.char_at(index).unwrap_or_else(|err| err.raise())
+    }
+}
+
+/// Byte-range slicing stays as it is on `str`, which the `i64` impl would
+/// otherwise hide from deref.
+macro_rules! impl_str_range_index {
+    ($($range:ty),*) => {
+        $(impl Index<$range> for PyStr {
+            type Output = str;
+
+            fn index(&self, range: $range) -> &str {
+                &self.as_str()[range]
+            }
+        })*
+    };
+}
+
+impl_str_range_index!(
+    Range<usize>,
+    RangeFrom<usize>,
+    RangeTo<usize>,
+    RangeFull,
+    RangeInclusive<usize>,
+    RangeToInclusive<usize>
+);
+
+impl fmt::Display for PyStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_code_points() {
+        let s = PyStr::new("héllo wörld");
+        assert_eq!(s.len(), 11);
+        assert_eq!(s.find("wö"), 6);
+        assert_eq!(s.find("x"), -1);
+    }
+
+    #[test]
+    fn indexes_by_code_point() {
+        let s = PyStr::new("héllo");
+        assert_eq!((&s[1], &s[-1], &s[-5]), ("é", "o", "h"));
+        assert_eq!((&s[1..3], &s[..1]), ("é", "h"));
+        assert_eq!(
+            s.char_at(5).unwrap_err().to_string(),
+            "IndexError: string index out of range"
+        );
+        assert!(s.char_at(-6).is_err() && s.char_at(i64::MIN).is_err());
+    }
+
+    #[test]
+    fn split_and_join() {
+        let s = PyStr::new("  a b\tc ");
+        let parts = s.split(None).unwrap();
+        assert_eq!(parts, vec!["a", "b", "c"]);
+        assert_eq!(PyStr::new("-").join(&parts), "a-b-c");
+        assert!(s.split(Some("")).unwrap_err().is("ValueError"));
+    }
+
+    #[test]
+    fn unchanged_results_share_storage() {
+        let s = PyStr::new("already lower");
+        assert!(s.lower().ptr_eq(&s));
+        assert!(s.strip().ptr_eq(&s));
+        assert!(s.replace("x", "y").ptr_eq(&s));
+        assert_eq!(s.upper(), "ALREADY LOWER");
+        assert_eq!(PyStr::new("Straße").upper(), "STRASSE");
+    }
+
+    #[test]
+    fn push_str_copies_on_write() {
+        let mut a = PyStr::new("spam");
+        let b = a.clone();
+        a.push_str(" and eggs");
+        assert_eq!(a, "spam and eggs");
+        assert_eq!(b, "spam");
+    }
+
+    #[test]
+    fn interned_strings_share_storage() {
+        let a = PyStr::intern("__init__");
+        let b = PyStr::intern(&["__", "init", "__"].concat());
+        assert!(a.ptr_eq(&b));
+        assert!(!PyStr::new("__init__").ptr_eq(&a));
+        assert_eq!(PyStr::new("__init__"), a);
+    }
+}
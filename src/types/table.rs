@@ -0,0 +1,132 @@
+//! The open-addressing hash index behind PyDictionary and PySet.
+//!
+//! Entries live in an insertion-ordered vector owned by the container; the
+//! table only maps hashes to positions in it, like CPython's compact dict.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    Empty,
+    Deleted,
+    Full { hash: u64, index: usize },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct IndexTable {
+    slots: Vec<Slot>,
+    /// Full plus deleted slots; drives resizing.
+    used: usize,
+}
+
+impl Default for IndexTable {
+    fn default() -> Self {
+        IndexTable::new()
+    }
+}
+
+impl IndexTable {
+    pub(crate) const fn new() -> Self {
+        IndexTable {
+            slots: Vec::new(),
+            used: 0,
+        }
+    }
+
+    fn mask(&self) -> usize {
+        self.slots.len() - 1
+    }
+
+    /// Position of the entry whose hash is `hash` and for which `eq` holds.
+    pub(crate) fn find<F: FnMut(usize) -> bool>(&self, hash: u64, eq: F) -> Option<usize> {
+        self.find_slot(hash, eq).map(|slot| match self.slots[slot] {
+            Slot::Full { index, .. } => index,
+            _ => unreachable!(),
+        })
+    }
+
+    fn find_slot<F: FnMut(usize) -> bool>(&self, hash: u64, mut eq: F) -> Option<usize> {
+        if self.slots.is_empty() {
+            return None;
+        }
+        let mask = self.mask();
+        let mut pos = hash as usize & mask;
+        loop {
+            match self.slots[pos] {
+                Slot::Empty => return None,
+                Slot::Full { hash: h, index } if h == hash && eq(index) => return Some(pos),
+                _ => pos = (pos + 1) & mask,
+            }
+        }
+    }
+
+    /// Records that entry `index` has `hash`. The caller has checked that no
+    /// equal key is present.
+    pub(crate) fn insert(&mut self, hash: u64, index: usize) {
+        if (self.used + 1) * 3 > self.slots.len() * 2 {
+            self.rehash(1);
+        }
+        let mask = self.mask();
+        let mut pos = hash as usize & mask;
+        while let Slot::Full { .. } = self.slots[pos] {
+            pos = (pos + 1) & mask;
+        }
+        if let Slot::Empty = self.slots[pos] {
+            self.used += 1;
+        }
+        self.slots[pos] = Slot::Full { hash, index };
+    }
+
+    /// Forgets the entry matching `eq`, returning its position.
+    pub(crate) fn remove<F: FnMut(usize) -> bool>(&mut self, hash: u64, eq: F) -> Option<usize> {
+        let slot = self.find_slot(hash, eq)?;
+        let Slot::Full { index, .. } = self.slots[slot] else {
+            unreachable!()
+        };
+        self.slots[slot] = Slot::Deleted;
+        Some(index)
+    }
+
+    /// Makes room for `additional` more inserts without resizing.
+    pub(crate) fn reserve(&mut self, additional: usize) {
+        if (self.used + additional) * 3 > self.slots.len() * 2 {
+            self.rehash(additional);
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.slots.clear();
+        self.used = 0;
+    }
+
+    /// Re-indexes from scratch, e.g. after the entry vector was compacted.
+    pub(crate) fn rebuild<I: Iterator<Item = (u64, usize)>>(&mut self, entries: I, len: usize) {
+        self.clear();
+        self.resize(len);
+        for (hash, index) in entries {
+            self.insert(hash, index);
+        }
+    }
+
+    fn rehash(&mut self, additional: usize) {
+        let live = self
+            .slots
+            .iter()
+            .filter(|s| matches!(s, Slot::Full { .. }))
+            .count();
+        let old = core::mem::take(&mut self.slots);
+        self.resize(live + additional);
+        for slot in old {
+            if let Slot::Full { hash, index } = slot {
+                self.insert(hash, index);
+            }
+        }
+    }
+
+    fn resize(&mut self, live: usize) {
+        let capacity = (live * 3).next_power_of_two().max(8);
+        self.slots = vec![Slot::Empty; capacity];
+        self.used = 0;
+    }
+}
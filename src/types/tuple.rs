@@ -0,0 +1,122 @@
+//! Python tuple.
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Index;
+
+use super::list::normalize_index;
+use super::repr::PyRepr;
+use crate::exceptions::{PyException, PyResult};
+
+/// Python-equivalent tuple: an immutable sequence, ordered lexicographically
+/// like list.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct PyTuple<T> {
+    items: Vec<T>,
+}
+
+impl<T> PyTuple<T> {
+    pub fn new() -> Self {
+        PyTuple { items: Vec::new() }
+    }
+
+    pub fn from_vec(items: Vec<T>) -> Self {
+        PyTuple { items }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.items
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, T> {
+        self.items.iter()
+    }
+
+    /// Python-equivalent tuple[index], with negative indices counting from the end.
+    pub fn get(&self, index: i64) -> PyResult<&T> {
+        normalize_index(index, self.len())
+            .map(|i| &self.items[i])
+            .ok_or_else(|| PyException::index_error("tuple index out of range"))
+    }
+}
+
+impl<T: PartialEq> PyTuple<T> {
+    /// Python-equivalent x in tuple.
+    pub fn contains(&self, value: &T) -> bool {
+        self.items.contains(value)
+    }
+
+    /// Python-equivalent tuple.index(x).
+    pub fn index(&self, value: &T) -> PyResult<usize> {
+        self.items
+            .iter()
+            .position(|v| v == value)
+            .ok_or_else(|| PyException::value_error("tuple.index(x): x not in tuple"))
+    }
+
+    /// Python-equivalent tuple.count(x).
+    pub fn count(&self, value: &T) -> usize {
+        self.items.iter().filter(|v| *v == value).count()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a PyTuple<T> {
+    type Item = &'a T;
+    type IntoIter = core::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl<T> IntoIterator for PyTuple<T> {
+    type Item = T;
+    type IntoIter = alloc::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<T> FromIterator<T> for PyTuple<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        PyTuple {
+            items: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for PyTuple<T> {
+    fn from(items: Vec<T>) -> Self {
+        PyTuple { items }
+    }
+}
+
+/// `tuple[i]`, raising IndexError as a panic; [`PyTuple::get`] is the
+/// checked form.
+impl<T> Index<i64> for PyTuple<T> {
+    type Output = T;
+
+    fn index(&self, index: i64) -> &T {
+        self.get(index).unwrap_or_else(|err| err.raise())
+    }
+}
+
+/// Python-equivalent str(tuple), which shows each element's repr.
+impl<T: PyRepr> fmt::Display for PyTuple<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.py_repr())
+    }
+}
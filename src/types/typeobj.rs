@@ -0,0 +1,251 @@
+//! Runtime type objects: the values returned by type(x) and accepted by
+//! isinstance()/issubclass().
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::hash::Hash;
+
+use super::{PyBytes, PyDictionary, PyList, PyRange, PySet, PyStr, PyTuple};
+use crate::sync::Lock;
+
+/// Identifies a Python type. Built-in types are fixed variants; compiled
+/// classes get a `Class` slot from [`register_class`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PyTypeId {
+    Object,
+    NoneType,
+    Bool,
+    Int,
+    Float,
+    Str,
+    Bytes,
+    List,
+    Tuple,
+    Dict,
+    Set,
+    Range,
+    Function,
+    Method,
+    NotImplementedType,
+    Class(usize),
+}
+
+struct ClassInfo {
+    name: &'static str,
+    bases: Vec<PyTypeId>,
+}
+
+static CLASSES: Lock<Vec<ClassInfo>> = Lock::new(Vec::new());
+
+/// Registers a compiled class under `name` with the given base classes.
+/// Registering the same name again returns the existing type.
+pub fn register_class(name: &'static str, bases: &[PyTypeId]) -> PyTypeId {
+    let mut classes = CLASSES.lock();
+    if let Some(slot) = classes.iter().position(|c| c.name == name) {
+        return PyTypeId::Class(slot);
+    }
+    let bases = if bases.is_empty() {
+        vec![PyTypeId::Object]
+    } else {
+        bases.to_vec()
+    };
+    classes.push(ClassInfo { name, bases });
+    PyTypeId::Class(classes.len() - 1)
+}
+
+/// Looks up a registered class by name.
+pub fn lookup_class(name: &str) -> Option<PyTypeId> {
+    let classes = CLASSES.lock();
+    classes
+        .iter()
+        .position(|c| c.name == name)
+        .map(PyTypeId::Class)
+}
+
+impl PyTypeId {
+    /// Python-equivalent type.__name__.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PyTypeId::Object => "object",
+            PyTypeId::NoneType => "NoneType",
+            PyTypeId::Bool => "bool",
+            PyTypeId::Int => "int",
+            PyTypeId::Float => "float",
+            PyTypeId::Str => "str",
+            PyTypeId::Bytes => "bytes",
+            PyTypeId::List => "list",
+            PyTypeId::Tuple => "tuple",
+            PyTypeId::Dict => "dict",
+            PyTypeId::Set => "set",
+            PyTypeId::Range => "range",
+            PyTypeId::Function => "function",
+            PyTypeId::Method => "method",
+            PyTypeId::NotImplementedType => "NotImplementedType",
+            PyTypeId::Class(slot) => {
+                let classes = CLASSES.lock();
+                classes.get(*slot).map_or("<unknown>", |c| c.name)
+            }
+        }
+    }
+
+    /// Python-equivalent type.__bases__.
+    pub fn bases(&self) -> Vec<PyTypeId> {
+        match self {
+            PyTypeId::Object => Vec::new(),
+            PyTypeId::Bool => vec![PyTypeId::Int],
+            PyTypeId::Class(slot) => {
+                let classes = CLASSES.lock();
+                classes
+                    .get(*slot)
+                    .map_or_else(Vec::new, |c| c.bases.clone())
+            }
+            _ => vec![PyTypeId::Object],
+        }
+    }
+
+    /// Python-equivalent type.__mro__, linearized depth-first with later
+    /// duplicates winning so `object` always comes last.
+    pub fn mro(&self) -> Vec<PyTypeId> {
+        fn walk(ty: PyTypeId, out: &mut Vec<PyTypeId>) {
+            out.retain(|t| *t != ty);
+            out.push(ty);
+            for base in ty.bases() {
+                walk(base, out);
+            }
+        }
+        let mut out = Vec::new();
+        walk(*self, &mut out);
+        out
+    }
+
+    /// Python-equivalent issubclass(self, other).
+    pub fn is_subclass_of(&self, other: PyTypeId) -> bool {
+        *self == other || other == PyTypeId::Object || self.mro().contains(&other)
+    }
+}
+
+impl fmt::Display for PyTypeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<class '{}'>", self.name())
+    }
+}
+
+/// Implemented by every value the runtime can hand to type(), isinstance()
+/// and id(). Compiled classes return the id from [`register_class`].
+pub trait PyTyped {
+    fn py_type(&self) -> PyTypeId;
+
+    /// Python-equivalent id(): the object's address, stable while it is not moved.
+    fn py_id(&self) -> usize {
+        (self as *const Self).cast::<()>() as usize
+    }
+}
+
+macro_rules! impl_py_typed {
+    ($ty:expr => $($t:ty),*) => {
+        $(impl PyTyped for $t {
+            fn py_type(&self) -> PyTypeId {
+                $ty
+            }
+        })*
+    };
+}
+
+impl_py_typed!(PyTypeId::Int => i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_py_typed!(PyTypeId::Float => f32, f64);
+impl_py_typed!(PyTypeId::Bool => bool);
+impl_py_typed!(PyTypeId::Str => str, String, PyStr, char);
+impl_py_typed!(PyTypeId::Bytes => PyBytes, [u8]);
+impl_py_typed!(PyTypeId::NoneType => ());
+impl_py_typed!(PyTypeId::Range => PyRange);
+
+impl<T> PyTyped for PyList<T> {
+    fn py_type(&self) -> PyTypeId {
+        PyTypeId::List
+    }
+}
+
+impl<T> PyTyped for Vec<T> {
+    fn py_type(&self) -> PyTypeId {
+        PyTypeId::List
+    }
+}
+
+impl<T> PyTyped for PyTuple<T> {
+    fn py_type(&self) -> PyTypeId {
+        PyTypeId::Tuple
+    }
+}
+
+impl<K: Hash + Eq, V> PyTyped for PyDictionary<K, V> {
+    fn py_type(&self) -> PyTypeId {
+        PyTypeId::Dict
+    }
+}
+
+impl<T: Hash + Eq> PyTyped for PySet<T> {
+    fn py_type(&self) -> PyTypeId {
+        PyTypeId::Set
+    }
+}
+
+impl<T: PyTyped> PyTyped for Option<T> {
+    fn py_type(&self) -> PyTypeId {
+        match self {
+            Some(value) => value.py_type(),
+            None => PyTypeId::NoneType,
+        }
+    }
+}
+
+impl<T: PyTyped + ?Sized> PyTyped for &T {
+    fn py_type(&self) -> PyTypeId {
+        (**self).py_type()
+    }
+
+    fn py_id(&self) -> usize {
+        (**self).py_id()
+    }
+}
+
+impl<T: PyTyped + ?Sized> PyTyped for Box<T> {
+    fn py_type(&self) -> PyTypeId {
+        (**self).py_type()
+    }
+
+    fn py_id(&self) -> usize {
+        (**self).py_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_hierarchy() {
+        assert!(PyTypeId::Bool.is_subclass_of(PyTypeId::Int));
+        assert!(PyTypeId::Bool.is_subclass_of(PyTypeId::Object));
+        assert!(!PyTypeId::Int.is_subclass_of(PyTypeId::Bool));
+        assert_eq!(
+            PyTypeId::Bool.mro(),
+            vec![PyTypeId::Bool, PyTypeId::Int, PyTypeId::Object]
+        );
+    }
+
+    #[test]
+    fn registered_classes() {
+        let animal = register_class("TypeobjAnimal", &[]);
+        let dog = register_class("TypeobjDog", &[animal]);
+        assert_eq!(register_class("TypeobjDog", &[animal]), dog);
+        assert_eq!(lookup_class("TypeobjAnimal"), Some(animal));
+        assert_eq!(dog.name(), "TypeobjDog");
+        assert!(dog.is_subclass_of(animal));
+        assert!(!animal.is_subclass_of(dog));
+        assert_eq!(dog.mro(), vec![dog, animal, PyTypeId::Object]);
+        assert_eq!(dog.to_string(), "<class 'TypeobjDog'>");
+    }
+}
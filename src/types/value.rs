@@ -0,0 +1,313 @@
+//! A dynamically typed Python value.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+
+use super::hash::{hash_float, hash_int, unhashable, PyHash};
+use super::repr::repr_float;
+use super::{PyBytes, PyDictionary, PyFunction, PyList, PySet, PyStr, PyTuple, PyTypeId, PyTyped};
+use crate::exceptions::PyResult;
+
+/// Any Python value whose type is only known at runtime.
+#[derive(Debug, Clone, Default)]
+pub enum PyValue {
+    #[default]
+    None,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(PyStr),
+    Bytes(PyBytes),
+    List(PyList<PyValue>),
+    Tuple(PyTuple<PyValue>),
+    Dict(PyDictionary<PyValue, PyValue>),
+    Set(PySet<PyValue>),
+    Function(PyFunction),
+    /// The NotImplemented singleton a binary or comparison method returns
+    /// to let the other operand try.
+    NotImplemented,
+}
+
+impl PyValue {
+    /// Python-equivalent type(x).__name__.
+    pub fn type_name(&self) -> &'static str {
+        self.py_type().name()
+    }
+
+    pub fn is_none(&self) -> bool {
+        matches!(self, PyValue::None)
+    }
+
+    pub fn is_not_implemented(&self) -> bool {
+        matches!(self, PyValue::NotImplemented)
+    }
+}
+
+impl PyTyped for PyValue {
+    fn py_type(&self) -> PyTypeId {
+        match self {
+            PyValue::None => PyTypeId::NoneType,
+            PyValue::Bool(_) => PyTypeId::Bool,
+            PyValue::Int(_) => PyTypeId::Int,
+            PyValue::Float(_) => PyTypeId::Float,
+            PyValue::Str(_) => PyTypeId::Str,
+            PyValue::Bytes(_) => PyTypeId::Bytes,
+            PyValue::List(_) => PyTypeId::List,
+            PyValue::Tuple(_) => PyTypeId::Tuple,
+            PyValue::Dict(_) => PyTypeId::Dict,
+            PyValue::Set(_) => PyTypeId::Set,
+            PyValue::Function(func) => func.py_type(),
+            PyValue::NotImplemented => PyTypeId::NotImplementedType,
+        }
+    }
+}
+
+impl PyHash for PyValue {
+    fn py_hash(&self) -> PyResult<i64> {
+        match self {
+            PyValue::None => ().py_hash(),
+            PyValue::Bool(b) => Ok(*b as i64),
+            PyValue::Int(i) => Ok(hash_int(*i as i128)),
+            PyValue::Float(f) => Ok(hash_float(*f)),
+            PyValue::Str(s) => s.py_hash(),
+            PyValue::Bytes(b) => b.py_hash(),
+            PyValue::Tuple(t) => t.py_hash(),
+            PyValue::Function(func) => Ok(hash_int(func.addr() as i128 >> 4)),
+            // A singleton, so any constant will do, as for None.
+            PyValue::NotImplemented => Ok(0x4E6F74496D706C),
+            other => Err(unhashable(other.type_name())),
+        }
+    }
+}
+
+/// Numeric view used so that `1 == 1.0 == True`, as in Python.
+fn as_number(value: &PyValue) -> Option<f64> {
+    match value {
+        PyValue::Bool(b) => Some(*b as i64 as f64),
+        PyValue::Int(i) => Some(*i as f64),
+        PyValue::Float(f) => Some(*f),
+        _ => None,
+    }
+}
+
+fn as_int(value: &PyValue) -> Option<i64> {
+    match value {
+        PyValue::Bool(b) => Some(*b as i64),
+        PyValue::Int(i) => Some(*i),
+        _ => None,
+    }
+}
+
+impl PartialEq for PyValue {
+    fn eq(&self, other: &Self) -> bool {
+        use PyValue::*;
+        match (self, other) {
+            (None, None) | (NotImplemented, NotImplemented) => true,
+            (Str(a), Str(b)) => a == b,
+            (Bytes(a), Bytes(b)) => a == b,
+            (List(a), List(b)) => a == b,
+            (Tuple(a), Tuple(b)) => a == b,
+            (Dict(a), Dict(b)) => a == b,
+            (Set(a), Set(b)) => a == b,
+            (Function(a), Function(b)) => a == b,
+            _ => match (as_int(self), as_int(other)) {
+                (Some(a), Some(b)) => a == b,
+                _ => match (as_number(self), as_number(other)) {
+                    (Some(a), Some(b)) => a == b,
+                    _ => false,
+                },
+            },
+        }
+    }
+}
+
+/// Python's ordering for values of compatible types: numbers with each other,
+/// str or bytes with their own kind, and lists or tuples lexicographically.
+/// Anything else is unordered, which callers surface as TypeError.
+impl PartialOrd for PyValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        use PyValue::*;
+        match (self, other) {
+            (Str(a), Str(b)) => a.partial_cmp(b),
+            (Bytes(a), Bytes(b)) => a.partial_cmp(b),
+            (List(a), List(b)) => a.as_slice().partial_cmp(b.as_slice()),
+            (Tuple(a), Tuple(b)) => a.as_slice().partial_cmp(b.as_slice()),
+            _ => match (as_int(self), as_int(other)) {
+                (Some(a), Some(b)) => a.partial_cmp(&b),
+                _ => as_number(self)?.partial_cmp(&as_number(other)?),
+            },
+        }
+    }
+}
+
+/// Python's `x == x` is not guaranteed for NaN either; dicts and sets rely on
+/// this the same way CPython's identity shortcut does.
+impl Eq for PyValue {}
+
+/// Consistent with [`PyHash`] for hashable values, so `PyValue` can key a
+/// dict or live in a set. Unhashable values hash by type and length only.
+impl Hash for PyValue {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self.py_hash() {
+            Ok(h) => state.write_i64(h),
+            Err(_) => {
+                self.type_name().hash(state);
+                match self {
+                    PyValue::List(l) => l.len().hash(state),
+                    PyValue::Dict(d) => d.len().hash(state),
+                    PyValue::Set(s) => s.len().hash(state),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Python-equivalent str(x).
+impl fmt::Display for PyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PyValue::None => f.write_str("None"),
+            PyValue::Bool(true) => f.write_str("True"),
+            PyValue::Bool(false) => f.write_str("False"),
+            PyValue::Int(i) => write!(f, "{}", i),
+            PyValue::Float(x) => f.write_str(&repr_float(*x)),
+            PyValue::Str(s) => write!(f, "{}", s),
+            PyValue::Bytes(b) => write!(f, "{}", b),
+            PyValue::List(l) => write!(f, "{}", l),
+            PyValue::Tuple(t) => write!(f, "{}", t),
+            PyValue::Dict(d) => write!(f, "{}", d),
+            PyValue::Set(s) => write!(f, "{}", s),
+            PyValue::Function(func) => write!(f, "{}", func),
+            PyValue::NotImplemented => f.write_str("NotImplemented"),
+        }
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for PyValue {
+            fn from(value: $t) -> Self {
+                PyValue::Int(value as i64)
+            }
+        })*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<()> for PyValue {
+    fn from(_: ()) -> Self {
+        PyValue::None
+    }
+}
+
+impl From<bool> for PyValue {
+    fn from(value: bool) -> Self {
+        PyValue::Bool(value)
+    }
+}
+
+impl From<f64> for PyValue {
+    fn from(value: f64) -> Self {
+        PyValue::Float(value)
+    }
+}
+
+impl From<&str> for PyValue {
+    fn from(value: &str) -> Self {
+        PyValue::Str(PyStr::new(value))
+    }
+}
+
+impl From<String> for PyValue {
+    fn from(value: String) -> Self {
+        PyValue::Str(PyStr::from(value))
+    }
+}
+
+impl From<PyStr> for PyValue {
+    fn from(value: PyStr) -> Self {
+        PyValue::Str(value)
+    }
+}
+
+impl From<PyBytes> for PyValue {
+    fn from(value: PyBytes) -> Self {
+        PyValue::Bytes(value)
+    }
+}
+
+impl<T: Into<PyValue>> From<Option<T>> for PyValue {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(PyValue::None, Into::into)
+    }
+}
+
+impl<T: Into<PyValue>> From<Vec<T>> for PyValue {
+    fn from(value: Vec<T>) -> Self {
+        PyValue::List(PyList::from_vec(
+            value.into_iter().map(Into::into).collect(),
+        ))
+    }
+}
+
+impl From<PyList<PyValue>> for PyValue {
+    fn from(value: PyList<PyValue>) -> Self {
+        PyValue::List(value)
+    }
+}
+
+impl From<PyTuple<PyValue>> for PyValue {
+    fn from(value: PyTuple<PyValue>) -> Self {
+        PyValue::Tuple(value)
+    }
+}
+
+impl From<PyDictionary<PyValue, PyValue>> for PyValue {
+    fn from(value: PyDictionary<PyValue, PyValue>) -> Self {
+        PyValue::Dict(value)
+    }
+}
+
+impl From<PySet<PyValue>> for PyValue {
+    fn from(value: PySet<PyValue>) -> Self {
+        PyValue::Set(value)
+    }
+}
+
+impl From<PyFunction> for PyValue {
+    fn from(value: PyFunction) -> Self {
+        PyValue::Function(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numeric_equality_and_hash_agree() {
+        let mut d = PyDictionary::new();
+        d.set_item(PyValue::Int(1), PyValue::from("int"));
+        d.set_item(PyValue::Float(1.0), PyValue::from("float"));
+        d.set_item(PyValue::Bool(true), PyValue::from("bool"));
+        assert_eq!(d.len(), 1);
+        assert_eq!(d.get(&PyValue::Int(1)), Some(&PyValue::from("bool")));
+    }
+
+    #[test]
+    fn str_formatting() {
+        let v = PyValue::from(vec![
+            PyValue::Float(3.0),
+            PyValue::None,
+            PyValue::Bool(true),
+        ]);
+        assert_eq!(v.to_string(), "[3.0, None, True]");
+        assert_eq!(v.type_name(), "list");
+        assert!(v.py_hash().unwrap_err().is("TypeError"));
+    }
+}